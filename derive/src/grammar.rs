@@ -0,0 +1,378 @@
+// The implementation of the `grammar!` macro
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    Attribute, Error, Expr, Ident, Lit, Result, Token, Type, Visibility,
+};
+
+pub struct Grammar {
+    input: Option<Type>,
+    extra: Option<Type>,
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    ty: Type,
+    body: Node,
+}
+
+// An expression of the grammar
+enum Node {
+    // Alternatives, tried in order
+    Choice(Vec<Seq>),
+    // Literal input, whose output is ignored within a sequence
+    Lit(Lit),
+    // A reference to another rule, and whether its output is `()`
+    Rule(Ident, bool),
+    // A parser written in Rust
+    Rust(Expr),
+    Repeat(Box<Node>, Repeat),
+}
+
+enum Repeat {
+    Many,
+    AtLeastOne,
+    Optional,
+}
+
+// A sequence of nodes, with an optional action applied to their outputs
+struct Seq {
+    nodes: Vec<Node>,
+    action: Option<Expr>,
+}
+
+impl Parse for Grammar {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut grammar = Grammar {
+            input: None,
+            extra: None,
+            rules: Vec::new(),
+        };
+        while !input.is_empty() {
+            if input.peek(Token![type]) {
+                input.parse::<Token![type]>()?;
+                let name = input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                let ty = input.parse::<Type>()?;
+                input.parse::<Token![;]>()?;
+                let slot = match name.to_string().as_str() {
+                    "Input" => &mut grammar.input,
+                    "Extra" => &mut grammar.extra,
+                    _ => return Err(Error::new(name.span(), "expected `Input` or `Extra`")),
+                };
+                if slot.replace(ty).is_some() {
+                    return Err(Error::new(
+                        name.span(),
+                        format!("`{}` is given more than once", name),
+                    ));
+                }
+            } else {
+                grammar.rules.push(input.parse()?);
+            }
+        }
+        Ok(grammar)
+    }
+}
+
+impl Parse for Rule {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let body = parse_choice(input)?;
+        input.parse::<Token![;]>()?;
+        Ok(Rule {
+            attrs,
+            vis,
+            name,
+            ty,
+            body,
+        })
+    }
+}
+
+fn parse_choice(input: ParseStream) -> Result<Node> {
+    let mut alternatives = vec![parse_seq(input)?];
+    while input.peek(Token![|]) && !input.peek(Token![||]) {
+        input.parse::<Token![|]>()?;
+        alternatives.push(parse_seq(input)?);
+    }
+    Ok(Node::Choice(alternatives))
+}
+
+fn parse_seq(input: ParseStream) -> Result<Seq> {
+    let mut nodes = Vec::new();
+    while !(input.is_empty()
+        || input.peek(Token![|])
+        || input.peek(Token![;])
+        || input.peek(Token![=>]))
+    {
+        nodes.push(parse_repeat(input)?);
+    }
+    if nodes.is_empty() {
+        return Err(input.error("expected a literal, a rule, a `(...)` group, or a `{...}` parser"));
+    }
+    let action = if input.peek(Token![=>]) {
+        input.parse::<Token![=>]>()?;
+        Some(parse_action(input)?)
+    } else {
+        None
+    };
+    Ok(Seq { nodes, action })
+}
+
+// An action is an expression that ends at the next `|` or `;` (other than the parameters of a closure)
+fn parse_action(input: ParseStream) -> Result<Expr> {
+    let mut tokens = TokenStream2::new();
+    if input.peek(Token![move]) {
+        tokens.extend(input.parse::<Token![move]>()?.into_token_stream());
+    }
+    if input.peek(Token![||]) {
+        tokens.extend(input.parse::<Token![||]>()?.into_token_stream());
+    } else if input.peek(Token![|]) {
+        tokens.extend(input.parse::<Token![|]>()?.into_token_stream());
+        while !input.peek(Token![|]) {
+            if input.is_empty() {
+                return Err(input.error("unterminated closure parameters"));
+            }
+            tokens.extend(input.parse::<proc_macro2::TokenTree>()?.into_token_stream());
+        }
+        tokens.extend(input.parse::<Token![|]>()?.into_token_stream());
+    }
+    while !(input.is_empty() || input.peek(Token![|]) || input.peek(Token![;])) {
+        tokens.extend(input.parse::<proc_macro2::TokenTree>()?.into_token_stream());
+    }
+    syn::parse2(tokens)
+}
+
+fn parse_repeat(input: ParseStream) -> Result<Node> {
+    let mut node = parse_atom(input)?;
+    loop {
+        let repeat = if input.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            Repeat::Many
+        } else if input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            Repeat::AtLeastOne
+        } else if input.peek(Token![?]) {
+            input.parse::<Token![?]>()?;
+            Repeat::Optional
+        } else {
+            break Ok(node);
+        };
+        node = Node::Repeat(Box::new(node), repeat);
+    }
+}
+
+fn parse_atom(input: ParseStream) -> Result<Node> {
+    if input.peek(Lit) {
+        Ok(Node::Lit(input.parse()?))
+    } else if input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let node = parse_choice(&content)?;
+        if !content.is_empty() {
+            return Err(content.error("expected `|` or `)`"));
+        }
+        Ok(node)
+    } else if input.peek(syn::token::Brace) {
+        let block = input.parse::<syn::ExprBlock>()?;
+        Ok(Node::Rust(Expr::Block(block)))
+    } else if input.peek(Ident) {
+        Ok(Node::Rule(input.parse()?, false))
+    } else {
+        Err(input.error("expected a literal, a rule, a `(...)` group, or a `{...}` parser"))
+    }
+}
+
+impl Node {
+    // Whether the node produces an output within a sequence
+    fn has_output(&self) -> bool {
+        match self {
+            Node::Choice(alternatives) => alternatives
+                .iter()
+                .any(|seq| seq.action.is_some() || seq.nodes.iter().any(Node::has_output)),
+            Node::Lit(_) => false,
+            Node::Rule(_, unit) => !unit,
+            Node::Rust(_) => true,
+            Node::Repeat(node, _) => node.has_output(),
+        }
+    }
+
+    // Check that referenced rules exist, and find those whose output is `()`
+    fn resolve(&mut self, rules: &[(Ident, bool)]) -> Result<()> {
+        match self {
+            Node::Choice(alternatives) => alternatives
+                .iter_mut()
+                .flat_map(|seq| &mut seq.nodes)
+                .try_for_each(|node| node.resolve(rules)),
+            Node::Rule(name, unit) => match rules.iter().find(|(rule, _)| rule == name) {
+                Some((_, is_unit)) => {
+                    *unit = *is_unit;
+                    Ok(())
+                }
+                None => Err(Error::new(
+                    name.span(),
+                    format!(
+                        "unknown rule `{}` (parsers written in Rust must be wrapped in `{{...}}`)",
+                        name
+                    ),
+                )),
+            },
+            Node::Repeat(node, _) => node.resolve(rules),
+            Node::Lit(_) | Node::Rust(_) => Ok(()),
+        }
+    }
+
+    fn expand(&self) -> TokenStream2 {
+        match self {
+            Node::Choice(alternatives) => {
+                let mut alternatives = alternatives.iter().map(Seq::expand);
+                let first = alternatives.next().expect("choice with no alternatives");
+                quote!(#first #(.or(#alternatives))*)
+            }
+            Node::Lit(lit) => quote!(::chumsky::primitive::just(#lit)),
+            Node::Rule(name, _) => quote!(#name.clone()),
+            Node::Rust(expr) => quote!(#expr),
+            Node::Repeat(node, repeat) => {
+                let parser = node.expand();
+                let has_output = node.has_output();
+                match repeat {
+                    Repeat::Many if has_output => {
+                        quote!(#parser.repeated().collect::<::chumsky::grammar::__private::Vec<_>>())
+                    }
+                    Repeat::Many => quote!(#parser.repeated()),
+                    Repeat::AtLeastOne if has_output => quote! {
+                        #parser.repeated().at_least(1).collect::<::chumsky::grammar::__private::Vec<_>>()
+                    },
+                    Repeat::AtLeastOne => quote!(#parser.repeated().at_least(1)),
+                    Repeat::Optional if has_output => quote!(#parser.or_not()),
+                    Repeat::Optional => quote!(#parser.or_not().ignored()),
+                }
+            }
+        }
+    }
+}
+
+impl Seq {
+    // The output of a sequence is `()`, the output of its only node with an output, or a tuple of the outputs of its
+    // nodes with outputs
+    fn expand(&self) -> TokenStream2 {
+        let outputs = self.nodes.iter().filter(|node| node.has_output()).count();
+        let parser = match (&self.nodes[..], outputs) {
+            ([node], 1) => node.expand(),
+            ([node], _) => {
+                let parser = node.expand();
+                quote!(#parser.ignored())
+            }
+            (nodes, _) => {
+                let mut names = Vec::new();
+                let parsers = nodes.iter().map(|node| {
+                    let parser = node.expand();
+                    if node.has_output() {
+                        names.push(format_ident!("__{}", names.len()));
+                        quote!(.then(#parser))
+                    } else {
+                        quote!(.then_ignore(#parser))
+                    }
+                });
+                let parsers = parsers.collect::<Vec<_>>();
+                let pattern = names
+                    .iter()
+                    .fold(quote!(()), |pattern, name| quote!((#pattern, #name)));
+                let output = match &names[..] {
+                    [name] => quote!(#name),
+                    names => quote!((#(#names),*)),
+                };
+                quote!(::chumsky::primitive::empty() #(#parsers)* .map(|#pattern| #output))
+            }
+        };
+        match &self.action {
+            Some(action) => quote!(#parser.map(#action)),
+            None => parser,
+        }
+    }
+}
+
+impl Grammar {
+    pub fn expand(mut self) -> Result<TokenStream2> {
+        let signatures = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let unit = matches!(&rule.ty, Type::Tuple(tuple) if tuple.elems.is_empty());
+                (rule.name.clone(), unit)
+            })
+            .collect::<Vec<_>>();
+        for rule in &mut self.rules {
+            if signatures
+                .iter()
+                .filter(|(name, _)| *name == rule.name)
+                .count()
+                > 1
+            {
+                return Err(Error::new(
+                    rule.name.span(),
+                    format!("the rule `{}` is defined more than once", rule.name),
+                ));
+            }
+            rule.body.resolve(&signatures)?;
+        }
+
+        let input = self
+            .input
+            .as_ref()
+            .map_or_else(|| quote!(&'a str), ToTokens::to_token_stream);
+        let extra = self.extra.as_ref().map_or_else(
+            || quote!(::chumsky::extra::Default),
+            ToTokens::to_token_stream,
+        );
+        let names = self.rules.iter().map(|rule| &rule.name).collect::<Vec<_>>();
+        let types = self.rules.iter().map(|rule| &rule.ty).collect::<Vec<_>>();
+        let bodies = self
+            .rules
+            .iter()
+            .map(|rule| rule.body.expand())
+            .collect::<Vec<_>>();
+
+        // Each rule becomes a function that builds the whole grammar, so that rules can refer to each other in any
+        // order
+        let functions = self.rules.iter().map(|rule| {
+            let Rule {
+                attrs,
+                vis,
+                name,
+                ty,
+                ..
+            } = rule;
+            let allow = matches!(vis, Visibility::Inherited).then(|| quote!(#[allow(dead_code)]));
+            quote! {
+                #(#attrs)*
+                #allow
+                // Parsers written in Rust are blocks, which would otherwise be linted as unnecessary braces
+                #[allow(unused_braces)]
+                #vis fn #name<'a>() -> impl ::chumsky::Parser<'a, #input, #ty, #extra> + Clone {
+                    #[allow(unused_imports)]
+                    use ::chumsky::{IterParser as _, Parser as _};
+                    #(
+                        let mut #names = ::chumsky::recursive::Recursive::<
+                            ::chumsky::recursive::Indirect<'a, 'a, #input, #types, #extra>,
+                        >::declare();
+                    )*
+                    #(#names.define(#bodies);)*
+                    #name
+                }
+            }
+        });
+        Ok(quote!(#(#functions)*))
+    }
+}
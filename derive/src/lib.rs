@@ -0,0 +1,250 @@
+//! Procedural macros for [chumsky](https://docs.rs/chumsky).
+//!
+//! These macros are re-exported by chumsky when its `derive` or `grammar` features are enabled, and should be used
+//! through it: see the documentation of `chumsky::derive` and `chumsky::grammar` for more information.
+
+mod grammar;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, LitInt,
+    LitStr, PathArguments, Result, Type,
+};
+
+/// Derive `chumsky::derive::Parseable` for a struct or enum, generating a parser for it from `#[parse(...)]`
+/// attributes.
+#[proc_macro_derive(Parseable, attributes(parse))]
+pub fn derive_parseable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Write parsers as rules in an EBNF-like notation, expanding to a function for each rule.
+#[proc_macro]
+pub fn grammar(input: TokenStream) -> TokenStream {
+    let grammar = parse_macro_input!(input as grammar::Grammar);
+    grammar
+        .expand()
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// The arguments of the `#[parse(...)]` attributes on an item, variant, or field
+#[derive(Default)]
+struct Attrs {
+    prefix: Option<LitStr>,
+    suffix: Option<LitStr>,
+    separator: Option<LitStr>,
+    at_least: Option<LitInt>,
+    at_most: Option<LitInt>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut this = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("parse")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("prefix") {
+                    this.prefix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("suffix") {
+                    this.suffix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("separator") {
+                    this.separator = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("at_least") {
+                    this.at_least = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("at_most") {
+                    this.at_most = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error(
+                        "expected `prefix`, `suffix`, `separator`, `at_least`, or `at_most`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(this)
+    }
+
+    // Repetition attributes only make sense on `Vec` fields
+    fn reject_repetition(&self) -> Result<()> {
+        let error = |span, name| {
+            Err(Error::new(
+                span,
+                format!("`{}` can only be used on `Vec` fields", name),
+            ))
+        };
+        if let Some(lit) = &self.separator {
+            return error(lit.span(), "separator");
+        }
+        if let Some(lit) = &self.at_least {
+            return error(lit.span(), "at_least");
+        }
+        if let Some(lit) = &self.at_most {
+            return error(lit.span(), "at_most");
+        }
+        Ok(())
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`Parseable` can't be derived for types with generic parameters",
+        ));
+    }
+    let attrs = Attrs::parse(&input.attrs)?;
+    attrs.reject_repetition()?;
+
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => fields(quote!(Self), &data.fields)?,
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let attrs = Attrs::parse(&variant.attrs)?;
+                    attrs.reject_repetition()?;
+                    let ident = &variant.ident;
+                    // Variants are introduced by their name, unless given another prefix
+                    let prefix = attrs.prefix.unwrap_or_else(|| {
+                        LitStr::new(&ident.to_string().to_lowercase(), ident.span())
+                    });
+                    let fields = fields(quote!(Self::#ident), &variant.fields)?;
+                    Ok(literals(Some(&prefix), attrs.suffix.as_ref(), fields))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let (first, rest) = variants.split_first().ok_or_else(|| {
+                Error::new_spanned(
+                    name,
+                    "`Parseable` can't be derived for enums with no variants",
+                )
+            })?;
+            quote!(#first #(.or(#rest))*)
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                name,
+                "`Parseable` can't be derived for unions",
+            ))
+        }
+    };
+    let body = literals(attrs.prefix.as_ref(), attrs.suffix.as_ref(), body);
+
+    Ok(quote! {
+        impl ::chumsky::derive::Parseable for #name {
+            fn parser<'a, E>() -> ::chumsky::Boxed<'a, 'a, &'a str, Self, E>
+            where
+                Self: 'a,
+                E: ::chumsky::extra::ParserExtra<'a, &'a str> + 'a,
+            {
+                use ::chumsky::Parser as _;
+                ::chumsky::Parser::boxed(#body)
+            }
+        }
+    })
+}
+
+// Surround a parser with literals
+fn literals(
+    prefix: Option<&LitStr>,
+    suffix: Option<&LitStr>,
+    parser: TokenStream2,
+) -> TokenStream2 {
+    let parser = match prefix {
+        Some(prefix) => {
+            quote!(::chumsky::derive::__private::literal::<E>(#prefix).ignore_then(#parser))
+        }
+        None => parser,
+    };
+    match suffix {
+        Some(suffix) => {
+            quote!(#parser.then_ignore(::chumsky::derive::__private::literal::<E>(#suffix)))
+        }
+        None => parser,
+    }
+}
+
+// Parse each of the fields in turn, then build the struct or variant from them
+fn fields(constructor: TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+    let parsers = fields.iter().map(field).collect::<Result<Vec<_>>>()?;
+    let names = (0..parsers.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect::<Vec<_>>();
+    let pattern = names
+        .iter()
+        .fold(quote!(()), |pattern, name| quote!((#pattern, #name)));
+    let value = match fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote!(#constructor { #(#idents: #names),* })
+        }
+        Fields::Unnamed(_) => quote!(#constructor(#(#names),*)),
+        Fields::Unit => constructor,
+    };
+    Ok(quote! {
+        ::chumsky::primitive::empty()
+            #(.then(#parsers))*
+            .map(|#pattern| #value)
+    })
+}
+
+fn field(field: &syn::Field) -> Result<TokenStream2> {
+    let attrs = Attrs::parse(&field.attrs)?;
+    let ty = &field.ty;
+    let parser = |ty: &Type| quote!(<#ty as ::chumsky::derive::Parseable>::parser::<E>());
+
+    if let Some(item) = type_argument(ty, "Vec") {
+        let item = parser(item);
+        let repeated = match &attrs.separator {
+            Some(separator) => quote! {
+                #item.separated_by(::chumsky::derive::__private::literal::<E>(#separator))
+            },
+            None => quote!(#item.repeated()),
+        };
+        let at_least = attrs.at_least.iter();
+        let at_most = attrs.at_most.iter();
+        let repeated = quote! {
+            #repeated #(.at_least(#at_least))* #(.at_most(#at_most))*.collect::<#ty>()
+        };
+        Ok(literals(
+            attrs.prefix.as_ref(),
+            attrs.suffix.as_ref(),
+            repeated,
+        ))
+    } else if let Some(inner) = type_argument(ty, "Option") {
+        attrs.reject_repetition()?;
+        // The literals are part of the optional value, so that a field can be left out entirely
+        let parser = literals(attrs.prefix.as_ref(), attrs.suffix.as_ref(), parser(inner));
+        Ok(quote!(#parser.or_not()))
+    } else {
+        attrs.reject_repetition()?;
+        Ok(literals(
+            attrs.prefix.as_ref(),
+            attrs.suffix.as_ref(),
+            parser(ty),
+        ))
+    }
+}
+
+// If the type is `Name<T>`, get `T`
+fn type_argument<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(arg)) if segment.ident == name && args.args.len() == 1 => {
+            Some(arg)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,170 @@
+//! Allocation of parser outputs in a [`bumpalo`](https://docs.rs/bumpalo) arena.
+//!
+//! Parsers that build large syntax trees can spend much of their time allocating nodes. Allocating them in an arena
+//! instead is often much faster, and allows the resulting tree to be freed all at once.
+//!
+//! The arena is provided to the parser through its state, which must implement [`ArenaState`]: either a plain
+//! `&'bump Bump`, or a custom state type that contains one. The parsers in this module then give access to it with
+//! the correct lifetime, so that outputs can borrow from the arena:
+//!
+//! - [`Parser::map_with_arena`] maps the output of a parser using the arena, such as to allocate a node within it.
+//!
+//! - [`IterParser::collect_in_arena`] collects the outputs of an iterable parser into a
+//!   [`bumpalo::collections::Vec`].
+//!
+//! *This module requires the `bumpalo` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, arena::Bump};
+//! use bumpalo::collections::Vec as BumpVec;
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Tree<'bump> {
+//!     Leaf(u32),
+//!     Branch(BumpVec<'bump, &'bump Tree<'bump>>),
+//! }
+//!
+//! fn parser<'src, 'bump: 'src>(
+//! ) -> impl Parser<'src, &'src str, &'bump Tree<'bump>, extra::State<&'bump Bump>> {
+//!     recursive(|tree| {
+//!         let leaf = text::int(10).from_str().unwrapped().map(Tree::Leaf);
+//!         let branch = tree
+//!             .separated_by(just(','))
+//!             .collect_in_arena()
+//!             .delimited_by(just('['), just(']'))
+//!             .map(Tree::Branch);
+//!         leaf.or(branch)
+//!             .map_with_arena(|tree, bump: &'bump Bump| &*bump.alloc(tree))
+//!     })
+//! }
+//!
+//! let bump = Bump::new();
+//! let tree = parser().parse_with_state("[1,[2,3],[]]", &mut &bump).into_result().unwrap();
+//!
+//! match tree {
+//!     Tree::Branch(items) => {
+//!         assert_eq!(items.len(), 3);
+//!         assert_eq!(*items[0], Tree::Leaf(1));
+//!     }
+//!     Tree::Leaf(_) => panic!("expected a branch"),
+//! }
+//! ```
+
+use super::*;
+use bumpalo::collections::Vec as BumpVec;
+
+pub use bumpalo::Bump;
+
+/// A parser state that provides an arena in which outputs can be allocated.
+///
+/// This trait is implemented for `&'bump Bump`. Custom state types that need to carry other information alongside the
+/// arena can implement it by returning the arena they contain.
+pub trait ArenaState<'bump> {
+    /// Get the arena that outputs should be allocated in.
+    fn arena(&self) -> &'bump Bump;
+}
+
+impl<'bump> ArenaState<'bump> for &'bump Bump {
+    #[inline(always)]
+    fn arena(&self) -> &'bump Bump {
+        self
+    }
+}
+
+/// See [`Parser::map_with_arena`].
+pub struct MapWithArena<'bump, A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(&'bump Bump, OA)>,
+}
+
+impl<'bump, A: Copy, OA, F: Copy> Copy for MapWithArena<'bump, A, OA, F> {}
+impl<'bump, A: Clone, OA, F: Clone> Clone for MapWithArena<'bump, A, OA, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, 'bump, I, O, E, A, OA, F> ParserSealed<'a, I, O, E> for MapWithArena<'bump, A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: ArenaState<'bump>,
+    A: Parser<'a, I, OA, E>,
+    F: Fn(OA, &'bump Bump) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        let bump = inp.state().arena();
+        Ok(M::map(out, |out| (self.mapper)(out, bump)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => O);
+
+    go_extra!(O);
+}
+
+/// See [`IterParser::collect_in_arena`].
+pub struct CollectInArena<'bump, A, O> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(&'bump Bump, O)>,
+}
+
+impl<'bump, A: Copy, O> Copy for CollectInArena<'bump, A, O> {}
+impl<'bump, A: Clone, O> Clone for CollectInArena<'bump, A, O> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, 'bump, I, O, E, A> ParserSealed<'a, I, BumpVec<'bump, O>, E>
+    for CollectInArena<'bump, A, O>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: ArenaState<'bump>,
+    A: IterParser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, BumpVec<'bump, O>> {
+        let bump = inp.state().arena();
+        let mut output = M::bind(|| BumpVec::with_capacity_in(self.parser.capacity_hint(), bump));
+        let mut iter_state = self.parser.make_iter::<M>(inp)?;
+        loop {
+            match self.parser.next::<M>(inp, &mut iter_state) {
+                Ok(Some(out)) => {
+                    M::combine_mut(&mut output, out, |output: &mut BumpVec<'bump, O>, item| {
+                        output.push(item)
+                    });
+                }
+                Ok(None) => break Ok(output),
+                Err(()) => break Err(()),
+            }
+        }
+    }
+
+    graph_node!(iter parser: O);
+
+    gen_input!(iter parser: O => BumpVec<'bump, O>);
+
+    go_extra!(BumpVec<'bump, O>);
+}
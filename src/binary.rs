@@ -0,0 +1,1835 @@
+//! Parsers for binary formats, over inputs of bytes.
+//!
+//! File formats and network protocols are full of fixed-size numbers stored in a particular byte order. The parsers in
+//! this module read such numbers directly from an input of bytes (such as `&[u8]`), producing native integers and
+//! floats with spans that cover the bytes they were read from.
+//!
+//! Each number type has a parser for each byte order: [`u32_le`] reads a little-endian `u32`, and [`u32_be`] reads a
+//! big-endian one. [`number`] reads any [`FromBytes`] type in a given byte order, which can also be chosen while
+//! parsing (such as from the header of a file) with [`ConfigParser::configure`].
+//!
+//! Files are often identified by the magic bytes at their start, which can be matched with [`magic`]. Parsers for
+//! several formats can be chosen between by their magic bytes with [`choice_by_magic`].
+//!
+//! Strings are read with [`cstr`], for null-terminated strings, and [`fixed_str`], for strings in fields of a fixed
+//! size, as either bytes or UTF-8.
+//!
+//! Data protected by a checksum, such as a [`crc32`], can be validated with [`checksummed`].
+//!
+//! Padding can be skipped with [`padding`], or up to an alignment boundary with [`align_to`].
+//!
+//! Length-prefixed data, the way most binary protocols delimit their messages and fields, can be parsed with
+//! [`length_prefixed`], or taken as a raw slice with [`take`].
+//!
+//! Variable-length integers can be read with [`uleb128`], [`sleb128`] and [`vlq`].
+//!
+//! Packed structures, whose fields are smaller than a byte, can be parsed by reading the input one bit at a time with
+//! [`Bits`], using [`bits`], [`bit_flag`] and [`aligned`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, binary};
+//! // A record of a big-endian `u16` tag, followed by a little-endian `f32` value
+//! let record = binary::u16_be::<&[u8], extra::Err<Simple<u8>>>()
+//!     .then(binary::f32_le())
+//!     .map_with_span(|(tag, value), span: SimpleSpan| (tag, value, span.into_range()));
+//!
+//! let input = [0x01, 0x02, 0x00, 0x00, 0x20, 0x40, 0x00, 0x07, 0x00, 0x00, 0x80, 0xbf];
+//! assert_eq!(
+//!     record.repeated().collect::<Vec<_>>().parse(&input).into_result(),
+//!     Ok(vec![(0x0102, 2.5, 0..6), (7, -1.0, 6..12)]),
+//! );
+//! ```
+
+use super::*;
+use crate::input::ExactSizeInput;
+
+/// The order in which the bytes of a number are stored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// The least significant byte comes first.
+    Little,
+    /// The most significant byte comes first.
+    Big,
+}
+
+/// A number that can be read from a fixed number of bytes. See [`number`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{number, Endian, FromBytes}};
+/// // A 256-bit integer, stored as 32 bytes
+/// #[derive(Debug, PartialEq)]
+/// struct U256([u8; 32]);
+///
+/// impl FromBytes for U256 {
+///     type Bytes = [u8; 32];
+///
+///     fn from_bytes(mut bytes: [u8; 32], endian: Endian) -> Self {
+///         // Store the bytes in big-endian order
+///         if endian == Endian::Little {
+///             bytes.reverse();
+///         }
+///         U256(bytes)
+///     }
+/// }
+///
+/// let mut input = [0; 32];
+/// input[0] = 1;
+/// let mut expected = [0; 32];
+/// expected[31] = 1;
+/// let le = number::<U256, &[u8], extra::Err<Simple<u8>>>(Endian::Little);
+/// assert_eq!(le.parse(&input).into_result(), Ok(U256(expected)));
+/// assert!(le.parse(&input[..31]).has_errors());
+/// ```
+pub trait FromBytes: Sized {
+    /// The bytes that the number is stored in, which is usually an array such as `[u8; 4]`. The number is read from
+    /// as many bytes as the default value of this type holds.
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Create the number from its bytes, stored in the given byte order.
+    fn from_bytes(bytes: Self::Bytes, endian: Endian) -> Self;
+}
+
+macro_rules! impl_from_bytes {
+    ($($T:ty),*) => {
+        $(
+            impl FromBytes for $T {
+                type Bytes = [u8; core::mem::size_of::<$T>()];
+
+                #[inline]
+                fn from_bytes(bytes: Self::Bytes, endian: Endian) -> Self {
+                    match endian {
+                        Endian::Little => <$T>::from_le_bytes(bytes),
+                        Endian::Big => <$T>::from_be_bytes(bytes),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+/// The configuration of a [`Number`], allowing its byte order to be chosen by the context of the parser.
+#[derive(Default)]
+pub struct NumberCfg {
+    endian: Option<Endian>,
+}
+
+impl NumberCfg {
+    /// Set the byte order to be used while parsing
+    #[inline]
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = Some(endian);
+        self
+    }
+}
+
+/// See [`number`].
+pub struct Number<T, I, E> {
+    endian: Endian,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(T, I, E)>,
+}
+
+impl<T, I, E> Copy for Number<T, I, E> {}
+impl<T, I, E> Clone for Number<T, I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, I, E> ParserSealed<'a, I, T, E> for Number<T, I, E>
+where
+    T: FromBytes,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        self.go_cfg::<M>(inp, NumberCfg::default())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<u8>) -> Result<Option<T>, ()> {
+        let mut bytes = T::Bytes::default();
+        for byte in bytes.as_mut() {
+            *byte = gen.any_token().ok_or(())?;
+        }
+        gen.tokens.extend_from_slice(bytes.as_ref());
+        Ok(Some(T::from_bytes(bytes, self.endian)))
+    }
+
+    go_extra!(T);
+}
+
+impl<'a, T, I, E> ConfigParserSealed<'a, I, T, E> for Number<T, I, E>
+where
+    T: FromBytes,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    type Config = NumberCfg;
+
+    #[inline]
+    fn go_cfg<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        cfg: Self::Config,
+    ) -> PResult<M, T> {
+        let endian = cfg.endian.unwrap_or(self.endian);
+        let before = inp.offset();
+        let mut bytes = T::Bytes::default();
+        for byte in bytes.as_mut() {
+            match inp.next_inner() {
+                (_, Some(b)) => *byte = b,
+                (at, found) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                    return Err(());
+                }
+            }
+        }
+        Ok(M::bind(|| T::from_bytes(bytes, endian)))
+    }
+
+    go_cfg_extra!(T);
+}
+
+/// A parser that reads a number of type `T` from its [`FromBytes::Bytes`], stored in the given byte order.
+///
+/// If the input ends before all of the bytes have been read, the parser fails with an error that spans the bytes that
+/// were read.
+///
+/// The output type of this parser is `T`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{number, Endian}};
+/// let le = number::<u16, &[u8], extra::Err<Simple<u8>>>(Endian::Little);
+/// let be = number::<u16, &[u8], extra::Err<Simple<u8>>>(Endian::Big);
+/// assert_eq!(le.parse(&[0x2a, 0x00]).into_result(), Ok(42));
+/// assert_eq!(be.parse(&[0x00, 0x2a]).into_result(), Ok(42));
+/// // Both bytes are needed
+/// assert!(be.parse(&[0x2a]).has_errors());
+///
+/// // The byte order of a TIFF file is given by its first two bytes, and applies to the rest of the file
+/// let endian = just::<_, &[u8], extra::Err<Simple<u8>>>(b"II")
+///     .to(Endian::Little)
+///     .or(just(b"MM").to(Endian::Big));
+/// let magic = number::<u16, _, _>(Endian::Little).configure(|cfg, endian: &Endian| cfg.endian(*endian));
+/// let header = endian.then_with_ctx(magic);
+/// assert_eq!(header.parse(b"II\x2a\x00").into_result(), Ok(42));
+/// assert_eq!(header.parse(b"MM\x00\x2a").into_result(), Ok(42));
+/// ```
+pub const fn number<'a, T, I, E>(endian: Endian) -> Number<T, I, E>
+where
+    T: FromBytes,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    Number {
+        endian,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+macro_rules! numbers {
+    ($($name:ident: $T:ty, $endian:ident, $doc:literal;)*) => {
+        $(
+            #[doc = $doc]
+            ///
+            /// See [`number`] for more information.
+            pub const fn $name<'a, I, E>() -> Number<$T, I, E>
+            where
+                I: ValueInput<'a> + Input<'a, Token = u8>,
+                E: ParserExtra<'a, I>,
+            {
+                number(Endian::$endian)
+            }
+        )*
+    };
+}
+
+numbers! {
+    u8: u8, Little, "A parser that reads a `u8` from one byte.";
+    i8: i8, Little, "A parser that reads an `i8` from one byte.";
+    u16_le: u16, Little, "A parser that reads a little-endian `u16`.";
+    u16_be: u16, Big, "A parser that reads a big-endian `u16`.";
+    u32_le: u32, Little, "A parser that reads a little-endian `u32`.";
+    u32_be: u32, Big, "A parser that reads a big-endian `u32`.";
+    u64_le: u64, Little, "A parser that reads a little-endian `u64`.";
+    u64_be: u64, Big, "A parser that reads a big-endian `u64`.";
+    u128_le: u128, Little, "A parser that reads a little-endian `u128`.";
+    u128_be: u128, Big, "A parser that reads a big-endian `u128`.";
+    i16_le: i16, Little, "A parser that reads a little-endian `i16`.";
+    i16_be: i16, Big, "A parser that reads a big-endian `i16`.";
+    i32_le: i32, Little, "A parser that reads a little-endian `i32`.";
+    i32_be: i32, Big, "A parser that reads a big-endian `i32`.";
+    i64_le: i64, Little, "A parser that reads a little-endian `i64`.";
+    i64_be: i64, Big, "A parser that reads a big-endian `i64`.";
+    i128_le: i128, Little, "A parser that reads a little-endian `i128`.";
+    i128_be: i128, Big, "A parser that reads a big-endian `i128`.";
+    f32_le: f32, Little, "A parser that reads a little-endian `f32`, stored in IEEE 754 format.";
+    f32_be: f32, Big, "A parser that reads a big-endian `f32`, stored in IEEE 754 format.";
+    f64_le: f64, Little, "A parser that reads a little-endian `f64`, stored in IEEE 754 format.";
+    f64_be: f64, Big, "A parser that reads a big-endian `f64`, stored in IEEE 754 format.";
+}
+
+/// The configuration of a [`Take`], allowing the number of tokens it takes to be chosen by the context of the parser.
+#[derive(Default)]
+pub struct TakeCfg {
+    count: Option<usize>,
+}
+
+impl TakeCfg {
+    /// Set the number of tokens to be taken while parsing
+    #[inline]
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+}
+
+/// See [`take`].
+pub struct Take<I, E> {
+    count: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Copy for Take<I, E> {}
+impl<I, E> Clone for Take<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, I::Slice, E> for Take<I, E>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Slice> {
+        self.go_cfg::<M>(inp, TakeCfg::default())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<I::Slice>, ()>
+    where
+        I::Token: Clone,
+    {
+        for _ in 0..self.count {
+            let token = gen.any_token().ok_or(())?;
+            gen.tokens.push(token);
+        }
+        Ok(None)
+    }
+
+    go_extra!(I::Slice);
+}
+
+impl<'a, I, E> ConfigParserSealed<'a, I, I::Slice, E> for Take<I, E>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+{
+    type Config = TakeCfg;
+
+    #[inline]
+    fn go_cfg<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        cfg: Self::Config,
+    ) -> PResult<M, I::Slice> {
+        let count = cfg.count.unwrap_or(self.count);
+        let before = inp.offset();
+        for taken in 0..count {
+            if inp.next_maybe_inner().1.is_none() {
+                let err = E::Error::custom(
+                    inp.span_since(before),
+                    format_args!("expected {count} tokens, but the input ends after {taken}"),
+                );
+                inp.add_alt_err(inp.offset().offset, err);
+                return Err(());
+            }
+        }
+        let after = inp.offset();
+        Ok(M::bind(|| inp.slice(before..after)))
+    }
+
+    go_cfg_extra!(I::Slice);
+}
+
+/// A parser that takes exactly `count` tokens (or bytes, for an input of bytes), producing the slice of the input that
+/// they occupy.
+///
+/// The number of tokens can also be chosen while parsing with [`ConfigParser::configure`], such as from a length that
+/// was read earlier: see [`length_prefixed`]. If the input ends before all of the tokens have been taken, the parser
+/// fails with an error (created with [`Error::custom`]) that spans the tokens that were taken, and says how many were
+/// missing.
+///
+/// The output type of this parser is `I::Slice`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{self, take}};
+/// let four = take::<&[u8], extra::Err<Rich<u8>>>(4);
+/// assert_eq!(four.parse(b"\x7fELF").into_result(), Ok(&b"\x7fELF"[..]));
+/// assert_eq!(
+///     four.parse(b"\x7fE").into_errors()[0].to_string(),
+///     "expected 4 tokens, but the input ends after 2",
+/// );
+///
+/// // The raw payload of a chunk, prefixed by its length
+/// let chunk = binary::u8::<&[u8], extra::Err<Rich<u8>>>()
+///     .then_with_ctx(take(0).configure(|cfg, len: &u8| cfg.count(*len as usize)));
+/// assert_eq!(chunk.parse(b"\x03abc").into_result(), Ok(&b"abc"[..]));
+/// ```
+pub const fn take<'a, I, E>(count: usize) -> Take<I, E>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+{
+    Take {
+        count,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that reads a length with the `length` parser, and then parses exactly that many of the following tokens
+/// with the `payload` parser. This is how most binary protocols delimit their messages and fields.
+///
+/// The payload parser must consume all of the tokens it is given, and cannot read beyond them. If the input ends
+/// before all of them are available, the parser fails with an error from [`take`]. Lengths that do not fit in a
+/// `usize` are treated as being larger than the input.
+///
+/// The raw tokens of the payload can be produced by using [`any`]`().repeated().slice()` as the payload parser.
+///
+/// The output type of this parser is `O`, the output of `payload`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{self, length_prefixed}};
+/// // A list of strings, each prefixed with its length as a big-endian `u16`
+/// let string = length_prefixed(
+///     binary::u16_be::<&[u8], extra::Err<Rich<u8>>>(),
+///     any().repeated().slice().map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+/// );
+/// let strings = string.clone().repeated().collect::<Vec<_>>();
+/// assert_eq!(
+///     strings.parse(b"\x00\x05hello\x00\x00\x00\x05world").into_result(),
+///     Ok(vec!["hello".to_string(), String::new(), "world".to_string()]),
+/// );
+///
+/// // The input is truncated
+/// assert!(string.parse(b"\x00\x05hell").has_errors());
+/// // The payload parser must consume the whole payload
+/// let byte = length_prefixed(binary::u8::<&[u8], extra::Err<Rich<u8>>>(), binary::u8());
+/// assert_eq!(byte.parse(b"\x01\x2a").into_result(), Ok(42));
+/// assert!(byte.parse(b"\x02\x2a\x2a").has_errors());
+/// ```
+pub fn length_prefixed<'a, L, OL, P, O, I, E>(
+    length: L,
+    payload: P,
+) -> impl Parser<'a, I, O, E> + Clone
+where
+    L: Parser<'a, I, OL, E> + Clone,
+    OL: TryInto<usize> + Clone + 'a,
+    P: Parser<'a, I, O, E> + Clone,
+    I: SliceInput<'a, Slice = I>,
+    E: ParserExtra<'a, I>,
+{
+    payload.nested_in(length.then_with_ctx(
+        take(0).configure(|cfg, len: &OL| cfg.count(len.clone().try_into().unwrap_or(usize::MAX))),
+    ))
+}
+
+/// See [`checksummed`].
+pub struct Checksummed<P, C, F, T> {
+    payload: P,
+    checksum: C,
+    compute: F,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<T>,
+}
+
+impl<P: Copy, C: Copy, F: Copy, T> Copy for Checksummed<P, C, F, T> {}
+impl<P: Clone, C: Clone, F: Clone, T> Clone for Checksummed<P, C, F, T> {
+    fn clone(&self) -> Self {
+        Self {
+            payload: self.payload.clone(),
+            checksum: self.checksum.clone(),
+            compute: self.compute.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, P, C, F, T> ParserSealed<'a, I, O, E> for Checksummed<P, C, F, T>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    P: Parser<'a, I, O, E>,
+    C: Parser<'a, I, T, E>,
+    F: Fn(I::Slice) -> T,
+    T: PartialEq,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let out = self.payload.go::<M>(inp)?;
+        let payload = inp.slice(before..inp.offset());
+        let before_checksum = inp.offset();
+        let checksum = self.checksum.go::<Emit>(inp)?;
+        if (self.compute)(payload) == checksum {
+            Ok(out)
+        } else {
+            let err = E::Error::custom(inp.span_since(before_checksum), "checksum mismatch");
+            inp.add_alt_err(inp.offset().offset, err);
+            Err(())
+        }
+    }
+
+    graph_node!(payload: O, checksum: T);
+
+    go_extra!(O);
+}
+
+/// A parser that parses a payload with the `payload` parser, and then a checksum of it with the `checksum` parser,
+/// failing unless the checksum is equal to the one computed from the payload's slice of the input by `compute`. This
+/// is how framed protocols and archive formats detect corrupted data.
+///
+/// [`crc32`] and [`adler32`] compute common checksums, and any other can be computed with a closure. When the checksums
+/// differ, the parser fails with a `checksum mismatch` error (created with [`Error::custom`]) that spans the checksum.
+///
+/// The output type of this parser is `O`, the output of `payload`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{self, checksummed, crc32}};
+/// // A frame of bytes, up to a zero, followed by the little-endian CRC-32 of the frame
+/// let frame = checksummed(
+///     any::<&[u8], extra::Err<Rich<u8>>>().filter(|b| *b != 0).repeated().then(just(0)).slice(),
+///     binary::u32_le(),
+///     crc32,
+/// );
+/// assert_eq!(frame.parse(b"hello\0\x3e\xd9\xef\xd6").into_result(), Ok(&b"hello\0"[..]));
+/// assert_eq!(
+///     frame.parse(b"jello\0\x3e\xd9\xef\xd6").into_errors()[0].to_string(),
+///     "checksum mismatch",
+/// );
+///
+/// // A checksum computed by a closure: the sum of the bytes, modulo 256
+/// let sum = checksummed(
+///     binary::u16_be::<&[u8], extra::Err<Rich<u8>>>(),
+///     binary::u8(),
+///     |bytes: &[u8]| bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)),
+/// );
+/// assert_eq!(sum.parse(&[0x01, 0x02, 0x03]).into_result(), Ok(0x0102));
+/// assert!(sum.parse(&[0x01, 0x02, 0x04]).has_errors());
+/// ```
+pub const fn checksummed<'a, I, O, E, P, C, F, T>(
+    payload: P,
+    checksum: C,
+    compute: F,
+) -> Checksummed<P, C, F, T>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    P: Parser<'a, I, O, E>,
+    C: Parser<'a, I, T, E>,
+    F: Fn(I::Slice) -> T,
+    T: PartialEq,
+{
+    Checksummed {
+        payload,
+        checksum,
+        compute,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// Compute the CRC-32 checksum of some bytes, as used by zip, gzip, PNG and Ethernet (the IEEE 802.3 polynomial, in
+/// its reflected form).
+///
+/// ```
+/// # use chumsky::binary::crc32;
+/// assert_eq!(crc32(b"123456789"), 0xcbf43926);
+/// ```
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, b| {
+        (0..8).fold(crc ^ u32::from(*b), |crc, _| {
+            (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Compute the Adler-32 checksum of some bytes, as used by zlib.
+///
+/// ```
+/// # use chumsky::binary::adler32;
+/// assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+/// ```
+pub fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (a, b) = bytes.iter().fold((1, 0), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % MOD;
+        (a, (b + a) % MOD)
+    });
+    b << 16 | a
+}
+
+// Skips `count` bytes of padding, each of which must be `fill` (if given)
+fn skip_padding<'a, I, E>(
+    inp: &mut InputRef<'a, '_, I, E>,
+    count: usize,
+    fill: Option<u8>,
+) -> Result<(), ()>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    for _ in 0..count {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(b)) if fill.map_or(true, |fill| b == fill) => {}
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(
+                    at,
+                    fill.map(|fill| Some(MaybeRef::Val(fill))),
+                    found.map(|f| f.into()),
+                    err_span,
+                );
+                return Err(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The configuration of an [`AlignTo`], allowing the offset that alignment is relative to be chosen by the context of
+/// the parser.
+#[derive(Default)]
+pub struct AlignToCfg {
+    origin: Option<usize>,
+}
+
+impl AlignToCfg {
+    /// Set the offset of the input that alignment is relative to, such as the start of the current section. If this is
+    /// beyond the offset that the parser starts at, the parser fails with an `alignment origin is beyond the current
+    /// offset` error (created with [`Error::custom`]).
+    #[inline]
+    pub fn origin(mut self, origin: usize) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+}
+
+/// See [`align_to`].
+pub struct AlignTo<I, E> {
+    align: usize,
+    fill: Option<u8>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Copy for AlignTo<I, E> {}
+impl<I, E> Clone for AlignTo<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, E> AlignTo<I, E> {
+    /// Require every skipped byte to be `fill` (typically zero), failing with an error at the first byte that is not.
+    pub const fn fill(self, fill: u8) -> Self {
+        Self {
+            fill: Some(fill),
+            ..self
+        }
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, (), E> for AlignTo<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        self.go_cfg::<M>(inp, AlignToCfg::default())
+    }
+
+    go_extra!(());
+}
+
+impl<'a, I, E> ConfigParserSealed<'a, I, (), E> for AlignTo<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    type Config = AlignToCfg;
+
+    #[inline]
+    fn go_cfg<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        cfg: Self::Config,
+    ) -> PResult<M, ()> {
+        let offset: usize = inp.offset().offset.into();
+        let Some(distance) = offset.checked_sub(cfg.origin.unwrap_or(0)) else {
+            let err = E::Error::custom(
+                inp.span_since(inp.offset()),
+                "alignment origin is beyond the current offset",
+            );
+            inp.add_alt_err(inp.offset().offset, err);
+            return Err(());
+        };
+        let misalignment = distance % self.align;
+        let count = (self.align - misalignment) % self.align;
+        skip_padding(inp, count, self.fill)?;
+        Ok(M::bind(|| ()))
+    }
+
+    go_cfg_extra!(());
+}
+
+/// A parser that skips bytes until the input is at a multiple of `align` bytes from its start, such as the padding
+/// before the sections of an ELF file, or between the entries of a TIFF or archive file.
+///
+/// Alignment can instead be made relative to another offset, such as the start of the current section, by setting
+/// [`AlignToCfg::origin`] with [`ConfigParser::configure`]. The skipped bytes can be required to have a particular value
+/// with [`AlignTo::fill`]. If the input ends before the boundary, the parser fails with an error.
+///
+/// The output type of this parser is `()`.
+///
+/// # Panics
+///
+/// Panics if `align` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{self, align_to}};
+/// // Each name ends with a zero, and is followed by more zeroes up to the next multiple of 4 bytes
+/// let name = any::<&[u8], extra::Err<Rich<u8>>>()
+///     .filter(|b: &u8| b.is_ascii_alphabetic())
+///     .repeated()
+///     .at_least(1)
+///     .slice()
+///     .then_ignore(just(0))
+///     .then_ignore(align_to(4).fill(0));
+/// let names = name.repeated().collect::<Vec<_>>();
+/// assert_eq!(
+///     names.parse(b"ab\0\0abcd\0\0\0\0").into_result(),
+///     Ok(vec![&b"ab"[..], b"abcd"]),
+/// );
+/// assert!(names.parse(b"ab\0x").has_errors());
+/// assert!(names.parse(b"ab\0").has_errors());
+///
+/// // Alignment relative to the start of a chunk, after its header byte
+/// let start = empty().map_with_span(|(), span: SimpleSpan| span.start);
+/// let field = binary::u8().then_ignore(align_to(2).configure(|cfg, start: &usize| cfg.origin(*start)));
+/// let chunk = binary::u8::<&[u8], extra::Err<Rich<u8>>>()
+///     .ignore_then(start.then_with_ctx(field.repeated().collect::<Vec<_>>()));
+/// assert_eq!(chunk.parse(b"\xff\x01.\x02.").into_result(), Ok(vec![1, 2]));
+///
+/// // The origin can't be beyond the current offset
+/// let bad = align_to::<&[u8], extra::Err<Rich<u8>>>(4).configure(|cfg, _| cfg.origin(8));
+/// let errs = bad.parse(b"").into_errors();
+/// assert_eq!(errs[0].to_string(), "alignment origin is beyond the current offset");
+/// ```
+pub const fn align_to<'a, I, E>(align: usize) -> AlignTo<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    assert!(align > 0, "alignment must be greater than zero");
+    AlignTo {
+        align,
+        fill: None,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// The configuration of a [`Padding`], allowing the number of bytes it skips to be chosen by the context of the parser.
+#[derive(Default)]
+pub struct PaddingCfg {
+    count: Option<usize>,
+}
+
+impl PaddingCfg {
+    /// Set the number of bytes to be skipped while parsing
+    #[inline]
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+}
+
+/// See [`padding`].
+pub struct Padding<I, E> {
+    count: usize,
+    fill: Option<u8>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Copy for Padding<I, E> {}
+impl<I, E> Clone for Padding<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, E> Padding<I, E> {
+    /// Require every skipped byte to be `fill` (typically zero), failing with an error at the first byte that is not.
+    pub const fn fill(self, fill: u8) -> Self {
+        Self {
+            fill: Some(fill),
+            ..self
+        }
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, (), E> for Padding<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        self.go_cfg::<M>(inp, PaddingCfg::default())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<u8>) -> Result<Option<()>, ()> {
+        for _ in 0..self.count {
+            let b = match self.fill {
+                Some(fill) => fill,
+                None => gen.any_token().ok_or(())?,
+            };
+            gen.tokens.push(b);
+        }
+        Ok(Some(()))
+    }
+
+    go_extra!(());
+}
+
+impl<'a, I, E> ConfigParserSealed<'a, I, (), E> for Padding<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    type Config = PaddingCfg;
+
+    #[inline]
+    fn go_cfg<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        cfg: Self::Config,
+    ) -> PResult<M, ()> {
+        skip_padding(inp, cfg.count.unwrap_or(self.count), self.fill)?;
+        Ok(M::bind(|| ()))
+    }
+
+    go_cfg_extra!(());
+}
+
+/// A parser that skips exactly `count` bytes of padding, such as reserved space in a header.
+///
+/// The number of bytes can also be chosen while parsing with [`ConfigParser::configure`], and the skipped bytes can be
+/// required to have a particular value with [`Padding::fill`]. See [`align_to`] for padding up to a boundary instead.
+///
+/// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{self, padding}};
+/// // A version byte, followed by three reserved bytes that must be zero
+/// let header = binary::u8::<&[u8], extra::Err<Rich<u8>>>().then_ignore(padding(3).fill(0));
+/// assert_eq!(header.parse(b"\x02\0\0\0").into_result(), Ok(2));
+/// assert!(header.parse(b"\x02\0\x01\0").has_errors());
+/// assert!(header.parse(b"\x02\0").has_errors());
+/// ```
+pub const fn padding<'a, I, E>(count: usize) -> Padding<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    Padding {
+        count,
+        fill: None,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`magic`].
+pub struct Magic<I, E> {
+    bytes: &'static [u8],
+    format: Option<&'static str>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Copy for Magic<I, E> {}
+impl<I, E> Clone for Magic<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, E> Magic<I, E> {
+    /// Name the format identified by the magic bytes (such as `ELF` or `PNG`), so that errors can say which format was
+    /// expected instead of listing the bytes.
+    pub const fn format(self, format: &'static str) -> Self {
+        Self {
+            format: Some(format),
+            ..self
+        }
+    }
+
+    // Returns true if the input continues with the magic bytes, without consuming them
+    fn matches<'a>(&self, inp: &mut InputRef<'a, '_, I, E>) -> bool
+    where
+        I: ValueInput<'a> + Input<'a, Token = u8>,
+        E: ParserExtra<'a, I>,
+    {
+        let before = inp.save();
+        let matches = self.bytes.iter().all(|b| inp.next_inner().1 == Some(*b));
+        inp.rewind(before);
+        matches
+    }
+}
+
+impl<I, E> fmt::Display for Magic<I, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.format {
+            Some(format) => write!(f, "{format}"),
+            None => {
+                for (i, b) in self.bytes.iter().enumerate() {
+                    write!(f, "{}{b:02x}", if i == 0 { "" } else { " " })?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, (), E> for Magic<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        let before = inp.offset();
+        for b in self.bytes {
+            if inp.next_inner().1 != Some(*b) {
+                let err = E::Error::custom(
+                    inp.span_since(before),
+                    format_args!("expected {self} magic bytes"),
+                );
+                inp.add_alt_err(inp.offset().offset, err);
+                return Err(());
+            }
+        }
+        Ok(M::bind(|| ()))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, u8>>> {
+        self.bytes.first().map(|b| vec![MaybeRef::Val(*b)])
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<u8>) -> Result<Option<()>, ()> {
+        gen.tokens.extend_from_slice(self.bytes);
+        Ok(Some(()))
+    }
+
+    go_extra!(());
+}
+
+/// A parser that accepts the given magic bytes: the signature at the start of a file (or message) that identifies its
+/// format, such as `\x7fELF` for ELF executables.
+///
+/// Unlike [`just`], a mismatch is reported with an error (created with [`Error::custom`]) naming the format that was
+/// expected, as given by [`Magic::format`], or listing the expected bytes in hexadecimal otherwise. The error spans the
+/// bytes that were examined.
+///
+/// See [`choice_by_magic`] for choosing between parsers of several formats.
+///
+/// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::magic};
+/// let elf = magic::<&[u8], extra::Err<Rich<u8>>>(b"\x7fELF").format("ELF");
+/// assert_eq!(elf.parse(b"\x7fELF").into_result(), Ok(()));
+/// assert_eq!(
+///     elf.parse(b"MZ\x90\x00").into_errors()[0].to_string(),
+///     "expected ELF magic bytes",
+/// );
+///
+/// let unnamed = magic::<&[u8], extra::Err<Rich<u8>>>(b"\xca\xfe\xba\xbe");
+/// assert_eq!(
+///     unnamed.parse(b"\xca\xfe").into_errors()[0].to_string(),
+///     "expected ca fe ba be magic bytes",
+/// );
+/// ```
+pub const fn magic<'a, I, E>(bytes: &'static [u8]) -> Magic<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    Magic {
+        bytes,
+        format: None,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`choice_by_magic`].
+#[derive(Copy, Clone)]
+pub struct ChoiceByMagic<T> {
+    branches: T,
+}
+
+// Lists the formats of a `ChoiceByMagic`, for its error
+struct Formats<'b, I, E>(&'b [&'b Magic<I, E>]);
+
+impl<I, E> fmt::Display for Formats<'_, I, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, magic) in self.0.iter().enumerate() {
+            write!(f, "{}{magic}", if i == 0 { "" } else { ", " })?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_choice_by_magic_for_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_choice_by_magic_for_tuple!($($X)*);
+        impl_choice_by_magic_for_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)+) => {
+        #[allow(unused_variables, non_snake_case)]
+        impl<'a, I, E, $($X),*, O> ParserSealed<'a, I, O, E> for ChoiceByMagic<($((Magic<I, E>, $X),)*)>
+        where
+            I: ValueInput<'a> + Input<'a, Token = u8>,
+            E: ParserExtra<'a, I>,
+            $($X: Parser<'a, I, O, E>),*
+        {
+            #[inline]
+            fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+                let ($($X,)*) = &self.branches;
+
+                $(
+                    if $X.0.matches(inp) {
+                        return $X.1.go::<M>(inp);
+                    }
+                )*
+
+                // The error spans as many bytes as the longest signature
+                let before = inp.offset();
+                let len = [$($X.0.bytes.len()),*].into_iter().max().unwrap_or(0);
+                for _ in 0..len {
+                    if inp.next_inner().1.is_none() {
+                        break;
+                    }
+                }
+                let err = E::Error::custom(
+                    inp.span_since(before),
+                    format_args!(
+                        "unrecognised magic bytes, expected one of: {}",
+                        Formats(&[$(&$X.0),*]),
+                    ),
+                );
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+
+            #[cfg(feature = "alloc")]
+            fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+                let id = graph.add::<Self>();
+                let ($($X,)*) = &self.branches;
+                $(
+                    let child = ParserSealed::<I, O, E>::graph_node(&$X.1, graph);
+                    graph.edge(id, child);
+                )*
+                id
+            }
+
+            #[cfg(feature = "alloc")]
+            fn gen_input(&self, gen: &mut generate::Generator<u8>) -> Result<Option<O>, ()> {
+                let ($($X,)*) = &self.branches;
+                let count = [$(stringify!($X)),*].len();
+                gen.choose(count, |gen, branch| {
+                    $(
+                        if branch == 0 {
+                            return $X.1.gen_input(gen);
+                        }
+                        let branch = branch - 1;
+                    )*
+                    unreachable!()
+                })
+            }
+
+            go_extra!(O);
+        }
+    };
+}
+
+impl_choice_by_magic_for_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_ M_ N_ O_ P_ Q_ R_ S_ T_ U_ V_ W_ X_ Y_ Z_);
+
+/// A parser that detects the format of its input from its magic bytes, and then parses the whole input with the
+/// parser for that format.
+///
+/// The branches are pairs of a [`magic`] signature and a parser, which is given the input from the start, so that it
+/// can parse the signature as part of the file's header. The first branch whose signature matches is chosen, and
+/// other branches are not attempted even if it fails, so the errors produced are those of the detected format alone.
+/// If no signature matches, the parser fails with an error (created with [`Error::custom`]) listing the expected
+/// formats.
+///
+/// The output type of this parser is `O`, the output shared by the parsers of every branch.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{self, choice_by_magic, magic}};
+/// #[derive(Debug, PartialEq)]
+/// enum Image {
+///     Png { width: u32, height: u32 },
+///     Gif { width: u16, height: u16 },
+/// }
+///
+/// type Extra = extra::Err<Rich<'static, u8>>;
+///
+/// let png_magic = magic::<&[u8], Extra>(b"\x89PNG\r\n\x1a\n").format("PNG");
+/// let png = png_magic
+///     .ignore_then(just(b"\x00\x00\x00\x0dIHDR"))
+///     .ignore_then(binary::u32_be().then(binary::u32_be()))
+///     .map(|(width, height)| Image::Png { width, height });
+///
+/// let gif_magic = magic::<&[u8], Extra>(b"GIF8").format("GIF");
+/// let gif = gif_magic
+///     .ignore_then(just(b"7a").or(just(b"9a")))
+///     .ignore_then(binary::u16_le().then(binary::u16_le()))
+///     .map(|(width, height)| Image::Gif { width, height });
+///
+/// let image = choice_by_magic(((png_magic, png), (gif_magic, gif)));
+///
+/// assert_eq!(
+///     image.parse(b"GIF89a\x40\x01\xf0\x00").into_result(),
+///     Ok(Image::Gif { width: 320, height: 240 }),
+/// );
+/// assert_eq!(
+///     image.parse(b"\xff\xd8\xff\xe0").into_errors()[0].to_string(),
+///     "unrecognised magic bytes, expected one of: PNG, GIF",
+/// );
+/// ```
+pub const fn choice_by_magic<T>(branches: T) -> ChoiceByMagic<T> {
+    ChoiceByMagic { branches }
+}
+
+/// A type of string that can be produced from the bytes of a string in a binary format, by [`cstr`] and [`fixed_str`].
+///
+/// This is implemented for `&[u8]`, which accepts any bytes, and `&str`, which accepts only valid UTF-8. With the
+/// `alloc` feature, it is also implemented for `Cow<str>`, which replaces invalid UTF-8 with
+/// [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+pub trait FromByteStr<'a>: Sized {
+    /// Create a string from its bytes, or return `None` if they are not a valid string of this type.
+    fn from_byte_str(bytes: &'a [u8]) -> Option<Self>;
+}
+
+impl<'a> FromByteStr<'a> for &'a [u8] {
+    fn from_byte_str(bytes: &'a [u8]) -> Option<Self> {
+        Some(bytes)
+    }
+}
+
+impl<'a> FromByteStr<'a> for &'a str {
+    fn from_byte_str(bytes: &'a [u8]) -> Option<Self> {
+        core::str::from_utf8(bytes).ok()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> FromByteStr<'a> for alloc::borrow::Cow<'a, str> {
+    fn from_byte_str(bytes: &'a [u8]) -> Option<Self> {
+        Some(String::from_utf8_lossy(bytes))
+    }
+}
+
+// Converts the bytes of a string, failing with an error that spans them if they're not a valid string
+fn byte_str<'a, 'parse, S, I, E>(
+    inp: &mut InputRef<'a, 'parse, I, E>,
+    range: Range<input::Offset<'a, 'parse, I>>,
+) -> Result<S, ()>
+where
+    S: FromByteStr<'a>,
+    I: SliceInput<'a, Slice = &'a [u8]>,
+    E: ParserExtra<'a, I>,
+{
+    match S::from_byte_str(inp.slice(range.clone())) {
+        Some(s) => Ok(s),
+        None => {
+            let err = E::Error::custom(inp.span(range), "invalid UTF-8 in string");
+            inp.add_alt_err(inp.offset().offset, err);
+            Err(())
+        }
+    }
+}
+
+/// See [`cstr`].
+pub struct CStr<S, I, E> {
+    max_len: Option<usize>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(S, I, E)>,
+}
+
+impl<S, I, E> Copy for CStr<S, I, E> {}
+impl<S, I, E> Clone for CStr<S, I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, I, E> CStr<S, I, E> {
+    /// Set the most bytes that the string may have, not including its terminator. Longer strings are rejected with an
+    /// error (created with [`Error::custom`]).
+    pub const fn max_len(self, max_len: usize) -> Self {
+        Self {
+            max_len: Some(max_len),
+            ..self
+        }
+    }
+}
+
+impl<'a, S, I, E> ParserSealed<'a, I, S, E> for CStr<S, I, E>
+where
+    S: FromByteStr<'a>,
+    I: ValueInput<'a> + SliceInput<'a, Slice = &'a [u8]> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, S> {
+        let before = inp.offset();
+        let mut len = 0;
+        let end = loop {
+            let end = inp.offset();
+            match inp.next_inner() {
+                (_, Some(0)) => break end,
+                (_, Some(_)) if Some(len) == self.max_len => {
+                    let err = E::Error::custom(inp.span_since(before), "string is too long");
+                    inp.add_alt_err(inp.offset().offset, err);
+                    return Err(());
+                }
+                (_, Some(_)) => len += 1,
+                (at, None) => {
+                    let err_span = inp.span_since(end);
+                    inp.add_alt(at, Some(Some(MaybeRef::Val(0))), None, err_span);
+                    return Err(());
+                }
+            }
+        };
+        let s = byte_str(inp, before..end)?;
+        Ok(M::bind(|| s))
+    }
+
+    go_extra!(S);
+}
+
+/// A parser that reads a null-terminated string, as used by C and the binary formats derived from it: the bytes up
+/// to (and including) the next zero, producing the bytes before it.
+///
+/// The output type `S` decides how the bytes are interpreted: `&[u8]` accepts any bytes, `&str` requires valid UTF-8,
+/// and `Cow<str>` replaces invalid UTF-8. See [`FromByteStr`]. Strings that are not valid are rejected with an error
+/// (created with [`Error::custom`]) that spans them. If the input ends before the terminator, the parser fails with
+/// an error expecting it.
+///
+/// The output type of this parser is `S`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::cstr};
+/// let string = cstr::<&str, &[u8], extra::Err<Rich<u8>>>();
+/// assert_eq!(string.parse(b"hello\0").into_result(), Ok("hello"));
+/// assert_eq!(
+///     string.parse(b"\xffhello\0").into_errors()[0].to_string(),
+///     "invalid UTF-8 in string",
+/// );
+///
+/// // Strings of any bytes, of at most 4 bytes
+/// let names = cstr::<&[u8], &[u8], extra::Err<Rich<u8>>>()
+///     .max_len(4)
+///     .repeated()
+///     .collect::<Vec<_>>();
+/// assert_eq!(names.parse(b"a\0\xffb\0\0").into_result(), Ok(vec![&b"a"[..], b"\xffb", b""]));
+/// assert_eq!(
+///     names.parse(b"hello\0").into_errors()[0].to_string(),
+///     "string is too long",
+/// );
+/// ```
+pub const fn cstr<'a, S, I, E>() -> CStr<S, I, E>
+where
+    S: FromByteStr<'a>,
+    I: ValueInput<'a> + SliceInput<'a, Slice = &'a [u8]> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    CStr {
+        max_len: None,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`fixed_str`].
+pub struct FixedStr<S, I, E> {
+    len: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(S, I, E)>,
+}
+
+impl<S, I, E> Copy for FixedStr<S, I, E> {}
+impl<S, I, E> Clone for FixedStr<S, I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, S, I, E> ParserSealed<'a, I, S, E> for FixedStr<S, I, E>
+where
+    S: FromByteStr<'a>,
+    I: ValueInput<'a> + SliceInput<'a, Slice = &'a [u8]> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, S> {
+        let before = inp.offset();
+        let mut end = None;
+        for _ in 0..self.len {
+            let offset = inp.offset();
+            match inp.next_inner() {
+                (_, Some(0)) => end = end.or(Some(offset)),
+                (_, Some(_)) => {}
+                (at, None) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(at, None, None, err_span);
+                    return Err(());
+                }
+            }
+        }
+        let end = end.unwrap_or_else(|| inp.offset());
+        let s = byte_str(inp, before..end)?;
+        Ok(M::bind(|| s))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<u8>) -> Result<Option<S>, ()> {
+        gen.tokens.extend(core::iter::repeat(0).take(self.len));
+        Ok(None)
+    }
+
+    go_extra!(S);
+}
+
+/// A parser that reads a string stored in a field of exactly `len` bytes, such as the names in the headers of tar
+/// archives. The string ends at the first zero within the field, with the bytes after it being padding, or fills the
+/// whole field if it has no zero.
+///
+/// Like [`cstr`], the output type `S` decides how the bytes are interpreted: see [`FromByteStr`]. If the input ends
+/// before the end of the field, the parser fails with an error.
+///
+/// The output type of this parser is `S`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::fixed_str};
+/// let name = fixed_str::<&str, &[u8], extra::Err<Rich<u8>>>(8);
+/// assert_eq!(name.parse(b"main.rs\0").into_result(), Ok("main.rs"));
+/// assert_eq!(name.parse(b"lib\0\0\0\0\0").into_result(), Ok("lib"));
+/// assert_eq!(name.parse(b"build.rs").into_result(), Ok("build.rs"));
+/// assert!(name.parse(b"lib\0").has_errors());
+/// ```
+pub const fn fixed_str<'a, S, I, E>(len: usize) -> FixedStr<S, I, E>
+where
+    S: FromByteStr<'a>,
+    I: ValueInput<'a> + SliceInput<'a, Slice = &'a [u8]> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    FixedStr {
+        len,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+// The most bytes that a variable-length integer can have, enough for any 64-bit integer
+const MAX_VARINT_BYTES: usize = 10;
+
+#[derive(Copy, Clone)]
+enum VarintKind {
+    Unsigned,
+    Signed,
+    Vlq,
+}
+
+/// See [`uleb128`], [`sleb128`] and [`vlq`].
+pub struct Varint<T, I, E> {
+    kind: VarintKind,
+    max_bytes: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(T, I, E)>,
+}
+
+impl<T, I, E> Copy for Varint<T, I, E> {}
+impl<T, I, E> Clone for Varint<T, I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, I, E> Varint<T, I, E> {
+    /// Set the most bytes that the integer may be made of, such as 5 for the 32-bit integers of WebAssembly, or 4 for
+    /// the lengths of MIDI events. Longer integers are rejected with an error (created with [`Error::custom`]). The
+    /// default is 10, enough for any 64-bit integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bytes` is zero or greater than 10.
+    pub fn max_bytes(self, max_bytes: usize) -> Self {
+        assert!(
+            (1..=MAX_VARINT_BYTES).contains(&max_bytes),
+            "a variable-length integer must have between 1 and 10 bytes",
+        );
+        Self { max_bytes, ..self }
+    }
+}
+
+impl<'a, T, I, E> ParserSealed<'a, I, T, E> for Varint<T, I, E>
+where
+    T: TryFrom<i128>,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let before = inp.offset();
+        // No more than 70 bits are read, so the value always fits
+        let (mut value, mut shift) = (0i128, 0);
+        let mut count = 0;
+        let last = loop {
+            let byte = match inp.next_inner() {
+                (_, Some(byte)) => byte,
+                (at, found) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                    return Err(());
+                }
+            };
+            count += 1;
+            let payload = i128::from(byte & 0x7f);
+            match self.kind {
+                VarintKind::Unsigned | VarintKind::Signed => value |= payload << shift,
+                VarintKind::Vlq => value = value << 7 | payload,
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break byte;
+            } else if count == self.max_bytes {
+                let err = E::Error::custom(
+                    inp.span_since(before),
+                    "variable-length integer is too long",
+                );
+                inp.add_alt_err(inp.offset().offset, err);
+                return Err(());
+            }
+        };
+        // The sign of a signed integer is the highest bit of its last byte
+        if let (VarintKind::Signed, true) = (self.kind, last & 0x40 != 0) {
+            value |= -1 << shift;
+        }
+        match T::try_from(value) {
+            Ok(value) => Ok(M::bind(|| value)),
+            Err(_) => {
+                let err = E::Error::custom(
+                    inp.span_since(before),
+                    "variable-length integer is out of range",
+                );
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(T);
+}
+
+/// A parser that reads an unsigned integer in the [LEB128](https://en.wikipedia.org/wiki/LEB128) variable-length
+/// encoding, as used by DWARF, WebAssembly and the varints of Protocol Buffers.
+///
+/// Each byte holds 7 bits of the integer, least significant first, with the high bit set on every byte but the last.
+/// If the integer does not fit within `T`, or is made of more than [`Varint::max_bytes`] bytes, the parser fails with
+/// an error (created with [`Error::custom`]) that spans the whole integer.
+///
+/// The output type of this parser is `T`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::uleb128};
+/// let int = uleb128::<u32, &[u8], extra::Err<Rich<u8>>>();
+/// assert_eq!(int.parse(&[0x2a]).into_result(), Ok(42));
+/// assert_eq!(int.parse(&[0xe5, 0x8e, 0x26]).into_result(), Ok(624485));
+///
+/// // Too large for a `u32`
+/// assert!(int.parse(&[0x80, 0x80, 0x80, 0x80, 0x10]).has_errors());
+/// // Unterminated
+/// assert!(int.parse(&[0x80, 0x80]).has_errors());
+/// ```
+pub const fn uleb128<'a, T, I, E>() -> Varint<T, I, E>
+where
+    T: TryFrom<i128>,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    Varint {
+        kind: VarintKind::Unsigned,
+        max_bytes: MAX_VARINT_BYTES,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that reads a signed integer in the [LEB128](https://en.wikipedia.org/wiki/LEB128) variable-length
+/// encoding, as used by DWARF and WebAssembly.
+///
+/// This is like [`uleb128`], except that the integer is in two's complement, sign-extended from the highest bit of
+/// its last byte.
+///
+/// The output type of this parser is `T`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::sleb128};
+/// let int = sleb128::<i64, &[u8], extra::Err<Rich<u8>>>();
+/// assert_eq!(int.parse(&[0x02]).into_result(), Ok(2));
+/// assert_eq!(int.parse(&[0x7e]).into_result(), Ok(-2));
+/// assert_eq!(int.parse(&[0xc0, 0xbb, 0x78]).into_result(), Ok(-123456));
+/// ```
+pub const fn sleb128<'a, T, I, E>() -> Varint<T, I, E>
+where
+    T: TryFrom<i128>,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    Varint {
+        kind: VarintKind::Signed,
+        max_bytes: MAX_VARINT_BYTES,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that reads an unsigned integer in the [variable-length quantity](https://en.wikipedia.org/wiki/Variable-length_quantity)
+/// encoding, as used by MIDI files.
+///
+/// This is like [`uleb128`], except that the most significant 7 bits of the integer come first.
+///
+/// The output type of this parser is `T`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::vlq};
+/// // The lengths of MIDI events have at most 4 bytes
+/// let length = vlq::<u32, &[u8], extra::Err<Rich<u8>>>().max_bytes(4);
+/// assert_eq!(length.parse(&[0x7f]).into_result(), Ok(127));
+/// assert_eq!(length.parse(&[0x81, 0x80, 0x00]).into_result(), Ok(16384));
+/// assert!(length.parse(&[0xff, 0xff, 0xff, 0xff, 0x7f]).has_errors());
+/// ```
+pub const fn vlq<'a, T, I, E>() -> Varint<T, I, E>
+where
+    T: TryFrom<i128>,
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    Varint {
+        kind: VarintKind::Vlq,
+        max_bytes: MAX_VARINT_BYTES,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// The position of a bit within a [`Bits`] input: the offset of its byte in the underlying input, and its index within
+/// that byte (where `0` is the most significant bit).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitOffset<O> {
+    byte: O,
+    bit: u8,
+}
+
+impl<O> BitOffset<O> {
+    /// Returns true if this position is at the start of a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.bit == 0
+    }
+}
+
+impl<O: Into<usize>> From<BitOffset<O>> for usize {
+    fn from(offset: BitOffset<O>) -> usize {
+        offset.byte.into() * 8 + offset.bit as usize
+    }
+}
+
+/// An input that reads the bytes of another input one bit at a time, with the most significant bit of each byte first.
+///
+/// The tokens of this input are `bool`s, and its spans are those of the underlying input, covering every byte that
+/// contains part of the spanned bits. This allows packed structures, such as the headers of network protocols, to be
+/// parsed field by field with [`bits`], [`bit_flag`] and [`aligned`], while errors still point at the bytes where they
+/// occurred.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, binary::{aligned, bit_flag, bits, Bits}};
+/// // The first byte of an IPv4 header holds two 4-bit fields, and a DNS header contains single-bit flags
+/// let version = bits::<Bits<&[u8]>, extra::Err<Simple<bool>>>(4);
+/// let ihl = bits(4);
+/// let flags = bit_flag().then(bit_flag()).then_ignore(aligned());
+/// let header = version.then(ihl).then(flags).then(bits(8));
+///
+/// let input = Bits::new(&[0x45, 0b1000_0000, 0xff][..]);
+/// assert_eq!(header.parse(input).into_result(), Ok(((((4, 5), (true, false)), 255))));
+///
+/// // The span of an error is the byte that the bits came from
+/// let errs = header.parse(Bits::new(&[0x45, 0x80][..])).into_errors();
+/// assert_eq!(errs[0].span().into_range(), 2..2);
+/// ```
+#[derive(Copy, Clone)]
+pub struct Bits<I> {
+    input: I,
+}
+
+impl<I> Bits<I> {
+    /// Read the given input of bytes one bit at a time.
+    pub fn new(input: I) -> Self {
+        Self { input }
+    }
+}
+
+impl<I> Sealed for Bits<I> {}
+impl<'a, I> Input<'a> for Bits<I>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+{
+    type Offset = BitOffset<I::Offset>;
+    type Token = bool;
+    type Span = I::Span;
+
+    #[inline(always)]
+    fn start(&self) -> Self::Offset {
+        BitOffset {
+            byte: self.input.start(),
+            bit: 0,
+        }
+    }
+
+    type TokenMaybe = bool;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        // Spans cover the whole of any byte that they end part of the way through
+        let end = if range.end.is_aligned() {
+            range.end.byte
+        } else {
+            self.input.next(range.end.byte).0
+        };
+        self.input.span(range.start.byte..end)
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        if offs.bit > 0 {
+            BitOffset {
+                bit: offs.bit - 1,
+                ..offs
+            }
+        } else if I::prev(offs.byte) != offs.byte {
+            BitOffset {
+                byte: I::prev(offs.byte),
+                bit: 7,
+            }
+        } else {
+            offs
+        }
+    }
+}
+
+impl<'a, I> ExactSizeInput<'a> for Bits<I>
+where
+    I: ValueInput<'a> + ExactSizeInput<'a> + Input<'a, Token = u8>,
+{
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        self.input.span_from(range.start.byte..)
+    }
+}
+
+impl<'a, I> ValueInput<'a> for Bits<I>
+where
+    I: ValueInput<'a> + Input<'a, Token = u8>,
+{
+    #[inline(always)]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        match self.input.next(offset.byte) {
+            (next, Some(byte)) => {
+                let bit = byte & (0x80 >> offset.bit) != 0;
+                let offset = if offset.bit == 7 {
+                    BitOffset { byte: next, bit: 0 }
+                } else {
+                    BitOffset {
+                        bit: offset.bit + 1,
+                        ..offset
+                    }
+                };
+                (offset, Some(bit))
+            }
+            (_, None) => (offset, None),
+        }
+    }
+}
+
+/// See [`bits`].
+pub struct BitField<I, E> {
+    count: u32,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Copy for BitField<I, E> {}
+impl<I, E> Clone for BitField<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, u64, E> for BitField<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = bool>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, u64> {
+        let before = inp.offset();
+        let mut value = 0;
+        for _ in 0..self.count {
+            match inp.next_inner() {
+                (_, Some(bit)) => value = value << 1 | bit as u64,
+                (at, found) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                    return Err(());
+                }
+            }
+        }
+        Ok(M::bind(|| value))
+    }
+
+    go_extra!(u64);
+}
+
+/// A parser that reads an unsigned integer from the given number of bits of an input of bits (such as [`Bits`]), most
+/// significant bit first.
+///
+/// The output type of this parser is `u64`.
+///
+/// # Panics
+///
+/// Panics if `count` is greater than 64.
+pub const fn bits<'a, I, E>(count: u32) -> BitField<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = bool>,
+    E: ParserExtra<'a, I>,
+{
+    assert!(count <= 64, "a bit field can't have more than 64 bits");
+    BitField {
+        count,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that reads a single bit of an input of bits (such as [`Bits`]), which is `true` if it is set.
+///
+/// The output type of this parser is `bool`.
+pub const fn bit_flag<'a, I, E>() -> Any<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = bool>,
+    E: ParserExtra<'a, I>,
+{
+    any()
+}
+
+/// See [`aligned`].
+pub struct Aligned<I, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Copy for Aligned<I, E> {}
+impl<I, E> Clone for Aligned<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I, O, E> ParserSealed<'a, I, (), E> for Aligned<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Offset = BitOffset<O>>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        // The rest of a byte is always present, so this can't fail
+        while !inp.offset.is_aligned() {
+            inp.next_inner();
+        }
+        Ok(M::bind(|| ()))
+    }
+
+    go_extra!(());
+}
+
+/// A parser that skips the remaining bits of the current byte of a [`Bits`] input (if any), so that the next parser
+/// starts at a byte boundary. The skipped bits are typically padding, or reserved for future use.
+///
+/// The output type of this parser is `()`.
+pub const fn aligned<'a, I, O, E>() -> Aligned<I, E>
+where
+    I: ValueInput<'a> + Input<'a, Offset = BitOffset<O>>,
+    E: ParserExtra<'a, I>,
+{
+    Aligned {
+        phantom: EmptyPhantom::new(),
+    }
+}
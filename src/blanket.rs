@@ -1,6 +1,6 @@
 use super::*;
 
-impl<'a, 'b, T, I, O, E> ParserSealed<'a, I, O, E> for &'b T
+impl<'a, T, I, O, E> ParserSealed<'a, I, O, E> for &T
 where
     T: Parser<'a, I, O, E>,
     I: Input<'a>,
@@ -13,10 +13,32 @@ where
         (*self).go::<M>(inp)
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        (*self).skip_leading_trivia(inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        (*self).first_tokens()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        (*self).graph_node(graph)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        (*self).gen_input(gen)
+    }
+
     go_extra!(O);
 }
 
-impl<'a, 'b, T, I, O, E> ConfigParserSealed<'a, I, O, E> for &'b T
+impl<'a, T, I, O, E> ConfigParserSealed<'a, I, O, E> for &T
 where
     T: ConfigParser<'a, I, O, E>,
     I: Input<'a>,
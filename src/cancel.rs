@@ -0,0 +1,182 @@
+//! Stopping long-running parses early, through cooperative cancellation or step limits.
+//!
+//! A language server that reparses a document on every edit should stop working on the old text as soon as a newer
+//! edit arrives. [`Parser::parse_cancellable`] takes a [`CancelToken`], which can be cloned and cancelled from another
+//! thread (or from within the parse itself, such as in a [`Parser::map`]). The parser checks the token periodically as
+//! it reads the input, and once it has been cancelled the parse stops as quickly as possible and returns
+//! [`Cancelled`] instead of its usual result.
+//!
+//! Services that parse untrusted input may instead want to limit the amount of work done by each parse, protecting
+//! them from inputs that cause a grammar to backtrack excessively. [`Parser::parse_with_step_limit`] counts each token
+//! read and each time the parser backtracks as a step, and returns [`StepLimitExceeded`] if the parse takes more than
+//! the given number of steps.
+//!
+//! *Cancellation requires the `cancel` feature, and step limits require the `step-limit` feature.*
+//!
+//! Once a parse is stopped, the parser behaves as if the input had ended, so parsers that don't read input (such as a
+//! [`Parser::map`] that does a lot of work) may still run after the token has been cancelled or the limit reached.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, cancel::{CancelToken, Cancelled}};
+//! let token = CancelToken::new();
+//! let words = text::ident::<_, _, extra::Err<Rich<char>>>()
+//!     .padded()
+//!     .repeated()
+//!     .count();
+//! let input = "hello world ".repeat(1000);
+//!
+//! assert_eq!(words.parse_cancellable(input.as_str(), &token).map(|res| res.into_result()), Ok(Ok(2000)));
+//!
+//! // Typically, the token would be cancelled by another thread
+//! token.cancel();
+//! assert_eq!(words.parse_cancellable(input.as_str(), &token).map(|res| res.into_result()), Err(Cancelled));
+//! ```
+//!
+//! ```
+//! # use chumsky::{prelude::*, cancel::StepLimitExceeded};
+//! let words = text::ident::<_, _, extra::Err<Rich<char>>>()
+//!     .padded()
+//!     .repeated()
+//!     .count();
+//!
+//! assert_eq!(words.parse_with_step_limit("hello world", 100).map(|res| res.into_result()), Ok(Ok(2)));
+//! assert_eq!(
+//!     words.parse_with_step_limit("hello world ".repeat(1000).as_str(), 100).map(|res| res.into_result()),
+//!     Err(StepLimitExceeded),
+//! );
+//! ```
+
+use super::*;
+#[cfg(feature = "cancel")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// The number of tokens read between checks of the cancellation token
+#[cfg(feature = "cancel")]
+const CHECK_INTERVAL: u32 = 1024;
+
+/// A handle that can be used to cancel a parse. See [`Parser::parse_cancellable`].
+///
+/// Clones of a token share the same state, so cancelling one of them cancels every parse using any of them.
+#[cfg(feature = "cancel")]
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+#[cfg(feature = "cancel")]
+impl CancelToken {
+    /// Create a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every parse using this token (or any of its clones).
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The error returned by [`Parser::parse_cancellable`] when the parse was cancelled before it finished.
+#[cfg(feature = "cancel")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+#[cfg(feature = "cancel")]
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the parse was cancelled")
+    }
+}
+
+#[cfg(all(feature = "cancel", feature = "std"))]
+impl std::error::Error for Cancelled {}
+
+/// The error returned by [`Parser::parse_with_step_limit`] when the parse ran out of steps before it finished.
+#[cfg(feature = "step-limit")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StepLimitExceeded;
+
+#[cfg(feature = "step-limit")]
+impl fmt::Display for StepLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the parse exceeded its step limit")
+    }
+}
+
+#[cfg(all(feature = "step-limit", feature = "std"))]
+impl std::error::Error for StepLimitExceeded {}
+
+// The state of a parse that may be stopped early, checked as the input is read
+pub(crate) struct Interrupt {
+    #[cfg(feature = "cancel")]
+    token: Option<CancelToken>,
+    #[cfg(feature = "cancel")]
+    countdown: u32,
+    // The number of steps remaining, if the parse has a step limit
+    #[cfg(feature = "step-limit")]
+    fuel: Option<u64>,
+    pub(crate) stopped: bool,
+}
+
+impl Interrupt {
+    #[cfg(feature = "cancel")]
+    pub(crate) fn cancellable(token: CancelToken) -> Self {
+        Self {
+            token: Some(token),
+            countdown: 0,
+            #[cfg(feature = "step-limit")]
+            fuel: None,
+            stopped: false,
+        }
+    }
+
+    #[cfg(feature = "step-limit")]
+    pub(crate) fn step_limit(steps: u64) -> Self {
+        Self {
+            #[cfg(feature = "cancel")]
+            token: None,
+            #[cfg(feature = "cancel")]
+            countdown: 0,
+            fuel: Some(steps),
+            stopped: false,
+        }
+    }
+
+    // Returns true if the parse should stop, only checking the token every so often. Each call is a step.
+    #[inline]
+    pub(crate) fn check(&mut self) -> bool {
+        if !self.stopped {
+            #[cfg(feature = "step-limit")]
+            if let Some(fuel) = &mut self.fuel {
+                match fuel.checked_sub(1) {
+                    Some(rest) => *fuel = rest,
+                    None => self.stopped = true,
+                }
+            }
+            #[cfg(feature = "cancel")]
+            if let Some(token) = &self.token {
+                if let Some(countdown) = self.countdown.checked_sub(1) {
+                    self.countdown = countdown;
+                } else {
+                    self.countdown = CHECK_INTERVAL;
+                    self.stopped |= token.is_cancelled();
+                }
+            }
+        }
+        self.stopped
+    }
+
+    // Backtracking is also a step, but doesn't stop the parse until the next token is read
+    #[cfg(feature = "step-limit")]
+    #[inline]
+    pub(crate) fn backtrack(&mut self) {
+        if let Some(fuel) = &mut self.fuel {
+            *fuel = fuel.saturating_sub(1);
+        }
+    }
+}
@@ -6,11 +6,16 @@
 //! when accessed through their respective methods on [`Parser`].
 
 use super::*;
+#[cfg(feature = "memoization")]
+use crate::input::MemoTable;
 
 /// The type of a lazy parser.
 pub type Lazy<'a, A, I, E> =
     ThenIgnore<A, Repeated<Any<I, E>, <I as Input<'a>>::Token, I, E>, (), E>;
 
+/// The type of a parser that must be followed by the end of input. See [`Parser::then_end`].
+pub type ThenEnd<A, I, E> = ThenIgnore<A, End<I, E>, (), E>;
+
 /// Alter the configuration of a struct using parse-time context
 #[derive(Copy, Clone)]
 pub struct Configure<A, F> {
@@ -34,6 +39,10 @@ where
         self.parser.go_cfg::<M>(inp, cfg)
     }
 
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
 
@@ -75,6 +84,10 @@ where
         }
     }
 
+    graph_node!(iter parser: OA);
+
+    gen_input!(iter parser: OA => ());
+
     go_extra!(());
 }
 
@@ -89,6 +102,11 @@ where
     where
         I: 'a;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.parser.capacity_hint()
+    }
+
     #[inline(always)]
     fn make_iter<M: Mode>(
         &self,
@@ -108,6 +126,10 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    graph_node!(iter parser: O);
+
+    gen_input!(iter parser: O);
 }
 
 /// See [`ConfigIterParser::try_configure`]
@@ -148,6 +170,10 @@ where
         }
     }
 
+    graph_node!(iter parser: OA);
+
+    gen_input!(iter parser: OA => ());
+
     go_extra!(());
 }
 
@@ -162,6 +188,11 @@ where
     where
         I: 'a;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.parser.capacity_hint()
+    }
+
     fn make_iter<M: Mode>(
         &self,
         inp: &mut InputRef<'a, '_, I, E>,
@@ -183,6 +214,10 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    graph_node!(iter parser: O);
+
+    gen_input!(iter parser: O);
 }
 
 /// See [`Parser::map_slice`].
@@ -239,6 +274,15 @@ where
         Ok(M::bind(|| (self.mapper)(inp.slice_inner(before..after))))
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O => U);
+
     go_extra!(U);
 }
 
@@ -277,6 +321,19 @@ where
         Ok(M::bind(|| inp.slice_inner(before..after)))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, O, E>::skip_leading_trivia(&self.parser, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O => I::Slice);
+
     go_extra!(I::Slice);
 }
 
@@ -317,6 +374,24 @@ where
         })
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.filtered(
+            |gen| ParserSealed::<I, O, E>::gen_input(&self.parser, gen),
+            &self.filter,
+        )
+    }
+
     go_extra!(O);
 }
 
@@ -352,6 +427,25 @@ where
         Ok(M::map(out, &self.mapper))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?.map(&self.mapper))
+    }
+
     go_extra!(O);
 }
 
@@ -366,6 +460,11 @@ where
     where
         I: 'a;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.parser.capacity_hint()
+    }
+
     #[inline(always)]
     fn make_iter<M: Mode>(
         &self,
@@ -386,6 +485,93 @@ where
             Err(()) => Err(()),
         }
     }
+
+    graph_node!(iter parser: OA);
+
+    #[cfg(feature = "alloc")]
+    fn gen_items(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<O>>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(IterParserSealed::<I, OA, E>::gen_items(&self.parser, gen)?
+            .map(|items| items.into_iter().map(&self.mapper).collect()))
+    }
+}
+
+/// A function that can be called with the elements of a tuple as its arguments. See [`Parser::map_group`].
+///
+/// This trait is implemented for all functions and closures that accept between 1 and 26 arguments.
+pub trait GroupFn<Args, O> {
+    /// Call the function, passing the elements of the tuple as individual arguments.
+    fn call_group(&self, args: Args) -> O;
+}
+
+macro_rules! impl_group_fn_for_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_group_fn_for_tuple!($($X)*);
+        impl_group_fn_for_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<Func, Out, $($X),*> GroupFn<($($X,)*), Out> for Func
+        where
+            Func: Fn($($X),*) -> Out,
+        {
+            #[inline(always)]
+            fn call_group(&self, ($($X,)*): ($($X,)*)) -> Out {
+                self($($X),*)
+            }
+        }
+    };
+}
+
+impl_group_fn_for_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_ M_ N_ O_ P_ Q_ R_ S_ T_ U_ V_ W_ X_ Y_ Z_);
+
+/// See [`Parser::map_group`].
+pub struct MapGroup<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy> Copy for MapGroup<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for MapGroup<A, OA, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, OA, F> ParserSealed<'a, I, O, E> for MapGroup<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    F: GroupFn<OA, O>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| self.mapper.call_group(out)))
+    }
+
+    graph_node!(parser: OA);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?
+            .map(|out| self.mapper.call_group(out)))
+    }
+
+    go_extra!(O);
 }
 
 /// See [`Parser::map_with_span`].
@@ -424,6 +610,19 @@ where
         }))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => O);
+
     go_extra!(O);
 }
 
@@ -464,9 +663,71 @@ where
         }))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => O);
+
     go_extra!(O);
 }
 
+/// See [`Parser::record_trivia`].
+pub struct RecordTrivia<A, OA> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA> Copy for RecordTrivia<A, OA> {}
+impl<A: Clone, OA> Clone for RecordTrivia<A, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, OA> ParserSealed<'a, I, OA, E> for RecordTrivia<A, OA>
+where
+    I: SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: text::TriviaSink<I::Slice, I::Span>,
+    A: Parser<'a, I, OA, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        let before = inp.offset();
+        let out = self.parser.go::<M>(inp)?;
+        // Trivia is recorded regardless of mode, since padding is usually parsed without emitting output
+        if inp.offset().offset != before.offset {
+            let trivia = inp.slice_inner(before.offset..inp.offset().offset);
+            let span = inp.span_since(before);
+            inp.state().push_trivia(trivia, span);
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA);
+
+    go_extra!(OA);
+}
+
 /// See [`Parser::try_map`].
 pub struct TryMap<A, OA, F> {
     pub(crate) parser: A,
@@ -507,6 +768,15 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => O);
+
     go_extra!(O);
 }
 
@@ -550,142 +820,375 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => O);
+
     go_extra!(O);
 }
 
-/// See [`Parser::to`].
-pub struct To<A, OA, O> {
+/// See [`Parser::map_with_ctx`].
+pub struct MapWithCtx<A, OA, F> {
     pub(crate) parser: A,
-    pub(crate) to: O,
+    pub(crate) mapper: F,
     #[allow(dead_code)]
     pub(crate) phantom: EmptyPhantom<OA>,
 }
 
-impl<A: Copy, OA, O: Copy> Copy for To<A, OA, O> {}
-impl<A: Clone, OA, O: Clone> Clone for To<A, OA, O> {
+impl<A: Copy, OA, F: Copy> Copy for MapWithCtx<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for MapWithCtx<A, OA, F> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
-            to: self.to.clone(),
+            mapper: self.mapper.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, O, E, A, OA> ParserSealed<'a, I, O, E> for To<A, OA, O>
+impl<'a, I, O, E, A, OA, F> ParserSealed<'a, I, O, E> for MapWithCtx<A, OA, F>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, OA, E>,
-    O: Clone,
+    F: Fn(OA, I::Span, &E::Context) -> O,
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        self.parser.go::<Check>(inp)?;
-        Ok(M::bind(|| self.to.clone()))
+        let before = inp.offset();
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| {
+            let span = inp.span_since(before);
+            (self.mapper)(out, span, inp.ctx())
+        }))
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => O);
+
     go_extra!(O);
 }
 
-/// See [`Parser::ignored`].
-pub struct Ignored<A, OA> {
+/// See [`Parser::filter_with_ctx`].
+pub struct FilterWithCtx<A, F> {
     pub(crate) parser: A,
-    #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<OA>,
+    pub(crate) filter: F,
 }
 
-impl<A: Copy, OA> Copy for Ignored<A, OA> {}
-impl<A: Clone, OA> Clone for Ignored<A, OA> {
+impl<A: Copy, F: Copy> Copy for FilterWithCtx<A, F> {}
+impl<A: Clone, F: Clone> Clone for FilterWithCtx<A, F> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
-            phantom: EmptyPhantom::new(),
+            filter: self.filter.clone(),
         }
     }
 }
 
-impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Ignored<A, OA>
+impl<'a, A, I, O, E, F> ParserSealed<'a, I, O, E> for FilterWithCtx<A, F>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, OA, E>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&O, &E::Context) -> bool,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
-        self.parser.go::<Check>(inp)?;
-        Ok(M::bind(|| ()))
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        self.parser.go::<Emit>(inp).and_then(|out| {
+            if (self.filter)(&out, inp.ctx()) {
+                Ok(M::bind(|| out))
+            } else {
+                let err_span = inp.span_since(before);
+                inp.add_alt(inp.offset().offset, None, None, err_span);
+                Err(())
+            }
+        })
     }
 
-    go_extra!(());
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    // The context isn't known while generating input, so the filter can't be applied
+    gen_input!(parser: O => O);
+
+    go_extra!(O);
 }
 
-/// See [`Parser::unwrapped`].
-pub struct Unwrapped<A, O> {
+/// See [`Parser::to`].
+pub struct To<A, OA, O> {
     pub(crate) parser: A,
-    pub(crate) location: Location<'static>,
+    pub(crate) to: O,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<O>,
+    pub(crate) phantom: EmptyPhantom<OA>,
 }
 
-impl<A: Copy, O> Copy for Unwrapped<A, O> {}
-impl<A: Clone, O> Clone for Unwrapped<A, O> {
+impl<A: Copy, OA, O: Copy> Copy for To<A, OA, O> {}
+impl<A: Clone, OA, O: Clone> Clone for To<A, OA, O> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
-            location: self.location,
+            to: self.to.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, E, A, O, U> ParserSealed<'a, I, O, E> for Unwrapped<A, Result<O, U>>
+impl<'a, I, O, E, A, OA> ParserSealed<'a, I, O, E> for To<A, OA, O>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, Result<O, U>, E>,
-    U: fmt::Debug,
+    A: Parser<'a, I, OA, E>,
+    O: Clone,
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        let out = self.parser.go::<M>(inp)?;
-        Ok(M::map(out, |out| match out {
-            Ok(out) => out,
-            Err(err) => panic!(
-                "called `Result::unwrap` on a `Err(_)` value at {}: {:?}",
-                self.location, err
-            ),
-        }))
+        self.parser.go::<Check>(inp)?;
+        Ok(M::bind(|| self.to.clone()))
     }
 
-    go_extra!(O);
-}
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser, inp)
+    }
 
-impl<'a, I, E, A, O> ParserSealed<'a, I, O, E> for Unwrapped<A, Option<O>>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    A: Parser<'a, I, Option<O>, E>,
-{
-    #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        let out = self.parser.go::<M>(inp)?;
-        Ok(M::map(out, |out| match out {
-            Some(out) => out,
-            None => panic!(
-                "called `Option::unwrap` on a `None` value at {}",
-                self.location
-            ),
-        }))
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+        Ok(Some(self.to.clone()))
     }
 
     go_extra!(O);
 }
 
-/// See [`Parser::memoised`].
-#[cfg(feature = "memoization")]
+/// See [`Parser::named`].
 #[derive(Copy, Clone)]
-pub struct Memoised<A> {
+pub struct Named<A> {
+    pub(crate) parser: A,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub(crate) name: &'static str,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Named<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let go = |inp: &mut InputRef<'a, '_, I, E>| self.parser.go::<M>(inp);
+        #[cfg(feature = "profile")]
+        let go = |inp: &mut InputRef<'a, '_, I, E>| {
+            crate::profile::go_profiled::<_, _, M, _>(inp, self.name, go)
+        };
+        #[cfg(feature = "trace")]
+        let go = |inp: &mut InputRef<'a, '_, I, E>| {
+            crate::trace::go_traced::<_, _, M, _>(inp, self.name, go)
+        };
+        go(inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add_labelled::<Self>(self.name.into());
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
+
+/// See [`Parser::ignored`].
+pub struct Ignored<A, OA> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA> Copy for Ignored<A, OA> {}
+impl<A: Clone, OA> Clone for Ignored<A, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Ignored<A, OA>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        self.parser.go::<Check>(inp)?;
+        Ok(M::bind(|| ()))
+    }
+
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+        Ok(Some(()))
+    }
+
+    go_extra!(());
+}
+
+/// See [`Parser::unwrapped`].
+pub struct Unwrapped<A, O> {
+    pub(crate) parser: A,
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O> Copy for Unwrapped<A, O> {}
+impl<A: Clone, O> Clone for Unwrapped<A, O> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, O, U> ParserSealed<'a, I, O, E> for Unwrapped<A, Result<O, U>>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, Result<O, U>, E>,
+    U: fmt::Debug,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| match out {
+            Ok(out) => out,
+            Err(err) => panic!(
+                "called `Result::unwrap` on a `Err(_)` value at {}: {:?}",
+                self.location, err
+            ),
+        }))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, Result<O, U>, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: Result<O, U>);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        match ParserSealed::<I, Result<O, U>, E>::gen_input(&self.parser, gen)? {
+            Some(Ok(out)) => Ok(Some(out)),
+            // Unwrapping this output would panic
+            Some(Err(_)) => Err(()),
+            None => Ok(None),
+        }
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, E, A, O> ParserSealed<'a, I, O, E> for Unwrapped<A, Option<O>>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, Option<O>, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| match out {
+            Some(out) => out,
+            None => panic!(
+                "called `Option::unwrap` on a `None` value at {}",
+                self.location
+            ),
+        }))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, Option<O>, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: Option<O>);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        match ParserSealed::<I, Option<O>, E>::gen_input(&self.parser, gen)? {
+            Some(Some(out)) => Ok(Some(out)),
+            // Unwrapping this output would panic
+            Some(None) => Err(()),
+            None => Ok(None),
+        }
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::memoised`].
+#[cfg(feature = "memoization")]
+#[derive(Copy, Clone)]
+pub struct Memoised<A> {
     pub(crate) parser: A,
 }
 
@@ -700,26 +1203,27 @@ where
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
         let before = inp.offset();
+        // The memo table is shared between contexts, so the same parser may be invoked with different contexts at
+        // the same offset: include the context's generation in the key to keep their results apart.
         // TODO: Don't use address, since this might not be constant?
         let key = (
             before.offset,
             &self.parser as *const _ as *const () as usize,
+            inp.ctx_generation,
         );
 
-        match inp.memos.entry(key) {
-            hashbrown::hash_map::Entry::Occupied(o) => {
-                if let Some(err) = o.get() {
-                    let err = err.clone();
-                    inp.add_alt_err(err.pos, err.err);
-                } else {
-                    let err_span = inp.span_since(before);
-                    inp.add_alt(key.0, None, None, err_span);
-                }
+        match inp.memos.get(&key) {
+            Some(Some(err)) => {
+                let err = err.clone();
+                inp.add_alt_err(err.pos, err.err);
                 return Err(());
             }
-            hashbrown::hash_map::Entry::Vacant(v) => {
-                v.insert(None);
+            Some(None) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(key.0, None, None, err_span);
+                return Err(());
             }
+            None => inp.memos.insert(key, None),
         }
 
         let res = self.parser.go::<M>(inp);
@@ -736,6 +1240,89 @@ where
         res
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
+
+/// A limit on the number of memoised results that are kept during a parse. See [`Parser::memo_bound`].
+#[cfg(feature = "memoization")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MemoBound {
+    /// Keep all memoised results for the duration of the parse. This is the default.
+    #[default]
+    Unbounded,
+    /// Keep at most the given number of memoised results, evicting the least recently used results first.
+    Lru(usize),
+    /// Evict memoised results for positions more than the given number of tokens behind the furthest position at
+    /// which a result has been memoised.
+    Window(usize),
+}
+
+/// Statistics about the memo table used by memoised parsers during a parse. See [`Parser::parse_with_memo_stats`].
+///
+/// Only failures are kept in the memo table (a successful parser is simply run again), so these statistics can be
+/// used to judge whether memoising a parser is worth the memory it costs: a parser whose results are rarely reused
+/// will have few hits relative to its misses.
+#[cfg(feature = "memoization")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    /// The number of times a memoised parser found an existing result in the table, and so did not need to run.
+    pub hits: u64,
+    /// The number of times a memoised parser found no result in the table, and so ran its inner parser.
+    pub misses: u64,
+    /// The number of results in the table when the parse finished.
+    pub entries: usize,
+    /// The largest number of results held by the table at any one time.
+    pub peak_entries: usize,
+    /// The number of results evicted from the table according to its [`MemoBound`].
+    pub evictions: u64,
+    /// An estimate of the memory, in bytes, allocated by the table when the parse finished. This does not include any
+    /// memory allocated by the errors held in the table (for example, the expected tokens of a [`Rich`] error).
+    ///
+    /// [`Rich`]: crate::error::Rich
+    pub memory: usize,
+}
+
+/// See [`Parser::memo_bound`].
+#[cfg(feature = "memoization")]
+#[derive(Copy, Clone)]
+pub struct MemoBounded<A> {
+    pub(crate) parser: A,
+    pub(crate) bound: MemoBound,
+}
+
+#[cfg(feature = "memoization")]
+impl<'a, I, E, A, O> ParserSealed<'a, I, O, E> for MemoBounded<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let old = core::mem::replace(&mut inp.memos.bound, self.bound);
+        let res = self.parser.go::<M>(inp);
+        inp.memos.bound = old;
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
 
@@ -772,9 +1359,97 @@ where
         Ok(M::combine(a, b, |a: OA, b: OB| (a, b)))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser_a, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser_a)
+    }
+
+    graph_node!(parser_a: OA, parser_b: OB);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<(OA, OB)>, ()>
+    where
+        I::Token: Clone,
+    {
+        let a = ParserSealed::<I, OA, E>::gen_input(&self.parser_a, gen)?;
+        let b = ParserSealed::<I, OB, E>::gen_input(&self.parser_b, gen)?;
+        Ok(a.zip(b))
+    }
+
     go_extra!((OA, OB));
 }
 
+/// See [`Parser::then_if`].
+pub struct ThenIf<A, B, F, OA, OB, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) predicate: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OA, OB, E)>,
+}
+
+impl<A: Copy, B: Copy, F: Copy, OA, OB, E> Copy for ThenIf<A, B, F, OA, OB, E> {}
+impl<A: Clone, B: Clone, F: Clone, OA, OB, E> Clone for ThenIf<A, B, F, OA, OB, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            predicate: self.predicate.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, B, F, OA, OB> ParserSealed<'a, I, (OA, Option<OB>), E>
+    for ThenIf<A, B, F, OA, OB, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+    F: Fn(&OA) -> bool,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (OA, Option<OB>)> {
+        // The predicate needs the output of the first parser, so it must always be generated
+        let a = self.parser_a.go::<Emit>(inp)?;
+        if (self.predicate)(&a) {
+            let b = self.parser_b.go::<M>(inp)?;
+            Ok(M::map(b, |b| (a, Some(b))))
+        } else {
+            Ok(M::bind(|| (a, None)))
+        }
+    }
+
+    graph_node!(parser_a: OA, parser_b: OB);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(
+        &self,
+        gen: &mut generate::Generator<I::Token>,
+    ) -> Result<Option<(OA, Option<OB>)>, ()>
+    where
+        I::Token: Clone,
+    {
+        let Some(a) = ParserSealed::<I, OA, E>::gen_input(&self.parser_a, gen)? else {
+            // Without the output of the first parser, there's no telling whether the second should follow it
+            return Ok(None);
+        };
+        if (self.predicate)(&a) {
+            let b = ParserSealed::<I, OB, E>::gen_input(&self.parser_b, gen)?;
+            Ok(b.map(|b| (a, Some(b))))
+        } else {
+            Ok(Some((a, None)))
+        }
+    }
+
+    go_extra!((OA, Option<OB>));
+}
+
 /// See [`Parser::ignore_then`].
 pub struct IgnoreThen<A, B, OA, E> {
     pub(crate) parser_a: A,
@@ -808,6 +1483,26 @@ where
         Ok(M::map(b, |b: OB| b))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser_a, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser_a)
+    }
+
+    graph_node!(parser_a: OA, parser_b: OB);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<OB>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OA, E>::gen_input(&self.parser_a, gen)?;
+        ParserSealed::<I, OB, E>::gen_input(&self.parser_b, gen)
+    }
+
     go_extra!(OB);
 }
 
@@ -844,6 +1539,27 @@ where
         Ok(M::map(a, |a: OA| a))
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, OA, E>::skip_leading_trivia(&self.parser_a, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser_a)
+    }
+
+    graph_node!(parser_a: OA, parser_b: OB);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<OA>, ()>
+    where
+        I::Token: Clone,
+    {
+        let a = ParserSealed::<I, OA, E>::gen_input(&self.parser_a, gen)?;
+        ParserSealed::<I, OB, E>::gen_input(&self.parser_b, gen)?;
+        Ok(a)
+    }
+
     go_extra!(OA);
 }
 
@@ -880,13 +1596,15 @@ where
         let alt = inp.errors.alt.take();
 
         #[cfg(feature = "memoization")]
-        let mut memos = HashMap::default();
+        let mut memos = MemoTable::new(inp.memos.bound);
         let res = inp.with_input(
             &inp2,
             |inp| (&self.parser_a).then_ignore(end()).go::<M>(inp),
             #[cfg(feature = "memoization")]
             &mut memos,
         );
+        #[cfg(feature = "memoization")]
+        inp.memos.merge_stats(&memos);
 
         // TODO: Translate secondary error offsets too
         let new_alt = inp.errors.alt.take();
@@ -898,6 +1616,10 @@ where
         res
     }
 
+    graph_node!(parser_a: O, parser_b: I);
+
+    gen_input!(parser_b: I => O);
+
     go_extra!(O);
 }
 
@@ -935,6 +1657,27 @@ where
         inp.with_ctx(&p1, |inp| self.then.go::<M>(inp))
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = ParserSealed::<I, OA, E>::graph_node(&self.parser, graph);
+        graph.edge(id, child);
+        let child = ParserSealed::<I, OB, extra::Full<E::Error, E::State, OA>>::graph_node(
+            &self.then, graph,
+        );
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<OB>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+        ParserSealed::<I, OB, extra::Full<E::Error, E::State, OA>>::gen_input(&self.then, gen)
+    }
+
     go_extra!(OB);
 }
 
@@ -947,18 +1690,27 @@ where
     B: IterParser<'a, I, OB, extra::Full<E::Error, E::State, OA>>,
     OA: 'a,
 {
-    type IterState<M: Mode> = (OA, B::IterState<M>)
+    // The output of the first parser, the generation of the context it provides, and the state of the iterator
+    type IterState<M: Mode> = (OA, usize, B::IterState<M>)
     where
         I: 'a;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.then.capacity_hint()
+    }
+
     #[inline(always)]
     fn make_iter<M: Mode>(
         &self,
         inp: &mut InputRef<'a, '_, I, E>,
     ) -> PResult<Emit, Self::IterState<M>> {
         let out = self.parser.go::<Emit>(inp)?;
-        let then = inp.with_ctx(&out, |inp| self.then.make_iter::<M>(inp))?;
-        Ok((out, then))
+        // Every item shares the same context, so they share a generation too
+        let generation = inp.new_ctx_generation();
+        let then =
+            inp.with_ctx_generation(&out, generation, |inp| self.then.make_iter::<M>(inp))?;
+        Ok((out, generation, then))
     }
 
     #[inline(always)]
@@ -967,9 +1719,30 @@ where
         inp: &mut InputRef<'a, '_, I, E>,
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, OB> {
-        let (ctx, inner_state) = state;
+        let (ctx, generation, inner_state) = state;
 
-        inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
+        inp.with_ctx_generation(ctx, *generation, |inp| self.then.next(inp, inner_state))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = ParserSealed::<I, OA, E>::graph_node(&self.parser, graph);
+        graph.edge(id, child);
+        let child = IterParserSealed::<I, OB, extra::Full<E::Error, E::State, OA>>::graph_node(
+            &self.then, graph,
+        );
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_items(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<OB>>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+        IterParserSealed::<I, OB, extra::Full<E::Error, E::State, OA>>::gen_items(&self.then, gen)
     }
 }
 
@@ -998,7 +1771,26 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        inp.with_ctx(&self.ctx, |inp| self.parser.go::<M>(inp))
+        inp.with_shared_ctx(&self.ctx, |inp| self.parser.go::<M>(inp))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = ParserSealed::<I, O, extra::Full<E::Error, E::State, Ctx>>::graph_node(
+            &self.parser,
+            graph,
+        );
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, O, extra::Full<E::Error, E::State, Ctx>>::gen_input(&self.parser, gen)
     }
 
     go_extra!(O);
@@ -1041,6 +1833,24 @@ where
         Ok(a)
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OB, E>::first_tokens(&self.start)
+    }
+
+    graph_node!(start: OB, parser: OA, end: OC);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<OA>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OB, E>::gen_input(&self.start, gen)?;
+        let out = ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+        ParserSealed::<I, OC, E>::gen_input(&self.end, gen)?;
+        Ok(out)
+    }
+
     go_extra!(OA);
 }
 
@@ -1078,6 +1888,31 @@ where
         Ok(a)
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        let before = inp.save();
+        if self.padding.go::<Check>(inp).is_err() {
+            inp.rewind(before);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OB, E>::first_tokens(&self.padding)
+    }
+
+    graph_node!(parser: OA, padding: OB);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<OA>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, OB, E>::gen_input(&self.padding, gen)?;
+        let out = ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+        ParserSealed::<I, OB, E>::gen_input(&self.padding, gen)?;
+        Ok(out)
+    }
+
     go_extra!(OA);
 }
 
@@ -1099,6 +1934,24 @@ where
         self.choice.go::<M>(inp)
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.choice)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        crate::primitive::ChoiceBranches::<I, O, E>::branch_graph_nodes(
+            &self.choice.parsers,
+            graph,
+            id,
+        );
+        id
+    }
+
+    gen_input!(choice: O);
+
     go_extra!(O);
 }
 
@@ -1136,6 +1989,7 @@ pub struct Repeated<A, OA, I, E> {
     pub(crate) at_least: usize,
     // Slightly evil: Should be `Option<usize>`, but we encode `!0` as 'no cap' because it's so large
     pub(crate) at_most: u64,
+    pub(crate) capacity: usize,
     #[cfg(debug_assertions)]
     pub(crate) location: Location<'static>,
     #[allow(dead_code)]
@@ -1149,6 +2003,7 @@ impl<A: Clone, OA, I, E> Clone for Repeated<A, OA, I, E> {
             parser: self.parser.clone(),
             at_least: self.at_least,
             at_most: self.at_most,
+            capacity: self.capacity,
             #[cfg(debug_assertions)]
             location: self.location,
             phantom: EmptyPhantom::new(),
@@ -1221,6 +2076,24 @@ where
             ..self
         }
     }
+
+    /// Hint that the pattern is likely to appear around the given number of times.
+    ///
+    /// Containers that this parser is collected into will pre-allocate space for this many items, avoiding repeated
+    /// reallocation when parsing long, uniform sequences. The hint does not change which inputs are accepted.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let bytes = text::int::<_, _, extra::Err<Simple<char>>>(16)
+    ///     .repeated()
+    ///     .with_capacity(256)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert!(bytes.parse("").into_result().unwrap().capacity() >= 256);
+    /// ```
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
 }
 
 impl<'a, I, E, A, OA> ParserSealed<'a, I, (), E> for Repeated<A, OA, I, E>
@@ -1271,6 +2144,26 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        if self.at_least == 0 {
+            graph.set_nullable(id);
+        }
+        let child = ParserSealed::<I, OA, E>::graph_node(&self.parser, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        IterParserSealed::<I, OA, E>::gen_items(self, gen)?;
+        Ok(Some(()))
+    }
+
     go_extra!(());
 }
 
@@ -1282,6 +2175,11 @@ where
 {
     type IterState<M: Mode> = usize;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.capacity.max(self.at_least)
+    }
+
     #[inline(always)]
     fn make_iter<M: Mode>(
         &self,
@@ -1316,6 +2214,27 @@ where
             }
         }
     }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        if self.at_least == 0 {
+            graph.set_nullable(id);
+        }
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_items(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<O>>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.repeat(self.at_least, self.at_most, |gen, _| {
+            ParserSealed::<I, O, E>::gen_input(&self.parser, gen)
+        })
+    }
 }
 
 impl<'a, A, O, I, E> ConfigIterParserSealed<'a, I, O, E> for Repeated<A, O, I, E>
@@ -1359,12 +2278,14 @@ where
 }
 
 /// See [`Parser::separated_by`].
-pub struct SeparatedBy<A, B, OA, OB, I, E> {
+pub struct SeparatedBy<A, B, OA, OB, I, E, S = NoRecovery> {
     pub(crate) parser: A,
     pub(crate) separator: B,
+    pub(crate) recovery: S,
     pub(crate) at_least: usize,
     // Slightly evil: Should be `Option<usize>`, but we encode `!0` as 'no cap' because it's so large
     pub(crate) at_most: u64,
+    pub(crate) capacity: usize,
     pub(crate) allow_leading: bool,
     pub(crate) allow_trailing: bool,
     #[cfg(debug_assertions)]
@@ -1373,14 +2294,16 @@ pub struct SeparatedBy<A, B, OA, OB, I, E> {
     pub(crate) phantom: EmptyPhantom<(OA, OB, E, I)>,
 }
 
-impl<A: Copy, B: Copy, OA, OB, I, E> Copy for SeparatedBy<A, B, OA, OB, I, E> {}
-impl<A: Clone, B: Clone, OA, OB, I, E> Clone for SeparatedBy<A, B, OA, OB, I, E> {
+impl<A: Copy, B: Copy, OA, OB, I, E, S: Copy> Copy for SeparatedBy<A, B, OA, OB, I, E, S> {}
+impl<A: Clone, B: Clone, OA, OB, I, E, S: Clone> Clone for SeparatedBy<A, B, OA, OB, I, E, S> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
             separator: self.separator.clone(),
+            recovery: self.recovery.clone(),
             at_least: self.at_least,
             at_most: self.at_most,
+            capacity: self.capacity,
             allow_leading: self.allow_leading,
             allow_trailing: self.allow_trailing,
             #[cfg(debug_assertions)]
@@ -1390,7 +2313,7 @@ impl<A: Clone, B: Clone, OA, OB, I, E> Clone for SeparatedBy<A, B, OA, OB, I, E>
     }
 }
 
-impl<'a, A, B, OA, OB, I, E> SeparatedBy<A, B, OA, OB, I, E>
+impl<'a, A, B, OA, OB, I, E, S> SeparatedBy<A, B, OA, OB, I, E, S>
 where
     A: Parser<'a, I, OA, E>,
     B: Parser<'a, I, OB, E>,
@@ -1471,6 +2394,29 @@ where
         }
     }
 
+    /// Hint that the pattern is likely to appear around the given number of times.
+    ///
+    /// Containers that this parser is collected into will pre-allocate space for this many items, avoiding repeated
+    /// reallocation when parsing long, uniform sequences such as large arrays. The hint does not change which inputs
+    /// are accepted.
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let array = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .padded()
+    ///     .separated_by(just(','))
+    ///     .with_capacity(1024)
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// let out = array.parse("[1, 2, 3]").into_result().unwrap();
+    /// assert_eq!(out, ["1", "2", "3"]);
+    /// assert!(out.capacity() >= 1024);
+    /// ```
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
+
     /// Allow a leading separator to appear before the first item.
     ///
     /// Note that even if no items are parsed, a leading separator *is* permitted.
@@ -1525,19 +2471,103 @@ where
             ..self
         }
     }
+
+    /// Recover from elements that fail to parse using the given [recovery strategy](crate::recovery), so that the
+    /// elements after them are still parsed.
+    ///
+    /// When an element fails to parse, the strategy is applied as if by [`Parser::recover_with`]. If it recovers,
+    /// the error is recorded and its output is used in place of the element before parsing continues from the next
+    /// separator. The strategy should therefore stop before the separator (and before whatever follows the list),
+    /// for example by using [`Parser::rewind`] for the `until` parser of [`skip_until`].
+    ///
+    /// A recovery that does not consume any input is ignored, and the element is treated as missing instead. This
+    /// means that an empty list or a trailing separator is not mistaken for a malformed element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, recovery::skip_until};
+    /// let list = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str::<i64>()
+    ///     .unwrapped()
+    ///     .padded()
+    ///     .separated_by(just(','))
+    ///     .allow_trailing()
+    ///     .recover_elements(skip_until(any().ignored(), one_of(",]").ignored().rewind(), || -1))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// let (out, errs) = list.parse("[1, two, 3, ?]").into_output_errors();
+    /// assert_eq!(out, Some(vec![1, -1, 3, -1]));
+    /// assert_eq!(errs.len(), 2);
+    /// assert_eq!(errs[0].span(), &SimpleSpan::from(4..5));
+    /// assert_eq!(errs[1].span(), &SimpleSpan::from(12..13));
+    ///
+    /// assert_eq!(list.parse("[]").into_result(), Ok(vec![]));
+    /// assert_eq!(list.parse("[1, 2,]").into_result(), Ok(vec![1, 2]));
+    /// ```
+    pub fn recover_elements<T>(self, strategy: T) -> SeparatedBy<A, B, OA, OB, I, E, T>
+    where
+        T: Strategy<'a, I, OA, E>,
+    {
+        SeparatedBy {
+            parser: self.parser,
+            separator: self.separator,
+            recovery: strategy,
+            at_least: self.at_least,
+            at_most: self.at_most,
+            capacity: self.capacity,
+            allow_leading: self.allow_leading,
+            allow_trailing: self.allow_trailing,
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
 }
 
-impl<'a, I, E, A, B, OA, OB> IterParserSealed<'a, I, OA, E> for SeparatedBy<A, B, OA, OB, I, E>
+/// The recovery strategy of a [`SeparatedBy`] that does not recover from elements that fail to parse. See
+/// [`SeparatedBy::recover_elements`].
+#[doc(hidden)]
+#[derive(Copy, Clone, Default)]
+pub struct NoRecovery;
+
+impl Sealed for NoRecovery {}
+impl<'a, I, O, E> Strategy<'a, I, O, E> for NoRecovery
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    const RECOVERS: bool = false;
+
+    #[inline(always)]
+    fn recover<M: Mode, P: Parser<'a, I, O, E>>(
+        &self,
+        _inp: &mut InputRef<'a, '_, I, E>,
+        _parser: &P,
+    ) -> PResult<M, O> {
+        Err(())
+    }
+}
+
+impl<'a, I, E, A, B, OA, OB, S> IterParserSealed<'a, I, OA, E>
+    for SeparatedBy<A, B, OA, OB, I, E, S>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, OA, E>,
     B: Parser<'a, I, OB, E>,
+    S: Strategy<'a, I, OA, E>,
 {
     type IterState<M: Mode> = usize
     where
         I: 'a;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.capacity.max(self.at_least)
+    }
+
     #[inline(always)]
     fn make_iter<M: Mode>(
         &self,
@@ -1578,7 +2608,25 @@ where
         }
 
         let before_item = inp.save();
-        match self.parser.go::<M>(inp) {
+        let res = match self.parser.go::<M>(inp) {
+            Ok(item) => Ok(item),
+            Err(()) if !S::RECOVERS => Err(()),
+            Err(()) => {
+                inp.rewind(before_item);
+                match self.recovery.recover::<M, _>(inp, &self.parser) {
+                    Ok(item) if inp.offset() != before_item.offset() => Ok(item),
+                    Ok(_) => {
+                        // Nothing was skipped, so this is a missing element rather than a malformed one. The element
+                        // is parsed again to restore the error that the recovery consumed.
+                        inp.rewind(before_item);
+                        let _ = self.parser.go::<Check>(inp);
+                        Err(())
+                    }
+                    Err(()) => Err(()),
+                }
+            }
+        };
+        match res {
             Ok(item) => {
                 *state += 1;
                 Ok(Some(item))
@@ -1605,14 +2653,49 @@ where
             }
         }
     }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        if self.at_least == 0 {
+            graph.set_nullable(id);
+        }
+        let item = ParserSealed::<I, OA, E>::graph_node(&self.parser, graph);
+        graph.edge(id, item);
+        let separator = ParserSealed::<I, OB, E>::graph_node(&self.separator, graph);
+        graph.edge(id, separator);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_items(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<OA>>, ()>
+    where
+        I::Token: Clone,
+    {
+        let leading = self.allow_leading && gen.chance();
+        let mut count = 0;
+        let items = gen.repeat(self.at_least, self.at_most, |gen, i| {
+            if i > 0 || leading {
+                ParserSealed::<I, OB, E>::gen_input(&self.separator, gen)?;
+            }
+            let item = ParserSealed::<I, OA, E>::gen_input(&self.parser, gen)?;
+            count += 1;
+            Ok(item)
+        })?;
+        if self.allow_trailing && count > 0 && gen.chance() {
+            ParserSealed::<I, OB, E>::gen_input(&self.separator, gen)?;
+        }
+        Ok(items)
+    }
 }
 
-impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, (), E> for SeparatedBy<A, B, OA, OB, I, E>
+impl<'a, I, E, A, B, OA, OB, S> ParserSealed<'a, I, (), E> for SeparatedBy<A, B, OA, OB, I, E, S>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, OA, E>,
     B: Parser<'a, I, OB, E>,
+    S: Strategy<'a, I, OA, E>,
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
@@ -1637,6 +2720,28 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        if self.at_least == 0 {
+            graph.set_nullable(id);
+        }
+        let item = ParserSealed::<I, OA, E>::graph_node(&self.parser, graph);
+        graph.edge(id, item);
+        let separator = ParserSealed::<I, OB, E>::graph_node(&self.separator, graph);
+        graph.edge(id, separator);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        IterParserSealed::<I, OA, E>::gen_items(self, gen)?;
+        Ok(Some(()))
+    }
+
     go_extra!(());
 }
 
@@ -1667,6 +2772,11 @@ where
     where
         I: 'a;
 
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        self.parser.capacity_hint()
+    }
+
     #[inline(always)]
     fn make_iter<M: Mode>(
         &self,
@@ -1688,6 +2798,20 @@ where
         state.0 += 1;
         Ok(out)
     }
+
+    graph_node!(iter parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_items(
+        &self,
+        gen: &mut generate::Generator<I::Token>,
+    ) -> Result<Option<Vec<(usize, O)>>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(IterParserSealed::<I, O, E>::gen_items(&self.parser, gen)?
+            .map(|items| items.into_iter().enumerate().collect()))
+    }
 }
 
 /// See [`IterParser::collect`].
@@ -1720,7 +2844,7 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
-        let mut output = M::bind::<C, _>(|| C::default());
+        let mut output = M::bind::<C, _>(|| C::with_capacity(self.parser.capacity_hint()));
         let mut iter_state = self.parser.make_iter::<M>(inp)?;
         #[cfg(debug_assertions)]
         let mut i = 0;
@@ -1750,6 +2874,22 @@ where
         }
     }
 
+    graph_node!(iter parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<C>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(
+            IterParserSealed::<I, O, E>::gen_items(&self.parser, gen)?.map(|items| {
+                let mut output = C::with_capacity(items.len());
+                items.into_iter().for_each(|item| output.push(item));
+                output
+            }),
+        )
+    }
+
     go_extra!(C);
 }
 
@@ -1804,20 +2944,65 @@ where
                 }
             }
         }
-        // SAFETY: If we reach this point, we guarantee to have initialized C::LEN values
-        Ok(M::map(output, |output| unsafe { C::take(output) }))
+        // SAFETY: If we reach this point, we guarantee to have initialized C::LEN values
+        Ok(M::map(output, |output| unsafe { C::take(output) }))
+    }
+
+    graph_node!(iter parser: O);
+
+    gen_input!(iter parser: O => C);
+
+    go_extra!(C);
+}
+
+/// See [`Parser::or_not`].
+#[derive(Copy, Clone)]
+pub struct OrNot<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, Option<O>, E> for OrNot<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Option<O>> {
+        let before = inp.save();
+        Ok(match self.parser.go::<M>(inp) {
+            Ok(out) => M::map::<O, _, _>(out, Some),
+            Err(()) => {
+                inp.rewind(before);
+                M::bind::<Option<O>, _>(|| None)
+            }
+        })
+    }
+
+    graph_node!(parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Option<O>>, ()>
+    where
+        I::Token: Clone,
+    {
+        if gen.chance() {
+            Ok(ParserSealed::<I, O, E>::gen_input(&self.parser, gen)?.map(Some))
+        } else {
+            Ok(Some(None))
+        }
     }
 
-    go_extra!(C);
+    go_extra!(Option<O>);
 }
 
-/// See [`Parser::or_not`].
+/// See [`Parser::or_not_with_err`].
 #[derive(Copy, Clone)]
-pub struct OrNot<A> {
+pub struct OrNotWithErr<A> {
     pub(crate) parser: A,
 }
 
-impl<'a, I, O, E, A> ParserSealed<'a, I, Option<O>, E> for OrNot<A>
+impl<'a, I, O, E, A> ParserSealed<'a, I, Option<O>, E> for OrNotWithErr<A>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
@@ -1826,13 +3011,55 @@ where
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Option<O>> {
         let before = inp.save();
-        Ok(match self.parser.go::<M>(inp) {
-            Ok(out) => M::map::<O, _, _>(out, Some),
+        // Set aside existing errors so that we can tell how far the pattern got before failing
+        let old_alt = inp.errors.alt.take();
+        // Errors are reported after the token that caused them, so the pattern was only present if it failed beyond
+        // its first significant token (that is, the first token after any leading trivia, such as padding)
+        self.parser.skip_leading_trivia(inp);
+        inp.next_maybe_inner();
+        let first = inp.offset().offset;
+        inp.rewind(before);
+        inp.errors.alt = None;
+        let res = self.parser.go::<M>(inp);
+        let new_alt = inp.errors.alt.take();
+        inp.errors.alt = old_alt;
+
+        let out = match res {
+            Ok(out) => {
+                if let Some(new_alt) = new_alt {
+                    inp.add_alt_err(new_alt.pos, new_alt.err);
+                }
+                M::map::<O, _, _>(out, Some)
+            }
             Err(()) => {
                 inp.rewind(before);
+                match new_alt {
+                    // The pattern was present, but malformed: report it as a recovered error
+                    Some(new_alt) if new_alt.pos.into() > first.into() => {
+                        inp.emit(new_alt.pos, new_alt.err)
+                    }
+                    // The pattern was absent
+                    Some(new_alt) => inp.add_alt_err(new_alt.pos, new_alt.err),
+                    None => {}
+                }
                 M::bind::<Option<O>, _>(|| None)
             }
-        })
+        };
+        Ok(out)
+    }
+
+    graph_node!(parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Option<O>>, ()>
+    where
+        I::Token: Clone,
+    {
+        if gen.chance() {
+            Ok(ParserSealed::<I, O, E>::gen_input(&self.parser, gen)?.map(Some))
+        } else {
+            Ok(Some(None))
+        }
     }
 
     go_extra!(Option<O>);
@@ -1883,6 +3110,17 @@ where
         }
     }
 
+    graph_node!(parser: OA);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, _gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        // Nothing is generated, in the hope that whatever follows does not match the parser
+        Ok(Some(()))
+    }
+
     go_extra!(());
 }
 
@@ -1942,10 +3180,15 @@ where
         }
     }
 
+    graph_node!(parser_a: OA, parser_b: OB);
+
+    gen_input!(parser_a: OA);
+
     go_extra!(OA);
 }
 
 /// See [`IterParser::foldr`].
+#[cfg(feature = "alloc")]
 pub struct Foldr<F, A, B, OA, E> {
     pub(crate) parser_a: A,
     pub(crate) parser_b: B,
@@ -1956,7 +3199,9 @@ pub struct Foldr<F, A, B, OA, E> {
     pub(crate) phantom: EmptyPhantom<(OA, E)>,
 }
 
+#[cfg(feature = "alloc")]
 impl<F: Copy, A: Copy, B: Copy, OA, E> Copy for Foldr<F, A, B, OA, E> {}
+#[cfg(feature = "alloc")]
 impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for Foldr<F, A, B, OA, E> {
     fn clone(&self) -> Self {
         Self {
@@ -1970,6 +3215,7 @@ impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for Foldr<F, A, B, OA, E> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, I, F, A, B, O, OA, E> ParserSealed<'a, I, O, E> for Foldr<F, A, B, OA, E>
 where
     I: Input<'a>,
@@ -2010,10 +3256,33 @@ where
         }))
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = IterParserSealed::<I, OA, E>::graph_node(&self.parser_a, graph);
+        graph.edge(id, child);
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser_b, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        let items = IterParserSealed::<I, OA, E>::gen_items(&self.parser_a, gen)?;
+        let last = ParserSealed::<I, O, E>::gen_input(&self.parser_b, gen)?;
+        Ok(items
+            .zip(last)
+            .map(|(items, last)| items.into_iter().rfold(last, |b, a| (self.folder)(a, b))))
+    }
+
     go_extra!(O);
 }
 
 /// See [`IterParser::foldr_with_state`].
+#[cfg(feature = "alloc")]
 pub struct FoldrWithState<F, A, B, OA, E> {
     pub(crate) parser_a: A,
     pub(crate) parser_b: B,
@@ -2024,7 +3293,9 @@ pub struct FoldrWithState<F, A, B, OA, E> {
     pub(crate) phantom: EmptyPhantom<(OA, E)>,
 }
 
+#[cfg(feature = "alloc")]
 impl<F: Copy, A: Copy, B: Copy, OA, E> Copy for FoldrWithState<F, A, B, OA, E> {}
+#[cfg(feature = "alloc")]
 impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for FoldrWithState<F, A, B, OA, E> {
     fn clone(&self) -> Self {
         Self {
@@ -2038,6 +3309,7 @@ impl<F: Clone, A: Clone, B: Clone, OA, E> Clone for FoldrWithState<F, A, B, OA,
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, I, F, A, B, O, OA, E> ParserSealed<'a, I, O, E> for FoldrWithState<F, A, B, OA, E>
 where
     I: Input<'a>,
@@ -2081,9 +3353,97 @@ where
         }))
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = IterParserSealed::<I, OA, E>::graph_node(&self.parser_a, graph);
+        graph.edge(id, child);
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser_b, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        IterParserSealed::<I, OA, E>::gen_items(&self.parser_a, gen)?;
+        ParserSealed::<I, O, E>::gen_input(&self.parser_b, gen)?;
+        Ok(None)
+    }
+
     go_extra!(O);
 }
 
+/// See [`IterParser::try_fold`].
+pub struct TryFold<A, O, B, F> {
+    pub(crate) parser: A,
+    pub(crate) init: B,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O, B: Copy, F: Copy> Copy for TryFold<A, O, B, F> {}
+impl<A: Clone, O, B: Clone, F: Clone> Clone for TryFold<A, O, B, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            init: self.init.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, O, B, F> ParserSealed<'a, I, B, E> for TryFold<A, O, B, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: IterParser<'a, I, O, E>,
+    B: Clone,
+    F: Fn(B, O) -> ControlFlow<B, B>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, B>
+    where
+        Self: Sized,
+    {
+        // The decision to stop depends on the accumulated output, so it must always be generated
+        let mut acc = self.init.clone();
+        let mut iter_state = self.parser.make_iter::<Emit>(inp)?;
+        loop {
+            #[cfg(debug_assertions)]
+            let before = inp.offset();
+            match self.parser.next::<Emit>(inp, &mut iter_state) {
+                Ok(Some(out)) => match (self.folder)(acc, out) {
+                    ControlFlow::Continue(new_acc) => acc = new_acc,
+                    ControlFlow::Break(new_acc) => break Ok(M::bind(|| new_acc)),
+                },
+                Ok(None) => break Ok(M::bind(|| acc)),
+                Err(()) => break Err(()),
+            }
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                before != inp.offset(),
+                "found TryFold combinator making no progress at {}",
+                self.location,
+            );
+        }
+    }
+
+    graph_node!(iter parser: O);
+
+    gen_input!(iter parser: O => B);
+
+    go_extra!(B);
+}
+
 /// See [`Parser::foldl`].
 pub struct Foldl<F, A, B, OB, E> {
     pub(crate) parser_a: A,
@@ -2143,6 +3503,119 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser_a, graph);
+        graph.edge(id, child);
+        let child = IterParserSealed::<I, OB, E>::graph_node(&self.parser_b, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        let first = ParserSealed::<I, O, E>::gen_input(&self.parser_a, gen)?;
+        let items = IterParserSealed::<I, OB, E>::gen_items(&self.parser_b, gen)?;
+        Ok(first
+            .zip(items)
+            .map(|(first, items)| items.into_iter().fold(first, &self.folder)))
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::foldl_with_op`].
+pub struct FoldlWithOp<F, A, B, OB, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OB, E)>,
+}
+
+impl<F: Copy, A: Copy, B: Copy, OB, E> Copy for FoldlWithOp<F, A, B, OB, E> {}
+impl<F: Clone, A: Clone, B: Clone, OB, E> Clone for FoldlWithOp<F, A, B, OB, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, F, A, B, O, OB, E> ParserSealed<'a, I, O, E> for FoldlWithOp<F, A, B, OB, E>
+where
+    I: Input<'a>,
+    A: Parser<'a, I, O, E>,
+    B: Parser<'a, I, OB, E>,
+    E: ParserExtra<'a, I>,
+    F: Fn(O, OB, O) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let mut out = self.parser_a.go::<M>(inp)?;
+        loop {
+            let before = inp.save();
+            let op = match self.parser_b.go::<M>(inp) {
+                Ok(op) => op,
+                Err(()) => {
+                    inp.rewind(before);
+                    break Ok(out);
+                }
+            };
+            let rhs = match self.parser_a.go::<M>(inp) {
+                Ok(rhs) => rhs,
+                Err(()) => {
+                    inp.rewind(before);
+                    break Ok(out);
+                }
+            };
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                before.offset() != inp.offset(),
+                "found FoldlWithOp combinator making no progress at {}",
+                self.location,
+            );
+            out = M::combine(
+                M::combine(out, op, |out, op| (out, op)),
+                rhs,
+                |(out, op), rhs| (self.folder)(out, op, rhs),
+            );
+        }
+    }
+
+    graph_node!(parser_a: O, parser_b: OB);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        let first = ParserSealed::<I, O, E>::gen_input(&self.parser_a, gen)?;
+        let rest = gen.repeat(0, u64::MAX, |gen, _| {
+            let op = ParserSealed::<I, OB, E>::gen_input(&self.parser_b, gen)?;
+            let rhs = ParserSealed::<I, O, E>::gen_input(&self.parser_a, gen)?;
+            Ok(op.zip(rhs))
+        })?;
+        Ok(first.zip(rest).map(|(first, rest)| {
+            rest.into_iter()
+                .fold(first, |lhs, (op, rhs)| (self.folder)(lhs, op, rhs))
+        }))
+    }
+
     go_extra!(O);
 }
 
@@ -2206,6 +3679,26 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser_a, graph);
+        graph.edge(id, child);
+        let child = IterParserSealed::<I, OB, E>::graph_node(&self.parser_b, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, O, E>::gen_input(&self.parser_a, gen)?;
+        IterParserSealed::<I, OB, E>::gen_items(&self.parser_b, gen)?;
+        Ok(None)
+    }
+
     go_extra!(O);
 }
 
@@ -2234,6 +3727,129 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, _gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        // The input is rewound, so whatever follows must match the same input instead
+        Ok(None)
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::atomic`].
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct Atomic<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Atomic<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let old_alt = inp.errors.alt.take();
+        let before = inp.save();
+        let res = self.parser.go::<M>(inp);
+
+        let new_alt = inp.errors.alt.take();
+        inp.errors.alt = old_alt;
+
+        match res {
+            Ok(out) => {
+                if let Some(new_alt) = new_alt {
+                    inp.add_alt_err(new_alt.pos, new_alt.err);
+                }
+                Ok(out)
+            }
+            Err(()) => {
+                inp.rewind(before);
+                // Step over the first token (however wide it is) to find where the parser began
+                let (at, found) = inp.next_maybe_inner();
+                let err_span = inp.span_since(before.offset());
+                inp.rewind(before);
+                match new_alt {
+                    // Errors found where the parser began already describe what it expected to find
+                    Some(new_alt) if new_alt.pos.into() <= at.into() => {
+                        inp.add_alt_err(new_alt.pos, new_alt.err)
+                    }
+                    _ => inp.add_alt(at, None, found.map(|f| f.into()), err_span),
+                }
+                Err(())
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
+
+/// See [`Parser::with_depth_limit`].
+#[cfg(feature = "depth-limit")]
+#[must_use]
+#[derive(Copy, Clone)]
+pub struct DepthLimit<A> {
+    pub(crate) parser: A,
+    pub(crate) limit: usize,
+}
+
+#[cfg(feature = "depth-limit")]
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for DepthLimit<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        if inp.depth >= self.limit {
+            let before = inp.save();
+            let (at, _) = inp.next_maybe_inner();
+            let err = E::Error::custom(
+                inp.span_since(before.offset()),
+                "input is nested too deeply",
+            );
+            inp.rewind(before);
+            inp.add_alt_err(at, err);
+            return Err(());
+        }
+
+        inp.depth += 1;
+        let res = self.parser.go::<M>(inp);
+        inp.depth -= 1;
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
 
@@ -2267,6 +3883,15 @@ where
         res
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
 
@@ -2337,6 +3962,15 @@ where
         res
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
 
@@ -2383,6 +4017,15 @@ where
         Ok(M::bind(|| out))
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, OA, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA => U);
+
     go_extra!(U);
 }
 
@@ -2430,7 +4073,7 @@ where
 //     go_extra!(O);
 // }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use crate::prelude::*;
 
@@ -1,7 +1,9 @@
 //! TODO
 
 use super::*;
+#[cfg(feature = "alloc")]
 use alloc::collections::LinkedList;
+#[cfg(feature = "alloc")]
 use hashbrown::HashSet;
 
 /// A utility trait for types that can be constructed from a series of items.
@@ -17,6 +19,7 @@ pub trait Container<T>: Default {
     fn push(&mut self, item: T);
 }
 
+#[cfg(feature = "alloc")]
 impl<T, C> Container<T> for Box<C>
 where
     C: Container<T>,
@@ -67,6 +70,7 @@ impl<T> Container<T> for usize {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> Container<T> for Vec<T> {
     fn with_capacity(n: usize) -> Self {
         Self::with_capacity(n)
@@ -76,12 +80,14 @@ impl<T> Container<T> for Vec<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> Container<T> for LinkedList<T> {
     fn push(&mut self, item: T) {
         (*self).push_back(item);
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Container<char> for String {
     fn with_capacity(n: usize) -> Self {
         // Note: we're assuming that most characters are going to be ASCII, and hence only require one byte to store.
@@ -92,6 +98,7 @@ impl Container<char> for String {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<K: Eq + Hash, V> Container<(K, V)> for HashMap<K, V> {
     fn with_capacity(n: usize) -> Self {
         Self::with_capacity(n)
@@ -111,6 +118,7 @@ impl<K: Eq + Hash, V> Container<(K, V)> for std::collections::HashMap<K, V> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T: Eq + Hash> Container<T> for HashSet<T> {
     fn with_capacity(n: usize) -> Self {
         Self::with_capacity(n)
@@ -130,12 +138,14 @@ impl<T: Eq + Hash> Container<T> for std::collections::HashSet<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<K: Ord, V> Container<(K, V)> for alloc::collections::BTreeMap<K, V> {
     fn push(&mut self, (key, value): (K, V)) {
         (*self).insert(key, value);
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T: Ord> Container<T> for alloc::collections::BTreeSet<T> {
     fn push(&mut self, item: T) {
         (*self).insert(item);
@@ -195,6 +205,7 @@ unsafe impl<T, const N: usize> ContainerExactly<T> for [T; N] {
 }
 
 // Safety: `Box<C::Uninit>` is sound to reinterpret assuming the inner `C` implements this trait soundly
+#[cfg(feature = "alloc")]
 unsafe impl<T, C> ContainerExactly<T> for Box<C>
 where
     C: ContainerExactly<T>,
@@ -217,6 +228,7 @@ where
 
 // Safety: `Rc<UnsafeCell<C::Uninit>>` is sound to reinterpret assuming the inner `C` implements
 //         this trait soundly
+#[cfg(feature = "alloc")]
 unsafe impl<T, C> ContainerExactly<T> for Rc<C>
 where
     C: ContainerExactly<T>,
@@ -241,6 +253,7 @@ where
 
 // Safety: `Arc<UnsafeCell<C::Uninit>>` is sound to reinterpret assuming the inner `C` implements
 //         this trait soundly
+#[cfg(feature = "alloc")]
 unsafe impl<T, C> ContainerExactly<T> for Arc<C>
 where
     C: ContainerExactly<T>,
@@ -289,6 +302,45 @@ pub trait Seq<'p, T> {
     fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
     where
         'p: 'b;
+
+    /// Describe this sequence as a list of inclusive ranges, if it is made up of ranges.
+    ///
+    /// This is used to generate compact errors (`expected 'a'..'z'`) instead of enumerating every item.
+    #[inline(always)]
+    fn seq_ranges(&self) -> Option<SeqRanges<'_, 'p, T>> {
+        None
+    }
+}
+
+/// An iterator over the inclusive ranges that make up a sequence. See [`Seq::seq_ranges`].
+pub struct SeqRanges<'b, 'p, T> {
+    inner: SeqRangesInner<'b, 'p, T>,
+}
+
+enum SeqRangesInner<'b, 'p, T> {
+    // Ranges owned by the sequence, whose bounds must be cloned with the given function
+    Owned(core::slice::Iter<'b, RangeInclusive<T>>, fn(&T) -> T),
+    // Ranges that live as long as the sequence's items
+    Borrowed(core::slice::Iter<'p, RangeInclusive<T>>),
+}
+
+impl<'b, 'p, T> Iterator for SeqRanges<'b, 'p, T> {
+    type Item = (MaybeRef<'p, T>, MaybeRef<'p, T>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            SeqRangesInner::Owned(ranges, clone) => ranges.next().map(|range| {
+                (
+                    MaybeRef::Val(clone(range.start())),
+                    MaybeRef::Val(clone(range.end())),
+                )
+            }),
+            SeqRangesInner::Borrowed(ranges) => ranges
+                .next()
+                .map(|range| (MaybeRef::Ref(range.start()), MaybeRef::Ref(range.end()))),
+        }
+    }
 }
 
 impl<'p, T: Clone> Seq<'p, T> for T {
@@ -447,6 +499,7 @@ impl<'p, T, const N: usize> Seq<'p, T> for &'p [T; N] {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'p, T: Clone> Seq<'p, T> for Vec<T> {
     type Item<'a> = &'a T
     where
@@ -478,6 +531,7 @@ impl<'p, T: Clone> Seq<'p, T> for Vec<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'p, T: Clone> Seq<'p, T> for LinkedList<T> {
     type Item<'a> = &'a T
     where
@@ -509,6 +563,7 @@ impl<'p, T: Clone> Seq<'p, T> for LinkedList<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'p, T: Clone + Eq + Hash> Seq<'p, T> for HashSet<T> {
     type Item<'a> = &'a T
     where
@@ -572,6 +627,7 @@ impl<'p, T: Clone + Eq + Hash> Seq<'p, T> for std::collections::HashSet<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'p, T: Clone + Ord> Seq<'p, T> for alloc::collections::BTreeSet<T> {
     type Item<'a> = &'a T
     where
@@ -665,6 +721,93 @@ where
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_ranges(&self) -> Option<SeqRanges<'_, 'p, T>> {
+        Some(SeqRanges {
+            inner: SeqRangesInner::Owned(core::slice::from_ref(self).iter(), T::clone),
+        })
+    }
+}
+
+impl<'p, T, const N: usize> Seq<'p, T> for [RangeInclusive<T>; N]
+where
+    T: Clone + PartialOrd,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    type Item<'a> = T
+    where
+        Self: 'a;
+
+    type Iter<'a>
+        = core::iter::Flatten<core::iter::Cloned<core::slice::Iter<'a, RangeInclusive<T>>>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.iter().cloned().flatten()
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &T) -> bool {
+        self.iter().any(|range| range.contains(val))
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+
+    #[inline]
+    fn seq_ranges(&self) -> Option<SeqRanges<'_, 'p, T>> {
+        Some(SeqRanges {
+            inner: SeqRangesInner::Owned(self.iter(), T::clone),
+        })
+    }
+}
+
+impl<'p, T> Seq<'p, T> for &'p [RangeInclusive<T>]
+where
+    T: Clone + PartialOrd,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    type Item<'a> = T
+    where
+        Self: 'a;
+
+    type Iter<'a>
+        = core::iter::Flatten<core::iter::Cloned<core::slice::Iter<'p, RangeInclusive<T>>>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.iter().cloned().flatten()
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &T) -> bool {
+        self.iter().any(|range| range.contains(val))
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+
+    #[inline]
+    fn seq_ranges(&self) -> Option<SeqRanges<'_, 'p, T>> {
+        Some(SeqRanges {
+            inner: SeqRangesInner::Borrowed(self.iter()),
+        })
+    }
 }
 
 impl<'p, T> Seq<'p, T> for RangeFrom<T>
@@ -755,6 +898,7 @@ impl<'p> Seq<'p, char> for &'p str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'p> Seq<'p, char> for String {
     type Item<'a> = char
     where
@@ -793,6 +937,7 @@ impl<'p, T> OrderedSeq<'p, T> for &'p T {}
 impl<'p, T> OrderedSeq<'p, T> for &'p [T] {}
 impl<'p, T: Clone, const N: usize> OrderedSeq<'p, T> for [T; N] {}
 impl<'p, T, const N: usize> OrderedSeq<'p, T> for &'p [T; N] {}
+#[cfg(feature = "alloc")]
 impl<'p, T: Clone> OrderedSeq<'p, T> for Vec<T> {}
 impl<'p, T> OrderedSeq<'p, T> for Range<T> where Self: Seq<'p, T> {}
 impl<'p, T> OrderedSeq<'p, T> for core::ops::RangeInclusive<T> where Self: Seq<'p, T> {}
@@ -800,9 +945,10 @@ impl<'p, T> OrderedSeq<'p, T> for RangeFrom<T> where Self: Seq<'p, T> {}
 
 impl<'p> OrderedSeq<'p, char> for str {}
 impl<'p> OrderedSeq<'p, char> for &'p str {}
+#[cfg(feature = "alloc")]
 impl<'p> OrderedSeq<'p, char> for String {}
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test {
     use super::*;
 
@@ -811,6 +957,7 @@ mod test {
         for idx in 0..C::LEN {
             C::write(&mut uninit, idx, idx);
         }
+        // SAFETY: All elements were initialized above.
         unsafe { C::take(uninit) }
     }
 
@@ -819,6 +966,7 @@ mod test {
         for idx in 0..(C::LEN / 2) {
             C::write(&mut uninit, idx, idx);
         }
+        // SAFETY: Only the first half of the elements were initialized above.
         unsafe { C::drop_before(&mut uninit, C::LEN / 2) };
     }
 
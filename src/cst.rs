@@ -0,0 +1,370 @@
+//! Lossless concrete syntax trees, for building formatters, refactoring tools, and other IDE tooling.
+//!
+//! The outputs of most parsers throw away the details of the input that don't affect its meaning, such as whitespace,
+//! comments, and punctuation. Tools that rewrite source code need to keep these details, so alongside the usual output
+//! [`Parser::parse_cst`] builds a *green tree* in the style of [`rowan`](https://docs.rs/rowan): a tree of
+//! [`GreenNode`]s, each identified by a [`SyntaxKind`], whose leaves are [`GreenToken`]s that together contain every
+//! character of the input. Printing the tree gives back exactly the input that was parsed.
+//!
+//! The parts of a grammar that make up the tree are marked with [`Parser::syntax_node`] and [`Parser::syntax_token`].
+//! Any text that is not covered by a token (such as whitespace skipped by [`Parser::padded`]) becomes a token of the
+//! *trivia* kind given to [`Parser::parse_cst`], so marking only the tokens you care about still produces a lossless
+//! tree. To mark the parts of an input that were skipped by error recovery, wrap the recovery strategy's parser in a
+//! node of an error kind: see the example below.
+//!
+//! Nodes and tokens are only recorded when parsing with [`Parser::parse_cst`] (or [`Parser::parse_with_state_cst`]):
+//! otherwise, they have almost no overhead. Nodes and tokens within [memoised](Parser::memoised) parsers are only
+//! recorded the first time the parser is invoked at each position, and those within
+//! [nested inputs](Parser::nested_in) are not recorded at all.
+//!
+//! *This module requires the `cst` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, cst::SyntaxKind};
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! #[repr(u16)]
+//! enum Kind { Root, Trivia, Error, Call, Ident, Punct }
+//!
+//! impl From<Kind> for SyntaxKind {
+//!     fn from(kind: Kind) -> Self {
+//!         SyntaxKind(kind as u16)
+//!     }
+//! }
+//!
+//! let ident = text::ident::<_, _, extra::Err<Rich<char>>>().syntax_token(Kind::Ident);
+//! let call = ident
+//!     .then_ignore(just("()").syntax_token(Kind::Punct))
+//!     .then_ignore(just(';').syntax_token(Kind::Punct))
+//!     .syntax_node(Kind::Call)
+//!     // Skip the rest of a malformed statement, marking it as an error
+//!     .recover_with(via_parser(
+//!         none_of(';').repeated().then(just(';')).to("").syntax_node(Kind::Error),
+//!     ));
+//! let calls = call.padded().repeated().collect::<Vec<_>>();
+//!
+//! let input = "foo();\n  bar(1);\nbaz();";
+//! let (result, tree) = calls.parse_cst(input, Kind::Root, Kind::Trivia);
+//! assert_eq!(result.into_output(), Some(vec!["foo", "", "baz"]));
+//!
+//! // The tree contains the whole input...
+//! assert_eq!(tree.to_string(), input);
+//! // ...with a node for each call, and an error node for the malformed one
+//! let kinds = tree.children().iter().map(|child| child.kind()).collect::<Vec<_>>();
+//! assert_eq!(kinds, [Kind::Call, Kind::Trivia, Kind::Error, Kind::Trivia, Kind::Call].map(SyntaxKind::from));
+//! ```
+
+use super::*;
+
+/// The kind of a node or token in a concrete syntax tree, such as "expression" or "identifier".
+///
+/// Grammars usually define their kinds as an enum, and convert them into this type with a [`From`] implementation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SyntaxKind(pub u16);
+
+impl From<u16> for SyntaxKind {
+    fn from(kind: u16) -> Self {
+        Self(kind)
+    }
+}
+
+/// A node of a concrete syntax tree, containing other nodes and tokens. See [`Parser::parse_cst`].
+///
+/// Nodes don't know where in the input they are: as in [`rowan`](https://docs.rs/rowan), this means that identical
+/// subtrees are equal, wherever they occur. The [`Display`](fmt::Display) implementation prints the text of every
+/// token within the node.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    len: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    /// Create a new node from its kind and children.
+    pub fn new(
+        kind: impl Into<SyntaxKind>,
+        children: impl IntoIterator<Item = GreenElement>,
+    ) -> Self {
+        let children = children.into_iter().collect::<Vec<_>>();
+        Self {
+            kind: kind.into(),
+            len: children.iter().map(GreenElement::text_len).sum(),
+            children,
+        }
+    }
+
+    /// Get the kind of this node.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Get the length of the text within this node, in bytes.
+    pub fn text_len(&self) -> usize {
+        self.len
+    }
+
+    /// Get the nodes and tokens within this node, in order.
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+}
+
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.children
+            .iter()
+            .try_for_each(|child| write!(f, "{}", child))
+    }
+}
+
+/// A token of a concrete syntax tree: a leaf of the tree, containing text from the input. See [`Parser::parse_cst`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+impl GreenToken {
+    /// Create a new token from its kind and text.
+    pub fn new(kind: impl Into<SyntaxKind>, text: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            text: text.into(),
+        }
+    }
+
+    /// Get the kind of this token.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Get the text of this token.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the length of the text of this token, in bytes.
+    pub fn text_len(&self) -> usize {
+        self.text.len()
+    }
+}
+
+impl fmt::Display for GreenToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Either a [`GreenNode`] or a [`GreenToken`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    /// A node.
+    Node(GreenNode),
+    /// A token.
+    Token(GreenToken),
+}
+
+impl GreenElement {
+    /// Get the kind of this node or token.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            Self::Node(node) => node.kind(),
+            Self::Token(token) => token.kind(),
+        }
+    }
+
+    /// Get the length of the text within this node or token, in bytes.
+    pub fn text_len(&self) -> usize {
+        match self {
+            Self::Node(node) => node.text_len(),
+            Self::Token(token) => token.text_len(),
+        }
+    }
+
+    /// Get this element as a node, if it is one.
+    pub fn as_node(&self) -> Option<&GreenNode> {
+        match self {
+            Self::Node(node) => Some(node),
+            Self::Token(_) => None,
+        }
+    }
+
+    /// Get this element as a token, if it is one.
+    pub fn as_token(&self) -> Option<&GreenToken> {
+        match self {
+            Self::Node(_) => None,
+            Self::Token(token) => Some(token),
+        }
+    }
+}
+
+impl fmt::Display for GreenElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(node) => node.fmt(f),
+            Self::Token(token) => token.fmt(f),
+        }
+    }
+}
+
+impl From<GreenNode> for GreenElement {
+    fn from(node: GreenNode) -> Self {
+        Self::Node(node)
+    }
+}
+
+impl From<GreenToken> for GreenElement {
+    fn from(token: GreenToken) -> Self {
+        Self::Token(token)
+    }
+}
+
+// Recorded while parsing, and rewound along with the input. Offsets are byte offsets into the input.
+#[derive(Clone, Debug)]
+pub(crate) enum Event {
+    Start(SyntaxKind, usize),
+    Token(SyntaxKind, Range<usize>),
+    Finish(usize),
+}
+
+// Build a tree from the events recorded while parsing, filling any text not covered by a token with trivia tokens
+pub(crate) fn build(
+    events: &[Event],
+    text: &str,
+    root: SyntaxKind,
+    trivia: SyntaxKind,
+) -> GreenNode {
+    fn fill(
+        children: &mut Vec<GreenElement>,
+        text: &str,
+        pos: &mut usize,
+        to: usize,
+        trivia: SyntaxKind,
+    ) {
+        if to > *pos {
+            children.push(GreenToken::new(trivia, &text[*pos..to]).into());
+            *pos = to;
+        }
+    }
+
+    let mut stack = vec![(root, Vec::new())];
+    let mut pos = 0;
+    for event in events {
+        let (_, children) = stack.last_mut().expect("unbalanced syntax node events");
+        match event {
+            Event::Start(kind, offset) => {
+                fill(children, text, &mut pos, *offset, trivia);
+                stack.push((*kind, Vec::new()));
+            }
+            // Tokens that overlap the previous one can only be recorded by lookahead that is not rewound (such as
+            // `and_is`), and are dropped
+            Event::Token(kind, range) if range.start >= pos => {
+                fill(children, text, &mut pos, range.start, trivia);
+                children.push(GreenToken::new(*kind, &text[range.clone()]).into());
+                pos = range.end;
+            }
+            Event::Token(..) => {}
+            Event::Finish(offset) => {
+                fill(children, text, &mut pos, *offset, trivia);
+                let (kind, children) = stack.pop().expect("unbalanced syntax node events");
+                stack
+                    .last_mut()
+                    .expect("unbalanced syntax node events")
+                    .1
+                    .push(GreenNode::new(kind, children).into());
+            }
+        }
+    }
+
+    let (kind, mut children) = stack.pop().expect("unbalanced syntax node events");
+    debug_assert!(stack.is_empty(), "unbalanced syntax node events");
+    fill(&mut children, text, &mut pos, text.len(), trivia);
+    GreenNode::new(kind, children)
+}
+
+/// See [`Parser::syntax_node`].
+#[derive(Copy, Clone)]
+pub struct SyntaxNode<A> {
+    pub(crate) parser: A,
+    pub(crate) kind: SyntaxKind,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for SyntaxNode<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let Some(events) = inp.cst.as_deref_mut() else {
+            return self.parser.go::<M>(inp);
+        };
+        let before = events.len();
+        events.push(Event::Start(self.kind, inp.offset.into()));
+        let res = self.parser.go::<M>(inp);
+        if let Some(events) = inp.cst.as_deref_mut() {
+            match res {
+                Ok(_) => events.push(Event::Finish(inp.offset.into())),
+                Err(()) => events.truncate(before),
+            }
+        }
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
+
+/// See [`Parser::syntax_token`].
+#[derive(Copy, Clone)]
+pub struct SyntaxToken<A> {
+    pub(crate) parser: A,
+    pub(crate) kind: SyntaxKind,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for SyntaxToken<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let Some(events) = inp.cst.as_deref_mut() else {
+            return self.parser.go::<M>(inp);
+        };
+        let before = events.len();
+        let start = inp.offset.into();
+        let res = self.parser.go::<M>(inp);
+        if let Some(events) = inp.cst.as_deref_mut() {
+            // Tokens are leaves of the tree, so anything recorded within them is discarded
+            events.truncate(before);
+            if res.is_ok() {
+                events.push(Event::Token(self.kind, start..inp.offset.into()));
+            }
+        }
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
@@ -0,0 +1,194 @@
+//! Deriving parsers for simple structs and enums, without writing any combinators.
+//!
+//! Many inputs, such as configuration files and the commands of a REPL, map directly onto a Rust type: a sequence of
+//! fields introduced by literal text. `#[derive(Parseable)]` implements the [`Parseable`] trait for such a type,
+//! generating a parser for it from `#[parse(...)]` attributes:
+//!
+//! - A struct is parsed as each of its fields in order. Each field is parsed by the [`Parseable`] implementation of
+//!   its type, which includes integers, floats, booleans, strings (quoted, or a single identifier), and other types
+//!   that derive [`Parseable`].
+//!
+//! - An enum is parsed as any one of its variants, tried in order. Each variant is parsed as its name in lowercase
+//!   (or the `prefix` given to it), followed by its fields.
+//!
+//! - A field of type `Option<T>` may be left out, along with its `prefix` and `suffix`. A field of type `Vec<T>` is
+//!   parsed as any number of `T`s.
+//!
+//! Whitespace is allowed around every literal and field. Literal text is matched one whitespace-separated word at a
+//! time, and words that look like identifiers must not be followed by other identifier characters (as with
+//! [`text::keyword`]).
+//!
+//! The attributes are:
+//!
+//! | Attribute           | Applies to                       | Meaning                               |
+//! |---------------------|----------------------------------|---------------------------------------|
+//! | `prefix = "..."`    | Structs, enums, variants, fields | Literal text that comes before it     |
+//! | `suffix = "..."`    | Structs, enums, variants, fields | Literal text that comes after it      |
+//! | `separator = "..."` | `Vec` fields                     | Literal text that comes between items |
+//! | `at_least = n`      | `Vec` fields                     | The minimum number of items           |
+//! | `at_most = n`       | `Vec` fields                     | The maximum number of items           |
+//!
+//! Types with generic parameters are not supported, and nor are recursive types (since each call to
+//! [`Parseable::parser`] creates the parsers of every field).
+//!
+//! *This module requires the `derive` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, derive::Parseable};
+//! #[derive(Debug, PartialEq, Parseable)]
+//! enum Command {
+//!     #[parse(prefix = "go to")]
+//!     GoTo(i32, i32),
+//!     Say(String),
+//!     Quit,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Parseable)]
+//! struct Config {
+//!     #[parse(prefix = "name =")]
+//!     name: String,
+//!     #[parse(prefix = "retries =")]
+//!     retries: Option<u8>,
+//!     #[parse(prefix = "hosts = [", separator = ",", suffix = "]")]
+//!     hosts: Vec<String>,
+//! }
+//!
+//! let command = Command::parser::<extra::Err<Rich<char>>>();
+//! assert_eq!(command.parse("go to 3 -4").into_result(), Ok(Command::GoTo(3, -4)));
+//! assert_eq!(command.parse("say \"hello, world\"").into_result(), Ok(Command::Say("hello, world".to_string())));
+//! assert_eq!(command.parse("quit").into_result(), Ok(Command::Quit));
+//! assert!(command.parse("quitting").has_errors());
+//!
+//! let config = Config::parser::<extra::Err<Rich<char>>>();
+//! assert_eq!(
+//!     config.parse("name = \"server\"\nhosts = [alpha, \"beta.local\"]\n").into_result(),
+//!     Ok(Config {
+//!         name: "server".to_string(),
+//!         retries: None,
+//!         hosts: vec!["alpha".to_string(), "beta.local".to_string()],
+//!     }),
+//! );
+//! ```
+
+use super::*;
+use alloc::{borrow::Cow, string::ToString};
+
+/// Derive [`Parseable`] for a struct or enum. See the [module documentation](self) for more information.
+pub use chumsky_derive::Parseable;
+
+/// A type that can be parsed from text, typically implemented with `#[derive(Parseable)]`. See the
+/// [module documentation](self) for more information.
+pub trait Parseable: Sized {
+    /// Create a parser for this type.
+    fn parser<'a, E>() -> Boxed<'a, 'a, &'a str, Self, E>
+    where
+        Self: 'a,
+        E: ParserExtra<'a, &'a str> + 'a;
+}
+
+macro_rules! impl_parseable_int {
+    ($($ty:ty => $int:expr),* $(,)?) => {
+        $(
+            impl Parseable for $ty {
+                fn parser<'a, E>() -> Boxed<'a, 'a, &'a str, Self, E>
+                where
+                    Self: 'a,
+                    E: ParserExtra<'a, &'a str> + 'a,
+                {
+                    Parser::boxed($int.value::<$ty>().padded())
+                }
+            }
+        )*
+    };
+}
+
+impl_parseable_int! {
+    u8 => text::int(10),
+    u16 => text::int(10),
+    u32 => text::int(10),
+    u64 => text::int(10),
+    u128 => text::int(10),
+    usize => text::int(10),
+    i8 => text::int(10).signed(),
+    i16 => text::int(10).signed(),
+    i32 => text::int(10).signed(),
+    i64 => text::int(10).signed(),
+    i128 => text::int(10).signed(),
+    isize => text::int(10).signed(),
+}
+
+macro_rules! impl_parseable_float {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Parseable for $ty {
+                fn parser<'a, E>() -> Boxed<'a, 'a, &'a str, Self, E>
+                where
+                    Self: 'a,
+                    E: ParserExtra<'a, &'a str> + 'a,
+                {
+                    Parser::boxed(text::float().signed().value::<$ty>().padded())
+                }
+            }
+        )*
+    };
+}
+
+impl_parseable_float!(f32, f64);
+
+impl Parseable for bool {
+    fn parser<'a, E>() -> Boxed<'a, 'a, &'a str, Self, E>
+    where
+        Self: 'a,
+        E: ParserExtra<'a, &'a str> + 'a,
+    {
+        Parser::boxed(
+            text::keyword("true")
+                .to(true)
+                .or(text::keyword("false").to(false))
+                .padded(),
+        )
+    }
+}
+
+impl Parseable for String {
+    fn parser<'a, E>() -> Boxed<'a, 'a, &'a str, Self, E>
+    where
+        Self: 'a,
+        E: ParserExtra<'a, &'a str> + 'a,
+    {
+        Parser::boxed(
+            text::string()
+                .map(Cow::into_owned)
+                .or(text::ident().map(ToString::to_string))
+                .padded(),
+        )
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    // Match literal text one word at a time, allowing whitespace around each word
+    pub fn literal<'a, E>(lit: &'static str) -> Boxed<'a, 'a, &'a str, (), E>
+    where
+        E: ParserExtra<'a, &'a str> + 'a,
+    {
+        lit.split_whitespace()
+            .fold(Parser::boxed(empty()), |literal, word| {
+                let mut chars = word.chars();
+                let is_ident = chars
+                    .next()
+                    .map_or(false, |c| c.is_alphabetic() || c == '_')
+                    && chars.all(|c| c.is_alphanumeric() || c == '_');
+                let word = if is_ident {
+                    Parser::boxed(text::keyword(word).ignored())
+                } else {
+                    Parser::boxed(just(word).ignored())
+                };
+                Parser::boxed(literal.then_ignore(word.padded()))
+            })
+    }
+}
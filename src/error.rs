@@ -7,6 +7,7 @@
 //! like [`Cheap`], [`Simple`] or [`Rich`].
 
 use super::*;
+#[cfg(feature = "alloc")]
 use alloc::string::ToString;
 
 /// A trait that describes parser error types.
@@ -30,6 +31,7 @@ use alloc::string::ToString;
 ///         found: Option<char>,
 ///     },
 ///     NotADigit(Span, char),
+///     Custom(Span, String),
 /// }
 ///
 /// impl<'a> Error<'a, &'a str> for MyError {
@@ -54,6 +56,10 @@ use alloc::string::ToString;
 ///         }
 ///         self
 ///     }
+///
+///     fn custom<M: std::fmt::Display>(span: Span, msg: M) -> Self {
+///         Self::Custom(span, msg.to_string())
+///     }
 /// }
 ///
 /// let numeral = any::<_, extra::Err<MyError>>().try_map(|c: char, span| match c.to_digit(10) {
@@ -106,10 +112,57 @@ pub trait Error<'a, I: Input<'a>>: Sized {
     ) -> Self {
         Self::expected_found(expected, found, span)
     }
+
+    /// Create a new error describing a conflict between a set of expected (inclusive) ranges of inputs and that which
+    /// was actually found.
+    ///
+    /// This is used by parsers like [`one_of`] when given ranges of tokens, allowing errors
+    /// to describe the expected input as `'a'..'z'` rather than enumerating every token. By default, the expected
+    /// ranges are discarded.
+    #[inline(always)]
+    fn expected_ranges_found<
+        R: IntoIterator<Item = (MaybeRef<'a, I::Token>, MaybeRef<'a, I::Token>)>,
+    >(
+        expected: R,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(None, found, span)
+    }
+
+    /// Create a new error describing a conflict between a named class of expected inputs (such as a Unicode general
+    /// category) and that which was actually found.
+    ///
+    /// This is used by parsers that accept inputs which cannot be usefully enumerated, such as those in
+    /// `text::unicode`. By default, the name of the class is discarded.
+    #[inline(always)]
+    fn expected_class_found(
+        class: &'static str,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(None, found, span)
+    }
+
+    /// Create a new error with a custom message, describing a problem with the input that is not a simple conflict
+    /// between expected and found inputs (such as an integer literal that does not fit within its type).
+    ///
+    /// Error types that cannot store the message should still record as much as they can, such as the span. By
+    /// default, this produces an error that expected and found nothing in particular.
+    fn custom<M: fmt::Display>(span: I::Span, msg: M) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(None, None, span)
+    }
 }
 
 /// A ZST error type that tracks only whether a parse error occurred at all. This type is for when
 /// you want maximum parse speed, at the cost of all error reporting.
+///
+/// This is the error type used by [`extra::Default`]. Since it records nothing, generating it never allocates: this
+/// makes it well suited to workloads that only need to know whether an input is valid, especially in combination with
+/// [`Parser::check`].
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Default)]
 pub struct EmptyErr(());
 
@@ -122,6 +175,11 @@ impl<'a, I: Input<'a>> Error<'a, I> for EmptyErr {
     ) -> Self {
         EmptyErr(())
     }
+
+    #[inline(always)]
+    fn custom<M: fmt::Display>(_: I::Span, _: M) -> Self {
+        EmptyErr(())
+    }
 }
 
 impl fmt::Display for EmptyErr {
@@ -137,6 +195,13 @@ pub struct Cheap<S = SimpleSpan<usize>> {
     span: S,
 }
 
+impl<S> Cheap<S> {
+    /// Get the span associated with this error.
+    pub fn span(&self) -> &S {
+        &self.span
+    }
+}
+
 impl<'a, I: Input<'a>> Error<'a, I> for Cheap<I::Span> {
     #[inline]
     fn expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
@@ -146,6 +211,11 @@ impl<'a, I: Input<'a>> Error<'a, I> for Cheap<I::Span> {
     ) -> Self {
         Self { span }
     }
+
+    #[inline]
+    fn custom<M: fmt::Display>(span: I::Span, _msg: M) -> Self {
+        Self { span }
+    }
 }
 
 impl<S> fmt::Debug for Cheap<S>
@@ -176,6 +246,16 @@ pub struct Simple<'a, T, S = SimpleSpan<usize>> {
 }
 
 impl<'a, T, S> Simple<'a, T, S> {
+    /// Get the span associated with this error.
+    pub fn span(&self) -> &S {
+        &self.span
+    }
+
+    /// Get the token found by this error when it occurred, or `None` if the end of input was found.
+    pub fn found(&self) -> Option<&T> {
+        self.found.as_deref()
+    }
+
     /// Transform this error's tokens using the given function.
     ///
     /// This is useful when you wish to combine errors from multiple compilation passes (lexing and parsing, say) where
@@ -200,6 +280,12 @@ impl<'a, I: Input<'a>> Error<'a, I> for Simple<'a, I::Token, I::Span> {
     ) -> Self {
         Self { span, found }
     }
+
+    // `Simple` has nowhere to store the message, so only the span of a custom error is kept
+    #[inline]
+    fn custom<M: fmt::Display>(span: I::Span, _msg: M) -> Self {
+        Self { span, found: None }
+    }
 }
 
 impl<'a, T, S> fmt::Debug for Simple<'a, T, S>
@@ -225,17 +311,23 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 /// An expected pattern for a [`Rich`] error.
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RichPattern<'a, T, L = &'static str> {
     /// A specific token was expected.
     Token(MaybeRef<'a, T>),
+    /// Any token within the given inclusive range was expected.
+    Range(MaybeRef<'a, T>, MaybeRef<'a, T>),
+    /// Any token within the named class (such as a Unicode general category) was expected.
+    Class(&'static str),
     /// A labelled pattern was expected.
     Label(L),
     /// The end of input was expected.
     EndOfInput,
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, L> RichPattern<'a, T, L> {
     /// Transform this pattern's tokens using the given function.
     ///
@@ -247,6 +339,10 @@ impl<'a, T, L> RichPattern<'a, T, L> {
     {
         match self {
             Self::Token(t) => RichPattern::Token(f(t.into_inner()).into()),
+            Self::Range(a, b) => {
+                RichPattern::Range(f(a.into_inner()).into(), f(b.into_inner()).into())
+            }
+            Self::Class(class) => RichPattern::Class(class),
             Self::Label(s) => RichPattern::Label(s),
             Self::EndOfInput => RichPattern::EndOfInput,
         }
@@ -259,6 +355,8 @@ impl<'a, T, L> RichPattern<'a, T, L> {
     {
         match self {
             Self::Token(tok) => RichPattern::Token(tok.into_owned()),
+            Self::Range(a, b) => RichPattern::Range(a.into_owned(), b.into_owned()),
+            Self::Class(class) => RichPattern::Class(class),
             Self::Label(label) => RichPattern::Label(label),
             Self::EndOfInput => RichPattern::EndOfInput,
         }
@@ -276,12 +374,21 @@ impl<'a, T, L> RichPattern<'a, T, L> {
                 fmt_token(tok, f)?;
                 write!(f, "'")
             }
+            Self::Range(a, b) => {
+                write!(f, "'")?;
+                fmt_token(a, f)?;
+                write!(f, "'..'")?;
+                fmt_token(b, f)?;
+                write!(f, "'")
+            }
+            Self::Class(class) => write!(f, "{}", class),
             Self::Label(label) => fmt_label(label, f),
             Self::EndOfInput => write!(f, "end of input"),
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, L> fmt::Debug for RichPattern<'a, T, L>
 where
     T: fmt::Debug,
@@ -290,12 +397,15 @@ where
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Token(t) => write!(f, "{:?}", t),
+            Self::Range(a, b) => write!(f, "{:?}..{:?}", a, b),
+            Self::Class(class) => write!(f, "{}", class),
             Self::Label(label) => write!(f, "{:?}", label),
             Self::EndOfInput => write!(f, "end of input"),
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, L> fmt::Display for RichPattern<'a, T, L>
 where
     T: fmt::Display,
@@ -304,6 +414,8 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Token(t) => write!(f, "'{}'", &**t),
+            Self::Range(a, b) => write!(f, "'{}'..'{}'", **a, **b),
+            Self::Class(class) => write!(f, "{}", class),
             Self::Label(s) => write!(f, "{}", s),
             Self::EndOfInput => write!(f, "end of input"),
         }
@@ -311,6 +423,7 @@ where
 }
 
 // TODO: Maybe should make ExpectedFound encapsulated a bit more
+#[cfg(feature = "alloc")]
 /// The reason for a [`Rich`] error.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RichReason<'a, T, L = &'static str> {
@@ -329,6 +442,7 @@ pub enum RichReason<'a, T, L = &'static str> {
     Many(Vec<Self>),
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, L> RichReason<'a, T, L> {
     /// Return the token that was found by this error reason. `None` implies that the end of input was expected.
     pub fn found(&self) -> Option<&T> {
@@ -356,6 +470,15 @@ impl<'a, T, L> RichReason<'a, T, L> {
         }
     }
 
+    // Collect the patterns expected by this reason, and any reasons within it
+    pub(crate) fn into_expected(self, expected: &mut Vec<RichPattern<'a, T, L>>) {
+        match self {
+            Self::ExpectedFound { expected: e, .. } => expected.extend(e),
+            Self::Custom(_) => {}
+            Self::Many(many) => many.into_iter().for_each(|r| r.into_expected(expected)),
+        }
+    }
+
     #[cfg(feature = "label")]
     fn take_found(&mut self) -> Option<MaybeRef<'a, T>> {
         match self {
@@ -447,6 +570,7 @@ impl<'a, T, L> RichReason<'a, T, L> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, L> RichReason<'a, T, L>
 where
     T: PartialEq,
@@ -496,6 +620,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, L> fmt::Display for RichReason<'a, T, L>
 where
     T: fmt::Display,
@@ -506,6 +631,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 /// A rich default error type that tracks error spans, expected inputs, and the actual input found at an error site.
 ///
 /// Please note that it uses a [`Vec`] to remember expected symbols. If you find this to be too slow, you can
@@ -518,6 +644,7 @@ pub struct Rich<'a, T, S = SimpleSpan<usize>, L = &'static str> {
     context: Vec<(L, S)>,
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, S, L> Rich<'a, T, S, L> {
     fn inner_fmt(
         &self,
@@ -537,6 +664,7 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, S, L> Rich<'a, T, S, L> {
     /// Create an error with a custom message and span
     #[inline]
@@ -623,6 +751,7 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, I: Input<'a>, L> Error<'a, I> for Rich<'a, I::Token, I::Span, L>
 where
     I::Token: PartialEq,
@@ -745,8 +874,53 @@ where
         self.context.clear();
         self
     }
+
+    #[inline]
+    fn custom<M: fmt::Display>(span: I::Span, msg: M) -> Self {
+        Rich::custom(span, msg)
+    }
+
+    #[inline]
+    fn expected_ranges_found<
+        R: IntoIterator<Item = (MaybeRef<'a, I::Token>, MaybeRef<'a, I::Token>)>,
+    >(
+        expected: R,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        Self {
+            span,
+            reason: Box::new(RichReason::ExpectedFound {
+                expected: expected
+                    .into_iter()
+                    .map(|(a, b)| RichPattern::Range(a, b))
+                    .collect(),
+                found,
+            }),
+            #[cfg(feature = "label")]
+            context: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn expected_class_found(
+        class: &'static str,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        Self {
+            span,
+            reason: Box::new(RichReason::ExpectedFound {
+                expected: vec![RichPattern::Class(class)],
+                found,
+            }),
+            #[cfg(feature = "label")]
+            context: Vec::new(),
+        }
+    }
 }
 
+#[cfg(feature = "alloc")]
 #[cfg(feature = "label")]
 impl<'a, I: Input<'a>, L> LabelError<'a, I, L> for Rich<'a, I::Token, I::Span, L>
 where
@@ -762,10 +936,11 @@ where
                 expected.push(RichPattern::Label(label));
             }
             _ => {
-                self.reason = Box::new(RichReason::ExpectedFound {
+                let found = self.reason.take_found();
+                *self.reason = RichReason::ExpectedFound {
                     expected: vec![RichPattern::Label(label)],
-                    found: self.reason.take_found(),
-                });
+                    found,
+                };
             }
         }
     }
@@ -778,6 +953,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, S, L> fmt::Debug for Rich<'a, T, S, L>
 where
     T: fmt::Debug,
@@ -789,6 +965,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, S, L> fmt::Display for Rich<'a, T, S, L>
 where
     T: fmt::Display,
@@ -800,6 +977,154 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<'a, T, S, L> Rich<'a, T, S, L> {
+    /// Render this error as plain text, along with an excerpt of the source it refers to.
+    ///
+    /// The excerpt shows the line on which the error occurred, with the span of the error underlined, followed by the
+    /// labelled contexts of the error (see [`Rich::contexts`]). The span of the error must be a byte range of `src`,
+    /// as it is when parsing a [`&str`]. For rendering with colours and multiple labels, see
+    /// [`ariadne`](https://docs.rs/ariadne).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let list = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let src = "1,2,x";
+    /// let errs = list.parse(src).into_errors();
+    /// assert_eq!(errs[0].excerpt(src).to_string(), [
+    ///     "error: found 'x' expected '0'..'9'",
+    ///     " --> 1:5",
+    ///     "  |",
+    ///     "1 | 1,2,x",
+    ///     "  |     ^",
+    /// ].join("\n"));
+    /// ```
+    pub fn excerpt<'b>(&'b self, src: &'b str) -> Excerpt<'b, 'a, T, S, L> {
+        Excerpt { error: self, src }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// A plain-text rendering of a [`Rich`] error, along with an excerpt of the source. See [`Rich::excerpt`].
+pub struct Excerpt<'b, 'a, T, S, L> {
+    error: &'b Rich<'a, T, S, L>,
+    src: &'b str,
+}
+
+#[cfg(feature = "alloc")]
+impl<'b, 'a, T, S, L> fmt::Display for Excerpt<'b, 'a, T, S, L>
+where
+    T: fmt::Display,
+    S: Span<Offset = usize> + fmt::Display,
+    L: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, col) = line_col(self.src, self.error.span.start());
+        let width = line.to_string().len();
+
+        // Find the line containing the start of the span, and underline the part of it within the span
+        let start = floor_char_boundary(self.src, self.error.span.start());
+        let line_start = self.src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.src[start..]
+            .find('\n')
+            .map_or(self.src.len(), |i| start + i);
+        let text = self.src[line_start..line_end].trim_end_matches('\r');
+        let end = floor_char_boundary(self.src, self.error.span.end())
+            .clamp(start, line_start + text.len());
+        let indent = self.src[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let carets = self.src[start..end].chars().count().max(1);
+
+        writeln!(f, "error: {}", self.error)?;
+        writeln!(f, "{:width$}--> {}:{}", "", line, col, width = width)?;
+        writeln!(f, "{:width$} |", "", width = width)?;
+        writeln!(f, "{} | {}", line, text)?;
+        write!(
+            f,
+            "{:width$} | {}{}",
+            "",
+            indent,
+            "^".repeat(carets),
+            width = width
+        )?;
+        #[cfg(feature = "label")]
+        for (label, span) in self.error.contexts() {
+            let (line, col) = line_col(self.src, span.start());
+            write!(
+                f,
+                "\n{:width$} = while parsing {} at {}:{}",
+                "",
+                label,
+                line,
+                col,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// A plain-text rendering of the errors of a [`ParseResult`], each with an excerpt of the source. See
+/// [`ParseResult::report`].
+pub struct Report<'b, 'a, T, S, L> {
+    errors: &'b [Rich<'a, T, S, L>],
+    src: &'b str,
+}
+
+#[cfg(feature = "alloc")]
+impl<'b, 'a, T, S, L> Report<'b, 'a, T, S, L> {
+    pub(crate) fn new(errors: &'b [Rich<'a, T, S, L>], src: &'b str) -> Self {
+        Self { errors, src }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'b, 'a, T, S, L> fmt::Display for Report<'b, 'a, T, S, L>
+where
+    T: fmt::Display,
+    S: Span<Offset = usize> + fmt::Display,
+    L: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, "\n\n")?;
+            }
+            write!(f, "{}", error.excerpt(self.src))?;
+        }
+        Ok(())
+    }
+}
+
+// The closest character boundary of a source at or before the given byte offset
+#[cfg(feature = "alloc")]
+fn floor_char_boundary(src: &str, offset: usize) -> usize {
+    let mut offset = offset.min(src.len());
+    while !src.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+// The line and column (both starting from 1) of the given byte offset of a source
+#[cfg(feature = "alloc")]
+fn line_col(src: &str, offset: usize) -> (usize, usize) {
+    let offset = floor_char_boundary(src, offset);
+    let line_start = src[..offset].rfind('\n').map_or(0, |i| i + 1);
+    (
+        src[..line_start].matches('\n').count() + 1,
+        src[line_start..offset].chars().count() + 1,
+    )
+}
+
 fn write_token<T>(
     f: &mut fmt::Formatter,
     mut fmt_token: impl FnMut(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
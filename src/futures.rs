@@ -0,0 +1,139 @@
+//! Parsing tokens that arrive asynchronously, from a [`futures_core::Stream`].
+//!
+//! Asynchronous pipelines (such as the messages of a websocket, or the frames decoded from a socket) produce their
+//! input a piece at a time. [`StreamInput`] feeds the tokens of a [`Stream`] into a [`PushParser`], so that items can
+//! be parsed as soon as enough of the input has arrived, without first collecting the whole stream into a `Vec`.
+//!
+//! Parsing itself is synchronous: [`StreamInput::next`] tries to parse the next item from the tokens received so far,
+//! just like [`PushParser::next`]. When it returns [`Step::Pending`], awaiting [`StreamInput::fill`] waits for more
+//! tokens to arrive (or for the stream to end). Tokens that are already available are read together, so a partial
+//! item is only parsed again once per batch of tokens.
+//!
+//! *This module requires the `futures` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, futures::StreamInput, push::Step};
+//! # use futures::{executor::block_on, stream};
+//! // Commands are words that end with a semicolon
+//! fn command<'a>() -> impl Parser<'a, &'a [char], String> {
+//!     any().filter(char::is_ascii_alphabetic).repeated().at_least(1).collect().then_ignore(just(';'))
+//! }
+//!
+//! // Typically, this would be a stream of characters decoded from a network connection
+//! let mut input = StreamInput::new(stream::iter("ls;cd;pwd;".chars()));
+//!
+//! let commands = block_on(async {
+//!     let mut commands = Vec::new();
+//!     loop {
+//!         let step = input.next(&command());
+//!         match step {
+//!             Step::Pending => input.fill().await,
+//!             Step::Done(result) => commands.push(result.into_output().unwrap()),
+//!             Step::Finished => break commands,
+//!         }
+//!     }
+//! });
+//! assert_eq!(commands, ["ls", "cd", "pwd"]);
+//! ```
+
+use super::*;
+use crate::push::{PushParser, Step};
+use core::{
+    future::poll_fn,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_core::Stream;
+
+// The most tokens read from the stream by one call to `fill`, so that a stream that always has tokens available can't
+// prevent parsing from making progress
+const FILL_LIMIT: usize = 4096;
+
+/// An asynchronous stream of tokens, buffered so that it can be parsed. See the [module documentation](self) for more
+/// information.
+///
+/// Streams that are not [`Unpin`] can be pinned with [`Box::pin`] before being given to [`StreamInput::new`].
+pub struct StreamInput<S: Stream> {
+    stream: S,
+    push: PushParser<S::Item>,
+    ended: bool,
+}
+
+impl<S: Stream + Unpin> StreamInput<S> {
+    /// Create a new input that reads tokens from the given stream.
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            push: PushParser::new(),
+            ended: false,
+        }
+    }
+
+    /// Wait until more tokens have been read from the stream, or the stream has ended.
+    ///
+    /// Once the stream has ended, this returns immediately.
+    pub async fn fill(&mut self) {
+        poll_fn(|cx| self.poll_fill(cx)).await
+    }
+
+    /// Attempt to read more tokens from the stream, returning [`Poll::Ready`] once some have been read or the stream
+    /// has ended. This is the polling version of [`StreamInput::fill`], for use when implementing futures by hand.
+    pub fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.ended {
+            return Poll::Ready(());
+        }
+        let mut read = 0;
+        while read < FILL_LIMIT {
+            match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Ready(Some(token)) => {
+                    self.push.extend([token]);
+                    read += 1;
+                }
+                Poll::Ready(None) => {
+                    self.ended = true;
+                    self.push.finish();
+                    return Poll::Ready(());
+                }
+                Poll::Pending if read == 0 => return Poll::Pending,
+                Poll::Pending => break,
+            }
+        }
+        Poll::Ready(())
+    }
+
+    /// Try to parse the next item from the tokens read so far. See [`PushParser::next`].
+    ///
+    /// When this returns [`Step::Pending`], await [`StreamInput::fill`] before trying again.
+    pub fn next<'a, P, O, E>(&'a mut self, parser: &P) -> Step<O, E::Error>
+    where
+        P: Parser<'a, &'a [S::Item], O, E>,
+        E: ParserExtra<'a, &'a [S::Item]>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.push.next(parser)
+    }
+
+    /// Returns true if the stream has ended, so no more tokens will be read from it.
+    pub fn has_ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Get the tokens that have been read from the stream but not yet parsed.
+    pub fn buffered(&self) -> &[S::Item] {
+        self.push.buffered()
+    }
+
+    /// Discard the tokens that have been read from the stream but not yet parsed, such as after an item failed to
+    /// parse. See [`PushParser::clear`].
+    pub fn clear(&mut self) {
+        self.push.clear()
+    }
+
+    /// Get the underlying stream, discarding any tokens that have not yet been parsed.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
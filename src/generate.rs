@@ -0,0 +1,386 @@
+//! Generation of random inputs from the structure of a parser, for fuzzing and property testing.
+//!
+//! [`Parser::generate`] walks a parser and the parsers it is made of, producing a random input that the parser should
+//! accept: choices pick a random branch, repetitions repeat a random number of times, and terminals such as [`just`],
+//! [`one_of`], or [`text::int`] produce a matching input. Recursive parsers are expanded to a limited depth (see
+//! [`Generator::max_depth`]), so that generation terminates. [`Generator::mutate`] then makes a small random edit to an
+//! input, producing a 'near miss' that is often (but not always) rejected.
+//!
+//! Both are deterministic for a given seed, so they can be used to produce a corpus for a fuzzer such as
+//! [`cargo-fuzz`](https://github.com/rust-fuzz/cargo-fuzz), or to drive property tests, such as checking that
+//! pretty-printing the output of a parser and parsing it again produces the same output. With the `proptest` feature
+//! enabled, `strategy` turns a parser into a [`proptest`](https://docs.rs/proptest) strategy for this purpose.
+//!
+//! Generation is best-effort: parsers that accept input according to arbitrary code, such as [`Parser::try_map`],
+//! [`Parser::validate`], [`select!`], or context-sensitive parsers, are generated from the parsers they wrap (or, for
+//! [`custom`] and [`select!`], not at all), so a generated input is not guaranteed to be accepted. For example,
+//! [`text::keyword`] generates arbitrary identifiers, since it is implemented with [`Parser::try_map`].
+//! [`Parser::filter`] is respected where the output of the parser it wraps can be determined, such as for [`any`].
+//! Inputs that need to be accepted should be checked by parsing them.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, generate::Generator};
+//! let list = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+//!     .separated_by(just(',').padded())
+//!     .collect::<Vec<_>>()
+//!     .delimited_by(just('['), just(']'));
+//!
+//! let mut gen = Generator::ascii(42);
+//! for _ in 0..100 {
+//!     let input = list.generate(&mut gen).unwrap().into_iter().collect::<String>();
+//!     assert!(!list.parse(&input).has_errors(), "rejected {input:?}");
+//!
+//!     // Near misses are useful for testing error recovery, but might still be accepted
+//!     let near_miss = gen.mutate(&input.chars().collect::<Vec<_>>());
+//!     let _ = list.parse(&near_miss.into_iter().collect::<String>());
+//! }
+//! ```
+
+use super::*;
+
+// The number of attempts made to generate an input accepted by `Parser::filter` before giving up
+const FILTER_ATTEMPTS: usize = 128;
+
+/// A source of random inputs for [`Parser::generate`].
+///
+/// A generator holds a seeded pseudo-random number generator, along with the alphabet of tokens from which parsers
+/// like [`any`] and [`none_of`] pick their tokens.
+#[derive(Clone, Debug)]
+pub struct Generator<T> {
+    state: u64,
+    alphabet: Vec<T>,
+    max_depth: usize,
+    max_repeats: usize,
+    pub(crate) depth: usize,
+    pub(crate) tokens: Vec<T>,
+}
+
+impl<T> Generator<T> {
+    /// Create a generator with the given seed, which picks arbitrary tokens from the given alphabet.
+    pub fn new(seed: u64, alphabet: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            state: seed,
+            alphabet: alphabet.into_iter().collect(),
+            max_depth: 16,
+            max_repeats: 4,
+            depth: 0,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Set the number of times that recursive parsers may be nested within each other (16, by default).
+    ///
+    /// Once half of this depth is reached, repetitions and optional parsers prefer to produce as little input as
+    /// possible, so that inputs of deeply recursive grammars don't grow exponentially.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Set the number of times beyond their minimum that repetitions may repeat (4, by default).
+    pub fn max_repeats(self, max_repeats: usize) -> Self {
+        Self {
+            max_repeats,
+            ..self
+        }
+    }
+
+    // The next pseudo-random number, using SplitMix64
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random number in `0..n`, where `n` is greater than zero.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A random item of the given items, if there are any.
+    pub(crate) fn pick<U>(&mut self, items: impl IntoIterator<Item = U>) -> Option<U> {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+        if items.is_empty() {
+            None
+        } else {
+            Some(items.swap_remove(self.below(items.len())))
+        }
+    }
+
+    /// A random boolean, which is less likely to be `true` when deeply nested within recursive parsers.
+    pub(crate) fn chance(&mut self) -> bool {
+        !self.is_deep() && self.next_u64() & 1 == 1
+    }
+
+    /// A random number of repetitions, between `at_least` and `at_most` (inclusive).
+    pub(crate) fn repeats(&mut self, at_least: usize, at_most: usize) -> usize {
+        let at_most = at_most.min(at_least.saturating_add(self.max_repeats));
+        if self.is_deep() || at_most <= at_least {
+            at_least
+        } else {
+            at_least + self.below(at_most - at_least + 1)
+        }
+    }
+
+    fn is_deep(&self) -> bool {
+        self.depth * 2 >= self.max_depth
+    }
+
+    /// Generate the input of a recursive parser, failing if they are already nested too deeply.
+    pub(crate) fn recurse<O>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<Option<O>, ()>,
+    ) -> Result<Option<O>, ()> {
+        if self.depth >= self.max_depth {
+            return Err(());
+        }
+        self.depth += 1;
+        let res = f(self);
+        self.depth -= 1;
+        res
+    }
+
+    /// Generate the input of one of several alternatives, picked at random. Alternatives that fail are undone, and
+    /// the others are tried in turn.
+    pub(crate) fn choose<O>(
+        &mut self,
+        n: usize,
+        mut f: impl FnMut(&mut Self, usize) -> Result<Option<O>, ()>,
+    ) -> Result<Option<O>, ()> {
+        if n == 0 {
+            return Err(());
+        }
+        let before = self.tokens.len();
+        let start = self.below(n);
+        for i in 0..n {
+            match f(self, (start + i) % n) {
+                Ok(out) => return Ok(out),
+                Err(()) => self.tokens.truncate(before),
+            }
+        }
+        Err(())
+    }
+
+    /// Generate the input of a repetition of between `at_least` and `at_most` items, given the index of each item.
+    /// Once `at_least` items have been generated, an item that fails is undone and ends the repetition.
+    pub(crate) fn repeat<O>(
+        &mut self,
+        at_least: usize,
+        at_most: u64,
+        mut item: impl FnMut(&mut Self, usize) -> Result<Option<O>, ()>,
+    ) -> Result<Option<Vec<O>>, ()> {
+        let count = self.repeats(at_least, usize::try_from(at_most).unwrap_or(usize::MAX));
+        let mut items = Some(Vec::with_capacity(count));
+        for i in 0..count {
+            let before = self.tokens.len();
+            match item(self, i) {
+                Ok(out) => {
+                    items = items.zip(out).map(|(mut items, out)| {
+                        items.push(out);
+                        items
+                    })
+                }
+                Err(()) if i >= at_least => {
+                    self.tokens.truncate(before);
+                    break;
+                }
+                Err(()) => return Err(()),
+            }
+        }
+        Ok(items)
+    }
+}
+
+impl<T: Clone> Generator<T> {
+    /// An arbitrary token from the alphabet, if there are any.
+    pub(crate) fn any_token(&mut self) -> Option<T> {
+        self.token_where(|_| true)
+    }
+
+    /// An arbitrary token from the alphabet that satisfies the given predicate, if there are any.
+    pub(crate) fn token_where(&mut self, f: impl Fn(&T) -> bool) -> Option<T> {
+        let indices = (0..self.alphabet.len())
+            .filter(|i| f(&self.alphabet[*i]))
+            .collect::<Vec<_>>();
+        let i = self.pick(indices)?;
+        Some(self.alphabet[i].clone())
+    }
+
+    /// Generate the input of a parser, repeating the attempt until its output satisfies the given predicate.
+    ///
+    /// Attempts that don't determine their output are accepted as they are.
+    pub(crate) fn filtered<O>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<Option<O>, ()>,
+        filter: impl Fn(&O) -> bool,
+    ) -> Result<Option<O>, ()> {
+        let before = self.tokens.len();
+        for _ in 0..FILTER_ATTEMPTS {
+            match f(self)? {
+                Some(out) if !filter(&out) => self.tokens.truncate(before),
+                out => return Ok(out),
+            }
+        }
+        Err(())
+    }
+
+    /// Make a small random change to an input, such as removing, inserting, replacing, or duplicating a token.
+    ///
+    /// This is useful for producing 'near misses': inputs that are similar to inputs accepted by a parser, but which
+    /// the parser will often reject.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::generate::Generator;
+    /// let mut gen = Generator::new(0, "ab".chars());
+    /// let input = "(a)".chars().collect::<Vec<_>>();
+    ///
+    /// let near_miss = gen.mutate(&input);
+    /// assert_ne!(near_miss, input);
+    /// assert!(near_miss.len().abs_diff(input.len()) <= 1);
+    /// ```
+    pub fn mutate(&mut self, input: &[T]) -> Vec<T>
+    where
+        T: PartialEq,
+    {
+        let mut output = input.to_vec();
+        let at = self.below(input.len() + 1);
+        let inserted = self.any_token();
+        match (self.below(4), inserted) {
+            (0, _) | (1, None) if at < input.len() => {
+                output.remove(at);
+            }
+            (1, Some(tok)) if at < input.len() && tok != input[at] => output[at] = tok,
+            (2, _) if at < input.len() => output.insert(at, input[at].clone()),
+            (_, Some(tok)) => output.insert(at, tok),
+            (_, None) if !input.is_empty() => {
+                output.pop();
+            }
+            (_, None) => {}
+        }
+        output
+    }
+}
+
+impl Generator<char> {
+    /// Create a generator with the given seed, which picks arbitrary tokens from the printable ASCII characters, along
+    /// with tabs and newlines.
+    pub fn ascii(seed: u64) -> Self {
+        Self::new(seed, (' '..='~').chain(['\t', '\n']))
+    }
+}
+
+impl Generator<u8> {
+    /// Create a generator with the given seed, which picks arbitrary tokens from all bytes.
+    pub fn bytes(seed: u64) -> Self {
+        Self::new(seed, 0..=u8::MAX)
+    }
+}
+
+/// Create a [`proptest`](https://docs.rs/proptest) strategy that generates inputs accepted by a parser of text, using
+/// [`Parser::generate`].
+///
+/// Each input is generated with a seed drawn from proptest's test runner, so failures can be reproduced as usual.
+/// Inputs are not shrunk, since a smaller version of an input is rarely accepted by the same parser. If no input can be
+/// generated, the test case is rejected. Use [`ParserStrategy::generator`] to configure generation.
+///
+/// *This function requires the `proptest` feature.*
+///
+/// # Examples
+///
+/// Checking that printing the output of a parser and parsing it again round-trips:
+///
+/// ```
+/// # use chumsky::{prelude::*, generate};
+/// use proptest::test_runner::TestRunner;
+///
+/// let list = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+///     .value::<u32>()
+///     .separated_by(just(',').padded())
+///     .collect::<Vec<_>>();
+///
+/// TestRunner::default()
+///     .run(&generate::strategy(&list), |input| {
+///         let output = list.parse(&input).into_result().unwrap();
+///         let printed = output.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+///         assert_eq!(list.parse(&printed).into_result().unwrap(), output);
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[cfg(feature = "proptest")]
+pub fn strategy<'a, P, I, O, E>(parser: P) -> ParserStrategy<'a, P, I, O, E>
+where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    ParserStrategy {
+        parser,
+        gen: Generator::ascii(0),
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A [`proptest`](https://docs.rs/proptest) strategy that generates inputs accepted by a parser. See [`strategy`].
+///
+/// *This type requires the `proptest` feature.*
+#[cfg(feature = "proptest")]
+pub struct ParserStrategy<'a, P, I, O, E> {
+    parser: P,
+    gen: Generator<char>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(&'a (), I, O, E)>,
+}
+
+#[cfg(feature = "proptest")]
+impl<'a, P, I, O, E> ParserStrategy<'a, P, I, O, E> {
+    /// Generate inputs with the given generator, such as one with a different alphabet or maximum depth. The seed of
+    /// the generator is ignored: each input is generated with a seed drawn from proptest's test runner.
+    pub fn generator(self, gen: Generator<char>) -> Self {
+        Self { gen, ..self }
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<'a, P, I, O, E> fmt::Debug for ParserStrategy<'a, P, I, O, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserStrategy")
+            .field("gen", &self.gen)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<'a, P, I, O, E> proptest::strategy::Strategy for ParserStrategy<'a, P, I, O, E>
+where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    type Tree = proptest::strategy::Just<String>;
+    type Value = String;
+
+    fn new_tree(
+        &self,
+        runner: &mut proptest::test_runner::TestRunner,
+    ) -> proptest::strategy::NewTree<Self> {
+        use proptest::strategy::ValueTree;
+
+        let seed = proptest::arbitrary::any::<u64>()
+            .new_tree(runner)?
+            .current();
+        let mut gen = Generator {
+            state: seed,
+            ..self.gen.clone()
+        };
+        match self.parser.generate(&mut gen) {
+            Some(input) => Ok(proptest::strategy::Just(input.into_iter().collect())),
+            None => Err("the parser could not generate an input".into()),
+        }
+    }
+}
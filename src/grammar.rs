@@ -0,0 +1,61 @@
+//! Writing parsers as grammar rules, in an EBNF-like notation.
+//!
+//! Some grammars are easier to read as a list of rules than as chains of combinators, especially when they are
+//! transcribed from a language specification. The [`grammar!`] macro accepts rules written in a notation similar to
+//! EBNF, with Rust expressions embedded to build their outputs, and expands each rule into a function that returns
+//! an ordinary parser built from chumsky's combinators. The resulting parsers are just as fast, report the same
+//! errors, and can be combined with hand-written parsers as usual.
+//!
+//! Each rule is written as `name: Type = expression;`, and may be preceded by attributes (such as doc comments) and
+//! a visibility, which are given to the rule's function. The function is called `name`, and returns an
+//! `impl Parser<'a, Input, Type, Extra> + Clone`. Rules can refer to each other (and themselves) in any order. The
+//! input and extra types of the rules default to `&'a str` and [`extra::Default`](crate::extra::Default), and can be changed
+//! with `type Input = ...;` and `type Extra = ...;` before the rules, using the lifetime `'a` for the input.
+//!
+//! Expressions are made of:
+//!
+//! | Notation        | Meaning                                                 | Output                                       |
+//! |-----------------|---------------------------------------------------------|----------------------------------------------|
+//! | `"text"`, `'c'` | Literal input, as with [`just`](crate::primitive::just)                         | Its output is ignored within a sequence      |
+//! | `rule`          | Another rule of the grammar                             | The output of the rule, unless it is `()`    |
+//! | `{ parser }`    | A parser written in Rust                                | The output of the parser                     |
+//! | `a b c`         | A sequence                                              | The outputs of its parts, as a tuple         |
+//! | `a \| b`        | Ordered choice: `b` is only tried if `a` fails          | The output of whichever matched              |
+//! | `a*`, `a+`      | Zero or more, or one or more repetitions                | A `Vec` of the outputs                       |
+//! | `a?`            | An optional part                                        | An `Option` of the output                    |
+//! | `( ... )`       | Grouping                                                | The output of the group                      |
+//! | `a b => action` | Applies a function to the output of a sequence          | The output of the function                   |
+//!
+//! A sequence whose parts have no outputs (such as literals, and rules of type `()`) has the output `()`, and a sequence with one output
+//! has that output rather than a tuple. An action extends to the next `|` or `;`, so a `|` within it (other than
+//! in the parameters of a closure) must be wrapped in brackets. Rules can be referred to by name within embedded
+//! Rust parsers, and are [`Recursive`](crate::recursive::Recursive) parsers.
+//!
+//! *This module requires the `grammar` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, grammar::grammar};
+//! grammar! {
+//!     type Extra = extra::Err<Rich<'a, char>>;
+//!
+//!     /// A sum of products of numbers
+//!     pub expr: i64 = product ("+" product)* => |(first, rest)| first + rest.iter().sum::<i64>();
+//!     product: i64 = atom ("*" atom)* => |(first, rest)| rest.iter().product::<i64>() * first;
+//!     atom: i64 = number | "(" expr ")" | "-" atom => |x: i64| -x;
+//!     number: i64 = { text::int(10).from_str().unwrapped() };
+//! }
+//!
+//! assert_eq!(expr().parse("2*(3+4)+-5").into_result(), Ok(9));
+//! assert_eq!(expr().parse("10*10*10").into_result(), Ok(1000));
+//! assert!(expr().parse("2*(3+4").has_errors());
+//! ```
+
+/// Write parsers as rules in an EBNF-like notation. See the [module documentation](self) for more information.
+pub use chumsky_derive::grammar;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec::Vec;
+}
@@ -0,0 +1,532 @@
+//! Introspection of the structure of parsers, for visualising and reviewing large grammars.
+//!
+//! [`Parser::graph`] walks a parser and the parsers it is made of, producing a [`Graph`] that can be rendered in the
+//! [GraphViz](https://graphviz.org/) DOT language with [`Graph::to_dot`]. Each node is named after the combinator it
+//! represents (such as `Then` or `Repeated`), or after its label if it was created by [`Parser::labelled`] or
+//! [`Parser::named`].
+//!
+//! A recursive parser appears only once in the graph: the places where it refers back to itself are shown as dashed
+//! edges, labelled `recursion`.
+//!
+//! The grammar that the parser recognises can also be written out as [EBNF](https://en.wikipedia.org/wiki/Extended_Backus%E2%80%93Naur_form)
+//! with [`Graph::to_ebnf`], or, with the `railroad` feature enabled, rendered as railroad diagrams with
+//! [`Graph::to_railroad_svg`]. Both are derived from the parser itself, so they can't fall out of date with it. The
+//! same goes for grammars exported for [tree-sitter](https://tree-sitter.github.io/) with [`Graph::to_tree_sitter`],
+//! which let editors highlight a language using the grammar that its parser is written with.
+//!
+//! Tools that need more than this, such as linters or documentation generators, can walk the graph themselves with
+//! [`Graph::visit`] or [`Graph::nodes`], using the [`kind`](NodeRef::kind) and [`label`](NodeRef::label) of each node.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! let tree = recursive(|tree| {
+//!     text::int::<&str, _, extra::Err<Rich<char>>>(10)
+//!         .or(tree.delimited_by(just('('), just(')')))
+//! });
+//!
+//! let dot = tree.graph().to_dot();
+//! assert!(dot.starts_with("digraph {"));
+//! assert!(dot.contains("label=\"Int\""));
+//! assert!(dot.contains("[style=dashed, label=\"recursion\"]"));
+//! ```
+
+use super::*;
+
+mod analyse;
+mod ebnf;
+#[cfg(feature = "railroad")]
+mod railroad;
+mod tree_sitter;
+
+pub use self::tree_sitter::TreeSitter;
+
+/// The identifier of a node within a [`Graph`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// The kind of parser that a node of a [`Graph`] represents, describing how it uses its children.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// A parser without children, whose grammar is opaque, such as [`just`] or [`any`].
+    Terminal,
+    /// A parser created by [`Parser::labelled`] or [`Parser::named`], with a single child.
+    Labelled,
+    /// A parser created by [`recursive()`], with a single child. Nodes within its child may refer back to it.
+    Recursive,
+    /// A parser that invokes each of its children in order, such as [`Parser::then`].
+    Sequence,
+    /// A parser that invokes one of its children, such as [`Parser::or`] or [`choice`].
+    Choice,
+    /// A parser that optionally invokes its child, such as [`Parser::or_not`].
+    Optional,
+    /// A parser that invokes its first child many times, separated by its second child if it has one, such as
+    /// [`Parser::repeated`] or [`Parser::separated_by`].
+    Repetition,
+    /// A parser that looks ahead without consuming input, such as [`Parser::not`] or [`Parser::rewind`].
+    Lookahead,
+    /// A parser that parses the output of its first child with its second child, such as [`Parser::nested_in`].
+    Nested,
+    /// A parser that invokes its single child, changing only its output or the way that errors are handled, such as
+    /// [`Parser::map`].
+    Wrapper,
+}
+
+/// A node of a [`Graph`], representing a single parser. See [`Graph::node`].
+#[derive(Copy, Clone)]
+pub struct NodeRef<'a> {
+    graph: &'a Graph,
+    id: NodeId,
+}
+
+impl<'a> NodeRef<'a> {
+    /// The identifier of this node.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// The kind of parser that this node represents.
+    pub fn kind(&self) -> NodeKind {
+        self.node().kind(self.children().count())
+    }
+
+    /// The name of the type of the parser that this node represents, without its path or generic parameters (such
+    /// as `Then` or `Repeated`).
+    pub fn name(&self) -> &'a str {
+        &self.node().name
+    }
+
+    /// The label of this node, if it represents a [labelled](Parser::labelled) or [named](Parser::named) parser.
+    pub fn label(&self) -> Option<&'a str> {
+        self.node().label.as_deref()
+    }
+
+    /// The children of this node, in the order that the parser uses them.
+    ///
+    /// Nodes within a [recursive](recursive()) parser refer back to it as one of their children, so walking a graph by following
+    /// children recursively must keep track of the recursive parsers that it has already seen. [`Graph::visit`] does
+    /// this automatically.
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        let (graph, id) = (self.graph, self.id);
+        graph
+            .edges
+            .iter()
+            .filter(move |(from, _, _)| *from == id)
+            .map(move |(_, to, _)| NodeRef { graph, id: *to })
+    }
+
+    fn node(&self) -> &'a Node {
+        &self.graph.nodes[self.id.0]
+    }
+}
+
+impl fmt::Debug for NodeRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeRef")
+            .field("id", &self.id)
+            .field("kind", &self.kind())
+            .field("name", &self.name())
+            .field("label", &self.label())
+            .finish()
+    }
+}
+
+/// A walker over the nodes of a [`Graph`]. See [`Graph::visit`].
+pub trait Visitor {
+    /// Called when a node is first reached, before its children are visited. Returning `false` skips the children of
+    /// the node.
+    fn enter(&mut self, node: NodeRef<'_>) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called after the children of a node have been visited (or skipped).
+    fn exit(&mut self, node: NodeRef<'_>) {
+        let _ = node;
+    }
+}
+
+struct Node {
+    name: String,
+    label: Option<String>,
+    recursive: bool,
+    // Whether the parser can succeed without consuming input, regardless of its children
+    nullable: bool,
+    open: bool,
+}
+
+impl Node {
+    fn kind(&self, children: usize) -> NodeKind {
+        if self.label.is_some() {
+            return NodeKind::Labelled;
+        } else if self.recursive {
+            return NodeKind::Recursive;
+        }
+        match (self.name.as_str(), children) {
+            ("Or" | "Choice" | "Predictive" | "ByteDispatch", _) => NodeKind::Choice,
+            ("OrNot" | "OrNotWithErr", _) => NodeKind::Optional,
+            ("Repeated" | "SeparatedBy", _) => NodeKind::Repetition,
+            ("Not" | "Rewind" | "AndIs", _) => NodeKind::Lookahead,
+            ("NestedIn", _) => NodeKind::Nested,
+            (_, 0) => NodeKind::Terminal,
+            (_, 1) => NodeKind::Wrapper,
+            _ => NodeKind::Sequence,
+        }
+    }
+}
+
+// The grammar described by a graph, as used to render EBNF and railroad diagrams
+enum Expr {
+    // A parser that has no children, named after its type
+    Terminal(String),
+    // A reference to another rule, by name
+    Rule(String),
+    Sequence(Vec<Expr>),
+    Choice(Vec<Expr>),
+    Optional(Box<Expr>),
+    // Zero or more repetitions of the first expression, separated by the second
+    Repeat(Box<Expr>, Option<Box<Expr>>),
+}
+
+/// The structure of a parser, as produced by [`Parser::graph`].
+#[derive(Default)]
+pub struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<(NodeId, NodeId, bool)>,
+    recursive: HashMap<usize, NodeId>,
+}
+
+impl Graph {
+    /// Render this graph in the [GraphViz](https://graphviz.org/) DOT language.
+    ///
+    /// Labelled parsers are drawn as boxes, and edges that refer back to an enclosing recursive parser are dashed.
+    pub fn to_dot(&self) -> String {
+        use core::fmt::Write;
+
+        let mut dot = String::from("digraph {\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let name = node.label.as_ref().unwrap_or(&node.name);
+            let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+            let shape = if node.label.is_some() {
+                ", shape=box"
+            } else {
+                ""
+            };
+            let _ = writeln!(dot, "    n{} [label=\"{}\"{}];", i, name, shape);
+        }
+        for (from, to, recursive) in &self.edges {
+            let style = if *recursive {
+                " [style=dashed, label=\"recursion\"]"
+            } else {
+                ""
+            };
+            let _ = writeln!(dot, "    n{} -> n{}{};", from.0, to.0, style);
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// The number of nodes in this graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The node of the parser that this graph was produced from, or `None` if the graph is empty.
+    pub fn root(&self) -> Option<NodeRef<'_>> {
+        (!self.nodes.is_empty()).then(|| self.node(NodeId(0)))
+    }
+
+    /// Get a node of this graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node does not belong to this graph.
+    pub fn node(&self, id: NodeId) -> NodeRef<'_> {
+        assert!(
+            id.0 < self.nodes.len(),
+            "node does not belong to this graph"
+        );
+        NodeRef { graph: self, id }
+    }
+
+    /// Iterate over all of the nodes of this graph, in the order that they were added (parents before their
+    /// children).
+    pub fn nodes(&self) -> impl Iterator<Item = NodeRef<'_>> + '_ {
+        (0..self.nodes.len()).map(move |id| NodeRef {
+            graph: self,
+            id: NodeId(id),
+        })
+    }
+
+    /// Walk the nodes of this graph depth-first, starting at the root, calling the visitor as each node is entered
+    /// and exited.
+    ///
+    /// Every node is visited once: references back to a [recursive](recursive()) parser that is already being (or has already
+    /// been) visited are not followed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, graph::{NodeKind, NodeRef, Visitor}};
+    /// // Find the greatest number of branches of any choice in a grammar
+    /// struct WidestChoice(usize);
+    ///
+    /// impl Visitor for WidestChoice {
+    ///     fn enter(&mut self, node: NodeRef<'_>) -> bool {
+    ///         if node.kind() == NodeKind::Choice {
+    ///             self.0 = self.0.max(node.children().count());
+    ///         }
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let tree = recursive(|tree| {
+    ///     choice((
+    ///         just::<_, &str, extra::Default>('a'),
+    ///         just('b'),
+    ///         tree.delimited_by(just('('), just(')')),
+    ///     ))
+    /// });
+    ///
+    /// let mut widest = WidestChoice(0);
+    /// tree.graph().visit(&mut widest);
+    /// assert_eq!(widest.0, 3);
+    /// ```
+    pub fn visit<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        fn visit_node<V: Visitor + ?Sized>(node: NodeRef<'_>, seen: &mut [bool], visitor: &mut V) {
+            if core::mem::replace(&mut seen[node.id.0], true) {
+                return;
+            }
+            if visitor.enter(node) {
+                for child in node.children() {
+                    visit_node(child, seen, visitor);
+                }
+            }
+            visitor.exit(node);
+        }
+
+        if let Some(root) = self.root() {
+            visit_node(root, &mut vec![false; self.nodes.len()], visitor);
+        }
+    }
+
+    /// Add a node named after the type `T`, without its path or generic parameters.
+    pub(crate) fn add<T: ?Sized>(&mut self) -> NodeId {
+        self.push::<T>(None)
+    }
+
+    /// Add a node for a labelled or named parser of type `T`, which is shown with its label.
+    pub(crate) fn add_labelled<T: ?Sized>(&mut self, label: String) -> NodeId {
+        self.push::<T>(Some(label))
+    }
+
+    fn push<T: ?Sized>(&mut self, label: Option<String>) -> NodeId {
+        let name = core::any::type_name::<T>();
+        let name = &name[..name.find('<').unwrap_or(name.len())];
+        let name = name.rsplit("::").next().unwrap_or(name);
+        self.nodes.push(Node {
+            name: name.into(),
+            label,
+            recursive: false,
+            nullable: false,
+            open: false,
+        });
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Mark a node as able to succeed without consuming input, regardless of its children.
+    pub(crate) fn set_nullable(&mut self, id: NodeId) {
+        self.nodes[id.0].nullable = true;
+    }
+
+    pub(crate) fn edge(&mut self, from: NodeId, to: NodeId) {
+        let recursive = self.nodes[to.0].open;
+        self.edges.push((from, to, recursive));
+    }
+
+    /// Add the node of a recursive parser, identified by the address of its shared definition. If the parser has been
+    /// seen before, its existing node is returned instead of walking it again.
+    pub(crate) fn add_recursive<T: ?Sized>(
+        &mut self,
+        key: usize,
+        inner: impl FnOnce(&mut Self) -> Option<NodeId>,
+    ) -> NodeId {
+        if let Some(id) = self.recursive.get(&key) {
+            return *id;
+        }
+        let id = self.add::<T>();
+        self.recursive.insert(key, id);
+        self.nodes[id.0].recursive = true;
+        self.nodes[id.0].open = true;
+        if let Some(child) = inner(self) {
+            self.edge(id, child);
+        }
+        self.nodes[id.0].open = false;
+        id
+    }
+
+    /// Extract the rules of the grammar described by this graph, starting with the rule of the root parser (which has
+    /// no name if it is neither labelled nor recursive). Labelled and recursive parsers become rules of their own, and
+    /// are referred to by name from other rules.
+    fn rules(&self) -> Vec<(Option<String>, Expr)> {
+        let children = self.children();
+        let names = self.rule_names(&children);
+
+        let mut rules = Vec::<(Option<String>, Expr)>::new();
+        let root = (!self.nodes.is_empty()).then_some(0);
+        for id in root.into_iter().chain(1..self.nodes.len()) {
+            let name = &names[id];
+            if (id != 0 && name.is_none())
+                || (name.is_some() && rules.iter().any(|(other, _)| other == name))
+            {
+                continue;
+            }
+            let body = match name {
+                Some(_) => self.rule_body(id, &children, &names),
+                None => self.expr(id, &children, &names),
+            };
+            rules.push((name.clone(), body));
+        }
+        rules
+    }
+
+    // The children of each node, in order
+    fn children(&self) -> Vec<Vec<usize>> {
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        for (from, to, _) in &self.edges {
+            children[from.0].push(to.0);
+        }
+        children
+    }
+
+    // The names of the nodes that are rules of the grammar: labelled and recursive parsers
+    fn rule_names(&self, children: &[Vec<usize>]) -> Vec<Option<String>> {
+        // Recursive parsers that just wrap a labelled parser are named after that label, and give a name to every
+        // other recursive parser
+        let mut recursive_count = 0;
+        let mut names = vec![None; self.nodes.len()];
+        for (id, node) in self.nodes.iter().enumerate() {
+            if node.label.is_some() {
+                names[id] = node.label.clone();
+            } else if node.recursive {
+                names[id] = match children[id].first() {
+                    Some(child) if self.nodes[*child].label.is_some() => None,
+                    _ => {
+                        recursive_count += 1;
+                        Some(alloc::format!("recursive {}", recursive_count))
+                    }
+                };
+            }
+        }
+        for (id, node) in self.nodes.iter().enumerate() {
+            if let (true, Some(child)) = (node.recursive, children[id].first()) {
+                if names[id].is_none() {
+                    names[id] = names[*child].clone();
+                }
+            }
+        }
+        names
+    }
+
+    // The body of a labelled or recursive parser, which is the grammar of the parser it wraps
+    fn rule_body(&self, id: usize, children: &[Vec<usize>], names: &[Option<String>]) -> Expr {
+        match children[id].first() {
+            // A recursive parser named after the labelled parser it wraps
+            Some(child) if names[*child] == names[id] => self.rule_body(*child, children, names),
+            Some(child) => self.expr(*child, children, names),
+            None => Expr::Sequence(Vec::new()),
+        }
+    }
+
+    fn expr(&self, id: usize, children: &[Vec<usize>], names: &[Option<String>]) -> Expr {
+        if let Some(name) = &names[id] {
+            return Expr::Rule(name.clone());
+        }
+
+        let expr = |id: &usize| self.expr(*id, children, names);
+        let node = &self.nodes[id];
+        match (node.name.as_str(), children[id].as_slice()) {
+            (_, children) if node.kind(children.len()) == NodeKind::Choice => {
+                Expr::Choice(children.iter().map(expr).fold(Vec::new(), |mut all, expr| {
+                    match expr {
+                        Expr::Choice(inner) => all.extend(inner),
+                        expr => all.push(expr),
+                    }
+                    all
+                }))
+            }
+            ("OrNot" | "OrNotWithErr", [child]) => Expr::Optional(Box::new(expr(child))),
+            ("Repeated", [item]) => Expr::Repeat(Box::new(expr(item)), None),
+            ("SeparatedBy", [item, separator]) => {
+                Expr::Repeat(Box::new(expr(item)), Some(Box::new(expr(separator))))
+            }
+            ("PaddedBy", [child, padding]) => {
+                Expr::Sequence(vec![expr(padding), expr(child), expr(padding)])
+            }
+            // The grammar of a nested parser is that of the parser producing its input
+            ("NestedIn", [_, outer]) => expr(outer),
+            // Lookahead is not part of the sequence of input consumed
+            ("AndIs", [child, _]) => expr(child),
+            ("Empty", []) => Expr::Sequence(Vec::new()),
+            ("Not", _) | (_, []) => Expr::Terminal(node.name.clone()),
+            // Most parsers with one child (such as `Map`) only change the output of their child
+            (_, [child]) => expr(child),
+            (_, children) => {
+                Expr::Sequence(children.iter().map(expr).fold(Vec::new(), |mut all, expr| {
+                    match expr {
+                        Expr::Sequence(inner) => all.extend(inner),
+                        expr => all.push(expr),
+                    }
+                    all
+                }))
+            }
+        }
+    }
+}
+
+/// A problem with a grammar that stops it from parsing some inputs, as found by [`Parser::analyse`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Problem {
+    /// A recursive parser can invoke itself again without consuming any input, so it will recurse until the stack
+    /// overflows (or forever, with the `spill-stack` feature). Left recursion is only supported through
+    /// [memoised](Parser::memoised) parsers.
+    LeftRecursion {
+        /// The name of the recursive rule: its label, or a name such as `recursive 1` if it has no label.
+        rule: String,
+    },
+    /// A parser that can succeed without consuming any input is repeated, so it will repeat forever.
+    NullableRepetition {
+        /// The name of the rule that contains the repetition, or `None` if it is part of the root parser.
+        rule: Option<String>,
+        /// The type of the repeating parser, such as `Repeated`.
+        parser: String,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeftRecursion { rule } => write!(f, "rule `{}` is left-recursive", rule),
+            Self::NullableRepetition { rule, parser } => {
+                write!(f, "`{}` ", parser)?;
+                match rule {
+                    Some(rule) => write!(f, "in rule `{}`", rule)?,
+                    None => write!(f, "in the root parser")?,
+                }
+                write!(
+                    f,
+                    " repeats a parser that can succeed without consuming input"
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_dot())
+    }
+}
@@ -0,0 +1,138 @@
+//! Detection of grammars that never terminate.
+
+use super::*;
+
+impl Graph {
+    /// Find the problems with the grammar described by this graph. See [`Parser::analyse`].
+    pub(crate) fn problems(&self) -> Vec<Problem> {
+        let children = self.children();
+        let names = self.rule_names(&children);
+        let nullable = self.nullable(&children);
+        let rules = self.enclosing_rules(&children, &names);
+
+        let mut problems = Vec::new();
+        for (id, node) in self.nodes.iter().enumerate() {
+            let problem = if node.recursive && self.reaches_at_start(id, &children, &nullable) {
+                Problem::LeftRecursion {
+                    rule: names[id].clone().unwrap_or_default(),
+                }
+            } else if node.label.is_none()
+                && match (node.name.as_str(), children[id].as_slice()) {
+                    ("Repeated", [item]) => nullable[*item],
+                    ("SeparatedBy", [item, separator]) => nullable[*item] && nullable[*separator],
+                    _ => false,
+                }
+            {
+                Problem::NullableRepetition {
+                    rule: rules[id].clone(),
+                    parser: node.name.clone(),
+                }
+            } else {
+                continue;
+            };
+            // Parsers that are used more than once appear more than once in the graph
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        }
+        problems
+    }
+
+    // Whether each node can succeed without consuming any input
+    fn nullable(&self, children: &[Vec<usize>]) -> Vec<bool> {
+        let mut nullable = vec![false; self.nodes.len()];
+        loop {
+            let mut changed = false;
+            for (id, node) in self.nodes.iter().enumerate() {
+                if nullable[id] {
+                    continue;
+                }
+                let is_nullable = node.nullable
+                    || match (node.name.as_str(), children[id].as_slice()) {
+                        (_, children) if node.label.is_some() || node.recursive => {
+                            children.iter().any(|child| nullable[*child])
+                        }
+                        (
+                            "Empty" | "End" | "Padding" | "OrNot" | "OrNotWithErr" | "Rewind"
+                            | "Not",
+                            _,
+                        ) => true,
+                        (_, children) if node.kind(children.len()) == NodeKind::Choice => {
+                            children.iter().any(|child| nullable[*child])
+                        }
+                        ("NestedIn", [_, outer]) => nullable[*outer],
+                        ("SeparatedBy", [item, _]) | ("AndIs", [item, _]) => nullable[*item],
+                        // Parsers without children are assumed to consume input, unless they say otherwise
+                        (_, []) => false,
+                        (_, children) => children.iter().all(|child| nullable[*child]),
+                    };
+                if is_nullable {
+                    nullable[id] = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break nullable;
+            }
+        }
+    }
+
+    // The children that a node may invoke before any input has been consumed
+    fn starts(&self, id: usize, children: &[Vec<usize>], nullable: &[bool]) -> Vec<usize> {
+        let node = &self.nodes[id];
+        let children = &children[id];
+        match (node.name.as_str(), children.as_slice()) {
+            _ if node.label.is_some() || node.recursive => children.clone(),
+            _ if node.kind(children.len()) == NodeKind::Choice => children.clone(),
+            ("AndIs", _) => children.clone(),
+            ("NestedIn", [_, outer]) => vec![*outer],
+            // Padding comes before the parser it pads
+            ("PaddedBy", [inner, padding]) if nullable[*padding] => vec![*padding, *inner],
+            ("PaddedBy", [_, padding]) => vec![*padding],
+            // Memoised parsers stop left recursion
+            ("Memoised" | "MemoBounded", _) => Vec::new(),
+            _ => {
+                let end = children
+                    .iter()
+                    .position(|child| !nullable[*child])
+                    .map_or(children.len(), |i| i + 1);
+                children[..end].to_vec()
+            }
+        }
+    }
+
+    // Whether a node can invoke itself again before any input has been consumed
+    fn reaches_at_start(&self, target: usize, children: &[Vec<usize>], nullable: &[bool]) -> bool {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = self.starts(target, children, nullable);
+        while let Some(id) = stack.pop() {
+            if id == target {
+                return true;
+            } else if !seen[id] {
+                seen[id] = true;
+                stack.extend(self.starts(id, children, nullable));
+            }
+        }
+        false
+    }
+
+    // The name of the rule that each node is part of, which is `None` for the root rule if it has no name
+    fn enclosing_rules(
+        &self,
+        children: &[Vec<usize>],
+        names: &[Option<String>],
+    ) -> Vec<Option<String>> {
+        let mut rules = vec![None; self.nodes.len()];
+        for start in 0..self.nodes.len() {
+            if start != 0 && names[start].is_none() {
+                continue;
+            }
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                rules[id] = names[start].clone();
+                stack.extend(children[id].iter().filter(|child| names[**child].is_none()));
+            }
+        }
+        rules
+    }
+}
@@ -0,0 +1,88 @@
+//! Writing graphs as EBNF.
+
+use super::*;
+use core::fmt::Write;
+
+impl Graph {
+    /// Write the grammar described by this graph in [ISO EBNF](https://en.wikipedia.org/wiki/Extended_Backus%E2%80%93Naur_form),
+    /// with one rule per line.
+    ///
+    /// The rule of the root parser (named `root`, unless it is labelled) comes first, followed by one rule for each
+    /// labelled or recursive parser. This is a best-effort description of the grammar: parsers without any children,
+    /// such as [`just`] or [`custom`], are opaque, and are written as special sequences (`? Just ?`) named after their
+    /// type. Repetitions are always written as zero or more repetitions, and parsers that only change the output of
+    /// another parser (such as [`Parser::map`]) are written as the parser they wrap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let list = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// assert_eq!(
+    ///     list.graph().to_ebnf(),
+    ///     "root = ? Just ? , [ ? Int ? , { ? Just ? , ? Int ? } ] , ? Just ? ;\n",
+    /// );
+    /// ```
+    pub fn to_ebnf(&self) -> String {
+        let mut ebnf = String::new();
+        for (name, expr) in self.rules() {
+            ebnf.push_str(name.as_deref().unwrap_or("root"));
+            ebnf.push_str(" = ");
+            expr.write_ebnf(&mut ebnf, false);
+            ebnf.push_str(" ;\n");
+        }
+        ebnf
+    }
+}
+
+impl Expr {
+    // Choices within sequences must be grouped, because they bind more loosely
+    fn write_ebnf(&self, ebnf: &mut String, in_sequence: bool) {
+        let write_all = |ebnf: &mut String, items: &[Expr], separator: &str, in_sequence: bool| {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    ebnf.push_str(separator);
+                }
+                item.write_ebnf(ebnf, in_sequence);
+            }
+        };
+
+        match self {
+            Self::Terminal(name) => {
+                let _ = write!(ebnf, "? {} ?", name);
+            }
+            Self::Rule(name) => ebnf.push_str(name),
+            Self::Sequence(items) if items.is_empty() => ebnf.push_str("( )"),
+            Self::Sequence(items) => write_all(ebnf, items, " , ", true),
+            Self::Choice(items) if in_sequence => {
+                ebnf.push_str("( ");
+                write_all(ebnf, items, " | ", false);
+                ebnf.push_str(" )");
+            }
+            Self::Choice(items) => write_all(ebnf, items, " | ", false),
+            Self::Optional(item) => {
+                ebnf.push_str("[ ");
+                item.write_ebnf(ebnf, false);
+                ebnf.push_str(" ]");
+            }
+            Self::Repeat(item, None) => {
+                ebnf.push_str("{ ");
+                item.write_ebnf(ebnf, false);
+                ebnf.push_str(" }");
+            }
+            Self::Repeat(item, Some(separator)) => {
+                ebnf.push_str("[ ");
+                item.write_ebnf(ebnf, true);
+                ebnf.push_str(" , { ");
+                separator.write_ebnf(ebnf, true);
+                ebnf.push_str(" , ");
+                item.write_ebnf(ebnf, true);
+                ebnf.push_str(" } ]");
+            }
+        }
+    }
+}
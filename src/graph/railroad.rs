@@ -0,0 +1,271 @@
+//! Rendering of graphs as railroad diagrams, in SVG.
+
+use super::*;
+use core::fmt::Write;
+
+// Layout, in pixels
+const CHAR_WIDTH: usize = 8;
+const BOX_HEIGHT: usize = 22;
+const BOX_PADDING: usize = 8;
+const GUTTER: usize = 10;
+const GAP: usize = 10;
+const TITLE_HEIGHT: usize = 28;
+const MARGIN: usize = 20;
+
+const STYLE: &str = "path{fill:none;stroke:#000;stroke-width:1.5}\
+    rect{fill:#fff;stroke:#000;stroke-width:1.5}\
+    text{font:13px monospace;text-anchor:middle}\
+    text.rule{font-weight:bold;text-anchor:start}";
+
+impl Graph {
+    /// Render the grammar described by this graph as [railroad diagrams](https://en.wikipedia.org/wiki/Syntax_diagram)
+    /// in an SVG image.
+    ///
+    /// The diagram of the root parser comes first, followed by one diagram for each labelled or recursive parser,
+    /// which are drawn as references (square boxes) wherever they are used. Parsers without any children, such as
+    /// [`just`], are drawn as terminals (rounded boxes) named after their type, so labelling parsers gives more
+    /// readable diagrams.
+    ///
+    /// Repetitions are always drawn as zero or more repetitions, and parsers that only change the output of another
+    /// parser (such as [`Parser::map`]) are drawn as the parser they wrap.
+    ///
+    /// *This method requires the `railroad` feature.*
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let list = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// let svg = list.graph().to_railroad_svg();
+    /// assert!(svg.starts_with("<svg"));
+    /// assert!(svg.contains(">Int</text>"));
+    /// ```
+    pub fn to_railroad_svg(&self) -> String {
+        let mut body = String::new();
+        let (mut width, mut height) = (0, 0);
+        for (name, expr) in self.rules() {
+            if let Some(name) = name {
+                let _ = write!(
+                    body,
+                    "<text class=\"rule\" x=\"{}\" y=\"{}\">{}</text>",
+                    MARGIN,
+                    height + TITLE_HEIGHT - 8,
+                    escape(&name),
+                );
+            }
+            height += TITLE_HEIGHT;
+
+            // Rules start and end with a short vertical bar
+            let (w, up, down) = expr.size();
+            let y = height + up.max(BOX_HEIGHT / 2);
+            let _ = write!(
+                body,
+                "<path d=\"M{x} {top}v14M{x} {y}h{g}\"/>",
+                x = MARGIN,
+                top = y - 7,
+                y = y,
+                g = GUTTER,
+            );
+            expr.draw(&mut body, MARGIN + GUTTER, y);
+            let end = MARGIN + GUTTER + w;
+            let _ = write!(
+                body,
+                "<path d=\"M{x} {y}h{g}v-7v14\"/>",
+                x = end,
+                y = y,
+                g = GUTTER,
+            );
+
+            width = width.max(end + GUTTER + MARGIN);
+            height = y + down.max(BOX_HEIGHT / 2) + MARGIN;
+        }
+
+        alloc::format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\
+            <style>{}</style>{}</svg>",
+            STYLE,
+            body,
+            w = width,
+            h = height,
+        )
+    }
+}
+
+impl Expr {
+    // The width of this expression, and its height above and below the line that passes through it
+    fn size(&self) -> (usize, usize, usize) {
+        match self {
+            Self::Terminal(name) | Self::Rule(name) => {
+                (box_width(name) + 2 * GUTTER, BOX_HEIGHT / 2, BOX_HEIGHT / 2)
+            }
+            Self::Sequence(items) => items
+                .iter()
+                .map(Self::size)
+                .fold((0, 0, 0), |(w, up, down), item| {
+                    (w + item.0, up.max(item.1), down.max(item.2))
+                }),
+            Self::Choice(items) => {
+                let sizes = items.iter().map(Self::size).collect::<Vec<_>>();
+                let (_, up, down) = sizes.first().copied().unwrap_or_default();
+                let width = sizes.iter().map(|(w, _, _)| *w).max().unwrap_or_default();
+                let below = sizes
+                    .iter()
+                    .skip(1)
+                    .map(|(_, up, down)| GAP + up + down)
+                    .sum::<usize>();
+                (width + 4 * GUTTER, up, down + below)
+            }
+            // An optional expression is drawn with a path that skips it, above it
+            Self::Optional(item) => {
+                let (w, up, down) = item.size();
+                (w + 4 * GUTTER, up + GAP, down)
+            }
+            Self::Repeat(item, separator) => {
+                let (w, up, down) = item.size();
+                let (sep_w, sep_up, sep_down) =
+                    separator.as_ref().map_or((0, 0, 0), |sep| sep.size());
+                (
+                    w.max(sep_w) + 4 * GUTTER,
+                    up,
+                    down + GAP + sep_up + sep_down,
+                )
+            }
+        }
+    }
+
+    // Draw this expression, starting at the given point on the line that passes through it
+    fn draw(&self, svg: &mut String, x: usize, y: usize) {
+        match self {
+            Self::Terminal(name) | Self::Rule(name) => {
+                let w = box_width(name);
+                let radius = if matches!(self, Self::Terminal(_)) {
+                    BOX_HEIGHT / 2
+                } else {
+                    0
+                };
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{x} {y}h{g}m{w} 0h{g}\"/>\
+                    <rect x=\"{bx}\" y=\"{by}\" width=\"{w}\" height=\"{h}\" rx=\"{r}\"/>\
+                    <text x=\"{tx}\" y=\"{ty}\">{name}</text>",
+                    x = x,
+                    y = y,
+                    g = GUTTER,
+                    w = w,
+                    bx = x + GUTTER,
+                    by = y - BOX_HEIGHT / 2,
+                    h = BOX_HEIGHT,
+                    r = radius,
+                    tx = x + GUTTER + w / 2,
+                    ty = y + 4,
+                    name = escape(name),
+                );
+            }
+            Self::Sequence(items) => {
+                let mut x = x;
+                for item in items {
+                    item.draw(svg, x, y);
+                    x += item.size().0;
+                }
+            }
+            Self::Choice(items) => {
+                let (w, _, _) = self.size();
+                let (left, right) = (x + GUTTER, x + w - GUTTER);
+                let mut item_y = y;
+                let mut prev_down = 0;
+                for (i, item) in items.iter().enumerate() {
+                    let (item_w, up, down) = item.size();
+                    if i > 0 {
+                        item_y += prev_down + GAP + up;
+                    }
+                    prev_down = down;
+                    let _ = write!(
+                        svg,
+                        "<path d=\"M{left} {y}V{iy}h{g}M{end} {iy}H{right}V{y}\"/>",
+                        left = left,
+                        right = right,
+                        y = y,
+                        iy = item_y,
+                        g = GUTTER,
+                        end = left + GUTTER + item_w,
+                    );
+                    item.draw(svg, left + GUTTER, item_y);
+                }
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{} {y}h{g}M{} {y}h{g}\"/>",
+                    x,
+                    right,
+                    y = y,
+                    g = GUTTER
+                );
+            }
+            Self::Optional(item) => {
+                let (w, _, _) = self.size();
+                let (item_w, up, _) = item.size();
+                let (left, right) = (x + GUTTER, x + w - GUTTER);
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{x} {y}H{end}M{left} {y}V{sy}H{right}V{y}M{item_end} {y}H{w_end}\"/>",
+                    x = x,
+                    y = y,
+                    end = left + GUTTER,
+                    left = left,
+                    right = right,
+                    sy = y - up - GAP,
+                    item_end = left + GUTTER + item_w,
+                    w_end = x + w,
+                );
+                item.draw(svg, left + GUTTER, y);
+            }
+            Self::Repeat(item, separator) => {
+                let (w, _, _) = self.size();
+                let (item_w, _, down) = item.size();
+                let (left, right) = (x + GUTTER, x + w - GUTTER);
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{x} {y}H{start}M{item_end} {y}H{w_end}\"/>",
+                    x = x,
+                    y = y,
+                    start = left + GUTTER,
+                    item_end = left + GUTTER + item_w,
+                    w_end = x + w,
+                );
+                item.draw(svg, left + GUTTER, y);
+
+                // The path back to the start of the repetition passes through the separator, if there is one
+                let (sep_w, sep_up, _) = separator.as_ref().map_or((0, 0, 0), |sep| sep.size());
+                let loop_y = y + down + GAP + sep_up;
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{right} {y}V{ly}H{sep_end}M{sep_start} {ly}H{left}V{y}\"/>",
+                    right = right,
+                    y = y,
+                    ly = loop_y,
+                    sep_end = left + GUTTER + sep_w,
+                    sep_start = left + GUTTER,
+                    left = left,
+                );
+                if let Some(separator) = separator {
+                    separator.draw(svg, left + GUTTER, loop_y);
+                }
+            }
+        }
+    }
+}
+
+// The width of the box containing the given text, excluding the paths on either side
+fn box_width(text: &str) -> usize {
+    text.chars().count() * CHAR_WIDTH + 2 * BOX_PADDING
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,158 @@
+//! Writing graphs as tree-sitter grammars.
+
+use super::*;
+use core::fmt::Write;
+
+impl Graph {
+    /// Begin exporting the grammar described by this graph as a [tree-sitter](https://tree-sitter.github.io/) grammar
+    /// with the given name, for use by editors that highlight code incrementally with tree-sitter.
+    ///
+    /// Each labelled, named or recursive parser becomes a rule of the grammar, with its label converted to a valid rule
+    /// name (so `binary op` becomes `binary_op`). The root parser comes first, as the start rule of the grammar, and is
+    /// called `source_file` unless it is labelled or named.
+    ///
+    /// This is a best-effort translation, like [`Graph::to_ebnf`]. Parsers without any children, such as [`just`] or
+    /// [`text::ident`], are opaque, so the tokens they match can't be exported. They are written as `blank()`,
+    /// followed by a comment naming their type, until they are given definitions with [`TreeSitter::rule`] or
+    /// [`TreeSitter::terminal`]. Whitespace added by [`Parser::padded`] is left out, since tree-sitter skips whitespace
+    /// between tokens by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let list = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+    ///     .separated_by(just(',').named("comma"))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let grammar = list
+    ///     .graph()
+    ///     .to_tree_sitter("list")
+    ///     .rule("comma", "','")
+    ///     .terminal("Int", "/\\d+/")
+    ///     .to_grammar_js();
+    ///
+    /// assert_eq!(grammar, "module.exports = grammar({
+    ///   name: 'list',
+    ///
+    ///   rules: {
+    ///     source_file: $ => optional(seq(/\\d+/, repeat(seq($.comma, /\\d+/)))),
+    ///     comma: $ => ',',
+    ///   },
+    /// });
+    /// ");
+    /// ```
+    pub fn to_tree_sitter(&self, name: &str) -> TreeSitter<'_> {
+        TreeSitter {
+            graph: self,
+            name: name.into(),
+            rules: Vec::new(),
+            terminals: Vec::new(),
+        }
+    }
+}
+
+/// A tree-sitter grammar, exported from a [`Graph`]. See [`Graph::to_tree_sitter`].
+///
+/// Definitions are written in the JavaScript DSL of tree-sitter's `grammar.js`, such as `/[a-z]+/` or
+/// `seq('(', $.expr, ')')`, and are copied into the grammar verbatim.
+pub struct TreeSitter<'a> {
+    graph: &'a Graph,
+    name: String,
+    rules: Vec<(String, String)>,
+    terminals: Vec<(String, String)>,
+}
+
+impl TreeSitter<'_> {
+    /// Define the rule of a labelled or named parser (or `source_file`, for an unlabelled root parser), replacing the
+    /// definition that would be derived from the parser.
+    ///
+    /// This is the way to export the tokens matched by opaque parsers: name them, and give their names definitions.
+    pub fn rule(mut self, label: &str, definition: &str) -> Self {
+        self.rules.push((label.into(), definition.into()));
+        self
+    }
+
+    /// Define every opaque parser of the given type (such as `Ident`) that is not within a rule defined by
+    /// [`TreeSitter::rule`].
+    pub fn terminal(mut self, name: &str, definition: &str) -> Self {
+        self.terminals.push((name.into(), definition.into()));
+        self
+    }
+
+    /// Write the grammar as the contents of a tree-sitter `grammar.js` file.
+    pub fn to_grammar_js(&self) -> String {
+        let mut js = String::from("module.exports = grammar({\n");
+        let _ = writeln!(js, "  name: '{}',\n", escape(&self.name));
+        js.push_str("  rules: {\n");
+        for (name, expr) in self.graph.rules() {
+            let name = name.as_deref().unwrap_or("source_file");
+            let _ = write!(js, "    {}: $ => ", rule_name(name));
+            match self.rules.iter().find(|(rule, _)| rule == name) {
+                Some((_, definition)) => js.push_str(definition),
+                None => self.write_expr(&mut js, &expr),
+            }
+            js.push_str(",\n");
+        }
+        js.push_str("  },\n});\n");
+        js
+    }
+
+    fn write_expr(&self, js: &mut String, expr: &Expr) {
+        let write_call = |js: &mut String, function: &str, items: &[&Expr]| {
+            let _ = write!(js, "{}(", function);
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    js.push_str(", ");
+                }
+                self.write_expr(js, item);
+            }
+            js.push(')');
+        };
+
+        match expr {
+            Expr::Terminal(name) => {
+                match self.terminals.iter().find(|(terminal, _)| terminal == name) {
+                    Some((_, definition)) => js.push_str(definition),
+                    None => {
+                        let _ = write!(js, "blank() /* {} */", name);
+                    }
+                }
+            }
+            Expr::Rule(name) => {
+                let _ = write!(js, "$.{}", rule_name(name));
+            }
+            Expr::Sequence(items) if items.is_empty() => js.push_str("blank()"),
+            Expr::Sequence(items) => write_call(js, "seq", &items.iter().collect::<Vec<_>>()),
+            Expr::Choice(items) => write_call(js, "choice", &items.iter().collect::<Vec<_>>()),
+            Expr::Optional(item) => write_call(js, "optional", &[item]),
+            Expr::Repeat(item, None) => write_call(js, "repeat", &[item]),
+            // `item (separator item)*`, possibly empty
+            Expr::Repeat(item, Some(separator)) => {
+                js.push_str("optional(seq(");
+                self.write_expr(js, item);
+                js.push_str(", repeat(seq(");
+                self.write_expr(js, separator);
+                js.push_str(", ");
+                self.write_expr(js, item);
+                js.push_str("))))");
+            }
+        }
+    }
+}
+
+// Rule names must be identifiers, so other characters (such as the spaces in `recursive 1`) become underscores
+fn rule_name(label: &str) -> String {
+    let mut name = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
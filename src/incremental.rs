@@ -0,0 +1,217 @@
+//! Incremental reparsing of inputs made up of independent items, for editors and language servers.
+//!
+//! Editors usually change a small part of a large input at a time, but reparsing the whole input after every
+//! keystroke can take longer than the few milliseconds a language server has to respond. Many inputs are a sequence
+//! of top-level items (functions, declarations, records, and so on) that can be parsed without knowing what comes
+//! before or after them. [`Incremental`] parses such an input one item at a time and remembers where each item began
+//! and ended. When the input is edited, [`Incremental::edit`] reparses only the items touched by the edit, then
+//! reuses the items that follow it as soon as the parser lines up with one of them again.
+//!
+//! For this to give the same results as parsing the whole input, the item parser must not depend on any input
+//! outside of the item itself (including through lookahead or state). Trivia between items, such as whitespace and
+//! comments, should be parsed as part of the item before or after it.
+//!
+//! Spans are offsets into the whole input (byte offsets for `&str`, indices for `&[T]`). Reused items are not
+//! reparsed, so any spans *within* their outputs keep the offsets they had when they were first parsed: outputs that
+//! need up-to-date absolute spans should store them relative to the start of the item, and combine them with the
+//! span given by [`Incremental::items`]. Items that produced errors are always reparsed, so errors are never stale.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, incremental::Incremental};
+//! let old = "x = 1\ny = 2\nz = 3\n";
+//! let new = "x = 1\ny = 42\nz = 3\n";
+//!
+//! let item = text::ident::<_, _, extra::Err<Simple<char>>>()
+//!     .then_ignore(just(" = "))
+//!     .then(text::int(10).from_str::<u32>().unwrapped())
+//!     .then_ignore(text::newline());
+//!
+//! let mut doc = Incremental::parse(&item, old);
+//! assert_eq!(doc.outputs().collect::<Vec<_>>(), [&("x", 1), &("y", 2), &("z", 3)]);
+//!
+//! // Replace the `2` at offset 10 with `42`
+//! doc.edit(&item, new, 10..11, 2);
+//! assert_eq!(doc.outputs().collect::<Vec<_>>(), [&("x", 1), &("y", 42), &("z", 3)]);
+//! assert_eq!(doc.items().map(|(span, _)| span).collect::<Vec<_>>(), [0..6, 6..13, 13..19]);
+//! ```
+
+use super::*;
+use error::Error;
+
+struct Item<O, Err> {
+    span: Range<usize>,
+    output: Option<O>,
+    errs: Vec<Err>,
+}
+
+/// The result of parsing an input one item at a time, which can be updated after the input is edited. See the
+/// [module documentation](self) for more information.
+pub struct Incremental<O, Err> {
+    // The items of the input, in order. Only the last item may have failed to produce an output, in which case the
+    // rest of the input was left unparsed.
+    items: Vec<Item<O, Err>>,
+}
+
+impl<O, Err> Incremental<O, Err> {
+    /// Parse an input as a sequence of items, each parsed by the given parser.
+    ///
+    /// Parsing stops early if an item fails to produce an output, or consumes no input.
+    pub fn parse<'a, I, E, P>(parser: &P, input: I) -> Self
+    where
+        I: Input<'a, Offset = usize> + Clone,
+        E: ParserExtra<'a, I, Error = Err>,
+        Err: Error<'a, I>,
+        E::State: Default,
+        E::Context: Default,
+        P: Parser<'a, I, O, E>,
+    {
+        let mut this = Self { items: Vec::new() };
+        this.resume(parser, input, 0, Vec::new());
+        this
+    }
+
+    /// Update the items after an edit that replaced the given range of the old input with `new_len` offsets' worth of
+    /// new input, resulting in `input`.
+    ///
+    /// Items that end before the edit are kept as they are, and items that begin after it are reused (with their spans
+    /// moved to account for the edit) once an item reparsed from the new input ends where one of them begins. Every
+    /// other item is parsed again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is reversed.
+    pub fn edit<'a, I, E, P>(&mut self, parser: &P, input: I, range: Range<usize>, new_len: usize)
+    where
+        I: Input<'a, Offset = usize> + Clone,
+        E: ParserExtra<'a, I, Error = Err>,
+        Err: Error<'a, I>,
+        E::State: Default,
+        E::Context: Default,
+        P: Parser<'a, I, O, E>,
+    {
+        assert!(range.start <= range.end, "edit range is reversed");
+
+        // An item that ends where the edit begins might continue into the new input, so it is parsed again too. An item
+        // that failed is always parsed again, since the input after it was never parsed.
+        let keep = self
+            .items
+            .iter()
+            .position(|item| item.span.end >= range.start || item.output.is_none())
+            .unwrap_or(self.items.len());
+        let start = self.items[..keep].last().map_or(0, |item| item.span.end);
+        let old = self
+            .items
+            .split_off(keep)
+            .into_iter()
+            // Only items that were parsed successfully without errors, and that lie entirely after the edit, can be
+            // reused
+            .filter(|item| {
+                item.span.start >= range.end && item.output.is_some() && item.errs.is_empty()
+            })
+            .map(|item| Item {
+                span: item.span.start - range.len() + new_len
+                    ..item.span.end - range.len() + new_len,
+                ..item
+            })
+            .collect();
+        self.resume(parser, input, start, old);
+    }
+
+    // Parse items from the given offset to the end of the input, reusing old items whenever one begins where the
+    // previous item ended
+    fn resume<'a, I, E, P>(
+        &mut self,
+        parser: &P,
+        input: I,
+        mut offset: usize,
+        old: Vec<Item<O, Err>>,
+    ) where
+        I: Input<'a, Offset = usize> + Clone,
+        E: ParserExtra<'a, I, Error = Err>,
+        Err: Error<'a, I>,
+        E::State: Default,
+        E::Context: Default,
+        P: Parser<'a, I, O, E>,
+    {
+        let mut old = old.into_iter().peekable();
+        loop {
+            while old.next_if(|item| item.span.start < offset).is_some() {}
+            if let Some(item) = old.next_if(|item| item.span.start == offset) {
+                offset = item.span.end;
+                self.items.push(item);
+                continue;
+            }
+
+            let mut own = InputOwn::<I, E>::new(input.clone());
+            let mut inp = own.as_ref_at(offset);
+            if inp.peek_maybe().is_none() {
+                break;
+            }
+            let res = parser.go::<Emit>(&mut inp);
+            let end = inp.offset;
+            let (res, alt) = match res {
+                Ok(_) if end == offset => {
+                    // An item that consumes no input would be parsed forever, so treat it as unexpected input
+                    let found = inp.peek_maybe();
+                    let span = inp.span_since(inp.offset());
+                    (None, Some(Err::expected_found(None, found, span)))
+                }
+                Ok(out) => (Some(out), None),
+                Err(()) => (None, inp.errors.alt.take().map(|alt| alt.err)),
+            };
+            let mut errs = own.into_errs();
+            errs.extend(alt);
+            let failed = res.is_none();
+            self.items.push(Item {
+                span: offset..end,
+                output: res,
+                errs,
+            });
+            if failed {
+                break;
+            }
+            offset = end;
+        }
+    }
+
+    /// Get the span and output of each item that was parsed successfully, in order.
+    pub fn items(&self) -> impl Iterator<Item = (Range<usize>, &O)> + '_ {
+        self.items
+            .iter()
+            .filter_map(|item| Some((item.span.clone(), item.output.as_ref()?)))
+    }
+
+    /// Get the output of each item that was parsed successfully, in order.
+    pub fn outputs(&self) -> impl Iterator<Item = &O> + '_ {
+        self.items.iter().filter_map(|item| item.output.as_ref())
+    }
+
+    /// Get the errors produced while parsing each item, in order.
+    pub fn errors(&self) -> impl Iterator<Item = &Err> + '_ {
+        self.items.iter().flat_map(|item| &item.errs)
+    }
+
+    /// Returns true if parsing any item produced errors.
+    pub fn has_errors(&self) -> bool {
+        self.items.iter().any(|item| !item.errs.is_empty())
+    }
+
+    /// Returns true if every item produced an output, such that the whole input was parsed.
+    pub fn has_output(&self) -> bool {
+        self.items.iter().all(|item| item.output.is_some())
+    }
+}
+
+impl<O: fmt::Debug, Err: fmt::Debug> fmt::Debug for Incremental<O, Err> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.items
+                    .iter()
+                    .map(|item| (&item.span, &item.output, &item.errs)),
+            )
+            .finish()
+    }
+}
@@ -5,10 +5,13 @@
 //! [`Input`] is the primary trait used to feed input data into a chumsky parser. You can create them in a number of
 //! ways: from strings, slices, arrays, etc.
 
+#[cfg(feature = "alloc")]
 pub use crate::stream::{BoxedExactSizeStream, BoxedStream, Stream};
 
 use super::*;
 #[cfg(feature = "memoization")]
+use alloc::collections::VecDeque;
+#[cfg(feature = "memoization")]
 use hashbrown::HashMap;
 
 /// A trait for types that represents a stream of input tokens. Unlike [`Iterator`], this type
@@ -105,6 +108,47 @@ pub trait Input<'a>: Sealed + 'a {
             context,
         }
     }
+
+    /// Skip a byte order mark (BOM) at the start of this input, if there is one.
+    ///
+    /// Files written by Windows tooling often begin with a BOM, which would otherwise be seen by the parser as the
+    /// first character of the input and cause grammars that start with [`just`] to fail. A UTF-8 BOM (`U+FEFF`) is
+    /// skipped on both [`&str`] and [`&[u8]`] inputs, and the UTF-16 BOMs (the bytes `FE FF` and `FF FE`) are skipped
+    /// on [`&[u8]`] inputs. To match a BOM explicitly instead, use [`text::bom`].
+    ///
+    /// The BOM is skipped without affecting the offsets of the rest of the input, so spans still refer to positions in
+    /// the original input (i.e: the first character after a UTF-8 BOM in a [`&str`] has a span starting at `3`).
+    ///
+    /// Note that the resulting input has a different type to the original, so a parser that should accept both must
+    /// be generic over its input type (such as any `I: StrInput<'a, char>`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let key = just::<_, _, extra::Err<Rich<char>>>("key")
+    ///     .map_with_span(|key, span| (key, span));
+    ///
+    /// // Spans are still relative to the original input
+    /// assert_eq!(
+    ///     key.parse("\u{feff}key".skip_bom()).into_result(),
+    ///     Ok(("key", SimpleSpan::from(3..6))),
+    /// );
+    /// // Inputs without a BOM are unaffected
+    /// assert_eq!(key.parse("key".skip_bom()).into_result(), Ok(("key", SimpleSpan::from(0..3))));
+    /// ```
+    fn skip_bom<C>(self) -> SkipBom<Self>
+    where
+        Self: StrInput<'a, C> + Sized,
+        C: Char,
+    {
+        let start = self.start();
+        let bom = text::bom_len(C::str_as_bytes(self.slice_from(start..)));
+        SkipBom {
+            input: self,
+            start: start + bom,
+        }
+    }
 }
 
 /// Implement by inputs that have a known size (including spans)
@@ -159,7 +203,7 @@ pub trait BorrowInput<'a>: Input<'a> {
     unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>);
 }
 
-impl<'a> Sealed for &'a str {}
+impl Sealed for &str {}
 impl<'a> Input<'a> for &'a str {
     type Offset = usize;
     type Token = char;
@@ -230,7 +274,7 @@ impl<'a> SliceInput<'a> for &'a str {
     }
 }
 
-impl<'a, T> Sealed for &'a [T] {}
+impl<T> Sealed for &[T] {}
 impl<'a, T> Input<'a> for &'a [T] {
     type Offset = usize;
     type Token = T;
@@ -613,12 +657,96 @@ where
 {
 }
 
+/// An input wrapper that skips a leading byte order mark, if there is one. See [`Input::skip_bom`].
+#[derive(Copy, Clone)]
+pub struct SkipBom<I> {
+    input: I,
+    start: usize,
+}
+
+impl<I> Sealed for SkipBom<I> {}
+impl<'a, I: Input<'a, Offset = usize>> Input<'a> for SkipBom<I> {
+    type Offset = usize;
+    type Token = I::Token;
+    type Span = I::Span;
+
+    #[inline(always)]
+    fn start(&self) -> Self::Offset {
+        self.start
+    }
+
+    type TokenMaybe = I::TokenMaybe;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.input.next_maybe(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        self.input.span(range)
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        I::prev(offs)
+    }
+}
+
+impl<'a, I: ExactSizeInput<'a, Offset = usize>> ExactSizeInput<'a> for SkipBom<I> {
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        self.input.span_from(range)
+    }
+}
+
+impl<'a, I: ValueInput<'a, Offset = usize>> ValueInput<'a> for SkipBom<I> {
+    #[inline(always)]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        self.input.next(offset)
+    }
+}
+
+impl<'a, I: BorrowInput<'a, Offset = usize>> BorrowInput<'a> for SkipBom<I> {
+    #[inline(always)]
+    unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>) {
+        self.input.next_ref(offset)
+    }
+}
+
+impl<'a, I: SliceInput<'a, Offset = usize>> SliceInput<'a> for SkipBom<I> {
+    type Slice = I::Slice;
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice(&self.input, range)
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice_from(&self.input, from)
+    }
+}
+
+impl<'a, C, I> StrInput<'a, C> for SkipBom<I>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+{
+}
+
 /// Represents a location in an input that can be rewound to.
 ///
 /// Markers can be created with [`InputRef::save`] and rewound to with [`InputRef::rewind`].
 pub struct Marker<'a, 'parse, I: Input<'a>> {
     pub(crate) offset: I::Offset,
     pub(crate) err_count: usize,
+    #[cfg(feature = "cst")]
+    pub(crate) cst_count: usize,
+    #[cfg(feature = "semantic")]
+    pub(crate) semantic_count: usize,
+    #[cfg(feature = "metadata")]
+    pub(crate) metadata_count: usize,
     phantom: PhantomData<fn(&'parse ()) -> &'parse ()>, // Invariance
 }
 
@@ -664,7 +792,7 @@ impl<'a, 'parse, I: Input<'a>> PartialEq for Offset<'a, 'parse, I> {
 
 pub(crate) struct Errors<T, E> {
     pub(crate) alt: Option<Located<T, E>>,
-    pub(crate) secondary: Vec<Located<T, E>>,
+    pub(crate) secondary: ErrorVec<Located<T, E>>,
 }
 
 impl<T, E> Errors<T, E> {
@@ -679,7 +807,187 @@ impl<T, E> Default for Errors<T, E> {
     fn default() -> Self {
         Self {
             alt: None,
-            secondary: Vec::new(),
+            secondary: ErrorVec::new(),
+        }
+    }
+}
+
+/// The key of a memoised result: the offset, the address of the memoised parser, and the generation of its context.
+#[cfg(feature = "memoization")]
+pub(crate) type MemoKey<T> = (T, usize, usize);
+
+/// Internal type holding the results of memoised parsers, evicting them according to a [`MemoBound`].
+///
+/// Entries that are `None` belong to memoised parsers that are still running, and are never evicted, since they are
+/// needed to detect left recursion.
+#[cfg(feature = "memoization")]
+pub(crate) struct MemoTable<T, E> {
+    entries: HashMap<MemoKey<T>, (Option<Located<T, E>>, u64)>,
+    // Keys in order of use, paired with the stamp of that use. Pairs whose stamp no longer matches the entry are stale.
+    order: VecDeque<(MemoKey<T>, u64)>,
+    stamp: u64,
+    swept: usize,
+    // The last generation given to a context introduced by `with_ctx`
+    generation: usize,
+    // The generations of contexts that live for the whole parse (such as those of `Parser::with_ctx`), by address
+    shared: HashMap<usize, usize>,
+    // The keys inserted within each scoped generation, which are removed when its scope ends
+    scoped: HashMap<usize, Vec<MemoKey<T>>>,
+    stats: MemoStats,
+    pub(crate) bound: MemoBound,
+}
+
+#[cfg(feature = "memoization")]
+impl<T: Copy + Hash + Eq + Into<usize>, E> MemoTable<T, E> {
+    pub(crate) fn new(bound: MemoBound) -> Self {
+        Self {
+            entries: HashMap::default(),
+            order: VecDeque::new(),
+            stamp: 0,
+            swept: 0,
+            generation: 0,
+            shared: HashMap::default(),
+            scoped: HashMap::default(),
+            stats: MemoStats::default(),
+            bound,
+        }
+    }
+
+    pub(crate) fn stats(&self) -> MemoStats {
+        MemoStats {
+            entries: self.entries.len(),
+            memory: self.entries.capacity()
+                * core::mem::size_of::<(MemoKey<T>, (Option<Located<T, E>>, u64))>()
+                + self.order.capacity() * core::mem::size_of::<(MemoKey<T>, u64)>(),
+            ..self.stats
+        }
+    }
+
+    /// Add the statistics of a table used for a nested parse to this one.
+    pub(crate) fn merge_stats<U: Copy + Hash + Eq + Into<usize>, F>(
+        &mut self,
+        other: &MemoTable<U, F>,
+    ) {
+        let other = other.stats();
+        self.stats.hits += other.hits;
+        self.stats.misses += other.misses;
+        self.stats.evictions += other.evictions;
+        self.stats.peak_entries = self.stats.peak_entries.max(other.peak_entries);
+    }
+
+    /// Create a new context generation, distinguishing the results of memoised parsers within a context.
+    pub(crate) fn new_generation(&mut self) -> usize {
+        self.generation += 1;
+        self.generation
+    }
+
+    /// Get the generation of a context that lives for the whole parse, given its address. Each use of the context
+    /// shares the same generation, and so the same memoised results.
+    pub(crate) fn shared_generation(&mut self, addr: usize) -> usize {
+        match self.shared.get(&addr) {
+            Some(generation) => *generation,
+            None => {
+                let generation = self.new_generation();
+                self.shared.insert(addr, generation);
+                generation
+            }
+        }
+    }
+
+    /// Create a new generation for a context that only lives until [`MemoTable::end_scope`] is called.
+    pub(crate) fn begin_scope(&mut self) -> usize {
+        let generation = self.new_generation();
+        self.scoped.insert(generation, Vec::new());
+        generation
+    }
+
+    /// Remove the results memoised within a scoped generation, which can never be used again.
+    pub(crate) fn end_scope(&mut self, generation: usize) {
+        for key in self.scoped.remove(&generation).into_iter().flatten() {
+            self.entries.remove(&key);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn get(&mut self, key: &MemoKey<T>) -> Option<&Option<Located<T, E>>> {
+        if self.entries.contains_key(key) {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        if let MemoBound::Lru(_) = self.bound {
+            if let Some((_, stamp)) = self.entries.get_mut(key) {
+                self.stamp += 1;
+                *stamp = self.stamp;
+                self.order.push_back((*key, self.stamp));
+                self.compact();
+            }
+        }
+        self.entries.get(key).map(|(res, _)| res)
+    }
+
+    pub(crate) fn insert(&mut self, key: MemoKey<T>, res: Option<Located<T, E>>) {
+        self.stamp += 1;
+        if self.entries.insert(key, (res, self.stamp)).is_none() {
+            if let Some(keys) = self.scoped.get_mut(&key.2) {
+                keys.push(key);
+            }
+        }
+        self.stats.peak_entries = self.stats.peak_entries.max(self.entries.len());
+        match self.bound {
+            MemoBound::Unbounded => {}
+            MemoBound::Lru(capacity) => {
+                self.order.push_back((key, self.stamp));
+                self.evict_lru(capacity);
+            }
+            MemoBound::Window(window) => {
+                let offset = key.0.into();
+                if offset >= self.swept + window.max(1) {
+                    self.swept = offset;
+                    let before = self.entries.len();
+                    self.entries
+                        .retain(|key, (res, _)| res.is_none() || key.0.into() + window >= offset);
+                    self.stats.evictions += (before - self.entries.len()) as u64;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &MemoKey<T>) {
+        self.entries.remove(key);
+    }
+
+    fn evict_lru(&mut self, capacity: usize) {
+        let mut running = 0;
+        while self.entries.len() > capacity && running < self.entries.len() {
+            let Some((key, stamp)) = self.order.pop_front() else {
+                break;
+            };
+            match self.entries.get(&key) {
+                Some((None, s)) if *s == stamp => {
+                    running += 1;
+                    self.order.push_back((key, stamp));
+                }
+                Some((Some(_), s)) if *s == stamp => {
+                    self.entries.remove(&key);
+                    self.stats.evictions += 1;
+                }
+                _ => {}
+            }
+        }
+        self.compact();
+    }
+
+    // Remove stale keys from the use order, so that it doesn't grow without bound when entries are used repeatedly.
+    fn compact(&mut self) {
+        if self.order.len() > self.entries.len() * 2 + 64 {
+            let entries = &self.entries;
+            self.order
+                .retain(|(key, stamp)| entries.get(key).map_or(false, |(_, s)| s == stamp));
         }
     }
 }
@@ -692,7 +1000,22 @@ pub(crate) struct InputOwn<'a, 's, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) state: MaybeMut<'s, E::State>,
     pub(crate) ctx: E::Context,
     #[cfg(feature = "memoization")]
-    pub(crate) memos: HashMap<(I::Offset, usize), Option<Located<I::Offset, E::Error>>>,
+    pub(crate) memos: MemoTable<I::Offset, E::Error>,
+    #[cfg(feature = "profile")]
+    pub(crate) profile: Option<crate::profile::Profile>,
+    #[cfg(feature = "trace")]
+    pub(crate) trace: Option<&'s mut dyn crate::trace::Subscriber>,
+    #[cfg(feature = "cst")]
+    pub(crate) cst: Option<Vec<crate::cst::Event>>,
+    // The span, end offset, and category of each semantic token recorded so far
+    #[cfg(feature = "semantic")]
+    pub(crate) semantic: Option<Vec<(I::Span, usize, crate::semantic::Category)>>,
+    #[cfg(feature = "metadata")]
+    pub(crate) metadata: Option<Vec<Box<dyn core::any::Any>>>,
+    #[cfg(any(feature = "cancel", feature = "step-limit"))]
+    pub(crate) interrupt: Option<crate::cancel::Interrupt>,
+    #[cfg(feature = "push")]
+    pub(crate) reached_end: Cell<bool>,
 }
 
 impl<'a, 's, I, E> InputOwn<'a, 's, I, E>
@@ -700,7 +1023,7 @@ where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
 {
-    #[cfg_attr(not(test), allow(dead_code))]
+    #[cfg_attr(not(all(test, feature = "alloc")), allow(dead_code))]
     pub(crate) fn new(input: I) -> InputOwn<'a, 's, I, E>
     where
         E::State: Default,
@@ -712,7 +1035,21 @@ where
             state: MaybeMut::Val(E::State::default()),
             ctx: E::Context::default(),
             #[cfg(feature = "memoization")]
-            memos: HashMap::default(),
+            memos: MemoTable::new(MemoBound::Unbounded),
+            #[cfg(feature = "profile")]
+            profile: None,
+            #[cfg(feature = "trace")]
+            trace: None,
+            #[cfg(feature = "cst")]
+            cst: None,
+            #[cfg(feature = "semantic")]
+            semantic: None,
+            #[cfg(feature = "metadata")]
+            metadata: None,
+            #[cfg(any(feature = "cancel", feature = "step-limit"))]
+            interrupt: None,
+            #[cfg(feature = "push")]
+            reached_end: Cell::new(false),
         }
     }
 
@@ -726,7 +1063,21 @@ where
             state: MaybeMut::Ref(state),
             ctx: E::Context::default(),
             #[cfg(feature = "memoization")]
-            memos: HashMap::default(),
+            memos: MemoTable::new(MemoBound::Unbounded),
+            #[cfg(feature = "profile")]
+            profile: None,
+            #[cfg(feature = "trace")]
+            trace: None,
+            #[cfg(feature = "cst")]
+            cst: None,
+            #[cfg(feature = "semantic")]
+            semantic: None,
+            #[cfg(feature = "metadata")]
+            metadata: None,
+            #[cfg(any(feature = "cancel", feature = "step-limit"))]
+            interrupt: None,
+            #[cfg(feature = "push")]
+            reached_end: Cell::new(false),
         }
     }
 
@@ -739,10 +1090,31 @@ where
             ctx: &self.ctx,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
+            #[cfg(feature = "memoization")]
+            ctx_generation: 0,
+            #[cfg(feature = "profile")]
+            profile: &mut self.profile,
+            #[cfg(feature = "trace")]
+            trace: match &mut self.trace {
+                Some(trace) => Some(&mut **trace),
+                None => None,
+            },
+            #[cfg(feature = "cst")]
+            cst: self.cst.as_mut(),
+            #[cfg(feature = "semantic")]
+            semantic: self.semantic.as_mut(),
+            #[cfg(feature = "metadata")]
+            metadata: self.metadata.as_mut(),
+            #[cfg(any(feature = "cancel", feature = "step-limit"))]
+            interrupt: &mut self.interrupt,
+            #[cfg(feature = "push")]
+            reached_end: &self.reached_end,
+            #[cfg(feature = "depth-limit")]
+            depth: 0,
         }
     }
 
-    #[cfg(test)]
+    #[cfg(feature = "alloc")]
     pub(crate) fn as_ref_at<'parse>(
         &'parse mut self,
         offset: I::Offset,
@@ -755,10 +1127,31 @@ where
             ctx: &self.ctx,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
+            #[cfg(feature = "memoization")]
+            ctx_generation: 0,
+            #[cfg(feature = "profile")]
+            profile: &mut self.profile,
+            #[cfg(feature = "trace")]
+            trace: match &mut self.trace {
+                Some(trace) => Some(&mut **trace),
+                None => None,
+            },
+            #[cfg(feature = "cst")]
+            cst: self.cst.as_mut(),
+            #[cfg(feature = "semantic")]
+            semantic: self.semantic.as_mut(),
+            #[cfg(feature = "metadata")]
+            metadata: self.metadata.as_mut(),
+            #[cfg(any(feature = "cancel", feature = "step-limit"))]
+            interrupt: &mut self.interrupt,
+            #[cfg(feature = "push")]
+            reached_end: &self.reached_end,
+            #[cfg(feature = "depth-limit")]
+            depth: 0,
         }
     }
 
-    pub(crate) fn into_errs(self) -> Vec<E::Error> {
+    pub(crate) fn into_errs(self) -> ErrorVec<E::Error> {
         self.errors
             .secondary
             .into_iter()
@@ -775,12 +1168,57 @@ pub struct InputRef<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) state: &'parse mut E::State,
     pub(crate) ctx: &'parse E::Context,
     #[cfg(feature = "memoization")]
-    pub(crate) memos: &'parse mut HashMap<(I::Offset, usize), Option<Located<I::Offset, E::Error>>>,
+    pub(crate) memos: &'parse mut MemoTable<I::Offset, E::Error>,
+    // The generation of the current context, which keeps apart the memoised results of different contexts
+    #[cfg(feature = "memoization")]
+    pub(crate) ctx_generation: usize,
+    #[cfg(feature = "profile")]
+    pub(crate) profile: &'parse mut Option<crate::profile::Profile>,
+    #[cfg(feature = "trace")]
+    pub(crate) trace: Option<&'parse mut dyn crate::trace::Subscriber>,
+    #[cfg(feature = "cst")]
+    pub(crate) cst: Option<&'parse mut Vec<crate::cst::Event>>,
+    #[cfg(feature = "semantic")]
+    pub(crate) semantic: Option<&'parse mut Vec<(I::Span, usize, crate::semantic::Category)>>,
+    #[cfg(feature = "metadata")]
+    pub(crate) metadata: Option<&'parse mut Vec<Box<dyn core::any::Any>>>,
+    #[cfg(any(feature = "cancel", feature = "step-limit"))]
+    pub(crate) interrupt: &'parse mut Option<crate::cancel::Interrupt>,
+    // Whether the parser has looked past the end of the input, in which case it might have parsed differently if the
+    // input were longer
+    #[cfg(feature = "push")]
+    pub(crate) reached_end: &'parse Cell<bool>,
+    // The number of depth-limited parsers (see `Parser::with_depth_limit`) that are currently being parsed
+    #[cfg(feature = "depth-limit")]
+    pub(crate) depth: usize,
 }
 
 impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E> {
+    /// Parse with a new context, which only lives for the duration of `f`.
     #[inline]
     pub(crate) fn with_ctx<'sub_parse, C, O>(
+        &'sub_parse mut self,
+        new_ctx: &'sub_parse C,
+        f: impl FnOnce(&mut InputRef<'a, '_, I, extra::Full<E::Error, E::State, C>>) -> O,
+    ) -> O
+    where
+        'parse: 'sub_parse,
+        C: 'a,
+    {
+        #[cfg(feature = "memoization")]
+        let generation = self.memos.begin_scope();
+        #[cfg(not(feature = "memoization"))]
+        let generation = 0;
+        let res = self.with_ctx_generation(new_ctx, generation, f);
+        #[cfg(feature = "memoization")]
+        self.memos.end_scope(generation);
+        res
+    }
+
+    /// Parse with a new context that lives for the whole parse (such as one stored in a parser), so that memoised
+    /// results are shared between each use of it.
+    #[inline]
+    pub(crate) fn with_shared_ctx<'sub_parse, C, O>(
         &'sub_parse mut self,
         new_ctx: &'sub_parse C,
         f: impl FnOnce(&mut InputRef<'a, 'sub_parse, I, extra::Full<E::Error, E::State, C>>) -> O,
@@ -789,6 +1227,38 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         'parse: 'sub_parse,
         C: 'a,
     {
+        #[cfg(feature = "memoization")]
+        let generation = self
+            .memos
+            .shared_generation(new_ctx as *const C as *const () as usize);
+        #[cfg(not(feature = "memoization"))]
+        let generation = 0;
+        self.with_ctx_generation(new_ctx, generation, f)
+    }
+
+    /// Create a new context generation, for use with [`InputRef::with_ctx_generation`].
+    #[inline]
+    pub(crate) fn new_ctx_generation(&mut self) -> usize {
+        #[cfg(feature = "memoization")]
+        return self.memos.new_generation();
+        #[cfg(not(feature = "memoization"))]
+        return 0;
+    }
+
+    /// Parse with a new context, keeping its memoised results apart from those of other contexts by its generation.
+    #[inline]
+    pub(crate) fn with_ctx_generation<'sub_parse, C, O>(
+        &'sub_parse mut self,
+        new_ctx: &'sub_parse C,
+        generation: usize,
+        f: impl FnOnce(&mut InputRef<'a, 'sub_parse, I, extra::Full<E::Error, E::State, C>>) -> O,
+    ) -> O
+    where
+        'parse: 'sub_parse,
+        C: 'a,
+    {
+        #[cfg(not(feature = "memoization"))]
+        let _ = generation;
         let mut new_inp = InputRef {
             input: self.input,
             offset: self.offset,
@@ -797,6 +1267,27 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             errors: self.errors,
             #[cfg(feature = "memoization")]
             memos: self.memos,
+            #[cfg(feature = "memoization")]
+            ctx_generation: generation,
+            #[cfg(feature = "profile")]
+            profile: self.profile,
+            #[cfg(feature = "trace")]
+            trace: match &mut self.trace {
+                Some(trace) => Some(&mut **trace),
+                None => None,
+            },
+            #[cfg(feature = "cst")]
+            cst: self.cst.as_deref_mut(),
+            #[cfg(feature = "semantic")]
+            semantic: self.semantic.as_deref_mut(),
+            #[cfg(feature = "metadata")]
+            metadata: self.metadata.as_deref_mut(),
+            #[cfg(any(feature = "cancel", feature = "step-limit"))]
+            interrupt: self.interrupt,
+            #[cfg(feature = "push")]
+            reached_end: self.reached_end,
+            #[cfg(feature = "depth-limit")]
+            depth: self.depth,
         };
         let res = f(&mut new_inp);
         self.offset = new_inp.offset;
@@ -808,10 +1299,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         &'sub_parse mut self,
         new_input: &'sub_parse I,
         f: impl FnOnce(&mut InputRef<'a, 'sub_parse, I, E>) -> O,
-        #[cfg(feature = "memoization")] memos: &'sub_parse mut HashMap<
-            (I::Offset, usize),
-            Option<Located<I::Offset, E::Error>>,
-        >,
+        #[cfg(feature = "memoization")] memos: &'sub_parse mut MemoTable<I::Offset, E::Error>,
     ) -> O
     where
         'parse: 'sub_parse,
@@ -824,6 +1312,28 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             errors: self.errors,
             #[cfg(feature = "memoization")]
             memos,
+            #[cfg(feature = "memoization")]
+            ctx_generation: self.ctx_generation,
+            #[cfg(feature = "profile")]
+            profile: self.profile,
+            #[cfg(feature = "trace")]
+            trace: match &mut self.trace {
+                Some(trace) => Some(&mut **trace),
+                None => None,
+            },
+            // Offsets within the nested input don't correspond to offsets of the outer input
+            #[cfg(feature = "cst")]
+            cst: None,
+            #[cfg(feature = "semantic")]
+            semantic: None,
+            #[cfg(feature = "metadata")]
+            metadata: self.metadata.as_deref_mut(),
+            #[cfg(any(feature = "cancel", feature = "step-limit"))]
+            interrupt: self.interrupt,
+            #[cfg(feature = "push")]
+            reached_end: self.reached_end,
+            #[cfg(feature = "depth-limit")]
+            depth: self.depth,
         };
         f(&mut new_inp)
     }
@@ -847,6 +1357,12 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         Marker {
             offset: self.offset,
             err_count: self.errors.secondary.len(),
+            #[cfg(feature = "cst")]
+            cst_count: self.cst.as_ref().map_or(0, |events| events.len()),
+            #[cfg(feature = "semantic")]
+            semantic_count: self.semantic.as_ref().map_or(0, |tokens| tokens.len()),
+            #[cfg(feature = "metadata")]
+            metadata_count: self.metadata.as_ref().map_or(0, |items| items.len()),
             phantom: PhantomData,
         }
     }
@@ -857,6 +1373,22 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     #[inline(always)]
     pub fn rewind(&mut self, marker: Marker<'a, 'parse, I>) {
         self.errors.secondary.truncate(marker.err_count);
+        #[cfg(feature = "step-limit")]
+        if let Some(interrupt) = self.interrupt {
+            interrupt.backtrack();
+        }
+        #[cfg(feature = "cst")]
+        if let Some(events) = &mut self.cst {
+            events.truncate(marker.cst_count);
+        }
+        #[cfg(feature = "semantic")]
+        if let Some(tokens) = &mut self.semantic {
+            tokens.truncate(marker.semantic_count);
+        }
+        #[cfg(feature = "metadata")]
+        if let Some(items) = &mut self.metadata {
+            items.truncate(marker.metadata_count);
+        }
         self.offset = marker.offset;
     }
 
@@ -880,10 +1412,10 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: ValueInput<'a>,
     {
-        loop {
+        while !self.interrupted() {
             // SAFETY: offset was generated by previous call to `Input::next`
             let (offset, token) = unsafe { self.input.next(self.offset) };
-            if token.filter(&mut f).is_none() {
+            if self.note_end(token).filter(&mut f).is_none() {
                 break;
             } else {
                 self.offset = offset;
@@ -891,23 +1423,75 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         }
     }
 
+    // Returns true if the parse has been cancelled or run out of steps, in which case the input should appear to have ended
+    #[cfg(any(feature = "cancel", feature = "step-limit"))]
+    #[inline(always)]
+    fn interrupted(&mut self) -> bool {
+        self.interrupt
+            .as_mut()
+            .map_or(false, |interrupt| interrupt.check())
+    }
+
+    #[cfg(not(any(feature = "cancel", feature = "step-limit")))]
+    #[inline(always)]
+    fn interrupted(&mut self) -> bool {
+        false
+    }
+
+    // Like `InputRef::interrupted`, but without counting as a check
+    #[cfg(any(feature = "cancel", feature = "step-limit"))]
+    #[inline(always)]
+    fn stopped(&self) -> bool {
+        self.interrupt
+            .as_ref()
+            .map_or(false, |interrupt| interrupt.stopped)
+    }
+
+    #[cfg(not(any(feature = "cancel", feature = "step-limit")))]
+    #[inline(always)]
+    fn stopped(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn note_end<T>(&self, token: Option<T>) -> Option<T> {
+        if token.is_none() {
+            self.reach_end();
+        }
+        token
+    }
+
+    // Records that the parser has looked past the end of the input, such as after scanning all of
+    // `InputRef::slice_trailing_inner`
+    #[inline(always)]
+    pub(crate) fn reach_end(&self) {
+        #[cfg(feature = "push")]
+        self.reached_end.set(true);
+    }
+
     #[inline(always)]
     pub(crate) fn next_inner(&mut self) -> (I::Offset, Option<I::Token>)
     where
         I: ValueInput<'a>,
     {
+        if self.interrupted() {
+            return (self.offset, None);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
         let (offset, token) = unsafe { self.input.next(self.offset) };
         self.offset = offset;
-        (self.offset, token)
+        (self.offset, self.note_end(token))
     }
 
     #[inline(always)]
     pub(crate) fn next_maybe_inner(&mut self) -> (I::Offset, Option<I::TokenMaybe>) {
+        if self.interrupted() {
+            return (self.offset, None);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
         let (offset, token) = unsafe { self.input.next_maybe(self.offset) };
         self.offset = offset;
-        (self.offset, token)
+        (self.offset, self.note_end(token))
     }
 
     #[inline(always)]
@@ -915,10 +1499,13 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: BorrowInput<'a>,
     {
+        if self.interrupted() {
+            return (self.offset, None);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
         let (offset, token) = unsafe { self.input.next_ref(self.offset) };
         self.offset = offset;
-        (self.offset, token)
+        (self.offset, self.note_end(token))
     }
 
     /// Attempt to parse this input using the given parser.
@@ -996,8 +1583,11 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     /// See [`InputRef::next_maybe`] for more information about what this function guarantees.
     #[inline(always)]
     pub fn peek_maybe(&self) -> Option<MaybeRef<'a, I::Token>> {
+        if self.stopped() {
+            return None;
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
-        unsafe { self.input.next_maybe(self.offset).1.map(Into::into) }
+        self.note_end(unsafe { self.input.next_maybe(self.offset).1.map(Into::into) })
     }
 
     /// Peek the next token in the input. Returns `None` if the end of the input has been reached.
@@ -1006,8 +1596,11 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: ValueInput<'a>,
     {
+        if self.stopped() {
+            return None;
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
-        unsafe { self.input.next(self.offset).1 }
+        self.note_end(unsafe { self.input.next(self.offset).1 })
     }
 
     /// Peek the next token in the input. Returns `None` if the end of the input has been reached.
@@ -1016,8 +1609,11 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: BorrowInput<'a>,
     {
+        if self.stopped() {
+            return None;
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
-        unsafe { self.input.next_ref(self.offset).1 }
+        self.note_end(unsafe { self.input.next_ref(self.offset).1 })
     }
 
     /// Skip the next token in the input.
@@ -1065,7 +1661,8 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         self.input.slice_from(range)
     }
 
-    #[cfg_attr(not(feature = "regex"), allow(dead_code))]
+    // Parsers that look at the end of the returned slice should call `InputRef::reach_end`
+    #[cfg_attr(not(any(feature = "regex", feature = "nom")), allow(dead_code))]
     #[inline(always)]
     pub(crate) fn slice_trailing_inner(&self) -> I::Slice
     where
@@ -1102,7 +1699,6 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         unsafe { self.input.span(before.offset..self.offset) }
     }
 
-    #[cfg(feature = "regex")]
     #[inline(always)]
     pub(crate) fn skip_bytes<C>(&mut self, skip: usize)
     where
@@ -1156,19 +1752,19 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
 
 /// Struct used in [`Parser::validate`] to collect user-emitted errors
 pub struct Emitter<E> {
-    emitted: Vec<E>,
+    emitted: ErrorVec<E>,
 }
 
 impl<E> Emitter<E> {
     #[inline]
     pub(crate) fn new() -> Emitter<E> {
         Emitter {
-            emitted: Vec::new(),
+            emitted: ErrorVec::new(),
         }
     }
 
     #[inline]
-    pub(crate) fn errors(self) -> Vec<E> {
+    pub(crate) fn errors(self) -> ErrorVec<E> {
         self.emitted
     }
 
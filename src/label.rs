@@ -36,6 +36,38 @@ impl<A, L> Labelled<A, L> {
             ..self
         }
     }
+
+    /// Record statistics about this parser when parsing with [`Parser::parse_profiled`], using the label to identify
+    /// it in the resulting [`Profile`](crate::profile::Profile).
+    ///
+    /// See the [`profile`] module for more information.
+    #[cfg(feature = "profile")]
+    pub fn profiled(self) -> crate::profile::Profiled<Self>
+    where
+        L: fmt::Display,
+    {
+        crate::profile::Profiled {
+            label: self.label.to_string(),
+            parser: self,
+        }
+    }
+
+    /// Emit events when this parser is entered, matches, or fails while parsing with [`Parser::parse_traced`], using
+    /// the label to identify it.
+    ///
+    /// See the [`trace`] module for more information.
+    #[cfg(feature = "trace")]
+    pub fn traced(self) -> crate::trace::Traced<Self>
+    where
+        L: fmt::Display,
+    {
+        use alloc::string::ToString;
+
+        crate::trace::Traced {
+            label: self.label.to_string(),
+            parser: self,
+        }
+    }
 }
 
 impl<'a, I, O, E, A, L> ParserSealed<'a, I, O, E> for Labelled<A, L>
@@ -43,7 +75,7 @@ where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, O, E>,
-    L: Clone,
+    L: Clone + fmt::Debug,
     E::Error: LabelError<'a, I, L>,
 {
     #[inline]
@@ -58,7 +90,8 @@ where
 
         if let Some(mut new_alt) = new_alt {
             let before_next = before.offset.into() + 1;
-            if new_alt.pos.into() == before_next {
+            // Errors found at the end of the input are located where the parser began, since no token was read
+            if new_alt.pos.into() == before_next || new_alt.pos.into() == before.offset.into() {
                 new_alt.err.label_with(self.label.clone());
             } else if self.is_context && new_alt.pos.into() > before_next {
                 // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
@@ -80,5 +113,30 @@ where
         res
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        ParserSealed::<I, O, E>::skip_leading_trivia(&self.parser, inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let label = alloc::format!("{:?}", self.label);
+        // Show string labels without their quotes
+        let label = match label.strip_prefix('"').and_then(|l| l.strip_suffix('"')) {
+            Some(label) => label.into(),
+            None => label,
+        };
+        let id = graph.add_labelled::<Self>(label);
+        let child = ParserSealed::<I, O, E>::graph_node(&self.parser, graph);
+        graph.edge(id, child);
+        id
+    }
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
@@ -0,0 +1,816 @@
+//! Utilities for two-phase parsing, where a lexer turns the input into tokens before a parser runs over them.
+//!
+//! Many languages are easier to parse (and produce better errors) when the input is first split into tokens, such as
+//! identifiers, literals, and punctuation, with whitespace and comments removed. Chumsky parsers can be used for both
+//! phases: the first phase produces a [`Vec`] of `(token, span)` pairs, and the second phase parses those tokens
+//! via [`Input::spanned`].
+//!
+//! [`Lexer`] packages this pattern: [`Lexer::lex`] runs the first phase and stores its tokens, and [`Tokens::parse`]
+//! runs the second phase over them, returning the errors from both phases together as [`LexParseError`]s.
+//!
+//! See [`Lexer`] for an example.
+//!
+//! Alternatively, [`Parser::lex_iter`] applies a parser for a single token repeatedly, yielding tokens one at a time
+//! as they are matched, so that chumsky can be used as a standalone lexer.
+//!
+//! For languages whose tokens depend on context, such as string interpolation or heredocs, [`ModeStack`] allows a
+//! lexer to switch between sets of token rules in a single pass.
+//!
+//! Between the two phases, [`group_delimiters`] can group tokens into [`TokenTree`]s by matching pairs of delimiters,
+//! so that the contents of each group can be parsed (and recovered from) in isolation.
+
+use super::*;
+use crate::input::SpannedInput;
+use alloc::collections::VecDeque;
+
+/// An error produced by either phase of a [`Lexer`] pipeline.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LexParseError<L, P> {
+    /// An error produced while lexing the input into tokens.
+    Lex(L),
+    /// An error produced while parsing the tokens.
+    Parse(P),
+}
+
+/// A lexer that runs as the first phase of a two-phase parse.
+///
+/// The lexer is a parser that produces a [`Vec`] of tokens, each paired with its span in the original input.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, lexer::{Lexer, LexParseError}, input::SpannedInput};
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token<'a> {
+///     Num(&'a str),
+///     Op(char),
+/// }
+///
+/// let lexer = Lexer::new(
+///     text::int::<_, _, extra::Err<Simple<char>>>(10)
+///         .map(Token::Num)
+///         .or(one_of("+-").map(Token::Op))
+///         .map_with_span(|tok, span| (tok, span))
+///         .padded()
+///         .repeated()
+///         .collect(),
+/// );
+///
+/// type TokenInput<'a, 'src> = SpannedInput<Token<'src>, SimpleSpan, &'a [(Token<'src>, SimpleSpan)]>;
+///
+/// fn parser<'a, 'src: 'a>() -> impl Parser<'a, TokenInput<'a, 'src>, i64, extra::Err<Simple<'a, Token<'src>>>> {
+///     let num = select! { Token::Num(x) => x.parse::<i64>().unwrap() };
+///     num.foldl(
+///         select! { Token::Op(op) => op }.then(num).repeated(),
+///         |a, (op, b)| if op == '+' { a + b } else { a - b },
+///     )
+/// }
+///
+/// let tokens = lexer.lex("1 + 2 - 4");
+/// assert_eq!(tokens.tokens().len(), 5);
+/// assert_eq!(tokens.parse(&parser()).into_result(), Ok(-1));
+///
+/// // Errors from lexing...
+/// let tokens = lexer.lex("1 + x");
+/// assert!(matches!(
+///     tokens.parse(&parser()).into_errors().as_slice(),
+///     [LexParseError::Lex(_)],
+/// ));
+///
+/// // ...and from parsing are both reported
+/// let tokens = lexer.lex("1 + + 2");
+/// assert!(matches!(
+///     tokens.parse(&parser()).into_errors().as_slice(),
+///     [LexParseError::Parse(_)],
+/// ));
+/// ```
+pub struct Lexer<L, I, T, E> {
+    lexer: L,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, T, E)>,
+}
+
+impl<L: Copy, I, T, E> Copy for Lexer<L, I, T, E> {}
+impl<L: Clone, I, T, E> Clone for Lexer<L, I, T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            lexer: self.lexer.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<L, I, T, E> Lexer<L, I, T, E> {
+    /// Create a new lexer from a parser that produces tokens paired with their spans.
+    pub const fn new<'a>(lexer: L) -> Self
+    where
+        I: Input<'a>,
+        E: ParserExtra<'a, I>,
+        L: Parser<'a, I, Vec<(T, I::Span)>, E>,
+    {
+        Self {
+            lexer,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Lex the given input into tokens, ready to be parsed by [`Tokens::parse`].
+    ///
+    /// If lexing fails without producing any tokens (i.e: the lexer was unable to recover from an error), the second
+    /// phase will not be run.
+    pub fn lex<'a>(&self, input: I) -> Tokens<T, I::Span, E::Error>
+    where
+        I: Input<'a>,
+        E: ParserExtra<'a, I>,
+        E::State: Default,
+        E::Context: Default,
+        L: Parser<'a, I, Vec<(T, I::Span)>, E>,
+    {
+        // The span of the (zero-width) pattern after the lexer is the end of input
+        let (output, errors) = (&self.lexer)
+            .then(empty().map_with_span(|(), span| span))
+            .parse(input)
+            .into_output_errors();
+        let (tokens, eoi) = match output {
+            Some((tokens, eoi)) => (Some(tokens), Some(eoi)),
+            None => (None, None),
+        };
+        Tokens::new(tokens, eoi, errors)
+    }
+}
+
+/// The output of [`Lexer::lex`]: a sequence of tokens, along with any errors that were produced while lexing.
+pub struct Tokens<T, S, E> {
+    tokens: Option<Vec<(T, S)>>,
+    eoi: Option<S>,
+    errors: Vec<E>,
+}
+
+impl<T, S, E> Tokens<T, S, E> {
+    pub(crate) fn new(tokens: Option<Vec<(T, S)>>, eoi: Option<S>, errors: Vec<E>) -> Self {
+        Self {
+            tokens,
+            eoi,
+            errors,
+        }
+    }
+
+    /// Get the tokens produced by the lexer, paired with their spans.
+    ///
+    /// This slice is empty if the lexer failed to produce any tokens.
+    pub fn tokens(&self) -> &[(T, S)] {
+        self.tokens.as_deref().unwrap_or(&[])
+    }
+
+    /// Get the errors produced by the lexer.
+    pub fn errors(&self) -> impl ExactSizeIterator<Item = &E> {
+        self.errors.iter()
+    }
+
+    /// Get the tokens as an input that can be passed to a parser, or `None` if the lexer failed to produce any tokens.
+    pub fn input(&self) -> Option<SpannedInput<T, S, &[(T, S)]>>
+    where
+        S: Span + Clone,
+    {
+        Some(self.tokens.as_deref()?.spanned(self.eoi.clone()?))
+    }
+
+    /// Parse the tokens with the given parser, returning errors from both lexing and parsing.
+    ///
+    /// Errors produced by the lexer come first, followed by those produced by the parser. If the lexer failed to
+    /// produce any tokens, the parser is not run and there is no output.
+    pub fn parse<'a, P, O, PE>(&'a self, parser: &P) -> ParseResult<O, LexParseError<E, PE::Error>>
+    where
+        T: 'a,
+        S: Span + Clone + 'a,
+        E: Clone,
+        P: Parser<'a, SpannedInput<T, S, &'a [(T, S)]>, O, PE>,
+        PE: ParserExtra<'a, SpannedInput<T, S, &'a [(T, S)]>>,
+        PE::State: Default,
+        PE::Context: Default,
+    {
+        let mut errors: Vec<_> = self
+            .errors
+            .iter()
+            .cloned()
+            .map(LexParseError::Lex)
+            .collect();
+        let output = self.input().and_then(|input| {
+            let (output, parse_errors) = parser.parse(input).into_output_errors();
+            errors.extend(parse_errors.into_iter().map(LexParseError::Parse));
+            output
+        });
+        ParseResult::new(output, errors)
+    }
+}
+
+/// An iterator over the successive matches of a parser in an input, skipping trivia between them. See
+/// [`Parser::lex_iter`].
+pub struct LexIter<'a, 's, P, T, I, O, OT, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    parser: P,
+    trivia: T,
+    own: InputOwn<'a, 's, I, E>,
+    offset: I::Offset,
+    // Items that have been lexed but not yet yielded, such as errors that the parser recovered from
+    pending: VecDeque<Result<(O, I::Span), E::Error>>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<OT>,
+}
+
+impl<'a, 's, P, T, I, O, OT, E> LexIter<'a, 's, P, T, I, O, OT, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    pub(crate) fn new(parser: P, trivia: T, own: InputOwn<'a, 's, I, E>) -> Self {
+        Self {
+            parser,
+            trivia,
+            offset: own.input.start(),
+            own,
+            pending: VecDeque::new(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, P, T, I, O, OT, E> Iterator for LexIter<'a, '_, P, T, I, O, OT, E>
+where
+    P: Parser<'a, I, O, E>,
+    T: Parser<'a, I, OT, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    type Item = Result<(O, I::Span), E::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.pop_front() {
+            return Some(item);
+        }
+
+        let mut inp = self.own.as_ref_at(self.offset);
+        loop {
+            let before = inp.save();
+            match self.trivia.go::<Check>(&mut inp) {
+                Ok(()) if inp.offset() != before.offset() => {}
+                _ => {
+                    inp.rewind(before);
+                    break;
+                }
+            }
+        }
+        if inp.peek_maybe().is_none() {
+            self.offset = inp.offset;
+            return None;
+        }
+
+        // Errors that trivia failed with are of no interest once the next match has been found
+        inp.errors.alt = None;
+        let before = inp.save();
+        let res = self.parser.go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let item = match res {
+            Ok(out) => {
+                #[cfg(debug_assertions)]
+                debug_assert!(
+                    before.offset() != inp.offset(),
+                    "found lex_iter parser making no progress",
+                );
+                Ok((out, inp.span_since(before.offset())))
+            }
+            // Skip the token that could not be lexed, so that lexing continues after it
+            Err(()) => {
+                inp.rewind(before);
+                inp.next_maybe();
+                Err(alt.expect("error but no alt?").err)
+            }
+        };
+        self.offset = inp.offset;
+
+        self.pending
+            .extend(self.own.errors.secondary.drain(..).map(|err| Err(err.err)));
+        self.pending.push_back(item);
+        self.pending.pop_front()
+    }
+}
+
+/// A stack of lexer modes, used as parser state to switch between sub-lexers.
+///
+/// Some languages cannot be tokenized by a single set of token rules: the contents of a string literal are lexed
+/// differently to the code around it, but an interpolated expression inside a string (`"a ${expr} b"`) is lexed like
+/// code again, and may itself contain strings. A mode stack tracks which set of rules applies at the current position.
+/// Parsers can be restricted to a mode with [`Parser::in_mode`], and change the mode with [`Parser::push_mode`] and
+/// [`Parser::pop_mode`].
+///
+/// The stack always contains at least one mode: the initial mode it was created with.
+///
+/// Note that parser state is not rewound when a parser backtracks, so mode changes made by a parser are not undone if
+/// a parser that contains it later fails. Mode changes should therefore be attached to tokens that commit the lexer to
+/// a new mode, such as an opening quote.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, lexer::ModeStack};
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Mode {
+///     Code,
+///     Str,
+///     Interp,
+/// }
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token<'a> {
+///     Ident(&'a str),
+///     StrStart,
+///     StrEnd,
+///     Text(&'a str),
+///     InterpStart,
+///     InterpEnd,
+/// }
+///
+/// type Extra = extra::State<ModeStack<Mode>>;
+///
+/// // Whitespace is skipped before code tokens, but not after, since it may belong to a string
+/// let code = text::whitespace::<_, _, Extra>().ignore_then(choice((
+///     text::ident().map(Token::Ident),
+///     just('"').to(Token::StrStart).push_mode(Mode::Str),
+///     just('}').to(Token::InterpEnd).in_mode(Mode::Interp).pop_mode(),
+/// )));
+///
+/// let string = choice((
+///     just('"').to(Token::StrEnd).pop_mode(),
+///     just("${").to(Token::InterpStart).push_mode(Mode::Interp),
+///     any()
+///         .and_is(just('"').or(just('$')).not())
+///         .repeated()
+///         .at_least(1)
+///         .slice()
+///         .map(Token::Text),
+/// ));
+///
+/// let lexer = choice((code.clone().in_mode(Mode::Code), code.in_mode(Mode::Interp), string.in_mode(Mode::Str)))
+///     .repeated()
+///     .collect::<Vec<Token<'_>>>();
+///
+/// let mut modes = ModeStack::new(Mode::Code);
+/// let tokens = lexer
+///     .parse_with_state(r#"x "a ${"b ${y}"} c""#, &mut modes)
+///     .into_result();
+///
+/// assert_eq!(
+///     tokens,
+///     Ok::<_, Vec<EmptyErr>>(vec![
+///         Token::Ident("x"),
+///         Token::StrStart,
+///         Token::Text("a "),
+///         Token::InterpStart,
+///         Token::StrStart,
+///         Token::Text("b "),
+///         Token::InterpStart,
+///         Token::Ident("y"),
+///         Token::InterpEnd,
+///         Token::StrEnd,
+///         Token::InterpEnd,
+///         Token::Text(" c"),
+///         Token::StrEnd,
+///     ]),
+/// );
+/// // All modes were exited
+/// assert_eq!(modes.depth(), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModeStack<Mo> {
+    modes: Vec<Mo>,
+}
+
+impl<Mo> ModeStack<Mo> {
+    /// Create a new mode stack, starting in the given mode.
+    pub fn new(initial: Mo) -> Self {
+        Self {
+            modes: vec![initial],
+        }
+    }
+
+    /// Get the current mode.
+    pub fn current(&self) -> &Mo {
+        self.modes.last().expect("mode stack is never empty")
+    }
+
+    /// Enter a new mode, which becomes the current mode until it is popped.
+    pub fn push(&mut self, mode: Mo) {
+        self.modes.push(mode);
+    }
+
+    /// Exit the current mode, returning to the previous mode.
+    ///
+    /// Returns `None` (and leaves the stack unchanged) if the current mode is the initial mode.
+    pub fn pop(&mut self) -> Option<Mo> {
+        if self.modes.len() > 1 {
+            self.modes.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of modes on the stack, including the initial mode.
+    pub fn depth(&self) -> usize {
+        self.modes.len()
+    }
+}
+
+/// Parser state that contains a [`ModeStack`].
+///
+/// This trait is implemented by [`ModeStack`] itself. It can also be implemented by a custom parser state that needs
+/// to track lexer modes alongside other information.
+pub trait ModalState<Mo> {
+    /// Get the mode stack.
+    fn mode_stack(&mut self) -> &mut ModeStack<Mo>;
+}
+
+impl<Mo> ModalState<Mo> for ModeStack<Mo> {
+    fn mode_stack(&mut self) -> &mut ModeStack<Mo> {
+        self
+    }
+}
+
+// Fail without consuming input, in the same way as a parser that does not accept the next token.
+fn reject<'a, I, E>(inp: &mut InputRef<'a, '_, I, E>)
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    let before = inp.save();
+    let (at, found) = inp.next_maybe_inner();
+    let err_span = inp.span_since(before.offset());
+    inp.rewind(before);
+    inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+}
+
+/// See [`Parser::in_mode`].
+pub struct InMode<A, Mo, OA> {
+    pub(crate) parser: A,
+    pub(crate) mode: Mo,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, Mo: Copy, OA> Copy for InMode<A, Mo, OA> {}
+impl<A: Clone, Mo: Clone, OA> Clone for InMode<A, Mo, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mode: self.mode.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, Mo, OA> ParserSealed<'a, I, OA, E> for InMode<A, Mo, OA>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: ModalState<Mo>,
+    A: Parser<'a, I, OA, E>,
+    Mo: PartialEq,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        if *inp.state().mode_stack().current() == self.mode {
+            self.parser.go::<M>(inp)
+        } else {
+            reject(inp);
+            Err(())
+        }
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA);
+
+    go_extra!(OA);
+}
+
+/// See [`Parser::push_mode`].
+pub struct PushMode<A, Mo, OA> {
+    pub(crate) parser: A,
+    pub(crate) mode: Mo,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, Mo: Copy, OA> Copy for PushMode<A, Mo, OA> {}
+impl<A: Clone, Mo: Clone, OA> Clone for PushMode<A, Mo, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mode: self.mode.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, Mo, OA> ParserSealed<'a, I, OA, E> for PushMode<A, Mo, OA>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: ModalState<Mo>,
+    A: Parser<'a, I, OA, E>,
+    Mo: Clone,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        let out = self.parser.go::<M>(inp)?;
+        inp.state().mode_stack().push(self.mode.clone());
+        Ok(out)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA);
+
+    go_extra!(OA);
+}
+
+/// See [`Parser::pop_mode`].
+pub struct PopMode<A, Mo, OA> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(Mo, OA)>,
+}
+
+impl<A: Copy, Mo, OA> Copy for PopMode<A, Mo, OA> {}
+impl<A: Clone, Mo, OA> Clone for PopMode<A, Mo, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, Mo, OA> ParserSealed<'a, I, OA, E> for PopMode<A, Mo, OA>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: ModalState<Mo>,
+    A: Parser<'a, I, OA, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        if inp.state().mode_stack().depth() <= 1 {
+            reject(inp);
+            return Err(());
+        }
+        let out = self.parser.go::<M>(inp)?;
+        inp.state().mode_stack().pop();
+        Ok(out)
+    }
+
+    graph_node!(parser: OA);
+
+    gen_input!(parser: OA);
+
+    go_extra!(OA);
+}
+
+/// A tree of tokens, in which matching pairs of delimiters (such as brackets) have been grouped together.
+///
+/// See [`group_delimiters`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TokenTree<T, S> {
+    /// A single token that is not a delimiter.
+    Token(T),
+    /// A group of token trees, surrounded by a pair of delimiters.
+    Group {
+        /// The opening delimiter, along with its span.
+        open: (T, S),
+        /// The closing delimiter, along with its span, or `None` if the group was never closed.
+        close: Option<(T, S)>,
+        /// The token trees within the group, each paired with its span.
+        trees: Vec<(TokenTree<T, S>, S)>,
+    },
+}
+
+/// An input over a sequence of [`TokenTree`]s, as produced by [`group_delimiters`].
+pub type TreeInput<'a, T, S> = SpannedInput<TokenTree<T, S>, S, &'a [(TokenTree<T, S>, S)]>;
+
+impl<T, S: Span + Clone> TokenTree<T, S>
+where
+    S::Offset: Clone,
+{
+    /// If this tree is a group, get the token trees within it as an input that can be parsed.
+    ///
+    /// The end of the input is a zero-width span at the start of the closing delimiter (or, if the group was never
+    /// closed, at the end of the group).
+    pub fn contents(&self) -> Option<TreeInput<'_, T, S>> {
+        match self {
+            Self::Token(_) => None,
+            Self::Group { open, close, trees } => {
+                let eoi = match close {
+                    Some((_, span)) => span.start(),
+                    None => trees.last().map_or(open.1.end(), |(_, span)| span.end()),
+                };
+                Some(
+                    trees
+                        .as_slice()
+                        .spanned(S::new(open.1.context(), eoi.clone()..eoi)),
+                )
+            }
+        }
+    }
+}
+
+/// Group a flat sequence of tokens into [`TokenTree`]s by matching pairs of delimiters.
+///
+/// `delimiters` is a list of `(open, close)` token pairs, such as `('(', ')')`. Each group's span extends from the
+/// start of its opening delimiter to the end of its closing delimiter.
+///
+/// Grouping delimiters before parsing (as `rustc` does) means that a parser can treat each group as a single token,
+/// parsing its contents separately with [`Parser::nested_in`] and [`group`]. Errors inside one group then cannot
+/// affect how the surrounding input is parsed.
+///
+/// Unbalanced delimiters are recovered from in the following way, and produce errors:
+///
+/// - A closing delimiter that does not match any open group produces an error (expecting the closing delimiter of the
+///   innermost open group, if any) and is removed.
+///
+/// - A closing delimiter that matches an outer open group closes that group, and any groups within it that are still
+///   open produce an `unclosed delimiter` error (created with [`Error::custom`]) spanning their opening delimiter.
+///
+/// - Groups that are still open at the end of the input also produce an `unclosed delimiter` error.
+///
+/// The output of this function always contains token trees, even if errors were produced.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::RichReason, lexer::{self, TokenTree, TreeInput}};
+/// #[derive(Debug, PartialEq)]
+/// enum SExpr {
+///     Atom(char),
+///     List(Vec<SExpr>),
+/// }
+///
+/// fn tokens(src: &str) -> Vec<(char, SimpleSpan)> {
+///     src.char_indices()
+///         .filter(|(_, c)| !c.is_whitespace())
+///         .map(|(i, c)| (c, (i..i + 1).into()))
+///         .collect()
+/// }
+///
+/// fn parser<'a>() -> impl Parser<'a, TreeInput<'a, char, SimpleSpan>, Vec<SExpr>, extra::Err<Rich<'a, TokenTree<char, SimpleSpan>>>> {
+///     recursive(|sexpr| {
+///         let atom = select! { TokenTree::Token(c) => SExpr::Atom(c) };
+///         let list = sexpr
+///             .repeated()
+///             .collect()
+///             .map(SExpr::List)
+///             .nested_in(lexer::group('('));
+///         atom.or(list)
+///     })
+///     .repeated()
+///     .collect()
+/// }
+///
+/// let src = "a (b (c d)) e";
+/// let trees = lexer::group_delimiters::<_, _, Rich<_>>(tokens(src), &[('(', ')')]).into_result().unwrap();
+/// assert_eq!(trees.len(), 3);
+/// assert_eq!(trees[1].1, (2..11).into());
+///
+/// let input = trees.as_slice().spanned((src.len()..src.len()).into());
+/// assert_eq!(
+///     parser().parse(input).into_result(),
+///     Ok(vec![
+///         SExpr::Atom('a'),
+///         SExpr::List(vec![SExpr::Atom('b'), SExpr::List(vec![SExpr::Atom('c'), SExpr::Atom('d')])]),
+///         SExpr::Atom('e'),
+///     ]),
+/// );
+///
+/// // Unbalanced delimiters are reported, but still produce trees
+/// let (trees, errs) = lexer::group_delimiters::<_, _, Rich<_>>(tokens("(a ) ) (b"), &[('(', ')')]).into_output_errors();
+/// assert_eq!(trees.unwrap().len(), 2);
+/// assert_eq!(errs.len(), 2);
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(5..6));
+/// assert!(matches!(errs[1].reason(), RichReason::Custom(msg) if msg == "unclosed delimiter"));
+/// assert_eq!(errs[1].span(), &SimpleSpan::from(7..8));
+/// ```
+pub fn group_delimiters<'a, T, S, E>(
+    tokens: impl IntoIterator<Item = (T, S)>,
+    delimiters: &[(T, T)],
+) -> ParseResult<Vec<(TokenTree<T, S>, S)>, E>
+where
+    T: PartialEq + Clone + 'a,
+    S: Span + Clone + 'a,
+    S::Offset: Clone,
+    E: Error<'a, TreeInput<'a, T, S>>,
+{
+    struct Frame<T, S> {
+        open: (T, S),
+        close: T,
+        trees: Vec<(TokenTree<T, S>, S)>,
+    }
+
+    fn finish<T, S: Span>(frame: Frame<T, S>, close: Option<(T, S)>) -> (TokenTree<T, S>, S) {
+        let end = match &close {
+            Some((_, span)) => span.end(),
+            None => frame
+                .trees
+                .last()
+                .map_or(frame.open.1.end(), |(_, span)| span.end()),
+        };
+        let span = S::new(frame.open.1.context(), frame.open.1.start()..end);
+        let tree = TokenTree::Group {
+            open: frame.open,
+            close,
+            trees: frame.trees,
+        };
+        (tree, span)
+    }
+
+    let mut stack: Vec<Frame<T, S>> = Vec::new();
+    let mut top = Vec::new();
+    let mut errors = Vec::new();
+
+    let unclosed = |frame: &Frame<T, S>| E::custom(frame.open.1.clone(), "unclosed delimiter");
+
+    for (tok, span) in tokens {
+        if let Some((_, close)) = delimiters.iter().find(|(open, _)| *open == tok) {
+            stack.push(Frame {
+                open: (tok, span),
+                close: close.clone(),
+                trees: Vec::new(),
+            });
+            continue;
+        }
+
+        let tree = if delimiters.iter().any(|(_, close)| *close == tok) {
+            match stack.iter().rposition(|frame| frame.close == tok) {
+                Some(depth) => {
+                    while stack.len() > depth + 1 {
+                        let frame = stack.pop().unwrap();
+                        errors.push(unclosed(&frame));
+                        let tree = finish(frame, None);
+                        stack.last_mut().unwrap().trees.push(tree);
+                    }
+                    finish(stack.pop().unwrap(), Some((tok, span)))
+                }
+                None => {
+                    errors.push(E::expected_found(
+                        stack.last().map(|frame| {
+                            Some(MaybeRef::Val(TokenTree::Token(frame.close.clone())))
+                        }),
+                        Some(MaybeRef::Val(TokenTree::Token(tok))),
+                        span,
+                    ));
+                    continue;
+                }
+            }
+        } else {
+            (TokenTree::Token(tok), span)
+        };
+
+        match stack.last_mut() {
+            Some(frame) => frame.trees.push(tree),
+            None => top.push(tree),
+        }
+    }
+
+    while let Some(frame) = stack.pop() {
+        errors.push(unclosed(&frame));
+        let tree = finish(frame, None);
+        match stack.last_mut() {
+            Some(frame) => frame.trees.push(tree),
+            None => top.push(tree),
+        }
+    }
+
+    ParseResult::new(Some(top), errors)
+}
+
+/// A parser over [`TokenTree`]s that accepts a group opened by the given delimiter, and outputs its contents as an
+/// input.
+///
+/// This is intended to be used with [`Parser::nested_in`] to parse the contents of a group. See [`group_delimiters`]
+/// for an example.
+///
+/// The output type of this parser is [`TreeInput`], the contents of the group.
+pub fn group<'a, T, S, E>(
+    open: T,
+) -> impl Parser<'a, TreeInput<'a, T, S>, TreeInput<'a, T, S>, E> + Clone
+where
+    T: PartialEq + Clone + 'a,
+    S: Span + Clone + 'a,
+    S::Offset: Clone,
+    E: ParserExtra<'a, TreeInput<'a, T, S>>,
+{
+    primitive::select_ref(move |tree: &'a TokenTree<T, S>, _| match tree {
+        TokenTree::Group { open: (tok, _), .. } if *tok == open => tree.contents(),
+        _ => None,
+    })
+}
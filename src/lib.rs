@@ -10,7 +10,11 @@
 )]
 // TODO: Talk about `.map` and purity assumptions
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+// Allows the code generated by `#[derive(Parseable)]` and `grammar!` to refer to this crate in its tests
+#[cfg(all(test, any(feature = "derive", feature = "grammar")))]
+extern crate self as chumsky;
 
 macro_rules! go_extra {
     ( $O :ty ) => {
@@ -46,28 +50,139 @@ macro_rules! go_cfg_extra {
     };
 }
 
+macro_rules! graph_node {
+    ( $( $field:ident : $O:ty ),* $(,)? ) => {
+        #[cfg(feature = "alloc")]
+        fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+            let id = graph.add::<Self>();
+            $(
+                let child = ParserSealed::<I, $O, E>::graph_node(&self.$field, graph);
+                graph.edge(id, child);
+            )*
+            id
+        }
+    };
+    ( iter $( $field:ident : $O:ty ),* $(,)? ) => {
+        #[cfg(feature = "alloc")]
+        fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+            let id = graph.add::<Self>();
+            $(
+                let child = IterParserSealed::<I, $O, E>::graph_node(&self.$field, graph);
+                graph.edge(id, child);
+            )*
+            id
+        }
+    };
+}
+
+// Implements `gen_input` (or `gen_items`) for parsers that generate the same input as the parser they contain. With
+// `=> Output`, the parser produces a different output, which is left undetermined.
+macro_rules! gen_input {
+    ($field:ident : $O:ty) => {
+        #[cfg(feature = "alloc")]
+        fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<$O>, ()>
+        where
+            I::Token: Clone,
+        {
+            ParserSealed::<I, $O, E>::gen_input(&self.$field, gen)
+        }
+    };
+    ($field:ident : $OA:ty => $O:ty) => {
+        #[cfg(feature = "alloc")]
+        fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<$O>, ()>
+        where
+            I::Token: Clone,
+        {
+            ParserSealed::<I, $OA, E>::gen_input(&self.$field, gen).map(|_| None)
+        }
+    };
+    (iter $field:ident : $O:ty) => {
+        #[cfg(feature = "alloc")]
+        fn gen_items(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<$O>>, ()>
+        where
+            I::Token: Clone,
+        {
+            IterParserSealed::<I, $O, E>::gen_items(&self.$field, gen)
+        }
+    };
+    (iter $field:ident : $OA:ty => $O:ty) => {
+        #[cfg(feature = "alloc")]
+        fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<$O>, ()>
+        where
+            I::Token: Clone,
+        {
+            IterParserSealed::<I, $OA, E>::gen_items(&self.$field, gen).map(|_| None)
+        }
+    };
+}
+
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+pub mod binary;
 mod blanket;
+#[cfg(any(feature = "cancel", feature = "step-limit"))]
+pub mod cancel;
 pub mod combinator;
 pub mod container;
+#[cfg(feature = "cst")]
+pub mod cst;
+#[cfg(feature = "derive")]
+pub mod derive;
 pub mod error;
 #[cfg(feature = "extension")]
 pub mod extension;
 pub mod extra;
+#[cfg(feature = "futures")]
+pub mod futures;
+#[cfg(feature = "alloc")]
+pub mod generate;
+#[cfg(feature = "grammar")]
+pub mod grammar;
+#[cfg(feature = "alloc")]
+pub mod graph;
 #[cfg(docsrs)]
 pub mod guide;
+#[cfg(feature = "alloc")]
+pub mod incremental;
 pub mod input;
 #[cfg(feature = "label")]
 pub mod label;
+#[cfg(feature = "alloc")]
+pub mod lexer;
+#[cfg(feature = "logos")]
+pub mod logos;
+#[cfg(feature = "metadata")]
+pub mod metadata;
+#[cfg(feature = "nom")]
+pub mod nom;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "pest")]
+pub mod pest;
 pub mod pratt;
 pub mod primitive;
 mod private;
+#[cfg(feature = "profile")]
+pub mod profile;
+#[cfg(feature = "push")]
+pub mod push;
 pub mod recovery;
 pub mod recursive;
 #[cfg(feature = "regex")]
 pub mod regex;
+#[cfg(feature = "semantic")]
+pub mod semantic;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod span;
+pub mod state;
+#[cfg(feature = "alloc")]
 mod stream;
+#[cfg(feature = "alloc")]
+pub mod test;
 pub mod text;
+#[cfg(feature = "trace")]
+pub mod trace;
 pub mod util;
 
 /// Commonly used functions, traits and types.
@@ -75,37 +190,54 @@ pub mod util;
 /// *Listen, three eyes,” he said, “don’t you try to outweird me, I get stranger things than you free with my breakfast
 /// cereal.”*
 pub mod prelude {
+    #[cfg(feature = "label")]
+    pub use super::primitive::labelled_choice;
     #[cfg(feature = "regex")]
     pub use super::regex::regex;
+    #[cfg(feature = "alloc")]
+    pub use super::{
+        error::Rich,
+        recovery::nested_delimiters,
+        recursive::{recursive, Recursive},
+        Boxed,
+    };
     pub use super::{
-        error::{Cheap, EmptyErr, Error as _, Rich, Simple},
+        error::{Cheap, EmptyErr, Error as _, Simple},
         extra,
         input::Input,
-        primitive::{any, choice, custom, empty, end, group, just, map_ctx, none_of, one_of, todo},
-        recovery::{nested_delimiters, skip_then_retry_until, skip_until, via_parser},
-        recursive::{recursive, Recursive},
+        primitive::{
+            any, any_except, choice, custom, empty, end, group, ignore_with_ctx, just, map_ctx,
+            none_of, one_of, todo,
+        },
+        recovery::{skip_then_retry_until, skip_until, via_parser},
         span::{SimpleSpan, Span as _},
-        text, Boxed, ConfigIterParser, ConfigParser, IterParser, ParseResult, Parser,
+        text, ConfigIterParser, ConfigParser, IterParser, ParseResult, Parser,
     };
     pub use crate::{select, select_ref};
 }
 
 use crate::input::InputOwn;
+#[cfg(feature = "alloc")]
 use alloc::{boxed::Box, rc::Rc, string::String, sync::Arc, vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::cell::UnsafeCell;
 use core::{
     borrow::Borrow,
-    cell::{Cell, RefCell, UnsafeCell},
+    cell::{Cell, RefCell},
     cmp::{Eq, Ordering},
     fmt,
     hash::Hash,
     marker::PhantomData,
     mem::MaybeUninit,
-    ops::{Range, RangeFrom},
+    ops::{ControlFlow, Range, RangeFrom, RangeInclusive},
     panic::Location,
     str::FromStr,
 };
+#[cfg(feature = "alloc")]
 use hashbrown::HashMap;
 
+#[cfg(feature = "alloc")]
+use self::input::ExactSizeInput;
 #[cfg(feature = "label")]
 use self::label::{LabelError, Labelled};
 use self::{
@@ -113,10 +245,10 @@ use self::{
     container::*,
     error::Error,
     extra::ParserExtra,
-    input::{BorrowInput, Emitter, ExactSizeInput, InputRef, SliceInput, StrInput, ValueInput},
+    input::{BorrowInput, Emitter, InputRef, SliceInput, StrInput, ValueInput},
     pratt::Pratt,
     prelude::*,
-    primitive::Any,
+    primitive::{Any, End},
     private::{
         Check, ConfigIterParserSealed, ConfigParserSealed, Emit, IPResult, IterParserSealed,
         Located, MaybeUninitExt, Mode, PResult, ParserSealed, Sealed,
@@ -124,7 +256,7 @@ use self::{
     recovery::{RecoverWith, Strategy},
     span::Span,
     text::*,
-    util::{MaybeMut, MaybeRef},
+    util::{ErrorVec, MaybeMut, MaybeRef},
 };
 #[cfg(all(feature = "extension", doc))]
 use self::{extension::v1::*, primitive::custom, stream::Stream};
@@ -155,10 +287,14 @@ impl<T> core::panic::RefUnwindSafe for EmptyPhantom<T> {}
 
 #[cfg(feature = "sync")]
 mod sync {
+    #[cfg(feature = "alloc")]
     use super::*;
 
+    #[cfg(feature = "alloc")]
     pub(crate) type RefC<T> = alloc::sync::Arc<T>;
+    #[cfg(feature = "alloc")]
     pub(crate) type RefW<T> = alloc::sync::Weak<T>;
+    #[cfg(feature = "alloc")]
     pub(crate) type DynParser<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + Send + Sync + 'b;
 
     /// A trait that requires either nothing or `Send` and `Sync` bounds depending on whether the `sync` feature is
@@ -169,10 +305,14 @@ mod sync {
 
 #[cfg(not(feature = "sync"))]
 mod sync {
+    #[cfg(feature = "alloc")]
     use super::*;
 
+    #[cfg(feature = "alloc")]
     pub(crate) type RefC<T> = alloc::rc::Rc<T>;
+    #[cfg(feature = "alloc")]
     pub(crate) type RefW<T> = alloc::rc::Weak<T>;
+    #[cfg(feature = "alloc")]
     pub(crate) type DynParser<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + 'b;
 
     /// A trait that requires either nothing or `Send` and `Sync` bounds depending on whether the `sync` feature is
@@ -181,6 +321,7 @@ mod sync {
     impl<T> MaybeSync for T {}
 }
 
+#[cfg(feature = "alloc")]
 use sync::{DynParser, MaybeSync, RefC, RefW};
 
 /// The result of running a [`Parser`]. Can be converted into a [`Result`] via
@@ -189,11 +330,11 @@ use sync::{DynParser, MaybeSync, RefC, RefW};
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ParseResult<T, E> {
     output: Option<T>,
-    errs: Vec<E>,
+    errs: ErrorVec<E>,
 }
 
 impl<T, E> ParseResult<T, E> {
-    pub(crate) fn new(output: Option<T>, errs: Vec<E>) -> ParseResult<T, E> {
+    pub(crate) fn new(output: Option<T>, errs: ErrorVec<E>) -> ParseResult<T, E> {
         ParseResult { output, errs }
     }
 
@@ -224,19 +365,19 @@ impl<T, E> ParseResult<T, E> {
 
     /// Convert this `ParseResult` into a vector containing any errors. The vector will be empty if there were no
     /// errors.
-    pub fn into_errors(self) -> Vec<E> {
+    pub fn into_errors(self) -> ErrorVec<E> {
         self.errs
     }
 
     /// Convert this `ParseResult` into a tuple containing the output, if any existed, and errors, if any were
     /// encountered.
-    pub fn into_output_errors(self) -> (Option<T>, Vec<E>) {
+    pub fn into_output_errors(self) -> (Option<T>, ErrorVec<E>) {
         (self.output, self.errs)
     }
 
     /// Convert this `ParseResult` into a standard `Result`. This discards output if parsing generated any errors,
     /// matching the old behavior of [`Parser::parse`].
-    pub fn into_result(self) -> Result<T, Vec<E>> {
+    pub fn into_result(self) -> Result<T, ErrorVec<E>> {
         if self.errs.is_empty() {
             self.output.ok_or(self.errs)
         } else {
@@ -244,9 +385,39 @@ impl<T, E> ParseResult<T, E> {
         }
     }
 
+    /// Apply a function to the output of this result, if it exists, keeping its errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let int = text::int::<_, _, extra::Err<Rich<char>>>(10);
+    ///
+    /// assert_eq!(int.parse("42").map(str::len).into_result(), Ok(2));
+    /// assert_eq!(int.parse("4x").map(str::len).into_errors().len(), 1);
+    /// ```
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> ParseResult<U, E> {
+        ParseResult {
+            output: self.output.map(f),
+            errs: self.errs,
+        }
+    }
+
     /// If the parse succeeded (i.e: no errors were produced), this function returns the output value, `T`.
     ///
-    /// If parsing generated errors, this function panics (even if these errors were non-fatal).
+    /// If parsing generated errors, this function panics (even if these errors were non-fatal), with a message that
+    /// lists the [`Debug`](fmt::Debug) form of each of the errors on its own line.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use chumsky::prelude::*;
+    /// let int = text::int::<_, _, extra::Err<Rich<char>>>(10);
+    ///
+    /// assert_eq!(int.parse("42").unwrap(), "42");
+    /// // Panics, listing the error
+    /// int.parse("x").unwrap();
+    /// ```
     #[track_caller]
     pub fn unwrap(self) -> T
     where
@@ -256,13 +427,55 @@ impl<T, E> ParseResult<T, E> {
             self.output.expect("parser generated no errors or output")
         } else {
             panic!(
-                "called `ParseResult::unwrap()` on a parse result with errors: {:?}",
-                self.errs
+                "called `ParseResult::unwrap()` on a parse result with errors:{}",
+                NumberedErrors(&self.errs)
             )
         }
     }
 }
 
+// The errors of a parse as a numbered list, one per line
+struct NumberedErrors<'b, E>(&'b [E]);
+
+impl<E: fmt::Debug> fmt::Display for NumberedErrors<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, err) in self.0.iter().enumerate() {
+            write!(f, "\n  {}. {:?}", i + 1, err)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, Tok, S, L> ParseResult<T, Rich<'a, Tok, S, L>> {
+    /// Render the errors of this result as plain text, each with an excerpt of the source they refer to. See
+    /// [`Rich::excerpt`] for the format of each error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let assign = text::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .then_ignore(just('=').padded())
+    ///     .then(text::int(10))
+    ///     .separated_by(text::newline())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let src = "x = 1\ny = z";
+    /// let result = assign.parse(src);
+    /// assert_eq!(result.report(src).to_string(), [
+    ///     "error: found 'z' expected '0'..'9'",
+    ///     " --> 2:5",
+    ///     "  |",
+    ///     "2 | y = z",
+    ///     "  |     ^",
+    /// ].join("\n"));
+    /// ```
+    pub fn report<'b>(&'b self, src: &'b str) -> error::Report<'b, 'a, Tok, S, L> {
+        error::Report::new(&self.errs, src)
+    }
+}
+
 /// A trait implemented by parsers.
 ///
 /// Parsers take inputs of type `I` (implementing [`Input`]) and attempt to parse them into a value of type `O`. In
@@ -339,1052 +552,1218 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
         ParseResult::new(out, errs)
     }
 
-    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
+    /// Parse a stream of tokens like [`Parser::parse`], starting from a default state and returning the final state
+    /// alongside the result.
     ///
-    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
-    /// If you want to include non-default state, use [`Parser::check_with_state`] instead.
+    /// This is useful when the state accumulates data that is needed after parsing, such as an
+    /// [`Interner`](state::Interner) or a table of definitions, without having to create the state beforehand.
+    /// If you want to start from non-default state, use [`Parser::parse_with_owned_state`] instead.
     ///
-    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
-    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
-    fn check(&self, input: I) -> ParseResult<(), E::Error>
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, state::Interner};
+    /// let idents = text::ident::<_, _, extra::State<Interner>>()
+    ///     .intern()
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let (result, interner) = idents.parse_returning_state("x y x");
+    /// let symbols = result.into_result().unwrap();
+    /// assert_eq!(interner.len(), 2);
+    /// assert_eq!(interner.resolve(symbols[2]), "x");
+    /// ```
+    fn parse_returning_state(&self, input: I) -> (ParseResult<O, E::Error>, E::State)
     where
         Self: Sized,
         I: Input<'a>,
         E::State: Default,
         E::Context: Default,
     {
-        self.check_with_state(input, &mut E::State::default())
+        self.parse_with_owned_state(input, E::State::default())
     }
 
-    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
-    ///
-    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
-    /// If you want to just use a default state value, use [`Parser::check`] instead.
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], taking ownership of the initial state and returning
+    /// the final state alongside the result.
     ///
-    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
-    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
-    fn check_with_state(&self, input: I, state: &mut E::State) -> ParseResult<(), E::Error>
+    /// See [`Parser::parse_returning_state`] for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_returning_state`] instead.
+    fn parse_with_owned_state(
+        &self,
+        input: I,
+        mut state: E::State,
+    ) -> (ParseResult<O, E::Error>, E::State)
     where
         Self: Sized,
         I: Input<'a>,
         E::Context: Default,
     {
-        let mut own = InputOwn::new_state(input, state);
-        let mut inp = own.as_ref_start();
-        let res = self.then_ignore(end()).go::<Check>(&mut inp);
-        let alt = inp.errors.alt.take();
-        let mut errs = own.into_errs();
-        let out = match res {
-            Ok(()) => Some(()),
-            Err(()) => {
-                errs.push(alt.expect("error but no alt?").err);
-                None
-            }
-        };
-        ParseResult::new(out, errs)
+        let result = self.parse_with_state(input, &mut state);
+        (result, state)
     }
 
-    /// Map from a slice of the input based on the current parser's span to a value.
+    /// Parse a prefix of a stream of tokens, yielding an output if possible along with the rest of the input that was
+    /// not parsed.
     ///
-    /// The returned value may borrow data from the input slice, making this function very useful
-    /// for creating zero-copy AST output values
-    fn map_slice<U, F: Fn(I::Slice) -> U>(self, f: F) -> MapSlice<'a, Self, I, O, E, F, U>
+    /// Unlike [`Parser::parse`], the parser does not need to consume the whole input, so this can be used to parse one
+    /// message at the start of a buffer, or to embed a parser within a larger hand-written scanner. If parsing fails,
+    /// nothing is consumed and the whole input is returned.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_partial`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let header = text::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .then_ignore(just(": "))
+    ///     .then(none_of('\n').repeated().slice())
+    ///     .then_ignore(just('\n'));
+    ///
+    /// let input = "Host: example.com\nAccept: */*\n\nbody";
+    /// let (result, rest) = header.parse_partial(input);
+    /// assert_eq!(result.into_result(), Ok(("Host", "example.com")));
+    /// assert_eq!(rest, "Accept: */*\n\nbody");
+    ///
+    /// // If parsing fails, the whole input is left over
+    /// let (result, rest) = header.parse_partial("\nbody");
+    /// assert!(result.has_errors());
+    /// assert_eq!(rest, "\nbody");
+    /// ```
+    fn parse_partial(&self, input: I) -> (ParseResult<O, E::Error>, I::Slice)
     where
         Self: Sized,
         I: SliceInput<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        MapSlice {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        self.parse_with_state_partial(input, &mut E::State::default())
     }
 
-    /// Convert the output of this parser into a slice of the input, based on the current parser's
-    /// span.
+    /// Parse a prefix of a stream of tokens like [`Parser::parse_partial`], using the given state like
+    /// [`Parser::parse_with_state`].
     ///
-    /// This is effectively a special case of [`map_slice`](Parser::map_slice)`(|x| x)`
-    fn slice(self) -> Slice<Self, O>
+    /// If you want to just use a default state value, use [`Parser::parse_partial`] instead.
+    fn parse_with_state_partial(
+        &self,
+        input: I,
+        state: &mut E::State,
+    ) -> (ParseResult<O, E::Error>, I::Slice)
     where
         Self: Sized,
+        I: SliceInput<'a>,
+        E::Context: Default,
     {
-        Slice {
-            parser: self,
-            phantom: EmptyPhantom::new(),
-        }
+        let mut own = InputOwn::new_state(input, state);
+        let mut inp = own.as_ref_start();
+        let res = self.go::<Emit>(&mut inp);
+        let offset = inp.offset;
+        let alt = inp.errors.alt.take();
+        let rest = match res {
+            Ok(_) => own.input.slice_from(offset..),
+            Err(()) => own.input.slice_from(own.input.start()..),
+        };
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        (ParseResult::new(out, errs), rest)
     }
 
-    /// Filter the output of this parser, accepting only inputs that match the given predicate.
-    ///
-    /// The output type of this parser is `I`, the input that was found.
+    /// Create an iterator over the successive matches of this parser in the input, skipping `trivia` (such as
+    /// whitespace and comments) before each match, and stopping at the end of the input.
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let lowercase = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(char::is_ascii_lowercase)
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
+    /// This makes a parser for a single token usable as a standalone lexer, without collecting all of the tokens up
+    /// front. Each item is either a match, along with its span, or an error. When the parser fails, the error is
+    /// yielded and the token where the match was attempted is skipped, so lexing continues just after it. Errors that
+    /// the parser recovered from while matching are yielded before the match that they belong to.
     ///
-    /// assert_eq!(lowercase.parse("hello").into_result(), Ok("hello".to_string()));
-    /// assert!(lowercase.parse("Hello").has_errors());
-    /// ```
-    fn filter<F: Fn(&O) -> bool>(self, f: F) -> Filter<Self, F>
-    where
-        Self: Sized,
-    {
-        Filter {
-            parser: self,
-            filter: f,
-        }
-    }
-
-    /// Map the output of this parser to another value.
+    /// The trivia parser is applied repeatedly until it fails or stops consuming input, so it only needs to match a
+    /// single piece of trivia (use [`empty`] if there is none). The parser should always consume input when it
+    /// succeeds.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// If you want to include non-default state, use [`Parser::lex_iter_with_state`] instead.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
+    /// # use chumsky::prelude::*;
     /// #[derive(Debug, PartialEq)]
-    /// enum Token { Word(String), Num(u64) }
-    ///
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated().at_least(1)
-    ///     .collect::<String>()
-    ///     .map(Token::Word);
-    ///
-    /// let num = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated().at_least(1)
-    ///     .collect::<String>()
-    ///     .map(|s| Token::Num(s.parse().unwrap()));
+    /// enum Token<'a> {
+    ///     Ident(&'a str),
+    ///     Num(u64),
+    ///     Op(char),
+    /// }
     ///
-    /// let token = word.or(num);
+    /// let token = choice((
+    ///     text::ident::<_, _, extra::Err<Simple<char>>>().map(Token::Ident),
+    ///     text::int(10).from_str().unwrapped().map(Token::Num),
+    ///     one_of("+-*/=").map(Token::Op),
+    /// ));
+    /// // Whitespace and line comments
+    /// let trivia = text::whitespace().at_least(1).or(just("//").then(none_of('\n').repeated()).ignored());
     ///
-    /// assert_eq!(token.parse("test").into_result(), Ok(Token::Word("test".to_string())));
-    /// assert_eq!(token.parse("42").into_result(), Ok(Token::Num(42)));
+    /// let tokens = token
+    ///     .lex_iter("x = 4 // four\n  * y2 ", trivia)
+    ///     .map(|token| token.map(|(token, span)| (token, span.into_range())))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(tokens, [
+    ///     Ok((Token::Ident("x"), 0..1)),
+    ///     Ok((Token::Op('='), 2..3)),
+    ///     Ok((Token::Num(4), 4..5)),
+    ///     Ok((Token::Op('*'), 16..17)),
+    ///     Ok((Token::Ident("y2"), 18..20)),
+    /// ]);
+    ///
+    /// // Characters that don't start a token are reported, and lexing continues after them
+    /// let errors = token.lex_iter("a ? b", text::whitespace().at_least(1)).filter(Result::is_err);
+    /// assert_eq!(errors.count(), 1);
     /// ```
-    fn map<U, F: Fn(O) -> U>(self, f: F) -> Map<Self, O, F>
+    #[cfg(feature = "alloc")]
+    fn lex_iter<T, OT>(
+        self,
+        input: I,
+        trivia: T,
+    ) -> lexer::LexIter<'a, 'static, Self, T, I, O, OT, E>
     where
         Self: Sized,
+        T: Parser<'a, I, OT, E>,
+        E::State: Default,
+        E::Context: Default,
     {
-        Map {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        lexer::LexIter::new(self, trivia, InputOwn::new(input))
     }
 
-    /// Map the output of this parser to another value, making use of the pattern's span when doing so.
-    ///
-    /// This is very useful when generating an AST that attaches a span to each AST node.
-    ///
-    /// The output type of this parser is `U`, the same as the function's output.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// use std::ops::Range;
-    ///
-    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
-    /// #[derive(Debug, PartialEq)]
-    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
-    ///
-    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .map_with_span(|ident, span| Spanned(ident, span))
-    ///     .padded();
+    /// Create an iterator over the successive matches of this parser like [`Parser::lex_iter`], using the given
+    /// state like [`Parser::parse_with_state`].
     ///
-    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
-    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
-    /// ```
-    fn map_with_span<U, F: Fn(O, I::Span) -> U>(self, f: F) -> MapWithSpan<Self, O, F>
+    /// If you want to just use a default state value, use [`Parser::lex_iter`] instead.
+    #[cfg(feature = "alloc")]
+    fn lex_iter_with_state<'s, T, OT>(
+        self,
+        input: I,
+        trivia: T,
+        state: &'s mut E::State,
+    ) -> lexer::LexIter<'a, 's, Self, T, I, O, OT, E>
     where
         Self: Sized,
+        T: Parser<'a, I, OT, E>,
+        E::Context: Default,
     {
-        MapWithSpan {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        lexer::LexIter::new(self, trivia, InputOwn::new_state(input, state))
     }
 
-    /// Map the output of this parser to another value, making use of the parser's state when doing so.
-    ///
-    /// This is very useful for parsing non context-free grammars.
+    /// Find the patterns that this parser would accept at the given offset of the input, such as to suggest
+    /// completions in an editor.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// The input is cut off at the offset, and the patterns are those that the parser expected to find instead of the
+    /// end of the input there. [Labelled](Parser::labelled) parsers are reported by their label, so labelling the
+    /// keywords and identifiers of a grammar gives more useful completions (this is needed for parsers that don't say
+    /// what they expected, such as [`text::keyword`]). If the input cannot be parsed up to the offset (because of an
+    /// error before it), no patterns are returned. To complete a partially typed word, ask for the patterns expected
+    /// at the start of the word.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// use std::ops::Range;
-    ///
-    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
-    /// #[derive(Debug, PartialEq)]
-    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
-    ///
-    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .map_with_span(|ident, span| Spanned(ident, span))
-    ///     .padded();
+    /// # use chumsky::{prelude::*, error::RichPattern};
+    /// let list = one_of::<_, _, extra::Err<Rich<char>>>("xyz")
+    ///     .separated_by(just(','))
+    ///     .delimited_by(just('['), just(']'));
     ///
-    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
-    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
+    /// assert_eq!(
+    ///     list.expected_at("[x,y]", 1),
+    ///     ['x', 'y', 'z', ']'].map(|c| RichPattern::Token(c.into())),
+    /// );
+    /// // A list can be closed, or continued with another item
+    /// assert_eq!(
+    ///     list.expected_at("[x,y]", 4),
+    ///     [',', ']'].map(|c| RichPattern::Token(c.into())),
+    /// );
     /// ```
-    fn map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> U>(
-        self,
-        f: F,
-    ) -> MapWithState<Self, O, F>
+    #[cfg(feature = "alloc")]
+    fn expected_at<L>(&self, input: I, offset: usize) -> Vec<error::RichPattern<'a, I::Token, L>>
     where
         Self: Sized,
+        I: SliceInput<'a, Offset = usize, Slice = I>,
+        E: ParserExtra<'a, I, Error = Rich<'a, I::Token, I::Span, L>>,
+        E::State: Default,
+        E::Context: Default,
     {
-        MapWithState {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
+        let mut own = InputOwn::<I, E>::new(input.slice(input.start()..offset));
+        let mut inp = own.as_ref_start();
+        let _ = self.then_ignore(end()).go::<Check>(&mut inp);
+        let mut expected = Vec::new();
+        if let Some(alt) = inp.errors.alt.take().filter(|alt| alt.pos == offset) {
+            alt.err.into_reason().into_expected(&mut expected);
         }
+        expected
     }
 
-    /// After a successful parse, apply a fallible function to the output. If the function produces an error, treat it
-    /// as a parsing error.
+    /// Parse a stream of tokens like [`Parser::parse`], additionally recording statistics about each profiled parser.
     ///
-    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
-    /// [`Parser::validate`] instead.
-    ///
-    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .try_map(|s: &str, span| s
-    ///         .parse::<u8>()
-    ///         .map_err(|e| Rich::custom(span, e)));
-    ///
-    /// assert!(byte.parse("255").has_output());
-    /// assert!(byte.parse("256").has_errors()); // Out of range
-    /// ```
-    #[doc(alias = "filter_map")]
-    fn try_map<U, F: Fn(O, I::Span) -> Result<U, E::Error>>(self, f: F) -> TryMap<Self, O, F>
+    /// Parsers are profiled with [`Labelled::profiled`]. See the [`profile`] module for more information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_profiled`] instead.
+    #[cfg(feature = "profile")]
+    fn parse_profiled(&self, input: I) -> (ParseResult<O, E::Error>, profile::Profile)
     where
         Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        TryMap {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        self.parse_with_state_profiled(input, &mut E::State::default())
     }
 
-    /// After a successful parse, apply a fallible function to the output, making use of the parser's state when
-    /// doing so. If the function produces an error, treat it as a parsing error.
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], additionally recording statistics about each
+    /// profiled parser.
     ///
-    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
-    /// [`Parser::validate`] instead.
-    ///
-    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
-    fn try_map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> Result<U, E::Error>>(
-        self,
-        f: F,
-    ) -> TryMapWithState<Self, O, F>
+    /// Parsers are profiled with [`Labelled::profiled`]. See the [`profile`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_profiled`] instead.
+    #[cfg(feature = "profile")]
+    fn parse_with_state_profiled(
+        &self,
+        input: I,
+        state: &mut E::State,
+    ) -> (ParseResult<O, E::Error>, profile::Profile)
     where
         Self: Sized,
+        I: Input<'a>,
+        E::Context: Default,
     {
-        TryMapWithState {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        let mut own = InputOwn::new_state(input, state);
+        own.profile = Some(profile::Profile::default());
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let profile = own.profile.take().unwrap_or_default();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        (ParseResult::new(out, errs), profile)
     }
 
-    /// Ignore the output of this parser, yielding `()` as an output instead.
-    ///
-    /// This can be used to reduce the cost of parsing by avoiding unnecessary allocations (most collections containing
-    /// [ZSTs](https://doc.rust-lang.org/nomicon/exotic-sizes.html#zero-sized-types-zsts)
-    /// [do not allocate](https://doc.rust-lang.org/std/vec/struct.Vec.html#guarantees)). For example, it's common to
-    /// want to ignore whitespace in many grammars (see [`text::whitespace`]).
-    ///
-    /// The output type of this parser is `()`.
+    /// Parse a stream of tokens like [`Parser::parse`], additionally sending events emitted by traced parsers to the
+    /// given subscriber.
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// // A parser that parses any number of whitespace characters without allocating
-    /// let whitespace = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_whitespace())
-    ///     .ignored()
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
-    ///
-    /// assert_eq!(whitespace.parse("    ").into_result(), Ok(vec![(); 4]));
-    /// assert!(whitespace.parse("  hello").has_errors());
-    /// ```
-    fn ignored(self) -> Ignored<Self, O>
+    /// Parsers are traced with [`Labelled::traced`]. See the [`trace`] module for more information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_traced`] instead.
+    #[cfg(feature = "trace")]
+    fn parse_traced(
+        &self,
+        input: I,
+        subscriber: &mut dyn trace::Subscriber,
+    ) -> ParseResult<O, E::Error>
     where
         Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        Ignored {
-            parser: self,
-            phantom: EmptyPhantom::new(),
-        }
+        self.parse_with_state_traced(input, &mut E::State::default(), subscriber)
     }
 
-    /// Memoise the parser such that later attempts to parse the same input 'remember' the attempt and exit early.
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], additionally sending events emitted by traced
+    /// parsers to the given subscriber.
     ///
-    /// If you're finding that certain inputs produce exponential behaviour in your parser, strategically applying
-    /// memoisation to a ['garden path'](https://en.wikipedia.org/wiki/Garden-path_sentence) rule is often an effective
-    /// way to solve the problem. At the limit, applying memoisation to all combinators will turn any parser into one
-    /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
-    ///
-    /// Memoisation also works with recursion, so this can be used to write parsers using
-    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
-    // TODO: Example
-    #[cfg(feature = "memoization")]
-    fn memoised(self) -> Memoised<Self>
+    /// Parsers are traced with [`Labelled::traced`]. See the [`trace`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_traced`] instead.
+    #[cfg(feature = "trace")]
+    fn parse_with_state_traced(
+        &self,
+        input: I,
+        state: &mut E::State,
+        subscriber: &mut dyn trace::Subscriber,
+    ) -> ParseResult<O, E::Error>
     where
         Self: Sized,
+        I: Input<'a>,
+        E::Context: Default,
     {
-        Memoised { parser: self }
+        let mut own = InputOwn::new_state(input, state);
+        own.trace = Some(subscriber);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        ParseResult::new(out, errs)
     }
 
-    /// Transform all outputs of this parser to a pretermined value.
-    ///
-    /// The output type of this parser is `U`, the type of the predetermined value.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Clone, Debug, PartialEq)]
-    /// enum Op { Add, Sub, Mul, Div }
-    ///
-    /// let op = just::<_, _, extra::Err<Simple<char>>>('+').to(Op::Add)
-    ///     .or(just('-').to(Op::Sub))
-    ///     .or(just('*').to(Op::Mul))
-    ///     .or(just('/').to(Op::Div));
+    /// Parse a stream of tokens like [`Parser::parse`], additionally building a lossless concrete syntax tree of the
+    /// input.
     ///
-    /// assert_eq!(op.parse("+").into_result(), Ok(Op::Add));
-    /// assert_eq!(op.parse("/").into_result(), Ok(Op::Div));
-    /// ```
-    fn to<U: Clone>(self, to: U) -> To<Self, O, U>
+    /// The tree is made up of the nodes and tokens recorded by [`Parser::syntax_node`] and [`Parser::syntax_token`],
+    /// within a root node of the given `root` kind. Any input not covered by a token becomes a token of the given
+    /// `trivia` kind, so the tree always contains the whole input, even if parsing failed. See the [`cst`] module for
+    /// more information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_cst`] instead.
+    #[cfg(feature = "cst")]
+    fn parse_cst(
+        &self,
+        input: I,
+        root: impl Into<cst::SyntaxKind>,
+        trivia: impl Into<cst::SyntaxKind>,
+    ) -> (ParseResult<O, E::Error>, cst::GreenNode)
     where
         Self: Sized,
+        I: SliceInput<'a, Offset = usize>,
+        I::Slice: AsRef<str>,
+        E::State: Default,
+        E::Context: Default,
     {
-        To {
-            parser: self,
-            to,
-            phantom: EmptyPhantom::new(),
-        }
+        self.parse_with_state_cst(input, &mut E::State::default(), root, trivia)
     }
 
-    /// Label this parser with the given label.
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], additionally building a lossless concrete syntax
+    /// tree of the input.
     ///
-    /// Labelling a parser makes all errors generated by the parser refer to the label rather than any sub-elements
-    /// within the parser. For example, labelling a parser for an expression would yield "expected expression" errors
-    /// rather than "expected integer, string, binary op, etc." errors.
-    // TODO: Example
-    #[cfg(feature = "label")]
-    fn labelled<L>(self, label: L) -> Labelled<Self, L>
+    /// See [`Parser::parse_cst`] and the [`cst`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_cst`] instead.
+    #[cfg(feature = "cst")]
+    fn parse_with_state_cst(
+        &self,
+        input: I,
+        state: &mut E::State,
+        root: impl Into<cst::SyntaxKind>,
+        trivia: impl Into<cst::SyntaxKind>,
+    ) -> (ParseResult<O, E::Error>, cst::GreenNode)
     where
         Self: Sized,
-        E::Error: LabelError<'a, I, L>,
+        I: SliceInput<'a, Offset = usize>,
+        I::Slice: AsRef<str>,
+        E::Context: Default,
     {
-        Labelled {
-            parser: self,
-            label,
-            is_context: false,
-        }
+        let text = input.slice_from(input.start()..);
+        let mut own = InputOwn::new_state(input, state);
+        own.cst = Some(Vec::new());
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let events = own.cst.take().unwrap_or_default();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        let tree = cst::build(&events, text.as_ref(), root.into(), trivia.into());
+        (ParseResult::new(out, errs), tree)
     }
 
-    /// Parse one thing and then another thing, yielding a tuple of the two outputs.
-    ///
-    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    /// let two_words = word.then_ignore(just(' ')).then(word);
+    /// Parse a stream of tokens like [`Parser::parse`], additionally collecting the span and category of each semantic
+    /// token recorded by [`Parser::semantic_token`].
     ///
-    /// assert_eq!(two_words.parse("dog cat").into_result(), Ok(("dog".to_string(), "cat".to_string())));
-    /// assert!(two_words.parse("hedgehog").has_errors());
-    /// ```
-    fn then<U, B: Parser<'a, I, U, E>>(self, other: B) -> Then<Self, B, O, U, E>
+    /// Tokens are collected even if parsing fails, and are given in the order they appear in the input. See the
+    /// [`semantic`] module for more information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_semantic`] instead.
+    #[cfg(feature = "semantic")]
+    #[allow(clippy::type_complexity)]
+    fn parse_semantic(
+        &self,
+        input: I,
+    ) -> (ParseResult<O, E::Error>, Vec<(I::Span, semantic::Category)>)
     where
         Self: Sized,
+        E::State: Default,
+        E::Context: Default,
     {
-        Then {
-            parser_a: self,
-            parser_b: other,
-            phantom: EmptyPhantom::new(),
-        }
+        self.parse_with_state_semantic(input, &mut E::State::default())
     }
 
-    /// Parse one thing and then another thing, yielding only the output of the latter.
-    ///
-    /// The output type of this parser is `U`, the same as the second parser.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let zeroes = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| *c == '0').ignored().repeated().collect::<Vec<_>>();
-    /// let digits = any().filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated()
-    ///     .collect::<String>();
-    /// let integer = zeroes
-    ///     .ignore_then(digits)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], additionally collecting the span and category of
+    /// each semantic token recorded by [`Parser::semantic_token`].
     ///
-    /// assert_eq!(integer.parse("00064").into_result(), Ok(64));
-    /// assert_eq!(integer.parse("32").into_result(), Ok(32));
-    /// ```
-    fn ignore_then<U, B: Parser<'a, I, U, E>>(self, other: B) -> IgnoreThen<Self, B, O, E>
+    /// See [`Parser::parse_semantic`] and the [`semantic`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_semantic`] instead.
+    #[cfg(feature = "semantic")]
+    #[allow(clippy::type_complexity)]
+    fn parse_with_state_semantic(
+        &self,
+        input: I,
+        state: &mut E::State,
+    ) -> (ParseResult<O, E::Error>, Vec<(I::Span, semantic::Category)>)
     where
         Self: Sized,
+        E::Context: Default,
     {
-        IgnoreThen {
-            parser_a: self,
-            parser_b: other,
-            phantom: EmptyPhantom::new(),
-        }
+        let mut own = InputOwn::new_state(input, state);
+        own.semantic = Some(Vec::new());
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let tokens = own.semantic.take().unwrap_or_default();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        let tokens = tokens
+            .into_iter()
+            .map(|(span, _, category)| (span, category))
+            .collect();
+        (ParseResult::new(out, errs), tokens)
     }
 
-    /// Parse one thing and then another thing, yielding only the output of the former.
-    ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    ///
-    /// # Examples
+    /// Parse a stream of tokens like [`Parser::parse`], additionally collecting the metadata recorded by
+    /// [`Parser::record_metadata`].
     ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
+    /// Metadata is collected even if parsing fails. See the [`metadata`] module for more information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_metadata`] instead.
+    #[cfg(feature = "metadata")]
+    fn parse_metadata(&self, input: I) -> (ParseResult<O, E::Error>, metadata::Metadata)
+    where
+        Self: Sized,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.parse_with_state_metadata(input, &mut E::State::default())
+    }
+
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], additionally collecting the metadata recorded by
+    /// [`Parser::record_metadata`].
     ///
-    /// let punctuated = word
-    ///     .then_ignore(just('!').or(just('?')).or_not());
+    /// See [`Parser::parse_metadata`] and the [`metadata`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_metadata`] instead.
+    #[cfg(feature = "metadata")]
+    fn parse_with_state_metadata(
+        &self,
+        input: I,
+        state: &mut E::State,
+    ) -> (ParseResult<O, E::Error>, metadata::Metadata)
+    where
+        Self: Sized,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::new_state(input, state);
+        own.metadata = Some(Vec::new());
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let items = own.metadata.take().unwrap_or_default();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        (
+            ParseResult::new(out, errs),
+            metadata::Metadata::from_items(items),
+        )
+    }
+
+    /// Parse a stream of tokens like [`Parser::parse`], stopping early if the given
+    /// [`CancelToken`](cancel::CancelToken) is cancelled before parsing finishes.
     ///
-    /// let sentence = punctuated
-    ///     .padded() // Allow for whitespace gaps
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// If the token is cancelled during the parse (or was already cancelled), this returns
+    /// [`Cancelled`](cancel::Cancelled) rather than the result of parsing. See the [`cancel`] module for more
+    /// information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_cancellable`] instead.
+    #[cfg(feature = "cancel")]
+    fn parse_cancellable(
+        &self,
+        input: I,
+        token: &cancel::CancelToken,
+    ) -> Result<ParseResult<O, E::Error>, cancel::Cancelled>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.parse_with_state_cancellable(input, &mut E::State::default(), token)
+    }
+
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], stopping early if the given
+    /// [`CancelToken`](cancel::CancelToken) is cancelled before parsing finishes.
     ///
-    /// assert_eq!(
-    ///     sentence.parse("hello! how are you?").into_result(),
-    ///     Ok(vec![
-    ///         "hello".to_string(),
-    ///         "how".to_string(),
-    ///         "are".to_string(),
-    ///         "you".to_string(),
-    ///     ]),
-    /// );
-    /// ```
-    fn then_ignore<U, B: Parser<'a, I, U, E>>(self, other: B) -> ThenIgnore<Self, B, U, E>
+    /// See [`Parser::parse_cancellable`] and the [`cancel`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_cancellable`] instead.
+    #[cfg(feature = "cancel")]
+    fn parse_with_state_cancellable(
+        &self,
+        input: I,
+        state: &mut E::State,
+        token: &cancel::CancelToken,
+    ) -> Result<ParseResult<O, E::Error>, cancel::Cancelled>
     where
         Self: Sized,
+        I: Input<'a>,
+        E::Context: Default,
     {
-        ThenIgnore {
-            parser_a: self,
-            parser_b: other,
-            phantom: EmptyPhantom::new(),
+        let mut own = InputOwn::new_state(input, state);
+        own.interrupt = Some(cancel::Interrupt::cancellable(token.clone()));
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        // Parsing may have finished just before the token was cancelled, so check it one last time
+        if token.is_cancelled() {
+            return Err(cancel::Cancelled);
         }
+        let alt = inp.errors.alt.take();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        Ok(ParseResult::new(out, errs))
     }
 
-    /// Parse input as part of a token-tree - using an input generated from within the current
-    /// input.
-    ///
-    /// The output of this parser is `O`, the output of the parser it is called on.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, util::MaybeRef, error::Simple};
-    /// #[derive(Debug, Clone, PartialEq)]
-    /// enum Token<'a> {
-    ///     Struct,
-    ///     Ident(&'a str),
-    ///     Item(&'a str),
-    ///     Group(Vec<Token<'a>>),
-    /// }
-    ///
-    /// let group = select_ref! { Token::Group(g) => g.as_slice() };
-    ///
-    /// let ident = select_ref! { Token::Ident(i) => *i };
-    ///
-    /// let items = select_ref! { Token::Item(i) => *i }
-    ///     .repeated()
-    ///     .collect::<Vec<_>>()
-    ///     .nested_in(group);
-    ///
-    /// let struc = just::<_, _, extra::Err<Simple<_>>>(&Token::Struct)
-    ///     .ignore_then(ident)
-    ///     .then(items);
-    ///
-    /// let tl = struc
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// Parse a stream of tokens like [`Parser::parse`], stopping early if parsing takes more than the given number of
+    /// steps.
     ///
-    /// let tokens = [
-    ///     Token::Struct,
-    ///     Token::Ident("foo"),
-    ///     Token::Group(vec![
-    ///         Token::Item("a"),
-    ///         Token::Item("b"),
-    ///     ]),
-    /// ];
+    /// Each token read from the input, and each time the parser backtracks, is a step. If the parse runs out of steps,
+    /// this returns [`StepLimitExceeded`](cancel::StepLimitExceeded) rather than the result of parsing. See the
+    /// [`cancel`] module for more information.
+    /// If you want to include non-default state, use [`Parser::parse_with_state_and_step_limit`] instead.
+    #[cfg(feature = "step-limit")]
+    fn parse_with_step_limit(
+        &self,
+        input: I,
+        steps: u64,
+    ) -> Result<ParseResult<O, E::Error>, cancel::StepLimitExceeded>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.parse_with_state_and_step_limit(input, &mut E::State::default(), steps)
+    }
+
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], stopping early if parsing takes more than the given
+    /// number of steps.
     ///
-    /// assert_eq!(tl.parse(&tokens).into_result(), Ok(vec![("foo", vec!["a", "b"])]));
-    /// ```
-    fn nested_in<B: Parser<'a, I, I, E>>(self, other: B) -> NestedIn<Self, B, O, E>
+    /// See [`Parser::parse_with_step_limit`] and the [`cancel`] module for more information.
+    /// If you want to just use a default state value, use [`Parser::parse_with_step_limit`] instead.
+    #[cfg(feature = "step-limit")]
+    fn parse_with_state_and_step_limit(
+        &self,
+        input: I,
+        state: &mut E::State,
+        steps: u64,
+    ) -> Result<ParseResult<O, E::Error>, cancel::StepLimitExceeded>
     where
         Self: Sized,
-        I: 'a,
+        I: Input<'a>,
+        E::Context: Default,
     {
-        NestedIn {
-            parser_a: self,
-            parser_b: other,
-            phantom: EmptyPhantom::new(),
+        let mut own = InputOwn::new_state(input, state);
+        own.interrupt = Some(cancel::Interrupt::step_limit(steps));
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        if inp
+            .interrupt
+            .as_ref()
+            .map_or(false, |interrupt| interrupt.stopped)
+        {
+            return Err(cancel::StepLimitExceeded);
         }
+        let alt = inp.errors.alt.take();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        Ok(ParseResult::new(out, errs))
     }
 
-    /// Parse one thing and then another thing, creating the second parser from the result of
-    /// the first. If you only have a couple cases to handle, prefer [`Parser::or`].
-    ///
-    /// The output of this parser is `U`, the result of the second parser
+    /// Parse a stream of tokens like [`Parser::parse`], additionally returning statistics about the memo table used by
+    /// [memoised](Parser::memoised) parsers.
     ///
-    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
-    /// recovery then the second produces an error, the primary error will point to the location in
-    /// the second parser which failed, ignoring that the first parser may be the root cause. There
-    /// may be other pathological errors cases as well.
+    /// This can be used to check that memoising a parser actually helps: see [`MemoStats`].
+    /// If you want to include non-default state, use [`Parser::parse_with_state_and_memo_stats`] instead.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let successor = just(b'\0').configure(|cfg, ctx: &u8| cfg.seq(*ctx + 1));
-    ///
-    /// // A parser that parses a single letter and then its successor
-    /// let successive_letters = one_of::<_, _, extra::Err<Simple<u8>>>(b'a'..=b'z')
-    ///     .then_with_ctx(successor);
+    /// # use chumsky::prelude::*;
+    /// let expr = recursive(|expr| {
+    ///     let atom = text::int::<_, _, extra::Default>(10).or(expr.delimited_by(just('('), just(')')));
+    ///     atom.clone()
+    ///         .then_ignore(just('+'))
+    ///         .then(atom.clone())
+    ///         .slice()
+    ///         .memoised()
+    ///         .or(atom)
+    /// });
     ///
-    /// assert_eq!(successive_letters.parse(b"ab").into_result(), Ok(b'b')); // 'b' follows 'a'
-    /// assert!(successive_letters.parse(b"ac").has_errors()); // 'c' does not follow 'a'
+    /// let (result, stats) = expr.parse_with_memo_stats("((((1))))");
+    /// assert_eq!(result.into_result(), Ok("1"));
+    /// // Without memoisation, each level of nesting would parse the levels within it twice
+    /// assert_eq!((stats.hits, stats.misses), (10, 5));
+    /// assert!(stats.memory > 0);
     /// ```
-    fn then_with_ctx<U, P>(
-        self,
-        then: P,
-    ) -> ThenWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
+    #[cfg(feature = "memoization")]
+    fn parse_with_memo_stats(&self, input: I) -> (ParseResult<O, E::Error>, MemoStats)
     where
         Self: Sized,
-        O: 'a,
-        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        ThenWithCtx {
-            parser: self,
-            then,
-            phantom: EmptyPhantom::new(),
-        }
+        self.parse_with_state_and_memo_stats(input, &mut E::State::default())
     }
 
-    /// Run the previous contextual parser with the provided context
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// # use chumsky::primitive::JustCfg;
-    ///
-    /// let generic = just(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+    /// Parse a stream of tokens like [`Parser::parse_with_state`], additionally returning statistics about the memo
+    /// table used by [memoised](Parser::memoised) parsers.
     ///
-    /// let parse_a = just::<_, _, extra::Default>(b'b').ignore_then(generic.with_ctx::<u8>(b'a'));
-    /// let parse_b = just::<_, _, extra::Default>(b'a').ignore_then(generic.with_ctx(b'b'));
-    ///
-    /// assert_eq!(parse_a.parse(b"ba" as &[_]).into_result(), Ok::<_, Vec<EmptyErr>>(b'a'));
-    /// assert!(parse_a.parse(b"bb").has_errors());
-    /// assert_eq!(parse_b.parse(b"ab" as &[_]).into_result(), Ok(b'b'));
-    /// assert!(parse_b.parse(b"aa").has_errors());
-    /// ```
-    fn with_ctx<Ctx>(self, ctx: Ctx) -> WithCtx<Self, Ctx>
+    /// If you want to just use a default state value, use [`Parser::parse_with_memo_stats`] instead.
+    #[cfg(feature = "memoization")]
+    fn parse_with_state_and_memo_stats(
+        &self,
+        input: I,
+        state: &mut E::State,
+    ) -> (ParseResult<O, E::Error>, MemoStats)
     where
         Self: Sized,
-        Ctx: 'a + Clone,
+        I: Input<'a>,
+        E::Context: Default,
     {
-        WithCtx { parser: self, ctx }
+        let mut own = InputOwn::new_state(input, state);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let stats = own.memos.stats();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => Some(out),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        (ParseResult::new(out, errs), stats)
     }
 
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
+    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
     ///
-    /// let escape = just("\\n").to('\n');
+    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
+    /// If you want to include non-default state, use [`Parser::check_with_state`] instead.
     ///
-    /// // C-style string literal
-    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
-    ///     .and_is(escape.not())
-    ///     .or(escape)
-    ///     .repeated()
-    ///     .collect::<String>()
-    ///     .padded_by(just('"'));
+    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
+    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
+    ///
+    /// # Performance
+    ///
+    /// Unlike [`Parser::parse`], this function runs the parser in a mode that never constructs output values: the
+    /// closures given to combinators like [`Parser::map`] and [`Parser::to`] are not called, and combinators like
+    /// [`IterParser::collect`] do not allocate their collections. Errors are generated exactly as they are by
+    /// [`Parser::parse`]. For parsers that build large syntax trees, this often makes checking an input considerably
+    /// faster than parsing it, which is useful when only the validity of an input matters, such as when validating
+    /// syntax, reporting errors in an editor as the user types, or fuzzing a grammar.
+    ///
+    /// Because output closures are skipped, any side effects they have will not occur (see 'Purity and optimisation' in
+    /// the technical notes of the guide). Closures that can produce errors, such as those given to [`Parser::filter`],
+    /// [`Parser::try_map`], and [`Parser::validate`], are still called.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(
-    ///     string.parse("\"wxyz\"").into_result().as_deref(),
-    ///     Ok("wxyz"),
-    /// );
-    /// assert_eq!(
-    ///     string.parse("\"a\nb\"").into_result().as_deref(),
-    ///     Ok("a\nb"),
-    /// );
     /// ```
-    fn and_is<U, B>(self, other: B) -> AndIs<Self, B, U>
+    /// # use chumsky::prelude::*;
+    /// let list = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .map(|s: &str| s.parse::<u64>().unwrap())
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// assert!(!list.check("[1,2,3]").has_errors());
+    /// assert_eq!(list.check("[1,2,3]").into_output(), Some(()));
+    /// assert!(list.check("[1,2,").has_errors());
+    /// ```
+    fn check(&self, input: I) -> ParseResult<(), E::Error>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        AndIs {
-            parser_a: self,
-            parser_b: other,
-            phantom: EmptyPhantom::new(),
-        }
+        self.check_with_state(input, &mut E::State::default())
     }
 
-    /// Parse the pattern surrounded by the given delimiters.
-    ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
     ///
-    /// # Examples
+    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
+    /// If you want to just use a default state value, use [`Parser::check`] instead.
     ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// // A LISP-style S-expression
-    /// #[derive(Debug, PartialEq)]
-    /// enum SExpr {
-    ///     Ident(String),
-    ///     Num(u64),
-    ///     List(Vec<SExpr>),
-    /// }
-    ///
-    /// let ident = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
+    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
+    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
+    fn check_with_state(&self, input: I, state: &mut E::State) -> ParseResult<(), E::Error>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::new_state(input, state);
+        let mut inp = own.as_ref_start();
+        let res = self.then_ignore(end()).go::<Check>(&mut inp);
+        let alt = inp.errors.alt.take();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(()) => Some(()),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        ParseResult::new(out, errs)
+    }
+
+    /// Walk the structure of this parser, producing a [`Graph`](graph::Graph) of the parsers it is made of that can
+    /// be rendered with [GraphViz](https://graphviz.org/).
     ///
-    /// let num = text::int(10)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// See the [`graph`] module for more information.
     ///
-    /// let s_expr = recursive(|s_expr| s_expr
-    ///     .padded()
-    ///     .repeated()
-    ///     .collect::<Vec<_>>()
-    ///     .map(SExpr::List)
-    ///     .delimited_by(just('('), just(')'))
-    ///     .or(ident.map(SExpr::Ident))
-    ///     .or(num.map(SExpr::Num)));
+    /// # Examples
     ///
-    /// // A valid input
-    /// assert_eq!(
-    ///     s_expr.parse("(add (mul 42 3) 15)").into_result(),
-    ///     Ok(SExpr::List(vec![
-    ///         SExpr::Ident("add".to_string()),
-    ///         SExpr::List(vec![
-    ///             SExpr::Ident("mul".to_string()),
-    ///             SExpr::Num(42),
-    ///             SExpr::Num(3),
-    ///         ]),
-    ///         SExpr::Num(15),
-    ///     ])),
-    /// );
     /// ```
-    fn delimited_by<U, V, B, C>(self, start: B, end: C) -> DelimitedBy<Self, B, C, U, V>
+    /// # use chumsky::prelude::*;
+    /// let pair = just::<_, &str, extra::Default>('a').then(just('b'));
+    ///
+    /// assert_eq!(pair.graph().to_dot(), "digraph {
+    ///     n0 [label=\"Then\"];
+    ///     n1 [label=\"Just\"];
+    ///     n2 [label=\"Just\"];
+    ///     n0 -> n1;
+    ///     n0 -> n2;
+    /// }");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn graph(&self) -> graph::Graph
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
-        C: Parser<'a, I, V, E>,
     {
-        DelimitedBy {
-            parser: self,
-            start,
-            end,
-            phantom: EmptyPhantom::new(),
-        }
+        let mut graph = graph::Graph::default();
+        self.graph_node(&mut graph);
+        graph
     }
 
-    /// Parse a pattern, but with an instance of another pattern on either end, yielding the output of the inner.
+    /// Analyse the grammar of this parser, looking for mistakes that would make parsing never terminate: rules that
+    /// are left-recursive, and repetitions of parsers that can succeed without consuming input.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// Both problems are found by walking the [`graph`](Parser::graph) of this parser, so this is a best-effort
+    /// analysis: parsers whose grammar is opaque to chumsky, such as [`custom`] parsers, are assumed to consume input
+    /// when they succeed. Left recursion through a [memoised](Parser::memoised) parser is not reported, since it is
+    /// supported.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded_by(just('!'));
+    /// # use chumsky::{prelude::*, graph::Problem};
+    /// let int = text::int::<&str, _, extra::Default>(10).ignored();
+    /// let sum = recursive(|sum| sum.then_ignore(just('+')).then(int).ignored().or(int));
     ///
-    /// assert_eq!(ident.parse("!hello!").into_result(), Ok("hello"));
-    /// assert!(ident.parse("hello!").has_errors());
-    /// assert!(ident.parse("!hello").has_errors());
-    /// assert!(ident.parse("hello").has_errors());
+    /// assert_eq!(
+    ///     sum.analyse(),
+    ///     vec![Problem::LeftRecursion { rule: "recursive 1".to_string() }],
+    /// );
+    ///
+    /// let spaces = just::<_, &str, extra::Default>(' ').or_not().repeated();
+    /// assert_eq!(
+    ///     spaces.analyse()[0].to_string(),
+    ///     "`Repeated` in the root parser repeats a parser that can succeed without consuming input",
+    /// );
     /// ```
-    fn padded_by<U, B>(self, padding: B) -> PaddedBy<Self, B, U>
+    #[cfg(feature = "alloc")]
+    fn analyse(&self) -> Vec<graph::Problem>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
     {
-        PaddedBy {
-            parser: self,
-            padding,
-            phantom: EmptyPhantom::new(),
-        }
+        self.graph().problems()
     }
 
-    /// Parse one thing or, on failure, another thing.
-    ///
-    /// The output of both parsers must be of the same type, because either output can be produced.
+    /// Generate a random input that this parser should accept, by walking the structure of the parser and the parsers
+    /// it is made of. Returns `None` if no input could be generated, such as when the parser contains a [`custom`]
+    /// parser, or when every alternative of a recursive parser recurses.
     ///
-    /// If both parser succeed, the output of the first parser is guaranteed to be prioritised over the output of the
-    /// second.
-    ///
-    /// If both parsers produce errors, the combinator will attempt to select from or combine the errors to produce an
-    /// error that is most likely to be useful to a human attempting to understand the problem. The exact algorithm
-    /// used is left unspecified, and is not part of the crate's semver guarantees, although regressions in error
-    /// quality should be reported in the issue tracker of the main repository.
-    ///
-    /// Please note that long chains of [`Parser::or`] combinators have been known to result in poor compilation times.
-    /// If you feel you are experiencing this, consider using [`choice`] instead.
-    ///
-    /// The output type of this parser is `O`, the output of both parsers.
+    /// See the [`generate`] module for more information.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let op = just::<_, _, extra::Err<Simple<char>>>('+')
-    ///     .or(just('-'))
-    ///     .or(just('*'))
-    ///     .or(just('/'));
-    ///
-    /// assert_eq!(op.parse("+").into_result(), Ok('+'));
-    /// assert_eq!(op.parse("/").into_result(), Ok('/'));
-    /// assert!(op.parse("!").has_errors());
+    /// # use chumsky::{prelude::*, generate::Generator};
+    /// let greeting = just::<_, &str, extra::Default>("hello")
+    ///     .or(just("hi"))
+    ///     .then(just('!').repeated().at_least(1));
+    ///
+    /// let mut gen = Generator::ascii(0);
+    /// let input = greeting.generate(&mut gen).unwrap().into_iter().collect::<String>();
+    /// assert!(input.starts_with('h') && input.ends_with('!'));
+    /// assert!(!greeting.parse(&input).has_errors());
     /// ```
-    fn or<B>(self, other: B) -> Or<Self, B>
+    #[cfg(feature = "alloc")]
+    fn generate(&self, gen: &mut generate::Generator<I::Token>) -> Option<Vec<I::Token>>
     where
         Self: Sized,
-        B: Parser<'a, I, O, E>,
+        I::Token: Clone,
     {
-        Or {
-            choice: choice((self, other)),
+        gen.depth = 0;
+        gen.tokens.clear();
+        self.gen_input(gen).ok()?;
+        Some(core::mem::take(&mut gen.tokens))
+    }
+
+    /// Map from a slice of the input based on the current parser's span to a value.
+    ///
+    /// The returned value may borrow data from the input slice, making this function very useful
+    /// for creating zero-copy AST output values
+    fn map_slice<U, F: Fn(I::Slice) -> U>(self, f: F) -> MapSlice<'a, Self, I, O, E, F, U>
+    where
+        Self: Sized,
+        I: SliceInput<'a>,
+    {
+        MapSlice {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Attempt to parse something, but only if it exists.
+    /// Convert the output of this parser into a slice of the input, based on the current parser's
+    /// span.
     ///
-    /// If parsing of the pattern is successful, the output is `Some(_)`. Otherwise, the output is `None`.
+    /// This is effectively a special case of [`map_slice`](Parser::map_slice)`(|x| x)`
+    fn slice(self) -> Slice<Self, O>
+    where
+        Self: Sized,
+    {
+        Slice {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Filter the output of this parser, accepting only inputs that match the given predicate.
     ///
-    /// The output type of this parser is `Option<O>`.
+    /// The output type of this parser is `I`, the input that was found.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    /// let lowercase = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(char::is_ascii_lowercase)
     ///     .repeated()
     ///     .at_least(1)
     ///     .collect::<String>();
     ///
-    /// let word_or_question = word
-    ///     .then(just('?').or_not());
-    ///
-    /// assert_eq!(word_or_question.parse("hello?").into_result(), Ok(("hello".to_string(), Some('?'))));
-    /// assert_eq!(word_or_question.parse("wednesday").into_result(), Ok(("wednesday".to_string(), None)));
+    /// assert_eq!(lowercase.parse("hello").into_result(), Ok("hello".to_string()));
+    /// assert!(lowercase.parse("Hello").has_errors());
     /// ```
-    fn or_not(self) -> OrNot<Self>
+    fn filter<F: Fn(&O) -> bool>(self, f: F) -> Filter<Self, F>
     where
         Self: Sized,
     {
-        OrNot { parser: self }
+        Filter {
+            parser: self,
+            filter: f,
+        }
     }
 
+    /// Map the output of this parser to another value.
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
+    ///
+    /// # Examples
+    ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    ///
     /// #[derive(Debug, PartialEq)]
-    /// enum Tree<'a> {
-    ///     Text(&'a str),
-    ///     Group(Vec<Self>),
-    /// }
+    /// enum Token { Word(String), Num(u64) }
     ///
-    /// // Arbitrary text, nested in a tree with { ... } delimiters
-    /// let tree = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|tree| {
-    ///     let text = any()
-    ///         .and_is(one_of("{}").not())
-    ///         .repeated()
-    ///         .at_least(1)
-    ///         .map_slice(Tree::Text);
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated().at_least(1)
+    ///     .collect::<String>()
+    ///     .map(Token::Word);
     ///
-    ///     let group = tree
-    ///         .repeated()
-    ///         .collect()
-    ///         .delimited_by(just('{'), just('}'))
-    ///         .map(Tree::Group);
+    /// let num = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated().at_least(1)
+    ///     .collect::<String>()
+    ///     .map(|s| Token::Num(s.parse().unwrap()));
     ///
-    ///     text.or(group)
-    /// });
+    /// let token = word.or(num);
     ///
-    /// assert_eq!(
-    ///     tree.parse("{abcd{efg{hijk}lmn{opq}rs}tuvwxyz}").into_result(),
-    ///     Ok(Tree::Group(vec![
-    ///         Tree::Text("abcd"),
-    ///         Tree::Group(vec![
-    ///             Tree::Text("efg"),
-    ///             Tree::Group(vec![
-    ///                 Tree::Text("hijk"),
-    ///             ]),
-    ///             Tree::Text("lmn"),
-    ///             Tree::Group(vec![
-    ///                 Tree::Text("opq"),
-    ///             ]),
-    ///             Tree::Text("rs"),
-    ///         ]),
-    ///         Tree::Text("tuvwxyz"),
-    ///     ])),
-    /// );
+    /// assert_eq!(token.parse("test").into_result(), Ok(Token::Word("test".to_string())));
+    /// assert_eq!(token.parse("42").into_result(), Ok(Token::Num(42)));
     /// ```
-    fn not(self) -> Not<Self, O>
+    fn map<U, F: Fn(O) -> U>(self, f: F) -> Map<Self, O, F>
     where
         Self: Sized,
     {
-        Not {
+        Map {
             parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern any number of times (including zero times).
+    /// Map the tuple output of this parser to another value, passing each element of the tuple as a separate argument
+    /// to the function.
     ///
-    /// Input is eagerly parsed. Be aware that the parser will accept no occurences of the pattern too. Consider using
-    /// [`Repeated::at_least`] instead if it better suits your use-case.
+    /// This avoids the need for tuple-destructuring patterns when mapping the output of parsers like [`group`] or
+    /// [`Parser::then`], and allows constructor functions like `Point::new` to be used directly.
     ///
-    /// The output type of this parser can be any [`Container`].
+    /// Note that, unlike [`Parser::map`], Rust is unable to infer the types of closure arguments from the parser's output
+    /// ahead of time: if your closure calls methods on its arguments, you may need to annotate their types.
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let num = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>()
-    ///     .from_str()
-    ///     .unwrapped();
+    /// #[derive(Debug, PartialEq)]
+    /// struct Point { x: u32, y: u32, z: u32 }
     ///
-    /// let sum = num.clone()
-    ///     .foldl(just('+').ignore_then(num).repeated(), |a, b| a + b);
+    /// impl Point {
+    ///     fn new(x: u32, y: u32, z: u32) -> Self { Self { x, y, z } }
+    /// }
     ///
-    /// assert_eq!(sum.parse("2+13+4+0+5").into_result(), Ok(24));
+    /// let coord = text::int::<_, _, extra::Err<Simple<char>>>(10).from_str::<u32>().unwrapped();
+    /// let point = group((coord, just(',').ignore_then(coord), just(',').ignore_then(coord)))
+    ///     .map_group(Point::new);
+    ///
+    /// assert_eq!(point.parse("1,2,3").into_result(), Ok(Point { x: 1, y: 2, z: 3 }));
+    ///
+    /// let sum = coord.then_ignore(just('+')).then(coord).map_group(|a: u32, b: u32| a + b);
+    ///
+    /// assert_eq!(sum.parse("40+2").into_result(), Ok(42));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn repeated(self) -> Repeated<Self, O, I, E>
+    fn map_group<U, F: GroupFn<O, U>>(self, f: F) -> MapGroup<Self, O, F>
     where
         Self: Sized,
     {
-        Repeated {
+        MapGroup {
             parser: self,
-            at_least: 0,
-            at_most: !0,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern, separated by another, any number of times.
+    /// Map the output of this parser to another value, making use of the pattern's span when doing so.
     ///
-    /// You can use [`SeparatedBy::allow_leading`] or [`SeparatedBy::allow_trailing`] to allow leading or trailing
-    /// separators.
+    /// This is very useful when generating an AST that attaches a span to each AST node.
     ///
-    /// The output type of this parser can be any [`Container`].
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let shopping = text::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded()
-    ///     .separated_by(just(','))
-    ///     .collect::<Vec<_>>();
+    /// # use chumsky::prelude::*;
+    /// use std::ops::Range;
     ///
-    /// assert_eq!(shopping.parse("eggs").into_result(), Ok(vec!["eggs"]));
-    /// assert_eq!(shopping.parse("eggs, flour, milk").into_result(), Ok(vec!["eggs", "flour", "milk"]));
-    /// ```
+    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
+    /// #[derive(Debug, PartialEq)]
+    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
     ///
-    /// See [`SeparatedBy::allow_leading`] and [`SeparatedBy::allow_trailing`] for more examples.
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn separated_by<U, B>(self, separator: B) -> SeparatedBy<Self, B, O, U, I, E>
+    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .map_with_span(|ident, span| Spanned(ident, span))
+    ///     .padded();
+    ///
+    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
+    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
+    /// ```
+    fn map_with_span<U, F: Fn(O, I::Span) -> U>(self, f: F) -> MapWithSpan<Self, O, F>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
     {
-        SeparatedBy {
+        MapWithSpan {
             parser: self,
-            separator,
-            at_least: 0,
-            at_most: !0,
-            allow_leading: false,
-            allow_trailing: false,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Left-fold the output of the parser into a single value.
+    /// Map the output of this parser to another value, making use of the parser's state when doing so.
     ///
-    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    /// This is very useful for parsing non context-free grammars.
     ///
-    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// # use chumsky::prelude::*;
+    /// use std::ops::Range;
     ///
-    /// let sum = int
-    ///     .clone()
-    ///     .foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
+    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
+    /// #[derive(Debug, PartialEq)]
+    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
     ///
-    /// assert_eq!(sum.parse("1+12+3+9").into_result(), Ok(25));
-    /// assert_eq!(sum.parse("6").into_result(), Ok(6));
+    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .map_with_span(|ident, span| Spanned(ident, span))
+    ///     .padded();
+    ///
+    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
+    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldl<B, F, OB>(self, other: B, f: F) -> Foldl<F, Self, B, OB, E>
+    fn map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> U>(
+        self,
+        f: F,
+    ) -> MapWithState<Self, O, F>
     where
-        F: Fn(O, OB) -> O,
-        B: IterParser<'a, I, OB, E>,
         Self: Sized,
     {
-        Foldl {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        MapWithState {
+            parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Left-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    /// Map the output of this parser to another value, making use of the arena provided by the parser's state.
     ///
-    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    /// The state must implement [`ArenaState`](arena::ArenaState), such as a plain `&'bump Bump`. Because the arena
+    /// is given to the function with its full lifetime, the output may borrow from it: this is typically used to
+    /// allocate syntax tree nodes within the arena. See the [`arena`] module for more information.
     ///
-    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let sum = int
-    ///     .clone()
-    ///     .foldl_with_state(just('+').ignore_then(int).repeated(), |a, b, state| (a + b) * *state);
+    /// # use chumsky::{prelude::*, arena::Bump};
+    /// fn digit<'src, 'bump: 'src>() -> impl Parser<'src, &'src str, &'bump u32, extra::State<&'bump Bump>> {
+    ///     any()
+    ///         .filter(|c: &char| c.is_ascii_digit())
+    ///         .map_with_arena(|c, bump: &'bump Bump| &*bump.alloc(c.to_digit(10).unwrap()))
+    /// }
     ///
-    /// let mut multiplier = 2i32;
-    /// assert_eq!(sum.parse_with_state("1+12+3+9", &mut multiplier).into_result(), Ok(134));
-    /// assert_eq!(sum.parse_with_state("6", &mut multiplier).into_result(), Ok(6));
+    /// let bump = Bump::new();
+    /// assert_eq!(digit().parse_with_state("7", &mut &bump).into_result(), Ok(&7));
     /// ```
-    // TODO: Add examples of interning/arena allocation
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldl_with_state<B, F, OB>(self, other: B, f: F) -> FoldlWithState<F, Self, B, OB, E>
+    #[cfg(feature = "bumpalo")]
+    fn map_with_arena<'bump, U, F: Fn(O, &'bump arena::Bump) -> U>(
+        self,
+        f: F,
+    ) -> arena::MapWithArena<'bump, Self, O, F>
     where
-        F: Fn(O, OB, &mut E::State) -> O,
-        B: IterParser<'a, I, OB, E>,
         Self: Sized,
     {
-        FoldlWithState {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        arena::MapWithArena {
+            parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern. Afterwards, the input stream will be rewound to its original state, as if parsing had not
-    /// occurred.
+    /// After a successful parse, apply a fallible function to the output. If the function produces an error, treat it
+    /// as a parsing error.
     ///
-    /// This combinator is useful for cases in which you wish to avoid a parser accidentally consuming too much input,
-    /// causing later parsers to fail as a result. A typical use-case of this is that you want to parse something that
-    /// is not followed by something else.
+    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
+    /// [`Parser::validate`] instead.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let just_numbers = text::digits::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .slice()
-    ///     .padded()
-    ///     .then_ignore(none_of("+-*/").rewind())
-    ///     .separated_by(just(','))
-    ///     .collect::<Vec<_>>();
-    /// // 3 is not parsed because it's followed by '+'.
-    /// assert_eq!(just_numbers.lazy().parse("1, 2, 3 + 4").into_result(), Ok(vec!["1", "2"]));
+    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .try_map(|s: &str, span| s
+    ///         .parse::<u8>()
+    ///         .map_err(|e| Rich::custom(span, e)));
+    ///
+    /// assert!(byte.parse("255").has_output());
+    /// assert!(byte.parse("256").has_errors()); // Out of range
     /// ```
-    fn rewind(self) -> Rewind<Self>
+    #[doc(alias = "filter_map")]
+    fn try_map<U, F: Fn(O, I::Span) -> Result<U, E::Error>>(self, f: F) -> TryMap<Self, O, F>
     where
         Self: Sized,
     {
-        Rewind { parser: self }
+        TryMap {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Make the parser lazy, such that it parses as much as it validly can and then finished successfully, leaving
-    /// trailing input untouched.
+    /// After a successful parse, apply a fallible function to the output, making use of the parser's state when
+    /// doing so. If the function produces an error, treat it as a parsing error.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
+    /// [`Parser::validate`] instead.
+    ///
+    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    fn try_map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> Result<U, E::Error>>(
+        self,
+        f: F,
+    ) -> TryMapWithState<Self, O, F>
+    where
+        Self: Sized,
+    {
+        TryMapWithState {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Map the output of this parser to another value, making use of the parser's context (see
+    /// [`Parser::then_with_ctx`]) when doing so, such as the current level of indentation.
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
-    ///     .repeated()
-    ///     .collect::<String>()
-    ///     .lazy();
+    /// #[derive(Debug, PartialEq)]
+    /// struct Line<'a> {
+    ///     indent: usize,
+    ///     text: &'a str,
+    /// }
     ///
-    /// assert_eq!(digits.parse("12345abcde").into_result().as_deref(), Ok("12345"));
+    /// let text = text::ident::<_, _, extra::Context<usize>>()
+    ///     .map_with_ctx(|text, _, indent: &usize| Line { indent: *indent, text });
+    /// let line = just::<_, &str, extra::Default>(' ').repeated().count().then_with_ctx(text);
+    ///
+    /// assert_eq!(line.parse("   hello").into_result(), Ok(Line { indent: 3, text: "hello" }));
     /// ```
-    fn lazy(self) -> Lazy<'a, Self, I, E>
+    fn map_with_ctx<U, F: Fn(O, I::Span, &E::Context) -> U>(self, f: F) -> MapWithCtx<Self, O, F>
     where
         Self: Sized,
-        I: ValueInput<'a>,
     {
-        self.then_ignore(any().repeated())
+        MapWithCtx {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Parse a pattern, ignoring any amount of whitespace both before and after the pattern.
+    /// Filter the output of this parser, accepting only outputs that match the given predicate, which can make use of
+    /// the parser's context (see [`Parser::then_with_ctx`]).
     ///
     /// The output type of this parser is `O`, the same as the original parser.
     ///
@@ -1392,1135 +1771,5744 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    /// // A string that can be quoted with any of several characters, and can contain the others
+    /// let contents = any::<_, extra::Full<EmptyErr, (), char>>()
+    ///     .filter_with_ctx(|c: &char, quote: &char| c != quote)
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .then_ignore(any().filter_with_ctx(|c: &char, quote: &char| c == quote));
+    /// let string = one_of::<_, &str, extra::Default>("'\"`").then_with_ctx(contents);
     ///
-    /// // A pattern with no whitespace surrounding it is accepted
-    /// assert_eq!(ident.parse("hello").into_result(), Ok("hello"));
-    /// // A pattern with arbitrary whitespace surrounding it is also accepted
-    /// assert_eq!(ident.parse(" \t \n  \t   world  \t  ").into_result(), Ok("world"));
+    /// assert_eq!(string.parse("`it's \"quoted\"`").into_result(), Ok("it's \"quoted\"".to_string()));
+    /// assert!(string.parse("'it's'").has_errors());
     /// ```
-    fn padded(self) -> Padded<Self>
+    fn filter_with_ctx<F: Fn(&O, &E::Context) -> bool>(self, f: F) -> FilterWithCtx<Self, F>
     where
         Self: Sized,
-        I: Input<'a>,
-        I::Token: Char,
     {
-        Padded { parser: self }
+        FilterWithCtx {
+            parser: self,
+            filter: f,
+        }
     }
 
-    // /// Flatten a nested collection.
-    // ///
-    // /// This use-cases of this method are broadly similar to those of [`Iterator::flatten`].
-    // ///
-    // /// The output type of this parser is `Vec<T>`, where the original parser output was
-    // /// `impl IntoIterator<Item = impl IntoIterator<Item = T>>`.
-    // fn flatten<T, Inner>(self) -> Map<Self, O, fn(O) -> Vec<T>>
-    // where
-    //     Self: Sized,
-    //     O: IntoIterator<Item = Inner>,
-    //     Inner: IntoIterator<Item = T>,
-    // {
-    //     self.map(|xs| xs.into_iter().flat_map(|xs| xs.into_iter()).collect())
-    // }
-
-    /// Apply a fallback recovery strategy to this parser should it fail.
+    /// Enter a new lexical scope of the parser's state before parsing this pattern, and leave it afterwards, whether or
+    /// not the pattern succeeds. The state must implement [`ScopeState`](state::ScopeState), or be a tuple with a
+    /// component that does, selected by `N` (see [`ScopeSlot`](state::ScopeSlot)).
     ///
-    /// There is no silver bullet for error recovery, so this function allows you to specify one of several different
-    /// strategies at the location of your choice. Prefer an error recovery strategy that more precisely mirrors valid
-    /// syntax where possible to make error recovery more reliable.
+    /// This is typically used for blocks that have their own scope of declarations. See the [`state`] module for more
+    /// information.
     ///
-    /// Because chumsky is a [PEG](https://en.m.wikipedia.org/wiki/Parsing_expression_grammar) parser, which always
-    /// take the first successful parsing route through a grammar, recovering from an error may cause the parser to
-    /// erroneously miss alternative valid routes through the grammar that do not generate recoverable errors. If you
-    /// run into cases where valid syntax fails to parse without errors, this might be happening: consider removing
-    /// error recovery or switching to a more specific error recovery strategy.
+    /// The output type of this parser is `O`, the same as the original parser.
+    fn with_scope<N>(self) -> state::WithScope<Self, N>
+    where
+        Self: Sized,
+        E::State: state::ScopeSlot<N>,
+    {
+        state::WithScope {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Undo the changes this pattern makes to the parser's state if it fails to parse. The state must implement
+    /// [`Rollback`](state::Rollback), or be a tuple with a component that does, selected by `N` (see
+    /// [`RollbackSlot`](state::RollbackSlot)).
+    ///
+    /// Parsers like [`Parser::map_with_state`] change the state as soon as they succeed, even if a parser that contains
+    /// them later fails and backtracks (such as one branch of a [`choice`]), which can leave behind stale entries like
+    /// the declarations of a statement that turned out not to be one. Wrapping each alternative in `transactional`
+    /// rolls these changes back, so the next alternative starts from the state as it was.
     ///
     /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Debug, PartialEq)]
-    /// enum Expr<'a> {
-    ///     Error,
-    ///     Int(&'a str),
-    ///     List(Vec<Expr<'a>>),
-    /// }
+    /// # use chumsky::prelude::*;
+    /// // The names declared so far
+    /// type Names = Vec<String>;
     ///
-    /// let recovery = just::<_, _, extra::Err<Simple<char>>>('[')
-    ///         .then(none_of(']').repeated().then(just(']')));
-    ///
-    /// let expr = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|expr| expr
-    ///     .separated_by(just(','))
-    ///     .collect::<Vec<_>>()
-    ///     .delimited_by(just('['), just(']'))
-    ///     .map(Expr::List)
-    ///     // If parsing a list expression fails, recover at the next delimiter, generating an error AST node
-    ///     .recover_with(via_parser(recovery.map(|_| Expr::Error)))
-    ///     .or(text::int(10).map(Expr::Int))
-    ///     .padded());
+    /// let declare = text::ident::<_, _, extra::Full<Rich<char>, Names, ()>>()
+    ///     .try_map_with_state(|name: &str, _, names: &mut Names| {
+    ///         names.push(name.to_string());
+    ///         Ok(())
+    ///     });
+    /// // A statement is either a declaration with a type (`x: int`), or a name on its own
+    /// let decl = declare.then_ignore(just(':').padded()).then_ignore(text::ident());
+    /// let stmt = decl.transactional().or(text::ident().ignored());
     ///
-    /// assert!(expr.parse("five").has_errors()); // Text is not a valid expression in this language...
-    /// assert_eq!(
-    ///     expr.parse("[1, 2, 3]").into_result(),
-    ///     Ok(Expr::List(vec![Expr::Int("1"), Expr::Int("2"), Expr::Int("3")])),
-    /// ); // ...but lists and numbers are!
+    /// let mut names = Names::new();
+    /// assert!(!stmt.parse_with_state("x", &mut names).has_errors());
+    /// // Without `transactional`, the failed declaration would have left `x` behind
+    /// assert!(names.is_empty());
     ///
-    /// // This input has two syntax errors...
-    /// let res = expr.parse("[[1, two], [3, four]]");
-    /// // ...and error recovery allows us to catch both of them!
-    /// assert_eq!(res.errors().len(), 2);
-    /// // Additionally, the AST we get back still has useful information.
-    /// assert_eq!(res.output(), Some(&Expr::List(vec![Expr::Error, Expr::Error])));
+    /// assert!(!stmt.parse_with_state("y: int", &mut names).has_errors());
+    /// assert_eq!(names, ["y"]);
     /// ```
-    fn recover_with<S: Strategy<'a, I, O, E>>(self, strategy: S) -> RecoverWith<Self, S>
+    fn transactional<N>(self) -> state::Transactional<Self, N>
     where
         Self: Sized,
+        E::State: state::RollbackSlot<N>,
     {
-        RecoverWith {
+        state::Transactional {
             parser: self,
-            strategy,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the primary error of this parser to another value.
+    /// Intern the output of this parser (such as an identifier) with the [`Interner`](state::Interner) in the parser's
+    /// state, producing its [`Symbol`](state::Symbol).
     ///
-    /// This function is most useful when using a custom error type, allowing you to augment errors according to
-    /// context.
+    /// The state must be an [`Interner`](state::Interner), implement [`BorrowMut<Interner>`](core::borrow::BorrowMut),
+    /// or be a tuple with an `Interner` component (see [`Slot`](state::Slot)). See the [`state`] module for more
+    /// information.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    // TODO: Map E -> D, not E -> E
-    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    /// The output type of this parser is [`Symbol`](state::Symbol).
+    #[cfg(feature = "alloc")]
+    fn intern<N>(self) -> state::Intern<Self, O, N>
     where
         Self: Sized,
-        F: Fn(E::Error) -> E::Error,
+        O: AsRef<str>,
+        E::State: state::Slot<state::Interner, N>,
     {
-        MapErr {
+        state::Intern {
             parser: self,
-            mapper: f,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    // /// Map the primary error of this parser to another value, making use of the span from the start of the attempted
-    // /// to the point at which the error was encountered.
-    // ///
-    // /// This function is useful for augmenting errors to allow them to display the span of the initial part of a
-    // /// pattern, for example to add a "while parsing" clause to your error messages.
-    // ///
-    // /// The output type of this parser is `O`, the same as the original parser.
-    // ///
-    // // TODO: Map E -> D, not E -> E
-    // fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
-    // where
-    //     Self: Sized,
-    //     F: Fn(E::Error, I::Span) -> E::Error,
-    // {
-    //     MapErrWithSpan {
-    //         parser: self,
-    //         mapper: f,
-    //     }
-    // }
-
-    /// Map the primary error of this parser to another value, making use of the parser state.
+    /// Declare a name in the innermost scope of the [`SymbolTable`](state::SymbolTable) in the parser's state. This
+    /// parser's output is the name, and what it refers to (such as its type, or the span of its declaration).
     ///
-    /// This function is useful for augmenting errors to allow them to include context in non context-free
-    /// languages, or provide contextual notes on possible causes.
+    /// The declaration is kept even if a parser that contains this one later backtracks, unless it is undone with
+    /// [`Parser::transactional`]. The state must be a [`SymbolTable`](state::SymbolTable), implement
+    /// [`BorrowMut<SymbolTable>`](core::borrow::BorrowMut), or be a tuple with a `SymbolTable` component (see
+    /// [`Slot`](state::Slot)).
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The output type of this parser is `K`, the declared name.
+    #[cfg(feature = "alloc")]
+    fn declare<K, V, N>(self) -> state::Declare<Self, K, V, N>
+    where
+        Self: Sized + Parser<'a, I, (K, V), E>,
+        K: Hash + Eq + Clone,
+        E::State: state::Slot<state::SymbolTable<K, V>, N>,
+    {
+        state::Declare {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Resolve the name produced by this parser with the [`SymbolTable`](state::SymbolTable) in the parser's state,
+    /// producing what it refers to in the innermost scope that declares it.
     ///
-    // TODO: Map E -> D, not E -> E
-    fn map_err_with_state<F>(self, f: F) -> MapErrWithState<Self, F>
+    /// If the name has not been declared, the parser fails with an `unresolved name` error (created with
+    /// [`Error::custom`]) that spans the name. Use [`Parser::try_map_with_state`] with
+    /// [`SymbolTable::resolve`](state::SymbolTable::resolve) instead for a more specific error.
+    ///
+    /// The output type of this parser is `V`, what the name refers to.
+    #[cfg(feature = "alloc")]
+    fn resolve<V, N>(self) -> state::Resolve<Self, O, V, N>
     where
         Self: Sized,
-        F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+        O: Hash + Eq,
+        V: Clone,
+        E::State: state::Slot<state::SymbolTable<O, V>, N>,
     {
-        MapErrWithState {
+        state::Resolve {
             parser: self,
-            mapper: f,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Validate an output, producing non-terminal errors if it does not fulfil certain criteria.
-    ///
-    /// This function also permits mapping the output to a value of another type, similar to [`Parser::map`].
+    /// Ignore the output of this parser, yielding `()` as an output instead.
     ///
-    /// If you wish parsing of this pattern to halt when an error is generated instead of continuing, consider using
-    /// [`Parser::try_map`] instead.
+    /// This can be used to reduce the cost of parsing by avoiding unnecessary allocations (most collections containing
+    /// [ZSTs](https://doc.rust-lang.org/nomicon/exotic-sizes.html#zero-sized-types-zsts)
+    /// [do not allocate](https://doc.rust-lang.org/std/vec/struct.Vec.html#guarantees)). For example, it's common to
+    /// want to ignore whitespace in many grammars (see [`text::whitespace`]).
     ///
-    /// The output type of this parser is `U`, the result of the validation closure.
+    /// The output type of this parser is `()`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let large_int = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped()
-    ///     .validate(|x: u32, span, emitter| {
-    ///         if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher.", x))) }
-    ///         x
-    ///     });
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// // A parser that parses any number of whitespace characters without allocating
+    /// let whitespace = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_whitespace())
+    ///     .ignored()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
     ///
-    /// assert_eq!(large_int.parse("537").into_result(), Ok(537));
-    /// assert!(large_int.parse("243").into_result().is_err());
+    /// assert_eq!(whitespace.parse("    ").into_result(), Ok(vec![(); 4]));
+    /// assert!(whitespace.parse("  hello").has_errors());
     /// ```
-    fn validate<U, F>(self, f: F) -> Validate<Self, O, F>
+    fn ignored(self) -> Ignored<Self, O>
     where
         Self: Sized,
-        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
     {
-        Validate {
+        Ignored {
             parser: self,
-            validator: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    // /// Map the primary error of this parser to a result. If the result is [`Ok`], the parser succeeds with that value.
-    // ///
-    // /// Note that, if the closure returns [`Err`], the parser will not consume any input.
-    // ///
-    // /// The output type of this parser is `U`, the [`Ok`] type of the result.
-    // fn or_else<F>(self, f: F) -> OrElse<Self, F>
-    // where
-    //     Self: Sized,
-    //     F: Fn(E::Error) -> Result<O, E::Error>,
-    // {
-    //     OrElse {
-    //         parser: self,
-    //         or_else: f,
-    //     }
-    // }
+    /// Memoise the parser such that later attempts to parse the same input 'remember' the attempt and exit early.
+    ///
+    /// If you're finding that certain inputs produce exponential behaviour in your parser, strategically applying
+    /// memoisation to a ['garden path'](https://en.wikipedia.org/wiki/Garden-path_sentence) rule is often an effective
+    /// way to solve the problem. At the limit, applying memoisation to all combinators will turn any parser into one
+    /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
+    ///
+    /// Memoisation also works with recursion, so this can be used to write parsers using
+    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
+    ///
+    /// Memoised results are only shared within a context. Every use of a [`Parser::with_ctx`] parser shares the same
+    /// context, while [`Parser::then_with_ctx`] and [`map_ctx`] start a new context each time they are used, whose
+    /// memoised results are discarded once it ends.
+    // TODO: Example
+    #[cfg(feature = "memoization")]
+    fn memoised(self) -> Memoised<Self>
+    where
+        Self: Sized,
+    {
+        Memoised { parser: self }
+    }
 
-    /// Attempt to convert the output of this parser into something else using Rust's [`FromStr`] trait.
+    /// Limit the number of memoised results that are kept while this parser runs, according to a [`MemoBound`].
     ///
-    /// This is most useful when wanting to convert literal values into their corresponding Rust type, such as when
-    /// parsing integers.
+    /// By default, every result memoised by [`Parser::memoised`] is kept until the parse finishes, so the memory used
+    /// by memoisation grows with the length of the input multiplied by the number of memoised parsers. For long
+    /// inputs, this is usually applied to the top-level parser to cap that memory. Results evicted from the table will
+    /// be recomputed if they are needed again, so a bound that is too small may reintroduce exponential behaviour.
     ///
-    /// The output type of this parser is `Result<U, U::Err>`, the result of attempting to parse the output, `O`, into
-    /// the value `U`.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let uint64 = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str::<u64>()
-    ///     .unwrapped();
+    /// # use chumsky::{prelude::*, combinator::MemoBound};
+    /// let expr = recursive(|expr| {
+    ///     let atom = text::int::<_, _, extra::Default>(10).or(expr.delimited_by(just('('), just(')')));
+    ///     atom.clone()
+    ///         .then_ignore(just('+'))
+    ///         .then(atom.clone())
+    ///         .slice()
+    ///         .memoised()
+    ///         .or(atom)
+    /// });
     ///
-    /// assert_eq!(uint64.parse("7").into_result(), Ok(7));
-    /// assert_eq!(uint64.parse("42").into_result(), Ok(42));
+    /// // Only keep memoised results for the last 64 characters of input
+    /// let parser = expr.then_ignore(end()).memo_bound(MemoBound::Window(64));
+    ///
+    /// assert_eq!(parser.parse("((((((1+2))))))").into_result(), Ok("1+2"));
     /// ```
-    #[allow(clippy::wrong_self_convention)]
-    fn from_str<U>(self) -> Map<Self, O, fn(O) -> Result<U, U::Err>>
+    #[cfg(feature = "memoization")]
+    fn memo_bound(self, bound: MemoBound) -> MemoBounded<Self>
     where
         Self: Sized,
-        U: FromStr,
-        O: AsRef<str>,
     {
-        self.map(|o| o.as_ref().parse())
+        MemoBounded {
+            parser: self,
+            bound,
+        }
     }
 
-    /// For parsers that produce a [`Result`] as their output, unwrap the result (panicking if an [`Err`] is
-    /// encountered).
-    ///
-    /// In general, this method should be avoided except in cases where all possible that the parser might produce can
-    /// by parsed using [`FromStr`] without producing an error.
-    ///
-    /// This combinator is not named `unwrap` to avoid confusion: it unwraps *during parsing*, not immediately.
+    /// Transform all outputs of this parser to a pretermined value.
     ///
-    /// The output type of this parser is `U`, the [`Ok`] value of the [`Result`].
+    /// The output type of this parser is `U`, the type of the predetermined value.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let boolean = just::<_, _, extra::Err<Simple<char>>>("true")
-    ///     .or(just("false"))
-    ///     .from_str::<bool>()
-    ///     .unwrapped(); // Cannot panic: the only possible outputs generated by the parser are "true" or "false"
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Op { Add, Sub, Mul, Div }
     ///
-    /// assert_eq!(boolean.parse("true").into_result(), Ok(true));
-    /// assert_eq!(boolean.parse("false").into_result(), Ok(false));
-    /// // Does not panic, because the original parser only accepts "true" or "false"
-    /// assert!(boolean.parse("42").has_errors());
+    /// let op = just::<_, _, extra::Err<Simple<char>>>('+').to(Op::Add)
+    ///     .or(just('-').to(Op::Sub))
+    ///     .or(just('*').to(Op::Mul))
+    ///     .or(just('/').to(Op::Div));
+    ///
+    /// assert_eq!(op.parse("+").into_result(), Ok(Op::Add));
+    /// assert_eq!(op.parse("/").into_result(), Ok(Op::Div));
     /// ```
-    #[track_caller]
-    fn unwrapped(self) -> Unwrapped<Self, O>
+    fn to<U: Clone>(self, to: U) -> To<Self, O, U>
     where
         Self: Sized,
     {
-        Unwrapped {
+        To {
             parser: self,
-            location: *Location::caller(),
+            to,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Box the parser, yielding a parser that performs parsing through dynamic dispatch.
-    ///
-    /// Boxing a parser might be useful for:
+    /// Label this parser with the given label.
     ///
-    /// - Dynamically building up parsers at run-time
+    /// Labelling a parser makes all errors generated by the parser refer to the label rather than any sub-elements
+    /// within the parser. For example, labelling a parser for an expression would yield "expected expression" errors
+    /// rather than "expected integer, string, binary op, etc." errors.
+    // TODO: Example
+    #[cfg(feature = "label")]
+    fn labelled<L>(self, label: L) -> Labelled<Self, L>
+    where
+        Self: Sized,
+        E::Error: LabelError<'a, I, L>,
+    {
+        Labelled {
+            parser: self,
+            label,
+            is_context: false,
+        }
+    }
+
+    /// Give this parser a name, identifying it to the tools that inspect parsers.
     ///
-    /// - Improving compilation times (Rust can struggle to compile code containing very long types)
+    /// Unlike [`Parser::labelled`], naming a parser has no effect on the errors it produces. Instead, the name is used
+    /// to refer to the parser:
     ///
-    /// - Passing a parser over an FFI boundary
+    /// - In the events emitted by [`Parser::parse_traced`], and the statistics recorded by
+    ///   [`Parser::parse_profiled`]: named parsers are always traced and profiled, as if they had been labelled and
+    ///   then marked with [`Labelled::traced`] and [`Labelled::profiled`].
     ///
-    /// - Getting around compiler implementation problems with long types such as
-    ///   [this](https://github.com/rust-lang/rust/issues/54540).
+    /// - In the [`graph`](Parser::graph) of a parser, and the DOT, EBNF, and railroad diagrams produced from it, where
+    ///   named parsers become rules of the grammar.
     ///
-    /// - Places where you need to name the type of a parser
+    /// # Examples
     ///
-    /// Boxing a parser is broadly equivalent to boxing other combinators via dynamic dispatch, such as [`Iterator`].
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ident::<&str, _, extra::Default>().named("ident");
+    /// let path = ident.separated_by(just("::")).collect::<Vec<_>>().named("path");
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
+    /// assert_eq!(path.graph().to_ebnf(), "path = [ ident , { ? Just ? , ident } ] ;\nident = ? Ident ? ;\n");
+    /// ```
+    fn named(self, name: &'static str) -> Named<Self>
     where
-        Self: MaybeSync + Sized + 'a + 'b,
+        Self: Sized,
     {
-        ParserSealed::boxed(self)
+        Named { parser: self, name }
     }
 
-    /// Use Pratt parsing to efficiently parse binary operators
-    /// with different associativity.
+    /// Record the input parsed by this parser as a node of the concrete syntax tree built by [`Parser::parse_cst`],
+    /// containing the nodes and tokens recorded by the parsers within it.
     ///
-    /// The parsing algorithm currently uses recursion
-    /// to parse nested expressions.
+    /// See the [`cst`] module for more information.
     ///
-    /// # Examples
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "cst")]
+    fn syntax_node(self, kind: impl Into<cst::SyntaxKind>) -> cst::SyntaxNode<Self>
+    where
+        Self: Sized,
+    {
+        cst::SyntaxNode {
+            parser: self,
+            kind: kind.into(),
+        }
+    }
+
+    /// Record the input parsed by this parser as a token of the concrete syntax tree built by [`Parser::parse_cst`].
     ///
-    /// ```
-    /// use chumsky::prelude::*;
-    /// use chumsky::pratt::{InfixOperator, InfixPrecedence, Associativity};
+    /// Tokens are the leaves of the tree, so any nodes or tokens recorded by the parsers within this one are
+    /// discarded. See the [`cst`] module for more information.
     ///
-    /// #[derive(Clone, Copy, Debug)]
-    /// enum Operator {
-    ///     Add,
-    ///     Sub,
-    ///     Mul,
-    ///     Div,
-    /// }
-    ///
-    /// enum Expr {
-    ///     Literal(i64),
-    ///     Add(Box<Expr>, Box<Expr>),
-    ///     Sub(Box<Expr>, Box<Expr>),
-    ///     Mul(Box<Expr>, Box<Expr>),
-    ///     Div(Box<Expr>, Box<Expr>),
-    /// }
-    ///
-    /// impl std::fmt::Display for Expr {
-    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    ///         match self {
-    ///             Self::Literal(literal) => write!(f, "{literal}"),
-    ///             Self::Add(left, right) => write!(f, "({left} + {right})"),
-    ///             Self::Sub(left, right) => write!(f, "({left} - {right})"),
-    ///             Self::Mul(left, right) => write!(f, "({left} * {right})"),
-    ///             Self::Div(left, right) => write!(f, "({left} / {right})"),
-    ///         }
-    ///     }
-    /// }
-    ///
-    /// impl InfixOperator<Expr> for Operator {
-    ///     type Strength = u8;
-    ///
-    ///     fn precedence(&self) -> InfixPrecedence<Self::Strength> {
-    ///         // NOTE: Usually, in Rust for example, all these operators
-    ///         // are left-associative. However, in this example we define
-    ///         // then with different associativities for demonstration purposes.
-    ///         // (Although it doesn't really matter here since these operations
-    ///         // are commutative for integers anyway.)
-    ///         match self {
-    ///             Self::Add => InfixPrecedence::new(0, Associativity::Left),
-    ///             Self::Sub => InfixPrecedence::new(0, Associativity::Left),
-    ///             Self::Mul => InfixPrecedence::new(1, Associativity::Right),
-    ///             Self::Div => InfixPrecedence::new(1, Associativity::Right),
-    ///         }
-    ///     }
-    ///
-    ///     fn build_expression(self, left: Expr, right: Expr) -> Expr {
-    ///         let (left, right) = (Box::new(left), Box::new(right));
-    ///         match self {
-    ///             Self::Add => Expr::Add(left, right),
-    ///             Self::Sub => Expr::Sub(left, right),
-    ///             Self::Mul => Expr::Mul(left, right),
-    ///             Self::Div => Expr::Div(left, right),
-    ///         }
-    ///     }
-    /// }
-    ///
-    /// let atom = text::int::<_, _, extra::Default>(10)
-    ///     .from_str()
-    ///     .unwrapped()
-    ///     .map(Expr::Literal);
-    ///
-    /// let operator = choice((
-    ///     just('+').to(Operator::Add),
-    ///     just('-').to(Operator::Sub),
-    ///     just('*').to(Operator::Mul),
-    ///     just('/').to(Operator::Div),
-    /// ));
-    ///
-    /// let expr = atom.pratt(operator.padded_by(just(' ')));
-    /// let expr_str = expr.map(|expr| expr.to_string()).then_ignore(end());
-    /// assert_eq!(expr_str.parse("1 + 2").into_result(), Ok("(1 + 2)".to_string()));
-    /// // `*` binds more strongly than `+`
-    /// assert_eq!(expr_str.parse("1 * 2 + 3").into_result(), Ok("((1 * 2) + 3)".to_string()));
-    /// assert_eq!(expr_str.parse("1 + 2 * 3").into_result(), Ok("(1 + (2 * 3))".to_string()));
-    /// // `+` is left-associative
-    /// assert_eq!(expr_str.parse("1 + 2 + 3").into_result(), Ok("((1 + 2) + 3)".to_string()));
-    /// // `*` is right-associative (in this example)
-    /// assert_eq!(expr_str.parse("1 * 2 * 3").into_result(), Ok("(1 * (2 * 3))".to_string()));
-    /// ```
-    fn pratt<OpParser, Op>(self, op_parser: OpParser) -> Pratt<E, Self, O, OpParser, Op>
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "cst")]
+    fn syntax_token(self, kind: impl Into<cst::SyntaxKind>) -> cst::SyntaxToken<Self>
     where
         Self: Sized,
-        OpParser: Parser<'a, I, Op, E>,
-        Op: pratt::InfixOperator<O>,
     {
-        Pratt {
-            parser_atom: self,
-            parser_op: op_parser,
-            phantom: PhantomData,
+        cst::SyntaxToken {
+            parser: self,
+            kind: kind.into(),
         }
     }
-}
-
-#[cfg(feature = "nightly")]
-impl<'a, I, O, E> ParserSealed<'a, I, O, E> for !
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        *self
-    }
-
-    go_extra!(O);
-}
 
-/// A parser that can be configured with runtime context
-pub trait ConfigParser<'a, I, O, E>: ConfigParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// A combinator that allows configuration of the parser from the current context
-    fn configure<F>(self, cfg: F) -> Configure<Self, F>
+    /// Record the input parsed by this parser as a semantic token of the given category, collected by
+    /// [`Parser::parse_semantic`] for syntax highlighting.
+    ///
+    /// Any semantic tokens recorded by the parsers within this one are discarded, so that tokens never overlap. See
+    /// the [`semantic`] module for more information.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "semantic")]
+    fn semantic_token(self, category: semantic::Category) -> semantic::SemanticToken<Self>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context) -> Self::Config,
     {
-        Configure { parser: self, cfg }
+        semantic::SemanticToken {
+            parser: self,
+            category,
+        }
     }
-}
-
-/// An iterator that wraps an iterable parser. See [`IterParser::parse_iter`].
-#[cfg(test)]
-pub struct ParserIter<'a, 'iter, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>>
-{
-    parser: P,
-    offset: I::Offset,
-    own: InputOwn<'a, 'iter, I, E>,
-    iter_state: Option<P::IterState<Emit>>,
-    #[allow(dead_code)]
-    phantom: EmptyPhantom<(&'a (), O)>,
-}
-
-#[cfg(test)]
-impl<'a, 'iter, P, I: Input<'a>, O, E: ParserExtra<'a, I>> Iterator
-    for ParserIter<'a, 'iter, P, I, O, E>
-where
-    P: IterParser<'a, I, O, E>,
-{
-    type Item = O;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut inp = self.own.as_ref_at(self.offset);
-        let parser = &self.parser;
-
-        let iter_state = match &mut self.iter_state {
-            Some(state) => state,
-            None => {
-                let state = parser.make_iter::<Emit>(&mut inp).ok()?;
-                self.iter_state = Some(state);
-                self.iter_state.as_mut().unwrap()
-            }
-        };
 
-        let res = parser.next::<Emit>(&mut inp, iter_state);
-        self.offset = inp.offset;
-        res.ok().and_then(|res| res)
+    /// Record an item of metadata for each successful parse of this pattern, created from its output and span, to be
+    /// collected by [`Parser::parse_metadata`].
+    ///
+    /// Metadata is discarded if a parser that contains this one backtracks. See the [`metadata`] module for more
+    /// information.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "metadata")]
+    fn record_metadata<T, F>(self, record: F) -> metadata::RecordMetadata<Self, F>
+    where
+        Self: Sized,
+        T: core::any::Any,
+        F: Fn(&O, I::Span) -> T,
+    {
+        metadata::RecordMetadata {
+            parser: self,
+            record,
+        }
     }
-}
 
-/// An iterable equivalent of [`Parser`], i.e: a parser that generates a sequence of outputs.
-pub trait IterParser<'a, I, O, E = extra::Default>: IterParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// Collect this iterable parser into a [`Container`].
-    ///
-    /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
-    /// [`Vec`]s, [`String`]s, or even [`HashMap`]s. This method is analogous to [`Iterator::collect`].
+    /// Parse one thing and then another thing, yielding a tuple of the two outputs.
     ///
-    /// The output type of this iterable parser is `C`, the type being collected into.
+    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic()) // This parser produces an output of `char`
-    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
-    ///     .collect::<String>(); // We collect the `char`s into a `String`
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    /// let two_words = word.then_ignore(just(' ')).then(word);
     ///
-    /// assert_eq!(word.parse("hello").into_result(), Ok("hello".to_string()));
+    /// assert_eq!(two_words.parse("dog cat").into_result(), Ok(("dog".to_string(), "cat".to_string())));
+    /// assert!(two_words.parse("hedgehog").has_errors());
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn collect<C: Container<O>>(self) -> Collect<Self, O, C>
+    fn then<U, B: Parser<'a, I, U, E>>(self, other: B) -> Then<Self, B, O, U, E>
     where
         Self: Sized,
     {
-        Collect {
-            parser: self,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        Then {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Collect this iterable parser into a [`ContainerExactly`].
+    /// Parse one thing and then, only if a predicate on its output holds, another thing, yielding a tuple of the first
+    /// output and the optional second output.
     ///
-    /// This is useful for situations where the number of items to consume is statically known.
-    /// A common use-case is collecting into an array.
+    /// This is useful for data-dependent structure in which a header determines whether a following element is
+    /// present, without needing to rebuild parsers during parsing. If the predicate does not hold, the second parser
+    /// is not run and no further input is consumed.
     ///
-    /// The output type of this iterable parser if `C`, the type being collected into.
+    /// Note that because the predicate inspects the output of the first parser, this output is always generated, even
+    /// when the output of this parser is not needed.
     ///
-    /// # Exmaples
+    /// The output type of this parser is `(O, Option<U>)`.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let three_digit = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_numeric())
-    ///     .repeated()
-    ///     .collect_exactly::<[_; 3]>();
-    ///
-    /// assert_eq!(three_digit.parse("123").into_result(), Ok(['1', '2', '3']));
-    /// assert!(three_digit.parse("12").into_result().is_err());
-    /// assert!(three_digit.parse("1234").into_result().is_err());
+    /// // A message header declares whether a body follows
+    /// let header = just::<_, _, extra::Err<Simple<char>>>("has_body=")
+    ///     .ignore_then(one_of("01").map(|c| c == '1'));
+    /// let body = text::ident().delimited_by(just('{'), just('}'));
+    ///
+    /// let message = header.then_if(body, |has_body| *has_body);
+    ///
+    /// assert_eq!(message.parse("has_body=1{hello}").into_result(), Ok((true, Some("hello"))));
+    /// assert_eq!(message.parse("has_body=0").into_result(), Ok((false, None)));
+    /// // The body is required when the header says so...
+    /// assert!(message.parse("has_body=1").has_errors());
+    /// // ...and not permitted otherwise
+    /// assert!(message.parse("has_body=0{hello}").has_errors());
     /// ```
-    fn collect_exactly<C: ContainerExactly<O>>(self) -> CollectExactly<Self, O, C>
+    fn then_if<U, B, F>(self, other: B, predicate: F) -> ThenIf<Self, B, F, O, U, E>
     where
         Self: Sized,
+        B: Parser<'a, I, U, E>,
+        F: Fn(&O) -> bool,
     {
-        CollectExactly {
-            parser: self,
+        ThenIf {
+            parser_a: self,
+            parser_b: other,
+            predicate,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Collect this iterable parser into a [`usize`], outputting the number of elements that were parsed.
+    /// Parse one thing and then another thing, yielding only the output of the latter.
     ///
-    /// This is sugar for [`.collect::<usize>()`](Self::collect).
+    /// The output type of this parser is `U`, the same as the second parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    ///
-    /// // Counts how many chess squares are in the input.
-    /// let squares = one_of::<_, _, extra::Err<Simple<char>>>('a'..='z').then(one_of('1'..='8')).padded().repeated().count();
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let zeroes = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| *c == '0').ignored().repeated().collect::<Vec<_>>();
+    /// let digits = any().filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated()
+    ///     .collect::<String>();
+    /// let integer = zeroes
+    ///     .ignore_then(digits)
+    ///     .from_str()
+    ///     .unwrapped();
     ///
-    /// assert_eq!(squares.parse("a1 b2 c3").into_result(), Ok(3));
-    /// assert_eq!(squares.parse("e5 e7 c6 c7 f6 d5 e6 d7 e4 c5 d6 c4 b6 f5").into_result(), Ok(14));
-    /// assert_eq!(squares.parse("").into_result(), Ok(0));
+    /// assert_eq!(integer.parse("00064").into_result(), Ok(64));
+    /// assert_eq!(integer.parse("32").into_result(), Ok(32));
     /// ```
-    fn count(self) -> Collect<Self, O, usize>
+    fn ignore_then<U, B: Parser<'a, I, U, E>>(self, other: B) -> IgnoreThen<Self, B, O, E>
     where
         Self: Sized,
     {
-        self.collect()
+        IgnoreThen {
+            parser_a: self,
+            parser_b: other,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Enumerate outputs of this iterable parser.
-    ///
-    /// This function behaves in a similar way to [`Iterator::enumerate`].
+    /// Parse one thing and then another thing, yielding only the output of the former.
     ///
-    /// The output type of this iterable parser is `(usize, O)`.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = text::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded()
-    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
-    ///     .enumerate()
-    ///     .collect::<Vec<(usize, &str)>>();
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
     ///
-    /// assert_eq!(word.parse("hello world").into_result(), Ok(vec![(0, "hello"), (1, "world")]));
+    /// let punctuated = word
+    ///     .then_ignore(just('!').or(just('?')).or_not());
+    ///
+    /// let sentence = punctuated
+    ///     .padded() // Allow for whitespace gaps
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     sentence.parse("hello! how are you?").into_result(),
+    ///     Ok(vec![
+    ///         "hello".to_string(),
+    ///         "how".to_string(),
+    ///         "are".to_string(),
+    ///         "you".to_string(),
+    ///     ]),
+    /// );
     /// ```
-    fn enumerate(self) -> Enumerate<Self, O>
+    fn then_ignore<U, B: Parser<'a, I, U, E>>(self, other: B) -> ThenIgnore<Self, B, U, E>
     where
         Self: Sized,
     {
-        Enumerate {
-            parser: self,
+        ThenIgnore {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Right-fold the output of the parser into a single value.
-    ///
-    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
-    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    /// Parse input as part of a token-tree - using an input generated from within the current
+    /// input.
     ///
-    /// The output type of this iterable parser is `B`, the right-hand component of the original parser's output.
+    /// The output of this parser is `O`, the output of the parser it is called on.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// # use chumsky::{prelude::*, util::MaybeRef, error::Simple};
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Token<'a> {
+    ///     Struct,
+    ///     Ident(&'a str),
+    ///     Item(&'a str),
+    ///     Group(Vec<Token<'a>>),
+    /// }
     ///
-    /// let signed = just('+').to(1)
-    ///     .or(just('-').to(-1))
+    /// let group = select_ref! { Token::Group(g) => g.as_slice() };
+    ///
+    /// let ident = select_ref! { Token::Ident(i) => *i };
+    ///
+    /// let items = select_ref! { Token::Item(i) => *i }
     ///     .repeated()
-    ///     .foldr(int, |a, b| a * b);
+    ///     .collect::<Vec<_>>()
+    ///     .nested_in(group);
     ///
-    /// assert_eq!(signed.parse("3").into_result(), Ok(3));
-    /// assert_eq!(signed.parse("-17").into_result(), Ok(-17));
-    /// assert_eq!(signed.parse("--+-+-5").into_result(), Ok(5));
+    /// let struc = just::<_, _, extra::Err<Simple<_>>>(&Token::Struct)
+    ///     .ignore_then(ident)
+    ///     .then(items);
+    ///
+    /// let tl = struc
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let tokens = [
+    ///     Token::Struct,
+    ///     Token::Ident("foo"),
+    ///     Token::Group(vec![
+    ///         Token::Item("a"),
+    ///         Token::Item("b"),
+    ///     ]),
+    /// ];
+    ///
+    /// assert_eq!(tl.parse(&tokens).into_result(), Ok(vec![("foo", vec!["a", "b"])]));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldr<B, F, OA>(self, other: B, f: F) -> Foldr<F, Self, B, O, E>
+    fn nested_in<B: Parser<'a, I, I, E>>(self, other: B) -> NestedIn<Self, B, O, E>
     where
-        F: Fn(O, OA) -> OA,
-        B: Parser<'a, I, OA, E>,
         Self: Sized,
+        I: 'a,
     {
-        Foldr {
+        NestedIn {
             parser_a: self,
             parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Right-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    /// Parse one thing and then another thing, creating the second parser from the result of
+    /// the first. If you only have a couple cases to handle, prefer [`Parser::or`].
     ///
-    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
-    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    /// The output of this parser is `U`, the result of the second parser
     ///
-    /// The output type of this parser is `B`, the right-hand component of the original parser's output.
+    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
+    /// recovery then the second produces an error, the primary error will point to the location in
+    /// the second parser which failed, ignoring that the first parser may be the root cause. There
+    /// may be other pathological errors cases as well.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// let successor = just(b'\0').configure(|cfg, ctx: &u8| cfg.seq(*ctx + 1));
     ///
-    /// let signed = just('+').to(1)
-    ///     .or(just('-').to(-1))
-    ///     .repeated()
-    ///     .foldr_with_state(int, |a, b, state| {
-    ///         (*state) += 1;
-    ///         (a * b)
-    ///     });
-    /// let mut folds = 0i32;
-    /// assert_eq!(signed.parse_with_state("3", &mut folds).into_result(), Ok(3));
-    /// assert_eq!(signed.parse_with_state("-17", &mut folds).into_result(), Ok(-17));
-    /// assert_eq!(signed.parse_with_state("--+-+-5", &mut folds).into_result(), Ok(5));
+    /// // A parser that parses a single letter and then its successor
+    /// let successive_letters = one_of::<_, _, extra::Err<Simple<u8>>>(b'a'..=b'z')
+    ///     .then_with_ctx(successor);
+    ///
+    /// assert_eq!(successive_letters.parse(b"ab").into_result(), Ok(b'b')); // 'b' follows 'a'
+    /// assert!(successive_letters.parse(b"ac").has_errors()); // 'c' does not follow 'a'
     /// ```
-    // TODO: Add examples of interning/arena allocation
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldr_with_state<B, F, OA>(self, other: B, f: F) -> FoldrWithState<F, Self, B, OA, E>
+    fn then_with_ctx<U, P>(
+        self,
+        then: P,
+    ) -> ThenWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
     where
-        F: Fn(O, OA, &mut E::State) -> OA,
-        B: Parser<'a, I, OA, E>,
         Self: Sized,
+        O: 'a,
+        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
     {
-        FoldrWithState {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        ThenWithCtx {
+            parser: self,
+            then,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Create an iterator over the outputs generated by an iterable parser.
+    /// Run the previous contextual parser with the provided context
     ///
-    /// Warning: Trailing errors will be ignored
-    // TODO: Stabilize once error handling is properly decided on
-    #[cfg(test)]
-    fn parse_iter(self, input: I) -> ParseResult<ParserIter<'a, 'static, Self, I, O, E>, E::Error>
-    where
-        Self: IterParser<'a, I, O, E> + Sized,
-        E::State: Default,
-        E::Context: Default,
-    {
-        ParseResult::new(
-            Some(ParserIter {
-                parser: self,
-                offset: input.start(),
-                own: InputOwn::new(input),
-                iter_state: None,
-                phantom: EmptyPhantom::new(),
-            }),
-            Vec::new(),
-        )
-    }
-
-    /// Create an iterator over the outputs generated by an iterable parser with the given parser state.
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use chumsky::primitive::JustCfg;
     ///
-    /// Warning: Trailing errors will be ignored
-    // TODO: Stabilize once error handling is properly decided on
-    #[cfg(test)]
-    fn parse_iter_with_state<'parse>(
-        self,
-        input: I,
-        state: &'parse mut E::State,
-    ) -> ParseResult<ParserIter<'a, 'parse, Self, I, O, E>, E::Error>
+    /// let generic = just(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+    ///
+    /// let parse_a = just::<_, _, extra::Default>(b'b').ignore_then(generic.with_ctx::<u8>(b'a'));
+    /// let parse_b = just::<_, _, extra::Default>(b'a').ignore_then(generic.with_ctx(b'b'));
+    ///
+    /// assert_eq!(parse_a.parse(b"ba" as &[_]).into_result(), Ok::<_, Vec<EmptyErr>>(b'a'));
+    /// assert!(parse_a.parse(b"bb").has_errors());
+    /// assert_eq!(parse_b.parse(b"ab" as &[_]).into_result(), Ok(b'b'));
+    /// assert!(parse_b.parse(b"aa").has_errors());
+    /// ```
+    fn with_ctx<Ctx>(self, ctx: Ctx) -> WithCtx<Self, Ctx>
     where
-        Self: IterParser<'a, I, O, E> + Sized,
-        E::Context: Default,
+        Self: Sized,
+        Ctx: 'a + Clone,
     {
-        ParseResult::new(
-            Some(ParserIter {
-                parser: self,
-                offset: input.start(),
-                own: InputOwn::new_state(input, state),
-                iter_state: None,
-                phantom: EmptyPhantom::new(),
-            }),
-            Vec::new(),
-        )
+        WithCtx { parser: self, ctx }
     }
-}
 
-/// An iterable equivalent of [`ConfigParser`], i.e: a parser that generates a sequence of outputs and
-/// can be configured at runtime.
-pub trait ConfigIterParser<'a, I, O, E = extra::Default>:
-    ConfigIterParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// A combinator that allows configuration of the parser from the current context
-    fn configure<F>(self, cfg: F) -> IterConfigure<Self, F, O>
+    /// Parse the pattern, but only succeed if another pattern also matches at the same position. Only the output of
+    /// the first pattern is kept, and only the first pattern's input is consumed.
+    ///
+    /// Combined with [`Parser::not`], this can be used to express 'X, except Y' patterns. See also [`any_except`].
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    ///
+    /// let escape = just("\\n").to('\n');
+    ///
+    /// // C-style string literal
+    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
+    ///     .and_is(escape.not())
+    ///     .or(escape)
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .padded_by(just('"'));
+    ///
+    /// assert_eq!(
+    ///     string.parse("\"wxyz\"").into_result().as_deref(),
+    ///     Ok("wxyz"),
+    /// );
+    /// assert_eq!(
+    ///     string.parse("\"a\nb\"").into_result().as_deref(),
+    ///     Ok("a\nb"),
+    /// );
+    /// ```
+    fn and_is<U, B>(self, other: B) -> AndIs<Self, B, U>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context) -> Self::Config,
+        B: Parser<'a, I, U, E>,
     {
-        IterConfigure {
-            parser: self,
-            cfg,
+        AndIs {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// A combinator that allows fallible configuration of the parser from the current context -
-    /// if an error is returned, parsing fails.
-    fn try_configure<F>(self, cfg: F) -> TryIterConfigure<Self, F, O>
-    where
+    /// Parse the pattern surrounded by the given delimiters.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// // A LISP-style S-expression
+    /// #[derive(Debug, PartialEq)]
+    /// enum SExpr {
+    ///     Ident(String),
+    ///     Num(u64),
+    ///     List(Vec<SExpr>),
+    /// }
+    ///
+    /// let ident = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    ///
+    /// let num = text::int(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let s_expr = recursive(|s_expr| s_expr
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>()
+    ///     .map(SExpr::List)
+    ///     .delimited_by(just('('), just(')'))
+    ///     .or(ident.map(SExpr::Ident))
+    ///     .or(num.map(SExpr::Num)));
+    ///
+    /// // A valid input
+    /// assert_eq!(
+    ///     s_expr.parse("(add (mul 42 3) 15)").into_result(),
+    ///     Ok(SExpr::List(vec![
+    ///         SExpr::Ident("add".to_string()),
+    ///         SExpr::List(vec![
+    ///             SExpr::Ident("mul".to_string()),
+    ///             SExpr::Num(42),
+    ///             SExpr::Num(3),
+    ///         ]),
+    ///         SExpr::Num(15),
+    ///     ])),
+    /// );
+    /// ```
+    fn delimited_by<U, V, B, C>(self, start: B, end: C) -> DelimitedBy<Self, B, C, U, V>
+    where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context, I::Span) -> Result<Self::Config, E::Error>,
+        B: Parser<'a, I, U, E>,
+        C: Parser<'a, I, V, E>,
     {
-        TryIterConfigure {
+        DelimitedBy {
             parser: self,
-            cfg,
+            start,
+            end,
             phantom: EmptyPhantom::new(),
         }
     }
-}
 
-/// See [`Parser::boxed`].
-///
-/// Due to current implementation details, the inner value is not, in fact, a [`Box`], but is an [`Rc`] to facilitate
-/// efficient cloning. This is likely to change in the future. Unlike [`Box`], [`Rc`] has no size guarantees: although
-/// it is *currently* the same size as a raw pointer.
-// TODO: Don't use an Rc
-pub struct Boxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
-    inner: RefC<DynParser<'a, 'b, I, O, E>>,
-}
+    /// Parse a pattern, but with an instance of another pattern on either end, yielding the output of the inner.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded_by(just('!'));
+    ///
+    /// assert_eq!(ident.parse("!hello!").into_result(), Ok("hello"));
+    /// assert!(ident.parse("hello!").has_errors());
+    /// assert!(ident.parse("!hello").has_errors());
+    /// assert!(ident.parse("hello").has_errors());
+    /// ```
+    fn padded_by<U, B>(self, padding: B) -> PaddedBy<Self, B, U>
+    where
+        Self: Sized,
+        B: Parser<'a, I, U, E>,
+    {
+        PaddedBy {
+            parser: self,
+            padding,
+            phantom: EmptyPhantom::new(),
+        }
+    }
 
-impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for Boxed<'a, 'b, I, O, E> {
-    fn clone(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
+    /// Record the input matched by this parser as trivia (such as whitespace and comments) in the parser's state.
+    ///
+    /// Parsers usually discard trivia, but formatters, documentation generators, and other tools may need to know
+    /// where comments appeared. This combinator records the slice of input matched by the parser (if it is not empty),
+    /// along with its span, in the parser's state via [`text::TriviaSink`]. [`text::TriviaLog`] is a suitable state
+    /// type. Trivia is recorded even when the parser is used in a context that discards its output, such as
+    /// [`Parser::padded_by`].
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, text::TriviaLog};
+    /// let comment = just("//")
+    ///     .then(any().and_is(text::newline().not()).repeated())
+    ///     .ignored()
+    ///     .record_trivia();
+    /// let padding = comment.or(text::whitespace().at_least(1)).repeated();
+    ///
+    /// let idents = text::ident::<_, _, extra::State<TriviaLog<&str, SimpleSpan>>>()
+    ///     .padded_by(padding)
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let mut trivia = TriviaLog::new();
+    /// let src = "// header\nfoo // about foo\nbar";
+    /// assert_eq!(idents.parse_with_state(src, &mut trivia).into_result(), Ok(vec!["foo", "bar"]));
+    /// assert_eq!(
+    ///     trivia.trivia(),
+    ///     &[("// header", (0..9).into()), ("// about foo", (14..26).into())],
+    /// );
+    /// // Find the comments between `foo` and `bar`
+    /// assert_eq!(trivia.trivia_in(13..27).len(), 1);
+    /// ```
+    fn record_trivia(self) -> RecordTrivia<Self, O>
+    where
+        Self: Sized,
+        I: SliceInput<'a>,
+        E::State: text::TriviaSink<I::Slice, I::Span>,
+    {
+        RecordTrivia {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Only parse this pattern when the current lexer mode is `mode`, failing otherwise.
+    ///
+    /// The parser's state must contain a [`lexer::ModeStack`] (see [`lexer::ModalState`]). See [`lexer::ModeStack`]
+    /// for an example.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "alloc")]
+    fn in_mode<Mo>(self, mode: Mo) -> lexer::InMode<Self, Mo, O>
+    where
+        Self: Sized,
+        E::State: lexer::ModalState<Mo>,
+        Mo: PartialEq,
+    {
+        lexer::InMode {
+            parser: self,
+            mode,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// After successfully parsing this pattern, enter the lexer mode `mode`.
+    ///
+    /// The parser's state must contain a [`lexer::ModeStack`] (see [`lexer::ModalState`]). See [`lexer::ModeStack`]
+    /// for an example.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "alloc")]
+    fn push_mode<Mo>(self, mode: Mo) -> lexer::PushMode<Self, Mo, O>
+    where
+        Self: Sized,
+        E::State: lexer::ModalState<Mo>,
+        Mo: Clone,
+    {
+        lexer::PushMode {
+            parser: self,
+            mode,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// After successfully parsing this pattern, exit the current lexer mode, returning to the previous one.
+    ///
+    /// If the current mode is the initial mode (and so cannot be exited), this parser fails without attempting to parse
+    /// the pattern.
+    ///
+    /// The parser's state must contain a [`lexer::ModeStack`] (see [`lexer::ModalState`]). See [`lexer::ModeStack`]
+    /// for an example.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "alloc")]
+    fn pop_mode<Mo>(self) -> lexer::PopMode<Self, Mo, O>
+    where
+        Self: Sized,
+        E::State: lexer::ModalState<Mo>,
+    {
+        lexer::PopMode {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse one thing or, on failure, another thing.
+    ///
+    /// The output of both parsers must be of the same type, because either output can be produced.
+    ///
+    /// If both parser succeed, the output of the first parser is guaranteed to be prioritised over the output of the
+    /// second.
+    ///
+    /// If both parsers produce errors, the combinator will attempt to select from or combine the errors to produce an
+    /// error that is most likely to be useful to a human attempting to understand the problem. The exact algorithm
+    /// used is left unspecified, and is not part of the crate's semver guarantees, although regressions in error
+    /// quality should be reported in the issue tracker of the main repository.
+    ///
+    /// Please note that long chains of [`Parser::or`] combinators have been known to result in poor compilation times.
+    /// If you feel you are experiencing this, consider using [`choice`] instead.
+    ///
+    /// The output type of this parser is `O`, the output of both parsers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let op = just::<_, _, extra::Err<Simple<char>>>('+')
+    ///     .or(just('-'))
+    ///     .or(just('*'))
+    ///     .or(just('/'));
+    ///
+    /// assert_eq!(op.parse("+").into_result(), Ok('+'));
+    /// assert_eq!(op.parse("/").into_result(), Ok('/'));
+    /// assert!(op.parse("!").has_errors());
+    /// ```
+    fn or<B>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+        B: Parser<'a, I, O, E>,
+    {
+        Or {
+            choice: choice((self, other)),
         }
     }
-}
 
-impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Boxed<'a, 'b, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        M::invoke(&*self.inner, inp)
+    /// Attempt to parse something, but only if it exists.
+    ///
+    /// If parsing of the pattern is successful, the output is `Some(_)`. Otherwise, the output is `None`.
+    ///
+    /// The output type of this parser is `Option<O>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    ///
+    /// let word_or_question = word
+    ///     .then(just('?').or_not());
+    ///
+    /// assert_eq!(word_or_question.parse("hello?").into_result(), Ok(("hello".to_string(), Some('?'))));
+    /// assert_eq!(word_or_question.parse("wednesday").into_result(), Ok(("wednesday".to_string(), None)));
+    /// ```
+    fn or_not(self) -> OrNot<Self>
+    where
+        Self: Sized,
+    {
+        OrNot { parser: self }
+    }
+
+    /// Attempt to parse something, but only if it exists, like [`Parser::or_not`]. However, if the pattern is present
+    /// but malformed (i.e: it fails after its first significant token), the error is emitted as a recovered error
+    /// rather than being silently discarded. Leading padding, such as that accepted by [`Parser::padded`], is not
+    /// significant.
+    ///
+    /// In both cases, no input is consumed and the output is `None`. This is useful for optional trailing clauses
+    /// (such as type annotations or default values) whose syntax errors should not go unreported.
+    ///
+    /// The output type of this parser is `Option<O>`, the same as the original parser wrapped in an [`Option`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// // A variable with an optional type annotation
+    /// let var = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then(just(':').padded().ignore_then(text::ident()).or_not_with_err());
+    ///
+    /// assert_eq!(var.parse("x: int").into_result(), Ok(("x", Some("int"))));
+    /// assert_eq!(var.parse("x").into_result(), Ok(("x", None)));
+    /// assert_eq!(var.lazy().parse("x;").into_result(), Ok(("x", None)));
+    ///
+    /// // The annotation is malformed, so an error is reported...
+    /// let res = var.lazy().parse("x: 42");
+    /// assert_eq!(res.errors().len(), 1);
+    /// // ...but parsing still continues, as if no annotation was present
+    /// assert_eq!(res.output(), Some(&("x", None)));
+    /// ```
+    fn or_not_with_err(self) -> OrNotWithErr<Self>
+    where
+        Self: Sized,
+    {
+        OrNotWithErr { parser: self }
+    }
+
+    /// Succeed, consuming no input, only if the pattern does *not* match at the current position.
+    ///
+    /// This is most useful in combination with [`Parser::and_is`] (or [`any_except`]) to exclude certain inputs.
+    ///
+    /// The output type of this parser is `()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Tree<'a> {
+    ///     Text(&'a str),
+    ///     Group(Vec<Self>),
+    /// }
+    ///
+    /// // Arbitrary text, nested in a tree with { ... } delimiters
+    /// let tree = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|tree| {
+    ///     let text = any()
+    ///         .and_is(one_of("{}").not())
+    ///         .repeated()
+    ///         .at_least(1)
+    ///         .map_slice(Tree::Text);
+    ///
+    ///     let group = tree
+    ///         .repeated()
+    ///         .collect()
+    ///         .delimited_by(just('{'), just('}'))
+    ///         .map(Tree::Group);
+    ///
+    ///     text.or(group)
+    /// });
+    ///
+    /// assert_eq!(
+    ///     tree.parse("{abcd{efg{hijk}lmn{opq}rs}tuvwxyz}").into_result(),
+    ///     Ok(Tree::Group(vec![
+    ///         Tree::Text("abcd"),
+    ///         Tree::Group(vec![
+    ///             Tree::Text("efg"),
+    ///             Tree::Group(vec![
+    ///                 Tree::Text("hijk"),
+    ///             ]),
+    ///             Tree::Text("lmn"),
+    ///             Tree::Group(vec![
+    ///                 Tree::Text("opq"),
+    ///             ]),
+    ///             Tree::Text("rs"),
+    ///         ]),
+    ///         Tree::Text("tuvwxyz"),
+    ///     ])),
+    /// );
+    /// ```
+    fn not(self) -> Not<Self, O>
+    where
+        Self: Sized,
+    {
+        Not {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a pattern any number of times (including zero times).
+    ///
+    /// Input is eagerly parsed. Be aware that the parser will accept no occurences of the pattern too. Consider using
+    /// [`Repeated::at_least`] instead if it better suits your use-case.
+    ///
+    /// The output type of this parser can be any [`Container`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let num = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>()
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let sum = num.clone()
+    ///     .foldl(just('+').ignore_then(num).repeated(), |a, b| a + b);
+    ///
+    /// assert_eq!(sum.parse("2+13+4+0+5").into_result(), Ok(24));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn repeated(self) -> Repeated<Self, O, I, E>
+    where
+        Self: Sized,
+    {
+        Repeated {
+            parser: self,
+            at_least: 0,
+            at_most: !0,
+            capacity: 0,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a pattern as many times as the current context says, such as a count parsed by an earlier
+    /// [`Parser::then_with_ctx`].
+    ///
+    /// This is useful for formats that give the number of records before the records themselves. It is sugar for
+    /// [`.repeated().configure(|cfg, n| cfg.exactly(n))`](ConfigIterParser::configure): use
+    /// [`ConfigIterParser::configure`] directly for counts that are part of a larger context, or that are a minimum or
+    /// maximum rather than an exact number.
+    ///
+    /// The context is converted to a `usize` with [`TryInto`]. If it doesn't fit (such as a negative count), the
+    /// pattern must be repeated [`usize::MAX`] times, so parsing will fail.
+    ///
+    /// The output type of this iterable parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// // A count, followed by that many words
+    /// let word = text::ident::<_, _, extra::Context<usize>>().padded();
+    /// let words = text::int::<_, _, extra::Default>(10)
+    ///     .from_str::<usize>()
+    ///     .unwrapped()
+    ///     .then_with_ctx(word.repeated_ctx().collect::<Vec<_>>());
+    ///
+    /// assert_eq!(words.parse("2 hello world").into_result(), Ok(vec!["hello", "world"]));
+    /// assert_eq!(words.parse("0").into_result(), Ok(vec![]));
+    /// assert!(words.parse("3 hello world").has_errors());
+    /// assert!(words.parse("1 hello world").has_errors());
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    #[allow(clippy::type_complexity)]
+    fn repeated_ctx(
+        self,
+    ) -> IterConfigure<Repeated<Self, O, I, E>, fn(RepeatedCfg, &E::Context) -> RepeatedCfg, O>
+    where
+        Self: Sized,
+        E::Context: Clone + TryInto<usize>,
+    {
+        self.repeated()
+            .configure(|cfg, n| cfg.exactly(n.clone().try_into().unwrap_or(usize::MAX)))
+    }
+
+    /// Parse a pattern exactly `N` times, collecting the outputs into an array of type `[O; N]`.
+    ///
+    /// This is useful for records made of a fixed number of fields, such as the components of a colour or the rows of a
+    /// matrix. The array is filled in place, so no allocation is needed. This is sugar for
+    /// [`.repeated().exactly(N).collect_exactly::<[O; N]>()`](IterParser::collect_exactly): use
+    /// [`IterParser::collect_exactly`] directly to collect other repetitions, such as those of
+    /// [`Parser::separated_by`], into an array.
+    ///
+    /// The output type of this parser is `[O; N]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let hex_byte = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(char::is_ascii_hexdigit)
+    ///     .array::<2>()
+    ///     .map(|[hi, lo]| (hi.to_digit(16).unwrap() * 16 + lo.to_digit(16).unwrap()) as u8);
+    ///
+    /// let colour = just('#').ignore_then(hex_byte.array::<3>());
+    ///
+    /// assert_eq!(colour.parse("#ff8000").into_result(), Ok([255, 128, 0]));
+    /// assert!(colour.parse("#ff80").has_errors());
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn array<const N: usize>(self) -> CollectExactly<Repeated<Self, O, I, E>, O, [O; N]>
+    where
+        Self: Sized,
+    {
+        self.repeated().exactly(N).collect_exactly()
+    }
+
+    /// Parse a pattern, separated by another, any number of times.
+    ///
+    /// You can use [`SeparatedBy::allow_leading`] or [`SeparatedBy::allow_trailing`] to allow leading or trailing
+    /// separators.
+    ///
+    /// The output type of this parser can be any [`Container`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let shopping = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(shopping.parse("eggs").into_result(), Ok(vec!["eggs"]));
+    /// assert_eq!(shopping.parse("eggs, flour, milk").into_result(), Ok(vec!["eggs", "flour", "milk"]));
+    /// ```
+    ///
+    /// See [`SeparatedBy::allow_leading`] and [`SeparatedBy::allow_trailing`] for more examples.
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn separated_by<U, B>(self, separator: B) -> SeparatedBy<Self, B, O, U, I, E>
+    where
+        Self: Sized,
+        B: Parser<'a, I, U, E>,
+    {
+        SeparatedBy {
+            parser: self,
+            separator,
+            recovery: NoRecovery,
+            at_least: 0,
+            at_most: !0,
+            capacity: 0,
+            allow_leading: false,
+            allow_trailing: false,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Left-fold the output of the parser into a single value.
+    ///
+    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    ///
+    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let sum = int
+    ///     .clone()
+    ///     .foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
+    ///
+    /// assert_eq!(sum.parse("1+12+3+9").into_result(), Ok(25));
+    /// assert_eq!(sum.parse("6").into_result(), Ok(6));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldl<B, F, OB>(self, other: B, f: F) -> Foldl<F, Self, B, OB, E>
+    where
+        F: Fn(O, OB) -> O,
+        B: IterParser<'a, I, OB, E>,
+        Self: Sized,
+    {
+        Foldl {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse one or more instances of the pattern separated by an operator, left-folding the results into a single
+    /// value. Each step of the fold is given the output of the operator in addition to the left- and right-hand sides.
+    ///
+    /// This is the standard shape of left-associative binary expressions, and is equivalent to
+    /// `self.foldl(op.then(self).repeated(), |lhs, (op, rhs)| f(lhs, op, rhs))`. If an operator is found but is not
+    /// followed by another instance of the pattern, the operator is not consumed.
+    ///
+    /// For grammars with many precedence levels, see [`Parser::pratt`].
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<i64>()
+    ///     .unwrapped();
+    ///
+    /// let op = just('+').to(i64::wrapping_add as fn(_, _) -> _)
+    ///     .or(just('-').to(i64::wrapping_sub as fn(_, _) -> _));
+    ///
+    /// let expr = int.foldl_with_op(op, |lhs, op, rhs| op(lhs, rhs));
+    ///
+    /// assert_eq!(expr.parse("10-3+4-1").into_result(), Ok(10));
+    /// assert_eq!(expr.parse("7").into_result(), Ok(7));
+    /// // The trailing operator is not consumed, so the lazy parser stops before it
+    /// assert_eq!(expr.lazy().parse("1+2+").into_result(), Ok(3));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldl_with_op<B, F, OB>(self, op: B, f: F) -> FoldlWithOp<F, Self, B, OB, E>
+    where
+        F: Fn(O, OB, O) -> O,
+        B: Parser<'a, I, OB, E>,
+        Self: Sized,
+    {
+        FoldlWithOp {
+            parser_a: self,
+            parser_b: op,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Left-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    ///
+    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    ///
+    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let sum = int
+    ///     .clone()
+    ///     .foldl_with_state(just('+').ignore_then(int).repeated(), |a, b, state| (a + b) * *state);
+    ///
+    /// let mut multiplier = 2i32;
+    /// assert_eq!(sum.parse_with_state("1+12+3+9", &mut multiplier).into_result(), Ok(134));
+    /// assert_eq!(sum.parse_with_state("6", &mut multiplier).into_result(), Ok(6));
+    /// ```
+    // TODO: Add examples of interning/arena allocation
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldl_with_state<B, F, OB>(self, other: B, f: F) -> FoldlWithState<F, Self, B, OB, E>
+    where
+        F: Fn(O, OB, &mut E::State) -> O,
+        B: IterParser<'a, I, OB, E>,
+        Self: Sized,
+    {
+        FoldlWithState {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a pattern. Afterwards, the input stream will be rewound to its original state, as if parsing had not
+    /// occurred.
+    ///
+    /// This combinator is useful for cases in which you wish to avoid a parser accidentally consuming too much input,
+    /// causing later parsers to fail as a result. A typical use-case of this is that you want to parse something that
+    /// is not followed by something else.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let just_numbers = text::digits::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .slice()
+    ///     .padded()
+    ///     .then_ignore(none_of("+-*/").rewind())
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
+    /// // 3 is not parsed because it's followed by '+'.
+    /// assert_eq!(just_numbers.lazy().parse("1, 2, 3 + 4").into_result(), Ok(vec!["1", "2"]));
+    /// ```
+    fn rewind(self) -> Rewind<Self>
+    where
+        Self: Sized,
+    {
+        Rewind { parser: self }
+    }
+
+    /// Make the parser all-or-nothing: it either matches fully, or fails as if it had not consumed any input.
+    ///
+    /// When a parser fails part of the way through its input, the error it produces is located where it failed. This
+    /// is usually what you want, but it means that an alternative which gets part of the way through the input before
+    /// failing can leave behind an error that is further into the input than any produced by the alternative that
+    /// eventually succeeds, corrupting the errors reported later on. This is common in maximal-munch lexers, where a
+    /// longer token (such as `..=`) is attempted before a shorter one (such as `.`).
+    ///
+    /// An atomic parser that fails reports its error where it began instead, and discards any secondary errors
+    /// produced within it. Errors found at its first token are kept as they are, but errors found further in are
+    /// replaced with an error that expects nothing in particular, so atomic parsers are often
+    /// [labelled](Parser::labelled) to describe what they expected.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::RichPattern};
+    /// let tokens = |range_incl| {
+    ///     choice((range_incl, just("..").to(2), just(".").to(1)))
+    ///         .repeated()
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// // Without `atomic`, the partial match of `..=` is reported once the next token fails to parse
+    /// let errs = tokens(just::<_, _, extra::Err<Rich<char>>>("..=").to(3).boxed())
+    ///     .parse("..=..x")
+    ///     .into_errors();
+    /// assert!(errs[0].expected().any(|pat| pat == &RichPattern::Token('='.into())));
+    ///
+    /// // With `atomic`, only the tokens that could have begun at the error are reported
+    /// let errs = tokens(just("..=").to(3).atomic().boxed())
+    ///     .parse("..=..x")
+    ///     .into_errors();
+    /// assert_eq!(errs[0].span(), &SimpleSpan::from(5..6));
+    /// assert!(errs[0].expected().all(|pat| pat != &RichPattern::Token('='.into())));
+    /// ```
+    fn atomic(self) -> Atomic<Self>
+    where
+        Self: Sized,
+    {
+        Atomic { parser: self }
+    }
+
+    /// Limit how deeply this parser may be nested within itself (or within other depth-limited parsers), producing an
+    /// error instead of recursing any further.
+    ///
+    /// Recursive grammars, such as those of expressions, use a stack frame for each level of nesting in the input, so
+    /// deeply nested inputs (like a million opening parentheses) can overflow the stack and abort the process. This
+    /// is a problem for services that parse untrusted input. A depth-limited parser keeps count of how many
+    /// depth-limited parsers it is nested within, and if there are already `limit` of them, fails with an error
+    /// saying that the input is nested too deeply, located at the next token.
+    ///
+    /// This is usually applied to the parser that a [`recursive`](recursive::recursive) definition refers to itself
+    /// through, so that each level of nesting is counted. Depth-limited parsers that refer to different recursive
+    /// definitions share the same count.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let expr = recursive::<_, _, extra::Err<Rich<char>>, _, _>(|expr| {
+    ///     let expr = expr.with_depth_limit(3);
+    ///     text::int(10)
+    ///         .map(|s: &str| s.len())
+    ///         .or(expr.delimited_by(just('('), just(')')).map(|depth| depth + 1))
+    /// });
+    ///
+    /// assert_eq!(expr.parse("(((1)))").into_result(), Ok(4));
+    ///
+    /// let errs = expr.parse("((((1))))").into_errors();
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].span(), &SimpleSpan::from(4..5));
+    /// assert_eq!(errs[0].to_string(), "input is nested too deeply");
+    /// ```
+    #[cfg(feature = "depth-limit")]
+    fn with_depth_limit(self, limit: usize) -> DepthLimit<Self>
+    where
+        Self: Sized,
+    {
+        DepthLimit {
+            parser: self,
+            limit,
+        }
+    }
+
+    /// Make the parser lazy, such that it parses as much as it validly can and then finished successfully, leaving
+    /// trailing input untouched.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .lazy();
+    ///
+    /// assert_eq!(digits.parse("12345abcde").into_result().as_deref(), Ok("12345"));
+    /// ```
+    fn lazy(self) -> Lazy<'a, Self, I, E>
+    where
+        Self: Sized,
+        I: ValueInput<'a>,
+    {
+        self.then_ignore(any().repeated())
+    }
+
+    /// Parse a pattern, then require that the end of input follows it.
+    ///
+    /// This is shorthand for the very common `.then_ignore(end())`, and is usually applied to the top-level parser of a
+    /// grammar to ensure that no trailing input remains. Combine with [`Parser::padded`] to permit trailing whitespace.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Rich};
+    /// let ident = text::ident::<_, _, extra::Err<Rich<char>>>().padded().then_end();
+    ///
+    /// assert_eq!(ident.parse("  hello  ").into_result(), Ok("hello"));
+    /// // Trailing input is not permitted
+    /// assert_eq!(
+    ///     ident.parse("hello world").into_errors()[0].to_string(),
+    ///     "found 'w' expected end of input",
+    /// );
+    /// ```
+    fn then_end(self) -> ThenEnd<Self, I, E>
+    where
+        Self: Sized,
+    {
+        self.then_ignore(end())
+    }
+
+    /// Parse a pattern, ignoring any amount of whitespace both before and after the pattern.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    ///
+    /// // A pattern with no whitespace surrounding it is accepted
+    /// assert_eq!(ident.parse("hello").into_result(), Ok("hello"));
+    /// // A pattern with arbitrary whitespace surrounding it is also accepted
+    /// assert_eq!(ident.parse(" \t \n  \t   world  \t  ").into_result(), Ok("world"));
+    /// ```
+    fn padded(self) -> Padded<Self>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        I::Token: Char,
+    {
+        Padded { parser: self }
+    }
+
+    // /// Flatten a nested collection.
+    // ///
+    // /// This use-cases of this method are broadly similar to those of [`Iterator::flatten`].
+    // ///
+    // /// The output type of this parser is `Vec<T>`, where the original parser output was
+    // /// `impl IntoIterator<Item = impl IntoIterator<Item = T>>`.
+    // fn flatten<T, Inner>(self) -> Map<Self, O, fn(O) -> Vec<T>>
+    // where
+    //     Self: Sized,
+    //     O: IntoIterator<Item = Inner>,
+    //     Inner: IntoIterator<Item = T>,
+    // {
+    //     self.map(|xs| xs.into_iter().flat_map(|xs| xs.into_iter()).collect())
+    // }
+
+    /// Apply a fallback recovery strategy to this parser should it fail.
+    ///
+    /// There is no silver bullet for error recovery, so this function allows you to specify one of several different
+    /// strategies at the location of your choice. Prefer an error recovery strategy that more precisely mirrors valid
+    /// syntax where possible to make error recovery more reliable.
+    ///
+    /// Because chumsky is a [PEG](https://en.m.wikipedia.org/wiki/Parsing_expression_grammar) parser, which always
+    /// take the first successful parsing route through a grammar, recovering from an error may cause the parser to
+    /// erroneously miss alternative valid routes through the grammar that do not generate recoverable errors. If you
+    /// run into cases where valid syntax fails to parse without errors, this might be happening: consider removing
+    /// error recovery or switching to a more specific error recovery strategy.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// #[derive(Debug, PartialEq)]
+    /// enum Expr<'a> {
+    ///     Error,
+    ///     Int(&'a str),
+    ///     List(Vec<Expr<'a>>),
+    /// }
+    ///
+    /// let recovery = just::<_, _, extra::Err<Simple<char>>>('[')
+    ///         .then(none_of(']').repeated().then(just(']')));
+    ///
+    /// let expr = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|expr| expr
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'))
+    ///     .map(Expr::List)
+    ///     // If parsing a list expression fails, recover at the next delimiter, generating an error AST node
+    ///     .recover_with(via_parser(recovery.map(|_| Expr::Error)))
+    ///     .or(text::int(10).map(Expr::Int))
+    ///     .padded());
+    ///
+    /// assert!(expr.parse("five").has_errors()); // Text is not a valid expression in this language...
+    /// assert_eq!(
+    ///     expr.parse("[1, 2, 3]").into_result(),
+    ///     Ok(Expr::List(vec![Expr::Int("1"), Expr::Int("2"), Expr::Int("3")])),
+    /// ); // ...but lists and numbers are!
+    ///
+    /// // This input has two syntax errors...
+    /// let res = expr.parse("[[1, two], [3, four]]");
+    /// // ...and error recovery allows us to catch both of them!
+    /// assert_eq!(res.errors().len(), 2);
+    /// // Additionally, the AST we get back still has useful information.
+    /// assert_eq!(res.output(), Some(&Expr::List(vec![Expr::Error, Expr::Error])));
+    /// ```
+    fn recover_with<S: Strategy<'a, I, O, E>>(self, strategy: S) -> RecoverWith<Self, S>
+    where
+        Self: Sized,
+    {
+        RecoverWith {
+            parser: self,
+            strategy,
+        }
+    }
+
+    /// Map the primary error of this parser to another value.
+    ///
+    /// This function is most useful when using a custom error type, allowing you to augment errors according to
+    /// context.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    // TODO: Map E -> D, not E -> E
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: Fn(E::Error) -> E::Error,
+    {
+        MapErr {
+            parser: self,
+            mapper: f,
+        }
+    }
+
+    // /// Map the primary error of this parser to another value, making use of the span from the start of the attempted
+    // /// to the point at which the error was encountered.
+    // ///
+    // /// This function is useful for augmenting errors to allow them to display the span of the initial part of a
+    // /// pattern, for example to add a "while parsing" clause to your error messages.
+    // ///
+    // /// The output type of this parser is `O`, the same as the original parser.
+    // ///
+    // // TODO: Map E -> D, not E -> E
+    // fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
+    // where
+    //     Self: Sized,
+    //     F: Fn(E::Error, I::Span) -> E::Error,
+    // {
+    //     MapErrWithSpan {
+    //         parser: self,
+    //         mapper: f,
+    //     }
+    // }
+
+    /// Map the primary error of this parser to another value, making use of the parser state.
+    ///
+    /// This function is useful for augmenting errors to allow them to include context in non context-free
+    /// languages, or provide contextual notes on possible causes.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    // TODO: Map E -> D, not E -> E
+    fn map_err_with_state<F>(self, f: F) -> MapErrWithState<Self, F>
+    where
+        Self: Sized,
+        F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+    {
+        MapErrWithState {
+            parser: self,
+            mapper: f,
+        }
+    }
+
+    /// Validate an output, producing non-terminal errors if it does not fulfil certain criteria.
+    ///
+    /// This function also permits mapping the output to a value of another type, similar to [`Parser::map`].
+    ///
+    /// If you wish parsing of this pattern to halt when an error is generated instead of continuing, consider using
+    /// [`Parser::try_map`] instead.
+    ///
+    /// The output type of this parser is `U`, the result of the validation closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let large_int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped()
+    ///     .validate(|x: u32, span, emitter| {
+    ///         if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher.", x))) }
+    ///         x
+    ///     });
+    ///
+    /// assert_eq!(large_int.parse("537").into_result(), Ok(537));
+    /// assert!(large_int.parse("243").into_result().is_err());
+    /// ```
+    fn validate<U, F>(self, f: F) -> Validate<Self, O, F>
+    where
+        Self: Sized,
+        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
+    {
+        Validate {
+            parser: self,
+            validator: f,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    // /// Map the primary error of this parser to a result. If the result is [`Ok`], the parser succeeds with that value.
+    // ///
+    // /// Note that, if the closure returns [`Err`], the parser will not consume any input.
+    // ///
+    // /// The output type of this parser is `U`, the [`Ok`] type of the result.
+    // fn or_else<F>(self, f: F) -> OrElse<Self, F>
+    // where
+    //     Self: Sized,
+    //     F: Fn(E::Error) -> Result<O, E::Error>,
+    // {
+    //     OrElse {
+    //         parser: self,
+    //         or_else: f,
+    //     }
+    // }
+
+    /// Attempt to convert the output of this parser into something else using Rust's [`FromStr`] trait.
+    ///
+    /// This is most useful when wanting to convert literal values into their corresponding Rust type, such as when
+    /// parsing integers.
+    ///
+    /// The output type of this parser is `Result<U, U::Err>`, the result of attempting to parse the output, `O`, into
+    /// the value `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let uint64 = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<u64>()
+    ///     .unwrapped();
+    ///
+    /// assert_eq!(uint64.parse("7").into_result(), Ok(7));
+    /// assert_eq!(uint64.parse("42").into_result(), Ok(42));
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    fn from_str<U>(self) -> Map<Self, O, fn(O) -> Result<U, U::Err>>
+    where
+        Self: Sized,
+        U: FromStr,
+        O: AsRef<str>,
+    {
+        self.map(|o| o.as_ref().parse())
+    }
+
+    /// For parsers that produce a [`Result`] as their output, unwrap the result (panicking if an [`Err`] is
+    /// encountered).
+    ///
+    /// In general, this method should be avoided except in cases where all possible that the parser might produce can
+    /// by parsed using [`FromStr`] without producing an error.
+    ///
+    /// This combinator is not named `unwrap` to avoid confusion: it unwraps *during parsing*, not immediately.
+    ///
+    /// The output type of this parser is `U`, the [`Ok`] value of the [`Result`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let boolean = just::<_, _, extra::Err<Simple<char>>>("true")
+    ///     .or(just("false"))
+    ///     .from_str::<bool>()
+    ///     .unwrapped(); // Cannot panic: the only possible outputs generated by the parser are "true" or "false"
+    ///
+    /// assert_eq!(boolean.parse("true").into_result(), Ok(true));
+    /// assert_eq!(boolean.parse("false").into_result(), Ok(false));
+    /// // Does not panic, because the original parser only accepts "true" or "false"
+    /// assert!(boolean.parse("42").has_errors());
+    /// ```
+    #[track_caller]
+    fn unwrapped(self) -> Unwrapped<Self, O>
+    where
+        Self: Sized,
+    {
+        Unwrapped {
+            parser: self,
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Box the parser, yielding a parser that performs parsing through dynamic dispatch.
+    ///
+    /// Boxing a parser might be useful for:
+    ///
+    /// - Dynamically building up parsers at run-time
+    ///
+    /// - Improving compilation times (Rust can struggle to compile code containing very long types)
+    ///
+    /// - Passing a parser over an FFI boundary
+    ///
+    /// - Getting around compiler implementation problems with long types such as
+    ///   [this](https://github.com/rust-lang/rust/issues/54540).
+    ///
+    /// - Places where you need to name the type of a parser
+    ///
+    /// Boxing a parser is broadly equivalent to boxing other combinators via dynamic dispatch, such as [`Iterator`].
+    ///
+    /// Boxing a parser that is already [`Boxed`] returns it unchanged rather than adding another layer of dynamic
+    /// dispatch, so generic code may freely box the parsers it is given. [`Boxed`] parsers are reference-counted, so
+    /// cloning them is cheap.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(feature = "alloc")]
+    fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a + 'b,
+    {
+        ParserSealed::boxed(self)
+    }
+
+    /// Use Pratt parsing to efficiently parse binary operators
+    /// with different associativity.
+    ///
+    /// The parsing algorithm currently uses recursion
+    /// to parse nested expressions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chumsky::prelude::*;
+    /// use chumsky::pratt::{InfixOperator, InfixPrecedence, Associativity};
+    ///
+    /// #[derive(Clone, Copy, Debug)]
+    /// enum Operator {
+    ///     Add,
+    ///     Sub,
+    ///     Mul,
+    ///     Div,
+    /// }
+    ///
+    /// enum Expr {
+    ///     Literal(i64),
+    ///     Add(Box<Expr>, Box<Expr>),
+    ///     Sub(Box<Expr>, Box<Expr>),
+    ///     Mul(Box<Expr>, Box<Expr>),
+    ///     Div(Box<Expr>, Box<Expr>),
+    /// }
+    ///
+    /// impl std::fmt::Display for Expr {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         match self {
+    ///             Self::Literal(literal) => write!(f, "{literal}"),
+    ///             Self::Add(left, right) => write!(f, "({left} + {right})"),
+    ///             Self::Sub(left, right) => write!(f, "({left} - {right})"),
+    ///             Self::Mul(left, right) => write!(f, "({left} * {right})"),
+    ///             Self::Div(left, right) => write!(f, "({left} / {right})"),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl InfixOperator<Expr> for Operator {
+    ///     type Strength = u8;
+    ///
+    ///     fn precedence(&self) -> InfixPrecedence<Self::Strength> {
+    ///         // NOTE: Usually, in Rust for example, all these operators
+    ///         // are left-associative. However, in this example we define
+    ///         // then with different associativities for demonstration purposes.
+    ///         // (Although it doesn't really matter here since these operations
+    ///         // are commutative for integers anyway.)
+    ///         match self {
+    ///             Self::Add => InfixPrecedence::new(0, Associativity::Left),
+    ///             Self::Sub => InfixPrecedence::new(0, Associativity::Left),
+    ///             Self::Mul => InfixPrecedence::new(1, Associativity::Right),
+    ///             Self::Div => InfixPrecedence::new(1, Associativity::Right),
+    ///         }
+    ///     }
+    ///
+    ///     fn build_expression(self, left: Expr, right: Expr) -> Expr {
+    ///         let (left, right) = (Box::new(left), Box::new(right));
+    ///         match self {
+    ///             Self::Add => Expr::Add(left, right),
+    ///             Self::Sub => Expr::Sub(left, right),
+    ///             Self::Mul => Expr::Mul(left, right),
+    ///             Self::Div => Expr::Div(left, right),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let atom = text::int::<_, _, extra::Default>(10)
+    ///     .from_str()
+    ///     .unwrapped()
+    ///     .map(Expr::Literal);
+    ///
+    /// let operator = choice((
+    ///     just('+').to(Operator::Add),
+    ///     just('-').to(Operator::Sub),
+    ///     just('*').to(Operator::Mul),
+    ///     just('/').to(Operator::Div),
+    /// ));
+    ///
+    /// let expr = atom.pratt(operator.padded_by(just(' ')));
+    /// let expr_str = expr.map(|expr| expr.to_string()).then_ignore(end());
+    /// assert_eq!(expr_str.parse("1 + 2").into_result(), Ok("(1 + 2)".to_string()));
+    /// // `*` binds more strongly than `+`
+    /// assert_eq!(expr_str.parse("1 * 2 + 3").into_result(), Ok("((1 * 2) + 3)".to_string()));
+    /// assert_eq!(expr_str.parse("1 + 2 * 3").into_result(), Ok("(1 + (2 * 3))".to_string()));
+    /// // `+` is left-associative
+    /// assert_eq!(expr_str.parse("1 + 2 + 3").into_result(), Ok("((1 + 2) + 3)".to_string()));
+    /// // `*` is right-associative (in this example)
+    /// assert_eq!(expr_str.parse("1 * 2 * 3").into_result(), Ok("(1 * (2 * 3))".to_string()));
+    /// ```
+    fn pratt<OpParser, Op>(self, op_parser: OpParser) -> Pratt<E, Self, O, OpParser, Op>
+    where
+        Self: Sized,
+        OpParser: Parser<'a, I, Op, E>,
+        Op: pratt::InfixOperator<O>,
+    {
+        Pratt {
+            parser_atom: self,
+            parser_op: op_parser,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, I, O, E> ParserSealed<'a, I, O, E> for !
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        *self
+    }
+
+    go_extra!(O);
+}
+
+/// A parser that can be configured with runtime context
+pub trait ConfigParser<'a, I, O, E>: ConfigParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// A combinator that allows configuration of the parser from the current context
+    fn configure<F>(self, cfg: F) -> Configure<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context) -> Self::Config,
+    {
+        Configure { parser: self, cfg }
+    }
+}
+
+/// An iterator that wraps an iterable parser. See [`IterParser::parse_iter`].
+#[cfg(all(test, feature = "alloc"))]
+pub struct ParserIter<'a, 'iter, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>>
+{
+    parser: P,
+    offset: I::Offset,
+    own: InputOwn<'a, 'iter, I, E>,
+    iter_state: Option<P::IterState<Emit>>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(&'a (), O)>,
+}
+
+#[cfg(all(test, feature = "alloc"))]
+impl<'a, 'iter, P, I: Input<'a>, O, E: ParserExtra<'a, I>> Iterator
+    for ParserIter<'a, 'iter, P, I, O, E>
+where
+    P: IterParser<'a, I, O, E>,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut inp = self.own.as_ref_at(self.offset);
+        let parser = &self.parser;
+
+        let iter_state = match &mut self.iter_state {
+            Some(state) => state,
+            None => {
+                let state = parser.make_iter::<Emit>(&mut inp).ok()?;
+                self.iter_state = Some(state);
+                self.iter_state.as_mut().unwrap()
+            }
+        };
+
+        let res = parser.next::<Emit>(&mut inp, iter_state);
+        self.offset = inp.offset;
+        res.ok().and_then(|res| res)
+    }
+}
+
+/// An iterable equivalent of [`Parser`], i.e: a parser that generates a sequence of outputs.
+pub trait IterParser<'a, I, O, E = extra::Default>: IterParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// Collect this iterable parser into a [`Container`].
+    ///
+    /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
+    /// [`Vec`]s, [`String`]s, or even [`HashMap`]s. This method is analogous to [`Iterator::collect`].
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic()) // This parser produces an output of `char`
+    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
+    ///     .collect::<String>(); // We collect the `char`s into a `String`
+    ///
+    /// assert_eq!(word.parse("hello").into_result(), Ok("hello".to_string()));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect<C: Container<O>>(self) -> Collect<Self, O, C>
+    where
+        Self: Sized,
+    {
+        Collect {
+            parser: self,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`bumpalo::collections::Vec`], allocated in the arena provided by the
+    /// parser's state.
+    ///
+    /// The state must implement [`ArenaState`](arena::ArenaState), such as a plain `&'bump Bump`. See the [`arena`]
+    /// module for more information.
+    ///
+    /// The output type of this iterable parser is `bumpalo::collections::Vec<'bump, O>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, arena::Bump};
+    /// let bump = Bump::new();
+    /// let digits = any::<_, extra::State<&Bump>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated()
+    ///     .collect_in_arena();
+    ///
+    /// let out = digits.parse_with_state("123", &mut &bump).into_result().unwrap();
+    /// assert_eq!(out.as_slice(), &['1', '2', '3']);
+    /// ```
+    #[cfg(feature = "bumpalo")]
+    fn collect_in_arena<'bump>(self) -> arena::CollectInArena<'bump, Self, O>
+    where
+        Self: Sized,
+    {
+        arena::CollectInArena {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`ContainerExactly`].
+    ///
+    /// This is useful for situations where the number of items to consume is statically known.
+    /// A common use-case is collecting into an array.
+    ///
+    /// The output type of this iterable parser if `C`, the type being collected into.
+    ///
+    /// # Exmaples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let three_digit = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_numeric())
+    ///     .repeated()
+    ///     .collect_exactly::<[_; 3]>();
+    ///
+    /// assert_eq!(three_digit.parse("123").into_result(), Ok(['1', '2', '3']));
+    /// assert!(three_digit.parse("12").into_result().is_err());
+    /// assert!(three_digit.parse("1234").into_result().is_err());
+    /// ```
+    fn collect_exactly<C: ContainerExactly<O>>(self) -> CollectExactly<Self, O, C>
+    where
+        Self: Sized,
+    {
+        CollectExactly {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`usize`], outputting the number of elements that were parsed.
+    ///
+    /// This is sugar for [`.collect::<usize>()`](Self::collect).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    ///
+    /// // Counts how many chess squares are in the input.
+    /// let squares = one_of::<_, _, extra::Err<Simple<char>>>('a'..='z').then(one_of('1'..='8')).padded().repeated().count();
+    ///
+    /// assert_eq!(squares.parse("a1 b2 c3").into_result(), Ok(3));
+    /// assert_eq!(squares.parse("e5 e7 c6 c7 f6 d5 e6 d7 e4 c5 d6 c4 b6 f5").into_result(), Ok(14));
+    /// assert_eq!(squares.parse("").into_result(), Ok(0));
+    /// ```
+    fn count(self) -> Collect<Self, O, usize>
+    where
+        Self: Sized,
+    {
+        self.collect()
+    }
+
+    /// Enumerate outputs of this iterable parser.
+    ///
+    /// This function behaves in a similar way to [`Iterator::enumerate`].
+    ///
+    /// The output type of this iterable parser is `(usize, O)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
+    ///     .enumerate()
+    ///     .collect::<Vec<(usize, &str)>>();
+    ///
+    /// assert_eq!(word.parse("hello world").into_result(), Ok(vec![(0, "hello"), (1, "world")]));
+    /// ```
+    ///
+    /// The index can be used when folding or collecting elements, such as when attaching column numbers to fields:
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// #[derive(Debug, PartialEq)]
+    /// struct Field<'a> { column: usize, value: &'a str }
+    ///
+    /// let field = none_of::<_, _, extra::Err<Simple<char>>>(",\n").repeated().slice();
+    /// let row = field
+    ///     .separated_by(just(','))
+    ///     .enumerate()
+    ///     .collect::<Vec<_>>()
+    ///     .map(|fields| fields.into_iter().map(|(column, value)| Field { column, value }).collect::<Vec<_>>());
+    ///
+    /// assert_eq!(
+    ///     row.parse("a,b,c").into_result(),
+    ///     Ok(vec![
+    ///         Field { column: 0, value: "a" },
+    ///         Field { column: 1, value: "b" },
+    ///         Field { column: 2, value: "c" },
+    ///     ]),
+    /// );
+    /// ```
+    fn enumerate(self) -> Enumerate<Self, O>
+    where
+        Self: Sized,
+    {
+        Enumerate {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Right-fold the output of the parser into a single value.
+    ///
+    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
+    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    ///
+    /// The output type of this iterable parser is `B`, the right-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let signed = just('+').to(1)
+    ///     .or(just('-').to(-1))
+    ///     .repeated()
+    ///     .foldr(int, |a, b| a * b);
+    ///
+    /// assert_eq!(signed.parse("3").into_result(), Ok(3));
+    /// assert_eq!(signed.parse("-17").into_result(), Ok(-17));
+    /// assert_eq!(signed.parse("--+-+-5").into_result(), Ok(5));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    #[cfg(feature = "alloc")]
+    fn foldr<B, F, OA>(self, other: B, f: F) -> Foldr<F, Self, B, O, E>
+    where
+        F: Fn(O, OA) -> OA,
+        B: Parser<'a, I, OA, E>,
+        Self: Sized,
+    {
+        Foldr {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Right-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    ///
+    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
+    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    ///
+    /// The output type of this parser is `B`, the right-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let signed = just('+').to(1)
+    ///     .or(just('-').to(-1))
+    ///     .repeated()
+    ///     .foldr_with_state(int, |a, b, state| {
+    ///         (*state) += 1;
+    ///         (a * b)
+    ///     });
+    /// let mut folds = 0i32;
+    /// assert_eq!(signed.parse_with_state("3", &mut folds).into_result(), Ok(3));
+    /// assert_eq!(signed.parse_with_state("-17", &mut folds).into_result(), Ok(-17));
+    /// assert_eq!(signed.parse_with_state("--+-+-5", &mut folds).into_result(), Ok(5));
+    /// ```
+    // TODO: Add examples of interning/arena allocation
+    #[cfg_attr(debug_assertions, track_caller)]
+    #[cfg(feature = "alloc")]
+    fn foldr_with_state<B, F, OA>(self, other: B, f: F) -> FoldrWithState<F, Self, B, OA, E>
+    where
+        F: Fn(O, OA, &mut E::State) -> OA,
+        B: Parser<'a, I, OA, E>,
+        Self: Sized,
+    {
+        FoldrWithState {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Fold the outputs of this iterable parser into an accumulator, stopping early if the fold function returns
+    /// [`ControlFlow::Break`].
+    ///
+    /// This behaves like [`Iterator::try_fold`], except that breaking out of the fold is not an error: the parser
+    /// succeeds with the accumulated value, leaving any remaining input unconsumed. This is useful for data-dependent
+    /// repetition, such as reading records until their combined length reaches a limit stated by a header.
+    ///
+    /// Note that because the decision to stop depends on the outputs of the parser, these outputs are always generated,
+    /// even when the output of this parser is not needed.
+    ///
+    /// The output type of this parser is `B`, the type of the accumulator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// use std::ops::ControlFlow;
+    ///
+    /// // Read words until their total length reaches at least 8 characters
+    /// let words = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated()
+    ///     .try_fold(Vec::new(), |mut words, word: &str| {
+    ///         words.push(word);
+    ///         if words.iter().map(|w| w.len()).sum::<usize>() >= 8 {
+    ///             ControlFlow::Break(words)
+    ///         } else {
+    ///             ControlFlow::Continue(words)
+    ///         }
+    ///     });
+    ///
+    /// assert_eq!(words.clone().lazy().parse("hello world again").into_result(), Ok(vec!["hello", "world"]));
+    /// // Running out of input before breaking is not an error
+    /// assert_eq!(words.parse("a b c").into_result(), Ok(vec!["a", "b", "c"]));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn try_fold<B, F>(self, init: B, f: F) -> TryFold<Self, O, B, F>
+    where
+        B: Clone,
+        F: Fn(B, O) -> ControlFlow<B, B>,
+        Self: Sized,
+    {
+        TryFold {
+            parser: self,
+            init,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a stream of tokens, passing each output of this iterable parser to `sink` as soon as it is produced
+    /// instead of collecting them.
+    ///
+    /// This allows very large inputs made up of many repeated items, such as multi-gigabyte streams of records, to be
+    /// processed without holding every output in memory at once. As with [`Parser::parse`], the whole input must be
+    /// consumed. Items passed to the sink before an error was encountered are not retracted, so the sink should be
+    /// prepared to discard its work if the returned [`ParseResult`] has errors.
+    ///
+    /// If you want to include non-default state, use [`IterParser::parse_with_state_and_sink`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let record = text::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .then_ignore(just(':').padded())
+    ///     .then(text::int(10).from_str::<u64>().unwrapped())
+    ///     .then_ignore(text::newline());
+    ///
+    /// let mut total = 0;
+    /// let mut longest = "";
+    /// let result = record.repeated().parse_with_sink("a: 3\nbcd: 4\nef: 5\n", |(name, n)| {
+    ///     total += n;
+    ///     if name.len() > longest.len() {
+    ///         longest = name;
+    ///     }
+    /// });
+    ///
+    /// assert!(!result.has_errors());
+    /// assert_eq!((total, longest), (12, "bcd"));
+    /// ```
+    fn parse_with_sink<F>(&self, input: I, sink: F) -> ParseResult<(), E::Error>
+    where
+        Self: Sized,
+        E::State: Default,
+        E::Context: Default,
+        F: FnMut(O),
+    {
+        self.parse_with_state_and_sink(input, &mut E::State::default(), sink)
+    }
+
+    /// Parse a stream of tokens with the given state, passing each output of this iterable parser to `sink` as soon as
+    /// it is produced. See [`IterParser::parse_with_sink`].
+    fn parse_with_state_and_sink<F>(
+        &self,
+        input: I,
+        state: &mut E::State,
+        mut sink: F,
+    ) -> ParseResult<(), E::Error>
+    where
+        Self: Sized,
+        E::Context: Default,
+        F: FnMut(O),
+    {
+        let mut own = InputOwn::new_state(input, state);
+        let mut inp = own.as_ref_start();
+        let res = self.make_iter::<Emit>(&mut inp).and_then(|mut iter_state| {
+            loop {
+                match self.next::<Emit>(&mut inp, &mut iter_state) {
+                    Ok(Some(out)) => sink(out),
+                    Ok(None) => break,
+                    Err(()) => return Err(()),
+                }
+            }
+            end().go::<Check>(&mut inp)
+        });
+        let alt = inp.errors.alt.take();
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(()) => Some(()),
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        ParseResult::new(out, errs)
+    }
+
+    /// Create an iterator over the outputs generated by an iterable parser.
+    ///
+    /// Warning: Trailing errors will be ignored
+    // TODO: Stabilize once error handling is properly decided on
+    #[cfg(all(test, feature = "alloc"))]
+    fn parse_iter(self, input: I) -> ParseResult<ParserIter<'a, 'static, Self, I, O, E>, E::Error>
+    where
+        Self: IterParser<'a, I, O, E> + Sized,
+        E::State: Default,
+        E::Context: Default,
+    {
+        ParseResult::new(
+            Some(ParserIter {
+                parser: self,
+                offset: input.start(),
+                own: InputOwn::new(input),
+                iter_state: None,
+                phantom: EmptyPhantom::new(),
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Create an iterator over the outputs generated by an iterable parser with the given parser state.
+    ///
+    /// Warning: Trailing errors will be ignored
+    // TODO: Stabilize once error handling is properly decided on
+    #[cfg(all(test, feature = "alloc"))]
+    fn parse_iter_with_state<'parse>(
+        self,
+        input: I,
+        state: &'parse mut E::State,
+    ) -> ParseResult<ParserIter<'a, 'parse, Self, I, O, E>, E::Error>
+    where
+        Self: IterParser<'a, I, O, E> + Sized,
+        E::Context: Default,
+    {
+        ParseResult::new(
+            Some(ParserIter {
+                parser: self,
+                offset: input.start(),
+                own: InputOwn::new_state(input, state),
+                iter_state: None,
+                phantom: EmptyPhantom::new(),
+            }),
+            Vec::new(),
+        )
+    }
+}
+
+/// An iterable equivalent of [`ConfigParser`], i.e: a parser that generates a sequence of outputs and
+/// can be configured at runtime.
+pub trait ConfigIterParser<'a, I, O, E = extra::Default>:
+    ConfigIterParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// A combinator that allows configuration of the parser from the current context
+    fn configure<F>(self, cfg: F) -> IterConfigure<Self, F, O>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context) -> Self::Config,
+    {
+        IterConfigure {
+            parser: self,
+            cfg,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// A combinator that allows fallible configuration of the parser from the current context -
+    /// if an error is returned, parsing fails.
+    fn try_configure<F>(self, cfg: F) -> TryIterConfigure<Self, F, O>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context, I::Span) -> Result<Self::Config, E::Error>,
+    {
+        TryIterConfigure {
+            parser: self,
+            cfg,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// See [`Parser::boxed`].
+///
+/// Due to current implementation details, the inner value is not, in fact, a [`Box`], but is an [`Rc`] to facilitate
+/// efficient cloning. This is likely to change in the future. Unlike [`Box`], [`Rc`] has no size guarantees: although
+/// it is *currently* the same size as a raw pointer.
+// TODO: Don't use an Rc
+#[cfg(feature = "alloc")]
+pub struct Boxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    inner: RefC<DynParser<'a, 'b, I, O, E>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for Boxed<'a, 'b, I, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Boxed<'a, 'b, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        M::invoke(&*self.inner, inp)
+    }
+
+    fn boxed<'c>(self) -> Boxed<'a, 'c, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a + 'c,
+    {
+        // Never double-box parsers
+        self
+    }
+
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        self.inner.skip_leading_trivia(inp)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        self.inner.first_tokens()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        self.inner.graph_node(graph)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        self.inner.gen_input(gen)
+    }
+
+    go_extra!(O);
+}
+
+/// Create a parser that selects one or more input patterns and map them to an output value.
+///
+/// This is most useful when turning the tokens of a previous compilation pass (such as lexing) into data that can be
+/// used for parsing, although it can also generally be used to select inputs and map them to outputs. Any unmapped
+/// input patterns will become syntax errors, just as with [`Parser::filter`].
+///
+/// Internally, [`select!`] is very similar to [`Parser::try_map`] and thinking of it as such might make it less
+/// confusing.
+///
+/// `select!` requires that tokens implement [`Clone`].
+///
+/// If you're trying to access tokens referentially (for the sake of nested parsing, or simply because you want to
+/// avoid cloning the token), see [`select_ref!`].
+///
+/// # Examples
+///
+/// `select!` is syntactically similar to a `match` expression and has support for
+/// [pattern guards](https://doc.rust-lang.org/reference/expressions/match-expr.html#match-guards):
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// #[derive(Clone)]
+/// enum Token<'a> { Ident(&'a str) }
+///
+/// enum Expr<'a> { Local(&'a str), Null, True, False }
+///
+/// # let _: chumsky::primitive::Select<_, &[Token], Expr, extra::Default> =
+/// select! {
+///     Token::Ident(s) if s == "true" => Expr::True,
+///     Token::Ident(s) if s == "false" => Expr::False,
+///     Token::Ident(s) if s == "null" => Expr::Null,
+///     Token::Ident(s) => Expr::Local(s),
+/// }
+/// # ;
+/// ```
+///
+/// If you require access to the token's span, you may add an argument after a pattern to gain access to it:
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// #[derive(Clone)]
+/// enum Token<'a> { Num(f64), Str(&'a str) }
+///
+/// enum Expr<'a> { Num(f64), Str(&'a str) }
+///
+/// type Span = SimpleSpan<usize>;
+///
+/// impl<'a> Expr<'a> {
+///     fn spanned(self, span: Span) -> (Self, Span) { (self, span) }
+/// }
+///
+/// # let _: chumsky::primitive::Select<_, &[Token], (Expr, Span), extra::Default> =
+/// select! {
+///     Token::Num(x) = span => Expr::Num(x).spanned(span),
+///     Token::Str(s) = span => Expr::Str(s).spanned(span),
+/// }
+/// # ;
+/// ```
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// // The type of our parser's input (tokens like this might be emitted by your compiler's lexer)
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token {
+///     Num(u64),
+///     Bool(bool),
+///     LParen,
+///     RParen,
+/// }
+///
+/// // The type of our parser's output, a syntax tree
+/// #[derive(Debug, PartialEq)]
+/// enum Ast {
+///     Num(u64),
+///     Bool(bool),
+///     List(Vec<Ast>),
+/// }
+///
+/// // Our parser converts a stream of input tokens into an AST
+/// // `select!` is used to deconstruct some of the tokens and turn them into AST nodes
+/// let ast = recursive::<_, _, extra::Err<Simple<Token>>, _, _>(|ast| {
+///     let literal = select! {
+///         Token::Num(x) => Ast::Num(x),
+///         Token::Bool(x) => Ast::Bool(x),
+///     };
+///
+///     literal.or(ast
+///         .repeated()
+///         .collect()
+///         .delimited_by(just(Token::LParen), just(Token::RParen))
+///         .map(Ast::List))
+/// });
+///
+/// use Token::*;
+/// assert_eq!(
+///     ast.parse(&[LParen, Num(5), LParen, Bool(false), Num(42), RParen, RParen]).into_result(),
+///     Ok(Ast::List(vec![
+///         Ast::Num(5),
+///         Ast::List(vec![
+///             Ast::Bool(false),
+///             Ast::Num(42),
+///         ]),
+///     ])),
+/// );
+/// ```
+#[macro_export]
+macro_rules! select {
+    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
+        $crate::primitive::select(
+            move |x, span| match x {
+                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
+                _ => ::core::option::Option::None,
+            }
+        )
+    });
+}
+
+/// A version of [`select!`] that selects on token by reference instead of by value.
+///
+/// Useful if you want to extract elements from a token in a zero-copy manner.
+///
+/// `select_ref` requires that the parser input implements [`BorrowInput`].
+#[macro_export]
+macro_rules! select_ref {
+    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
+        $crate::primitive::select_ref(
+            move |x, span| match x {
+                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
+                _ => ::core::option::Option::None,
+            }
+        )
+    });
+}
+
+/// Implement [`FromStr`](core::str::FromStr) for a type using a parser, so that it can be parsed with
+/// [`str::parse`].
+///
+/// The macro is given the type, an expression that creates the parser, and a conversion from the errors of the
+/// parser (an [`ErrorVec`](util::ErrorVec) of them) to the error type of the implementation, written like a closure
+/// with an explicit return type. As with [`Parser::parse`], the whole string must be matched by the parser. Adding
+/// `TryFrom` at the end also implements `TryFrom<&str>` for the type.
+///
+/// The type must not have generic parameters, and the output of the parser must not borrow from the string.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, impl_from_str};
+/// #[derive(Debug, PartialEq)]
+/// struct Rgb(u8, u8, u8);
+///
+/// #[derive(Debug, PartialEq)]
+/// struct ParseRgbError(String);
+///
+/// fn rgb<'a>() -> impl Parser<'a, &'a str, Rgb, extra::Err<Rich<'a, char>>> {
+///     let channel = text::digits(16)
+///         .exactly(2)
+///         .slice()
+///         .map(|hex| u8::from_str_radix(hex, 16).unwrap());
+///     just('#')
+///         .ignore_then(channel.repeated().collect_exactly::<[u8; 3]>())
+///         .map(|[r, g, b]| Rgb(r, g, b))
+/// }
+///
+/// impl_from_str!(Rgb, rgb(), |errors| -> ParseRgbError { ParseRgbError(errors[0].to_string()) }, TryFrom);
+///
+/// assert_eq!("#ff8000".parse(), Ok(Rgb(255, 128, 0)));
+/// assert_eq!(Rgb::try_from("#000000"), Ok(Rgb(0, 0, 0)));
+/// // The whole string must be a colour
+/// assert_eq!(
+///     "#ff8000!".parse::<Rgb>(),
+///     Err(ParseRgbError("found '!' expected end of input".to_string())),
+/// );
+/// ```
+#[macro_export]
+macro_rules! impl_from_str {
+    ($ty:ty, $parser:expr, |$errors:pat_param| -> $err:ty $convert:block $(,)?) => {
+        impl ::core::str::FromStr for $ty {
+            type Err = $err;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match $crate::Parser::parse(&$parser, s).into_result() {
+                    ::core::result::Result::Ok(out) => ::core::result::Result::Ok(out),
+                    ::core::result::Result::Err($errors) => ::core::result::Result::Err($convert),
+                }
+            }
+        }
+    };
+    ($ty:ty, $parser:expr, |$errors:pat_param| -> $err:ty $convert:block, TryFrom $(,)?) => {
+        $crate::impl_from_str!($ty, $parser, |$errors| -> $err $convert);
+
+        impl ::core::convert::TryFrom<&str> for $ty {
+            type Error = $err;
+
+            fn try_from(s: &str) -> ::core::result::Result<Self, Self::Error> {
+                <Self as ::core::str::FromStr>::from_str(s)
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    std::thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // Counts the allocations made by each thread, so that tests can check that parsing doesn't allocate
+    struct CountingAlloc;
+
+    // SAFETY: allocation is delegated to the system allocator
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+    fn allocations(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    #[test]
+    fn zero_copy() {
+        use self::input::WithContext;
+        use self::prelude::*;
+
+        #[derive(PartialEq, Debug)]
+        enum Token<'a> {
+            Ident(&'a str),
+            String(&'a str),
+        }
+
+        type FileId = u32;
+
+        type Span = (FileId, SimpleSpan<usize>);
+
+        fn parser<'a>() -> impl Parser<'a, WithContext<FileId, &'a str>, [(Span, Token<'a>); 6]> {
+            let ident = any()
+                .filter(|c: &char| c.is_alphanumeric())
+                .repeated()
+                .at_least(1)
+                .map_slice(Token::Ident);
+
+            let string = just('"')
+                .then(any().filter(|c: &char| *c != '"').repeated())
+                .then(just('"'))
+                .map_slice(Token::String);
+
+            ident
+                .or(string)
+                .map_with_span(|token, span| (span, token))
+                .padded()
+                .repeated()
+                .collect_exactly()
+        }
+
+        assert_eq!(
+            parser()
+                .parse(r#"hello "world" these are "test" tokens"#.with_context(42))
+                .into_result(),
+            Ok([
+                ((42, (0..5).into()), Token::Ident("hello")),
+                ((42, (6..13).into()), Token::String("\"world\"")),
+                ((42, (14..19).into()), Token::Ident("these")),
+                ((42, (20..23).into()), Token::Ident("are")),
+                ((42, (24..30).into()), Token::String("\"test\"")),
+                ((42, (31..37).into()), Token::Ident("tokens")),
+            ]),
+        );
+    }
+
+    #[test]
+    fn zero_copy_repetition() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            any()
+                .filter(|c: &char| c.is_ascii_digit())
+                .repeated()
+                .at_least(1)
+                .at_most(3)
+                .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                .padded()
+                .separated_by(just(',').padded())
+                .allow_trailing()
+                .collect()
+                .delimited_by(just('['), just(']'))
+        }
+
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+
+        assert!(parser().parse("[1234,123,12,1]").has_errors());
+        assert!(parser().parse("[,0, 1, 456]").has_errors());
+        assert!(parser().parse("[3, 4, 5, 67 89,]").has_errors());
+    }
+
+    #[test]
+    fn zero_copy_group() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, (&'a str, u64, char)> {
+            group((
+                any()
+                    .filter(|c: &char| c.is_ascii_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .slice()
+                    .padded(),
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .map_slice(|s: &str| s.parse::<u64>().unwrap())
+                    .padded(),
+                any().filter(|c: &char| !c.is_whitespace()).padded(),
+            ))
+        }
+
+        assert_eq!(
+            parser().parse("abc 123 [").into_result(),
+            Ok(("abc", 123, '[')),
+        );
+        assert_eq!(
+            parser().parse("among3d").into_result(),
+            Ok(("among", 3, 'd')),
+        );
+        assert_eq!(
+            parser().parse("cba321,").into_result(),
+            Ok(("cba", 321, ',')),
+        );
+
+        assert!(parser().parse("abc 123  ").has_errors());
+        assert!(parser().parse("123abc ]").has_errors());
+        assert!(parser().parse("and one &").has_errors());
+    }
+
+    #[test]
+    fn zero_copy_group_array() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, [char; 3]> {
+            group([just('a'), just('b'), just('c')])
+        }
+
+        assert_eq!(parser().parse("abc").into_result(), Ok(['a', 'b', 'c']));
+        assert!(parser().parse("abd").has_errors());
+    }
+
+    #[test]
+    fn unicode_str() {
+        let input = "🄯🄚🹠🴎🄐🝋🰏🄂🬯🈦g🸵🍩🕔🈳2🬙🨞🅢🭳🎅h🵚🧿🏩🰬k🠡🀔🈆🝹🤟🉗🴟📵🰄🤿🝜🙘🹄5🠻🡉🱖🠓";
+        let mut own = InputOwn::<_, extra::Default>::new(input);
+        let mut inp = own.as_ref_start();
+
+        while inp.next().is_some() {}
+    }
+
+    #[test]
+    fn iter() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl IterParser<'a, &'a str, char> {
+            any().repeated()
+        }
+
+        let mut chars = String::new();
+        for c in parser().parse_iter("abcdefg").into_result().unwrap() {
+            chars.push(c);
+        }
+
+        assert_eq!(&chars, "abcdefg");
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn exponential() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+            recursive(|expr| {
+                let atom = any()
+                    .filter(|c: &char| c.is_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .collect()
+                    .or(expr.delimited_by(just('('), just(')')));
+
+                atom.clone()
+                    .then_ignore(just('+'))
+                    .then(atom.clone())
+                    .map(|(a, b)| format!("{}{}", a, b))
+                    .memoised()
+                    .or(atom)
+            })
+            .then_ignore(end())
+        }
+
+        parser()
+            .parse("((((((((((((((((((((((((((((((a+b))))))))))))))))))))))))))))))")
+            .into_result()
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn left_recursive() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+            recursive(|expr| {
+                let atom = any()
+                    .filter(|c: &char| c.is_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .collect();
+
+                let sum = expr
+                    .clone()
+                    .then_ignore(just('+'))
+                    .then(expr)
+                    .map(|(a, b)| format!("{}{}", a, b))
+                    .memoised();
+
+                sum.or(atom)
+            })
+            .then_ignore(end())
+        }
+
+        assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
+    }
+
+    #[test]
+    fn choice_predictive() {
+        use self::prelude::*;
+
+        macro_rules! tokens {
+            () => {
+                choice((
+                    just::<_, _, extra::Err<Rich<char>>>("=="),
+                    just("=").then(just('>')).slice(),
+                    just("=").or_not().slice().then_ignore(just(';')),
+                    one_of('0'..='9').repeated().at_least(1).slice(),
+                ))
+            };
+        }
+
+        for input in ["==", "=>", "=;", ";", "42", "=", "x", ""] {
+            assert_eq!(
+                tokens!().parse(input).into_result(),
+                tokens!().predictive().parse(input).into_result(),
+                "input: {input:?}",
+            );
+        }
+
+        let parser = choice([just::<_, _, extra::Default>('a'), just('b'), just('c')]).predictive();
+        assert_eq!(parser.parse("c").into_result(), Ok('c'));
+        assert!(parser.parse("d").has_errors());
+    }
+
+    #[test]
+    fn empty_err_no_alloc() {
+        use self::prelude::*;
+
+        let value = choice((
+            text::keyword::<_, _, _, extra::Default>("true").ignored(),
+            text::keyword("false").ignored(),
+            text::int(10).ignored(),
+            text::ident().ignored(),
+        ));
+        let list = value
+            .padded()
+            .separated_by(just(','))
+            .allow_trailing()
+            .delimited_by(just('['), just(']'));
+        let ops = choice((
+            just::<_, _, extra::Default>("->"),
+            just("-"),
+            just("=="),
+            just("="),
+        ))
+        .predictive()
+        .padded()
+        .repeated();
+
+        // Errors are zero-sized, so even failing parses don't allocate
+        for (input, ok) in [("[true, 12, foo,false ,]", true), ("[true, 12 foo]", false)] {
+            assert_eq!(
+                allocations(|| assert_eq!(list.check(input).has_errors(), !ok)),
+                0
+            );
+            assert_eq!(
+                allocations(|| assert_eq!(list.parse(input).has_errors(), !ok)),
+                0
+            );
+        }
+        for (input, ok) in [("-> - == =", true), ("-> + ==", false)] {
+            assert_eq!(
+                allocations(|| assert_eq!(ops.check(input).has_errors(), !ok)),
+                0
+            );
+        }
+        // Including parsers that report ranges of expected tokens
+        let digit = one_of::<_, _, extra::Default>('0'..='9');
+        assert_eq!(allocations(|| assert!(digit.parse("x").has_errors())), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_chunks() {
+        use self::prelude::*;
+
+        let item = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .map_with_span(|x: &str, span: SimpleSpan| (x.len(), span.into_range()))
+            .then_ignore(just(';'));
+
+        let input = "1;22;333;4444;";
+        let chunks = [0..2, 2..5, 5..9, 9..14];
+        assert_eq!(
+            parallel::parse_chunks(&item, input, chunks).into_result(),
+            Ok(vec![(1, 0..1), (2, 2..4), (3, 5..8), (4, 9..13)]),
+        );
+
+        // Errors in later chunks have spans relative to the whole input, and chunks may not run past their end
+        let result = parallel::parse_chunks(&item, "1;2x;3;", [0..2, 2..5, 5..7]);
+        assert_eq!(result.output(), None);
+        assert_eq!(
+            result.errors().map(|e| *e.span()).collect::<Vec<_>>(),
+            [SimpleSpan::from(3..4)]
+        );
+    }
+
+    #[test]
+    fn incremental_edits() {
+        use self::prelude::*;
+        use core::cell::Cell;
+        use incremental::Incremental;
+
+        let (a, b, c, d) = ("1;22;333;", "1;2x2;333;", "1;242;333;", "1;242;7;333;");
+        let parsed = Cell::new(0);
+        let item = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .then_ignore(just(';'))
+            .map(|x: &str| {
+                parsed.set(parsed.get() + 1);
+                x.len()
+            });
+        let check = |doc: &Incremental<_, _>, input| {
+            let full = Incremental::parse(&item, input);
+            assert_eq!(
+                doc.items().collect::<Vec<_>>(),
+                full.items().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                doc.errors().collect::<Vec<_>>(),
+                full.errors().collect::<Vec<_>>()
+            );
+        };
+
+        let mut doc = Incremental::parse(&item, a);
+        assert_eq!(parsed.replace(0), 3);
+
+        // Breaking an item stops parsing there
+        doc.edit(&item, b, 3..3, 1);
+        assert_eq!(parsed.replace(0), 0);
+        assert!(!doc.has_output());
+        check(&doc, b);
+        parsed.set(0);
+
+        // Fixing it reparses the rest of the input, since it was never parsed
+        doc.edit(&item, c, 3..4, 1);
+        assert_eq!(parsed.replace(0), 2);
+        assert!(doc.has_output());
+        check(&doc, c);
+        parsed.set(0);
+
+        // Inserting an item after another reparses the item it touches, then reuses the rest
+        doc.edit(&item, d, 6..6, 2);
+        assert_eq!(parsed.replace(0), 2);
+        assert_eq!(doc.outputs().collect::<Vec<_>>(), [&1, &3, &1, &3]);
+        assert_eq!(
+            doc.items().map(|(span, _)| span).collect::<Vec<_>>(),
+            [0..2, 2..6, 6..8, 8..12]
+        );
+        check(&doc, d);
+    }
+
+    #[test]
+    fn sink_streams_outputs() {
+        use self::prelude::*;
+
+        let item = text::int::<_, _, extra::Default>(10)
+            .from_str::<u32>()
+            .unwrapped()
+            .then_ignore(just(','));
+        let input = "1,".repeat(10_000);
+
+        // Outputs are never buffered
+        let mut sum = 0;
+        assert_eq!(
+            allocations(|| assert!(!item
+                .repeated()
+                .parse_with_sink(input.as_str(), |x| sum += x)
+                .has_errors())),
+            0
+        );
+        assert_eq!(sum, 10_000);
+
+        // Items before an error still reach the sink
+        let mut items = Vec::new();
+        let result = item.repeated().parse_with_sink("1,2,x", |x| items.push(x));
+        assert!(result.has_errors());
+        assert_eq!(items, [1, 2]);
+    }
+
+    #[test]
+    fn capacity_hint_preallocates() {
+        use self::prelude::*;
+
+        let input = "1,".repeat(1000);
+        let item = text::int::<_, _, extra::Default>(10);
+        let list = item
+            .separated_by(just(','))
+            .allow_trailing()
+            .with_capacity(1000)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            allocations(|| assert_eq!(
+                list.parse(input.as_str()).into_result().unwrap().len(),
+                1000
+            )),
+            1
+        );
+
+        let repeated = item
+            .then_ignore(just(','))
+            .repeated()
+            .with_capacity(1000)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            allocations(|| assert_eq!(
+                repeated.parse(input.as_str()).into_result().unwrap().len(),
+                1000
+            )),
+            1
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cancel")]
+    fn cancel_parse() {
+        use self::{
+            cancel::{CancelToken, Cancelled},
+            prelude::*,
+        };
+
+        // The token is cancelled partway through the parse, which then stops long before the end of the input
+        let token = CancelToken::new();
+        let items = Cell::new(0);
+        let parser = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .then_ignore(just(','))
+            .map(|_| {
+                items.set(items.get() + 1);
+                if items.get() == 100 {
+                    token.cancel();
+                }
+            })
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let input = "123,".repeat(100_000);
+        assert_eq!(
+            parser.parse_cancellable(input.as_str(), &token).map(|_| ()),
+            Err(Cancelled)
+        );
+        assert!(items.get() < 1000);
+
+        // Parsing normally ignores the token
+        items.set(0);
+        assert!(!parser.parse(input.as_str()).has_errors());
+        assert_eq!(items.get(), 100_000);
+    }
+
+    #[test]
+    fn parse_partial_returns_rest() {
+        use self::prelude::*;
+
+        let word = text::ident::<_, _, extra::Err<Simple<u8>>>().padded();
+        let input = b"alpha beta 42";
+        let (result, rest) = word.parse_partial(&input[..]);
+        assert_eq!(result.into_result(), Ok(&b"alpha"[..]));
+        assert_eq!(rest, b"beta 42");
+
+        // Peel words off until one fails to parse
+        let mut words = Vec::new();
+        let mut rest = &input[..];
+        loop {
+            let (result, after) = word.parse_partial(rest);
+            let Some(word) = result.into_output() else {
+                break;
+            };
+            words.push(word);
+            rest = after;
+        }
+        assert_eq!(words, [&b"alpha"[..], b"beta"]);
+        assert_eq!(rest, b"42");
+    }
+
+    #[test]
+    #[cfg(feature = "label")]
+    fn expected_at_offsets() {
+        use self::{error::RichPattern, prelude::*};
+
+        let name = || text::ident().padded().labelled("name");
+        let stmt = choice((
+            text::keyword::<_, _, _, extra::Err<Rich<char>>>("let")
+                .labelled("let")
+                .ignore_then(name())
+                .ignore_then(just('='))
+                .ignore_then(text::int(10).padded().labelled("number"))
+                .ignored(),
+            text::keyword("print")
+                .labelled("print")
+                .ignore_then(name())
+                .ignored(),
+        ))
+        .then_ignore(just(';').padded())
+        .repeated();
+
+        let input = "let x = 1; print x; prin";
+        let expected = |offset| stmt.expected_at(input, offset);
+        let keywords = [RichPattern::Label("let"), RichPattern::Label("print")];
+        assert_eq!(expected(0), keywords);
+        assert_eq!(expected(3), [RichPattern::Label("name")]);
+        assert_eq!(expected(4), [RichPattern::Label("name")]);
+        assert_eq!(expected(6), [RichPattern::Token('='.into())]);
+        assert_eq!(expected(8), [RichPattern::Label("number")]);
+        assert_eq!(expected(9), [RichPattern::Token(';'.into())]);
+        assert_eq!(expected(20), keywords);
+        // The input before the offset is malformed
+        assert_eq!(expected(24), []);
+    }
+
+    #[test]
+    fn atomic_errors() {
+        use self::prelude::*;
+
+        let pair = just::<_, _, extra::Err<Rich<char>>>('(')
+            .then(text::ident())
+            .then(just(')'))
+            .atomic();
+
+        // Partial matches are reported where the parser began
+        let errs = pair.parse("(a]").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..1));
+        assert_eq!(errs[0].found(), Some(&'('));
+
+        // Errors at the first token are unaffected
+        let errs = pair.parse("[a)").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..1));
+        assert_eq!(errs[0].expected().count(), 1);
+
+        // Including when the first token is more than one byte wide
+        let errs = pair.parse("ä)").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..2));
+        assert_eq!(errs[0].expected().count(), 1);
+        let errs = pair.parse("(é]").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..1));
+        assert_eq!(errs[0].found(), Some(&'('));
+    }
+
+    #[test]
+    fn separated_by_recover_elements() {
+        use self::{prelude::*, recovery::skip_until};
+
+        let list = |recover: bool| {
+            let item = text::ident::<_, _, extra::Err<Rich<char>>>().padded();
+            let skip = skip_until(any().ignored(), one_of(";)").ignored().rewind(), || "?");
+            let items = item.separated_by(just(';')).at_least(1);
+            let items = if recover {
+                Parser::boxed(items.recover_elements(skip).collect::<Vec<_>>())
+            } else {
+                Parser::boxed(items.collect::<Vec<_>>())
+            };
+            items.delimited_by(just('('), just(')'))
+        };
+
+        let (out, errs) = list(true).parse("(1; b; 2 3)").into_output_errors();
+        assert_eq!(out, Some(vec!["?", "b", "?"]));
+        assert_eq!(errs.len(), 2);
+
+        // Missing elements are reported as they would be without recovery
+        for input in ["()", "(a;)", "(a;;b)"] {
+            assert_eq!(
+                list(true).parse(input).into_errors(),
+                list(false).parse(input).into_errors(),
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "depth-limit")]
+    fn depth_limit() {
+        use self::prelude::*;
+
+        let deep = "[".repeat(100_000) + &"]".repeat(100_000);
+        let list = recursive::<_, _, extra::Err<Rich<char>>, _, _>(|list| {
+            list.with_depth_limit(8)
+                .repeated()
+                .delimited_by(just('['), just(']'))
+        });
+
+        // Siblings don't count towards the depth of each other
+        assert!(!list.parse("[[[[[[[[]]]]]]][[[[[[[[]]]]]]]]]").has_errors());
+
+        let errs = list.parse(deep.as_str()).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(9..10));
+    }
+
+    #[test]
+    #[cfg(feature = "label")]
+    fn labelled_choice_branches() {
+        use self::{error::RichPattern, prelude::*};
+
+        let item = labelled_choice([
+            ("statement", just::<_, _, extra::Err<Rich<char>>>("print x")),
+            ("declaration", just("let x")),
+        ]);
+        let labels = [
+            RichPattern::Label("statement"),
+            RichPattern::Label("declaration"),
+        ];
+        assert_eq!(item.expected_at("", 0), labels);
+
+        // Labels replace the expected tokens of branches that fail on their first token
+        let errs = item.parse("x").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..1));
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [&labels[0], &labels[1]]
+        );
+        let errs = item.parse("print y").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(6..7));
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [&RichPattern::Token('x'.into())],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "semantic")]
+    fn semantic_tokens() {
+        use self::{prelude::*, semantic::Category};
+
+        let string = just('"')
+            .then(none_of('"').repeated())
+            .then(just('"'))
+            .semantic_token(Category::String);
+        // Tokens recorded within the string are discarded in favour of the string itself
+        let value = choice((
+            text::int::<_, _, extra::Err<Rich<char>>>(10)
+                .semantic_token(Category::Number)
+                .ignored(),
+            just('"')
+                .semantic_token(Category::Operator)
+                .ignore_then(text::ident().semantic_token(Category::Variable))
+                .then_ignore(just('"'))
+                .semantic_token(Category::String)
+                .ignored(),
+            string.ignored(),
+        ));
+        let entry = text::ident()
+            .semantic_token(Category::Property)
+            .then_ignore(just(':').padded())
+            .then(value);
+        let entries = entry.padded().separated_by(just(',')).collect::<Vec<_>>();
+
+        let spans = |input| {
+            let (_, tokens) = entries.parse_semantic(input);
+            tokens
+                .into_iter()
+                .map(|(span, category): (SimpleSpan, _)| (span.into_range(), category))
+                .collect::<Vec<_>>()
+        };
+
+        // The string branch backtracks after recording an operator token, which is discarded
+        assert_eq!(
+            spans(r#"a: 1, b: "x y", c: "z""#),
+            [
+                (0..1, Category::Property),
+                (3..4, Category::Number),
+                (6..7, Category::Property),
+                (9..14, Category::String),
+                (16..17, Category::Property),
+                (19..22, Category::String),
+            ],
+        );
+        // Tokens before a parse error are still collected
+        assert_eq!(
+            spans("a: 1, b: 2 ?"),
+            [
+                (0..1, Category::Property),
+                (3..4, Category::Number),
+                (6..7, Category::Property),
+                (9..10, Category::Number),
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_deserialize() {
+        use self::{
+            prelude::*,
+            serde::{from_input, Error, Value},
+        };
+        use ::serde::Deserialize;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Shape {
+            Empty,
+            Circle(f64),
+            Rect { w: u32, h: u32 },
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Scene {
+            name: String,
+            shapes: Vec<Shape>,
+            origin: (i64, i64),
+            parent: Option<Box<Scene>>,
+        }
+
+        fn value<'a>() -> impl Parser<'a, &'a str, Value<'a>, extra::Err<Rich<'a, char>>> {
+            recursive(|value| {
+                let entry = text::ident()
+                    .padded()
+                    .map(Value::Str)
+                    .then_ignore(just(':').padded())
+                    .then(value.clone());
+                choice((
+                    just("null").to(Value::Unit),
+                    just('-')
+                        .or_not()
+                        .then(text::int(10))
+                        .slice()
+                        .from_str()
+                        .unwrapped()
+                        .map(Value::I64),
+                    none_of('"')
+                        .repeated()
+                        .slice()
+                        .delimited_by(just('"'), just('"'))
+                        .map(Value::Str),
+                    text::ident().map(Value::Str),
+                    value
+                        .separated_by(just(',').padded())
+                        .collect()
+                        .delimited_by(just('['), just(']'))
+                        .map(Value::Seq),
+                    entry
+                        .separated_by(just(',').padded())
+                        .allow_trailing()
+                        .collect()
+                        .delimited_by(just('{'), just('}'))
+                        .map(Value::Map),
+                ))
+                .padded()
+            })
+        }
+
+        let input = r#"{
+            name: "outer",
+            shapes: [Empty, {Circle: 2}, {Rect: {w: 3, h: 4}}],
+            origin: [-1, 5],
+            parent: {name: "inner", shapes: [], origin: [0, 0], parent: null},
+        }"#;
+        assert_eq!(
+            from_input::<Scene, _, _, _>(&value(), input),
+            Ok(Scene {
+                name: "outer".to_string(),
+                shapes: vec![Shape::Empty, Shape::Circle(2.0), Shape::Rect { w: 3, h: 4 }],
+                origin: (-1, 5),
+                parent: Some(Box::new(Scene {
+                    name: "inner".to_string(),
+                    shapes: Vec::new(),
+                    origin: (0, 0),
+                    parent: None,
+                })),
+            }),
+        );
+
+        assert!(matches!(
+            from_input::<Scene, _, _, _>(&value(), "{name: }"),
+            Err(Error::Parse(errs)) if errs.len() == 1,
+        ));
+        assert!(matches!(
+            from_input::<Shape, _, _, _>(&value(), "{Rect: [1, 2], Empty: null}"),
+            Err(Error::Custom(_)),
+        ));
+        assert!(matches!(
+            from_input::<Scene, _, _, _>(&value(), "{name: \"x\"}"),
+            Err(Error::Custom(msg)) if msg == "missing field `shapes`",
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_parseable() {
+        use self::{derive::Parseable, prelude::*};
+
+        #[derive(Debug, PartialEq, Parseable)]
+        #[parse(prefix = "point (", suffix = ")")]
+        struct Point {
+            x: f64,
+            #[parse(prefix = ",")]
+            y: f64,
+        }
+
+        #[derive(Debug, PartialEq, Parseable)]
+        enum Shape {
+            Circle(Point, #[parse(prefix = "radius")] f64),
+            Polygon {
+                #[parse(separator = "->", at_least = 3)]
+                points: Vec<Point>,
+                #[parse(prefix = "closed")]
+                closed: Option<bool>,
+            },
+            #[parse(prefix = "nothing", suffix = ";")]
+            Empty,
+        }
+
+        #[derive(Debug, PartialEq, Parseable)]
+        struct Scene {
+            #[parse(prefix = "scene")]
+            name: String,
+            #[parse(prefix = "{", suffix = "}")]
+            shapes: Vec<Shape>,
+        }
+
+        let parser = Scene::parser::<extra::Err<Rich<char>>>();
+        let origin = || Point { x: 0.0, y: 0.0 };
+        assert_eq!(
+            parser
+                .parse(
+                    "scene \"demo\" {
+                        circle point(0, 0) radius 2.5
+                        polygon point(0,0) -> point(1, 0) -> point(0, 1) closed true
+                        polygon point(0,0) -> point(0,0) -> point(0,0)
+                        nothing ;
+                    }"
+                )
+                .into_result(),
+            Ok(Scene {
+                name: "demo".to_string(),
+                shapes: vec![
+                    Shape::Circle(origin(), 2.5),
+                    Shape::Polygon {
+                        points: vec![origin(), Point { x: 1.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }],
+                        closed: Some(true),
+                    },
+                    Shape::Polygon {
+                        points: vec![origin(), origin(), origin()],
+                        closed: None,
+                    },
+                    Shape::Empty,
+                ],
+            }),
+        );
+
+        // Too few points
+        assert!(parser
+            .parse("scene s { polygon point(0, 0) -> point(1, 1) }")
+            .has_errors());
+        // Out of range
+        assert_eq!(
+            u8::parser::<extra::Err<Rich<char>>>()
+                .parse("256")
+                .into_errors()[0]
+                .to_string(),
+            "integer literal is out of range",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "grammar")]
+    fn grammar_rules() {
+        use self::{grammar::grammar, prelude::*};
+
+        #[derive(Clone, Debug, PartialEq)]
+        enum Json {
+            Null,
+            Bool(bool),
+            Num(u64),
+            Str(String),
+            Array(Vec<Json>),
+            Object(Vec<(String, Json)>),
+        }
+
+        grammar! {
+            type Extra = extra::Err<Rich<'a, char>>;
+
+            json: Json = ws value ws;
+            value: Json = "null" => |()| Json::Null
+                | "true" => |()| Json::Bool(true)
+                | "false" => |()| Json::Bool(false)
+                | { text::int(10).from_str().unwrapped() } => Json::Num
+                | string => Json::Str
+                | "[" ws (value ws ("," ws value ws)*)? "]" => |items| Json::Array(list(items))
+                | "{" ws (member ("," ws member)*)? "}" => |members| Json::Object(list(members));
+            member: (String, Json) = string ws ':' ws value ws;
+            string: String = '"' { none_of('"').repeated().collect::<String>() } '"';
+            ws: () = (' ' | '\n')*;
+        }
+
+        fn list<T>(items: Option<(T, Vec<T>)>) -> Vec<T> {
+            items.map_or_else(Vec::new, |(first, rest)| {
+                core::iter::once(first).chain(rest).collect()
+            })
+        }
+
+        assert_eq!(
+            json()
+                .parse(r#" {"a": [1, true, null], "b": {}, "c": []} "#)
+                .into_result(),
+            Ok(Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![Json::Num(1), Json::Bool(true), Json::Null])
+                ),
+                ("b".to_string(), Json::Object(Vec::new())),
+                ("c".to_string(), Json::Array(Vec::new())),
+            ])),
+        );
+        let errs = json().parse("[1, nul]").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(7..8));
+    }
+
+    #[test]
+    #[cfg(feature = "pest")]
+    fn pest_grammar() {
+        use self::{pest::Grammar, prelude::*};
+
+        let grammar = Grammar::new(
+            r##"
+            // Assignments of values to names
+            WHITESPACE = _{ " " | "\t" | NEWLINE }
+            COMMENT = _{ "#" ~ (!NEWLINE ~ ANY)* }
+            file = { SOI ~ assignment* ~ EOI }
+            assignment = { ^"let" ~ name ~ "=" ~ value ~ ";" }
+            name = @{ ('a'..'z' | "_")+ ~ ASCII_DIGIT{0, 2} }
+            value = _{ string | number | name }
+            string = ${ "\"" ~ inner ~ "\"" }
+            inner = @{ (!"\"" ~ ANY)* }
+            number = @{ "-"? ~ ASCII_DIGIT+ }
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            grammar.rules().collect::<Vec<_>>(),
+            [
+                "WHITESPACE",
+                "COMMENT",
+                "file",
+                "assignment",
+                "name",
+                "value",
+                "string",
+                "inner",
+                "number"
+            ],
+        );
+
+        let file = grammar.parser::<extra::Err<Rich<char>>>("file").unwrap();
+        let input = "LET x1 = -5; # five\nlet greeting = \"hi there\";\n";
+        let pairs = file.parse(input).into_result().unwrap();
+        // The rule, text, and number of children of each child of a pair
+        fn tree<'a>(pair: &pest::Pair<'a>, input: &'a str) -> Vec<(&'a str, &'a str, usize)> {
+            pair.inner
+                .iter()
+                .map(|inner| (inner.rule, inner.as_str(input), inner.inner.len()))
+                .collect()
+        }
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].span, SimpleSpan::from(0..input.len()));
+        assert_eq!(
+            tree(&pairs[0], input),
+            [
+                ("assignment", "LET x1 = -5;", 2),
+                ("assignment", "let greeting = \"hi there\";", 2),
+                ("EOI", "", 0),
+            ],
+        );
+        assert_eq!(
+            tree(&pairs[0].inner[1], input),
+            [("name", "greeting", 0), ("string", "\"hi there\"", 1)],
+        );
+        assert_eq!(
+            tree(&pairs[0].inner[1].inner[1], input),
+            [("inner", "hi there", 0)]
+        );
+
+        // Atomic rules don't allow implicit whitespace
+        assert!(file.parse("let x 1 = 5;").has_errors());
+        assert!(file.parse("let x123 = 5;").has_errors());
+        assert!(file.parse("let x = - 5;").has_errors());
+
+        let errs = Grammar::new("a = { b ~ \"c\" }\na = { PUSH }").unwrap_err();
+        assert_eq!(errs.len(), 3);
+        assert!(Grammar::new("a = { \"b\"").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "nom")]
+    fn nom_parsers() {
+        use self::{nom::from_nom, prelude::*};
+        use ::nom::{bytes::complete::tag, error::VerboseError, number::complete::be_u16, IResult};
+
+        fn version(input: &[u8]) -> IResult<&[u8], u16, VerboseError<&[u8]>> {
+            let (input, _) = tag(b"V")(input)?;
+            be_u16(input)
+        }
+
+        // A version, followed by the payload
+        let frame = from_nom::<_, _, _, _, _, extra::Err<Rich<u8>>>(version)
+            .then(any().repeated().collect::<Vec<_>>());
+
+        assert_eq!(
+            frame.parse(b"V\x01\x02abc" as &[_]).into_result(),
+            Ok((0x0102, b"abc".to_vec())),
+        );
+        let errs = frame.parse(b"X\x01\x02" as &[_]).into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..1));
+        // The error refers to where nom failed, not where it began
+        let errs = frame.parse(b"V\x01" as &[_]).into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(1..2));
+    }
+
+    #[test]
+    fn fixed_vec_errors() {
+        use util::FixedVec;
+
+        let mut errs = FixedVec::<Rc<usize>, 3>::new();
+        let counted = Rc::new(0);
+        for _ in 0..5 {
+            errs.push(counted.clone());
+        }
+        assert!(errs.is_full());
+        assert_eq!(errs.len(), 3);
+        // Errors pushed once the vector is full are dropped straight away
+        assert_eq!(Rc::strong_count(&counted), 4);
+
+        errs.truncate(1);
+        assert_eq!(Rc::strong_count(&counted), 2);
+        let cloned = errs.clone();
+        assert_eq!(cloned, errs);
+        assert_eq!(errs.into_iter().chain(cloned).count(), 2);
+        assert_eq!(Rc::strong_count(&counted), 1);
+
+        // Partially consumed iterators drop the rest of their items
+        let errs = (0..3).map(|_| counted.clone()).collect::<FixedVec<_, 3>>();
+        let mut iter = errs.into_iter();
+        assert!(iter.next().is_some());
+        assert_eq!(iter.len(), 2);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counted), 1);
+    }
+
+    #[test]
+    fn binary_numbers() {
+        use self::{binary, prelude::*};
+
+        let header = binary::u8::<&[u8], extra::Err<Rich<u8>>>()
+            .then(binary::i16_le())
+            .then(binary::i32_be())
+            .then(binary::u64_le())
+            .then(binary::f64_be());
+        let mut input = vec![7, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xfd];
+        input.extend(u64::MAX.to_le_bytes());
+        input.extend(0.5f64.to_be_bytes());
+        assert_eq!(
+            header.parse(&input).into_result(),
+            Ok(((((7, -2), -3), u64::MAX), 0.5)),
+        );
+
+        // A truncated number is an error, spanning the bytes that were there
+        let errs = header.parse(&input[..10]).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 7..10);
+        assert_eq!(errs[0].found(), None);
+    }
+
+    #[test]
+    fn bit_fields() {
+        use self::{
+            binary::{aligned, bit_flag, bits, Bits},
+            prelude::*,
+        };
+
+        type Extra = extra::Err<Rich<'static, bool>>;
+
+        // A field that straddles two bytes is spanned by both of them
+        let field = bits::<Bits<&[u8]>, Extra>(6)
+            .ignore_then(
+                bits(4).map_with_span(|value, span: SimpleSpan| (value, span.into_range())),
+            )
+            .then_ignore(aligned())
+            .then(bit_flag())
+            .then_ignore(aligned());
+        assert_eq!(
+            field
+                .parse(Bits::new(&[0b0000_0010, 0b1100_0000, 0x80][..]))
+                .into_result(),
+            Ok(((0b1011, 0..2), true)),
+        );
+
+        // Aligning at the start of a byte skips nothing
+        let flags = bit_flag::<Bits<&[u8]>, Extra>()
+            .repeated()
+            .exactly(8)
+            .then_ignore(aligned())
+            .then(bits(8));
+        assert_eq!(
+            flags.parse(Bits::new(&[0xff, 7][..])).into_result(),
+            Ok(((), 7))
+        );
+        assert!(flags.parse(Bits::new(&[0xff][..])).has_errors());
+    }
+
+    #[test]
+    fn length_prefixed() {
+        use self::{
+            binary::{length_prefixed, take, uleb128},
+            prelude::*,
+        };
+
+        type Extra = extra::Err<Rich<'static, u8>>;
+
+        // Payloads can be nested
+        let field = length_prefixed(uleb128::<u32, &[u8], Extra>(), any().repeated().slice());
+        let message = length_prefixed(
+            binary::u8::<&[u8], Extra>(),
+            field.clone().repeated().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            message.parse(b"\x06\x02ab\x00\x01c").into_result(),
+            Ok(vec![&b"ab"[..], b"", b"c"]),
+        );
+        // The inner payloads can't extend beyond the outer one
+        assert!(message.parse(b"\x03\x02ab\x00").has_errors());
+
+        let errs = field.parse(b"\x05abc").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 1..4);
+        assert_eq!(
+            errs[0].to_string(),
+            "expected 5 tokens, but the input ends after 3"
+        );
+
+        // Lengths too large for a `usize` can never be satisfied
+        let huge = length_prefixed(binary::u128_le::<&[u8], Extra>(), any().repeated().slice());
+        assert!(huge.parse(&[0xff; 20]).has_errors());
+
+        // Any input of slices can be taken from
+        let text = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+            .from_str::<usize>()
+            .unwrapped()
+            .then_with_ctx(take(0).configure(|cfg, len: &usize| cfg.count(*len)));
+        assert_eq!(text.parse("3abc").into_result(), Ok("abc"));
+        assert!(text.parse("3ab").has_errors());
+    }
+
+    #[test]
+    fn map_and_filter_with_ctx() {
+        use self::prelude::*;
+
+        // Items must be indented by exactly the amount given by the context
+        let item = just::<_, &str, extra::Full<Simple<char>, (), usize>>(' ')
+            .repeated()
+            .count()
+            .filter_with_ctx(|indent, expected| indent == expected)
+            .ignore_then(text::ident())
+            .map_with_ctx(|name, span: SimpleSpan, indent| (*indent, name, span.into_range()))
+            .then_ignore(text::newline().or(end()));
+        let items = item.repeated().collect::<Vec<_>>();
+        let items_at =
+            |indent| empty::<&str, extra::Err<Simple<char>>>().ignore_then(items.with_ctx(indent));
+
+        assert_eq!(
+            items_at(2).parse("  a\n  b").into_result(),
+            Ok(vec![(2, "a", 0..3), (2, "b", 4..7)]),
+        );
+        assert!(items_at(2).parse("  a\n   b").has_errors());
+        assert!(items_at(0).parse("  a").has_errors());
+    }
+
+    #[test]
+    fn transactional_state() {
+        use self::prelude::*;
+        use self::state::{Rollback, ScopeState, SymbolTable};
+
+        type Table = SymbolTable<String, usize>;
+
+        let ident = text::ident::<&str, _, extra::Full<Rich<char>, Table, ()>>().map(String::from);
+        let decl = ident
+            .map_with_span(|name, span: SimpleSpan| (name, span.start))
+            .declare();
+        // A declaration list (`[a b c]`), or a tuple of names that must already be declared (`[a, b, c]`)
+        let decls = decl
+            .clone()
+            .padded()
+            .repeated()
+            .delimited_by(just('['), just(']'))
+            .ignored();
+        let tuple = ident
+            .resolve()
+            .padded()
+            .separated_by(just(','))
+            .allow_trailing()
+            .delimited_by(just('['), just(']'))
+            .ignored();
+        let item = decls.transactional().or(tuple).or(decl.ignored());
+        let items = item.padded().repeated();
+
+        // `[x, y]` redeclares `x` before failing to be a declaration list, but the declaration is rolled back
+        let mut table = Table::new();
+        assert!(!items
+            .parse_with_state("x y [x, y]", &mut table)
+            .has_errors());
+        assert_eq!(table.resolve("x"), Some(&0));
+        assert_eq!(table.resolve("y"), Some(&2));
+
+        let mut table = Table::new();
+        assert!(items.parse_with_state("x [x, y]", &mut table).has_errors());
+        assert_eq!(table.resolve("x"), Some(&0));
+        assert_eq!(table.resolve("y"), None);
+
+        // Replaced declarations are restored, and declarations in scopes that have been left are forgotten
+        let mut table = Table::new();
+        table.declare("a".to_string(), 0);
+        let checkpoint = table.checkpoint();
+        table.declare("a".to_string(), 1);
+        table.push_scope();
+        table.declare("b".to_string(), 2);
+        table.pop_scope();
+        table.declare("c".to_string(), 3);
+        table.rollback(checkpoint);
+        assert_eq!(table.resolve("a"), Some(&0));
+        assert_eq!(table.resolve("c"), None);
+
+        // Committed declarations can still be rolled back by an enclosing checkpoint
+        let outer = table.checkpoint();
+        let inner = table.checkpoint();
+        table.declare("d".to_string(), 4);
+        table.commit(inner);
+        assert_eq!(table.resolve("d"), Some(&4));
+        table.rollback(outer);
+        assert_eq!(table.resolve("d"), None);
+
+        let mut log = vec![1, 2];
+        let checkpoint = log.checkpoint();
+        log.extend([3, 4]);
+        log.rollback(checkpoint);
+        assert_eq!(log, [1, 2]);
+    }
+
+    #[test]
+    fn scoped_state() {
+        use self::{prelude::*, state::ScopeState};
+
+        // Records the deepest scope that was entered
+        #[derive(Default)]
+        struct Depth {
+            current: usize,
+            deepest: usize,
+        }
+
+        impl ScopeState for Depth {
+            fn push_scope(&mut self) {
+                self.current += 1;
+                self.deepest = self.deepest.max(self.current);
+            }
+
+            fn pop_scope(&mut self) {
+                self.current -= 1;
+            }
+        }
+
+        // The first branch fails after entering several scopes, and must leave all of them. Scopes are entered before
+        // their parser is attempted, so a scope is entered for the innermost `(` that isn't there.
+        let nested = recursive(|nested| {
+            just::<_, &str, extra::State<Depth>>('(')
+                .ignore_then(nested.or_not())
+                .then_ignore(just(')'))
+                .ignored()
+                .with_scope()
+        });
+        let parser = nested.clone().then_ignore(just('!')).or(nested);
+
+        let mut depth = Depth::default();
+        assert!(!parser.parse_with_state("(())", &mut depth).has_errors());
+        assert_eq!(depth.current, 0);
+        assert_eq!(depth.deepest, 3);
+
+        let mut depth = Depth::default();
+        assert!(parser.parse_with_state("((()", &mut depth).has_errors());
+        assert_eq!(depth.current, 0);
+        assert_eq!(depth.deepest, 4);
+    }
+
+    #[test]
+    fn interner_and_symbol_table() {
+        use self::{
+            prelude::*,
+            state::{Interner, ScopeState, Symbol, SymbolTable},
+        };
+        use core::borrow::BorrowMut;
+
+        // Names are interned, and refer to the index of their declaration
+        #[derive(Default)]
+        struct Frontend {
+            interner: Interner,
+            table: SymbolTable<Symbol, usize>,
+            declared: usize,
+        }
+
+        impl BorrowMut<Interner> for Frontend {
+            fn borrow_mut(&mut self) -> &mut Interner {
+                &mut self.interner
+            }
+        }
+        impl Borrow<Interner> for Frontend {
+            fn borrow(&self) -> &Interner {
+                &self.interner
+            }
+        }
+        impl BorrowMut<SymbolTable<Symbol, usize>> for Frontend {
+            fn borrow_mut(&mut self) -> &mut SymbolTable<Symbol, usize> {
+                &mut self.table
+            }
+        }
+        impl Borrow<SymbolTable<Symbol, usize>> for Frontend {
+            fn borrow(&self) -> &SymbolTable<Symbol, usize> {
+                &self.table
+            }
+        }
+        impl ScopeState for Frontend {
+            fn push_scope(&mut self) {
+                self.table.push_scope();
+            }
+            fn pop_scope(&mut self) {
+                self.table.pop_scope();
+            }
+        }
+
+        type Extra = extra::Full<Rich<'static, char>, Frontend, ()>;
+
+        let name = text::ident::<&str, _, Extra>().intern();
+        let decl = just('+')
+            .ignore_then(name)
+            .map_with_state(|name, _, state: &mut Frontend| {
+                state.declared += 1;
+                (name, state.declared)
+            })
+            .declare();
+        let usage = name.resolve::<usize, _>();
+        let items = recursive(|items| {
+            choice((
+                decl.to(Vec::new()),
+                usage.map(|index| vec![index]),
+                items.delimited_by(just('('), just(')')).with_scope(),
+            ))
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>()
+            .map(|items| items.into_iter().flatten().collect::<Vec<_>>())
+        });
+        let program = items.then_ignore(end());
+
+        // Inner declarations shadow outer ones until the end of their scope
+        let mut state = Frontend::default();
+        assert_eq!(
+            program
+                .parse_with_state("+a a (+a a +b b) a", &mut state)
+                .into_result(),
+            Ok(vec![1, 2, 3, 1]),
+        );
+        assert_eq!(state.table.depth(), 1);
+        assert_eq!(state.interner.len(), 2);
+        let a = state.interner.get("a").unwrap();
+        assert_eq!(state.interner.resolve(a), "a");
+        assert_eq!(state.table.resolve(&a), Some(&1));
+        assert_eq!(state.interner.get("c"), None);
+
+        let mut state = Frontend::default();
+        let errs = usage.parse_with_state("c", &mut state).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "unresolved name");
+        assert_eq!(errs[0].span().into_range(), 0..1);
+    }
+
+    #[test]
+    fn multi_slot_state() {
+        use self::prelude::*;
+        use self::state::{At, Interner, Symbol, SymbolTable};
+
+        // Keywords and names are interned separately, and each declaration is numbered by the counter
+        type State = (Interner, Interner, SymbolTable<Symbol, usize>, usize);
+
+        let keyword = text::ident::<&str, _, extra::Full<Rich<char>, State, ()>>()
+            .filter(|s: &&str| ["fn", "let"].contains(s))
+            .intern::<At<0>>();
+        let name = text::ident().intern::<At<1>>();
+        let decl = keyword
+            .padded()
+            .ignore_then(name)
+            .map_with_state(|name, _, state: &mut State| {
+                state.3 += 1;
+                (name, state.3)
+            })
+            .declare();
+        let usage = name.resolve();
+        let items = recursive(|items| {
+            choice((
+                decl.then_ignore(just(';')).transactional().to(Vec::new()),
+                usage.map(|index| vec![index]),
+                items.delimited_by(just('('), just(')')).with_scope(),
+            ))
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>()
+            .map(|items| items.into_iter().flatten().collect::<Vec<_>>())
+        });
+
+        let mut state = State::default();
+        assert_eq!(
+            items
+                .parse_with_state("let a; fn b; (let a; a) a b", &mut state)
+                .into_result(),
+            Ok(vec![3, 1, 2]),
+        );
+        assert_eq!(state.0.len(), 2);
+        assert_eq!(state.1.len(), 2);
+        assert_eq!(state.2.depth(), 1);
+
+        // The declaration without a semicolon is rolled back, so `x` is not declared
+        let mut state = State::default();
+        assert!(items.parse_with_state("let x x", &mut state).has_errors());
+        assert_eq!(state.2.resolve(&state.1.get("x").unwrap()), None);
+    }
+
+    #[test]
+    #[cfg(feature = "metadata")]
+    fn metadata_collection() {
+        use self::prelude::*;
+
+        #[derive(Debug, PartialEq)]
+        struct Literal(i64, core::ops::Range<usize>);
+
+        let int = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+            .from_str::<i64>()
+            .unwrapped()
+            .record_metadata(|n, span: SimpleSpan| Literal(*n, span.into_range()));
+        let name = text::ident().record_metadata(|name: &&str, _| name.to_string());
+        // The first alternative records the integer before failing to find the `+`
+        let expr = int
+            .then_ignore(just('+'))
+            .then(int)
+            .ignored()
+            .or(int.ignored())
+            .or(name.ignored());
+        let exprs = expr.padded().separated_by(just(','));
+
+        let (result, mut metadata) = exprs.parse_metadata("1+2, 3, x, 4+y");
+        assert!(result.has_errors());
+        assert_eq!(metadata.len(), 5);
+        assert_eq!(metadata.iter::<String>().collect::<Vec<_>>(), vec!["x"],);
+        assert_eq!(
+            metadata.take::<Literal>(),
+            vec![
+                Literal(1, 0..1),
+                Literal(2, 2..3),
+                Literal(3, 5..6),
+                Literal(4, 11..12)
+            ],
+        );
+        assert_eq!(metadata.take::<String>(), vec!["x".to_string()]);
+        assert!(metadata.is_empty());
+
+        // Nothing is recorded when metadata isn't being collected
+        assert_eq!(exprs.parse("1, 2").into_result(), Ok(()));
+    }
+
+    #[test]
+    fn repeated_ctx_counts() {
+        use self::prelude::*;
+
+        // A count byte, followed by that many little-endian `u16` records
+        let records = binary::u8::<&[u8], extra::Err<Rich<u8>>>()
+            .then_with_ctx(binary::u16_le().repeated_ctx().collect::<Vec<_>>());
+        assert_eq!(
+            records.parse(&[2, 1, 0, 0, 1]).into_result(),
+            Ok(vec![1, 256]),
+        );
+        assert_eq!(records.parse(&[0]).into_result(), Ok(vec![]));
+        assert!(records.parse(&[2, 1, 0]).has_errors());
+        assert!(records.parse(&[1, 1, 0, 0, 1]).has_errors());
+
+        // Records can be followed by more input
+        let blocks = records.repeated().collect::<Vec<_>>();
+        assert_eq!(
+            blocks.parse(&[1, 5, 0, 0, 2, 6, 0, 7, 0]).into_result(),
+            Ok(vec![vec![5], vec![], vec![6, 7]]),
+        );
+
+        // Negative counts can't be satisfied
+        let signed = binary::i8::<&[u8], extra::Err<Rich<u8>>>()
+            .then_with_ctx(binary::u8().repeated_ctx().count());
+        assert_eq!(signed.parse(&[1, 9]).into_result(), Ok(1));
+        assert!(signed.parse(&[0xff]).has_errors());
+    }
+
+    #[test]
+    fn ignore_with_ctx_tags() {
+        use self::prelude::*;
+
+        #[derive(Debug, PartialEq)]
+        struct Element(String, Vec<Element>);
+
+        // Every parser has the name of the innermost open tag as its context
+        let element = recursive(|element| {
+            let open = text::ident::<&str, _, extra::Full<Rich<char>, (), &str>>()
+                .delimited_by(just('<'), just('>'));
+            let close = ignore_with_ctx().delimited_by(just("</"), just('>'));
+            open.then_with_ctx(
+                element
+                    .repeated()
+                    .collect::<Vec<_>>()
+                    .then_ignore(close)
+                    .map_with_ctx(|children, _, name: &&str| Element(name.to_string(), children)),
+            )
+        });
+
+        assert_eq!(
+            element.parse("<a><b></b><c><b></b></c></a>").into_result(),
+            Ok(Element(
+                "a".into(),
+                vec![
+                    Element("b".into(), vec![]),
+                    Element("c".into(), vec![Element("b".into(), vec![])]),
+                ],
+            )),
+        );
+
+        let errs = element.parse("<a><b></a></b>").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 8..9);
+        assert!(errs[0]
+            .expected()
+            .any(|e| e == &error::RichPattern::Token('b'.into())));
+    }
+
+    #[test]
+    fn parse_result_accessors() {
+        use self::prelude::*;
+
+        // Items that fail to parse are skipped, so there is output even if there are errors
+        let items = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+            .recover_with(via_parser(text::ident().to("0")))
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
+
+        let result = items.parse("1,x,3").map(|items| items.len());
+        assert_eq!(result.output(), Some(&3));
+        assert_eq!(result.errors().len(), 1);
+        let (output, errs) = result.into_output_errors();
+        assert_eq!(output, Some(3));
+        assert_eq!(errs[0].span().into_range(), 2..3);
+
+        assert_eq!(items.parse("1,2").map(|items| items.len()).unwrap(), 2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "called `ParseResult::unwrap()` on a parse result with errors:\n  1. found ''x'' at 2..3 expected ''0''..''9''\n  2. found ''y'' at 4..5 expected ''0''..''9''"
+    )]
+    fn parse_result_unwrap_lists_errors() {
+        use self::prelude::*;
+
+        let items = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+            .recover_with(via_parser(text::ident().to("0")))
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
+
+        items.parse("1,x,y").unwrap();
+    }
+
+    #[test]
+    fn returned_state() {
+        use self::prelude::*;
+
+        // Each definition is recorded in the state, in order
+        let def = text::ident::<&str, _, extra::Full<Rich<char>, Vec<String>, ()>>()
+            .then_ignore(just('=').padded())
+            .then(text::int(10))
+            .try_map_with_state(|(name, _), _, defs: &mut Vec<String>| {
+                defs.push(name.to_string());
+                Ok(())
+            });
+        let defs = def.padded().repeated();
+
+        let (result, state) = defs.parse_returning_state("a = 1 b = 2");
+        assert!(!result.has_errors());
+        assert_eq!(state, ["a", "b"]);
+
+        // The state is returned even if parsing fails
+        let (result, state) = defs.parse_with_owned_state("c = 3 d", vec!["x".to_string()]);
+        assert!(result.has_errors());
+        assert_eq!(state, ["x", "c"]);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn unicode_class_errors() {
+        use self::{
+            error::RichPattern,
+            prelude::*,
+            text::unicode::{self, GeneralCategory, Property},
+        };
+
+        let upper_or_digit = unicode::class::<_, _, extra::Err<Rich<char>>>([
+            GeneralCategory::UppercaseLetter,
+            GeneralCategory::DecimalNumber,
+        ]);
+        let errs = upper_or_digit.parse("a").into_errors();
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [
+                &RichPattern::Class("uppercase letter"),
+                &RichPattern::Class("decimal number"),
+            ],
+        );
+        assert_eq!(
+            errs[0].to_string(),
+            "found 'a' expected uppercase letter, or decimal number",
+        );
+
+        let alphabetic = unicode::property::<_, extra::Err<Rich<char>>>(Property::Alphabetic);
+        assert_eq!(
+            alphabetic.parse("1").into_errors()[0].to_string(),
+            "found '1' expected alphabetic character",
+        );
+
+        // Extra combining marks are reported against the character that they modify
+        let cafe = unicode::just_normalized::<_, extra::Err<Rich<char>>>("cafe");
+        let errs = cafe.parse("cafe\u{301}").into_errors();
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [&RichPattern::Token('e'.into())],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-segmentation")]
+    fn grapheme_and_word_segmentation() {
+        use self::prelude::*;
+
+        // Letters with combining marks and multi-codepoint emoji are single graphemes
+        let graphemes = text::grapheme::<&str, extra::Err<Simple<char>>>()
+            .repeated()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            graphemes.parse("a\u{308}👨‍👩‍👧🇫🇷!").into_result(),
+            Ok(vec!["a\u{308}", "👨‍👩‍👧", "🇫🇷", "!"]),
+        );
+
+        let word = text::word::<&str, extra::Err<Simple<char>>>();
+        let sentence = word
+            .separated_by(one_of(" ,.").repeated().at_least(1))
+            .allow_trailing()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sentence.parse("Grüße, l'été. Ça va").into_result(),
+            Ok(vec!["Grüße", "l'été", "Ça", "va"]),
+        );
+
+        // Words don't start with punctuation, and never match an empty input
+        assert!(word.parse("'quoted'").has_errors());
+        assert!(word.parse("").has_errors());
+        assert!(text::grapheme::<&str, extra::Err<Simple<char>>>()
+            .parse("")
+            .has_errors());
+    }
+
+    #[test]
+    fn bom_handling() {
+        use self::prelude::*;
+
+        fn section<'a, I: StrInput<'a, char>>(
+        ) -> impl Parser<'a, I, (&'a str, I::Span), extra::Err<Rich<'a, char, I::Span>>> {
+            just('[')
+                .ignore_then(text::ident())
+                .then_ignore(just(']'))
+                .map_with_span(|name, span| (name, span))
+        }
+
+        // A BOM is only accepted where the grammar allows it
+        assert!(section().parse("\u{feff}[a]").has_errors());
+        assert_eq!(
+            text::bom()
+                .ignore_then(section())
+                .parse("\u{feff}[a]")
+                .into_result(),
+            Ok(("a", SimpleSpan::from(3..6))),
+        );
+
+        // Skipped BOMs keep spans (including those of errors) relative to the original input
+        assert_eq!(
+            section().parse("\u{feff}[a]".skip_bom()).into_result(),
+            Ok(("a", SimpleSpan::from(3..6))),
+        );
+        let errs = section().parse("\u{feff}[1]".skip_bom()).into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::from(4..5));
+
+        // UTF-16 BOMs are skipped in byte inputs, but only once
+        let ok = just::<_, _, extra::Err<Simple<u8>>>(b"ok").map_with_span(|_, span| span);
+        assert_eq!(
+            ok.parse(b"\xFE\xFFok".as_slice().skip_bom()).into_result(),
+            Ok(SimpleSpan::from(2..4)),
+        );
+        assert!(ok
+            .parse(b"\xFF\xFE\xFF\xFEok".as_slice().skip_bom())
+            .has_errors());
+    }
+
+    #[test]
+    fn array_outputs() {
+        use self::prelude::*;
+
+        // A UUID is made of 16 bytes
+        let uuid = binary::u8::<&[u8], extra::Err<Rich<u8>>>().array::<16>();
+        assert_eq!(uuid.parse(&[7; 16]).into_result(), Ok([7; 16]));
+        assert!(uuid.parse(&[7; 15]).has_errors());
+        assert!(uuid.parse(&[7; 17]).has_errors());
+
+        let none = just::<_, &str, extra::Default>('a').array::<0>();
+        assert_eq!(none.then(just('a')).parse("a").into_result(), Ok(([], 'a')));
+
+        // Outputs that were parsed before a failure are dropped
+        let word = text::ident::<&str, char, extra::Err<Simple<char>>>()
+            .map(String::from)
+            .padded();
+        let matrix = word.array::<2>().array::<2>();
+        assert_eq!(
+            matrix.parse("a b c d").into_result(),
+            Ok([
+                ["a".to_string(), "b".to_string()],
+                ["c".to_string(), "d".to_string()]
+            ]),
+        );
+        assert!(matrix.parse("a b c").has_errors());
+    }
+
+    #[test]
+    fn byte_strings() {
+        use self::{
+            binary::{cstr, fixed_str},
+            prelude::*,
+        };
+        use alloc::borrow::Cow;
+
+        type Extra = extra::Err<Rich<'static, u8>>;
+
+        // Invalid UTF-8 is replaced, or rejected with an error that spans the string
+        let lossy = cstr::<Cow<str>, &[u8], Extra>();
+        assert_eq!(
+            lossy.parse(b"caf\xe9\0").into_result(),
+            Ok(Cow::Owned("caf\u{fffd}".to_string())),
+        );
+        let errs = cstr::<&str, &[u8], Extra>()
+            .parse(b"caf\xe9\0")
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 0..4);
+
+        // A missing terminator is expected at the end of the input
+        let errs = lossy.parse(b"cafe").into_errors();
+        assert_eq!(errs[0].span().into_range(), 4..4);
+        assert_eq!(errs[0].found(), None);
+
+        // A string may be exactly as long as the limit
+        let short = cstr::<&str, &[u8], Extra>().max_len(2);
+        assert_eq!(short.parse(b"ab\0").into_result(), Ok("ab"));
+        assert!(short.parse(b"abc\0").has_errors());
+
+        let errs = fixed_str::<&str, &[u8], Extra>(4)
+            .parse(b"a\xff\0\0")
+            .into_errors();
+        assert_eq!(errs[0].span().into_range(), 0..2);
+        assert_eq!(
+            fixed_str::<&[u8], &[u8], Extra>(4)
+                .then(fixed_str(2))
+                .parse(b"a\0bcde")
+                .into_result(),
+            Ok((&b"a"[..], &b"de"[..])),
+        );
+    }
+
+    #[test]
+    fn checksums() {
+        use self::{
+            binary::{adler32, checksummed, crc32, length_prefixed},
+            prelude::*,
+        };
+
+        type Extra = extra::Err<Rich<'static, u8>>;
+
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414fa339
+        );
+        assert_eq!(adler32(b""), 1);
+
+        // A length-prefixed chunk, followed by the big-endian Adler-32 of its length and contents
+        let chunk = checksummed(
+            length_prefixed(binary::u8::<&[u8], Extra>(), any().repeated().slice()),
+            binary::u32_be(),
+            adler32,
+        );
+        assert_eq!(
+            chunk.parse(b"\x03abc\x02\x5a\x01\x2a").into_result(),
+            Ok(&b"abc"[..])
+        );
+
+        let errs = chunk.parse(b"\x03abc\x00\x00\x00\x00").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 4..8);
+        assert_eq!(errs[0].to_string(), "checksum mismatch");
+    }
+
+    #[test]
+    fn alignment_and_padding() {
+        use self::{
+            binary::{align_to, padding},
+            prelude::*,
+        };
+
+        type Extra = extra::Err<Rich<'static, u8>>;
+
+        // Aligning at a boundary skips nothing
+        let aligned = binary::u32_le::<&[u8], Extra>().then_ignore(align_to(4));
+        assert_eq!(aligned.parse(b"\x01\0\0\0").into_result(), Ok(1));
+
+        // Errors point at the first byte that isn't padding
+        let byte = binary::u8::<&[u8], Extra>().then_ignore(align_to(8).fill(0xff));
+        assert_eq!(
+            byte.parse(b"\x07\xff\xff\xff\xff\xff\xff\xff")
+                .into_result(),
+            Ok(7)
+        );
+        let errs = byte
+            .parse(b"\x07\xff\xff\x00\xff\xff\xff\xff")
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 3..4);
+        assert_eq!(errs[0].found(), Some(&0));
+
+        // The amount of padding can depend on an earlier field
+        let record = binary::u8::<&[u8], Extra>()
+            .then_with_ctx(padding(0).configure(|cfg, len: &u8| cfg.count(*len as usize)))
+            .ignore_then(binary::u8());
+        assert_eq!(record.parse(b"\x02..\x2a").into_result(), Ok(42));
+        assert_eq!(record.parse(b"\x00\x2a").into_result(), Ok(42));
+        assert!(record.parse(b"\x03..\x2a").has_errors());
+    }
+
+    #[test]
+    fn magic_bytes() {
+        use self::{
+            binary::{choice_by_magic, magic},
+            prelude::*,
+        };
+
+        type Extra = extra::Err<Rich<'static, u8>>;
+
+        // Errors span the bytes up to the mismatch
+        let zip = magic::<&[u8], Extra>(b"PK\x03\x04").format("ZIP");
+        let errs = zip.parse(b"PKxx").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 0..3);
+        assert_eq!(errs[0].to_string(), "expected ZIP magic bytes");
+
+        // Once a format is detected, only its parser is attempted
+        let gzip = magic::<&[u8], Extra>(b"\x1f\x8b");
+        let archive = choice_by_magic((
+            (zip, zip.ignore_then(binary::u16_le()).map(|v| ("zip", v))),
+            (gzip, gzip.ignore_then(binary::u8()).to(("gzip", 0))),
+            (magic(b"PK"), magic(b"PK").to(("other", 0))),
+        ));
+        assert_eq!(
+            archive.parse(b"PK\x03\x04\x14\x00").into_result(),
+            Ok(("zip", 20))
+        );
+        assert_eq!(
+            archive.parse(b"\x1f\x8b\x08").into_result(),
+            Ok(("gzip", 0))
+        );
+        assert_eq!(archive.parse(b"PK").into_result(), Ok(("other", 0)));
+        let errs = archive.parse(b"PK\x03\x04\x14").into_errors();
+        assert_eq!(errs[0].span().into_range(), 4..5);
+
+        let errs = archive.parse(b"\x00\x01\x02\x03\x04").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 0..4);
+        assert_eq!(
+            errs[0].to_string(),
+            "unrecognised magic bytes, expected one of: ZIP, 1f 8b, 50 4b"
+        );
+    }
+
+    #[test]
+    fn varints() {
+        use self::{
+            binary::{sleb128, uleb128, vlq},
+            prelude::*,
+        };
+
+        type Extra = extra::Err<Rich<'static, u8>>;
+
+        let unsigned = uleb128::<u64, &[u8], Extra>();
+        assert_eq!(unsigned.parse(&[0x00]).into_result(), Ok(0));
+        assert_eq!(
+            unsigned
+                .parse(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01])
+                .into_result(),
+            Ok(u64::MAX),
+        );
+        // One bit too many for a `u64`
+        assert!(unsigned
+            .parse(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x03])
+            .has_errors());
+
+        let signed = sleb128::<i64, &[u8], Extra>();
+        assert_eq!(signed.parse(&[0x40]).into_result(), Ok(-64));
+        assert_eq!(signed.parse(&[0xc0, 0x00]).into_result(), Ok(64));
+        assert_eq!(
+            signed
+                .parse(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f])
+                .into_result(),
+            Ok(i64::MIN),
+        );
+        assert_eq!(signed.parse(&[0xff, 0x00]).into_result(), Ok(127));
+
+        // Errors span the whole integer
+        let errs = uleb128::<u8, &[u8], Extra>()
+            .max_bytes(2)
+            .parse(&[0x80, 0x80, 0x01])
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span().into_range(), 0..2);
+        let errs = uleb128::<u8, &[u8], Extra>()
+            .parse(&[0x80, 0x02])
+            .into_errors();
+        assert_eq!(errs[0].span().into_range(), 0..2);
+
+        let quantity = vlq::<u32, &[u8], Extra>().repeated().collect::<Vec<_>>();
+        assert_eq!(
+            quantity
+                .parse(&[0x00, 0x40, 0xc0, 0x00, 0xff, 0xff, 0xff, 0x7f])
+                .into_result(),
+            Ok(vec![0, 0x40, 0x2000, 0x0fff_ffff]),
+        );
+    }
+
+    #[test]
+    fn impl_from_str() {
+        use self::prelude::*;
+
+        #[derive(Debug, PartialEq)]
+        struct Point(i64, i64);
+
+        fn point<'a>() -> impl Parser<'a, &'a str, Point, extra::Err<Simple<'a, char>>> {
+            let coord = just('-')
+                .or_not()
+                .then(text::int(10))
+                .slice()
+                .from_str()
+                .unwrapped();
+            coord
+                .then_ignore(just(',').padded())
+                .then(coord)
+                .delimited_by(just('('), just(')'))
+                .map(|(x, y)| Point(x, y))
+        }
+
+        impl_from_str!(Point, point(), |errors| -> usize { errors.len() });
+
+        assert_eq!("(3, -4)".parse(), Ok(Point(3, -4)));
+        assert_eq!("(3, -4) ".parse::<Point>(), Err(1));
+        assert_eq!("(3 -4)".parse::<Point>(), Err(1));
+    }
+
+    #[test]
+    fn lex_iter() {
+        use self::prelude::*;
+
+        // Numbers longer than two digits are reported, but still lexed
+        let number = text::int::<_, _, extra::Err<Rich<char>>>(10).validate(
+            |digits: &str, span, emitter| {
+                if digits.len() > 2 {
+                    emitter.emit(Rich::custom(span, "too long"));
+                }
+                digits
+            },
+        );
+
+        let tokens = number
+            .lex_iter("1 23 x 456  ", text::whitespace().at_least(1))
+            .map(|token| match token {
+                Ok((digits, span)) => Ok((digits, span.into_range())),
+                Err(err) => Err(err.span().into_range()),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            [
+                Ok(("1", 0..1)),
+                Ok(("23", 2..4)),
+                // The unexpected character is skipped
+                Err(5..6),
+                // Errors that were emitted come before the match
+                Err(7..10),
+                Ok(("456", 7..10)),
+            ],
+        );
+
+        // Without trivia, the parser must match everything
+        assert_eq!(
+            number
+                .lex_iter("1 2", empty())
+                .filter(Result::is_err)
+                .count(),
+            1
+        );
+        assert_eq!(number.lex_iter("", empty()).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn stream_input() {
+        use self::{futures::StreamInput, push::Step};
+        use ::futures::{executor::block_on, Stream};
+        use core::{
+            pin::Pin,
+            task::{Context, Poll},
+        };
+
+        // A stream whose tokens are only available one at a time
+        struct Trickle<I> {
+            tokens: I,
+            ready: bool,
+        }
+
+        impl<I: Iterator + Unpin> Stream for Trickle<I> {
+            type Item = I::Item;
+
+            fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+                self.ready = !self.ready;
+                if self.ready {
+                    Poll::Ready(self.tokens.next())
+                } else {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+
+        fn number<'a>() -> impl Parser<'a, &'a [u8], u32, extra::Err<Simple<'a, u8>>> {
+            text::int(10)
+                .map(|digits: &[u8]| digits.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+                .then_ignore(just(b','))
+        }
+
+        let mut input = StreamInput::new(Trickle {
+            tokens: b"12,345,6x,78,".iter().copied(),
+            ready: false,
+        });
+        let (numbers, errors) = block_on(async {
+            let (mut numbers, mut errors) = (Vec::new(), 0);
+            loop {
+                let step = input.next(&number());
+                let failed = match step {
+                    Step::Pending => {
+                        input.fill().await;
+                        false
+                    }
+                    Step::Done(result) => match result.into_output() {
+                        Some(n) => {
+                            numbers.push(n);
+                            false
+                        }
+                        None => true,
+                    },
+                    Step::Finished => break (numbers, errors),
+                };
+                if failed {
+                    errors += 1;
+                    input.clear();
+                }
+            }
+        });
+        // Clearing the input after an error discards what had arrived so far, so the stray `,` is an error too
+        assert_eq!(numbers, [12, 345, 78]);
+        assert_eq!(errors, 2);
+        assert!(input.has_ended());
+    }
+
+    #[test]
+    #[cfg(feature = "push")]
+    fn push_parser_waits_for_input() {
+        use self::{
+            prelude::*,
+            push::{PushParser, Step},
+        };
+
+        // Lines of space-separated numbers, where the last line may not end with a newline
+        fn line<'a>() -> impl Parser<'a, &'a [u8], Vec<&'a [u8]>, extra::Err<Simple<'a, u8>>> {
+            text::int(10)
+                .separated_by(just(b' '))
+                .collect()
+                .then_ignore(text::newline().or(end()))
+        }
+
+        let mut push = PushParser::new();
+        let mut lines = Vec::new();
+        for chunk in [&b"1 2"[..], b"3 4\n5", b"\n6 x\n"] {
+            push.push(chunk);
+            loop {
+                match push.next(&line()) {
+                    Step::Pending => break,
+                    Step::Done(result) => match result.into_output() {
+                        Some(numbers) => lines.push(numbers.len()),
+                        None => break,
+                    },
+                    Step::Finished => unreachable!(),
+                }
+            }
+        }
+        // The `2` and `3` were pushed separately, but are parsed as one number
+        assert_eq!(lines, [3, 1]);
+
+        // The malformed line is kept until it is skipped
+        assert_eq!(push.buffered(), b"6 x\n");
+        assert!(matches!(push.next(&line()), Step::Done(result) if result.has_errors()));
+        push.clear();
+
+        // The last line can only be parsed once the parser knows that no more digits are coming
+        push.push(b"7 8");
+        assert!(matches!(push.next(&line()), Step::Pending));
+        push.finish();
+        assert!(
+            matches!(push.next(&line()), Step::Done(result) if result.output().map(Vec::len) == Some(2))
+        );
+        assert!(matches!(push.next(&line()), Step::Finished));
+    }
+
+    #[test]
+    #[cfg(feature = "push")]
+    fn push_parser_scans_input() {
+        use self::{
+            prelude::*,
+            push::{PushParser, Step},
+            text::scan::{scan_until, ByteSet},
+        };
+
+        // Fields ended by a `;`, where the first may follow a BOM
+        fn field<'a>() -> impl Parser<'a, &'a [u8], &'a [u8]> {
+            text::bom()
+                .or_not()
+                .ignore_then(scan_until(ByteSet::from_bytes(b";")))
+                .then_ignore(just(b';'))
+        }
+
+        let mut push = PushParser::new();
+        // Scanning stops at the `;`, so the field is finished without looking at the rest of the input
+        push.push(b"\xEF\xBB\xBFab;c");
+        assert!(
+            matches!(push.next(&field()), Step::Done(result) if result.output() == Some(&&b"ab"[..]))
+        );
+        assert!(matches!(push.next(&field()), Step::Pending));
+        push.push(b"d;");
+        assert!(
+            matches!(push.next(&field()), Step::Done(result) if result.output() == Some(&&b"cd"[..]))
+        );
+
+        // Part of a BOM might be the start of a whole one
+        push.push(b"\xEF");
+        assert!(matches!(push.next(&field()), Step::Pending));
+        push.push(b"\xBB\xBFe;");
+        assert!(
+            matches!(push.next(&field()), Step::Done(result) if result.output() == Some(&&b"e"[..]))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "push")]
+    fn push_parser_skips_input() {
+        use self::{
+            prelude::*,
+            push::{PushParser, Step},
+        };
+
+        // Words ended by a `;`
+        fn word<'a>() -> impl Parser<'a, &'a [u8], &'a [u8], extra::Err<Simple<'a, u8>>> {
+            text::ident().then_ignore(just(b';'))
+        }
+
+        let mut push = PushParser::new();
+        push.push(b"ab;1;cd;");
+        assert!(matches!(push.next(&word()), Step::Done(result) if !result.has_errors()));
+        // The malformed item can be skipped, leaving the rest of the input to be parsed
+        assert!(matches!(push.next(&word()), Step::Done(result) if result.has_errors()));
+        push.skip(2);
+        assert!(
+            matches!(push.next(&word()), Step::Done(result) if result.output() == Some(&&b"cd"[..]))
+        );
+
+        // Items that consume no input are rejected, rather than being parsed forever
+        fn digits<'a>() -> impl Parser<'a, &'a [u8], &'a [u8], extra::Err<Simple<'a, u8>>> {
+            text::digits(10).or_not().slice()
+        }
+        push.push(b"x");
+        match push.next(&digits()) {
+            Step::Done(result) => {
+                let errs = result.into_errors();
+                assert_eq!(errs.len(), 1);
+                assert_eq!(errs[0].span(), &SimpleSpan::from(0..1));
+            }
+            _ => panic!("expected an item"),
+        }
+        assert_eq!(push.buffered(), b"x");
+        push.skip(1);
+        // With no input, there is nothing to consume yet
+        assert!(matches!(push.next(&digits()), Step::Pending));
+        push.finish();
+        assert!(matches!(push.next(&digits()), Step::Finished));
+    }
+
+    #[test]
+    #[cfg(feature = "step-limit")]
+    fn step_limit_stops_backtracking() {
+        use self::{cancel::StepLimitExceeded, prelude::*};
+
+        // Each alternative parses the same nested term before failing, so this takes exponential time
+        let long = "a".repeat(40);
+        let term = recursive(|term| {
+            let nested = just::<_, _, extra::Err<Rich<char>>>('a').then(term);
+            nested
+                .clone()
+                .then_ignore(just('b'))
+                .or(nested.then_ignore(just('c')))
+                .ignored()
+                .or(just('a').ignored())
+        });
+
+        assert!(matches!(
+            term.parse_with_step_limit("aaacb", 1000)
+                .map(|res| res.into_result()),
+            Ok(Ok(()))
+        ));
+        assert!(matches!(
+            term.parse_with_step_limit("aaab", 1000)
+                .map(|res| res.has_errors()),
+            Ok(true)
+        ));
+        assert!(matches!(
+            term.parse_with_step_limit(long.as_str(), 1_000_000),
+            Err(StepLimitExceeded)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "cst")]
+    fn cst_lossless() {
+        use self::{
+            cst::{GreenNode, GreenToken},
+            prelude::*,
+        };
+
+        const ROOT: u16 = 0;
+        const TRIVIA: u16 = 1;
+        const LIST: u16 = 2;
+        const NUM: u16 = 3;
+        const IDENT: u16 = 4;
+        const PUNCT: u16 = 5;
+
+        let list = recursive(|list| {
+            let num = text::int::<_, _, extra::Err<Rich<char>>>(10)
+                .syntax_token(NUM)
+                .ignored();
+            // Calls and identifiers both begin with an identifier, so the call's token is rewound when it fails
+            let call = text::ident()
+                .syntax_token(IDENT)
+                .then(just("()").syntax_token(PUNCT))
+                .ignored();
+            let ident = text::ident().syntax_token(IDENT).ignored();
+            num.or(call)
+                .or(ident)
+                .or(list)
+                .padded()
+                .separated_by(just(',').syntax_token(PUNCT))
+                .collect::<Vec<_>>()
+                .delimited_by(just('[').syntax_token(PUNCT), just(']').syntax_token(PUNCT))
+                .ignored()
+                .syntax_node(LIST)
+        });
+
+        let token = |kind, text| GreenToken::new(kind, text).into();
+        let (result, tree) = list
+            .clone()
+            .padded()
+            .parse_cst("[1, f(), [x] ]\n", ROOT, TRIVIA);
+        assert!(!result.has_errors());
+        assert_eq!(
+            tree,
+            GreenNode::new(
+                ROOT,
+                [
+                    GreenNode::new(
+                        LIST,
+                        [
+                            token(PUNCT, "["),
+                            token(NUM, "1"),
+                            token(PUNCT, ","),
+                            token(TRIVIA, " "),
+                            token(IDENT, "f"),
+                            token(PUNCT, "()"),
+                            token(PUNCT, ","),
+                            token(TRIVIA, " "),
+                            GreenNode::new(
+                                LIST,
+                                [token(PUNCT, "["), token(IDENT, "x"), token(PUNCT, "]")]
+                            )
+                            .into(),
+                            token(TRIVIA, " "),
+                            token(PUNCT, "]"),
+                        ]
+                    )
+                    .into(),
+                    token(TRIVIA, "\n"),
+                ]
+            )
+        );
+
+        // The tree still contains the whole input when parsing fails
+        let input = "[1, [2, x()]";
+        let (result, tree) = list.parse_cst(input, ROOT, TRIVIA);
+        assert!(result.has_errors());
+        assert_eq!(tree.to_string(), input);
+        assert_eq!(tree.text_len(), input.len());
+    }
+
+    #[test]
+    #[cfg(feature = "trace")]
+    fn trace_events() {
+        use self::{prelude::*, trace::Event};
+
+        // Events are still emitted by parsers that run with a different context
+        let digit = any::<_, extra::Full<Rich<char>, (), char>>()
+            .filter(|c: &char| c.is_ascii_digit())
+            .labelled("digit")
+            .traced();
+        let parser = any::<_, extra::Err<Rich<char>>>()
+            .then_with_ctx(digit.repeated().at_least(1).slice())
+            .then_ignore(just(';'));
+
+        let mut events = Vec::new();
+        let result =
+            parser.parse_traced("x12;", &mut |event: Event| events.push(event.to_string()));
+        assert!(!result.has_errors());
+        assert_eq!(
+            events,
+            [
+                "enter digit at 1",
+                "match digit at 1..2",
+                "enter digit at 2",
+                "match digit at 2..3",
+                "enter digit at 3",
+                "fail digit at 4, backtracking 1",
+            ]
+        );
+
+        // Parsing without a subscriber emits nothing
+        assert!(!parser.parse("x1;").has_errors());
     }
 
-    fn boxed<'c>(self) -> Boxed<'a, 'c, I, O, E>
-    where
-        Self: MaybeSync + Sized + 'a + 'c,
-    {
-        // Never double-box parsers
-        self
+    #[test]
+    #[cfg(all(feature = "trace", feature = "profile"))]
+    fn named_parsers_are_traced_and_profiled() {
+        use self::{prelude::*, trace::Event};
+
+        let digit = any::<&str, extra::Err<Rich<char>>>()
+            .filter(|c: &char| c.is_ascii_digit())
+            .named("digit");
+        let number = digit.repeated().at_least(1).slice().named("number");
+
+        let mut events = Vec::new();
+        let result = number.parse_traced("12", &mut |event: Event| events.push(event.to_string()));
+        assert_eq!(result.into_result(), Ok("12"));
+        assert_eq!(
+            events,
+            [
+                "enter number at 0",
+                "enter digit at 0",
+                "match digit at 0..1",
+                "enter digit at 1",
+                "match digit at 1..2",
+                "enter digit at 2",
+                "fail digit at 2, backtracking 0",
+                "match number at 0..2",
+            ]
+        );
+
+        let (result, profile) = number.parse_profiled("123");
+        assert!(!result.has_errors());
+        assert_eq!(profile.get("number").unwrap().invocations, 1);
+        assert_eq!(profile.get("digit").unwrap().invocations, 4);
+        assert_eq!(profile.get("digit").unwrap().failures, 1);
+
+        // Names don't affect errors
+        let unnamed = any::<&str, extra::Err<Rich<char>>>()
+            .filter(|c: &char| c.is_ascii_digit())
+            .repeated()
+            .at_least(1)
+            .slice();
+        assert_eq!(
+            number.parse("x").into_errors(),
+            unnamed.parse("x").into_errors()
+        );
     }
 
-    go_extra!(O);
-}
+    #[test]
+    fn test_assertions() {
+        use self::prelude::*;
 
-/// Create a parser that selects one or more input patterns and map them to an output value.
-///
-/// This is most useful when turning the tokens of a previous compilation pass (such as lexing) into data that can be
-/// used for parsing, although it can also generally be used to select inputs and map them to outputs. Any unmapped
-/// input patterns will become syntax errors, just as with [`Parser::filter`].
-///
-/// Internally, [`select!`] is very similar to [`Parser::try_map`] and thinking of it as such might make it less
-/// confusing.
-///
-/// `select!` requires that tokens implement [`Clone`].
-///
-/// If you're trying to access tokens referentially (for the sake of nested parsing, or simply because you want to
-/// avoid cloning the token), see [`select_ref!`].
-///
-/// # Examples
-///
-/// `select!` is syntactically similar to a `match` expression and has support for
-/// [pattern guards](https://doc.rust-lang.org/reference/expressions/match-expr.html#match-guards):
-///
-/// ```
-/// # use chumsky::{prelude::*, error::Simple};
-/// #[derive(Clone)]
-/// enum Token<'a> { Ident(&'a str) }
-///
-/// enum Expr<'a> { Local(&'a str), Null, True, False }
-///
-/// # let _: chumsky::primitive::Select<_, &[Token], Expr, extra::Default> =
-/// select! {
-///     Token::Ident(s) if s == "true" => Expr::True,
-///     Token::Ident(s) if s == "false" => Expr::False,
-///     Token::Ident(s) if s == "null" => Expr::Null,
-///     Token::Ident(s) => Expr::Local(s),
-/// }
-/// # ;
-/// ```
-///
-/// If you require access to the token's span, you may add an argument after a pattern to gain access to it:
-///
-/// ```
-/// # use chumsky::{prelude::*, error::Simple};
-/// #[derive(Clone)]
-/// enum Token<'a> { Num(f64), Str(&'a str) }
-///
-/// enum Expr<'a> { Num(f64), Str(&'a str) }
-///
-/// type Span = SimpleSpan<usize>;
-///
-/// impl<'a> Expr<'a> {
-///     fn spanned(self, span: Span) -> (Self, Span) { (self, span) }
-/// }
-///
-/// # let _: chumsky::primitive::Select<_, &[Token], (Expr, Span), extra::Default> =
-/// select! {
-///     Token::Num(x) = span => Expr::Num(x).spanned(span),
-///     Token::Str(s) = span => Expr::Str(s).spanned(span),
-/// }
-/// # ;
-/// ```
-///
-/// ```
-/// # use chumsky::{prelude::*, error::Simple};
-/// // The type of our parser's input (tokens like this might be emitted by your compiler's lexer)
-/// #[derive(Clone, Debug, PartialEq)]
-/// enum Token {
-///     Num(u64),
-///     Bool(bool),
-///     LParen,
-///     RParen,
-/// }
-///
-/// // The type of our parser's output, a syntax tree
-/// #[derive(Debug, PartialEq)]
-/// enum Ast {
-///     Num(u64),
-///     Bool(bool),
-///     List(Vec<Ast>),
-/// }
-///
-/// // Our parser converts a stream of input tokens into an AST
-/// // `select!` is used to deconstruct some of the tokens and turn them into AST nodes
-/// let ast = recursive::<_, _, extra::Err<Simple<Token>>, _, _>(|ast| {
-///     let literal = select! {
-///         Token::Num(x) => Ast::Num(x),
-///         Token::Bool(x) => Ast::Bool(x),
-///     };
-///
-///     literal.or(ast
-///         .repeated()
-///         .collect()
-///         .delimited_by(just(Token::LParen), just(Token::RParen))
-///         .map(Ast::List))
-/// });
-///
-/// use Token::*;
-/// assert_eq!(
-///     ast.parse(&[LParen, Num(5), LParen, Bool(false), Num(42), RParen, RParen]).into_result(),
-///     Ok(Ast::List(vec![
-///         Ast::Num(5),
-///         Ast::List(vec![
-///             Ast::Bool(false),
-///             Ast::Num(42),
-///         ]),
-///     ])),
-/// );
-/// ```
-#[macro_export]
-macro_rules! select {
-    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
-        $crate::primitive::select(
-            move |x, span| match x {
-                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
-                _ => ::core::option::Option::None,
-            }
-        )
-    });
-}
+        let digits = text::radix_digits::<_, &str, extra::Err<Rich<char>>>(10)
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
 
-/// A version of [`select!`] that selects on token by reference instead of by value.
-///
-/// Useful if you want to extract elements from a token in a zero-copy manner.
-///
-/// `select_ref` requires that the parser input implements [`BorrowInput`].
-#[macro_export]
-macro_rules! select_ref {
-    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
-        $crate::primitive::select_ref(
-            move |x, span| match x {
-                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
-                _ => ::core::option::Option::None,
-            }
-        )
-    });
-}
+        assert_parses!(digits, "12,3", vec!["12", "3"]);
+        assert_fails_with!(digits, "1,x", 2..3, "found 'x'");
+        assert_errors!(digits, "1;", ["found ';' expected ',', or end of input"]);
+        assert_errors!(digits, "1,2", []);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    #[should_panic(
+        expected = "unexpected errors (- expected, + actual):\n- found 'y' expected end of input\n+ found ';' expected end of input\n"
+    )]
+    fn test_assertions_diff_errors() {
+        use self::prelude::*;
+
+        let digits = text::radix_digits::<_, &str, extra::Err<Rich<char>>>(10);
+        assert_errors!(digits, "1;", ["found 'y' expected end of input"]);
+    }
 
     #[test]
-    fn zero_copy() {
-        use self::input::WithContext;
+    #[should_panic(
+        expected = "expected an error at 0..1 containing \"'x'\", but the parser produced these errors:\n  1. 0..1: found 'y' expected '0'..'9'"
+    )]
+    fn test_assertions_list_errors() {
         use self::prelude::*;
 
-        #[derive(PartialEq, Debug)]
-        enum Token<'a> {
-            Ident(&'a str),
-            String(&'a str),
-        }
+        let digits = text::radix_digits::<_, &str, extra::Err<Rich<char>>>(10);
+        assert_fails_with!(digits, "y", 0..1, "'x'");
+    }
 
-        type FileId = u32;
+    #[test]
+    #[cfg(feature = "label")]
+    fn report_excerpts() {
+        use self::prelude::*;
 
-        type Span = (FileId, SimpleSpan<usize>);
+        let call = text::ident::<&str, _, extra::Err<Rich<char>>>()
+            .then(
+                text::int(10)
+                    .separated_by(just(',').padded())
+                    .collect::<Vec<_>>()
+                    .delimited_by(just('('), just(')')),
+            )
+            .labelled("call")
+            .as_context();
+        let calls = call
+            .ignored()
+            .recover_with(via_parser(none_of('\n').repeated()))
+            .separated_by(text::newline())
+            .collect::<Vec<_>>();
+
+        // Spans are measured in bytes, but columns and underlines in characters
+        let src = "f(1, 2)\ng(1,\t\u{e9})\nh(4";
+        let report = calls.parse(src).report(src).to_string();
+        assert_eq!(
+            report,
+            [
+                "error: found '\u{e9}' expected '0'..'9'",
+                " --> 2:6",
+                "  |",
+                "2 | g(1,\t\u{e9})",
+                "  |     \t^",
+                "  = while parsing call at 2:1",
+                "",
+                "error: found end of input expected ',', or ')'",
+                " --> 3:4",
+                "  |",
+                "3 | h(4",
+                "  |    ^",
+                "  = while parsing call at 3:1",
+            ]
+            .join("\n")
+        );
+    }
 
-        fn parser<'a>() -> impl Parser<'a, WithContext<FileId, &'a str>, [(Span, Token<'a>); 6]> {
-            let ident = any()
-                .filter(|c: &char| c.is_alphanumeric())
-                .repeated()
-                .at_least(1)
-                .map_slice(Token::Ident);
+    #[test]
+    fn generate_recursive_grammar() {
+        use self::{generate::Generator, prelude::*};
+
+        // The inputs must outlive the parser, which borrows from them
+        let (inputs, again);
+        let value = recursive(|value| {
+            let member = text::string::<&str, _, extra::Err<Rich<char>>>()
+                .then_ignore(just(':').padded())
+                .then(value.clone());
+            choice((
+                text::float().signed().value::<f64>().to(()),
+                text::int(10).value::<u8>().to(()),
+                text::ident().to(()),
+                text::string().to(()),
+                value
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect::<Vec<_>>()
+                    .delimited_by(just('['), just(']'))
+                    .to(()),
+                member
+                    .separated_by(just(',').padded())
+                    .collect::<Vec<_>>()
+                    .delimited_by(just('{'), just('}'))
+                    .to(()),
+            ))
+        });
 
-            let string = just('"')
-                .then(any().filter(|c: &char| *c != '"').repeated())
-                .then(just('"'))
-                .map_slice(Token::String);
+        let mut gen = Generator::ascii(7);
+        inputs = (0..200)
+            .map(|_| value.generate(&mut gen).unwrap())
+            .map(|input| input.into_iter().collect::<String>())
+            .collect::<Vec<_>>();
+        for input in &inputs {
+            assert!(!value.parse(input).has_errors(), "rejected {:?}", input);
+        }
+        assert!(inputs.iter().any(|input| input.starts_with('[')));
+        assert!(inputs.iter().any(|input| input.starts_with('{')));
+
+        // Generation is deterministic for a given seed
+        let mut gen = Generator::ascii(7);
+        again = (0..200)
+            .map(|_| {
+                value
+                    .generate(&mut gen)
+                    .unwrap()
+                    .into_iter()
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(inputs, again);
+    }
 
-            ident
-                .or(string)
-                .map_with_span(|token, span| (span, token))
-                .padded()
-                .repeated()
-                .collect_exactly()
+    #[test]
+    fn generate_respects_filter() {
+        use self::{generate::Generator, prelude::*};
+
+        let vowels = any::<&str, extra::Default>()
+            .filter(|c: &char| "aeiou".contains(*c))
+            .repeated()
+            .at_least(1)
+            .collect::<String>();
+        let mut gen = Generator::new(0, 'a'..='z');
+        for _ in 0..50 {
+            let input = vowels.generate(&mut gen).unwrap();
+            assert!(input.iter().all(|c| "aeiou".contains(*c)), "{:?}", input);
         }
 
-        assert_eq!(
-            parser()
-                .parse(r#"hello "world" these are "test" tokens"#.with_context(42))
-                .into_result(),
-            Ok([
-                ((42, (0..5).into()), Token::Ident("hello")),
-                ((42, (6..13).into()), Token::String("\"world\"")),
-                ((42, (14..19).into()), Token::Ident("these")),
-                ((42, (20..23).into()), Token::Ident("are")),
-                ((42, (24..30).into()), Token::String("\"test\"")),
-                ((42, (31..37).into()), Token::Ident("tokens")),
-            ]),
-        );
+        // Nothing can be generated for a parser that accepts arbitrary input
+        let custom = custom::<_, &str, (), extra::Default>(|_| Ok(()));
+        assert_eq!(custom.generate(&mut gen), None);
     }
 
     #[test]
-    fn zero_copy_repetition() {
+    #[cfg(feature = "proptest")]
+    fn generate_proptest_strategy() {
+        use self::{generate::Generator, prelude::*};
+        use proptest::test_runner::{TestError, TestRunner};
+
+        let words = text::ident::<&str, _, extra::Err<Rich<char>>>()
+            .separated_by(just(' '))
+            .at_least(1)
+            .collect::<Vec<_>>();
+        let strategy = generate::strategy(&words).generator(Generator::new(0, 'a'..='c'));
+        TestRunner::default()
+            .run(&strategy, |input| {
+                assert!(input.chars().all(|c| "abc ".contains(c)), "{:?}", input);
+                assert!(!words.parse(&input).has_errors(), "rejected {:?}", input);
+                Ok(())
+            })
+            .unwrap();
+
+        // Parsers that can't generate an input reject every test case
+        let custom = custom::<_, &str, (), extra::Default>(|_| Ok(()));
+        let res = TestRunner::default().run(&generate::strategy(&custom), |_| Ok(()));
+        assert!(matches!(res, Err(TestError::Abort(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "label")]
+    fn graph_labels_and_recursion() {
         use self::prelude::*;
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            any()
-                .filter(|c: &char| c.is_ascii_digit())
-                .repeated()
-                .at_least(1)
-                .at_most(3)
-                .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                .padded()
-                .separated_by(just(',').padded())
-                .allow_trailing()
-                .collect()
-                .delimited_by(just('['), just(']'))
-        }
+        let list = recursive(|list| {
+            let item = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+                .labelled("number")
+                .ignored()
+                .or(list.delimited_by(just('['), just(']')));
+            choice((item.separated_by(just(',')).collect::<Vec<_>>(),)).ignored()
+        });
+        let graph = list.graph();
+        let dot = graph.to_dot();
+
+        // The recursive parser is only walked once, and refers back to itself with a recursion edge
+        assert_eq!(dot.matches("[label=\"Recursive\"]").count(), 1);
+        assert_eq!(dot.matches("label=\"recursion\"").count(), 1);
+        assert!(str::contains(&dot, "[label=\"number\", shape=box]"));
+        assert!(str::contains(&dot, "[label=\"Choice\"]"));
+        assert_eq!(graph.node_count(), dot.matches(" [label=").count());
+    }
 
+    #[test]
+    #[cfg(feature = "label")]
+    fn graph_visitor() {
+        use self::{
+            graph::{NodeKind, NodeRef, Visitor},
+            prelude::*,
+        };
+
+        struct Log(Vec<String>);
+
+        impl Visitor for Log {
+            fn enter(&mut self, node: NodeRef<'_>) -> bool {
+                self.0.push(format!(
+                    "{:?} {}",
+                    node.kind(),
+                    node.label().unwrap_or(node.name())
+                ));
+                // Don't look inside labelled parsers
+                node.kind() != NodeKind::Labelled
+            }
+        }
+
+        let tree = recursive(|tree| {
+            just::<_, &str, extra::Err<Rich<char>>>('x')
+                .labelled("leaf")
+                .or(tree.delimited_by(just('('), just(')')))
+                .or_not()
+                .map(|c| c.unwrap_or('x'))
+        });
+
+        let mut log = Log(Vec::new());
+        tree.graph().visit(&mut log);
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
-        );
-        assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            log.0,
+            [
+                "Recursive Recursive",
+                "Wrapper Map",
+                "Optional OrNot",
+                "Choice Or",
+                "Labelled leaf",
+                "Sequence DelimitedBy",
+                "Terminal Just",
+                "Terminal Just",
+            ]
         );
+    }
+
+    #[test]
+    #[cfg(feature = "label")]
+    fn ebnf_grammar() {
+        use self::prelude::*;
+
+        let expr = recursive(|expr| {
+            let atom = text::ident::<&str, _, extra::Err<Rich<char>>>()
+                .ignored()
+                .or(expr.delimited_by(just('('), just(')')))
+                .labelled("atom");
+            atom.clone()
+                .then(just('+').or(just('-')).then(atom).repeated())
+                .ignored()
+                .labelled("expr")
+        });
+
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            expr.graph().to_ebnf(),
+            "expr = atom , { ( ? Just ? | ? Just ? ) , atom } ;\n\
+             atom = ? Ident ? | ? Just ? , expr , ? Just ? ;\n",
         );
-
-        assert!(parser().parse("[1234,123,12,1]").has_errors());
-        assert!(parser().parse("[,0, 1, 456]").has_errors());
-        assert!(parser().parse("[3, 4, 5, 67 89,]").has_errors());
     }
 
     #[test]
-    fn zero_copy_group() {
+    fn tree_sitter_grammar() {
         use self::prelude::*;
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, (&'a str, u64, char)> {
-            group((
-                any()
-                    .filter(|c: &char| c.is_ascii_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .slice()
-                    .padded(),
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .map_slice(|s: &str| s.parse::<u64>().unwrap())
-                    .padded(),
-                any().filter(|c: &char| !c.is_whitespace()).padded(),
-            ))
-        }
+        let expr = recursive(|expr| {
+            let atom = text::ident::<&str, _, extra::Err<Rich<char>>>()
+                .ignored()
+                .or(expr.delimited_by(just('('), just(')')))
+                .padded();
+            atom.clone()
+                .then(just('+').named("binary op").then(atom).repeated())
+                .ignored()
+        });
 
+        let graph = expr.graph();
+        let grammar = graph.to_tree_sitter("calc").terminal("Ident", "/[a-z]+/");
+        // Opaque parsers stay blank until they are defined, and the whitespace of `padded` is left to tree-sitter
         assert_eq!(
-            parser().parse("abc 123 [").into_result(),
-            Ok(("abc", 123, '[')),
+            grammar.to_grammar_js(),
+            "module.exports = grammar({\n  name: 'calc',\n\n  rules: {\n    \
+             recursive_1: $ => seq(choice(/[a-z]+/, seq(blank() /* Just */, $.recursive_1, blank() /* Just */)), \
+             repeat(seq($.binary_op, choice(/[a-z]+/, seq(blank() /* Just */, $.recursive_1, blank() /* Just */))))),\n    \
+             binary_op: $ => blank() /* Just */,\n  },\n});\n",
         );
+
+        let grammar = grammar.rule("binary op", "'+'");
+        assert!(grammar
+            .to_grammar_js()
+            .lines()
+            .any(|line| line == "    binary_op: $ => '+',"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "label", feature = "memoization"))]
+    fn analyse_grammars() {
+        use self::{graph::Problem, prelude::*};
+
+        let int = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+            .ignored()
+            .labelled("int");
+
+        // Left recursion hidden behind a nullable parser, and through another rule
+        let expr = recursive(|expr| {
+            let atom = text::whitespace()
+                .ignore_then(expr)
+                .labelled("atom")
+                .or(int);
+            atom.then_ignore(just('+')).ignored().labelled("expr")
+        });
         assert_eq!(
-            parser().parse("among3d").into_result(),
-            Ok(("among", 3, 'd')),
+            expr.analyse(),
+            [Problem::LeftRecursion {
+                rule: "expr".to_string()
+            }]
         );
+
+        // Left recursion through a memoised parser is supported
+        let expr = recursive(|expr| {
+            expr.then_ignore(just('+'))
+                .then(int)
+                .ignored()
+                .or(int)
+                .memoised()
+        });
+        assert_eq!(expr.analyse(), []);
+
+        // Well-formed grammars have no problems
+        let list = recursive(|list| {
+            int.or(list.delimited_by(just('['), just(']')))
+                .padded()
+                .separated_by(just(','))
+                .allow_trailing()
+                .collect::<Vec<_>>()
+                .ignored()
+        });
+        assert_eq!(list.analyse(), []);
+
+        let items = int
+            .or_not()
+            .separated_by(text::whitespace())
+            .collect::<Vec<_>>()
+            .labelled("items");
         assert_eq!(
-            parser().parse("cba321,").into_result(),
-            Ok(("cba", 321, ',')),
+            items.analyse(),
+            [Problem::NullableRepetition {
+                rule: Some("items".to_string()),
+                parser: "SeparatedBy".to_string(),
+            }]
         );
+    }
 
-        assert!(parser().parse("abc 123  ").has_errors());
-        assert!(parser().parse("123abc ]").has_errors());
-        assert!(parser().parse("and one &").has_errors());
+    #[test]
+    #[cfg(all(feature = "label", feature = "railroad"))]
+    fn railroad_rules() {
+        use self::prelude::*;
+
+        let value = recursive(|value| {
+            let number = text::int::<&str, _, extra::Err<Rich<char>>>(10).labelled("number");
+            let array = value
+                .separated_by(just(','))
+                .collect::<Vec<_>>()
+                .delimited_by(just('['), just(']'))
+                .ignored()
+                .labelled("array");
+            number
+                .ignored()
+                .or(array)
+                .or_not()
+                .ignored()
+                .labelled("value")
+        });
+        let svg = value.graph().to_railroad_svg();
+
+        // The recursive parser is named after the labelled parser it wraps, and each rule is drawn once
+        let rules = svg
+            .match_indices("<text class=\"rule\"")
+            .map(|(i, _)| {
+                &svg[svg[i..].find('>').unwrap() + i + 1..svg[i..].find("</").unwrap() + i]
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rules, ["value", "number", "array"]);
+        assert_eq!(svg.matches(">value</text>").count(), 2);
     }
 
     #[test]
-    fn zero_copy_group_array() {
+    fn boxed_collapses() {
         use self::prelude::*;
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, [char; 3]> {
-            group([just('a'), just('b'), just('c')])
+        fn rebox<'a, P>(parser: P) -> Boxed<'a, 'a, &'a str, char, extra::Default>
+        where
+            P: Parser<'a, &'a str, char> + MaybeSync + 'a,
+        {
+            Parser::boxed(parser)
         }
 
-        assert_eq!(parser().parse("abc").into_result(), Ok(['a', 'b', 'c']));
-        assert!(parser().parse("abd").has_errors());
+        let boxed = Parser::boxed(just::<_, &str, extra::Default>('a'));
+        let reboxed = rebox(rebox(boxed.clone()));
+        assert!(RefC::ptr_eq(&boxed.inner, &reboxed.inner));
+        assert_eq!(reboxed.parse("a").into_result(), Ok('a'));
     }
 
     #[test]
-    fn unicode_str() {
-        let input = "🄯🄚🹠🴎🄐🝋🰏🄂🬯🈦g🸵🍩🕔🈳2🬙🨞🅢🭳🎅h🵚🧿🏩🰬k🠡🀔🈆🝹🤟🉗🴟📵🰄🤿🝜🙘🹄5🠻🡉🱖🠓";
-        let mut own = InputOwn::<_, extra::Default>::new(input);
-        let mut inp = own.as_ref_start();
+    fn check_skips_output() {
+        use self::prelude::*;
+        use core::cell::Cell;
 
-        while let Some(c) = inp.next() {
-            drop(c);
-        }
+        let calls = Cell::new(0);
+        let parser = just::<_, _, extra::Err<Simple<char>>>('a')
+            .map(|c| {
+                calls.set(calls.get() + 1);
+                c
+            })
+            .repeated()
+            .collect::<Vec<_>>();
+
+        assert_eq!(parser.check("aaa").into_result(), Ok(()));
+        assert_eq!(calls.get(), 0);
+        assert!(parser.check("aab").has_errors());
+        assert_eq!(parser.parse("aaa").into_result(), Ok(vec!['a'; 3]));
+        assert_eq!(calls.get(), 3);
     }
 
     #[test]
-    fn iter() {
+    fn choice_byte_dispatch() {
         use self::prelude::*;
 
-        fn parser<'a>() -> impl IterParser<'a, &'a str, char> {
-            any().repeated()
+        // 'é' and 'è' share a leading byte, as do "ab" and "a"
+        macro_rules! tokens {
+            () => {
+                choice((
+                    just::<_, _, extra::Err<Rich<char>>>("ab"),
+                    just("é"),
+                    just("a"),
+                    just("è").or_not().slice().then_ignore(just(';')),
+                    one_of('0'..='9').repeated().at_least(1).slice(),
+                ))
+            };
         }
 
-        let mut chars = String::new();
-        for c in parser().parse_iter(&"abcdefg").into_result().unwrap() {
-            chars.push(c);
+        // Skipped branches may change the order in which expected tokens are reported, but not the tokens themselves
+        fn summary<'a>(
+            result: ParseResult<&'a str, Rich<'a, char>>,
+        ) -> (
+            Option<&'a str>,
+            Vec<(SimpleSpan, Option<char>, Vec<String>)>,
+        ) {
+            let (output, errs) = result.into_output_errors();
+            let errs = errs
+                .into_iter()
+                .map(|e| {
+                    let mut expected = e.expected().map(|e| e.to_string()).collect::<Vec<_>>();
+                    expected.sort();
+                    (*e.span(), e.found().copied(), expected)
+                })
+                .collect();
+            (output, errs)
         }
 
-        assert_eq!(&chars, "abcdefg");
+        for input in ["ab", "a", "é", "è;", ";", "è", "42", "x", "\u{FFFD}", ""] {
+            assert_eq!(
+                summary(tokens!().parse(input)),
+                summary(tokens!().byte_dispatch().parse(input)),
+                "input: {input:?}",
+            );
+        }
+
+        let parser = choice([
+            just::<_, _, extra::Default>(b'a'),
+            just(b'\xFF'),
+            just(b'c'),
+        ])
+        .byte_dispatch();
+        assert_eq!(parser.parse(b"\xFF" as &[u8]).into_result(), Ok(b'\xFF'));
+        assert!(parser.parse(b"b" as &[u8]).has_errors());
     }
 
     #[test]
-    #[cfg(feature = "memoization")]
-    fn exponential() {
-        use self::prelude::*;
+    fn scan_fast_paths() {
+        use self::{prelude::*, text::scan::ByteSet};
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
-            recursive(|expr| {
-                let atom = any()
-                    .filter(|c: &char| c.is_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .collect()
-                    .or(expr.delimited_by(just('('), just(')')));
+        // Non-ASCII whitespace and line breaks are still handled after the byte-level fast paths
+        let padded = text::padding::<_, _, extra::Err<Simple<char>>>()
+            .line_comment("//")
+            .ignore_then(just('x'));
+        assert_eq!(
+            padded
+                .parse(" \t\u{3000} // comment é\u{2028}\u{A0}x")
+                .into_result(),
+            Ok('x'),
+        );
+        assert!(padded.parse("// comment é\u{A0}x").has_errors());
 
-                atom.clone()
-                    .then_ignore(just('+'))
-                    .then(atom.clone())
-                    .map(|(a, b)| format!("{}{}", a, b))
-                    .memoised()
-                    .or(atom)
-            })
-            .then_ignore(end())
-        }
+        let padded = text::padding::<_, _, extra::Err<Simple<u8>>>()
+            .line_comment("#")
+            .ignore_then(just(b'x'));
+        assert_eq!(
+            padded.parse(b" # \x85\xFF\nx" as &[u8]).into_result(),
+            Ok(b'x')
+        );
 
-        parser()
-            .parse("((((((((((((((((((((((((((((((a+b))))))))))))))))))))))))))))))")
-            .into_result()
-            .unwrap();
+        // Non-ASCII bytes in a set never match `char`s
+        let set = ByteSet::from_bytes(b"ab").with(0xE9);
+        let parser = one_of::<_, _, extra::Err<Simple<char>>>(set);
+        assert_eq!(parser.parse("b").into_result(), Ok('b'));
+        assert!(parser.parse("é").has_errors());
+        assert_eq!(
+            text::scan::scan_while::<_, _, extra::Err<Simple<char>>>(set)
+                .lazy()
+                .parse("abé")
+                .into_result(),
+            Ok("ab"),
+        );
+        assert_eq!(
+            text::scan::scan_while::<_, _, extra::Err<Simple<u8>>>(set)
+                .parse(b"ab\xE9" as &[u8])
+                .into_result(),
+            Ok(b"ab\xE9" as &[u8]),
+        );
     }
 
     #[test]
     #[cfg(feature = "memoization")]
-    fn left_recursive() {
-        use self::prelude::*;
+    fn memo_bound() {
+        use self::{input::MemoTable, prelude::*};
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+        fn parser<'a>(bound: MemoBound) -> impl Parser<'a, &'a str, String> {
             recursive(|expr| {
                 let atom = any()
                     .filter(|c: &char| c.is_alphabetic())
@@ -2538,9 +7526,111 @@ mod tests {
                 sum.or(atom)
             })
             .then_ignore(end())
+            .memo_bound(bound)
         }
 
-        assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
+        // Left recursion still works, since running parsers are never evicted
+        for bound in [MemoBound::Lru(1), MemoBound::Window(1)] {
+            assert_eq!(parser(bound).parse("a+b+c").into_result().unwrap(), "abc");
+        }
+
+        let mut table = MemoTable::<usize, ()>::new(MemoBound::Lru(2));
+        table.insert((0, 0, 0), Some(Located::at(0, ())));
+        table.insert((1, 0, 0), Some(Located::at(1, ())));
+        assert!(table.get(&(0, 0, 0)).is_some());
+        table.insert((2, 0, 0), Some(Located::at(2, ())));
+        assert_eq!(table.len(), 2);
+        assert!(table.get(&(0, 0, 0)).is_some());
+        assert!(table.get(&(1, 0, 0)).is_none());
+
+        let mut table = MemoTable::<usize, ()>::new(MemoBound::Window(4));
+        table.insert((0, 0, 0), None);
+        table.insert((1, 0, 0), Some(Located::at(1, ())));
+        table.insert((8, 0, 0), Some(Located::at(8, ())));
+        assert_eq!(table.len(), 2);
+        assert!(table.get(&(0, 0, 0)).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn memo_stats() {
+        use self::{input::MemoTable, prelude::*};
+
+        let mut table = MemoTable::<usize, ()>::new(MemoBound::Lru(2));
+        for i in 0..4 {
+            table.insert((i, 0, 0), Some(Located::at(i, ())));
+        }
+        assert!(table.get(&(3, 0, 0)).is_some());
+        assert!(table.get(&(0, 0, 0)).is_none());
+        let stats = table.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(
+            (stats.entries, stats.peak_entries, stats.evictions),
+            (2, 3, 2)
+        );
+
+        // Statistics of nested parses are included
+        let inner = just::<_, _, extra::Default>('a')
+            .memoised()
+            .then_ignore(just('b'))
+            .or(just('a'));
+        let (_, stats) = inner.parse_with_memo_stats("a");
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+        let nested = inner.nested_in(just('x').ignore_then(any().repeated().slice()));
+        let (_, stats) = nested.parse_with_memo_stats("xa");
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn memoised_with_ctx() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, char> {
+            let generic = Parser::boxed(
+                just('0')
+                    .configure(|cfg: primitive::JustCfg<char>, ctx: &char| cfg.seq(*ctx))
+                    .memoised(),
+            );
+            // Both branches share the same memoised parser, invoked at the same offset with different contexts
+            Parser::<_, _, extra::Default>::or(generic.clone().with_ctx('a'), generic.with_ctx('b'))
+        }
+
+        let parser = parser();
+        assert_eq!(parser.parse("a").into_result(), Ok('a'));
+        assert_eq!(parser.parse("b").into_result(), Ok('b'));
+        assert!(parser.parse("c").has_errors());
+
+        // Contexts need not implement `Hash`
+        fn scaled<'a>() -> impl Parser<'a, &'a str, (f64, f64)> {
+            let digit = any::<_, extra::Context<f64>>()
+                .map_with_ctx(|c: char, _, scale: &f64| c.to_digit(10).unwrap() as f64 * *scale)
+                .memoised();
+            // The same memoised parser, invoked at the same offset with different contexts
+            Parser::<_, _, extra::Default>::then(
+                Parser::<_, _, extra::Default>::rewind(digit.with_ctx(0.5)),
+                digit.with_ctx(2.0),
+            )
+        }
+
+        assert_eq!(scaled().parse("4").into_result(), Ok((2.0, 8.0)));
+
+        // Each use of a `with_ctx` parser shares its context, and so its memoised results
+        let generic = just::<_, _, extra::Context<char>>('0')
+            .configure(|cfg: primitive::JustCfg<char>, ctx: &char| cfg.seq(*ctx))
+            .memoised();
+        let a = generic.with_ctx::<char>('a');
+        let parser =
+            Parser::<_, _, extra::Default>::or(&a, &a).or(just::<_, _, extra::Default>('x'));
+        let (res, stats) = parser.parse_with_memo_stats("x");
+        assert_eq!(res.into_result(), Ok('x'));
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+
+        // The results memoised within a `then_with_ctx` are discarded once it ends
+        let item = any::<_, extra::Default>().then_with_ctx(generic.or(any()));
+        let (res, stats) = item.repeated().parse_with_memo_stats("abcd");
+        assert!(!res.has_errors());
+        assert_eq!((stats.misses, stats.entries), (2, 0));
     }
 
     #[cfg(debug_assertions)]
@@ -2620,6 +7710,16 @@ mod tests {
             );
         }
 
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldl_with_op() {
+            empty::<&str, extra::Default>()
+                .to(1)
+                .foldl_with_op(empty(), |a, (), b| a + b)
+                .parse("a+b+c");
+        }
+
         #[test]
         #[should_panic]
         #[cfg(debug_assertions)]
@@ -2694,4 +7794,254 @@ mod tests {
         let expr = todo::<&str, String, extra::Default>();
         expr.then_ignore(end()).parse("a+b+c");
     }
+
+    #[test]
+    #[should_panic(expected = "binary operators")]
+    fn todo_err_message() {
+        let expr = todo::<&str, String, extra::Default>().with_message("binary operators");
+        expr.then_ignore(end()).parse("a+b+c");
+    }
+
+    #[test]
+    fn one_of_ranges() {
+        use self::prelude::*;
+
+        let ident = one_of::<_, _, extra::Err<Rich<u8>>>(text::ascii::alphabetic())
+            .then(one_of(text::ascii::alphanumeric()).repeated())
+            .slice();
+
+        assert_eq!(
+            ident.parse(&b"abc123"[..]).into_result(),
+            Ok(&b"abc123"[..])
+        );
+
+        let errs = ident.parse(&b"1abc"[..]).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![
+                &error::RichPattern::Range(b'a'.into(), b'z'.into()),
+                &error::RichPattern::Range(b'A'.into(), b'Z'.into()),
+            ],
+        );
+
+        let not_digit = none_of::<_, _, extra::Err<Simple<char>>>(['0'..='9']);
+        assert_eq!(not_digit.parse("x").into_result(), Ok('x'));
+        assert!(not_digit.parse("5").has_errors());
+    }
+
+    #[test]
+    fn indented_block() {
+        use self::prelude::*;
+
+        fn parser<'a>(
+        ) -> impl Parser<'a, &'a [u8], Vec<(&'a [u8], Vec<&'a [u8]>)>, extra::Err<Rich<'a, u8>>>
+        {
+            text::ident()
+                .then_ignore(just(b':'))
+                .then(text::indented_block(text::ident()))
+                .separated_by(text::newline())
+                .collect()
+        }
+
+        assert_eq!(
+            parser()
+                .parse(&b"a:\n\tb\n\n\tc\nd:\n  e"[..])
+                .into_result(),
+            Ok(vec![
+                (&b"a"[..], vec![&b"b"[..], &b"c"[..]]),
+                (&b"d"[..], vec![&b"e"[..]]),
+            ]),
+        );
+
+        let errs = parser().parse(&b"a:\n  b\n    c"[..]).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "unexpected indentation");
+        assert_eq!(errs[0].span(), &SimpleSpan::from(7..11));
+    }
+
+    #[test]
+    fn string_bytes() {
+        use self::prelude::*;
+        use alloc::borrow::Cow;
+
+        let string = text::string::<_, _, extra::Err<Rich<u8>>>();
+
+        assert_eq!(
+            string.parse(&b"\"caf\xC3\xA9\""[..]).into_result(),
+            Ok(Cow::Borrowed("caf\u{e9}".as_bytes())),
+        );
+        assert_eq!(
+            string.parse(&b"\"a\\\"b\""[..]).into_result(),
+            Ok(Cow::Owned(b"a\"b".to_vec())),
+        );
+
+        // Unterminated
+        let errs = string.parse(&b"\"abc"[..]).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(4..4));
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![&error::RichPattern::Token(b'"'.into())],
+        );
+    }
+
+    #[test]
+    fn whitespace_predicates() {
+        use self::{prelude::*, text::Char};
+
+        // The predicates on `Char` keep their meaning...
+        assert!(!'\u{3000}'.is_inline_whitespace());
+        // ...while the parsers partition whitespace into inline whitespace and newlines
+        assert!('\u{3000}'.is_horizontal_whitespace());
+        assert!(text::inline_whitespace::<_, _, extra::Err<EmptyErr>>()
+            .exactly(1)
+            .parse("\u{3000}")
+            .into_result()
+            .is_ok());
+        assert!(b'\x0B'.is_newline() && !b'\x0B'.is_horizontal_whitespace());
+        assert!(text::whitespace::<_, _, extra::Err<EmptyErr>>()
+            .exactly(1)
+            .parse(&b"\x0B"[..])
+            .into_result()
+            .is_ok());
+
+        // `Char::is_whitespace` agrees with `text::whitespace`
+        for b in 0..=u8::MAX {
+            let parsed = text::whitespace::<_, _, extra::Err<EmptyErr>>()
+                .exactly(1)
+                .parse(&[b][..])
+                .into_result()
+                .is_ok();
+            assert_eq!(b.is_whitespace(), parsed, "{b:#04x}");
+        }
+        assert!(b'\x0B'.is_whitespace());
+    }
+
+    #[test]
+    fn ident_errors() {
+        use self::{error::RichPattern, prelude::*};
+
+        let ident = text::ident::<_, _, extra::Err<Rich<char>>>();
+        let errs = ident.parse("2x").into_errors();
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [&RichPattern::Class("identifier")],
+        );
+        assert_eq!(errs[0].to_string(), "found '2' expected identifier");
+
+        // Identifiers with a custom start are described in the same way
+        let var = ident.start(|c: &char| *c == '$');
+        assert_eq!(
+            var.parse("x").into_errors()[0].to_string(),
+            "found 'x' expected identifier",
+        );
+    }
+
+    #[test]
+    fn string_bad_hex_escape() {
+        use self::prelude::*;
+
+        let string = text::string::<_, _, extra::Err<Rich<char>>>();
+
+        for (src, span) in [("\"\\xZ\"", 1..3), ("\"\\x4\"", 1..4), ("\"\\x\"", 1..3)] {
+            let errs = string.parse(src).into_errors();
+            assert_eq!(errs.len(), 1);
+            assert_eq!(errs[0].to_string(), "invalid hex escape");
+            assert_eq!(errs[0].span(), &SimpleSpan::from(span));
+        }
+    }
+
+    #[test]
+    fn int_trailing_separator() {
+        use self::{error::RichPattern, prelude::*};
+
+        let int = text::int::<_, _, extra::Err<Rich<char>>>(10).separators();
+        assert_eq!(int.parse("1_0").into_result(), Ok("1_0"));
+
+        // Each separator must be followed by a digit
+        for (src, span) in [("1_", 2..2), ("1__0", 2..3), ("1_x", 2..3)] {
+            let errs = int.parse(src).into_errors();
+            assert_eq!(errs.len(), 1, "{src:?}");
+            assert_eq!(errs[0].span(), &SimpleSpan::from(span));
+            assert_eq!(
+                errs[0].expected().collect::<Vec<_>>(),
+                [&RichPattern::Range('0'.into(), '9'.into())],
+            );
+        }
+    }
+
+    #[test]
+    fn or_not_with_err_padded() {
+        use self::prelude::*;
+
+        let ty = just(':').padded().ignore_then(text::ident());
+        let var = text::ident::<_, _, extra::Err<Rich<char>>>().then(ty.or_not_with_err());
+
+        // Leading padding does not count as the pattern being present
+        for input in ["x = 5", "x ;", "x\n\t;"] {
+            let res = var.lazy().parse(input);
+            assert_eq!(res.errors().len(), 0, "{input:?}");
+            assert_eq!(res.output(), Some(&("x", None)));
+        }
+
+        let errs = var.lazy().parse("x : 42").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(4..5));
+
+        // The same goes for explicit padding
+        let ty = just(':')
+            .padded_by(just(' ').repeated())
+            .ignore_then(text::ident());
+        let var = text::ident::<_, _, extra::Err<Rich<char>>>().then(ty.or_not_with_err());
+        assert_eq!(var.lazy().parse("x  = 5").into_result(), Ok(("x", None)));
+        assert_eq!(var.lazy().parse("x  : 42").into_errors().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "label")]
+    fn label_at_end_of_input() {
+        use self::{error::RichPattern, prelude::*};
+
+        let kw = just::<_, _, extra::Err<Rich<char>>>("let").labelled("keyword");
+
+        // Errors at the first token are labelled, including when that token is the end of the input
+        for input in ["x", ""] {
+            let errs = kw.parse(input).into_errors();
+            assert_eq!(
+                errs[0].expected().collect::<Vec<_>>(),
+                [&RichPattern::Label("keyword")],
+            );
+        }
+        // Errors further in are not
+        let errs = kw.parse("le").into_errors();
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [&RichPattern::Token('t'.into())]
+        );
+    }
+
+    #[test]
+    fn record_trivia_backtracking() {
+        use text::TriviaLog;
+
+        let comment = just::<_, _, extra::State<TriviaLog<&str, SimpleSpan>>>('#')
+            .then(any().and_is(just('\n').not()).repeated())
+            .ignored()
+            .record_trivia();
+        let padding = comment.or(text::whitespace().at_least(1)).repeated();
+        let stmt = |kw| text::keyword(kw).padded_by(padding).then(just(';'));
+
+        // The leading comment is parsed twice, once by each alternative
+        let parser = stmt("let").or(stmt("var")).repeated().collect::<Vec<_>>();
+
+        let mut trivia = TriviaLog::new();
+        assert!(!parser
+            .parse_with_state("# a\nvar #b\n;", &mut trivia)
+            .has_errors());
+        assert_eq!(
+            trivia.into_vec(),
+            vec![("# a", (0..3).into()), ("#b", (8..10).into())],
+        );
+    }
 }
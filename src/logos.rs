@@ -0,0 +1,156 @@
+//! Integration with the [`logos`](https://docs.rs/logos) lexer generator.
+//!
+//! Logos generates fast lexers from token definitions, while chumsky excels at parsing structured token streams. The
+//! functions in this module turn a [`logos::Lexer`] into something that chumsky can parse, with spans
+//! that refer to the original source:
+//!
+//! - [`lex`] runs the lexer to completion, translating logos' error tokens into errors and returning
+//!   [`Tokens`] that can be parsed with [`Tokens::parse`], which reports lexing and parsing errors together.
+//!
+//! - [`input()`] wraps the lexer in an [`Input`] that produces tokens lazily, as the parser requests them. Error tokens
+//!   are passed through to the parser unchanged.
+//!
+//! *This module requires the `logos` feature.*
+
+use super::*;
+use crate::{
+    input::{SpannedInput, Stream},
+    lexer::Tokens,
+};
+use ::logos::{Lexer, Logos, Source};
+
+/// An iterator over the tokens of a [`logos::Lexer`], paired with their spans. See [`input()`].
+pub struct SpannedTokens<'src, T: Logos<'src>> {
+    iter: ::logos::SpannedIter<'src, T>,
+}
+
+impl<'src, T: Logos<'src>> Iterator for SpannedTokens<'src, T> {
+    type Item = (T, SimpleSpan);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(tok, span)| (tok, span.into()))
+    }
+}
+
+/// The input type produced by [`input()`].
+pub type LogosInput<'src, T> = SpannedInput<T, SimpleSpan, Stream<SpannedTokens<'src, T>>>;
+
+/// Wrap a [`logos::Lexer`] in an input that chumsky parsers can consume.
+///
+/// Tokens are lexed lazily as the parser requests them, and each token's span is its byte range in the source. The
+/// end of input span is a zero-width span at the end of the source.
+///
+/// Unlike [`lex`], tokens that logos failed to recognise are passed to the parser as [`Logos::ERROR`], so the parser
+/// will report them as unexpected tokens.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::ValueInput};
+/// use logos::Logos;
+///
+/// #[derive(Logos, Clone, Debug, PartialEq)]
+/// enum Token {
+///     #[regex("[0-9]+", |lex| lex.slice().parse())]
+///     Num(u64),
+///     #[token("+")]
+///     Plus,
+///     #[regex(r"\s+", logos::skip)]
+///     #[error]
+///     Error,
+/// }
+///
+/// fn sum<'a, I>() -> impl Parser<'a, I, u64, extra::Err<Rich<'a, Token>>>
+/// where
+///     I: ValueInput<'a, Token = Token, Span = SimpleSpan>,
+/// {
+///     select! { Token::Num(x) => x }
+///         .separated_by(just(Token::Plus))
+///         .collect::<Vec<_>>()
+///         .map(|xs| xs.into_iter().sum())
+/// }
+///
+/// let src = "1 + 2 + 39";
+/// let result = sum().parse(chumsky::logos::input(Token::lexer(src)));
+/// assert_eq!(result.into_result(), Ok(42));
+///
+/// // Unrecognised input produces an error token, which the parser does not expect
+/// assert!(sum().parse(chumsky::logos::input(Token::lexer("1 ? 2"))).has_errors());
+/// ```
+pub fn input<'src, T>(lexer: Lexer<'src, T>) -> LogosInput<'src, T>
+where
+    T: Logos<'src> + Clone + 'src,
+{
+    let eoi = lexer.source().len();
+    Stream::from_iter(SpannedTokens {
+        iter: lexer.spanned(),
+    })
+    .spanned((eoi..eoi).into())
+}
+
+/// Run a [`logos::Lexer`] to completion, producing [`Tokens`] that can be parsed with
+/// [`Tokens::parse`].
+///
+/// Tokens that logos failed to recognise (i.e: [`Logos::ERROR`]) are not included in the output. Instead, each one
+/// produces an error of type `E` that spans the unrecognised input. When the tokens are parsed, these errors are
+/// reported as [`LexParseError::Lex`](crate::lexer::LexParseError::Lex), before any errors produced by the parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::ValueInput, lexer::LexParseError};
+/// use logos::Logos;
+///
+/// #[derive(Logos, Clone, Debug, PartialEq)]
+/// enum Token {
+///     #[regex("[0-9]+", |lex| lex.slice().parse())]
+///     Num(u64),
+///     #[token("+")]
+///     Plus,
+///     #[regex(r"\s+", logos::skip)]
+///     #[error]
+///     Error,
+/// }
+///
+/// fn sum<'a, I>() -> impl Parser<'a, I, u64, extra::Err<Rich<'a, Token>>>
+/// where
+///     I: ValueInput<'a, Token = Token, Span = SimpleSpan>,
+/// {
+///     select! { Token::Num(x) => x }
+///         .separated_by(just(Token::Plus))
+///         .collect::<Vec<_>>()
+///         .map(|xs| xs.into_iter().sum())
+/// }
+///
+/// let tokens = chumsky::logos::lex::<_, Rich<char>>(Token::lexer("1 + 2 + 39"));
+/// assert_eq!(tokens.parse(&sum()).into_result(), Ok(42));
+///
+/// // The unrecognised `?` is skipped, and reported alongside the parser's errors
+/// let tokens = chumsky::logos::lex::<_, Rich<char>>(Token::lexer("1 ? 2 +"));
+/// let errors = tokens.parse(&sum()).into_errors();
+/// assert!(matches!(
+///     errors.as_slice(),
+///     [LexParseError::Lex(lex_err), LexParseError::Parse(parse_err)]
+///         if *lex_err.span() == (2..3).into() && *parse_err.span() == (4..5).into(),
+/// ));
+/// ```
+pub fn lex<'src, T, E>(mut lexer: Lexer<'src, T>) -> Tokens<T, SimpleSpan, E>
+where
+    T: Logos<'src> + PartialEq,
+    &'src T::Source: Input<'src, Span = SimpleSpan>,
+    E: Error<'src, &'src T::Source>,
+{
+    let eoi = lexer.source().len();
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    while let Some(tok) = lexer.next() {
+        let span = SimpleSpan::from(lexer.span());
+        if tok == T::ERROR {
+            errors.push(E::custom(span, "unrecognised token"));
+        } else {
+            tokens.push((tok, span));
+        }
+    }
+    Tokens::new(Some(tokens), Some((eoi..eoi).into()), errors)
+}
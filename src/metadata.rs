@@ -0,0 +1,126 @@
+//! Collecting metadata about an input while it is parsed.
+//!
+//! Tools that only need to know a few facts about an input (such as the modules it imports, or the string literals it
+//! contains) often have to walk the output of a parser a second time to find them. Instead, the parts of a grammar
+//! that produce such facts can be tagged with [`Parser::record_metadata`], and [`Parser::parse_metadata`] collects
+//! them during the parse, alongside the output.
+//!
+//! Metadata can be of any type that is `'static` (so it may need to own its data, such as a `String` rather than a
+//! `&str`), and items of different types can be recorded during the same parse. They are kept in the order they were
+//! recorded in, and are retrieved by type with [`Metadata::iter`] or [`Metadata::take`].
+//!
+//! Metadata recorded by a parser that later backtracks (such as one branch of a [`choice`]) is discarded along with
+//! it, just like errors emitted by [`Parser::validate`]. Metadata within [memoised](Parser::memoised) parsers is only
+//! recorded the first time the parser is invoked at each position.
+//!
+//! *This module requires the `metadata` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! #[derive(Debug, PartialEq)]
+//! struct Import(String);
+//!
+//! let path = text::ident::<_, _, extra::Err<Rich<char>>>()
+//!     .separated_by(just("::"))
+//!     .at_least(1)
+//!     .slice();
+//! let import = text::keyword("use")
+//!     .ignore_then(path.padded())
+//!     .record_metadata(|path: &&str, _| Import(path.to_string()))
+//!     .then_ignore(just(';'));
+//! let call = text::ident().then_ignore(just("()")).then_ignore(just(';'));
+//! let module = import.ignored().or(call.ignored()).padded().repeated();
+//!
+//! let (result, mut metadata) = module.parse_metadata("use std::fs; main(); use foo;");
+//! assert!(!result.has_errors());
+//! assert_eq!(metadata.take::<Import>(), [Import("std::fs".into()), Import("foo".into())]);
+//! ```
+
+use super::*;
+use core::any::Any;
+
+/// The metadata recorded by [`Parser::record_metadata`] during a parse. See the [module documentation](self) for more
+/// information.
+#[derive(Debug, Default)]
+pub struct Metadata {
+    items: Vec<Box<dyn Any>>,
+}
+
+impl Metadata {
+    /// Iterate over the recorded items of type `T`, in the order they were recorded.
+    pub fn iter<T: Any>(&self) -> impl Iterator<Item = &T> + '_ {
+        self.items.iter().filter_map(|item| item.downcast_ref())
+    }
+
+    /// Remove the recorded items of type `T`, returning them in the order they were recorded.
+    ///
+    /// Items of other types are kept.
+    pub fn take<T: Any>(&mut self) -> Vec<T> {
+        let mut taken = Vec::new();
+        let mut kept = Vec::new();
+        for item in self.items.drain(..) {
+            match item.downcast() {
+                Ok(item) => taken.push(*item),
+                Err(item) => kept.push(item),
+            }
+        }
+        self.items = kept;
+        taken
+    }
+
+    /// Get the number of recorded items, of any type.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if no items were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub(crate) fn from_items(items: Vec<Box<dyn Any>>) -> Self {
+        Self { items }
+    }
+}
+
+/// See [`Parser::record_metadata`].
+#[derive(Copy, Clone)]
+pub struct RecordMetadata<A, F> {
+    pub(crate) parser: A,
+    pub(crate) record: F,
+}
+
+impl<'a, I, O, E, A, F, T> ParserSealed<'a, I, O, E> for RecordMetadata<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&O, I::Span) -> T,
+    T: Any,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        if inp.metadata.is_none() {
+            return self.parser.go::<M>(inp);
+        }
+        let before = inp.offset();
+        let out = self.parser.go::<Emit>(inp)?;
+        let item = (self.record)(&out, inp.span_since(before));
+        if let Some(items) = inp.metadata.as_deref_mut() {
+            items.push(Box::new(item));
+        }
+        Ok(M::bind(|| out))
+    }
+
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
@@ -0,0 +1,153 @@
+//! Integration with the [`nom`](https://docs.rs/nom) parser combinator library.
+//!
+//! Projects moving from nom to chumsky often have nom parsers that are well tested and that nobody wants to rewrite
+//! at the same time as everything else. [`from_nom`] wraps such a parser so that it can be used within a chumsky
+//! grammar, allowing a grammar to be ported one part at a time.
+//!
+//! *This module requires the `nom` feature.*
+
+use super::*;
+use ::nom::{
+    error::{ErrorKind, VerboseError},
+    Err as NomErr, IResult, InputLength,
+};
+
+/// A nom error type that records where in the input the error occurred, so that it can be translated into a chumsky
+/// error at the same position.
+///
+/// This is implemented for nom's own error types. Errors without a position (such as `()`) are reported at the
+/// position where the nom parser began.
+pub trait NomError<I> {
+    /// Get the remaining input at the point where the error occurred, if known.
+    fn remaining(&self) -> Option<I>;
+}
+
+impl<I: Clone> NomError<I> for ::nom::error::Error<I> {
+    fn remaining(&self) -> Option<I> {
+        Some(self.input.clone())
+    }
+}
+
+impl<I: Clone> NomError<I> for VerboseError<I> {
+    fn remaining(&self) -> Option<I> {
+        self.errors.first().map(|(input, _)| input.clone())
+    }
+}
+
+impl<I: Clone> NomError<I> for (I, ErrorKind) {
+    fn remaining(&self) -> Option<I> {
+        Some(self.0.clone())
+    }
+}
+
+impl<I> NomError<I> for () {
+    fn remaining(&self) -> Option<I> {
+        None
+    }
+}
+
+/// See [`from_nom`].
+pub struct FromNom<C, F, NE, I, E> {
+    parser: F,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(C, NE, I, E)>,
+}
+
+impl<C, F: Copy, NE, I, E> Copy for FromNom<C, F, NE, I, E> {}
+impl<C, F: Clone, NE, I, E> Clone for FromNom<C, F, NE, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// Use a nom parser as a chumsky parser, over `&str` or `&[u8]` inputs.
+///
+/// The nom parser is given the rest of the input, and the input is advanced past whatever it consumed. If it fails,
+/// the error is reported at the position that the nom error refers to (see [`NomError`]), as an unexpected token.
+/// Nom's unrecoverable failures ([`nom::Err::Failure`](::nom::Err::Failure)) are treated like any other error, so
+/// chumsky may still try alternatives, and a request for more input ([`nom::Err::Incomplete`](::nom::Err::Incomplete))
+/// is reported as an unexpected end of input.
+///
+/// The output type of this parser is the output of the nom parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, nom::from_nom};
+/// use nom::{bytes::complete::tag, character::complete::hex_digit1, combinator::map_res, IResult};
+///
+/// // An existing nom parser for hexadecimal colours
+/// fn colour(input: &str) -> IResult<&str, u32> {
+///     let (input, _) = tag("#")(input)?;
+///     map_res(hex_digit1, |digits| u32::from_str_radix(digits, 16))(input)
+/// }
+///
+/// let colours = from_nom::<_, _, _, _, _, extra::Err<Simple<char>>>(colour)
+///     .padded()
+///     .separated_by(just(','))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(colours.parse("#ff0000, #00ff00").into_result(), Ok(vec![0xff0000, 0x00ff00]));
+/// // The error points at the part of the input that nom rejected
+/// let errs = colours.parse("#ff0000, #xyz").into_errors();
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(10..11));
+/// ```
+pub const fn from_nom<'a, C, I, O, F, NE, E>(parser: F) -> FromNom<C, F, NE, I, E>
+where
+    C: Char,
+    I: StrInput<'a, C>,
+    E: ParserExtra<'a, I>,
+    F: Fn(&'a C::Str) -> IResult<&'a C::Str, O, NE>,
+    NE: NomError<&'a C::Str>,
+{
+    FromNom {
+        parser,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, C, I, O, E, F, NE> ParserSealed<'a, I, O, E> for FromNom<C, F, NE, I, E>
+where
+    C: Char,
+    I: StrInput<'a, C>,
+    E: ParserExtra<'a, I>,
+    F: Fn(&'a C::Str) -> IResult<&'a C::Str, O, NE>,
+    NE: NomError<&'a C::Str>,
+    &'a C::Str: InputLength,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let trailing = inp.slice_trailing_inner();
+        let remaining = match (self.parser)(trailing) {
+            Ok((rest, out)) => {
+                if rest.input_len() == 0 {
+                    inp.reach_end();
+                }
+                inp.skip_bytes(trailing.input_len() - rest.input_len());
+                return Ok(M::bind(|| out));
+            }
+            Err(NomErr::Error(err) | NomErr::Failure(err)) => {
+                // Errors without a position are reported where the nom parser began
+                err.remaining()
+                    .map_or(trailing.input_len(), |rest| rest.input_len())
+            }
+            Err(NomErr::Incomplete(_)) => {
+                inp.reach_end();
+                0
+            }
+        };
+        let before = inp.save();
+        inp.skip_bytes(trailing.input_len() - remaining.min(trailing.input_len()));
+        let start = inp.offset();
+        let (at, found) = inp.next_maybe_inner();
+        let span = inp.span_since(start);
+        inp.add_alt(at, None, found.map(|f| f.into()), span);
+        inp.rewind(before);
+        Err(())
+    }
+
+    go_extra!(O);
+}
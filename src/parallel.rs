@@ -0,0 +1,122 @@
+//! Parallel parsing of independent parts of an input, using [`rayon`](https://docs.rs/rayon).
+//!
+//! Many large inputs are made up of items that can be parsed independently of one another, such as the records of a
+//! log file or the top-level items of a data file. If the boundaries between these items can be found cheaply (for
+//! example, by splitting the input at blank lines, or by running a simple scanner parser that recognises items without
+//! producing any output), [`parse_chunks`] can parse the items on multiple threads at once.
+//!
+//! Each chunk is parsed as if it were part of the whole input, so the spans of outputs and errors are relative to the
+//! start of the whole input rather than the start of the chunk.
+//!
+//! Parsers must be [`Sync`] to be shared between threads. Parsers that use [`Recursive`] or [`Boxed`] are only
+//! [`Sync`] when the `sync` feature is enabled.
+//!
+//! *This module requires the `rayon` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, parallel::parse_chunks};
+//! let entry = text::ident::<_, _, extra::Err<Simple<char>>>()
+//!     .then_ignore(just(" = "))
+//!     .then(text::int(10).from_str::<u32>().unwrapped())
+//!     .then_ignore(text::newline());
+//! let record = entry
+//!     .repeated()
+//!     .collect::<Vec<_>>()
+//!     .then_ignore(text::newline().or_not());
+//!
+//! let input = "x = 1\ny = 2\n\nx = 3\n\nz = 4\n";
+//!
+//! // Records are separated by blank lines
+//! let chunks = input.split_inclusive("\n\n").scan(0, |start, record| {
+//!     let range = *start..*start + record.len();
+//!     *start = range.end;
+//!     Some(range)
+//! });
+//!
+//! assert_eq!(
+//!     parse_chunks(&record, input, chunks).into_result(),
+//!     Ok(vec![
+//!         vec![("x", 1), ("y", 2)],
+//!         vec![("x", 3)],
+//!         vec![("z", 4)],
+//!     ]),
+//! );
+//! ```
+
+use super::*;
+use ::rayon::prelude::*;
+
+/// Parse each of the given chunks of the input in parallel, collecting their outputs and errors in order.
+///
+/// The chunks are given as ranges of offsets into the input (byte offsets for `&str`, indices for `&[T]`), and must
+/// not overlap. Each chunk must be parsed completely by the parser. If parsing any chunk fails to produce an output,
+/// the result has no output, but still contains the errors of every chunk.
+///
+/// # Panics
+///
+/// Panics if a chunk lies outside the input or, for `&str`, does not begin and end on a character boundary.
+pub fn parse_chunks<'a, I, O, E, P>(
+    parser: &P,
+    input: I,
+    chunks: impl IntoIterator<Item = Range<usize>>,
+) -> ParseResult<Vec<O>, E::Error>
+where
+    I: SliceInput<'a, Offset = usize, Slice = I> + Sync,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    E::Error: Send,
+    P: Parser<'a, I, O, E> + Sync,
+    O: Send,
+{
+    let results = chunks
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|chunk| parse_chunk(parser, &input, chunk))
+        .collect::<Vec<_>>();
+
+    let mut outputs = Some(Vec::with_capacity(results.len()));
+    let mut errs = Vec::new();
+    for (output, chunk_errs) in results {
+        outputs = outputs.zip(output).map(|(mut outputs, output)| {
+            outputs.push(output);
+            outputs
+        });
+        errs.extend(chunk_errs);
+    }
+    ParseResult::new(outputs, errs)
+}
+
+fn parse_chunk<'a, I, O, E, P>(
+    parser: &P,
+    input: &I,
+    chunk: Range<usize>,
+) -> (Option<O>, Vec<E::Error>)
+where
+    I: SliceInput<'a, Offset = usize, Slice = I>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    P: Parser<'a, I, O, E>,
+{
+    // Cutting the input off at the end of the chunk stops the parser there, while starting at the beginning of the
+    // chunk (rather than cutting it off there too) keeps offsets relative to the whole input. Slicing also checks
+    // that both ends of the chunk are valid offsets.
+    let _ = input.slice(chunk.clone());
+    let mut own = InputOwn::<I, E>::new(input.slice(0..chunk.end));
+    let mut inp = own.as_ref_at(chunk.start);
+    let res = parser.then_ignore(end()).go::<Emit>(&mut inp);
+    let alt = inp.errors.alt.take();
+    let mut errs = own.into_errs();
+    let out = match res {
+        Ok(out) => Some(out),
+        Err(()) => {
+            errs.push(alt.expect("error but no alt?").err);
+            None
+        }
+    };
+    (out, errs)
+}
@@ -0,0 +1,595 @@
+//! Importing grammars written for [pest](https://pest.rs), to ease migrating existing parsers to chumsky.
+//!
+//! A [`Grammar`] is loaded from the source of a `.pest` file at runtime, and can then create a parser for any of its
+//! rules. Like pest itself, these parsers produce a tree of [`Pair`]s, one for each (non-silent) rule that matched,
+//! so code that walks the output of a pest parser can be ported without rewriting the grammar. Because the parsers
+//! are built from chumsky's combinators, they can be given any error type, and can be combined with hand-written
+//! parsers (to add error recovery, for example) as the grammar is ported.
+//!
+//! The supported subset of pest's grammar syntax is:
+//!
+//! - Rules, with the `_` (silent), `@` (atomic), `$` (compound-atomic), and `!` (non-atomic) modifiers.
+//!
+//! - Strings (`"..."`), case-insensitive strings (`^"..."`), and character ranges (`'a'..'z'`).
+//!
+//! - Sequences (`a ~ b`), ordered choices (`a | b`), repetitions (`a*`, `a+`, `a{n}`, `a{n,}`, `a{,m}`, `a{n,m}`),
+//!   optional parts (`a?`), and lookahead (`&a`, `!a`).
+//!
+//! - The `WHITESPACE` and `COMMENT` rules, which are implicitly allowed between the parts of sequences and
+//!   repetitions of non-atomic rules.
+//!
+//! - The built-in rules `ANY`, `SOI`, `EOI`, `NEWLINE`, `ASCII`, and the `ASCII_*` character classes.
+//!
+//! The stack operations (such as `PUSH` and `POP`), node tags, and Unicode property rules are not supported, and
+//! are reported as errors when the grammar is loaded.
+//!
+//! *This module requires the `pest` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, pest::Grammar};
+//! let grammar = Grammar::new(r#"
+//!     WHITESPACE = _{ " " }
+//!     list = { "[" ~ (number ~ ("," ~ number)*)? ~ "]" }
+//!     number = @{ "-"? ~ ASCII_DIGIT+ }
+//! "#).unwrap();
+//!
+//! let list = grammar.parser::<extra::Err<Rich<char>>>("list").unwrap();
+//! let input = "[1, -23 ,4]";
+//! let pairs = list.parse(input).into_result().unwrap();
+//!
+//! assert_eq!(pairs.len(), 1);
+//! assert_eq!(pairs[0].rule, "list");
+//! let numbers = pairs[0].inner.iter().map(|pair| pair.as_str(input)).collect::<Vec<_>>();
+//! assert_eq!(numbers, ["1", "-23", "4"]);
+//!
+//! assert!(list.parse("[1, 2,]").has_errors());
+//! ```
+
+use super::*;
+use crate::recursive::{Indirect, Recursive};
+use alloc::string::{String, ToString};
+
+/// A node in the tree produced by the parsers of a [`Grammar`], as in pest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pair<'a> {
+    /// The name of the rule that matched.
+    pub rule: &'a str,
+    /// The span of the input that the rule matched.
+    pub span: SimpleSpan,
+    /// The pairs of the (non-silent) rules that matched within this rule.
+    pub inner: Vec<Pair<'a>>,
+}
+
+impl<'a> Pair<'a> {
+    /// Get the text that the rule matched, given the input that was parsed.
+    pub fn as_str<'i>(&self, input: &'i str) -> &'i str {
+        &input[self.span.start..self.span.end]
+    }
+}
+
+/// A grammar loaded from the source of a `.pest` file. See the [module documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub struct Grammar {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    name: String,
+    modifier: Modifier,
+    expr: Expr,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Modifier {
+    Normal,
+    Silent,
+    Atomic,
+    CompoundAtomic,
+    NonAtomic,
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Str(String),
+    Insensitive(String),
+    Range(char, char),
+    // An identifier, before it is resolved to a rule or a built-in rule
+    Ident(String, SimpleSpan),
+    Rule(usize),
+    Builtin(Builtin),
+    Seq(Vec<Expr>),
+    Choice(Vec<Expr>),
+    Optional(Box<Expr>),
+    Repeat(Box<Expr>, usize, Option<usize>),
+    PosPred(Box<Expr>),
+    NegPred(Box<Expr>),
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Builtin {
+    Any,
+    Soi,
+    Eoi,
+    Newline,
+    Class(fn(&char) -> bool),
+}
+
+impl Builtin {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ANY" => Self::Any,
+            "SOI" => Self::Soi,
+            "EOI" => Self::Eoi,
+            "NEWLINE" => Self::Newline,
+            "ASCII" => Self::Class(char::is_ascii),
+            "ASCII_DIGIT" => Self::Class(char::is_ascii_digit),
+            "ASCII_NONZERO_DIGIT" => Self::Class(|c| matches!(c, '1'..='9')),
+            "ASCII_BIN_DIGIT" => Self::Class(|c| matches!(c, '0'..='1')),
+            "ASCII_OCT_DIGIT" => Self::Class(|c| matches!(c, '0'..='7')),
+            "ASCII_HEX_DIGIT" => Self::Class(char::is_ascii_hexdigit),
+            "ASCII_ALPHA_LOWER" => Self::Class(char::is_ascii_lowercase),
+            "ASCII_ALPHA_UPPER" => Self::Class(char::is_ascii_uppercase),
+            "ASCII_ALPHA" => Self::Class(char::is_ascii_alphabetic),
+            "ASCII_ALPHANUMERIC" => Self::Class(char::is_ascii_alphanumeric),
+            _ => return None,
+        })
+    }
+}
+
+// Whether implicit whitespace is allowed, and whether rules produce pairs
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Atomicity {
+    NonAtomic,
+    // Within a compound-atomic rule
+    Atomic,
+    // Within an atomic rule, where inner rules are silent
+    Silent,
+}
+
+const ATOMICITIES: [Atomicity; 3] = [Atomicity::NonAtomic, Atomicity::Atomic, Atomicity::Silent];
+
+type PairsParser<'a, E> = Boxed<'a, 'a, &'a str, Vec<Pair<'a>>, E>;
+
+fn concat<'a>((mut a, b): (Vec<Pair<'a>>, Vec<Pair<'a>>)) -> Vec<Pair<'a>> {
+    a.extend(b);
+    a
+}
+
+fn flatten<'a>(pairs: Vec<Vec<Pair<'a>>>) -> Vec<Pair<'a>> {
+    pairs.into_iter().flatten().collect()
+}
+
+// The syntax of `.pest` files
+fn syntax<'a>(
+) -> impl Parser<'a, &'a str, Vec<(String, SimpleSpan, Modifier, Expr)>, extra::Err<Rich<'a, char>>>
+{
+    let line_comment = just("//")
+        .then(any().and_is(text::newline().not()).repeated())
+        .ignored();
+    let block_comment = just("/*")
+        .then(any().and_is(just("*/").not()).repeated())
+        .then(just("*/"))
+        .ignored();
+    let ws = Parser::boxed(
+        any()
+            .filter(|c: &char| c.is_whitespace())
+            .ignored()
+            .or(line_comment)
+            .or(block_comment)
+            .repeated(),
+    );
+    let sym = |s| just(s).padded_by(ws.clone());
+
+    let hex = |n| {
+        any()
+            .filter(char::is_ascii_hexdigit)
+            .repeated()
+            .at_least(1)
+            .at_most(n)
+            .slice()
+            .try_map(|digits, span| {
+                u32::from_str_radix(digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| Rich::custom(span, "invalid character code"))
+            })
+    };
+    let escape = Parser::boxed(just('\\').ignore_then(choice((
+        one_of("\"'\\"),
+        just('n').to('\n'),
+        just('r').to('\r'),
+        just('t').to('\t'),
+        just('0').to('\0'),
+        just('x').ignore_then(hex(2)),
+        just('u').ignore_then(hex(6).delimited_by(just('{'), just('}'))),
+    ))));
+    let string = Parser::boxed(
+        none_of("\"\\")
+            .or(escape.clone())
+            .repeated()
+            .collect::<String>()
+            .delimited_by(just('"'), just('"')),
+    );
+    let character = Parser::boxed(
+        none_of("'\\")
+            .or(escape)
+            .delimited_by(just('\''), just('\'')),
+    );
+
+    let expr = recursive(|expr| {
+        let bound = text::int(10)
+            .from_str::<usize>()
+            .unwrapped()
+            .padded_by(ws.clone());
+        let bounds = bound
+            .clone()
+            .or_not()
+            .then(sym(",").ignore_then(bound.or_not()).or_not())
+            .delimited_by(sym("{"), sym("}"))
+            .try_map(|bounds, span| match bounds {
+                (Some(n), None) => Ok((n, Some(n))),
+                (min, Some(max)) if min.is_some() || max.is_some() => Ok((min.unwrap_or(0), max)),
+                _ => Err(Rich::custom(span, "expected a number of repetitions")),
+            });
+
+        let atom = choice((
+            expr.delimited_by(sym("("), sym(")")),
+            just('^').ignore_then(string.clone()).map(Expr::Insensitive),
+            string.clone().map(Expr::Str),
+            character
+                .clone()
+                .then_ignore(just(".."))
+                .then(character.clone())
+                .map(|(from, to)| Expr::Range(from, to)),
+            text::ident().map_with_span(|name: &str, span| Expr::Ident(name.to_string(), span)),
+        ))
+        .padded_by(ws.clone());
+
+        let postfix = choice((
+            sym("?").to(None),
+            sym("*").to(Some((0, None))),
+            sym("+").to(Some((1, None))),
+            bounds.map(Some),
+        ));
+        let term = sym("&")
+            .to(true)
+            .or(sym("!").to(false))
+            .repeated()
+            .collect::<Vec<_>>()
+            .then(atom)
+            .then(postfix.repeated().collect::<Vec<_>>())
+            .map(|((prefixes, atom), postfixes)| {
+                let expr = postfixes
+                    .into_iter()
+                    .fold(atom, |expr, postfix| match postfix {
+                        None => Expr::Optional(Box::new(expr)),
+                        Some((min, max)) => Expr::Repeat(Box::new(expr), min, max),
+                    });
+                prefixes.into_iter().rev().fold(expr, |expr, positive| {
+                    if positive {
+                        Expr::PosPred(Box::new(expr))
+                    } else {
+                        Expr::NegPred(Box::new(expr))
+                    }
+                })
+            });
+
+        let seq = term
+            .separated_by(sym("~"))
+            .at_least(1)
+            .collect::<Vec<_>>()
+            .map(|mut terms| {
+                if terms.len() == 1 {
+                    terms.remove(0)
+                } else {
+                    Expr::Seq(terms)
+                }
+            });
+        sym("|")
+            .or_not()
+            .ignore_then(seq.separated_by(sym("|")).at_least(1).collect::<Vec<_>>())
+            .map(|mut seqs| {
+                if seqs.len() == 1 {
+                    seqs.remove(0)
+                } else {
+                    Expr::Choice(seqs)
+                }
+            })
+    });
+
+    let modifier = choice((
+        just('_').to(Modifier::Silent),
+        just('@').to(Modifier::Atomic),
+        just('$').to(Modifier::CompoundAtomic),
+        just('!').to(Modifier::NonAtomic),
+    ))
+    .or_not()
+    .map(|modifier| modifier.unwrap_or(Modifier::Normal))
+    .padded_by(ws.clone());
+
+    let rule = text::ident()
+        .map_with_span(|name: &str, span| (name.to_string(), span))
+        .padded_by(ws.clone())
+        .then_ignore(sym("="))
+        .then(modifier)
+        .then(expr.delimited_by(sym("{"), sym("}")))
+        .map(|(((name, span), modifier), expr)| (name, span, modifier, expr));
+
+    ws.ignore_then(rule.repeated().collect())
+}
+
+impl Expr {
+    // Resolve identifiers to the rules that they refer to
+    fn resolve<'a>(&mut self, names: &[String], errors: &mut Vec<Rich<'a, char>>) {
+        match self {
+            Expr::Ident(name, span) => {
+                if let Some(rule) = names.iter().position(|other| other == name) {
+                    *self = Expr::Rule(rule);
+                } else if let Some(builtin) = Builtin::from_name(name) {
+                    *self = Expr::Builtin(builtin);
+                } else {
+                    errors.push(Rich::custom(
+                        *span,
+                        alloc::format!("unknown or unsupported rule `{}`", name),
+                    ));
+                }
+            }
+            Expr::Seq(exprs) | Expr::Choice(exprs) => {
+                for expr in exprs {
+                    expr.resolve(names, errors);
+                }
+            }
+            Expr::Optional(expr)
+            | Expr::Repeat(expr, _, _)
+            | Expr::PosPred(expr)
+            | Expr::NegPred(expr) => expr.resolve(names, errors),
+            Expr::Str(_)
+            | Expr::Insensitive(_)
+            | Expr::Range(_, _)
+            | Expr::Rule(_)
+            | Expr::Builtin(_) => {}
+        }
+    }
+}
+
+impl Grammar {
+    /// Load a grammar from the source of a `.pest` file, returning any syntax errors, references to unknown rules,
+    /// or rules that are defined more than once.
+    pub fn new(src: &str) -> Result<Self, Vec<Rich<'_, char>>> {
+        let defs = syntax().parse(src).into_result()?;
+        let names = defs
+            .iter()
+            .map(|(name, _, _, _)| name.clone())
+            .collect::<Vec<_>>();
+        let mut errors = Vec::new();
+        let mut rules = Vec::new();
+        for (i, (name, span, modifier, mut expr)) in defs.into_iter().enumerate() {
+            if names[..i].contains(&name) {
+                errors.push(Rich::custom(
+                    span,
+                    alloc::format!("the rule `{}` is defined more than once", name),
+                ));
+            }
+            expr.resolve(&names, &mut errors);
+            rules.push(Rule {
+                name,
+                modifier,
+                expr,
+            });
+        }
+        if errors.is_empty() {
+            Ok(Self { rules })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Get the names of the rules in this grammar, in the order that they were defined.
+    pub fn rules(&self) -> impl Iterator<Item = &str> + '_ {
+        self.rules.iter().map(|rule| rule.name.as_str())
+    }
+
+    /// Create a parser for the rule with the given name, producing the pairs of the rules that match. Returns `None`
+    /// if the grammar has no such rule.
+    ///
+    /// As in pest, the parser doesn't need to consume all of its input: add [`end`] after it if it should.
+    pub fn parser<'a, E>(&'a self, rule: &str) -> Option<PairsParser<'a, E>>
+    where
+        E: ParserExtra<'a, &'a str> + 'a,
+    {
+        let start = self.rules.iter().position(|other| other.name == rule)?;
+        // Each rule behaves differently depending on the atomicity of the rule that uses it
+        let mut decls = self
+            .rules
+            .iter()
+            .map(|_| ATOMICITIES.map(|_| Recursive::declare()))
+            .collect::<Vec<[Recursive<Indirect<'a, 'a, &'a str, Vec<Pair<'a>>, E>>; 3]>>();
+        let builder = Builder {
+            grammar: self,
+            rules: decls.clone(),
+        };
+        for (i, decls) in decls.iter_mut().enumerate() {
+            for (decl, atomicity) in decls.iter_mut().zip(ATOMICITIES) {
+                decl.define(builder.rule(i, atomicity));
+            }
+        }
+        Some(builder.call(start, Atomicity::NonAtomic))
+    }
+}
+
+struct Builder<'a, E: ParserExtra<'a, &'a str>> {
+    grammar: &'a Grammar,
+    rules: Vec<[Recursive<Indirect<'a, 'a, &'a str, Vec<Pair<'a>>, E>>; 3]>,
+}
+
+impl<'a, E> Builder<'a, E>
+where
+    E: ParserExtra<'a, &'a str> + 'a,
+{
+    fn call(&self, rule: usize, atomicity: Atomicity) -> PairsParser<'a, E> {
+        let index = ATOMICITIES.iter().position(|a| *a == atomicity).unwrap();
+        Parser::boxed(self.rules[rule][index].clone())
+    }
+
+    // Create the parser for a rule when it's used with the given atomicity
+    fn rule(&self, rule: usize, atomicity: Atomicity) -> PairsParser<'a, E> {
+        let Rule {
+            name,
+            modifier,
+            expr,
+        } = &self.grammar.rules[rule];
+        let inner = match modifier {
+            Modifier::Atomic => Atomicity::Silent,
+            Modifier::CompoundAtomic => Atomicity::Atomic,
+            Modifier::NonAtomic => Atomicity::NonAtomic,
+            Modifier::Normal | Modifier::Silent => atomicity,
+        };
+        let body = self.expr(expr, inner);
+        if *modifier == Modifier::Silent || atomicity == Atomicity::Silent {
+            body
+        } else {
+            let name = name.as_str();
+            Parser::boxed(body.map_with_span(move |inner, span| {
+                vec![Pair {
+                    rule: name,
+                    span,
+                    inner,
+                }]
+            }))
+        }
+    }
+
+    // The implicit whitespace and comments between the parts of non-atomic rules
+    fn skip(&self) -> PairsParser<'a, E> {
+        let rules = self.grammar.rules.iter().enumerate();
+        let skip = rules
+            .filter(|(_, rule)| rule.name == "WHITESPACE" || rule.name == "COMMENT")
+            .map(|(i, _)| self.call(i, Atomicity::Atomic))
+            .reduce(|a, b| Parser::boxed(a.or(b)));
+        match skip {
+            Some(skip) => Parser::boxed(skip.repeated().collect().map(flatten)),
+            None => Parser::boxed(empty().to(Vec::new())),
+        }
+    }
+
+    fn expr(&self, expr: &'a Expr, atomicity: Atomicity) -> PairsParser<'a, E> {
+        match expr {
+            Expr::Str(s) => Parser::boxed(just(s.as_str()).to(Vec::new())),
+            Expr::Insensitive(s) => {
+                s.chars()
+                    .fold(Parser::boxed(empty().to(Vec::new())), |p, c| {
+                        Parser::boxed(p.then_ignore(
+                            any().filter(move |other: &char| {
+                                other.to_lowercase().eq(c.to_lowercase())
+                            }),
+                        ))
+                    })
+            }
+            Expr::Range(from, to) => {
+                let (from, to) = (*from, *to);
+                Parser::boxed(
+                    any()
+                        .filter(move |c: &char| (from..=to).contains(c))
+                        .to(Vec::new()),
+                )
+            }
+            Expr::Ident(_, _) => {
+                unreachable!("identifiers are resolved when the grammar is loaded")
+            }
+            Expr::Rule(rule) => self.call(*rule, atomicity),
+            Expr::Builtin(builtin) => self.builtin(*builtin, atomicity),
+            Expr::Seq(exprs) => {
+                let skip = (atomicity == Atomicity::NonAtomic).then(|| self.skip());
+                let mut exprs = exprs.iter().map(|expr| self.expr(expr, atomicity));
+                let first = exprs.next().expect("sequence with no parts");
+                exprs.fold(first, |seq, expr| match &skip {
+                    Some(skip) => {
+                        Parser::boxed(seq.then(skip.clone()).map(concat).then(expr).map(concat))
+                    }
+                    None => Parser::boxed(seq.then(expr).map(concat)),
+                })
+            }
+            Expr::Choice(exprs) => exprs
+                .iter()
+                .map(|expr| self.expr(expr, atomicity))
+                .reduce(|a, b| Parser::boxed(a.or(b)))
+                .expect("choice with no alternatives"),
+            Expr::Optional(expr) => Parser::boxed(
+                self.expr(expr, atomicity)
+                    .or_not()
+                    .map(Option::unwrap_or_default),
+            ),
+            Expr::Repeat(expr, min, max) => {
+                self.repeat(self.expr(expr, atomicity), *min, *max, atomicity)
+            }
+            Expr::PosPred(expr) => {
+                Parser::boxed(self.expr(expr, atomicity).rewind().to(Vec::new()))
+            }
+            Expr::NegPred(expr) => Parser::boxed(self.expr(expr, atomicity).not().to(Vec::new())),
+        }
+    }
+
+    fn repeat(
+        &self,
+        item: PairsParser<'a, E>,
+        min: usize,
+        max: Option<usize>,
+        atomicity: Atomicity,
+    ) -> PairsParser<'a, E> {
+        if max == Some(0) {
+            return Parser::boxed(empty().to(Vec::new()));
+        }
+        if atomicity != Atomicity::NonAtomic {
+            let items = item.repeated().at_least(min);
+            let items = match max {
+                Some(max) => items.at_most(max),
+                None => items,
+            };
+            return Parser::boxed(items.collect().map(flatten));
+        }
+        // Implicit whitespace is allowed between items, but not before the first or after the last
+        let rest = self
+            .skip()
+            .then(item.clone())
+            .map(concat)
+            .repeated()
+            .at_least(min.saturating_sub(1));
+        let rest = match max {
+            Some(max) => rest.at_most(max - 1),
+            None => rest,
+        };
+        let items = item.then(rest.collect().map(flatten)).map(concat);
+        if min == 0 {
+            Parser::boxed(items.or_not().map(Option::unwrap_or_default))
+        } else {
+            Parser::boxed(items)
+        }
+    }
+
+    fn builtin(&self, builtin: Builtin, atomicity: Atomicity) -> PairsParser<'a, E> {
+        match builtin {
+            Builtin::Any => Parser::boxed(any().to(Vec::new())),
+            Builtin::Soi => Parser::boxed(empty().try_map(|(), span: SimpleSpan| {
+                if span.start == 0 {
+                    Ok(Vec::new())
+                } else {
+                    Err(Error::expected_found(core::iter::empty(), None, span))
+                }
+            })),
+            // As in pest, reaching the end of the input produces an `EOI` pair
+            Builtin::Eoi if atomicity == Atomicity::Silent => Parser::boxed(end().to(Vec::new())),
+            Builtin::Eoi => Parser::boxed(end().map_with_span(|(), span| {
+                vec![Pair {
+                    rule: "EOI",
+                    span,
+                    inner: Vec::new(),
+                }]
+            })),
+            Builtin::Newline => Parser::boxed(text::newline().to(Vec::new())),
+            Builtin::Class(class) => Parser::boxed(any().filter(class).to(Vec::new())),
+        }
+    }
+}
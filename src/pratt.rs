@@ -191,7 +191,9 @@ impl<E, AtomParser, Expr, OpParser, Op> Pratt<E, AtomParser, Expr, OpParser, Op>
                 Err(_) => return Ok(left),
             };
 
-            let right = self.pratt_parse::<M, _>(inp, Some(prec.strength_right()))?;
+            // Long chains of right-associative operators recurse once per operator
+            let right =
+                recursive::recurse(|| self.pratt_parse::<M, _>(inp, Some(prec.strength_right())))?;
             left = M::combine(left, right, |l: Expr, r: Expr| op.build_expression(l, r));
         }
     }
@@ -213,10 +215,15 @@ where
         self.pratt_parse::<M, _>(inp, None)
     }
 
+    graph_node!(parser_atom: Expr, parser_op: Op);
+
+    // Operators are not generated, but an atom alone is always an expression
+    gen_input!(parser_atom: Expr);
+
     go_extra!(Expr);
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
 
@@ -303,8 +310,8 @@ mod tests {
     fn missing_first_expression() {
         assert_eq!(
             parse("").into_result(),
-            Err(vec![<Rich<_> as Error<&str>>::expected_found(
-                Some(Some('0'.into())),
+            Err(vec![<Rich<_> as Error<&str>>::expected_ranges_found(
+                [('0'.into(), '9'.into())],
                 None,
                 (0..0).into()
             )])
@@ -315,10 +322,10 @@ mod tests {
     fn missing_later_expression() {
         assert_eq!(
             parse("1+").into_result(),
-            Err(vec![<Rich<_> as Error<&str>>::expected_found(
-                Some(Some('0'.into())),
-                None, // TODO: Should be Some('+')?
-                (1..2).into()
+            Err(vec![<Rich<_> as Error<&str>>::expected_ranges_found(
+                [('0'.into(), '9'.into())],
+                None,
+                (2..2).into()
             )]),
         );
     }
@@ -327,11 +334,10 @@ mod tests {
     fn invalid_first_expression() {
         assert_eq!(
             parse("?").into_result(),
-            Err(vec![<Rich<_> as Error<&str>>::expected_found(
-                Some(Some('0'.into())),
-                // Should this not be: Some('?')?
-                None,
-                (0..1).into(),
+            Err(vec![<Rich<_> as Error<&str>>::expected_ranges_found(
+                [('0'.into(), '9'.into())],
+                Some('?'.into()),
+                (0..1).into()
             )]),
         );
     }
@@ -340,11 +346,10 @@ mod tests {
     fn invalid_later_expression() {
         assert_eq!(
             parse("1+?").into_result(),
-            Err(vec![<Rich<_> as Error<&str>>::expected_found(
-                Some(Some('0'.into())),
-                // Should this not be: Some('?')?
-                None,
-                (2..3).into(),
+            Err(vec![<Rich<_> as Error<&str>>::expected_ranges_found(
+                [('0'.into(), '9'.into())],
+                Some('?'.into()),
+                (2..3).into()
             )]),
         );
     }
@@ -358,9 +363,10 @@ mod tests {
                     Some('+'.into()),
                     Some('-'.into()),
                     Some('*'.into()),
-                    Some('/'.into())
+                    Some('/'.into()),
+                    None,
                 ],
-                None, // TODO: Should be Some('?')?
+                Some('?'.into()),
                 (1..2).into(),
             )]),
         );
@@ -378,4 +384,16 @@ mod tests {
             Ok("(((((1 + (2 * (3 / (4 * 5)))) - (6 * 7)) + 8) - 9) + 10)".to_string()),
         );
     }
+
+    #[test]
+    #[cfg(feature = "spill-stack")]
+    fn deep_right_associative_chain() {
+        let atom = just::<_, &str, extra::Default>('1').map(|_| Expr::Literal(1));
+        let operator = just('*').to(Operator::Mul);
+        let chain = "1*".repeat(100_000) + "1";
+        assert_eq!(
+            atom.pratt(operator).ignored().parse(&chain).into_result(),
+            Ok(()),
+        );
+    }
 }
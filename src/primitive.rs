@@ -22,7 +22,26 @@ pub struct End<I, E>(EmptyPhantom<(E, I)>);
 
 /// A parser that accepts only the end of input.
 ///
+/// If any input remains, the resulting error will report that the end of input was expected. To require that a parser
+/// consumes all input, see [`Parser::then_end`].
+///
 /// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// let end = end::<_, extra::Err<Rich<char>>>();
+///
+/// assert_eq!(end.parse("").into_result(), Ok(()));
+/// assert_eq!(
+///     end.parse("!").into_errors()[0].to_string(),
+///     "found '!' expected end of input",
+/// );
+/// // Whitespace is not ignored unless explicitly permitted
+/// assert!(end.parse(" ").has_errors());
+/// assert_eq!(end.padded().parse(" ").into_result(), Ok(()));
+/// ```
 pub const fn end<'a, I: Input<'a>, E: ParserExtra<'a, I>>() -> End<I, E> {
     End(EmptyPhantom::new())
 }
@@ -30,7 +49,7 @@ pub const fn end<'a, I: Input<'a>, E: ParserExtra<'a, I>>() -> End<I, E> {
 impl<I, E> Copy for End<I, E> {}
 impl<I, E> Clone for End<I, E> {
     fn clone(&self) -> Self {
-        End(EmptyPhantom::new())
+        *self
     }
 }
 
@@ -45,21 +64,41 @@ where
         match inp.next_maybe_inner() {
             (_, None) => Ok(M::bind(|| ())),
             (at, Some(tok)) => {
-                inp.add_alt(at, None, Some(tok.into()), inp.span_since(before));
+                inp.add_alt(at, Some(None), Some(tok.into()), inp.span_since(before));
                 Err(())
             }
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, _gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(Some(()))
+    }
+
     go_extra!(());
 }
 
 /// See [`empty`].
 pub struct Empty<I, E>(EmptyPhantom<(E, I)>);
 
-/// A parser that parses no inputs.
+/// A parser that parses no inputs. It always succeeds, and never consumes any input.
 ///
 /// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// // An optional sign, represented as a multiplier
+/// let sign = just::<_, _, extra::Err<Simple<char>>>('-').to(-1)
+///     .or(empty().to(1));
+///
+/// assert_eq!(sign.then(text::int(10)).parse("-42").into_result(), Ok((-1, "42")));
+/// assert_eq!(sign.then(text::int(10)).parse("42").into_result(), Ok((1, "42")));
+/// ```
 pub const fn empty<I, E>() -> Empty<I, E> {
     Empty(EmptyPhantom::new())
 }
@@ -67,7 +106,7 @@ pub const fn empty<I, E>() -> Empty<I, E> {
 impl<I, E> Copy for Empty<I, E> {}
 impl<I, E> Clone for Empty<I, E> {
     fn clone(&self) -> Self {
-        Empty(EmptyPhantom::new())
+        *self
     }
 }
 
@@ -81,6 +120,14 @@ where
         Ok(M::bind(|| ()))
     }
 
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, _gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        Ok(Some(()))
+    }
+
     go_extra!(());
 }
 
@@ -168,6 +215,24 @@ where
         Self::go_cfg::<M>(self, inp, JustCfg::default())
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        self.seq
+            .seq_iter()
+            .next()
+            .map(|tok| vec![T::to_maybe_ref(tok)])
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<T>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.tokens
+            .extend(self.seq.seq_iter().map(|tok| tok.borrow().clone()));
+        Ok(Some(self.seq.clone()))
+    }
+
     go_extra!(T);
 }
 
@@ -245,6 +310,23 @@ impl<T: Clone, I, E> Clone for OneOf<T, I, E> {
 /// assert_eq!(digits.parse("48791").into_result(), Ok("48791".to_string()));
 /// assert!(digits.parse("421!53").has_errors());
 /// ```
+///
+/// Ranges, arrays of ranges, and the named classes in [`text::ascii`] are also accepted. Errors
+/// produced by these describe the expected input as ranges rather than listing every possible token.
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// let hex = one_of::<_, _, extra::Err<Rich<char>>>(['0'..='9', 'a'..='f', 'A'..='F'])
+///     .repeated()
+///     .at_least(1)
+///     .collect::<String>();
+///
+/// assert_eq!(hex.parse("DEADbeef").into_result(), Ok("DEADbeef".to_string()));
+/// assert_eq!(
+///     hex.parse("g").into_errors()[0].to_string(),
+///     "found 'g' expected '0'..'9', 'a'..'f', or 'A'..'F'",
+/// );
+/// ```
 pub const fn one_of<'a, T, I, E>(seq: T) -> OneOf<T, I, E>
 where
     I: ValueInput<'a>,
@@ -272,6 +354,13 @@ where
             (_, Some(tok)) if self.seq.contains(tok.borrow()) => Ok(M::bind(|| tok)),
             (at, found) => {
                 let err_span = inp.span_since(before);
+                if let Some(ranges) = self.seq.seq_ranges() {
+                    inp.add_alt_err(
+                        at,
+                        E::Error::expected_ranges_found(ranges, found.map(|f| f.into()), err_span),
+                    );
+                    return Err(());
+                }
                 inp.add_alt(
                     at,
                     self.seq.seq_iter().map(|e| Some(T::to_maybe_ref(e))),
@@ -283,6 +372,33 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        // Large sets (such as wide ranges) would make checking membership slower than just running the parser
+        let tokens = self
+            .seq
+            .seq_iter()
+            .take(MAX_FIRST_TOKENS + 1)
+            .map(T::to_maybe_ref)
+            .collect::<Vec<_>>();
+        (tokens.len() <= MAX_FIRST_TOKENS).then_some(tokens)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<I::Token>, ()>
+    where
+        I::Token: Clone,
+    {
+        let tok = gen
+            // Wide ranges are common, so only pick from the start of the sequence
+            .pick(self.seq.seq_iter().take(256))
+            .ok_or(())?
+            .borrow()
+            .clone();
+        gen.tokens.push(tok.clone());
+        Ok(Some(tok))
+    }
+
     go_extra!(I::Token);
 }
 
@@ -352,6 +468,16 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<I::Token>, ()>
+    where
+        I::Token: Clone,
+    {
+        let tok = gen.token_where(|tok| !self.seq.contains(tok)).ok_or(())?;
+        gen.tokens.push(tok.clone());
+        Ok(Some(tok))
+    }
+
     go_extra!(I::Token);
 }
 
@@ -543,9 +669,7 @@ pub struct Any<I, E> {
 impl<I, E> Copy for Any<I, E> {}
 impl<I, E> Clone for Any<I, E> {
     fn clone(&self) -> Self {
-        Self {
-            phantom: EmptyPhantom::new(),
-        }
+        *self
     }
 }
 
@@ -567,6 +691,16 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<I::Token>, ()>
+    where
+        I::Token: Clone,
+    {
+        let tok = gen.any_token().ok_or(())?;
+        gen.tokens.push(tok.clone());
+        Ok(Some(tok))
+    }
+
     go_extra!(I::Token);
 }
 
@@ -591,6 +725,43 @@ pub const fn any<'a, I: Input<'a>, E: ParserExtra<'a, I>>() -> Any<I, E> {
     }
 }
 
+/// A parser that accepts any single input, provided that the given pattern does *not* match at the same position.
+///
+/// This is shorthand for `any().and_is(pattern.not())` and is useful for 'anything except X' patterns, such as the
+/// bodies of string literals or comments, where the terminator may be more than a single token. If you only need to
+/// exclude a fixed set of tokens, [`none_of`] is more efficient.
+///
+/// The output type of this parser is `I`, the input that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// // A C-style block comment
+/// let comment = just::<_, _, extra::Err<Simple<char>>>("/*")
+///     .ignore_then(any_except(just("*/")).repeated().slice())
+///     .then_ignore(just("*/"));
+///
+/// assert_eq!(comment.parse("/* hello * world */").into_result(), Ok(" hello * world "));
+/// assert_eq!(comment.parse("/**/").into_result(), Ok(""));
+/// assert!(comment.parse("/* unterminated *").has_errors());
+/// ```
+pub const fn any_except<'a, A, OA, I, E>(pattern: A) -> AndIs<Any<I, E>, Not<A, OA>, ()>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+{
+    AndIs {
+        parser_a: any(),
+        parser_b: Not {
+            parser: pattern,
+            phantom: EmptyPhantom::new(),
+        },
+        phantom: EmptyPhantom::new(),
+    }
+}
+
 /// See [`map_ctx`].
 pub struct MapCtx<A, F> {
     pub(crate) parser: A,
@@ -620,6 +791,25 @@ where
         inp.with_ctx(&(self.mapper)(inp.ctx()), |inp| self.parser.go::<M>(inp))
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        let child = ParserSealed::<I, O, extra::Full<E::Error, E::State, Ctx>>::graph_node(
+            &self.parser,
+            graph,
+        );
+        graph.edge(id, child);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        ParserSealed::<I, O, extra::Full<E::Error, E::State, Ctx>>::gen_input(&self.parser, gen)
+    }
+
     go_extra!(O);
 }
 
@@ -660,9 +850,96 @@ where
     MapCtx { parser, mapper }
 }
 
+/// See [`ignore_with_ctx`].
+pub struct IgnoreWithCtx<I, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<I, E> Copy for IgnoreWithCtx<I, E> {}
+impl<I, E> Clone for IgnoreWithCtx<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A parser that accepts only the token or sequence of tokens given by the current context, such as one captured
+/// earlier by [`Parser::then_with_ctx`].
+///
+/// This is the way to match the same thing twice, like the delimiter of a heredoc or the name of an XML tag, which a
+/// context-free grammar can't express. It behaves like [`just`] with the context as its sequence, so the context must
+/// be a sequence of tokens (such as a `&str` or a `char` for a text input), and errors expect the tokens of the
+/// context.
+///
+/// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// // The body of a heredoc ends at the first line that is the same as its delimiter
+/// let body = any::<_, extra::Full<Rich<char>, (), &str>>()
+///     .and_is(just('\n').then(ignore_with_ctx()).not())
+///     .repeated()
+///     .slice()
+///     .then_ignore(just('\n'))
+///     .then_ignore(ignore_with_ctx());
+/// let heredoc = just::<_, _, extra::Err<Rich<char>>>("<<")
+///     .ignore_then(text::ident())
+///     .then_ignore(just('\n'))
+///     .then_with_ctx(body);
+///
+/// assert_eq!(heredoc.parse("<<END\nhello\nworld\nEND").into_result(), Ok("hello\nworld"));
+/// assert_eq!(heredoc.parse("<<EOF\nEND\nEOF").into_result(), Ok("END"));
+/// assert!(heredoc.parse("<<END\nhello\nEOF").has_errors());
+/// ```
+pub const fn ignore_with_ctx<'a, I, E>() -> IgnoreWithCtx<I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    E::Context: OrderedSeq<'a, I::Token>,
+{
+    IgnoreWithCtx {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, (), E> for IgnoreWithCtx<I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    E::Context: OrderedSeq<'a, I::Token>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        let seq = inp.ctx;
+        for next in seq.seq_iter() {
+            let before = inp.offset();
+            match inp.next_maybe_inner() {
+                (_, Some(tok)) if next.borrow() == tok.borrow() => {}
+                (at, found) => {
+                    inp.add_alt(
+                        at,
+                        Some(Some(E::Context::to_maybe_ref(next))),
+                        found.map(|f| f.into()),
+                        inp.span_since(before),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Ok(M::bind(|| ()))
+    }
+
+    go_extra!(());
+}
+
 /// See [`fn@todo`].
 pub struct Todo<I, O, E> {
     location: Location<'static>,
+    message: Option<&'static str>,
     #[allow(dead_code)]
     phantom: EmptyPhantom<(O, E, I)>,
 }
@@ -674,6 +951,28 @@ impl<I, O, E> Clone for Todo<I, O, E> {
     }
 }
 
+impl<I, O, E> Todo<I, O, E> {
+    /// Attach a message to this parser that will be displayed alongside its location if it is used, like
+    /// `todo!("...")`.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use chumsky::prelude::*;
+    /// let hex = just::<_, _, extra::Err<Simple<char>>>("0x")
+    ///     .ignore_then(todo::<_, &str, _>().with_message("hexadecimal literals"));
+    ///
+    /// // Panics, reporting both the location of the `todo()` and the message
+    /// hex.parse("0xd4");
+    /// ```
+    pub fn with_message(self, message: &'static str) -> Self {
+        Self {
+            message: Some(message),
+            ..self
+        }
+    }
+}
+
 /// A parser that can be used wherever you need to implement a parser later.
 ///
 /// This parser is analogous to the [`todo!`] and [`unimplemented!`] macros, but will produce a panic when used to
@@ -699,10 +998,13 @@ impl<I, O, E> Clone for Todo<I, O, E> {
 /// // Parsing hexidecimal numbers results in a panic because the parser is unimplemented
 /// int.parse("0xd4");
 /// ```
+///
+/// Like [`todo!`], a message describing the missing functionality can be attached with [`Todo::with_message`].
 #[track_caller]
 pub fn todo<'a, I: Input<'a>, O, E: ParserExtra<'a, I>>() -> Todo<I, O, E> {
     Todo {
         location: *Location::caller(),
+        message: None,
         phantom: EmptyPhantom::new(),
     }
 }
@@ -714,10 +1016,17 @@ where
 {
     #[inline]
     fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        todo!(
-            "Attempted to use an unimplemented parser at {}",
-            self.location
-        )
+        match self.message {
+            Some(message) => todo!(
+                "Attempted to use an unimplemented parser at {}: {}",
+                self.location,
+                message
+            ),
+            None => todo!(
+                "Attempted to use an unimplemented parser at {}",
+                self.location
+            ),
+        }
     }
 
     go_extra!(O);
@@ -726,7 +1035,7 @@ where
 /// See [`choice`].
 #[derive(Copy, Clone)]
 pub struct Choice<T> {
-    parsers: T,
+    pub(crate) parsers: T,
 }
 
 /// Parse using a tuple of many parsers, producing the output of the first to successfully parse.
@@ -779,6 +1088,160 @@ pub const fn choice<T>(parsers: T) -> Choice<T> {
     Choice { parsers }
 }
 
+/// Parse using a tuple or array of labelled parsers, producing the output of the first to successfully parse.
+///
+/// Each branch is a `(label, parser)` pair. This behaves like [`choice`], except that each branch is
+/// [labelled](Parser::labelled): if every branch fails before getting past its first token, the error reports the
+/// labels of the branches (i.e: 'expected statement or declaration') rather than every token that any of them could
+/// have begun with. Errors found further into a branch are reported as normal.
+///
+/// The result is a regular [`Choice`]. Note that branches skipped by [`Choice::predictive`] or
+/// [`Choice::byte_dispatch`] report the tokens they could have begun with, rather than their labels.
+///
+/// The output type of this parser is the output type of the inner parsers.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::RichPattern};
+/// let item = labelled_choice((
+///     ("statement", text::keyword::<_, _, _, extra::Err<Rich<char>>>("print").ignore_then(text::int(10).padded())),
+///     ("declaration", text::keyword("let").ignore_then(text::ident().padded())),
+/// ));
+///
+/// assert_eq!(item.parse("print 42").into_result(), Ok("42"));
+/// assert_eq!(item.parse("let x").into_result(), Ok("x"));
+///
+/// let errs = item.parse("42").into_errors();
+/// assert_eq!(
+///     errs[0].expected().collect::<Vec<_>>(),
+///     [&RichPattern::Label("statement"), &RichPattern::Label("declaration")],
+/// );
+/// assert_eq!(errs[0].to_string(), "found '4' expected statement, or declaration");
+///
+/// // Errors within a branch are unaffected by its label
+/// let errs = item.parse("let 42").into_errors();
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(4..5));
+/// ```
+#[cfg(feature = "label")]
+pub fn labelled_choice<T: LabelledBranches>(branches: T) -> Choice<T::Branches> {
+    Choice {
+        parsers: branches.into_branches(),
+    }
+}
+
+/// A collection of `(label, parser)` pairs that can be used as the branches of a [`labelled_choice`].
+///
+/// This trait is an implementation detail of [`labelled_choice`].
+#[cfg(feature = "label")]
+#[doc(hidden)]
+pub trait LabelledBranches {
+    type Branches;
+
+    fn into_branches(self) -> Self::Branches;
+}
+
+#[cfg(feature = "label")]
+impl<L, A, const N: usize> LabelledBranches for [(L, A); N] {
+    type Branches = [Labelled<A, L>; N];
+
+    fn into_branches(self) -> Self::Branches {
+        self.map(|(label, parser)| Labelled {
+            parser,
+            label,
+            is_context: false,
+        })
+    }
+}
+
+macro_rules! impl_labelled_branches_for_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_labelled_branches_for_tuple!($($X)*);
+        impl_labelled_branches_for_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)+) => {
+        #[allow(non_snake_case)]
+        #[cfg(feature = "label")]
+        impl<L, $($X),*> LabelledBranches for ($((L, $X),)*) {
+            type Branches = ($(Labelled<$X, L>,)*);
+
+            fn into_branches(self) -> Self::Branches {
+                let ($($X,)*) = self;
+                ($(Labelled {
+                    parser: $X.1,
+                    label: $X.0,
+                    is_context: false,
+                },)*)
+            }
+        }
+    };
+}
+
+impl_labelled_branches_for_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_ M_ N_ O_ P_ Q_ R_ S_ T_ U_ V_ W_ X_ Y_ Z_);
+
+macro_rules! impl_choice_branches_for_tuple {
+    ($($X:ident)+) => {
+        #[allow(unused_assignments, non_snake_case)]
+        #[cfg(feature = "alloc")]
+        impl<'a, I, E, $($X),*, O> ChoiceBranches<'a, I, O, E> for ($($X,)*)
+        where
+            I: Input<'a>,
+            E: ParserExtra<'a, I>,
+            $($X: Parser<'a, I, O, E>),*
+        {
+            fn branch_first_tokens(&self) -> Vec<Option<Vec<MaybeRef<'a, I::Token>>>> {
+                let ($($X,)*) = self;
+                vec![$(ParserSealed::<I, O, E>::first_tokens($X)),*]
+            }
+
+            fn branch_graph_nodes(&self, graph: &mut graph::Graph, parent: graph::NodeId) {
+                let ($($X,)*) = self;
+                $(
+                    let child = ParserSealed::<I, O, E>::graph_node($X, graph);
+                    graph.edge(parent, child);
+                )*
+            }
+
+            fn branch_count(&self) -> usize {
+                [$(stringify!($X)),*].len()
+            }
+
+            fn gen_branch(
+                &self,
+                branch: usize,
+                gen: &mut generate::Generator<I::Token>,
+            ) -> Result<Option<O>, ()>
+            where
+                I::Token: Clone,
+            {
+                let ($($X,)*) = self;
+                let mut i = 0;
+                $(
+                    if i == branch {
+                        return ParserSealed::<I, O, E>::gen_input($X, gen);
+                    }
+                    i += 1;
+                )*
+                unreachable!()
+            }
+
+            #[inline]
+            fn go_branch<M: Mode>(&self, branch: usize, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+                let ($($X,)*) = self;
+                let mut i = 0;
+                $(
+                    if i == branch {
+                        return $X.go::<M>(inp);
+                    }
+                    i += 1;
+                )*
+                unreachable!()
+            }
+        }
+    };
+}
+
 macro_rules! impl_choice_for_tuple {
     () => {};
     ($head:ident $($X:ident)*) => {
@@ -786,6 +1249,8 @@ macro_rules! impl_choice_for_tuple {
         impl_choice_for_tuple!(~ $head $($X)*);
     };
     (~ $Head:ident $($X:ident)+) => {
+        impl_choice_branches_for_tuple!($Head $($X)*);
+
         #[allow(unused_variables, non_snake_case)]
         impl<'a, I, E, $Head, $($X),*, O> ParserSealed<'a, I, O, E> for Choice<($Head, $($X,)*)>
         where
@@ -815,10 +1280,34 @@ macro_rules! impl_choice_for_tuple {
                 Err(())
             }
 
+            #[cfg(feature = "alloc")]
+            fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+                union_first_tokens(self.parsers.branch_first_tokens())
+            }
+
+            #[cfg(feature = "alloc")]
+            fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+                let id = graph.add::<Self>();
+                self.parsers.branch_graph_nodes(graph, id);
+                id
+            }
+
+            #[cfg(feature = "alloc")]
+            fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+            where
+                I::Token: Clone,
+            {
+                gen.choose(self.parsers.branch_count(), |gen, branch| {
+                    self.parsers.gen_branch(branch, gen)
+                })
+            }
+
             go_extra!(O);
         }
     };
     (~ $Head:ident) => {
+        impl_choice_branches_for_tuple!($Head);
+
         impl<'a, I, E, $Head, O> ParserSealed<'a, I, O, E> for Choice<($Head,)>
         where
             I: Input<'a>,
@@ -830,6 +1319,28 @@ macro_rules! impl_choice_for_tuple {
                 self.parsers.0.go::<M>(inp)
             }
 
+            #[cfg(feature = "alloc")]
+            fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+                self.parsers.0.first_tokens()
+            }
+
+            #[cfg(feature = "alloc")]
+            fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+                let id = graph.add::<Self>();
+                self.parsers.branch_graph_nodes(graph, id);
+                id
+            }
+
+            #[cfg(feature = "alloc")]
+            fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+            where
+                I::Token: Clone,
+            {
+                gen.choose(self.parsers.branch_count(), |gen, branch| {
+                    self.parsers.gen_branch(branch, gen)
+                })
+            }
+
             go_extra!(O);
         }
     };
@@ -854,10 +1365,7 @@ where
             let before = inp.save();
             match self.parsers.iter().find_map(|parser| {
                 inp.rewind(before);
-                match parser.go::<M>(inp) {
-                    Ok(out) => Some(out),
-                    Err(()) => None,
-                }
+                parser.go::<M>(inp).ok()
             }) {
                 Some(out) => Ok(out),
                 None => Err(()),
@@ -865,6 +1373,416 @@ where
         }
     }
 
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        union_first_tokens(self.parsers.branch_first_tokens())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        self.parsers.branch_graph_nodes(graph, id);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.choose(self.parsers.branch_count(), |gen, branch| {
+            self.parsers.gen_branch(branch, gen)
+        })
+    }
+
+    go_extra!(O);
+}
+
+// The largest set of first tokens that a parser will report. See `ParserSealed::first_tokens`.
+#[cfg(feature = "alloc")]
+const MAX_FIRST_TOKENS: usize = 256;
+
+// The union of the first tokens of several parsers, which is unknown if any of them is unknown
+#[cfg(feature = "alloc")]
+fn union_first_tokens<'a, T>(
+    sets: impl IntoIterator<Item = Option<Vec<MaybeRef<'a, T>>>>,
+) -> Option<Vec<MaybeRef<'a, T>>> {
+    sets.into_iter().try_fold(Vec::new(), |mut all, set| {
+        all.extend(set?);
+        Some(all)
+    })
+}
+
+/// A collection of parsers that can be used as the branches of a [`choice`].
+///
+/// This trait is an implementation detail of [`Choice::predictive`] and [`Choice::byte_dispatch`].
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub trait ChoiceBranches<'a, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    fn branch_first_tokens(&self) -> Vec<Option<Vec<MaybeRef<'a, I::Token>>>>;
+
+    fn branch_graph_nodes(&self, graph: &mut graph::Graph, parent: graph::NodeId);
+
+    fn branch_count(&self) -> usize;
+
+    fn gen_branch(
+        &self,
+        branch: usize,
+        gen: &mut generate::Generator<I::Token>,
+    ) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone;
+
+    fn go_branch<M: Mode>(&self, branch: usize, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>;
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, A, I, O, E, const N: usize> ChoiceBranches<'a, I, O, E> for [A; N]
+where
+    A: Parser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn branch_first_tokens(&self) -> Vec<Option<Vec<MaybeRef<'a, I::Token>>>> {
+        self.iter().map(|parser| parser.first_tokens()).collect()
+    }
+
+    fn branch_graph_nodes(&self, graph: &mut graph::Graph, parent: graph::NodeId) {
+        for parser in self {
+            let child = parser.graph_node(graph);
+            graph.edge(parent, child);
+        }
+    }
+
+    fn branch_count(&self) -> usize {
+        N
+    }
+
+    fn gen_branch(
+        &self,
+        branch: usize,
+        gen: &mut generate::Generator<I::Token>,
+    ) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        self[branch].gen_input(gen)
+    }
+
+    #[inline]
+    fn go_branch<M: Mode>(&self, branch: usize, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        self[branch].go::<M>(inp)
+    }
+}
+
+// Try the given branches of a choice in order, stopping at the first that succeeds. If all of them fail, the tokens
+// expected by the branches that were not tried are reported. `branches` must be in ascending order.
+#[cfg(feature = "alloc")]
+#[inline]
+fn go_branches<'a, T, I, O, E, M>(
+    parsers: &T,
+    firsts: &[Option<Vec<I::Token>>],
+    inp: &mut InputRef<'a, '_, I, E>,
+    branches: impl Iterator<Item = usize> + Clone,
+) -> PResult<M, O>
+where
+    T: ChoiceBranches<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    M: Mode,
+    I::Token: Clone,
+{
+    let before = inp.save();
+    for branch in branches.clone() {
+        match parsers.go_branch::<M>(branch, inp) {
+            Ok(out) => return Ok(out),
+            Err(()) => inp.rewind(before),
+        }
+    }
+
+    // Produced lazily, so that error types which ignore expected tokens don't pay for them
+    let expected = || {
+        let mut tried = branches.clone().peekable();
+        firsts
+            .iter()
+            .enumerate()
+            .filter(move |(branch, _)| tried.next_if_eq(branch).is_none())
+            .flat_map(|(_, set)| set.iter().flatten())
+    };
+    if expected().next().is_some() {
+        let (at, found) = inp.next_maybe_inner();
+        let err_span = inp.span_since(before.offset());
+        inp.rewind(before);
+        inp.add_alt(
+            at,
+            expected().map(|tok| Some(MaybeRef::Val(tok.clone()))),
+            found.map(|f| f.into()),
+            err_span,
+        );
+    }
+    Err(())
+}
+
+// The union of a list of FIRST sets, as reported by `ParserSealed::first_tokens`
+#[cfg(feature = "alloc")]
+fn union_firsts<'a, T: Clone>(firsts: &[Option<Vec<T>>]) -> Option<Vec<MaybeRef<'a, T>>> {
+    union_first_tokens(firsts.iter().map(|set| {
+        set.as_ref()
+            .map(|set| set.iter().cloned().map(MaybeRef::Val).collect())
+    }))
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Choice<T> {
+    /// Skip branches that cannot match the next token, using the set of tokens that each branch can begin with.
+    ///
+    /// When this method is called, each branch is analysed to determine the tokens that it may begin with (its
+    /// 'FIRST set'). When parsing, a branch is then only attempted if the next token is in its FIRST set, avoiding the
+    /// cost of trying every branch in large alternations, such as the keywords and operators of a lexer.
+    ///
+    /// FIRST sets are known for [`just`] and [`one_of`] (when the set of tokens is reasonably small), and for
+    /// combinators built on them, such as [`Parser::map`], [`Parser::then`], [`Parser::delimited_by`], [`Parser::or`],
+    /// and nested [`choice`]s. Branches whose FIRST set is unknown, or that may succeed without consuming input (such
+    /// as [`Parser::or_not`], [`Parser::repeated`], and [`Parser::padded`]), are always attempted. The output and
+    /// errors of the parser are unaffected, except that skipped branches report the tokens they expected without
+    /// being run.
+    ///
+    /// The output type of this parser is the output type of the inner parsers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Token {
+    ///     Arrow,
+    ///     Minus,
+    ///     Eq,
+    ///     EqEq,
+    ///     LParen,
+    ///     RParen,
+    ///     Num(u64),
+    /// }
+    ///
+    /// let token = choice((
+    ///     just::<_, _, extra::Err<Rich<char>>>("->").to(Token::Arrow),
+    ///     just('-').to(Token::Minus),
+    ///     just("==").to(Token::EqEq),
+    ///     just('=').to(Token::Eq),
+    ///     just('(').to(Token::LParen),
+    ///     just(')').to(Token::RParen),
+    ///     text::int(10).from_str().unwrapped().map(Token::Num),
+    /// ))
+    /// .predictive();
+    ///
+    /// let tokens = token.padded().repeated().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     tokens.parse("(1 == 2) -> 3").into_result(),
+    ///     Ok(vec![Token::LParen, Token::Num(1), Token::EqEq, Token::Num(2), Token::RParen, Token::Arrow, Token::Num(3)]),
+    /// );
+    /// ```
+    pub fn predictive<'a, I, O, E>(self) -> Predictive<T, I::Token>
+    where
+        I: Input<'a>,
+        E: ParserExtra<'a, I>,
+        I::Token: Clone,
+        T: ChoiceBranches<'a, I, O, E>,
+    {
+        let firsts = self
+            .parsers
+            .branch_first_tokens()
+            .into_iter()
+            .map(|set| set.map(|set| set.into_iter().map(MaybeRef::into_inner).collect()))
+            .collect();
+        Predictive {
+            parsers: self.parsers,
+            firsts,
+        }
+    }
+    /// Select the branches to attempt using a lookup table indexed by the first byte of the remaining input.
+    ///
+    /// This is a variant of [`Choice::predictive`] for [`StrInput`]s, such as `&str` and `&[u8]`. When this method is
+    /// called, the FIRST set of each branch is used to build a table that maps each possible leading byte (plus the end
+    /// of input) to the branches that might match input beginning with that byte. When parsing, only the branches
+    /// listed for the next byte are attempted, so choosing between many keywords, operators, or punctuation tokens in
+    /// a lexer costs a single table lookup rather than an attempt at every branch.
+    ///
+    /// As with [`Choice::predictive`], branches whose FIRST set is unknown are always attempted, and the output and
+    /// errors of the parser are unaffected except that skipped branches report the tokens they expected without being
+    /// run.
+    ///
+    /// The output type of this parser is the output type of the inner parsers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Op {
+    ///     Add,
+    ///     AddAssign,
+    ///     Sub,
+    ///     Arrow,
+    ///     Eq,
+    ///     EqEq,
+    ///     Not,
+    ///     NotEq,
+    /// }
+    ///
+    /// let op = choice((
+    ///     just::<_, _, extra::Err<Rich<char>>>("+=").to(Op::AddAssign),
+    ///     just('+').to(Op::Add),
+    ///     just("->").to(Op::Arrow),
+    ///     just('-').to(Op::Sub),
+    ///     just("==").to(Op::EqEq),
+    ///     just('=').to(Op::Eq),
+    ///     just("!=").to(Op::NotEq),
+    ///     just('!').to(Op::Not),
+    /// ))
+    /// .byte_dispatch();
+    ///
+    /// let ops = op.padded().repeated().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     ops.parse("+= - -> == ! !=").into_result(),
+    ///     Ok(vec![Op::AddAssign, Op::Sub, Op::Arrow, Op::EqEq, Op::Not, Op::NotEq]),
+    /// );
+    /// assert!(ops.parse("+ * -").has_errors());
+    /// ```
+    pub fn byte_dispatch<'a, I, C, O, E>(self) -> ByteDispatch<T, C>
+    where
+        I: StrInput<'a, C>,
+        C: Char,
+        E: ParserExtra<'a, I>,
+        T: ChoiceBranches<'a, I, O, E>,
+    {
+        let Predictive { parsers, firsts } = self.predictive::<I, O, E>();
+        let mut table = vec![Vec::new(); 257];
+        for (branch, set) in firsts.iter().enumerate() {
+            match set {
+                Some(set) => {
+                    let bytes = set.iter().fold(text::scan::ByteSet::EMPTY, |bytes, c| {
+                        bytes.with(c.leading_byte())
+                    });
+                    for byte in bytes.bytes() {
+                        table[byte as usize].push(branch);
+                    }
+                }
+                None => table.iter_mut().for_each(|branches| branches.push(branch)),
+            }
+        }
+        ByteDispatch {
+            parsers,
+            firsts,
+            table,
+        }
+    }
+}
+
+/// See [`Choice::predictive`].
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct Predictive<T, Tok> {
+    parsers: T,
+    firsts: Vec<Option<Vec<Tok>>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, I, O, E> ParserSealed<'a, I, O, E> for Predictive<T, I::Token>
+where
+    T: ChoiceBranches<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq + Clone,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let next = inp.peek_maybe();
+        let next = next.as_deref();
+        // Branches with an unknown FIRST set might match anything
+        let possible = (0..self.firsts.len()).filter(|branch| match &self.firsts[*branch] {
+            Some(set) => next.map_or(false, |tok| <[I::Token]>::contains(set, tok)),
+            None => true,
+        });
+        go_branches::<_, _, _, _, M>(&self.parsers, &self.firsts, inp, possible)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        union_firsts(&self.firsts)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        self.parsers.branch_graph_nodes(graph, id);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.choose(self.parsers.branch_count(), |gen, branch| {
+            self.parsers.gen_branch(branch, gen)
+        })
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Choice::byte_dispatch`].
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct ByteDispatch<T, C> {
+    parsers: T,
+    firsts: Vec<Option<Vec<C>>>,
+    // The branches to attempt for each leading byte, in order, followed by those to attempt at the end of input
+    table: Vec<Vec<usize>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, I, C, O, E> ParserSealed<'a, I, O, E> for ByteDispatch<T, C>
+where
+    T: ChoiceBranches<'a, I, O, E>,
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let next = C::str_as_bytes(inp.slice_trailing_inner()).first();
+        if next.is_none() {
+            inp.reach_end();
+        }
+        let branches = &self.table[next.map_or(256, |byte| *byte as usize)];
+        go_branches::<_, _, _, _, M>(&self.parsers, &self.firsts, inp, branches.iter().copied())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, C>>> {
+        union_firsts(&self.firsts)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        self.parsers.branch_graph_nodes(graph, id);
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.choose(self.parsers.branch_count(), |gen, branch| {
+            self.parsers.gen_branch(branch, gen)
+        })
+    }
+
     go_extra!(O);
 }
 
@@ -903,6 +1821,32 @@ where
         Ok(M::array(unsafe { MaybeUninitExt::array_assume_init(arr) }))
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let id = graph.add::<Self>();
+        for parser in &self.parsers {
+            let child = parser.graph_node(graph);
+            graph.edge(id, child);
+        }
+        id
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<[O; N]>, ()>
+    where
+        I::Token: Clone,
+    {
+        let mut outs = Some(Vec::with_capacity(N));
+        for parser in &self.parsers {
+            let out = parser.gen_input(gen)?;
+            outs = outs.zip(out).map(|(mut outs, out)| {
+                outs.push(out);
+                outs
+            });
+        }
+        Ok(outs.and_then(|outs| outs.try_into().ok()))
+    }
+
     go_extra!([O; N]);
 }
 
@@ -960,6 +1904,29 @@ macro_rules! impl_group_for_tuple {
                 Ok(flatten_map!(<M> $($X)*))
             }
 
+            #[cfg(feature = "alloc")]
+            fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+                let id = graph.add::<Self>();
+                let Group { parsers: ($($X,)*) } = self;
+                $(
+                    let child = ParserSealed::<I, $O, E>::graph_node($X, graph);
+                    graph.edge(id, child);
+                )*
+                id
+            }
+
+            #[cfg(feature = "alloc")]
+            fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<($($O,)*)>, ()>
+            where
+                I::Token: Clone,
+            {
+                let Group { parsers: ($($X,)*) } = self;
+                $(
+                    let $X = ParserSealed::<I, $O, E>::gen_input($X, gen)?;
+                )*
+                Ok((|| Some(($($X?,)*)))())
+            }
+
             go_extra!(($($O,)*));
         }
     };
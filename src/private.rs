@@ -201,6 +201,7 @@ pub trait ParserSealed<'a, I: Input<'a>, O, E: ParserExtra<'a, I>> {
     fn go_emit(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<Emit, O>;
     fn go_check(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<Check, O>;
 
+    #[cfg(feature = "alloc")]
     fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
     where
         Self: MaybeSync + Sized + 'a + 'b,
@@ -209,6 +210,38 @@ pub trait ParserSealed<'a, I: Input<'a>, O, E: ParserExtra<'a, I>> {
             inner: RefC::new(self),
         }
     }
+
+    /// Skip any trivia (such as the whitespace skipped by [`Parser::padded`]) that this parser accepts before its
+    /// first significant token. See [`Parser::or_not_with_err`].
+    #[doc(hidden)]
+    fn skip_leading_trivia(&self, _inp: &mut InputRef<'a, '_, I, E>) {}
+
+    /// The tokens that this parser can begin with: if this returns `Some`, the parser only succeeds when the next
+    /// token is one of the returned tokens. `None` means that the set is unknown, or that the parser may succeed
+    /// without consuming input. See [`Choice::predictive`].
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        None
+    }
+
+    /// Add the node representing this parser, and the parsers it contains, to a graph. See [`Parser::graph`].
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        graph.add::<Self>()
+    }
+
+    /// Generate a random input that this parser accepts, returning its output if it can be determined. `Err` means
+    /// that no input could be generated. See [`Parser::generate`].
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, _gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        Err(())
+    }
 }
 
 pub trait ConfigParserSealed<'a, I, O, E>: ParserSealed<'a, I, O, E>
@@ -250,6 +283,31 @@ where
         inp: &mut InputRef<'a, '_, I, E>,
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, O>;
+
+    /// The number of items this parser is expected to produce, used to pre-allocate output containers.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn capacity_hint(&self) -> usize {
+        0
+    }
+
+    /// Add the node representing this parser, and the parsers it contains, to a graph. See [`Parser::graph`].
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        graph.add::<Self>()
+    }
+
+    /// Generate a random input that this parser accepts, returning its items if they can be determined. See
+    /// [`Parser::generate`].
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn gen_items(&self, _gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<O>>, ()>
+    where
+        I::Token: Clone,
+    {
+        Err(())
+    }
 }
 
 pub trait ConfigIterParserSealed<'a, I, O, E>: IterParserSealed<'a, I, O, E>
@@ -285,9 +343,7 @@ impl<T> MaybeUninitExt<T> for MaybeUninit<T> {
     }
 
     unsafe fn array_assume_init<const N: usize>(uninit: [Self; N]) -> [T; N] {
-        let out = (&uninit as *const [Self; N] as *const [T; N]).read();
-        core::mem::forget(uninit);
-        out
+        (&uninit as *const [Self; N] as *const [T; N]).read()
     }
 }
 
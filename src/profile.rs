@@ -0,0 +1,184 @@
+//! Profiling of parsers, to find the rules that cause excessive backtracking.
+//!
+//! Most grammars can be parsed efficiently, but a poorly structured rule may repeatedly parse the same input only to
+//! fail and backtrack, leading to exponential parse times. To find such rules, mark the parsers you're interested in
+//! with [`Labelled::profiled`] (or name them with [`Parser::named`]) and then parse the input with
+//! [`Parser::parse_profiled`]. Alongside the usual [`ParseResult`], this returns a [`Profile`] containing statistics
+//! for each profiled parser, keyed by its label.
+//!
+//! Profiled parsers only record statistics when invoked by [`Parser::parse_profiled`] (or
+//! [`Parser::parse_with_state_profiled`]): otherwise, they have almost no overhead.
+//!
+//! *This module requires the `profile` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! // `call` and `ident` share a prefix, so the identifier is parsed twice for every variable
+//! let ident = text::ident::<_, _, extra::Err<Rich<char>>>()
+//!     .labelled("ident")
+//!     .profiled();
+//! let call = ident
+//!     .clone()
+//!     .then_ignore(just("()"))
+//!     .labelled("call")
+//!     .profiled();
+//! let expr = call.or(ident.clone());
+//!
+//! let (result, profile) = expr.padded().repeated().collect::<Vec<_>>().parse_profiled("a b() c");
+//! assert!(!result.has_errors());
+//!
+//! let call = profile.get("call").unwrap();
+//! assert_eq!(call.invocations, 4);
+//! assert_eq!(call.failures, 3);
+//! // `call` read `a` and the space after it, and `c`, before failing
+//! assert_eq!(call.backtracked, 3);
+//! assert_eq!(profile.get("ident").unwrap().invocations, 7);
+//!
+//! println!("{profile}");
+//! ```
+
+use super::*;
+use std::time::{Duration, Instant};
+
+/// Statistics recorded for a single profiled parser. See [`Profile`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleStats {
+    /// The number of times the parser was invoked.
+    pub invocations: u64,
+    /// The number of invocations that failed.
+    pub failures: u64,
+    /// The total amount of input read by failed invocations, including the tokens at which they failed. This input
+    /// must be parsed again by whatever is attempted next. It is measured in offsets of the input (bytes for `&str`,
+    /// tokens for slices).
+    pub backtracked: usize,
+    /// The total time spent within the parser, including the time spent in any parsers it contains.
+    pub time: Duration,
+}
+
+/// A report of the statistics recorded for each profiled parser during a parse. See [`Parser::parse_profiled`].
+///
+/// When displayed, the report is formatted as a table, with the parsers that took the most time first.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    rules: HashMap<String, RuleStats>,
+}
+
+impl Profile {
+    /// Get the statistics recorded for the parser with the given label, if it was invoked.
+    pub fn get(&self, label: &str) -> Option<&RuleStats> {
+        self.rules.get(label)
+    }
+
+    /// Iterate over the label and statistics of each profiled parser that was invoked, with the parsers that took the
+    /// most time first.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &RuleStats)> {
+        let mut rules = self
+            .rules
+            .iter()
+            .map(|(label, stats)| (label.as_str(), stats))
+            .collect::<Vec<_>>();
+        rules.sort_by(|(a_label, a), (b_label, b)| b.time.cmp(&a.time).then(a_label.cmp(b_label)));
+        rules.into_iter()
+    }
+
+    fn record(&mut self, label: &str, failed: bool, backtracked: usize, time: Duration) {
+        let stats = match self.rules.get_mut(label) {
+            Some(stats) => stats,
+            None => self.rules.entry(label.to_string()).or_default(),
+        };
+        stats.invocations += 1;
+        stats.time += time;
+        if failed {
+            stats.failures += 1;
+            stats.backtracked += backtracked;
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .rules
+            .keys()
+            .map(|label| label.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        writeln!(
+            f,
+            "{:width$}  {:>11}  {:>9}  {:>11}  {:>12}",
+            "rule", "invocations", "failures", "backtracked", "time"
+        )?;
+        for (label, stats) in self.iter() {
+            writeln!(
+                f,
+                "{:width$}  {:>11}  {:>9}  {:>11}  {:>12}",
+                label,
+                stats.invocations,
+                stats.failures,
+                stats.backtracked,
+                format!("{:?}", stats.time),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Invoke a parser, recording statistics for it under the given label if the parse is being profiled
+#[inline]
+pub(crate) fn go_profiled<'a, 'parse, I, E, M, O>(
+    inp: &mut InputRef<'a, 'parse, I, E>,
+    label: &str,
+    go: impl FnOnce(&mut InputRef<'a, 'parse, I, E>) -> PResult<M, O>,
+) -> PResult<M, O>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    M: Mode,
+{
+    if inp.profile.is_none() {
+        return go(inp);
+    }
+
+    let before = inp.offset;
+    let start = Instant::now();
+    let res = go(inp);
+    let time = start.elapsed();
+    let backtracked = inp.offset.into().saturating_sub(before.into());
+    if let Some(profile) = inp.profile.as_mut() {
+        profile.record(label, res.is_err(), backtracked, time);
+    }
+    res
+}
+
+/// See [`Labelled::profiled`].
+#[derive(Clone)]
+pub struct Profiled<A> {
+    pub(crate) parser: A,
+    pub(crate) label: String,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Profiled<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        go_profiled::<_, _, M, _>(inp, &self.label, |inp| self.parser.go::<M>(inp))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
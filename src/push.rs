@@ -0,0 +1,200 @@
+//! Push-based parsing of inputs that arrive in pieces, such as messages read from a network connection.
+//!
+//! Parsers normally need their whole input up front. A [`PushParser`] instead accepts input a chunk at a time with
+//! [`PushParser::push`], and parses one item (such as a message of a framed protocol) at a time from the input it has
+//! been given so far with [`PushParser::next`]. If the parser runs out of input before it can finish an item, `next`
+//! returns [`Step::Pending`] and the partial item is kept until more input arrives, so only the unparsed part of the
+//! input is ever buffered.
+//!
+//! A partial item is parsed again from its beginning when more input arrives, rather than resuming exactly where the
+//! parser stopped, so this works best for streams of many small items. Parsers may look past the end of an item to
+//! decide that it has ended (for example, [`Parser::repeated`] must find something other than another repetition):
+//! when that happens at the end of the input, `next` waits for more input, or for [`PushParser::finish`] to be called,
+//! before returning the item.
+//!
+//! Spans of outputs and errors are relative to the start of the item being parsed. Outputs may borrow from the input,
+//! so the parser is usually created afresh (by a function that returns `impl Parser`) each time `next` is called.
+//!
+//! *This module requires the `push` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, push::{PushParser, Step}};
+//! // Each message is a single-digit length, a colon, and then that many bytes of payload
+//! fn message<'a>() -> impl Parser<'a, &'a [u8], &'a [u8]> {
+//!     let payload = any()
+//!         .repeated()
+//!         .configure(|cfg, len: &usize| cfg.exactly(*len))
+//!         .slice();
+//!     one_of(b'0'..=b'9')
+//!         .map(|digit: u8| usize::from(digit - b'0'))
+//!         .then_ignore(just(b':'))
+//!         .then_with_ctx(payload)
+//! }
+//!
+//! let mut push = PushParser::new();
+//! let mut payloads = Vec::new();
+//! for chunk in [&b"5:hel"[..], b"lo3:", b"abc6:wo", b"rld!"] {
+//!     push.push(chunk);
+//!     while let Step::Done(result) = push.next(&message()) {
+//!         payloads.push(result.into_output().unwrap().to_vec());
+//!     }
+//! }
+//! assert_eq!(payloads, [&b"hello"[..], b"abc", b"world!"]);
+//! ```
+
+use super::*;
+
+/// A parser that is fed its input a chunk at a time, parsing one item after another. See the
+/// [module documentation](self) for more information.
+#[derive(Clone, Debug, Default)]
+pub struct PushParser<T> {
+    buffer: Vec<T>,
+    // The length of the item most recently returned by `next`, which is removed from the buffer once its output is no
+    // longer borrowed
+    consumed: usize,
+    finished: bool,
+}
+
+/// The result of trying to parse the next item with [`PushParser::next`].
+#[derive(Debug)]
+pub enum Step<O, Err> {
+    /// More input is needed before the next item can be parsed.
+    Pending,
+    /// An item was parsed. If it was parsed successfully, it is removed from the input.
+    Done(ParseResult<O, Err>),
+    /// [`PushParser::finish`] has been called, and every item has been parsed.
+    Finished,
+}
+
+impl<T> PushParser<T> {
+    /// Create a new push parser, with no input.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            consumed: 0,
+            finished: false,
+        }
+    }
+
+    /// Add a chunk to the end of the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`PushParser::finish`] has already been called.
+    pub fn push(&mut self, chunk: &[T])
+    where
+        T: Clone,
+    {
+        assert!(!self.finished, "cannot push input after calling `finish`");
+        self.buffer.drain(..self.consumed);
+        self.consumed = 0;
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Signal that the end of the input has been reached, so that the last item can be parsed without waiting for more
+    /// input.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Get the input that has not yet been parsed.
+    pub fn buffered(&self) -> &[T] {
+        &self.buffer[self.consumed..]
+    }
+
+    /// Discard the input that has not yet been parsed, such as after an item failed to parse.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.consumed = 0;
+    }
+
+    /// Discard the next `n` tokens of the input that has not yet been parsed, such as to skip past an item that failed
+    /// to parse and carry on with the next one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` tokens are buffered.
+    pub fn skip(&mut self, n: usize) {
+        assert!(
+            n <= self.buffered().len(),
+            "cannot skip more input than is buffered"
+        );
+        self.consumed += n;
+    }
+
+    /// Try to parse the next item from the input given so far.
+    ///
+    /// If the item is parsed successfully, it is removed from the input. If it fails to parse, the input is left as it
+    /// was: to carry on parsing, skip past the malformed item with [`PushParser::skip`] (or discard the rest of the
+    /// input with [`PushParser::clear`]).
+    ///
+    /// Every item must consume some input, since otherwise the same item would be parsed again forever: if the parser
+    /// succeeds without consuming any input, the item is reported as an error at the next token instead.
+    pub fn next<'a, P, O, E>(&'a mut self, parser: &P) -> Step<O, E::Error>
+    where
+        P: Parser<'a, &'a [T], O, E>,
+        E: ParserExtra<'a, &'a [T]>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let Self {
+            buffer,
+            consumed,
+            finished,
+        } = self;
+        buffer.drain(..*consumed);
+        *consumed = 0;
+        if *finished && buffer.is_empty() {
+            return Step::Finished;
+        }
+
+        let mut own = InputOwn::<&'a [T], E>::new(&buffer[..]);
+        let mut inp = own.as_ref_start();
+        let before = inp.offset();
+        let res = match parser.go::<Emit>(&mut inp) {
+            // Without any input, an item that consumes none can't be told apart from the end of the input
+            Ok(_) if inp.offset == 0 && buffer.is_empty() => return Step::Pending,
+            Ok(_) if inp.offset == 0 => {
+                let (at, found) = inp.next_maybe_inner();
+                let span = inp.span_since(before);
+                inp.add_alt(at, None, found.map(|f| f.into()), span);
+                Err(())
+            }
+            res => res,
+        };
+        let end = inp.offset;
+        let alt = inp.errors.alt.take();
+        // The parser needed to know what came after the input it was given, so more input might change the result
+        if !*finished && own.reached_end.get() {
+            return Step::Pending;
+        }
+        let mut errs = own.into_errs();
+        let out = match res {
+            Ok(out) => {
+                *consumed = end;
+                Some(out)
+            }
+            Err(()) => {
+                errs.push(alt.expect("error but no alt?").err);
+                None
+            }
+        };
+        Step::Done(ParseResult::new(out, errs))
+    }
+}
+
+impl<T> Extend<T> for PushParser<T> {
+    /// Add tokens to the end of the input, as with [`PushParser::push`] but without cloning them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`PushParser::finish`] has already been called.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, tokens: I) {
+        assert!(!self.finished, "cannot push input after calling `finish`");
+        self.buffer.drain(..self.consumed);
+        self.consumed = 0;
+        self.buffer.extend(tokens);
+    }
+}
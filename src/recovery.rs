@@ -8,6 +8,10 @@ use super::*;
 /// eventually change. For now, if you wish to implement a new strategy, consider using [`via_parser`] or
 /// [opening an issue/PR](https://github.com/zesterer/chumsky/issues/new).
 pub trait Strategy<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>: Sealed {
+    // Whether the strategy might recover at all, so that parsers can skip preparing for recovery when it never will.
+    #[doc(hidden)]
+    const RECOVERS: bool = true;
+
     // Attempt to recover from a parsing failure.
     // The strategy should properly handle the alt error but is not required to handle rewinding.
     #[doc(hidden)]
@@ -84,6 +88,10 @@ where
         }
     }
 
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
     go_extra!(O);
 }
 
@@ -205,6 +213,7 @@ pub fn skip_until<S, U, F>(skip: S, until: U, fallback: F) -> SkipUntil<S, U, F>
 ///
 /// A function that generates a fallback output on recovery is also required.
 // TODO: Make this a strategy, add an unclosed_delimiter error
+#[cfg(feature = "alloc")]
 pub fn nested_delimiters<'a, I, O, E, F, const N: usize>(
     start: I::Token,
     end: I::Token,
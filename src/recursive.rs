@@ -8,11 +8,12 @@
 //! definition of parsers more corefully, particularly for mutually-recursive parsers. In such cases, the functions on
 //! [`Recursive`] allow for this.
 
+#[cfg(feature = "alloc")]
 use super::*;
 
-#[cfg(not(feature = "sync"))]
+#[cfg(all(feature = "alloc", not(feature = "sync")))]
 struct OnceCell<T>(core::cell::Cell<Option<T>>);
-#[cfg(not(feature = "sync"))]
+#[cfg(all(feature = "alloc", not(feature = "sync")))]
 impl<T> OnceCell<T> {
     pub fn new() -> Self {
         Self(core::cell::Cell::new(None))
@@ -37,9 +38,9 @@ impl<T> OnceCell<T> {
     }
 }
 
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "alloc", feature = "sync"))]
 struct OnceCell<T>(spin::once::Once<T>);
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "alloc", feature = "sync"))]
 impl<T> OnceCell<T> {
     pub fn new() -> Self {
         Self(spin::once::Once::new())
@@ -61,6 +62,7 @@ impl<T> OnceCell<T> {
 }
 
 // TODO: Ensure that this doesn't produce leaks
+#[cfg(feature = "alloc")]
 enum RecursiveInner<T: ?Sized> {
     Owned(RefC<T>),
     Unowned(RefW<T>),
@@ -68,22 +70,26 @@ enum RecursiveInner<T: ?Sized> {
 
 /// Type for recursive parsers that are defined through a call to `recursive`, and as such
 /// need no internal indirection
+#[cfg(feature = "alloc")]
 pub type Direct<'a, 'b, I, O, Extra> = DynParser<'a, 'b, I, O, Extra>;
 
 /// Type for recursive parsers that are defined through a call to [`Recursive::declare`], and as
 /// such require an additional layer of allocation.
+#[cfg(feature = "alloc")]
 pub struct Indirect<'a, 'b, I: Input<'a>, O, Extra: ParserExtra<'a, I>> {
-    inner: OnceCell<Box<DynParser<'a, 'b, I, O, Extra>>>,
+    inner: OnceCell<Boxed<'a, 'b, I, O, Extra>>,
 }
 
 /// A parser that can be defined in terms of itself by separating its [declaration](Recursive::declare) from its
 /// [definition](Recursive::define).
 ///
 /// Prefer to use [`recursive()`], which exists as a convenient wrapper around both operations, if possible.
+#[cfg(feature = "alloc")]
 pub struct Recursive<P: ?Sized> {
     inner: RecursiveInner<P>,
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Recursive<Indirect<'a, 'b, I, O, E>> {
     /// Declare the existence of a recursive parser, allowing it to be used to construct parser combinators before
     /// being fulled defined.
@@ -136,9 +142,10 @@ impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Recursive<Indirect<'a, 'b,
     #[track_caller]
     pub fn define<P: Parser<'a, I, O, E> + Clone + MaybeSync + 'a + 'b>(&mut self, parser: P) {
         let location = *Location::caller();
+        // Boxing an already-boxed parser reuses it, avoiding a second layer of dynamic dispatch
         self.parser()
             .inner
-            .set(Box::new(parser))
+            .set(Parser::boxed(parser))
             .unwrap_or_else(|_| {
                 panic!(
                     "recursive parsers can only be defined once, trying to redefine it at {}",
@@ -148,6 +155,7 @@ impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Recursive<Indirect<'a, 'b,
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<P: ?Sized> Recursive<P> {
     #[inline]
     fn parser(&self) -> RefC<P> {
@@ -160,6 +168,7 @@ impl<P: ?Sized> Recursive<P> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<P: ?Sized> Clone for Recursive<P> {
     fn clone(&self) -> Self {
         Self {
@@ -171,17 +180,19 @@ impl<P: ?Sized> Clone for Recursive<P> {
     }
 }
 
+// Run a function that recurses into a parser, growing the stack beforehand if it is nearly exhausted
 #[cfg(feature = "stacker")]
 #[inline]
-fn recurse<R, F: FnOnce() -> R>(f: F) -> R {
+pub(crate) fn recurse<R, F: FnOnce() -> R>(f: F) -> R {
     stacker::maybe_grow(1024 * 64, 1024 * 1024, f)
 }
 #[cfg(not(feature = "stacker"))]
 #[inline]
-fn recurse<R, F: FnOnce() -> R>(f: F) -> R {
+pub(crate) fn recurse<R, F: FnOnce() -> R>(f: F) -> R {
     f()
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Recursive<Indirect<'a, 'b, I, O, E>>
 where
     I: Input<'a>,
@@ -194,16 +205,34 @@ where
                 self.parser()
                     .inner
                     .get()
-                    .expect("Recursive parser used before being defined")
-                    .as_ref(),
+                    .expect("Recursive parser used before being defined"),
                 inp,
             )
         })
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let parser = self.parser();
+        graph.add_recursive::<Self>(RefC::as_ptr(&parser) as *const () as usize, |graph| {
+            parser.inner.get().map(|inner| inner.graph_node(graph))
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        let parser = self.parser();
+        let inner = parser.inner.get().ok_or(())?;
+        gen.recurse(|gen| inner.gen_input(gen))
+    }
+
     go_extra!(O);
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Recursive<Direct<'a, 'b, I, O, E>>
 where
     I: Input<'a>,
@@ -214,6 +243,22 @@ where
         recurse(move || M::invoke(&*self.parser(), inp))
     }
 
+    #[cfg(feature = "alloc")]
+    fn graph_node(&self, graph: &mut graph::Graph) -> graph::NodeId {
+        let parser = self.parser();
+        graph.add_recursive::<Self>(RefC::as_ptr(&parser) as *const () as usize, |graph| {
+            Some(parser.graph_node(graph))
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        gen.recurse(|gen| self.parser().gen_input(gen))
+    }
+
     go_extra!(O);
 }
 
@@ -267,6 +312,7 @@ where
 /// ])));
 /// ```
 // INFO: Clone bound not actually needed, but good to be safe for future compat
+#[cfg(feature = "alloc")]
 pub fn recursive<'a, 'b, I, O, E, A, F>(f: F) -> Recursive<Direct<'a, 'b, I, O, E>>
 where
     I: Input<'a>,
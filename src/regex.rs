@@ -8,7 +8,54 @@ pub struct Regex<C: Char, I, E> {
     phantom: PhantomData<(E, I)>,
 }
 
-/// Match input based on a provided regex pattern
+impl<C: Char, I, E> Clone for Regex<C, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            regex: self.regex.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Match input based on a provided regex pattern.
+///
+/// The pattern must match at the current position in the input (i.e: it is implicitly anchored at the start, but not
+/// at the end).
+///
+/// This parser works with both `&str` inputs (using [`regex::Regex`](::regex::Regex)) and `&[u8]` inputs (using
+/// [`regex::bytes::Regex`](::regex::bytes::Regex)). When matching bytes, Unicode mode can be disabled with `(?-u)` to
+/// match arbitrary bytes that are not valid UTF-8, making this parser suitable for text-based binary protocols.
+///
+/// The output type of this parser is `&C::Str` (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]).
+///
+/// The pattern is compiled once, when the parser is created. To share a compiled regex between several parsers, or to
+/// configure it with a builder, use [`Regex::from_regex`].
+///
+/// # Panics
+///
+/// Panics if the pattern is not a valid regex.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// // An HTTP header line, matched directly on the raw bytes
+/// let header = regex::<_, _, extra::Err<Simple<u8>>>(r"[!#$%&'*+.^_`|~0-9A-Za-z-]+")
+///     .then_ignore(just(b": "))
+///     .then(regex(r"(?-u)[^\r\n]*"))
+///     .then_ignore(just(b"\r\n"));
+///
+/// assert_eq!(
+///     header.parse(b"Content-Type: text/html\r\n" as &[_]).into_result(),
+///     Ok((b"Content-Type" as &[_], b"text/html" as &[_])),
+/// );
+/// // Header values need not be valid UTF-8
+/// assert_eq!(
+///     header.parse(b"X-Data: \xFF\xFE\r\n" as &[_]).into_result(),
+///     Ok((b"X-Data" as &[_], b"\xFF\xFE" as &[_])),
+/// );
+/// ```
 pub fn regex<C: Char, I, E>(pattern: &str) -> Regex<C, I, E> {
     Regex {
         regex: C::new_regex(pattern),
@@ -25,14 +72,24 @@ where
     #[inline]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
         let before = inp.offset();
-        match C::match_regex(&self.regex, inp.slice_trailing_inner()) {
+        let trailing = inp.slice_trailing_inner();
+        match C::match_regex(&self.regex, trailing) {
             Some(len) => {
+                // A match that runs to the end of the input might have been longer with more input
+                if len == C::str_as_bytes(trailing).len() {
+                    inp.reach_end();
+                }
                 let before = inp.offset();
                 inp.skip_bytes(len);
                 let after = inp.offset();
                 Ok(M::bind(|| inp.slice_inner(before.offset..after.offset)))
             }
             None => {
+                // Regexes can't tell whether more input would have let them match, so only an empty input counts as
+                // having been looked past
+                if C::str_as_bytes(trailing).is_empty() {
+                    inp.reach_end();
+                }
                 // TODO: Improve error
                 inp.add_alt(inp.offset().offset, None, None, inp.span_since(before));
                 Err(())
@@ -43,6 +100,128 @@ where
     go_extra!(&'a C::Str);
 }
 
+impl<C: Char, I, E> Regex<C, I, E> {
+    /// Create a regex parser from an already compiled regex ([`regex::Regex`](::regex::Regex) for `&str` inputs,
+    /// [`regex::bytes::Regex`](::regex::bytes::Regex) for `&[u8]` inputs).
+    ///
+    /// Like [`regex()`], the parser only accepts matches that begin at the current position in the input. Patterns
+    /// passed to [`regex()`] are anchored automatically; for best performance, a pre-built regex should begin with `^`
+    /// so that a failed match does not search the remainder of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, regex::Regex};
+    /// let re = regex::RegexBuilder::new(r"^select")
+    ///     .case_insensitive(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let select = Regex::<_, _, extra::Err<Simple<char>>>::from_regex(re);
+    ///
+    /// assert_eq!(select.parse("SeLeCt").into_result(), Ok("SeLeCt"));
+    /// assert!(select.parse("update").has_errors());
+    /// ```
+    pub fn from_regex(regex: C::Regex) -> Self {
+        Self {
+            regex,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Output the capture groups of the regex, rather than the whole match.
+    ///
+    /// `N` must be the number of capture groups in the pattern (not counting the implicit group for the whole match).
+    /// Each group is `None` if it did not participate in the match.
+    ///
+    /// The output type of this parser is `[Option<&C::Str>; N]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern does not have exactly `N` capture groups.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let date = regex::<_, _, extra::Err<Simple<char>>>(r"(\d{4})-(\d{2})-(\d{2})")
+    ///     .captures::<3>()
+    ///     .map(|[y, m, d]| (y.unwrap(), m.unwrap(), d.unwrap()));
+    ///
+    /// assert_eq!(date.parse("2023-04-01").into_result(), Ok(("2023", "04", "01")));
+    ///
+    /// let version = regex::<_, _, extra::Err<Simple<char>>>(r"(\d+)\.(\d+)(?:-(\w+))?").captures::<3>();
+    ///
+    /// assert_eq!(version.parse("1.2-beta").into_result(), Ok([Some("1"), Some("2"), Some("beta")]));
+    /// assert_eq!(version.parse("1.2").into_result(), Ok([Some("1"), Some("2"), None]));
+    /// ```
+    #[track_caller]
+    pub fn captures<const N: usize>(self) -> RegexCaptures<C, I, E, N> {
+        let groups = C::regex_captures_len(&self.regex) - 1;
+        assert_eq!(
+            groups, N,
+            "regex has {groups} capture group(s), but {N} were requested"
+        );
+        RegexCaptures {
+            regex: self.regex,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// See [`Regex::captures`].
+pub struct RegexCaptures<C: Char, I, E, const N: usize> {
+    regex: C::Regex,
+    phantom: PhantomData<(E, I)>,
+}
+
+impl<C: Char, I, E, const N: usize> Clone for RegexCaptures<C, I, E, N> {
+    fn clone(&self) -> Self {
+        Self {
+            regex: self.regex.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, C, I, E, const N: usize> ParserSealed<'a, I, [Option<&'a C::Str>; N], E>
+    for RegexCaptures<C, I, E, N>
+where
+    C: Char,
+    I: StrInput<'a, C>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, [Option<&'a C::Str>; N]> {
+        let before = inp.offset();
+        let mut groups = core::array::from_fn(|_| None);
+        let trailing = inp.slice_trailing_inner();
+        match C::match_regex_captures(&self.regex, trailing, &mut groups) {
+            Some(len) => {
+                if len == C::str_as_bytes(trailing).len() {
+                    inp.reach_end();
+                }
+                inp.skip_bytes(len);
+                Ok(M::bind(|| {
+                    groups.map(|group| {
+                        group.map(|r| {
+                            inp.slice_inner(before.offset + r.start..before.offset + r.end)
+                        })
+                    })
+                }))
+            }
+            None => {
+                if C::str_as_bytes(trailing).is_empty() {
+                    inp.reach_end();
+                }
+                inp.add_alt(inp.offset().offset, None, None, inp.span_since(before));
+                Err(())
+            }
+        }
+    }
+
+    go_extra!([Option<&'a C::Str>; N]);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,4 +254,21 @@ mod tests {
             ]),
         );
     }
+
+    #[test]
+    fn regex_bytes_non_utf8() {
+        use self::prelude::*;
+
+        let parser = regex::<_, _, extra::Err<Simple<u8>>>(r"(?-u)\xFF+")
+            .then(regex(r"(?-u)([\x00-\x7F])(\xC0)").captures::<2>());
+
+        assert_eq!(
+            parser.parse(b"\xFF\xFFa\xC0" as &[_]).into_result(),
+            Ok((
+                b"\xFF\xFF" as &[_],
+                [Some(b"a" as &[_]), Some(b"\xC0" as &[_])]
+            )),
+        );
+        assert!(parser.parse(b"\xFEa\xC0" as &[_]).has_errors());
+    }
 }
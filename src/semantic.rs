@@ -0,0 +1,170 @@
+//! Semantic tokens, for syntax highlighting in editors.
+//!
+//! Editors that speak the Language Server Protocol highlight source code using *semantic tokens*: spans of the input
+//! tagged with a category, such as "keyword" or "function". Rather than walking the output of a parser a second time
+//! to find them, the parts of a grammar that should be highlighted can be tagged with [`Parser::semantic_token`], and
+//! [`Parser::parse_semantic`] collects the span and [`Category`] of each of them during the parse.
+//!
+//! Tokens are returned in the order they appear in the input, and never overlap: when a tagged parser contains other
+//! tagged parsers, only the outermost token is kept. Tokens recorded by a parser that later backtracks (such as one
+//! branch of a [`choice`]) are discarded along with it, as are tokens within [nested inputs](Parser::nested_in).
+//! Tokens within [memoised](Parser::memoised) parsers are only recorded the first time the parser is invoked at each
+//! position.
+//!
+//! *This module requires the `semantic` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, semantic::Category};
+//! let ident = text::ident::<_, _, extra::Err<Rich<char>>>();
+//! let call = ident
+//!     .semantic_token(Category::Function)
+//!     .then(
+//!         text::int(10)
+//!             .semantic_token(Category::Number)
+//!             .or(ident.semantic_token(Category::Variable))
+//!             .separated_by(just(',').padded())
+//!             .collect::<Vec<_>>()
+//!             .delimited_by(just('('), just(')')),
+//!     );
+//! let stmt = text::keyword("let")
+//!     .semantic_token(Category::Keyword)
+//!     .ignore_then(ident.semantic_token(Category::Variable).padded())
+//!     .then_ignore(just('=').semantic_token(Category::Operator))
+//!     .then(call.padded());
+//!
+//! let (result, tokens) = stmt.parse_semantic("let x = max(1, y)");
+//! assert!(!result.has_errors());
+//! assert_eq!(
+//!     tokens,
+//!     [
+//!         ((0..3).into(), Category::Keyword),
+//!         ((4..5).into(), Category::Variable),
+//!         ((6..7).into(), Category::Operator),
+//!         ((8..11).into(), Category::Function),
+//!         ((12..13).into(), Category::Number),
+//!         ((15..16).into(), Category::Variable),
+//!     ],
+//! );
+//! ```
+
+use super::*;
+
+/// The category of a semantic token, used by editors to decide how to highlight it.
+///
+/// The categories correspond to the standard token types of the Language Server Protocol. Other token types can be
+/// given with [`Category::Custom`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Category {
+    /// A namespace, module, or package.
+    Namespace,
+    /// A type that isn't covered by a more specific category.
+    Type,
+    /// A parameter of a function or method.
+    Parameter,
+    /// A local or global variable.
+    Variable,
+    /// A member of a type, such as a field.
+    Property,
+    /// A function.
+    Function,
+    /// A member function of a type.
+    Method,
+    /// A macro.
+    Macro,
+    /// A keyword of the language.
+    Keyword,
+    /// A modifier, such as `pub` or `static`.
+    Modifier,
+    /// A comment.
+    Comment,
+    /// A string literal.
+    String,
+    /// A number literal.
+    Number,
+    /// An operator.
+    Operator,
+    /// A token type that isn't one of the standard ones, by name.
+    Custom(&'static str),
+}
+
+impl Category {
+    /// Get the name of this category's token type in the Language Server Protocol, such as `"keyword"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Namespace => "namespace",
+            Self::Type => "type",
+            Self::Parameter => "parameter",
+            Self::Variable => "variable",
+            Self::Property => "property",
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Macro => "macro",
+            Self::Keyword => "keyword",
+            Self::Modifier => "modifier",
+            Self::Comment => "comment",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Operator => "operator",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// See [`Parser::semantic_token`].
+#[derive(Copy, Clone)]
+pub struct SemanticToken<A> {
+    pub(crate) parser: A,
+    pub(crate) category: Category,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for SemanticToken<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let Some(tokens) = inp.semantic.as_deref_mut() else {
+            return self.parser.go::<M>(inp);
+        };
+        let before_count = tokens.len();
+        let before = inp.offset();
+        let res = self.parser.go::<M>(inp);
+        let span = inp.span_since(before);
+        if let Some(tokens) = inp.semantic.as_deref_mut() {
+            // Tokens can't overlap, so anything recorded within this one is discarded
+            tokens.truncate(before_count);
+            // Tokens that overlap the previous one can only be recorded by lookahead that is not rewound (such as
+            // `and_is`), and are dropped, as are empty tokens
+            let (start, end) = (before.offset.into(), inp.offset.into());
+            let overlaps = tokens
+                .last()
+                .map_or(false, |(_, prev_end, _)| start < *prev_end);
+            if res.is_ok() && !overlaps && end > start {
+                tokens.push((span, end, self.category));
+            }
+        }
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
@@ -0,0 +1,412 @@
+//! Integration with [`serde`](https://docs.rs/serde), for deserializing custom text formats.
+//!
+//! Defining a data format usually means writing both a parser for it and a [`serde::Deserializer`] that walks the
+//! parsed input. Instead, a chumsky parser that produces a generic [`Value`] tree can act as a deserializer directly,
+//! so a format only needs its grammar:
+//!
+//! - [`Deserializer`] wraps a parser and an input, parsing the input when a type is deserialized from it.
+//!
+//! - [`from_input`] parses an input and deserializes a type from it in one step.
+//!
+//! Values are interpreted in the same way as most self-describing formats (such as JSON): a [`Value::Map`] can be
+//! deserialized as a struct or a map, a [`Value::Seq`] as a sequence or a tuple, and [`Value::Unit`] as `None`.
+//! Enums are given either as a string naming a unit variant, or as a map with a single entry whose key names the
+//! variant.
+//!
+//! *This module requires the `serde` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, serde::{from_input, Value}};
+//! # use serde::Deserialize;
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct Config<'a> {
+//!     name: &'a str,
+//!     version: u32,
+//!     tags: Vec<String>,
+//!     license: Option<String>,
+//! }
+//!
+//! // A format of `key = value` lines
+//! let value = recursive(|value| {
+//!     choice((
+//!         text::int::<_, _, extra::Err<Rich<char>>>(10).from_str().unwrapped().map(Value::U64),
+//!         none_of('"').repeated().slice().delimited_by(just('"'), just('"')).map(Value::Str),
+//!         just("none").to(Value::Unit),
+//!         value
+//!             .separated_by(just(',').padded())
+//!             .collect()
+//!             .delimited_by(just('['), just(']'))
+//!             .map(Value::Seq),
+//!     ))
+//! });
+//! let config = text::ident()
+//!     .map(Value::Str)
+//!     .then_ignore(just('=').padded())
+//!     .then(value)
+//!     .separated_by(text::newline())
+//!     .allow_trailing()
+//!     .collect()
+//!     .map(Value::Map);
+//!
+//! let input = "name = \"chumsky\"\nversion = 1\ntags = [\"parser\", \"combinator\"]\nlicense = none\n";
+//! assert_eq!(
+//!     from_input::<Config, _, _, _>(&config, input),
+//!     Ok(Config {
+//!         name: "chumsky",
+//!         version: 1,
+//!         tags: vec!["parser".to_string(), "combinator".to_string()],
+//!         license: None,
+//!     }),
+//! );
+//! ```
+
+use super::*;
+use ::serde::de::{
+    self,
+    value::{MapDeserializer, SeqDeserializer, StrDeserializer, StringDeserializer},
+    DeserializeSeed, IntoDeserializer, Visitor,
+};
+use alloc::string::ToString;
+
+/// A generic tree of values, produced by a parser so that types can be deserialized from its input. See the
+/// [module documentation](self) for more information.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    /// The absence of a value, such as `null`.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating point number.
+    F64(f64),
+    /// A character.
+    Char(char),
+    /// A string borrowed from the input.
+    Str(&'a str),
+    /// A string that isn't part of the input, such as one that contained escape sequences.
+    String(String),
+    /// A sequence of values.
+    Seq(Vec<Value<'a>>),
+    /// A map from keys to values, in order.
+    Map(Vec<(Value<'a>, Value<'a>)>),
+}
+
+/// An error produced while deserializing a type from an input.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error<E> {
+    /// The input could not be parsed.
+    Parse(Vec<E>),
+    /// The input was parsed, but the type could not be deserialized from the resulting [`Value`].
+    Custom(String),
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(errs) => {
+                for (i, err) in errs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", err)?;
+                }
+                Ok(())
+            }
+            Self::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> de::StdError for Error<E> {}
+
+impl<E: fmt::Display + fmt::Debug> de::Error for Error<E> {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// A [`serde::Deserializer`] over a [`Value`], created with [`IntoDeserializer::into_deserializer`].
+pub struct ValueDeserializer<'a, E> {
+    value: Value<'a>,
+    phantom: PhantomData<E>,
+}
+
+impl<'a, E> IntoDeserializer<'a, Error<E>> for Value<'a>
+where
+    E: fmt::Display + fmt::Debug,
+{
+    type Deserializer = ValueDeserializer<'a, E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        ValueDeserializer {
+            value: self,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> de::Deserializer<'de> for ValueDeserializer<'de, E>
+where
+    E: fmt::Display + fmt::Debug,
+{
+    type Error = Error<E>;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Unit => visitor.visit_unit(),
+            Value::Bool(x) => visitor.visit_bool(x),
+            Value::I64(x) => visitor.visit_i64(x),
+            Value::U64(x) => visitor.visit_u64(x),
+            Value::F64(x) => visitor.visit_f64(x),
+            Value::Char(x) => visitor.visit_char(x),
+            Value::Str(x) => visitor.visit_borrowed_str(x),
+            Value::String(x) => visitor.visit_string(x),
+            Value::Seq(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let out = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(out)
+            }
+            Value::Map(entries) => {
+                let mut map = MapDeserializer::new(entries.into_iter());
+                let out = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(out)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Str(variant) => visitor.visit_enum(StrDeserializer::new(variant)),
+            Value::String(variant) => visitor.visit_enum(StringDeserializer::new(variant)),
+            Value::Map(entries) if entries.len() == 1 => {
+                let (variant, value) = entries.into_iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value,
+                    phantom: PhantomData,
+                })
+            }
+            _ => Err(de::Error::invalid_type(
+                self.unexpected(),
+                &"a string or a map with a single entry",
+            )),
+        }
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit unit_struct seq
+        tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<E> ValueDeserializer<'_, E> {
+    fn unexpected(&self) -> de::Unexpected<'_> {
+        match &self.value {
+            Value::Unit => de::Unexpected::Unit,
+            Value::Bool(x) => de::Unexpected::Bool(*x),
+            Value::I64(x) => de::Unexpected::Signed(*x),
+            Value::U64(x) => de::Unexpected::Unsigned(*x),
+            Value::F64(x) => de::Unexpected::Float(*x),
+            Value::Char(x) => de::Unexpected::Char(*x),
+            Value::Str(x) => de::Unexpected::Str(x),
+            Value::String(x) => de::Unexpected::Str(x),
+            Value::Seq(_) => de::Unexpected::Seq,
+            Value::Map(_) => de::Unexpected::Map,
+        }
+    }
+}
+
+// An enum variant given as a map with a single entry
+struct EnumDeserializer<'a, E> {
+    variant: Value<'a>,
+    value: Value<'a>,
+    phantom: PhantomData<E>,
+}
+
+impl<'de, E> de::EnumAccess<'de> for EnumDeserializer<'de, E>
+where
+    E: fmt::Display + fmt::Debug,
+{
+    type Error = Error<E>;
+    type Variant = ValueDeserializer<'de, E>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value.into_deserializer()))
+    }
+}
+
+impl<'de, E> de::VariantAccess<'de> for ValueDeserializer<'de, E>
+where
+    E: fmt::Display + fmt::Debug,
+{
+    type Error = Error<E>;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+/// A [`serde::Deserializer`] that parses an input into a [`Value`] with a parser, then deserializes a type from it.
+/// See the [module documentation](self) for more information.
+pub struct Deserializer<'p, P, I, E> {
+    parser: &'p P,
+    input: I,
+    phantom: PhantomData<E>,
+}
+
+impl<'p, P, I, E> Deserializer<'p, P, I, E> {
+    /// Create a deserializer that parses the given input with the given parser.
+    pub fn new(parser: &'p P, input: I) -> Self {
+        Self {
+            parser,
+            input,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, P, I, E> Deserializer<'_, P, I, E>
+where
+    I: Input<'de>,
+    E: ParserExtra<'de, I>,
+    E::Error: fmt::Display + fmt::Debug,
+    E::State: Default,
+    E::Context: Default,
+    P: Parser<'de, I, Value<'de>, E>,
+{
+    fn value(self) -> Result<ValueDeserializer<'de, E::Error>, Error<E::Error>> {
+        let value = self
+            .parser
+            .parse(self.input)
+            .into_result()
+            .map_err(Error::Parse)?;
+        Ok(value.into_deserializer())
+    }
+}
+
+// Parse the input, then forward to the value's deserializer
+macro_rules! forward_to_value {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+                de::Deserializer::$method(self.value()?, $($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, P, I, E> de::Deserializer<'de> for Deserializer<'_, P, I, E>
+where
+    I: Input<'de>,
+    E: ParserExtra<'de, I>,
+    E::Error: fmt::Display + fmt::Debug,
+    E::State: Default,
+    E::Context: Default,
+    P: Parser<'de, I, Value<'de>, E>,
+{
+    type Error = Error<E::Error>;
+
+    forward_to_value! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+}
+
+/// Parse an input into a [`Value`] with a parser, then deserialize a type from it.
+///
+/// This is a shorthand for deserializing from a [`Deserializer`].
+pub fn from_input<'de, T, P, I, E>(parser: &P, input: I) -> Result<T, Error<E::Error>>
+where
+    T: de::Deserialize<'de>,
+    I: Input<'de>,
+    E: ParserExtra<'de, I>,
+    E::Error: fmt::Display + fmt::Debug,
+    E::State: Default,
+    E::Context: Default,
+    P: Parser<'de, I, Value<'de>, E>,
+{
+    T::deserialize(Deserializer::new(parser, input))
+}
@@ -0,0 +1,707 @@
+//! Helpers for the state of a parser, given with [`extra::State`] and used by [`Parser::map_with_state`].
+//!
+//! Many languages have lexical scopes: names declared within a block are only visible until the end of that block.
+//! Parsers that keep track of declarations in their state (to resolve names, or to reject undeclared ones) need to
+//! enter a new scope at the start of each block, and leave it at the end. [`Parser::with_scope`] does this for any
+//! state that implements [`ScopeState`], leaving the scope even if the block fails to parse, so that a parser that
+//! backtracks out of a block (such as one branch of a [`choice`]) does not leave its scope behind.
+//!
+//! Note that changes made to the state within a scope are not undone when a parser backtracks: only the scope itself
+//! is left. To undo them too, wrap the parser that might backtrack with [`Parser::transactional`], which rolls back the
+//! changes made by a pattern that fails to parse, for any state that implements [`Rollback`].
+//!
+//! This module also provides state for the two tables that nearly every language frontend keeps: an [`Interner`], which
+//! turns identifiers into cheap [`Symbol`]s with [`Parser::intern`], and a [`SymbolTable`], which records the names
+//! declared in each scope with [`Parser::declare`] and looks them up with [`Parser::resolve`].
+//!
+//! # Combining state
+//!
+//! Large grammars often need several independent pieces of state, such as an interner, a symbol table, a list of
+//! warnings and some configuration. Rather than combining them into one struct, the state can be a tuple of them, and
+//! each combinator uses the component it needs: [`Parser::intern`] uses the [`Interner`], [`Parser::with_scope`] uses
+//! the component that has scopes, and so on. The component is chosen by its type (see [`Slot`]), or by its position in
+//! the tuple (with [`At`]) when more than one component could be used. Parsers like [`Parser::map_with_state`] are
+//! given the whole tuple.
+//!
+//! ```
+//! # use chumsky::{prelude::*, state::{At, Interner, Symbol, SymbolTable}};
+//! // An interner, the span at which each name was declared, and a list of warnings
+//! type State = (Interner, SymbolTable<Symbol, SimpleSpan>, Vec<String>);
+//!
+//! let name = text::ident::<_, _, extra::Full<Rich<char>, State, ()>>().intern();
+//! let decl = text::keyword("let")
+//!     .padded()
+//!     .ignore_then(name.map_with_span(|name, span| (name, span)))
+//!     .declare();
+//! let usage = name.resolve().try_map_with_state(|declared: SimpleSpan, span: SimpleSpan, state: &mut State| {
+//!     if declared.end + 10 < span.start {
+//!         state.2.push(format!("name declared at {declared} is far from its use at {span}"));
+//!     }
+//!     Ok(())
+//! });
+//! let block = recursive(|block| {
+//!     choice((decl.ignored(), usage, block))
+//!         .padded()
+//!         .repeated()
+//!         .delimited_by(just('{'), just('}'))
+//!         // Both the symbol table and the list of warnings have scopes (`Vec<String>` is a stack of them), so the
+//!         // symbol table must be chosen by its position
+//!         .with_scope::<At<1>>()
+//! });
+//!
+//! let mut state = State::default();
+//! assert!(!block.parse_with_state("{ let x { x } let y y x }", &mut state).has_errors());
+//! assert_eq!(state.0.len(), 2);
+//! assert_eq!(state.2, ["name declared at 6..7 is far from its use at 22..23"]);
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! // The names declared in each scope, innermost last
+//! type Scopes = Vec<Vec<String>>;
+//!
+//! // A block of statements, which are declarations (`let x;`), uses of names (`x;`), or inner blocks
+//! let block = recursive(|block| {
+//!     let decl = text::keyword("let")
+//!         .ignore_then(text::ident().padded())
+//!         .then_ignore(just(';'))
+//!         .try_map_with_state(|name: &str, _, scopes: &mut Scopes| {
+//!             scopes.last_mut().unwrap().push(name.to_string());
+//!             Ok(())
+//!         });
+//!     let usage = text::ident::<_, _, extra::Full<Rich<char>, Scopes, ()>>()
+//!         .then_ignore(just(';'))
+//!         .try_map_with_state(|name: &str, span, scopes: &mut Scopes| {
+//!             if scopes.iter().flatten().any(|declared| declared == name) {
+//!                 Ok(())
+//!             } else {
+//!                 Err(Rich::custom(span, format!("`{name}` is not declared")))
+//!             }
+//!         });
+//!     decl.or(usage)
+//!         .or(block)
+//!         .padded()
+//!         .repeated()
+//!         .delimited_by(just('{'), just('}'))
+//!         .with_scope()
+//! });
+//!
+//! let mut scopes = Scopes::new();
+//! assert!(!block.parse_with_state("{ let a; { let b; a; b; } a; }", &mut scopes).has_errors());
+//! assert!(scopes.is_empty());
+//!
+//! // `b` is not visible outside of the block that declares it
+//! let errs = block.parse_with_state("{ let a; { let b; } b; }", &mut scopes).into_errors();
+//! assert_eq!(errs[0].to_string(), "`b` is not declared");
+//! assert!(scopes.is_empty());
+//! ```
+
+use super::*;
+use core::borrow::BorrowMut;
+
+/// A parser state that has lexical scopes, which can be entered and left with [`Parser::with_scope`].
+///
+/// With the `alloc` feature, this is implemented for `Vec<T>`, which enters a scope by pushing `T::default()` (such as
+/// an empty table of the names declared within the scope) and leaves it by popping it.
+pub trait ScopeState {
+    /// Enter a new scope, nested within the current one.
+    fn push_scope(&mut self);
+
+    /// Leave the current scope, returning to the one it is nested within.
+    fn pop_scope(&mut self);
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Default> ScopeState for Vec<T> {
+    fn push_scope(&mut self) {
+        self.push(T::default());
+    }
+
+    fn pop_scope(&mut self) {
+        self.pop();
+    }
+}
+
+/// A parser state whose changes can be undone, so that [`Parser::transactional`] can roll back the changes made by a
+/// pattern that fails to parse.
+///
+/// With the `alloc` feature, this is implemented for `Vec<T>`, which rolls back by truncating the vector to the length
+/// it had at the checkpoint. This undoes items being pushed, but not changes to items that were already there.
+pub trait Rollback {
+    /// A record of the state at some point, which it can be rolled back to.
+    type Checkpoint;
+
+    /// Record the current state, so that later changes can be undone.
+    ///
+    /// Every checkpoint is later passed to either [`Rollback::rollback`] or [`Rollback::commit`], innermost first.
+    fn checkpoint(&mut self) -> Self::Checkpoint;
+
+    /// Undo the changes made since the checkpoint was taken.
+    fn rollback(&mut self, checkpoint: Self::Checkpoint);
+
+    /// Keep the changes made since the checkpoint was taken. They may still be undone by rolling back to an earlier
+    /// checkpoint.
+    ///
+    /// By default, this does nothing.
+    fn commit(&mut self, checkpoint: Self::Checkpoint) {
+        #![allow(unused_variables)]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Rollback for Vec<T> {
+    type Checkpoint = usize;
+
+    fn checkpoint(&mut self) -> Self::Checkpoint {
+        self.len()
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        self.truncate(checkpoint);
+    }
+}
+
+/// Selects the whole state of a parser as a [`Slot`], rather than one of its components.
+#[derive(Copy, Clone, Debug)]
+pub struct Whole;
+
+/// Selects the component of a tuple state at the given position as a [`Slot`].
+#[derive(Copy, Clone, Debug)]
+pub struct At<const N: usize>;
+
+/// A parser state that contains a component of type `T`, selected by `N`.
+///
+/// This is implemented for every state that implements [`BorrowMut<T>`](BorrowMut) (including `T` itself), selected
+/// by [`Whole`], and for tuples whose component at position `N` is a `T`, selected by [`At<N>`](At). The selector is
+/// inferred when there is only one component of type `T`. See the [module documentation](self) for more information.
+pub trait Slot<T, N> {
+    /// Get the component.
+    fn slot(&mut self) -> &mut T;
+}
+
+impl<T, S: BorrowMut<T>> Slot<T, Whole> for S {
+    fn slot(&mut self) -> &mut T {
+        self.borrow_mut()
+    }
+}
+
+/// A parser state that contains a component that implements [`ScopeState`], selected by `N`, for use with
+/// [`Parser::with_scope`].
+///
+/// This is implemented for every state that implements [`ScopeState`], selected by [`Whole`], and for tuples whose
+/// component at position `N` implements it, selected by [`At<N>`](At).
+pub trait ScopeSlot<N> {
+    /// The type of the component.
+    type Scope: ScopeState;
+
+    /// Get the component.
+    fn scope_slot(&mut self) -> &mut Self::Scope;
+}
+
+impl<S: ScopeState> ScopeSlot<Whole> for S {
+    type Scope = S;
+
+    fn scope_slot(&mut self) -> &mut S {
+        self
+    }
+}
+
+/// A parser state that contains a component that implements [`Rollback`], selected by `N`, for use with
+/// [`Parser::transactional`].
+///
+/// This is implemented for every state that implements [`Rollback`], selected by [`Whole`], and for tuples whose
+/// component at position `N` implements it, selected by [`At<N>`](At).
+pub trait RollbackSlot<N> {
+    /// The type of the component.
+    type Target: Rollback;
+
+    /// Get the component.
+    fn rollback_slot(&mut self) -> &mut Self::Target;
+}
+
+impl<S: Rollback> RollbackSlot<Whole> for S {
+    type Target = S;
+
+    fn rollback_slot(&mut self) -> &mut S {
+        self
+    }
+}
+
+macro_rules! impl_slots_for_tuple {
+    ($tuple:tt $($X:ident $n:tt)+) => {
+        $(impl_slots_for_tuple!(~ $tuple $X $n);)+
+    };
+    (~ ($($T:ident)+) $X:ident $n:tt) => {
+        impl<$($T),+> Slot<$X, At<$n>> for ($($T,)+) {
+            fn slot(&mut self) -> &mut $X {
+                &mut self.$n
+            }
+        }
+
+        impl<$($T),+> ScopeSlot<At<$n>> for ($($T,)+)
+        where
+            $X: ScopeState,
+        {
+            type Scope = $X;
+
+            fn scope_slot(&mut self) -> &mut $X {
+                &mut self.$n
+            }
+        }
+
+        impl<$($T),+> RollbackSlot<At<$n>> for ($($T,)+)
+        where
+            $X: Rollback,
+        {
+            type Target = $X;
+
+            fn rollback_slot(&mut self) -> &mut $X {
+                &mut self.$n
+            }
+        }
+    };
+}
+
+impl_slots_for_tuple!((A) A 0);
+impl_slots_for_tuple!((A B) A 0 B 1);
+impl_slots_for_tuple!((A B C) A 0 B 1 C 2);
+impl_slots_for_tuple!((A B C D) A 0 B 1 C 2 D 3);
+impl_slots_for_tuple!((A B C D E) A 0 B 1 C 2 D 3 E 4);
+impl_slots_for_tuple!((A B C D E F) A 0 B 1 C 2 D 3 E 4 F 5);
+impl_slots_for_tuple!((A B C D E F G) A 0 B 1 C 2 D 3 E 4 F 5 G 6);
+impl_slots_for_tuple!((A B C D E F G H) A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7);
+
+/// See [`Parser::with_scope`].
+#[derive(Copy, Clone)]
+pub struct WithScope<A, N> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<N>,
+}
+
+impl<'a, I, O, E, A, N> ParserSealed<'a, I, O, E> for WithScope<A, N>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: ScopeSlot<N>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        inp.state().scope_slot().push_scope();
+        let res = self.parser.go::<M>(inp);
+        inp.state().scope_slot().pop_scope();
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        self.parser.first_tokens()
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
+
+/// See [`Parser::transactional`].
+#[derive(Copy, Clone)]
+pub struct Transactional<A, N> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<N>,
+}
+
+impl<'a, I, O, E, A, N> ParserSealed<'a, I, O, E> for Transactional<A, N>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: RollbackSlot<N>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let checkpoint = inp.state().rollback_slot().checkpoint();
+        let res = self.parser.go::<M>(inp);
+        if res.is_ok() {
+            inp.state().rollback_slot().commit(checkpoint);
+        } else {
+            inp.state().rollback_slot().rollback(checkpoint);
+        }
+        res
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        self.parser.first_tokens()
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
+
+/// A string that has been interned by an [`Interner`]: a small identifier that can be cheaply copied and compared,
+/// which is the same for every occurrence of the same string.
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+#[cfg(feature = "alloc")]
+impl Symbol {
+    /// Get the index of this symbol, counting the strings in the order they were first interned.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A string interner, which gives each distinct string a [`Symbol`].
+///
+/// Identifiers are typically interned while parsing (with [`Parser::intern`]), so that later stages can compare them
+/// without comparing strings. The interner is kept in the state of the parser: either the state is an `Interner`, it
+/// implements [`BorrowMut<Interner>`](BorrowMut), or it is a tuple with an `Interner` component (see [`Slot`]).
+///
+/// *This type requires the `alloc` feature.*
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, state::Interner};
+/// let idents = text::ident::<_, _, extra::State<Interner>>()
+///     .intern()
+///     .padded()
+///     .repeated()
+///     .collect::<Vec<_>>();
+///
+/// let mut interner = Interner::new();
+/// let symbols = idents.parse_with_state("x y x", &mut interner).into_result().unwrap();
+/// assert_eq!(symbols[0], symbols[2]);
+/// assert_ne!(symbols[0], symbols[1]);
+/// assert_eq!(interner.resolve(symbols[1]), "y");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    symbols: HashMap<String, Symbol>,
+    strings: Vec<String>,
+}
+
+#[cfg(feature = "alloc")]
+impl Interner {
+    /// Create a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the symbol of a string, interning it if it has not been interned before.
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(s) {
+            return *symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        self.symbols.insert(s.into(), symbol);
+        self.strings.push(s.into());
+        symbol
+    }
+
+    /// Get the symbol of a string, if it has been interned.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).copied()
+    }
+
+    /// Get the string of a symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol was given by another interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.index()]
+    }
+
+    /// Get the number of strings that have been interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns true if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// See [`Parser::intern`].
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+pub struct Intern<A, O, N> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(O, N)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, O, E, A, N> ParserSealed<'a, I, Symbol, E> for Intern<A, O, N>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Slot<Interner, N>,
+    A: Parser<'a, I, O, E>,
+    O: AsRef<str>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Symbol> {
+        let out = self.parser.go::<Emit>(inp)?;
+        let symbol = inp.state().slot().intern(out.as_ref());
+        Ok(M::bind(|| symbol))
+    }
+
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O => Symbol);
+
+    go_extra!(Symbol);
+}
+
+/// A table of the names declared in each lexical scope, such as the variables of a block, and what they refer to.
+///
+/// Names are declared with [`Parser::declare`] in the innermost scope, and resolved with [`Parser::resolve`] by
+/// searching the scopes from the innermost outwards, so inner declarations shadow outer ones. Scopes are entered and
+/// left with [`Parser::with_scope`]. A new table has a single, global scope.
+///
+/// The table is kept in the state of the parser: either the state is a `SymbolTable`, it implements
+/// [`BorrowMut<SymbolTable>`](BorrowMut) (and [`ScopeState`], to be used with [`Parser::with_scope`]), or it is a
+/// tuple with a `SymbolTable` component (see [`Slot`]). Names are
+/// typically strings, or the [`Symbol`]s of an [`Interner`].
+///
+/// The table implements [`Rollback`], so declarations made by a parser that fails to parse can be undone with
+/// [`Parser::transactional`]. Declarations that replace an earlier one in the same scope are rolled back to the
+/// earlier one.
+///
+/// *This type requires the `alloc` feature.*
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, state::SymbolTable};
+/// // Each name refers to the offset at which it was declared
+/// type Table = SymbolTable<String, usize>;
+///
+/// let ident = text::ident::<_, _, extra::Full<Rich<char>, Table, ()>>().map(String::from);
+/// let decl = text::keyword("let")
+///     .ignore_then(ident.padded())
+///     .map_with_span(|name, span: SimpleSpan| (name, span.start))
+///     .declare();
+/// let usage = ident.resolve();
+/// let block = recursive(|block| {
+///     choice((decl.ignored(), usage.ignored(), block))
+///         .padded()
+///         .separated_by(just(';'))
+///         .allow_trailing()
+///         .padded()
+///         .delimited_by(just('{'), just('}'))
+///         .with_scope()
+/// });
+///
+/// let mut table = Table::new();
+/// assert!(!block.parse_with_state("{ let x; { let y; x; y }; x }", &mut table).has_errors());
+///
+/// // `y` can't be resolved outside of the block that declares it
+/// let errs = block.parse_with_state("{ { let y; }; y }", &mut table).into_errors();
+/// assert_eq!(errs[0].span().into_range(), 14..15);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct SymbolTable<K, V> {
+    scopes: Vec<HashMap<K, V>>,
+    // Each declaration in a scope that is still open, with its scope and the declaration it replaced, for rollback.
+    // Declarations are only recorded while there is a checkpoint to roll back to.
+    journal: Vec<(usize, K, Option<V>)>,
+    // The number of checkpoints that have been neither rolled back nor committed
+    checkpoints: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> Default for SymbolTable<K, V> {
+    fn default() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+            journal: Vec::new(),
+            checkpoints: 0,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Hash + Eq, V> SymbolTable<K, V> {
+    /// Create a new table, with an empty global scope.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a name in the innermost scope, replacing any declaration of it already in that scope.
+    pub fn declare(&mut self, name: K, value: V)
+    where
+        K: Clone,
+    {
+        if let Some(scope) = self.scopes.last_mut() {
+            if self.checkpoints == 0 {
+                scope.insert(name, value);
+            } else {
+                let replaced = scope.insert(name.clone(), value);
+                self.journal.push((self.scopes.len() - 1, name, replaced));
+            }
+        }
+    }
+
+    /// Find what a name refers to, in the innermost scope that declares it.
+    pub fn resolve<Q>(&self, name: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Get the number of scopes, including the global scope.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> ScopeState for SymbolTable<K, V> {
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+        // Declarations in a scope that has been left can no longer be rolled back
+        while matches!(self.journal.last(), Some((scope, _, _)) if *scope >= self.scopes.len()) {
+            self.journal.pop();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Hash + Eq, V> Rollback for SymbolTable<K, V> {
+    type Checkpoint = usize;
+
+    fn checkpoint(&mut self) -> Self::Checkpoint {
+        self.checkpoints += 1;
+        self.journal.len()
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        while self.journal.len() > checkpoint {
+            let Some((scope, name, replaced)) = self.journal.pop() else {
+                break;
+            };
+            if let Some(scope) = self.scopes.get_mut(scope) {
+                match replaced {
+                    Some(value) => scope.insert(name, value),
+                    None => scope.remove(&name),
+                };
+            }
+        }
+        self.checkpoints = self.checkpoints.saturating_sub(1);
+    }
+
+    fn commit(&mut self, _: Self::Checkpoint) {
+        self.checkpoints = self.checkpoints.saturating_sub(1);
+        // Nothing is left that could roll these declarations back
+        if self.checkpoints == 0 {
+            self.journal.clear();
+        }
+    }
+}
+
+/// See [`Parser::declare`].
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+pub struct Declare<A, K, V, N> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(K, V, N)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, E, A, K, V, N> ParserSealed<'a, I, K, E> for Declare<A, K, V, N>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Slot<SymbolTable<K, V>, N>,
+    A: Parser<'a, I, (K, V), E>,
+    K: Hash + Eq + Clone,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, K> {
+        let (name, value) = self.parser.go::<Emit>(inp)?;
+        inp.state().slot().declare(name.clone(), value);
+        Ok(M::bind(|| name))
+    }
+
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, (K, V), E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: (K, V));
+
+    gen_input!(parser: (K, V) => K);
+
+    go_extra!(K);
+}
+
+/// See [`Parser::resolve`].
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+pub struct Resolve<A, K, V, N> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(K, V, N)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, E, A, K, V, N> ParserSealed<'a, I, V, E> for Resolve<A, K, V, N>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Slot<SymbolTable<K, V>, N>,
+    A: Parser<'a, I, K, E>,
+    K: Hash + Eq,
+    V: Clone,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, V> {
+        let before = inp.offset();
+        let name = self.parser.go::<Emit>(inp)?;
+        let value = Slot::<SymbolTable<K, V>, N>::slot(inp.state())
+            .resolve(&name)
+            .cloned();
+        match value {
+            Some(value) => Ok(M::bind(|| value)),
+            None => {
+                let err = E::Error::custom(inp.span_since(before), "unresolved name");
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        }
+    }
+
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, K, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: K);
+
+    go_extra!(V);
+}
@@ -0,0 +1,235 @@
+//! Helpers for testing parsers.
+//!
+//! Grammar test suites tend to check the same few things over and over: that a parser accepts an input and produces
+//! a particular output, that it rejects an input with a particular error, or that it produces exactly a particular list
+//! of errors. This module provides assertions for each of these, which print every error the parser produced when they
+//! fail.
+//!
+//! - [`assert_parses!`] checks that a parser produces the expected output, without any errors.
+//! - [`assert_fails_with!`] checks that a parser produces an error with the given span, whose message contains the given
+//!   text.
+//! - [`assert_errors!`] checks that a parser produces exactly the expected error messages, in order, and shows a diff
+//!   of the expected and actual messages when they differ.
+//!
+//! # Examples
+//!
+//! ```
+//! use chumsky::{prelude::*, test::{assert_errors, assert_fails_with, assert_parses}};
+//!
+//! let list = text::int::<&str, _, extra::Err<Rich<char>>>(10)
+//!     .separated_by(just(','))
+//!     .collect::<Vec<_>>();
+//!
+//! assert_parses!(list, "1,2,3", vec!["1", "2", "3"]);
+//! assert_fails_with!(list, "1,x", 2..3, "found 'x'");
+//! assert_errors!(list, "1;2", ["found ';' expected ',', or end of input"]);
+//! ```
+
+use super::*;
+use alloc::string::ToString;
+use core::fmt::Write;
+
+/// An error that is associated with a span of the input, such as [`Cheap`], [`Simple`], or [`Rich`].
+///
+/// This is used by [`assert_fails_with!`] to check where an error occurred.
+pub trait SpannedError {
+    /// The type of span associated with the error.
+    type Span;
+
+    /// Get the span associated with this error.
+    fn span(&self) -> &Self::Span;
+}
+
+impl<S> SpannedError for Cheap<S> {
+    type Span = S;
+
+    fn span(&self) -> &S {
+        Cheap::span(self)
+    }
+}
+
+impl<'a, T, S> SpannedError for Simple<'a, T, S> {
+    type Span = S;
+
+    fn span(&self) -> &S {
+        Simple::span(self)
+    }
+}
+
+impl<'a, T, S, L> SpannedError for Rich<'a, T, S, L> {
+    type Span = S;
+
+    fn span(&self) -> &S {
+        Rich::span(self)
+    }
+}
+
+/// Assert that a parser successfully parses an input, producing the expected output and no errors.
+///
+/// On failure, this panics with a message listing the errors produced by the parser, or showing the expected and
+/// actual outputs. See the [module documentation](crate::test) for an example.
+#[macro_export]
+macro_rules! assert_parses {
+    ($parser:expr, $input:expr, $expected:expr $(,)?) => {
+        $crate::test::parses(&$parser, $input, $expected)
+    };
+}
+
+/// Assert that a parser produces an error with the given span, whose message contains the given text.
+///
+/// The span may be given as anything that converts into the span type of the error (such as a range, for
+/// [`SimpleSpan`]). On failure, this panics with a message listing the errors produced by the parser. See the
+/// [module documentation](crate::test) for an example.
+#[macro_export]
+macro_rules! assert_fails_with {
+    ($parser:expr, $input:expr, $span:expr, $msg_contains:expr $(,)?) => {
+        $crate::test::fails_with(&$parser, $input, $span, $msg_contains)
+    };
+}
+
+/// Assert that a parser produces exactly the expected list of error messages, in order.
+///
+/// On failure, this panics with a line-by-line diff of the expected and actual messages. See the
+/// [module documentation](crate::test) for an example.
+#[macro_export]
+macro_rules! assert_errors {
+    ($parser:expr, $input:expr, $expected:expr $(,)?) => {
+        $crate::test::errors(&$parser, $input, $expected)
+    };
+}
+
+pub use crate::{assert_errors, assert_fails_with, assert_parses};
+
+#[doc(hidden)]
+#[track_caller]
+pub fn parses<'a, P, I, O, E>(parser: &P, input: I, expected: O)
+where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    E::Error: fmt::Display,
+    O: PartialEq + fmt::Debug,
+{
+    let (output, errs) = parser.parse(input).into_output_errors();
+    if !errs.is_empty() {
+        panic!(
+            "expected the parser to succeed, but it produced {}",
+            describe(errs.iter().map(|err| err.to_string()))
+        );
+    }
+    let output = output.expect("parser produced neither an output nor any errors");
+    if output != expected {
+        panic!(
+            "the parser produced an unexpected output\n  expected: {:?}\n    actual: {:?}",
+            expected, output
+        );
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn fails_with<'a, P, I, O, E>(
+    parser: &P,
+    input: I,
+    span: impl Into<<E::Error as SpannedError>::Span>,
+    msg_contains: &str,
+) where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    E::Error: SpannedError + fmt::Display,
+    <E::Error as SpannedError>::Span: PartialEq + fmt::Debug,
+{
+    let span = span.into();
+    let errs = parser.parse(input).into_errors();
+    if !errs
+        .iter()
+        .any(|err| err.span() == &span && str::contains(&err.to_string(), msg_contains))
+    {
+        panic!(
+            "expected an error at {:?} containing {:?}, but the parser produced {}",
+            span,
+            msg_contains,
+            describe(
+                errs.iter()
+                    .map(|err| alloc::format!("{:?}: {}", err.span(), err))
+            )
+        );
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn errors<'a, 'b, P, I, O, E>(parser: &P, input: I, expected: impl IntoIterator<Item = &'b str>)
+where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    E::Error: fmt::Display,
+{
+    let actual = parser
+        .parse(input)
+        .into_errors()
+        .iter()
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>();
+    let expected = expected.into_iter().map(String::from).collect::<Vec<_>>();
+    if actual != expected {
+        panic!(
+            "the parser produced unexpected errors (- expected, + actual):\n{}",
+            diff(&expected, &actual)
+        );
+    }
+}
+
+// A numbered list of error messages, for panic messages
+fn describe(errs: impl Iterator<Item = String>) -> String {
+    let mut out = String::new();
+    for (i, err) in errs.enumerate() {
+        let _ = write!(out, "\n  {}. {}", i + 1, err);
+    }
+    if out.is_empty() {
+        "no errors".into()
+    } else {
+        alloc::format!("these errors:{}", out)
+    }
+}
+
+// A line-by-line diff of two lists, marking lines only in `expected` with `-` and lines only in `actual` with `+`
+fn diff(expected: &[String], actual: &[String]) -> String {
+    // The length of the longest common subsequence of each pair of suffixes of the lists
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            let _ = writeln!(out, "  {}", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            let _ = writeln!(out, "- {}", expected[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(out, "+ {}", actual[j]);
+            j += 1;
+        }
+    }
+    out
+}
@@ -10,6 +10,13 @@ use crate::prelude::*;
 
 use super::*;
 
+#[cfg(feature = "alloc")]
+use crate::input::Offset;
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
 /// A trait implemented by textual character types (currently, [`u8`] and [`char`]).
 ///
 /// This trait is currently sealed to minimise the impact of breaking changes. If you find a type that you think should
@@ -18,11 +25,18 @@ pub trait Char: Sized + Copy + PartialEq + Sealed + 'static {
     /// The default unsized [`str`]-like type of a linear sequence of this character.
     ///
     /// For [`char`], this is [`str`]. For [`u8`], this is [`[u8]`].
+    #[cfg(feature = "alloc")]
+    type Str: ?Sized + ToOwned + 'static;
+
+    /// The default unsized [`str`]-like type of a linear sequence of this character.
+    ///
+    /// For [`char`], this is [`str`]. For [`u8`], this is [`[u8]`].
+    #[cfg(not(feature = "alloc"))]
     type Str: ?Sized + 'static;
 
     /// The type of a regex expression which can match on this type
     #[cfg(feature = "regex")]
-    type Regex;
+    type Regex: Clone;
 
     #[cfg(feature = "regex")]
     #[doc(hidden)]
@@ -30,6 +44,16 @@ pub trait Char: Sized + Copy + PartialEq + Sealed + 'static {
     #[cfg(feature = "regex")]
     #[doc(hidden)]
     fn match_regex(regex: &Self::Regex, trailing: &Self::Str) -> Option<usize>;
+    #[cfg(feature = "regex")]
+    #[doc(hidden)]
+    fn regex_captures_len(regex: &Self::Regex) -> usize;
+    #[cfg(feature = "regex")]
+    #[doc(hidden)]
+    fn match_regex_captures(
+        regex: &Self::Regex,
+        trailing: &Self::Str,
+        groups: &mut [Option<Range<usize>>],
+    ) -> Option<usize>;
 
     /// Convert the given ASCII character to this character type.
     fn from_ascii(c: u8) -> Self;
@@ -37,6 +61,14 @@ pub trait Char: Sized + Copy + PartialEq + Sealed + 'static {
     /// Returns true if the character is canonically considered to be inline whitespace (i.e: not part of a newline).
     fn is_inline_whitespace(&self) -> bool;
 
+    /// Returns true if the character is whitespace that is not a line break (see [`inline_whitespace`]).
+    ///
+    /// Unlike [`Char::is_inline_whitespace`], this includes Unicode spaces (such as the no-break space) for [`char`].
+    fn is_horizontal_whitespace(&self) -> bool;
+
+    /// Returns true if the character is canonically considered to be a line break (see [`newline`]).
+    fn is_newline(&self) -> bool;
+
     /// Returns true if the character is canonically considered to be whitespace.
     fn is_whitespace(&self) -> bool;
 
@@ -48,6 +80,23 @@ pub trait Char: Sized + Copy + PartialEq + Sealed + 'static {
 
     /// Returns this character as a [`char`].
     fn to_char(&self) -> char;
+
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn push_str(buf: &mut <Self::Str as ToOwned>::Owned, s: &Self::Str);
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn push_char(buf: &mut <Self::Str as ToOwned>::Owned, c: char);
+    #[doc(hidden)]
+    fn last_line_indent(s: &Self::Str) -> usize;
+    #[doc(hidden)]
+    fn str_as_bytes(s: &Self::Str) -> &[u8];
+    // Whether the given byte is, on its own, a complete character of this type
+    #[doc(hidden)]
+    fn byte_is_char(byte: u8) -> bool;
+    // The first byte of the encoding of this character
+    #[doc(hidden)]
+    fn leading_byte(&self) -> u8;
 }
 
 impl Sealed for char {}
@@ -59,7 +108,8 @@ impl Char for char {
 
     #[cfg(feature = "regex")]
     fn new_regex(pattern: &str) -> Self::Regex {
-        ::regex::Regex::new(pattern).expect("Failed to compile regex")
+        // Anchor the pattern so that failing to match at the current position doesn't scan the rest of the input
+        ::regex::Regex::new(&alloc::format!("^(?:{pattern})")).expect("Failed to compile regex")
     }
     #[cfg(feature = "regex")]
     #[inline]
@@ -69,6 +119,25 @@ impl Char for char {
             .filter(|m| m.start() == 0)
             .map(|m| m.end())
     }
+    #[cfg(feature = "regex")]
+    fn regex_captures_len(regex: &Self::Regex) -> usize {
+        regex.captures_len()
+    }
+    #[cfg(feature = "regex")]
+    #[inline]
+    fn match_regex_captures(
+        regex: &Self::Regex,
+        trailing: &Self::Str,
+        groups: &mut [Option<Range<usize>>],
+    ) -> Option<usize> {
+        let captures = regex
+            .captures(trailing)
+            .filter(|c| c.get(0).unwrap().start() == 0)?;
+        for (i, group) in groups.iter_mut().enumerate() {
+            *group = captures.get(i + 1).map(|m| m.range());
+        }
+        Some(captures.get(0).unwrap().end())
+    }
 
     fn from_ascii(c: u8) -> Self {
         c as char
@@ -76,6 +145,21 @@ impl Char for char {
     fn is_inline_whitespace(&self) -> bool {
         *self == ' ' || *self == '\t'
     }
+    fn is_horizontal_whitespace(&self) -> bool {
+        char::is_whitespace(*self) && !self.is_newline()
+    }
+    fn is_newline(&self) -> bool {
+        [
+            '\n',       // Line feed
+            '\r',       // Carriage return
+            '\x0B',     // Vertical tab
+            '\x0C',     // Form feed
+            '\u{0085}', // Next line
+            '\u{2028}', // Line separator
+            '\u{2029}', // Paragraph separator
+        ]
+        .contains(self)
+    }
     fn is_whitespace(&self) -> bool {
         char::is_whitespace(*self)
     }
@@ -88,6 +172,31 @@ impl Char for char {
     fn to_char(&self) -> char {
         *self
     }
+    #[cfg(feature = "alloc")]
+    fn push_str(buf: &mut String, s: &str) {
+        buf.push_str(s);
+    }
+    #[cfg(feature = "alloc")]
+    fn push_char(buf: &mut String, c: char) {
+        buf.push(c);
+    }
+    fn last_line_indent(s: &str) -> usize {
+        s.rsplit(|c: char| c.is_newline())
+            .next()
+            .unwrap_or("")
+            .chars()
+            .take_while(|c| c.is_horizontal_whitespace())
+            .count()
+    }
+    fn str_as_bytes(s: &str) -> &[u8] {
+        s.as_bytes()
+    }
+    fn byte_is_char(byte: u8) -> bool {
+        byte.is_ascii()
+    }
+    fn leading_byte(&self) -> u8 {
+        self.encode_utf8(&mut [0; 4]).as_bytes()[0]
+    }
 }
 
 impl Sealed for u8 {}
@@ -99,7 +208,9 @@ impl Char for u8 {
 
     #[cfg(feature = "regex")]
     fn new_regex(pattern: &str) -> Self::Regex {
-        ::regex::bytes::Regex::new(pattern).expect("Failed to compile regex")
+        // Anchor the pattern so that failing to match at the current position doesn't scan the rest of the input
+        ::regex::bytes::Regex::new(&alloc::format!("^(?:{pattern})"))
+            .expect("Failed to compile regex")
     }
     #[cfg(feature = "regex")]
     #[inline]
@@ -109,6 +220,25 @@ impl Char for u8 {
             .filter(|m| m.start() == 0)
             .map(|m| m.end())
     }
+    #[cfg(feature = "regex")]
+    fn regex_captures_len(regex: &Self::Regex) -> usize {
+        regex.captures_len()
+    }
+    #[cfg(feature = "regex")]
+    #[inline]
+    fn match_regex_captures(
+        regex: &Self::Regex,
+        trailing: &Self::Str,
+        groups: &mut [Option<Range<usize>>],
+    ) -> Option<usize> {
+        let captures = regex
+            .captures(trailing)
+            .filter(|c| c.get(0).unwrap().start() == 0)?;
+        for (i, group) in groups.iter_mut().enumerate() {
+            *group = captures.get(i + 1).map(|m| m.range());
+        }
+        Some(captures.get(0).unwrap().end())
+    }
 
     fn from_ascii(c: u8) -> Self {
         c
@@ -116,8 +246,15 @@ impl Char for u8 {
     fn is_inline_whitespace(&self) -> bool {
         *self == b' ' || *self == b'\t'
     }
+    fn is_horizontal_whitespace(&self) -> bool {
+        self.is_inline_whitespace()
+    }
+    fn is_newline(&self) -> bool {
+        b"\n\r\x0B\x0C".contains(self)
+    }
     fn is_whitespace(&self) -> bool {
-        self.is_ascii_whitespace()
+        // Unlike `u8::is_ascii_whitespace`, this includes vertical tab, in agreement with `whitespace`
+        self.is_horizontal_whitespace() || self.is_newline()
     }
     fn digit_zero() -> Self {
         b'0'
@@ -128,6 +265,31 @@ impl Char for u8 {
     fn to_char(&self) -> char {
         *self as char
     }
+    #[cfg(feature = "alloc")]
+    fn push_str(buf: &mut Vec<u8>, s: &[u8]) {
+        buf.extend_from_slice(s);
+    }
+    #[cfg(feature = "alloc")]
+    fn push_char(buf: &mut Vec<u8>, c: char) {
+        buf.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+    fn last_line_indent(s: &[u8]) -> usize {
+        s.rsplit(|c| c.is_newline())
+            .next()
+            .unwrap_or(&[])
+            .iter()
+            .take_while(|c| c.is_horizontal_whitespace())
+            .count()
+    }
+    fn str_as_bytes(s: &[u8]) -> &[u8] {
+        s
+    }
+    fn byte_is_char(_byte: u8) -> bool {
+        true
+    }
+    fn leading_byte(&self) -> u8 {
+        *self
+    }
 }
 
 /// A parser that accepts (and ignores) any number of whitespace characters before or after another pattern.
@@ -150,11 +312,35 @@ where
         Ok(out)
     }
 
+    fn skip_leading_trivia(&self, inp: &mut InputRef<'a, '_, I, E>) {
+        inp.skip_while(|c| c.is_whitespace());
+    }
+
+    graph_node!(parser: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<O>, ()>
+    where
+        I::Token: Clone,
+    {
+        if gen.chance() {
+            gen.tokens.push(I::Token::from_ascii(b' '));
+        }
+        let out = ParserSealed::<I, O, E>::gen_input(&self.parser, gen)?;
+        if gen.chance() {
+            gen.tokens.push(I::Token::from_ascii(b' '));
+        }
+        Ok(out)
+    }
+
     go_extra!(O);
 }
 
 /// A parser that accepts (and ignores) any number of whitespace characters.
 ///
+/// This includes line breaks. See [`inline_whitespace`] and [`newline`] for grammars in which line breaks are
+/// significant.
+///
 /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
 ///
 /// The output type of this parser is `()`.
@@ -171,18 +357,23 @@ where
 /// assert_eq!(whitespace.parse("").into_result(), Ok(()));
 /// ```
 pub fn whitespace<'a, C: Char, I: ValueInput<'a> + StrInput<'a, C>, E: ParserExtra<'a, I>>(
-) -> Repeated<impl Parser<'a, I, (), E> + Copy + Clone, (), I, E>
+) -> Repeated<impl Parser<'a, I, (), E> + Copy, (), I, E>
 where
     I::Token: Char,
 {
     any()
-        .filter(|c: &I::Token| c.is_whitespace())
+        .filter(|c: &I::Token| c.is_horizontal_whitespace() || c.is_newline())
         .ignored()
         .repeated()
 }
 
 /// A parser that accepts (and ignores) any number of inline whitespace characters.
 ///
+/// Inline whitespace is any whitespace that is not a line break (as recognised by [`newline`]). For [`char`] inputs,
+/// this includes Unicode spaces such as the no-break space (`\u{00A0}`) and the ideographic space (`\u{3000}`).
+/// Together, this parser and [`newline`] accept exactly the characters accepted by [`whitespace`], making it easy to
+/// write line-oriented grammars in which newlines are significant.
+///
 /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
 ///
 /// The output type of this parser is `()`.
@@ -194,23 +385,322 @@ where
 /// let inline_whitespace = text::inline_whitespace::<_, _, extra::Err<Simple<char>>>();
 ///
 /// // Any amount of inline whitespace is parsed...
-/// assert_eq!(inline_whitespace.parse("\t  ").into_result(), Ok(()));
+/// assert_eq!(inline_whitespace.parse("\t  \u{3000}").into_result(), Ok(()));
 /// // ...including none at all!
 /// assert_eq!(inline_whitespace.parse("").into_result(), Ok(()));
 /// // ... but not newlines
 /// assert!(inline_whitespace.at_least(1).parse("\n\r").has_errors());
+/// assert!(inline_whitespace.at_least(1).parse("\u{2028}").has_errors());
+/// ```
+///
+/// A line-oriented grammar:
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let line = text::ident::<_, _, extra::Err<Simple<char>>>()
+///     .padded_by(text::inline_whitespace())
+///     .repeated()
+///     .collect::<Vec<_>>();
+///
+/// let lines = line.separated_by(text::newline()).collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     lines.parse("foo bar\r\n  baz\u{2028}").into_result(),
+///     Ok(vec![vec!["foo", "bar"], vec!["baz"], vec![]]),
+/// );
 /// ```
 pub fn inline_whitespace<'a, C: Char, I: ValueInput<'a> + StrInput<'a, C>, E: ParserExtra<'a, I>>(
-) -> Repeated<impl Parser<'a, I, (), E> + Copy + Clone, (), I, E>
+) -> Repeated<impl Parser<'a, I, (), E> + Copy, (), I, E>
 where
     I::Token: Char,
 {
     any()
-        .filter(|c: &I::Token| c.is_inline_whitespace())
+        .filter(|c: &I::Token| c.is_horizontal_whitespace())
         .ignored()
         .repeated()
 }
 
+/// A parser that accepts (and ignores) any amount of whitespace and comments, collectively known as 'trivia'.
+///
+/// By default, this parser behaves like [`whitespace`]. The returned [`Padding`] can be configured to also skip line
+/// comments (such as `// ...` or `# ...`) and block comments (such as `/* ... */`, optionally nested), or to skip
+/// only inline whitespace so that newlines remain significant. The result can be used with [`Parser::padded_by`] to
+/// allow trivia around a pattern.
+///
+/// An unterminated block comment produces an `unterminated block comment` error (created with [`Error::custom`])
+/// spanning the comment.
+///
+/// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// let padding = text::padding::<_, _, extra::Err<Rich<char>>>()
+///     .line_comment("//")
+///     .nested_block_comment("/*", "*/");
+///
+/// let ident = text::ident().padded_by(padding.clone());
+///
+/// assert_eq!(ident.parse("  foo  ").into_result(), Ok("foo"));
+/// assert_eq!(ident.parse("// comment\nfoo // another\n").into_result(), Ok("foo"));
+/// assert_eq!(ident.parse("/* a /* nested */ comment */ foo").into_result(), Ok("foo"));
+///
+/// let errs = ident.parse("foo /* /* */").into_errors();
+/// assert_eq!(errs[0].to_string(), "unterminated block comment");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(4..12));
+/// ```
+///
+/// SQL-style comments in a line-oriented grammar:
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let padding = text::padding::<_, _, extra::Err<Simple<char>>>()
+///     .inline()
+///     .line_comment("--")
+///     .block_comment("/*", "*/");
+///
+/// let line = text::ident().padded_by(padding).repeated().collect::<Vec<_>>();
+/// let lines = line.separated_by(text::newline()).collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     lines.parse("select /* all */ x -- the x column\nfrom y").into_result(),
+///     Ok(vec![vec!["select", "x"], vec!["from", "y"]]),
+/// );
+/// ```
+#[must_use]
+#[cfg(feature = "alloc")]
+pub fn padding<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> Padding<I, C, E> {
+    Padding {
+        inline: false,
+        line_comments: Vec::new(),
+        block_comments: Vec::new(),
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`padding`].
+#[cfg(feature = "alloc")]
+pub struct Padding<I, C, E> {
+    inline: bool,
+    line_comments: Vec<&'static str>,
+    // (open, close, nested)
+    block_comments: Vec<(&'static str, &'static str, bool)>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, C, E> Clone for Padding<I, C, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inline: self.inline,
+            line_comments: self.line_comments.clone(),
+            block_comments: self.block_comments.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I, C, E> Padding<I, C, E> {
+    /// Only skip inline whitespace (see [`inline_whitespace`]), leaving newlines to be parsed elsewhere.
+    ///
+    /// Line comments do not include the newline that ends them, so they may still be used in this mode.
+    pub fn inline(self) -> Self {
+        Self {
+            inline: true,
+            ..self
+        }
+    }
+
+    /// Skip line comments, which begin with `start` and continue until the end of the line.
+    pub fn line_comment(mut self, start: &'static str) -> Self {
+        self.line_comments.push(start);
+        self
+    }
+
+    /// Skip block comments, which begin with `open` and end with the first following `close`.
+    pub fn block_comment(mut self, open: &'static str, close: &'static str) -> Self {
+        self.block_comments.push((open, close, false));
+        self
+    }
+
+    /// Skip nested block comments, which begin with `open` and end with the matching `close` (i.e: `/* /* */ */` is
+    /// a single comment).
+    pub fn nested_block_comment(mut self, open: &'static str, close: &'static str) -> Self {
+        self.block_comments.push((open, close, true));
+        self
+    }
+}
+
+// Attempt to consume the given text, consuming nothing if it is not present
+#[cfg(feature = "alloc")]
+fn eat<'a, I, C, E>(inp: &mut InputRef<'a, '_, I, E>, s: &str) -> bool
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    let before = inp.save();
+    if s.chars()
+        .all(|c| inp.next().map_or(false, |i| i.to_char() == c))
+    {
+        true
+    } else {
+        inp.rewind(before);
+        false
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, C, E> ParserSealed<'a, I, (), E> for Padding<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        'trivia: loop {
+            if self.inline {
+                scan::skip_while_fast(inp, scan::ByteSet::INLINE_WHITESPACE, |c: &C| {
+                    c.is_horizontal_whitespace()
+                });
+            } else {
+                scan::skip_while_fast(inp, scan::ByteSet::WHITESPACE, |c: &C| {
+                    c.is_horizontal_whitespace() || c.is_newline()
+                });
+            }
+
+            for start in &self.line_comments {
+                if eat(inp, start) {
+                    scan::skip_until_fast(inp, scan::ByteSet::NEWLINE, |c: &C| c.is_newline());
+                    continue 'trivia;
+                }
+            }
+
+            for (open, close, nested) in &self.block_comments {
+                let before = inp.offset();
+                if eat(inp, open) {
+                    let mut depth = 1;
+                    while depth > 0 {
+                        if eat(inp, close) {
+                            depth -= 1;
+                        } else if *nested && eat(inp, open) {
+                            depth += 1;
+                        } else if inp.next().is_none() {
+                            let err = E::Error::custom(
+                                inp.span_since(before),
+                                "unterminated block comment",
+                            );
+                            inp.add_alt_err(inp.offset().offset, err);
+                            return Err(());
+                        }
+                    }
+                    continue 'trivia;
+                }
+            }
+
+            break Ok(M::bind(|| ()));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<()>, ()>
+    where
+        I::Token: Clone,
+    {
+        // Comments are never generated
+        if gen.chance() {
+            gen.tokens.push(C::from_ascii(b' '));
+        }
+        Ok(Some(()))
+    }
+
+    go_extra!(());
+}
+
+/// A destination for trivia recorded by [`Parser::record_trivia`].
+///
+/// This trait is implemented by [`TriviaLog`]. It can also be implemented by a custom parser state that needs to
+/// record trivia alongside other information.
+pub trait TriviaSink<T, S> {
+    /// Record a piece of trivia, along with its span.
+    ///
+    /// Because parsers may backtrack, the same trivia may be recorded more than once.
+    fn push_trivia(&mut self, trivia: T, span: S);
+}
+
+/// A log of the trivia (such as whitespace and comments) encountered during a parse, ordered by position.
+///
+/// Use this as the parser's state (see [`extra::State`]) along with [`Parser::record_trivia`] to preserve trivia that
+/// would otherwise be discarded, such as comments needed by a formatter or documentation generator. Trivia recorded
+/// more than once at the same position (because the parser backtracked) only appears in the log once.
+///
+/// Trivia recorded by a branch of the parser that later failed is still included in the log.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TriviaLog<T, S> {
+    trivia: Vec<(T, S)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T, S> Default for TriviaLog<T, S> {
+    fn default() -> Self {
+        Self { trivia: Vec::new() }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, S: Span> TriviaLog<T, S>
+where
+    S::Offset: Ord,
+{
+    /// Create a new, empty trivia log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the recorded trivia, paired with their spans, ordered by position.
+    pub fn trivia(&self) -> &[(T, S)] {
+        &self.trivia
+    }
+
+    /// Get the recorded trivia that start within the given range of offsets, ordered by position.
+    ///
+    /// This is useful for finding the comments that appear between two nodes of a syntax tree.
+    pub fn trivia_in(&self, range: Range<S::Offset>) -> &[(T, S)] {
+        let start = self
+            .trivia
+            .partition_point(|(_, s)| s.start() < range.start);
+        let end = self.trivia.partition_point(|(_, s)| s.start() < range.end);
+        &self.trivia[start..end.max(start)]
+    }
+
+    /// Convert this log into a [`Vec`] of trivia, paired with their spans, ordered by position.
+    pub fn into_vec(self) -> Vec<(T, S)> {
+        self.trivia
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, S: Span> TriviaSink<T, S> for TriviaLog<T, S>
+where
+    S::Offset: Ord,
+{
+    fn push_trivia(&mut self, trivia: T, span: S) {
+        match self
+            .trivia
+            .binary_search_by(|(_, s)| s.start().cmp(&span.start()))
+        {
+            Ok(i) => self.trivia[i] = (trivia, span),
+            Err(i) => self.trivia.insert(i, (trivia, span)),
+        }
+    }
+}
+
 /// A parser that accepts (and ignores) any newline characters or character sequences.
 ///
 /// The output type of this parser is `()`.
@@ -226,6 +716,8 @@ where
 /// - Line separator (`\u{2028}`)
 /// - Paragraph separator (`\u{2029}`)
 ///
+/// The last three are only recognised for [`char`] inputs, since they are not ASCII characters.
+///
 /// # Examples
 ///
 /// ```
@@ -242,151 +734,2347 @@ where
 /// assert_eq!(newline.parse("\u{2029}").into_result(), Ok(()));
 /// ```
 #[must_use]
-pub fn newline<'a, I: ValueInput<'a>, E: ParserExtra<'a, I>>(
-) -> impl Parser<'a, I, (), E> + Copy + Clone
+pub fn newline<'a, I: ValueInput<'a>, E: ParserExtra<'a, I>>() -> impl Parser<'a, I, (), E> + Copy
 where
     I::Token: Char,
 {
     just(I::Token::from_ascii(b'\r'))
-        .or_not()
-        .ignore_then(just(I::Token::from_ascii(b'\n')))
-        .or(any().filter(|c: &I::Token| {
-            [
-                '\r',       // Carriage return
-                '\x0B',     // Vertical tab
-                '\x0C',     // Form feed
-                '\u{0085}', // Next line
-                '\u{2028}', // Line separator
-                '\u{2029}', // Paragraph separator
-            ]
-            .contains(&c.to_char())
-        }))
+        .then(just(I::Token::from_ascii(b'\n')))
         .ignored()
+        .or(any().filter(|c: &I::Token| c.is_newline()).ignored())
 }
 
-/// A parser that accepts one or more ASCII digits.
+/// A parser that accepts a byte order mark (BOM), as found at the start of many files written by Windows tooling.
 ///
-/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
-/// when `I::Slice` is [`&[u8]`]).
+/// A UTF-8 BOM (`U+FEFF`, or the bytes `EF BB BF`) is accepted by both [`char`] and [`u8`] inputs. The UTF-16 BOMs
+/// (the bytes `FE FF` and `FF FE`) are only accepted by [`u8`] inputs, since they are not valid UTF-8.
 ///
-/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+/// A BOM is usually optional, so this parser is typically combined with [`Parser::or_not`] at the start of a grammar.
+/// To ignore a leading BOM without changing the grammar, use [`Input::skip_bom`] instead.
+///
+/// The output type of this parser is `()`.
 ///
 /// # Examples
 ///
 /// ```
 /// # use chumsky::prelude::*;
-/// let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).slice();
+/// let file = text::bom::<_, _, extra::Err<Simple<char>>>()
+///     .or_not()
+///     .ignore_then(just("[section]"));
 ///
-/// assert_eq!(digits.parse("0").into_result(), Ok("0"));
-/// assert_eq!(digits.parse("1").into_result(), Ok("1"));
-/// assert_eq!(digits.parse("01234").into_result(), Ok("01234"));
-/// assert_eq!(digits.parse("98345").into_result(), Ok("98345"));
-/// // A string of zeroes is still valid. Use `int` if this is not desirable.
-/// assert_eq!(digits.parse("0000").into_result(), Ok("0000"));
-/// assert!(digits.parse("").has_errors());
+/// assert_eq!(file.parse("\u{feff}[section]").into_result(), Ok("[section]"));
+/// assert_eq!(file.parse("[section]").into_result(), Ok("[section]"));
+///
+/// // UTF-16 BOMs can be found in byte inputs
+/// let bom = text::bom::<&[u8], _, extra::Err<Simple<u8>>>();
+///
+/// assert_eq!(bom.parse(b"\xEF\xBB\xBF".as_slice()).into_result(), Ok(()));
+/// assert_eq!(bom.parse(b"\xFF\xFE".as_slice()).into_result(), Ok(()));
+/// assert!(bom.parse(b"\xFF".as_slice()).has_errors());
 /// ```
 #[must_use]
-pub fn digits<'a, C, I, E>(radix: u32) -> Repeated<impl Parser<'a, I, C, E> + Copy + Clone, C, I, E>
+pub const fn bom<'a, I: StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>() -> Bom<I, C, E> {
+    Bom {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`bom`].
+pub struct Bom<I, C, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for Bom<I, C, E> {}
+impl<I, C, E> Clone for Bom<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, (), E> for Bom<I, C, E>
 where
+    I: StrInput<'a, C>,
     C: Char,
-    I: ValueInput<'a> + Input<'a, Token = C>,
     E: ParserExtra<'a, I>,
 {
-    any()
-        .filter(move |c: &C| c.is_digit(radix))
-        .repeated()
-        .at_least(1)
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        let before = inp.offset();
+        let bytes = C::str_as_bytes(inp.slice_trailing_inner());
+        // The input might be the start of a BOM that hasn't all arrived yet
+        if is_partial_bom(bytes) {
+            inp.reach_end();
+        }
+        match bom_len(bytes) {
+            0 => {
+                let (at, found) = inp.next_inner();
+                let err_span = inp.span_since(before);
+                inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                Err(())
+            }
+            len => {
+                inp.skip_bytes(len);
+                Ok(M::bind(|| ()))
+            }
+        }
+    }
+
+    go_extra!(());
 }
 
-/// A parser that accepts a non-negative integer.
-///
-/// An integer is defined as a non-empty sequence of ASCII digits, where the first digit is non-zero or the sequence
-/// has length one.
+// The length in bytes of the byte order mark at the start of `bytes`, or 0 if there isn't one
+pub(crate) fn bom_len(bytes: &[u8]) -> usize {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, ..] => 3,
+        [0xFE, 0xFF, ..] | [0xFF, 0xFE, ..] => 2,
+        _ => 0,
+    }
+}
+
+// Whether the bytes are the start of a BOM, but not all of it
+fn is_partial_bom(bytes: &[u8]) -> bool {
+    [&[0xEF, 0xBB, 0xBF][..], &[0xFE, 0xFF], &[0xFF, 0xFE]]
+        .iter()
+        .any(|bom| bom.len() > bytes.len() && bom.starts_with(bytes))
+}
+
+/// A parser that accepts an indentation-sensitive block of items, as found in Python, YAML, or Haskell.
 ///
-/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
-/// when `I::Slice` is [`&[u8]`]).
+/// This parser should be used immediately after the 'header' of a block (such as `if x:` in Python). It expects the
+/// rest of the header's line to be empty, followed by one or more lines that are each indented further than the header.
+/// Every item in the block must begin on its own line, at the same indentation as the first. The block ends when a
+/// line is indented no further than the header, or at the end of the input.
 ///
-/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+/// The indentation of the enclosing header line acts as the current indentation level, so no separate pass that
+/// inserts `INDENT`/`DEDENT` tokens is required, and blocks may be nested arbitrarily (i.e: by using this parser
+/// within a [`recursive()`] item parser). Indentation is measured by counting inline whitespace characters (see
+/// [`inline_whitespace`]), so a tab counts as a single level of indentation. Blank lines are ignored.
 ///
-/// # Examples
+/// The following errors are produced (created with [`Error::custom`]):
 ///
-/// ```
-/// # use chumsky::prelude::*;
-/// let dec = text::int::<_, _, extra::Err<Simple<char>>>(10);
+/// - `expected an indented block`, if the first line is indented no further than the header
+/// - `unexpected indentation`, if a line is indented further than the items of the block (and is not part of an item)
+/// - `unindent does not match any outer indentation level`, if a line is indented less than the items of the block,
+///   but further than the header
 ///
-/// assert_eq!(dec.parse("0").into_result(), Ok("0"));
-/// assert_eq!(dec.parse("1").into_result(), Ok("1"));
-/// assert_eq!(dec.parse("1452").into_result(), Ok("1452"));
-/// // No leading zeroes are permitted!
-/// assert!(dec.parse("04").has_errors());
+/// The output type of this parser is `Vec<O>`, where `O` is the output type of the item parser.
 ///
-/// let hex = text::int::<_, _, extra::Err<Simple<char>>>(16);
+/// # Examples
 ///
-/// assert_eq!(hex.parse("2A").into_result(), Ok("2A"));
-/// assert_eq!(hex.parse("d").into_result(), Ok("d"));
-/// assert_eq!(hex.parse("b4").into_result(), Ok("b4"));
-/// assert!(hex.parse("0B").has_errors());
 /// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// #[derive(Debug, PartialEq)]
+/// enum Stmt {
+///     Expr(String),
+///     Block(String, Vec<Stmt>),
+/// }
 ///
-#[must_use]
-pub fn int<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
-    radix: u32,
-) -> impl Parser<'a, I, &'a C::Str, E> + Copy + Clone {
-    any()
-        .filter(move |c: &C| c.is_digit(radix) && c != &C::digit_zero())
-        .map(Some)
-        .then(any().filter(move |c: &C| c.is_digit(radix)).repeated())
-        .ignored()
-        .or(just(C::digit_zero()).ignored())
-        .slice()
-}
-
-/// A parser that accepts a C-style identifier.
+/// let stmt = recursive(|stmt| {
+///     let block = text::ident::<_, _, extra::Err<Rich<char>>>()
+///         .then_ignore(just(':'))
+///         .then(text::indented_block(stmt))
+///         .map(|(name, body): (&str, _)| Stmt::Block(name.to_string(), body));
 ///
-/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
-/// [`u8`]).
+///     block.or(text::ident().map(|name: &str| Stmt::Expr(name.to_string())))
+/// });
 ///
-/// An identifier is defined as an ASCII alphabetic character or an underscore followed by any number of alphanumeric
-/// characters or underscores. The regex pattern for it is `[a-zA-Z_][a-zA-Z0-9_]*`.
-#[must_use]
-pub fn ident<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
-) -> impl Parser<'a, I, &'a C::Str, E> + Copy + Clone {
-    any()
-        .filter(|c: &C| c.to_char().is_ascii_alphabetic() || c.to_char() == '_')
-        .then(
-            any()
-                .filter(|c: &C| c.to_char().is_ascii_alphanumeric() || c.to_char() == '_')
-                .repeated(),
-        )
-        .slice()
-}
-
-// TODO: Better native form of semantic indentation that uses the context system?
-
-/// Like [`ident`], but only accepts a specific identifier while rejecting trailing identifier characters.
+/// let program = stmt
+///     .separated_by(text::newline().repeated().at_least(1))
+///     .allow_trailing()
+///     .collect::<Vec<_>>();
 ///
-/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
-/// when `I::Slice` is [`&[u8]`]).
+/// let source = "\
+/// if:
+///     a
+///     while:
+///         b
 ///
-/// # Examples
+///     c
+/// d
+/// ";
 ///
-/// ```
-/// # use chumsky::prelude::*;
-/// let def = text::keyword::<_, _, _, extra::Err<Simple<char>>>("def");
+/// assert_eq!(
+///     program.parse(source).into_result(),
+///     Ok(vec![
+///         Stmt::Block("if".to_string(), vec![
+///             Stmt::Expr("a".to_string()),
+///             Stmt::Block("while".to_string(), vec![Stmt::Expr("b".to_string())]),
+///             Stmt::Expr("c".to_string()),
+///         ]),
+///         Stmt::Expr("d".to_string()),
+///     ]),
+/// );
 ///
-/// // Exactly 'def' was found
-/// assert_eq!(def.parse("def").into_result(), Ok("def"));
-/// // Exactly 'def' was found, with non-identifier trailing characters
-/// // This works because we made the parser lazy: it parses 'def' and ignores the rest
-/// assert_eq!(def.clone().lazy().parse("def(foo, bar)").into_result(), Ok("def"));
-/// // 'def' was found, but only as part of a larger identifier, so this fails to parse
-/// assert!(def.lazy().parse("define").has_errors());
+/// let errs = program.parse("if:\n    a\n  b").into_errors();
+/// assert_eq!(errs[0].to_string(), "unindent does not match any outer indentation level");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(10..12));
+///
+/// let errs = program.parse("if:\na").into_errors();
+/// assert_eq!(errs[0].to_string(), "expected an indented block");
 /// ```
-pub fn keyword<
-    'a,
+#[must_use]
+#[cfg(feature = "alloc")]
+pub fn indented_block<'a, A, O, I, C, E>(item: A) -> IndentedBlock<A, O, C>
+where
     I: ValueInput<'a> + StrInput<'a, C>,
-    C: Char + 'a,
-    Str: AsRef<C::Str> + 'a + Clone,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    IndentedBlock {
+        item,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`indented_block`].
+#[cfg(feature = "alloc")]
+pub struct IndentedBlock<A, O, C> {
+    item: A,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(O, C)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<A: Copy, O, C> Copy for IndentedBlock<A, O, C> {}
+#[cfg(feature = "alloc")]
+impl<A: Clone, O, C> Clone for IndentedBlock<A, O, C> {
+    fn clone(&self) -> Self {
+        Self {
+            item: self.item.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+// Skip any blank lines, returning the indentation of the next non-blank line (along with the offset at which the line
+// begins) and leaving the input after the indentation. Returns `None` if only blank lines remain.
+#[cfg(feature = "alloc")]
+fn next_line<'a, 'parse, I, C, E>(
+    inp: &mut InputRef<'a, 'parse, I, E>,
+) -> Option<(usize, Offset<'a, 'parse, I>)>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    loop {
+        let line_start = inp.offset();
+        let mut indent = 0;
+        while inp.peek().map_or(false, |c| c.is_horizontal_whitespace()) {
+            inp.skip();
+            indent += 1;
+        }
+        match inp.peek() {
+            Some(c) if c.is_newline() => inp.skip(),
+            Some(_) => break Some((indent, line_start)),
+            None => break None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, A, O, I, C, E> ParserSealed<'a, I, Vec<O>, E> for IndentedBlock<A, O, C>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Vec<O>> {
+        // The current indentation level is that of the line containing the block's header
+        let header_indent = C::last_line_indent(inp.slice_inner(0..inp.offset().offset));
+
+        inp.skip_while(|c: &C| c.is_horizontal_whitespace());
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(c)) if c.is_newline() => {}
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(
+                    at,
+                    Some(Some(MaybeRef::Val(C::from_ascii(b'\n')))),
+                    found.map(|f| f.into()),
+                    err_span,
+                );
+                return Err(());
+            }
+        }
+
+        let indent = match next_line(inp) {
+            Some((indent, _)) if indent > header_indent => indent,
+            line => {
+                let before = line.map_or(inp.offset(), |(_, line_start)| line_start);
+                let (at, _) = inp.next_inner();
+                let err = E::Error::custom(inp.span_since(before), "expected an indented block");
+                inp.add_alt_err(at, err);
+                return Err(());
+            }
+        };
+
+        let mut out = M::bind(Vec::new);
+        loop {
+            let item = self.item.go::<M>(inp)?;
+            M::combine_mut(&mut out, item, |out, item| out.push(item));
+
+            // Find the indentation of the next line to determine whether the block continues
+            let after_item = inp.save();
+            inp.skip_while(|c: &C| c.is_horizontal_whitespace());
+            if !inp.peek().map_or(false, |c| c.is_newline()) {
+                inp.rewind(after_item);
+                break;
+            }
+            inp.skip();
+            let msg = match next_line(inp) {
+                Some((next_indent, _)) if next_indent == indent => continue,
+                Some((next_indent, _)) if next_indent <= header_indent => None,
+                None => None,
+                Some((next_indent, line_start)) => Some((
+                    line_start,
+                    if next_indent > indent {
+                        "unexpected indentation"
+                    } else {
+                        "unindent does not match any outer indentation level"
+                    },
+                )),
+            };
+            match msg {
+                Some((line_start, msg)) => {
+                    let err = E::Error::custom(inp.span_since(line_start), msg);
+                    inp.add_alt_err(inp.offset().offset, err);
+                    return Err(());
+                }
+                // The block has ended: leave the newline for the enclosing parser
+                None => {
+                    inp.rewind(after_item);
+                    break;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    graph_node!(item: O);
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<O>>, ()>
+    where
+        I::Token: Clone,
+    {
+        // Indent the block further than the line containing its header
+        let line_start = gen
+            .tokens
+            .iter()
+            .rposition(|c| c.is_newline())
+            .map_or(0, |i| i + 1);
+        let mut indent = gen.tokens[line_start..]
+            .iter()
+            .take_while(|c| c.is_horizontal_whitespace())
+            .copied()
+            .collect::<Vec<_>>();
+        indent.extend([C::from_ascii(b' '); 4]);
+
+        gen.repeat(1, u64::MAX, |gen, _| {
+            gen.tokens.push(C::from_ascii(b'\n'));
+            gen.tokens.extend(indent.iter().copied());
+            ParserSealed::<I, O, E>::gen_input(&self.item, gen)
+        })
+    }
+
+    go_extra!(Vec<O>);
+}
+
+/// A parser that accepts one or more ASCII digits.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]).
+///
+/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+///
+/// See [`radix_digits`] for a parser that outputs the digits' slice directly, reports the expected digits when none
+/// are found, and can produce the value of the digits.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).slice();
+///
+/// assert_eq!(digits.parse("0").into_result(), Ok("0"));
+/// assert_eq!(digits.parse("1").into_result(), Ok("1"));
+/// assert_eq!(digits.parse("01234").into_result(), Ok("01234"));
+/// assert_eq!(digits.parse("98345").into_result(), Ok("98345"));
+/// // A string of zeroes is still valid. Use `int` if this is not desirable.
+/// assert_eq!(digits.parse("0000").into_result(), Ok("0000"));
+/// assert!(digits.parse("").has_errors());
+/// ```
+#[must_use]
+pub fn digits<'a, C, I, E>(radix: u32) -> Repeated<impl Parser<'a, I, C, E> + Copy, C, I, E>
+where
+    C: Char,
+    I: ValueInput<'a> + Input<'a, Token = C>,
+    E: ParserExtra<'a, I>,
+{
+    any()
+        .filter(move |c: &C| c.is_digit(radix))
+        .repeated()
+        .at_least(1)
+}
+
+/// A parser that accepts one or more digits of the given radix, producing their slice.
+///
+/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+///
+/// The returned [`Digits`] can be limited to a range of lengths with [`Digits::at_least`], [`Digits::at_most`], and
+/// [`Digits::exactly`], and can produce the value of the digits via [`Digits::value`]. It is also an [`IterParser`]
+/// over the individual digits, so [`IterParser::collect`] may be used.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let digits = text::radix_digits::<_, _, extra::Err<Simple<char>>>(10);
+///
+/// assert_eq!(digits.parse("01234").into_result(), Ok("01234"));
+/// assert!(digits.parse("").has_errors());
+/// ```
+///
+/// Producing values:
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// // The 4 hexadecimal digits of a `\uXXXX` escape
+/// let code = text::radix_digits::<_, _, extra::Err<Rich<char>>>(16).exactly(4).value::<u32>();
+///
+/// assert_eq!(code.parse("00e9").into_result(), Ok(0xE9));
+/// assert!(code.parse("0e9").has_errors());
+///
+/// let byte = text::radix_digits::<_, _, extra::Err<Rich<char>>>(10).value::<u8>();
+///
+/// assert_eq!(byte.parse("255").into_result(), Ok(255));
+/// let errs = byte.parse("256").into_errors();
+/// assert_eq!(errs[0].to_string(), "integer literal is out of range");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(0..3));
+/// ```
+#[must_use]
+pub fn radix_digits<'a, C, I, E>(radix: u32) -> Digits<I, C, E>
+where
+    C: Char,
+    I: ValueInput<'a> + Input<'a, Token = C>,
+    E: ParserExtra<'a, I>,
+{
+    Digits {
+        radix,
+        at_least: 1,
+        at_most: usize::MAX,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`radix_digits`].
+pub struct Digits<I, C, E> {
+    radix: u32,
+    at_least: usize,
+    at_most: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for Digits<I, C, E> {}
+impl<I, C, E> Clone for Digits<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, C, E> Digits<I, C, E> {
+    /// Require at least a minimum number of digits. By default, at least one digit is required.
+    pub fn at_least(self, at_least: usize) -> Self {
+        Self { at_least, ..self }
+    }
+
+    /// Accept at most a maximum number of digits. Any further digits are left unparsed.
+    pub fn at_most(self, at_most: usize) -> Self {
+        Self { at_most, ..self }
+    }
+
+    /// Require an exact number of digits. Any further digits are left unparsed.
+    pub fn exactly(self, exactly: usize) -> Self {
+        Self {
+            at_least: exactly,
+            at_most: exactly,
+            ..self
+        }
+    }
+
+    /// Produce the value of the digits, rather than their slice.
+    ///
+    /// If the value does not fit within `T`, the parser fails with an error (created with [`Error::custom`]) that
+    /// spans all of the digits. See [`FromDigits`] for how to produce other numeric types.
+    pub fn value<T: FromDigits>(self) -> DigitsValue<T, I, C, E> {
+        DigitsValue {
+            digits: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    // Generate a random number of digits, without adding them to the input
+    #[cfg(feature = "alloc")]
+    fn gen_digits(&self, gen: &mut generate::Generator<C>) -> Vec<C>
+    where
+        C: Char,
+    {
+        (0..gen.repeats(self.at_least, self.at_most))
+            .map(|_| digit_char(gen.below(self.radix as usize) as u32, self.radix))
+            .collect()
+    }
+
+    // Attempt to parse another digit, generating an error if it is missing and required
+    fn next_digit<'a>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        count: usize,
+    ) -> Result<Option<C>, ()>
+    where
+        I: ValueInput<'a> + Input<'a, Token = C>,
+        C: Char,
+        E: ParserExtra<'a, I>,
+    {
+        if count >= self.at_most {
+            return Ok(None);
+        }
+        match inp.peek() {
+            Some(c) if c.is_digit(self.radix) => {
+                inp.skip();
+                Ok(Some(c))
+            }
+            _ if count >= self.at_least => Ok(None),
+            _ => {
+                let before = inp.save();
+                let (at, found) = inp.next_inner();
+                let err_span = inp.span_since(before.offset());
+                inp.rewind(before);
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_ranges_found(
+                        digit_ranges(self.radix),
+                        found.map(|f| f.into()),
+                        err_span,
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, I::Slice, E> for Digits<I, C, E>
+where
+    I: ValueInput<'a> + SliceInput<'a> + Input<'a, Token = C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Slice> {
+        let before = inp.offset().offset;
+        let mut count = 0;
+        while self.next_digit(inp, count)?.is_some() {
+            count += 1;
+        }
+        let after = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(before..after)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<I::Slice>, ()>
+    where
+        I::Token: Clone,
+    {
+        IterParserSealed::<I, C, E>::gen_items(self, gen)?;
+        Ok(None)
+    }
+
+    go_extra!(I::Slice);
+}
+
+impl<'a, I, C, E> IterParserSealed<'a, I, C, E> for Digits<I, C, E>
+where
+    I: ValueInput<'a> + Input<'a, Token = C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    type IterState<M: Mode> = usize;
+
+    #[inline(always)]
+    fn make_iter<M: Mode>(
+        &self,
+        _inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<Emit, Self::IterState<M>> {
+        Ok(0)
+    }
+
+    #[inline(always)]
+    fn next<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        count: &mut Self::IterState<M>,
+    ) -> IPResult<M, C> {
+        let digit = self.next_digit(inp, *count)?;
+        if digit.is_some() {
+            *count += 1;
+        }
+        Ok(digit.map(|c| M::bind(|| c)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_items(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<Vec<C>>, ()>
+    where
+        I::Token: Clone,
+    {
+        let digits = self.gen_digits(gen);
+        gen.tokens.extend(digits.iter().copied());
+        Ok(Some(digits))
+    }
+}
+
+/// See [`Digits::value`].
+pub struct DigitsValue<T, I, C, E> {
+    digits: Digits<I, C, E>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<T>,
+}
+
+impl<T, I, C, E> Copy for DigitsValue<T, I, C, E> {}
+impl<T, I, C, E> Clone for DigitsValue<T, I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, I, C, E> ParserSealed<'a, I, T, E> for DigitsValue<T, I, C, E>
+where
+    T: FromDigits,
+    I: ValueInput<'a> + Input<'a, Token = C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let before = inp.offset();
+        let radix = self.digits.radix;
+        // Overflow can only be detected by computing the value, so do so regardless of the mode
+        let mut value = Some(T::zero());
+        let mut count = 0;
+        while let Some(c) = self.digits.next_digit(inp, count)? {
+            count += 1;
+            value = value.and_then(|value| {
+                value.push_digit(c.to_char().to_digit(radix).unwrap(), radix, false)
+            });
+        }
+        match value {
+            Some(value) => Ok(M::bind(|| value)),
+            None => {
+                let err =
+                    E::Error::custom(inp.span_since(before), "integer literal is out of range");
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<T>, ()>
+    where
+        I::Token: Clone,
+    {
+        let radix = self.digits.radix;
+        // Retry until the value fits
+        let value = gen.filtered(
+            |gen| {
+                let digits = self.digits.gen_digits(gen);
+                gen.tokens.extend(digits.iter().copied());
+                Ok(Some(digits.iter().try_fold(T::zero(), |value, c| {
+                    value.push_digit(c.to_char().to_digit(radix).unwrap(), radix, false)
+                })))
+            },
+            Option::is_some,
+        )?;
+        Ok(value.flatten())
+    }
+
+    go_extra!(T);
+}
+
+/// A parser that accepts a non-negative integer.
+///
+/// An integer is defined as a non-empty sequence of ASCII digits, where the first digit is non-zero or the sequence
+/// has length one.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]).
+///
+/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+///
+/// The returned [`Int`] can be configured to accept signs, radix prefixes (`0x`, `0o`, `0b`), digit separators (`_`),
+/// and leading zeroes. It can also produce the value of the integer rather than a slice, via [`Int::value`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let dec = text::int::<_, _, extra::Err<Simple<char>>>(10);
+///
+/// assert_eq!(dec.parse("0").into_result(), Ok("0"));
+/// assert_eq!(dec.parse("1").into_result(), Ok("1"));
+/// assert_eq!(dec.parse("1452").into_result(), Ok("1452"));
+/// // No leading zeroes are permitted!
+/// assert!(dec.parse("04").has_errors());
+///
+/// let hex = text::int::<_, _, extra::Err<Simple<char>>>(16);
+///
+/// assert_eq!(hex.parse("2A").into_result(), Ok("2A"));
+/// assert_eq!(hex.parse("d").into_result(), Ok("d"));
+/// assert_eq!(hex.parse("b4").into_result(), Ok("b4"));
+/// assert!(hex.parse("0B").has_errors());
+/// ```
+///
+/// A more permissive integer, like those found in many programming languages:
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+///     .signed()
+///     .with_prefixes()
+///     .separators();
+///
+/// assert_eq!(int.parse("-1_000").into_result(), Ok("-1_000"));
+/// assert_eq!(int.parse("0xFF_FF").into_result(), Ok("0xFF_FF"));
+/// assert_eq!(int.parse("+0b1010").into_result(), Ok("+0b1010"));
+/// assert_eq!(int.parse("0").into_result(), Ok("0"));
+/// assert!(int.parse("05").has_errors());
+/// assert!(int.parse("1_").has_errors());
+///
+/// let byte = int.value::<i8>();
+///
+/// assert_eq!(byte.parse("-0x80").into_result(), Ok(-128));
+/// assert_eq!(byte.parse("1_2_7").into_result(), Ok(127));
+/// let errs = byte.parse("128").into_errors();
+/// assert_eq!(errs[0].to_string(), "integer literal is out of range");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(0..3));
+/// ```
+#[must_use]
+pub fn int<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+    radix: u32,
+) -> Int<I, C, E> {
+    Int {
+        radix,
+        prefixes: false,
+        signed: false,
+        separators: false,
+        leading_zeros: false,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`int`].
+pub struct Int<I, C, E> {
+    radix: u32,
+    prefixes: bool,
+    signed: bool,
+    separators: bool,
+    leading_zeros: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for Int<I, C, E> {}
+impl<I, C, E> Clone for Int<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, C, E> Int<I, C, E> {
+    /// Accept an optional leading sign (`+` or `-`).
+    pub fn signed(self) -> Self {
+        Self {
+            signed: true,
+            ..self
+        }
+    }
+
+    /// Accept the radix prefixes `0x` (hexadecimal), `0o` (octal), and `0b` (binary), in either case. When a prefix is
+    /// present, it overrides the radix given to [`int`].
+    pub fn with_prefixes(self) -> Self {
+        Self {
+            prefixes: true,
+            ..self
+        }
+    }
+
+    /// Accept underscores (`_`) as digit separators after the first digit, as in `1_000_000`. Each separator must be
+    /// followed by a digit, so `1_` and `1__0` are rejected.
+    pub fn separators(self) -> Self {
+        Self {
+            separators: true,
+            ..self
+        }
+    }
+
+    /// Accept leading zeroes, as in `007`. By default, an integer starting with `0` ends immediately after it
+    /// (unless a radix prefix is used).
+    pub fn leading_zeros(self) -> Self {
+        Self {
+            leading_zeros: true,
+            ..self
+        }
+    }
+
+    /// Produce the value of the integer, rather than its slice.
+    ///
+    /// If the integer does not fit within `T`, the parser fails with an error (created with [`Error::custom`]) that
+    /// spans the entire literal.
+    pub fn value<T: FromDigits>(self) -> IntValue<T, I, C, E> {
+        IntValue {
+            int: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    // Parse the integer, passing each digit (along with the radix, and whether the integer is negative) to `digit`.
+    fn scan<'a, F>(&self, inp: &mut InputRef<'a, '_, I, E>, mut digit: F) -> Result<(), ()>
+    where
+        I: StrInput<'a, C>,
+        C: Char,
+        E: ParserExtra<'a, I>,
+        F: FnMut(u32, u32, bool),
+    {
+        let mut negative = false;
+        if self.signed {
+            match inp.peek() {
+                Some(c) if c == C::from_ascii(b'-') => {
+                    inp.skip();
+                    negative = true;
+                }
+                Some(c) if c == C::from_ascii(b'+') => inp.skip(),
+                _ => {}
+            }
+        }
+
+        let mut radix = self.radix;
+        let mut prefixed = false;
+        if self.prefixes {
+            let before = inp.save();
+            let prefix = if inp.next() == Some(C::digit_zero()) {
+                match inp.next().map(|c| c.to_char()) {
+                    Some('x' | 'X') => Some(16),
+                    Some('o' | 'O') => Some(8),
+                    Some('b' | 'B') => Some(2),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            match prefix {
+                Some(prefix) => {
+                    radix = prefix;
+                    prefixed = true;
+                }
+                None => inp.rewind(before),
+            }
+        }
+
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(c)) if c.is_digit(radix) => {
+                digit(c.to_char().to_digit(radix).unwrap(), radix, negative);
+                if c == C::digit_zero() && !prefixed && !self.leading_zeros {
+                    return Ok(());
+                }
+            }
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_ranges_found(
+                        digit_ranges(radix),
+                        found.map(|f| f.into()),
+                        err_span,
+                    ),
+                );
+                return Err(());
+            }
+        }
+
+        // Whether the previous character was a separator, which must be followed by a digit
+        let mut separated = false;
+        loop {
+            match inp.peek() {
+                Some(c) if c.is_digit(radix) => {
+                    inp.skip();
+                    digit(c.to_char().to_digit(radix).unwrap(), radix, negative);
+                    separated = false;
+                }
+                Some(c) if self.separators && !separated && c == C::from_ascii(b'_') => {
+                    inp.skip();
+                    separated = true;
+                }
+                _ if separated => {
+                    let before = inp.offset();
+                    let (at, found) = inp.next_inner();
+                    let err_span = inp.span_since(before);
+                    inp.add_alt_err(
+                        at,
+                        E::Error::expected_ranges_found(
+                            digit_ranges(radix),
+                            found.map(|f| f.into()),
+                            err_span,
+                        ),
+                    );
+                    break Err(());
+                }
+                _ => break Ok(()),
+            }
+        }
+    }
+
+    // Generate a random integer, passing each digit (along with the radix, and whether the integer is negative) to
+    // `digit`
+    #[cfg(feature = "alloc")]
+    fn gen_scan<F>(&self, gen: &mut generate::Generator<C>, mut digit: F)
+    where
+        C: Char,
+        F: FnMut(u32, u32, bool),
+    {
+        let mut negative = false;
+        if self.signed && gen.chance() {
+            negative = gen.chance();
+            gen.tokens
+                .push(C::from_ascii(if negative { b'-' } else { b'+' }));
+        }
+
+        let mut radix = self.radix;
+        let mut prefixed = false;
+        if self.prefixes && gen.chance() {
+            let (prefix, prefix_radix) = gen.pick([(b'x', 16), (b'o', 8), (b'b', 2)]).unwrap();
+            gen.tokens.extend([C::digit_zero(), C::from_ascii(prefix)]);
+            radix = prefix_radix;
+            prefixed = true;
+        }
+
+        let count = gen.repeats(1, usize::MAX);
+        for i in 0..count {
+            if i > 0 && self.separators && gen.chance() {
+                gen.tokens.push(C::from_ascii(b'_'));
+            }
+            let mut value = gen.below(radix as usize) as u32;
+            // Without a prefix, a leading zero is an integer on its own
+            if i == 0 && count > 1 && !prefixed && !self.leading_zeros {
+                value = 1 + gen.below(radix as usize - 1) as u32;
+            }
+            gen.tokens.push(digit_char(value, radix));
+            digit(value, radix, negative);
+        }
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, &'a C::Str, E> for Int<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset().offset;
+        self.scan(inp, |_, _, _| {})?;
+        let after = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(before..after)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<&'a C::Str>, ()>
+    where
+        I::Token: Clone,
+    {
+        self.gen_scan(gen, |_, _, _| {});
+        Ok(None)
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+/// See [`Int::value`].
+pub struct IntValue<T, I, C, E> {
+    int: Int<I, C, E>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<T>,
+}
+
+impl<T, I, C, E> Copy for IntValue<T, I, C, E> {}
+impl<T, I, C, E> Clone for IntValue<T, I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, I, C, E> ParserSealed<'a, I, T, E> for IntValue<T, I, C, E>
+where
+    T: FromDigits,
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let before = inp.offset();
+        // Overflow can only be detected by computing the value, so do so regardless of the mode
+        let mut value = Some(T::zero());
+        self.int.scan(inp, |digit, radix, negative| {
+            value = value
+                .take()
+                .and_then(|value| value.push_digit(digit, radix, negative));
+        })?;
+        match value {
+            Some(value) => Ok(M::bind(|| value)),
+            None => {
+                let err =
+                    E::Error::custom(inp.span_since(before), "integer literal is out of range");
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<T>, ()>
+    where
+        I::Token: Clone,
+    {
+        // Retry until the value fits
+        let value = gen.filtered(
+            |gen| {
+                let mut value = Some(T::zero());
+                self.int.gen_scan(gen, |digit, radix, negative| {
+                    value = value
+                        .take()
+                        .and_then(|value| value.push_digit(digit, radix, negative));
+                });
+                Ok(Some(value))
+            },
+            Option::is_some,
+        )?;
+        Ok(value.flatten())
+    }
+
+    go_extra!(T);
+}
+
+/// A numeric type that can be built up from a sequence of digits. See [`Int::value`].
+///
+/// This trait is implemented for all of Rust's primitive integer types, and may be implemented for other types (such
+/// as arbitrary-precision integers) to allow [`int`] to produce them.
+///
+/// With the `num-bigint` feature enabled, this trait is implemented for `BigInt` and `BigUint`, which never overflow.
+/// With the `rust_decimal` feature enabled, this trait is implemented for `Decimal`, which supports integers of up to
+/// 96 bits.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// # #[cfg(feature = "num-bigint")]
+/// # {
+/// use num_bigint::BigInt;
+///
+/// let int = text::int::<_, _, extra::Err<Rich<char>>>(10).signed().value::<BigInt>();
+///
+/// assert_eq!(
+///     int.parse("-123456789012345678901234567890").into_result(),
+///     Ok("-123456789012345678901234567890".parse::<BigInt>().unwrap()),
+/// );
+/// # }
+/// ```
+pub trait FromDigits: Sized {
+    /// The value zero, before any digits have been added.
+    fn zero() -> Self;
+
+    /// Append a digit (i.e: `self * radix + digit`), returning `None` if the result does not fit within this type.
+    ///
+    /// For negative integers, `negative` is `true` and the digit should be subtracted instead (i.e:
+    /// `self * radix - digit`), allowing the full range of signed types to be represented.
+    fn push_digit(self, digit: u32, radix: u32, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_from_digits {
+    ($($T:ty),*) => {
+        $(
+            impl FromDigits for $T {
+                #[inline]
+                fn zero() -> Self {
+                    0
+                }
+
+                #[inline]
+                fn push_digit(self, digit: u32, radix: u32, negative: bool) -> Option<Self> {
+                    let digit = <$T>::try_from(digit).ok()?;
+                    let shifted = self.checked_mul(<$T>::try_from(radix).ok()?)?;
+                    if negative {
+                        shifted.checked_sub(digit)
+                    } else {
+                        shifted.checked_add(digit)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_digits!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(feature = "num-bigint")]
+impl FromDigits for num_bigint::BigInt {
+    #[inline]
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn push_digit(self, digit: u32, radix: u32, negative: bool) -> Option<Self> {
+        let shifted = self * radix;
+        Some(if negative {
+            shifted - digit
+        } else {
+            shifted + digit
+        })
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl FromDigits for num_bigint::BigUint {
+    #[inline]
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn push_digit(self, digit: u32, radix: u32, negative: bool) -> Option<Self> {
+        let shifted = self * radix;
+        if negative {
+            // Only `-0` is representable
+            (digit == 0 && shifted == Self::default()).then_some(shifted)
+        } else {
+            Some(shifted + digit)
+        }
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl FromDigits for rust_decimal::Decimal {
+    #[inline]
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    #[inline]
+    fn push_digit(self, digit: u32, radix: u32, negative: bool) -> Option<Self> {
+        let shifted = self.checked_mul(Self::from(radix))?;
+        if negative {
+            shifted.checked_sub(Self::from(digit))
+        } else {
+            shifted.checked_add(Self::from(digit))
+        }
+    }
+}
+
+// The ranges of characters that are digits in the given radix
+// The character of the given digit, in the given radix
+#[cfg(feature = "alloc")]
+fn digit_char<C: Char>(digit: u32, radix: u32) -> C {
+    C::from_ascii(char::from_digit(digit, radix).unwrap() as u8)
+}
+
+fn digit_ranges<'a, C: Char>(
+    radix: u32,
+) -> impl Iterator<Item = (MaybeRef<'a, C>, MaybeRef<'a, C>)> {
+    let range = |from: u8, len: u32| {
+        (
+            MaybeRef::Val(C::from_ascii(from)),
+            MaybeRef::Val(C::from_ascii(from + len as u8 - 1)),
+        )
+    };
+    // Produced lazily, so that error types which discard the ranges don't allocate
+    let letters = (radix > 10).then(|| [range(b'a', radix - 10), range(b'A', radix - 10)]);
+    core::iter::once(range(b'0', radix.min(10))).chain(letters.into_iter().flatten())
+}
+
+/// A parser that accepts a floating-point number.
+///
+/// By default, a float is a non-empty sequence of decimal digits, optionally followed by a fractional part (a `.` and
+/// any number of digits) and an exponent (`e` or `E`, an optional sign, and one or more digits).
+///
+/// The parser takes care not to consume characters that likely belong to a following token: a `.` is only consumed if
+/// it is not followed by another `.` or an identifier character (so `1..2` and `1.max(2)` parse `1`), and an exponent
+/// marker is only consumed if it is followed by digits (so `2else` parses `2`).
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]).
+///
+/// The returned [`Float`] can be configured to accept signs, digit separators (`_`), and `inf`/`nan`, or to reject
+/// integers. It can also produce the value of the float rather than a slice, via [`Float::value`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let float = text::float::<_, _, extra::Err<Simple<char>>>();
+///
+/// assert_eq!(float.parse("42").into_result(), Ok("42"));
+/// assert_eq!(float.parse("3.14").into_result(), Ok("3.14"));
+/// assert_eq!(float.parse("1.").into_result(), Ok("1."));
+/// assert_eq!(float.parse("6.02e23").into_result(), Ok("6.02e23"));
+/// assert_eq!(float.parse("1E-9").into_result(), Ok("1E-9"));
+/// assert!(float.parse(".5").has_errors());
+///
+/// // Trailing characters that belong to other tokens are left alone
+/// assert_eq!(float.lazy().parse("1..2").into_result(), Ok("1"));
+/// assert_eq!(float.lazy().parse("1.max(2)").into_result(), Ok("1"));
+/// assert_eq!(float.lazy().parse("2else").into_result(), Ok("2"));
+/// ```
+///
+/// Producing values:
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let float = text::float::<_, _, extra::Err<Simple<char>>>()
+///     .signed()
+///     .separators()
+///     .inf_nan()
+///     .value::<f64>();
+///
+/// assert_eq!(float.parse("-1_000.5").into_result(), Ok(-1000.5));
+/// assert_eq!(float.parse("2.5e-3").into_result(), Ok(0.0025));
+/// assert_eq!(float.parse("-inf").into_result(), Ok(f64::NEG_INFINITY));
+/// assert!(float.parse("nan").into_result().unwrap().is_nan());
+/// // Not `inf`, but the start of an identifier
+/// assert!(float.parse("info").has_errors());
+///
+/// // Integers can be rejected when they should be lexed separately
+/// let strict = text::float::<_, _, extra::Err<Simple<char>>>().require_fraction();
+///
+/// assert_eq!(strict.parse("1e3").into_result(), Ok("1e3"));
+/// assert!(strict.parse("1").has_errors());
+/// ```
+#[must_use]
+pub fn float<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> Float<I, C, E> {
+    Float {
+        signed: false,
+        separators: false,
+        inf_nan: false,
+        require_fraction: false,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`float`].
+pub struct Float<I, C, E> {
+    signed: bool,
+    separators: bool,
+    inf_nan: bool,
+    require_fraction: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for Float<I, C, E> {}
+impl<I, C, E> Clone for Float<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, C, E> Float<I, C, E> {
+    /// Accept an optional leading sign (`+` or `-`).
+    pub fn signed(self) -> Self {
+        Self {
+            signed: true,
+            ..self
+        }
+    }
+
+    /// Accept underscores (`_`) as digit separators after the first digit of the integer part, fractional part, or
+    /// exponent, as in `1_000.000_1`.
+    pub fn separators(self) -> Self {
+        Self {
+            separators: true,
+            ..self
+        }
+    }
+
+    /// Accept `inf` and `nan` (in any case), provided they are not the start of a longer identifier.
+    pub fn inf_nan(self) -> Self {
+        Self {
+            inf_nan: true,
+            ..self
+        }
+    }
+
+    /// Require a fractional part or an exponent, rejecting plain integers such as `42`. This is useful when integers
+    /// and floats are distinct tokens.
+    pub fn require_fraction(self) -> Self {
+        Self {
+            require_fraction: true,
+            ..self
+        }
+    }
+
+    /// Produce the value of the float, rather than its slice.
+    ///
+    /// The value is produced via [`FromStr`] after removing any digit separators, so any type that accepts Rust's
+    /// float syntax (such as [`f32`] and [`f64`]) may be used. If conversion fails, the parser fails with an error
+    /// (created with [`Error::custom`]) that spans the entire literal.
+    ///
+    /// With the `rust_decimal` feature enabled, `Decimal` may be used to parse literals exactly, without the rounding
+    /// of binary floating-point types. Literals that are out of range for `Decimal` produce an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # #[cfg(feature = "rust_decimal")]
+    /// # {
+    /// use rust_decimal::Decimal;
+    ///
+    /// let decimal = text::float::<_, _, extra::Err<Simple<char>>>().signed().value::<Decimal>();
+    ///
+    /// assert_eq!(decimal.parse("-0.1").into_result(), Ok(Decimal::new(-1, 1)));
+    /// assert_eq!(decimal.parse("1.5e3").into_result(), Ok(Decimal::new(1500, 0)));
+    /// assert!(decimal.parse("1e40").has_errors());
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn value<T: core::str::FromStr>(self) -> FloatValue<T, I, C, E> {
+        FloatValue {
+            float: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    // Parse the float, passing each significant character (i.e: not digit separators) to `push`.
+    fn scan<'a, F>(&self, inp: &mut InputRef<'a, '_, I, E>, mut push: F) -> Result<(), ()>
+    where
+        I: StrInput<'a, C>,
+        C: Char,
+        E: ParserExtra<'a, I>,
+        F: FnMut(C),
+    {
+        let is_ident_char = |c: C| c.to_char().is_ascii_alphanumeric() || c.to_char() == '_';
+
+        if self.signed {
+            match inp.peek() {
+                Some(c) if c == C::from_ascii(b'-') || c == C::from_ascii(b'+') => {
+                    inp.skip();
+                    push(c);
+                }
+                _ => {}
+            }
+        }
+
+        if self.inf_nan {
+            for word in ["inf", "nan"] {
+                let before = inp.save();
+                let matches = word.chars().all(|w| {
+                    inp.next()
+                        .map_or(false, |c| c.to_char().eq_ignore_ascii_case(&w))
+                });
+                if matches && !inp.peek().map_or(false, is_ident_char) {
+                    word.chars().for_each(|w| push(C::from_ascii(w as u8)));
+                    return Ok(());
+                }
+                inp.rewind(before);
+            }
+        }
+
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(c)) if c.is_digit(10) => push(c),
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_ranges_found(
+                        digit_ranges(10),
+                        found.map(|f| f.into()),
+                        err_span,
+                    ),
+                );
+                return Err(());
+            }
+        }
+        self.scan_digits(inp, &mut push);
+
+        let mut fractional = false;
+
+        // Only treat the `.` as a decimal point if it doesn't look like the start of a range or field access
+        let before = inp.save();
+        if inp.next() == Some(C::from_ascii(b'.'))
+            && !inp.peek().map_or(false, |c| {
+                c == C::from_ascii(b'.') || (is_ident_char(c) && !c.is_digit(10))
+            })
+        {
+            push(C::from_ascii(b'.'));
+            if inp.peek().map_or(false, |c| c.is_digit(10)) {
+                self.scan_digits(inp, &mut push);
+            }
+            fractional = true;
+        } else {
+            inp.rewind(before);
+        }
+
+        // Only treat `e` as an exponent marker if digits follow it
+        let before = inp.save();
+        if let Some(e) = inp
+            .next()
+            .filter(|c| c.to_char().eq_ignore_ascii_case(&'e'))
+        {
+            let sign = inp
+                .peek()
+                .filter(|c| *c == C::from_ascii(b'-') || *c == C::from_ascii(b'+'));
+            if sign.is_some() {
+                inp.skip();
+            }
+            if inp.peek().map_or(false, |c| c.is_digit(10)) {
+                push(e);
+                if let Some(sign) = sign {
+                    push(sign);
+                }
+                self.scan_digits(inp, &mut push);
+                fractional = true;
+            } else {
+                inp.rewind(before);
+            }
+        } else {
+            inp.rewind(before);
+        }
+
+        if self.require_fraction && !fractional {
+            let before = inp.save();
+            let (at, found) = inp.next_inner();
+            let err_span = inp.span_since(before.offset());
+            inp.rewind(before);
+            inp.add_alt_err(
+                at,
+                E::Error::expected_found(
+                    [
+                        Some(MaybeRef::Val(C::from_ascii(b'.'))),
+                        Some(MaybeRef::Val(C::from_ascii(b'e'))),
+                    ],
+                    found.map(|f| f.into()),
+                    err_span,
+                ),
+            );
+            return Err(());
+        }
+
+        Ok(())
+    }
+
+    // Parse any number of digits (and separators, if enabled)
+    fn scan_digits<'a, F>(&self, inp: &mut InputRef<'a, '_, I, E>, push: &mut F)
+    where
+        I: StrInput<'a, C>,
+        C: Char,
+        E: ParserExtra<'a, I>,
+        F: FnMut(C),
+    {
+        loop {
+            match inp.peek() {
+                Some(c) if c.is_digit(10) => {
+                    inp.skip();
+                    push(c);
+                }
+                Some(c) if self.separators && c == C::from_ascii(b'_') => inp.skip(),
+                _ => break,
+            }
+        }
+    }
+
+    // Generate a random float, passing each significant character to `push`
+    #[cfg(feature = "alloc")]
+    fn gen_scan<F>(&self, gen: &mut generate::Generator<C>, mut push: F)
+    where
+        C: Char,
+        F: FnMut(C),
+    {
+        let mut emit = |gen: &mut generate::Generator<C>, c: C| {
+            gen.tokens.push(c);
+            push(c);
+        };
+
+        if self.signed && gen.chance() {
+            let sign = gen.pick(b"-+".iter().copied()).unwrap();
+            emit(gen, C::from_ascii(sign));
+        }
+
+        if self.inf_nan && gen.below(4) == 0 {
+            let word = gen.pick(["inf", "nan"]).unwrap();
+            word.bytes().for_each(|w| emit(gen, C::from_ascii(w)));
+            return;
+        }
+
+        let fraction = gen.chance();
+        let exponent = gen.chance() || (self.require_fraction && !fraction);
+        self.gen_digits(gen, &mut emit);
+        if fraction {
+            emit(gen, C::from_ascii(b'.'));
+            self.gen_digits(gen, &mut emit);
+        }
+        if exponent {
+            emit(gen, C::from_ascii(b'e'));
+            if gen.chance() {
+                let sign = gen.pick(b"-+".iter().copied()).unwrap();
+                emit(gen, C::from_ascii(sign));
+            }
+            self.gen_digits(gen, &mut emit);
+        }
+    }
+
+    // Generate one or more digits (and separators, if enabled)
+    #[cfg(feature = "alloc")]
+    fn gen_digits<F>(&self, gen: &mut generate::Generator<C>, emit: &mut F)
+    where
+        C: Char,
+        F: FnMut(&mut generate::Generator<C>, C),
+    {
+        for i in 0..gen.repeats(1, usize::MAX) {
+            if i > 0 && self.separators && gen.chance() {
+                gen.tokens.push(C::from_ascii(b'_'));
+            }
+            let digit = gen.below(10) as u32;
+            emit(gen, digit_char(digit, 10));
+        }
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, &'a C::Str, E> for Float<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset().offset;
+        self.scan(inp, |_| {})?;
+        let after = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(before..after)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<&'a C::Str>, ()>
+    where
+        I::Token: Clone,
+    {
+        self.gen_scan(gen, |_| {});
+        Ok(None)
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+/// See [`Float::value`].
+#[cfg(feature = "alloc")]
+pub struct FloatValue<T, I, C, E> {
+    float: Float<I, C, E>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T, I, C, E> Copy for FloatValue<T, I, C, E> {}
+#[cfg(feature = "alloc")]
+impl<T, I, C, E> Clone for FloatValue<T, I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, I, C, E> ParserSealed<'a, I, T, E> for FloatValue<T, I, C, E>
+where
+    T: core::str::FromStr,
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let before = inp.offset();
+        // Only collect the literal when its value is needed
+        M::choose(
+            &mut *inp,
+            |inp| {
+                let mut s = String::new();
+                self.float.scan(inp, |c| s.push(c.to_char()))?;
+                s.parse().map_err(|_| {
+                    let err = E::Error::custom(inp.span_since(before), "invalid float literal");
+                    inp.add_alt_err(inp.offset().offset, err);
+                })
+            },
+            |inp| self.float.scan(inp, |_| {}),
+        )
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<T>, ()>
+    where
+        I::Token: Clone,
+    {
+        // Retry until the value can be converted
+        let value = gen.filtered(
+            |gen| {
+                let mut s = String::new();
+                self.float.gen_scan(gen, |c| s.push(c.to_char()));
+                Ok(Some(s.parse().ok()))
+            },
+            Option::is_some,
+        )?;
+        Ok(value.flatten())
+    }
+
+    go_extra!(T);
+}
+
+/// A parser that accepts an escape sequence, producing the character that it represents.
+///
+/// An escape sequence is a backslash (`\`) followed by one of the following:
+///
+/// - `\` (backslash)
+/// - `"` (double quote)
+/// - `'` (single quote)
+/// - `n` (line feed)
+/// - `r` (carriage return)
+/// - `t` (tab)
+/// - `0` (null)
+/// - `xNN`, where `NN` is two hexadecimal digits (see [`Escape::hex`])
+/// - `u{N...}`, where `N...` is one to six hexadecimal digits forming a Unicode scalar value (see [`Escape::unicode`])
+///
+/// The returned [`Escape`] can also be configured to accept octal escapes and line continuations.
+///
+/// If a backslash is followed by anything else, the parser fails with an error (created with [`Error::custom`]) that
+/// describes the problem (such as `invalid unicode escape`) and spans the escape sequence up to the point of failure.
+///
+/// This parser is used by [`string`] by default, but may also be used in custom string and character literal grammars.
+///
+/// The output type of this parser is `Option<char>`. Escape sequences that do not represent a character (i.e: line
+/// continuations) produce `None`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// let escape = text::escape::<_, _, extra::Err<Rich<char>>>();
+///
+/// assert_eq!(escape.parse("\\n").into_result(), Ok(Some('\n')));
+/// assert_eq!(escape.parse("\\\"").into_result(), Ok(Some('"')));
+/// assert_eq!(escape.parse("\\x41").into_result(), Ok(Some('A')));
+/// assert_eq!(escape.parse("\\u{1F600}").into_result(), Ok(Some('😀')));
+///
+/// let errs = escape.parse("\\q").into_errors();
+/// assert_eq!(errs[0].to_string(), "invalid escape sequence");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(0..2));
+///
+/// // Surrogates are not Unicode scalar values
+/// let errs = escape.parse("\\u{D800}").into_errors();
+/// assert_eq!(errs[0].to_string(), "invalid unicode escape");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(0..8));
+/// ```
+///
+/// C-style escapes:
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let escape = text::escape::<_, _, extra::Err<Simple<char>>>()
+///     .unicode(false)
+///     .octal(true)
+///     .line_continuation(true);
+///
+/// assert_eq!(escape.parse("\\101").into_result(), Ok(Some('A')));
+/// assert_eq!(escape.parse("\\0").into_result(), Ok(Some('\0')));
+/// assert_eq!(escape.parse("\\\n    ").into_result(), Ok(None));
+/// assert!(escape.parse("\\u{41}").has_errors());
+/// ```
+#[must_use]
+pub fn escape<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> Escape<I, C, E> {
+    Escape {
+        hex: true,
+        unicode: true,
+        octal: false,
+        line_continuation: false,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`escape`].
+pub struct Escape<I, C, E> {
+    hex: bool,
+    unicode: bool,
+    octal: bool,
+    line_continuation: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for Escape<I, C, E> {}
+impl<I, C, E> Clone for Escape<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, C, E> Escape<I, C, E> {
+    /// Whether to accept `\xNN` escapes, where `NN` is exactly two hexadecimal digits. The value is interpreted as a
+    /// Unicode code point (i.e: `\xE9` is `é`). Enabled by default.
+    pub fn hex(self, enabled: bool) -> Self {
+        Self {
+            hex: enabled,
+            ..self
+        }
+    }
+
+    /// Whether to accept `\u{N...}` escapes, where `N...` is one to six hexadecimal digits forming a Unicode scalar
+    /// value. Enabled by default.
+    pub fn unicode(self, enabled: bool) -> Self {
+        Self {
+            unicode: enabled,
+            ..self
+        }
+    }
+
+    /// Whether to accept `\NNN` escapes, where `NNN` is one to three octal digits with a value no greater than `\377`.
+    /// When enabled, `\0` is treated as an octal escape. Disabled by default.
+    pub fn octal(self, enabled: bool) -> Self {
+        Self {
+            octal: enabled,
+            ..self
+        }
+    }
+
+    /// Whether to accept line continuations: a backslash at the end of a line, which escapes the line break and any
+    /// whitespace at the start of the next line. Line continuations produce `None`. Disabled by default.
+    pub fn line_continuation(self, enabled: bool) -> Self {
+        Self {
+            line_continuation: enabled,
+            ..self
+        }
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, Option<char>, E> for Escape<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Option<char>> {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(c)) if c == C::from_ascii(b'\\') => {}
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(
+                    at,
+                    Some(Some(MaybeRef::Val(C::from_ascii(b'\\')))),
+                    found.map(|f| f.into()),
+                    err_span,
+                );
+                return Err(());
+            }
+        }
+
+        // Parse up to `max` digits of the given radix, returning their value and how many were found
+        let digits = |inp: &mut InputRef<'a, '_, I, E>, radix: u32, max: usize| {
+            let mut value = 0u32;
+            let mut count = 0;
+            while let Some(digit) = inp
+                .peek()
+                .and_then(|c| c.to_char().to_digit(radix))
+                .filter(|_| count < max)
+            {
+                inp.skip();
+                value = value * radix + digit;
+                count += 1;
+            }
+            (value, count)
+        };
+
+        let res = match inp.peek().map(|c| c.to_char()) {
+            Some('0'..='7') if self.octal => match digits(inp, 8, 3) {
+                (value, _) if value <= 0o377 => Ok(Some(char::from(value as u8))),
+                _ => Err("invalid octal escape"),
+            },
+            Some('x') if self.hex => {
+                inp.skip();
+                match digits(inp, 16, 2) {
+                    (value, 2) => Ok(Some(char::from(value as u8))),
+                    // Don't consume the offending character: it may be the end of the enclosing literal
+                    _ => Err("invalid hex escape"),
+                }
+            }
+            Some('u') if self.unicode => {
+                inp.skip();
+                let c = if inp.next() == Some(C::from_ascii(b'{')) {
+                    match digits(inp, 16, 6) {
+                        (value, 1..) if inp.next() == Some(C::from_ascii(b'}')) => {
+                            char::from_u32(value)
+                        }
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                c.map(Some).ok_or("invalid unicode escape")
+            }
+            Some(c @ ('\n' | '\r')) if self.line_continuation => {
+                inp.skip();
+                if c == '\r' && inp.peek() == Some(C::from_ascii(b'\n')) {
+                    inp.skip();
+                }
+                inp.skip_while(|c| c.is_whitespace());
+                Ok(None)
+            }
+            c => {
+                inp.skip();
+                match c {
+                    Some(c @ ('\\' | '"' | '\'')) => Ok(Some(c)),
+                    Some('n') => Ok(Some('\n')),
+                    Some('r') => Ok(Some('\r')),
+                    Some('t') => Ok(Some('\t')),
+                    Some('0') => Ok(Some('\0')),
+                    _ => Err("invalid escape sequence"),
+                }
+            }
+        };
+
+        match res {
+            Ok(c) => Ok(M::bind(|| c)),
+            Err(msg) => {
+                // The error spans the escape sequence up to the point of failure
+                let err = E::Error::custom(inp.span_since(before), msg);
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(Option<char>);
+}
+
+/// A parser that accepts a quoted string literal, producing its contents with any escape sequences replaced by the
+/// characters they represent.
+///
+/// By default, a string literal is delimited by double quotes (`"`), may contain the escape sequences accepted by
+/// [`escape`], and may not span multiple lines. The returned [`StringLiteral`] can be configured to use other quote
+/// characters, other escape sequences (or none at all), to permit newlines, or to accept raw strings.
+///
+/// The output type of this parser is `Cow<C::Str>` (i.e: `Cow<str>` when `I` is [`&str`], and `Cow<[u8]>` when `I` is
+/// [`&[u8]`]). If the literal contains no escape sequences, its contents are borrowed from the input without any
+/// allocation.
+///
+/// Errors produced by the escape parser (such as an `invalid escape sequence` error) are reported as-is, so they
+/// point at the offending escape sequence rather than the whole literal.
+///
+/// # Examples
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use chumsky::{prelude::*, error::Rich};
+/// let string = text::string::<_, _, extra::Err<Rich<char>>>();
+///
+/// assert_eq!(string.parse("\"hello\"").into_result(), Ok(Cow::Borrowed("hello")));
+/// assert_eq!(
+///     string.parse("\"a\\tb\\u{21}\"").into_result(),
+///     Ok(Cow::Owned("a\tb!".to_string())),
+/// );
+///
+/// // Strings must be terminated on the same line
+/// assert!(string.parse("\"hello\nworld\"").has_errors());
+///
+/// // Bad escape sequences are reported precisely
+/// let errs = string.parse("\"abc\\qdef\"").into_errors();
+/// assert_eq!(errs[0].to_string(), "invalid escape sequence");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(4..6));
+///
+/// let errs = string.parse("\"\\u{110000}\"").into_errors();
+/// assert_eq!(errs[0].to_string(), "invalid unicode escape");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(1..11));
+/// ```
+///
+/// Single-quoted, multi-line, and raw strings:
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use chumsky::prelude::*;
+/// let string = text::string::<_, _, extra::Err<Simple<char>>>()
+///     .quotes(['"', '\''])
+///     .multiline()
+///     .raw('r', '#');
+///
+/// assert_eq!(string.parse("'it\\'s'").into_result(), Ok(Cow::Owned("it's".to_string())));
+/// assert_eq!(string.parse("\"a\nb\"").into_result(), Ok(Cow::Borrowed("a\nb")));
+/// // A string must end with the same quote that it started with
+/// assert!(string.parse("'hello\"").has_errors());
+/// // Raw strings may contain quotes and backslashes
+/// assert_eq!(
+///     string.parse("r#\"C:\\\"quoted\"\"#").into_result(),
+///     Ok(Cow::Borrowed("C:\\\"quoted\"")),
+/// );
+/// ```
+#[must_use]
+#[cfg(feature = "alloc")]
+pub fn string<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> StringLiteral<I, C, E, Escape<I, C, E>> {
+    StringLiteral {
+        quotes: vec![C::from_ascii(b'"')],
+        escape: Some(escape()),
+        escape_prefix: C::from_ascii(b'\\'),
+        multiline: false,
+        raw: None,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`string`].
+#[cfg(feature = "alloc")]
+pub struct StringLiteral<I, C, E, Esc> {
+    quotes: Vec<C>,
+    escape: Option<Esc>,
+    escape_prefix: C,
+    multiline: bool,
+    raw: Option<(C, C)>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, C: Clone, E, Esc: Clone> Clone for StringLiteral<I, C, E, Esc> {
+    fn clone(&self) -> Self {
+        Self {
+            quotes: self.quotes.clone(),
+            escape: self.escape.clone(),
+            escape_prefix: self.escape_prefix.clone(),
+            multiline: self.multiline,
+            raw: self.raw.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I, C, E, Esc> StringLiteral<I, C, E, Esc> {
+    /// Set the characters that may be used to delimit the string. A string must end with the same quote character that
+    /// it started with.
+    pub fn quotes<Q: IntoIterator<Item = C>>(self, quotes: Q) -> Self {
+        Self {
+            quotes: quotes.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Use a different parser for escape sequences, such as a configured [`escape`] or a custom parser.
+    ///
+    /// The parser is attempted wherever the escape prefix (see [`StringLiteral::escape_prefix`]) appears within the
+    /// string. If it fails without consuming any input, the prefix is treated as part of the string. Otherwise, its
+    /// error is reported. If it produces `None`, the escape sequence is removed from the string without being replaced.
+    pub fn escape<Esc2>(self, escape: Esc2) -> StringLiteral<I, C, E, Esc2> {
+        StringLiteral {
+            quotes: self.quotes,
+            escape: Some(escape),
+            escape_prefix: self.escape_prefix,
+            multiline: self.multiline,
+            raw: self.raw,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Set the character that starts an escape sequence (`\` by default).
+    pub fn escape_prefix(self, prefix: C) -> Self {
+        Self {
+            escape_prefix: prefix,
+            ..self
+        }
+    }
+
+    /// Do not accept any escape sequences: all characters between the quotes are part of the string.
+    pub fn no_escapes(self) -> Self {
+        Self {
+            escape: None,
+            ..self
+        }
+    }
+
+    /// Allow the string to contain newlines.
+    pub fn multiline(self) -> Self {
+        Self {
+            multiline: true,
+            ..self
+        }
+    }
+
+    /// Accept raw strings, which start with `prefix` followed by any number of `delimiter`s and a quote, and end with
+    /// the same quote followed by the same number of `delimiter`s (such as Rust's `r#"..."#`). Raw strings do not
+    /// process escape sequences.
+    pub fn raw(self, prefix: C, delimiter: C) -> Self {
+        Self {
+            raw: Some((prefix, delimiter)),
+            ..self
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, C, E, Esc> ParserSealed<'a, I, Cow<'a, C::Str>, E> for StringLiteral<I, C, E, Esc>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    Esc: Parser<'a, I, Option<char>, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Cow<'a, C::Str>> {
+        // Raw string prefix and delimiters
+        let mut raw = None;
+        if let Some((prefix, delimiter)) = self.raw {
+            let before = inp.save();
+            if inp.next() == Some(prefix) {
+                let mut delimiters = 0;
+                while inp.peek() == Some(delimiter) {
+                    inp.skip();
+                    delimiters += 1;
+                }
+                raw = Some((delimiter, delimiters));
+            }
+            if raw.is_none() || !inp.peek().map_or(false, |c| self.quotes.contains(&c)) {
+                raw = None;
+                inp.rewind(before);
+            }
+        }
+
+        let before = inp.offset();
+        let quote = match inp.next_inner() {
+            (_, Some(c)) if self.quotes.contains(&c) => c,
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(
+                    at,
+                    self.quotes.iter().map(|q| Some(MaybeRef::Val(*q))),
+                    found.map(|f| f.into()),
+                    err_span,
+                );
+                return Err(());
+            }
+        };
+
+        let start = inp.offset().offset;
+        let mut chunk_start = start;
+        let mut owned: Option<<C::Str as ToOwned>::Owned> = None;
+        loop {
+            let before = inp.save();
+            match inp.peek() {
+                Some(c) if c == quote => {
+                    inp.skip();
+                    // Raw strings only end if the quote is followed by the same number of delimiters
+                    let closed = raw.map_or(true, |(delimiter, delimiters)| {
+                        (0..delimiters).all(|_| inp.next() == Some(delimiter))
+                    });
+                    inp.rewind(before);
+                    if closed {
+                        break;
+                    }
+                    inp.skip();
+                }
+                Some(c)
+                    if !self.multiline
+                        && (c == C::from_ascii(b'\n') || c == C::from_ascii(b'\r')) =>
+                {
+                    inp.skip();
+                    let err_span = inp.span_since(before.offset());
+                    inp.add_alt(
+                        inp.offset().offset,
+                        Some(Some(MaybeRef::Val(quote))),
+                        Some(MaybeRef::Val(c)),
+                        err_span,
+                    );
+                    return Err(());
+                }
+                None => {
+                    let err_span = inp.span_since(before.offset());
+                    inp.add_alt(
+                        inp.offset().offset,
+                        Some(Some(MaybeRef::Val(quote))),
+                        None,
+                        err_span,
+                    );
+                    return Err(());
+                }
+                Some(c) => match self
+                    .escape
+                    .as_ref()
+                    .filter(|_| raw.is_none() && c == self.escape_prefix)
+                {
+                    Some(escape) => {
+                        // Errors are reported after the token that caused them, so the escape sequence was only
+                        // present if it failed beyond the first token
+                        inp.skip();
+                        let first = inp.offset().offset;
+                        inp.rewind(before);
+                        // Set aside existing errors so that we can tell whether the escape sequence was present
+                        let old_alt = inp.errors.alt.take();
+                        let res = escape.go::<Emit>(inp);
+                        let new_alt = inp.errors.alt.take();
+                        inp.errors.alt = old_alt;
+
+                        match res {
+                            Ok(c) => {
+                                let buf = owned.get_or_insert_with(|| {
+                                    inp.slice_inner(start..start).to_owned()
+                                });
+                                C::push_str(buf, inp.slice_inner(chunk_start..before.offset));
+                                if let Some(c) = c {
+                                    C::push_char(buf, c);
+                                }
+                                chunk_start = inp.offset().offset;
+                            }
+                            Err(()) => match new_alt {
+                                // The escape sequence was present, but malformed
+                                Some(new_alt) if new_alt.pos > first => {
+                                    inp.add_alt_err(new_alt.pos, new_alt.err);
+                                    return Err(());
+                                }
+                                _ => {
+                                    inp.rewind(before);
+                                    inp.skip();
+                                }
+                            },
+                        }
+                    }
+                    None => inp.skip(),
+                },
+            }
+        }
+
+        let end = inp.offset().offset;
+        let out = match owned {
+            Some(mut buf) => {
+                C::push_str(&mut buf, inp.slice_inner(chunk_start..end));
+                Cow::Owned(buf)
+            }
+            None => Cow::Borrowed(inp.slice_inner(start..end)),
+        };
+
+        // Closing quote and delimiters
+        inp.skip();
+        if let Some((_, delimiters)) = raw {
+            (0..delimiters).for_each(|_| inp.skip());
+        }
+
+        Ok(M::bind(|| out))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(
+        &self,
+        gen: &mut generate::Generator<I::Token>,
+    ) -> Result<Option<Cow<'a, C::Str>>, ()>
+    where
+        I::Token: Clone,
+    {
+        // Escape sequences and raw strings are never generated
+        let quote = gen.pick(self.quotes.iter().copied()).ok_or(())?;
+        gen.tokens.push(quote);
+        for _ in 0..gen.repeats(0, usize::MAX) {
+            let c = gen.token_where(|c| {
+                !self.quotes.contains(c)
+                    && *c != self.escape_prefix
+                    && (self.multiline
+                        || (*c != C::from_ascii(b'\n') && *c != C::from_ascii(b'\r')))
+            });
+            gen.tokens.extend(c);
+        }
+        gen.tokens.push(quote);
+        Ok(None)
+    }
+
+    go_extra!(Cow<'a, C::Str>);
+}
+
+/// A parser that accepts a C-style identifier.
+///
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]).
+///
+/// By default, an identifier is defined as an ASCII alphabetic character or an underscore followed by any number of
+/// alphanumeric characters or underscores. The regex pattern for it is `[a-zA-Z_][a-zA-Z0-9_]*`. The characters that
+/// may start and continue an identifier can be changed with [`Ident::start`] and [`Ident::continue_with`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let ident = text::ident::<_, _, extra::Err<Simple<char>>>();
+///
+/// assert_eq!(ident.parse("foo_bar2").into_result(), Ok("foo_bar2"));
+/// assert!(ident.parse("2foo").has_errors());
+///
+/// // Lisp-style identifiers, which may contain (but not start with) a hyphen
+/// let lisp_ident = text::ident::<_, _, extra::Err<Simple<char>>>()
+///     .continue_with(|c: &char| c.is_ascii_alphanumeric() || "_-?!".contains(*c));
+///
+/// assert_eq!(lisp_ident.parse("list-empty?").into_result(), Ok("list-empty?"));
+/// assert!(lisp_ident.parse("-foo").has_errors());
+///
+/// // Variables with a sigil
+/// let var = text::ident::<_, _, extra::Err<Simple<char>>>().start(|c: &char| *c == '$');
+///
+/// assert_eq!(var.parse("$count").into_result(), Ok("$count"));
+/// assert!(var.parse("count").has_errors());
+/// ```
+#[must_use]
+pub fn ident<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> Ident<I, C, E> {
+    Ident {
+        start: is_ident_start,
+        cont: is_ident_continue,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+fn is_ident_start<C: Char>(c: &C) -> bool {
+    c.to_char().is_ascii_alphabetic() || c.to_char() == '_'
+}
+
+fn is_ident_continue<C: Char>(c: &C) -> bool {
+    c.to_char().is_ascii_alphanumeric() || c.to_char() == '_'
+}
+
+/// See [`ident`].
+pub struct Ident<I, C, E, S = fn(&C) -> bool, Co = fn(&C) -> bool> {
+    start: S,
+    cont: Co,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E, S: Copy, Co: Copy> Copy for Ident<I, C, E, S, Co> {}
+impl<I, C, E, S: Clone, Co: Clone> Clone for Ident<I, C, E, S, Co> {
+    fn clone(&self) -> Self {
+        Self {
+            start: self.start.clone(),
+            cont: self.cont.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<I, C, E, S, Co> Ident<I, C, E, S, Co> {
+    /// Set the predicate that determines which characters may start an identifier.
+    ///
+    /// Note that characters accepted here are not automatically accepted after the first character: see
+    /// [`Ident::continue_with`].
+    pub fn start<S2: Fn(&C) -> bool>(self, start: S2) -> Ident<I, C, E, S2, Co> {
+        Ident {
+            start,
+            cont: self.cont,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Set the predicate that determines which characters may appear in an identifier after the first character.
+    pub fn continue_with<Co2: Fn(&C) -> bool>(self, cont: Co2) -> Ident<I, C, E, S, Co2> {
+        Ident {
+            start: self.start,
+            cont,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, C, E, S, Co> ParserSealed<'a, I, &'a C::Str, E> for Ident<I, C, E, S, Co>
+where
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    S: Fn(&C) -> bool,
+    Co: Fn(&C) -> bool,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(c)) if (self.start)(&c) => {}
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_class_found("identifier", found.map(|f| f.into()), err_span),
+                );
+                return Err(());
+            }
+        }
+        while inp.peek().map_or(false, |c| (self.cont)(&c)) {
+            inp.skip();
+        }
+        let end = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(before.offset..end)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<&'a C::Str>, ()>
+    where
+        I::Token: Clone,
+    {
+        let start = gen.token_where(|c| (self.start)(c)).ok_or(())?;
+        gen.tokens.push(start);
+        for _ in 0..gen.repeats(0, usize::MAX) {
+            let c = gen.token_where(|c| (self.cont)(c));
+            gen.tokens.extend(c);
+        }
+        Ok(None)
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+/// Like [`ident`], but only accepts a specific identifier while rejecting trailing identifier characters.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]).
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let def = text::keyword::<_, _, _, extra::Err<Simple<char>>>("def");
+///
+/// // Exactly 'def' was found
+/// assert_eq!(def.parse("def").into_result(), Ok("def"));
+/// // Exactly 'def' was found, with non-identifier trailing characters
+/// // This works because we made the parser lazy: it parses 'def' and ignores the rest
+/// assert_eq!(def.clone().lazy().parse("def(foo, bar)").into_result(), Ok("def"));
+/// // 'def' was found, but only as part of a larger identifier, so this fails to parse
+/// assert!(def.lazy().parse("define").has_errors());
+/// ```
+pub fn keyword<
+    'a,
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char + 'a,
+    Str: AsRef<C::Str> + 'a + Clone,
     E: ParserExtra<'a, I> + 'a,
 >(
     keyword: Str,
@@ -405,3 +3093,489 @@ where
         })
         .slice()
 }
+
+/// Like [`just`], but matches textual input case-insensitively.
+///
+/// By default, only ASCII letters are matched case-insensitively. Use [`JustIgnoreCase::unicode`] to also match
+/// non-ASCII characters case-insensitively.
+///
+/// Errors describe the expected input using the spelling that was given to this function, regardless of the case
+/// of the input.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]), with the case of the input preserved.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Rich};
+/// let get = text::just_ignore_case::<_, _, _, extra::Err<Rich<char>>>("GET");
+///
+/// assert_eq!(get.parse("GET").into_result(), Ok("GET"));
+/// assert_eq!(get.parse("get").into_result(), Ok("get"));
+/// assert_eq!(get.parse("gEt").into_result(), Ok("gEt"));
+///
+/// // Errors refer to the canonical spelling
+/// let errs = get.parse("gex").into_errors();
+/// assert_eq!(errs[0].to_string(), "found 'x' expected 'T'");
+/// assert_eq!(errs[0].span(), &SimpleSpan::from(2..3));
+///
+/// // Non-ASCII characters must match exactly, unless Unicode matching is enabled
+/// let summer = text::just_ignore_case::<_, _, _, extra::Err<Simple<char>>>("ÉTÉ");
+/// assert!(summer.parse("été").has_errors());
+/// assert_eq!(summer.unicode().parse("été").into_result(), Ok("été"));
+/// ```
+#[must_use]
+pub fn just_ignore_case<'a, T, I, C, E>(seq: T) -> JustIgnoreCase<T, I, C, E>
+where
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    T: OrderedSeq<'a, C>,
+{
+    JustIgnoreCase {
+        seq,
+        unicode: false,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`just_ignore_case`].
+pub struct JustIgnoreCase<T, I, C, E> {
+    seq: T,
+    unicode: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<T: Copy, I, C, E> Copy for JustIgnoreCase<T, I, C, E> {}
+impl<T: Clone, I, C, E> Clone for JustIgnoreCase<T, I, C, E> {
+    fn clone(&self) -> Self {
+        Self {
+            seq: self.seq.clone(),
+            unicode: self.unicode,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<T, I, C, E> JustIgnoreCase<T, I, C, E> {
+    /// Match all characters case-insensitively according to their Unicode lowercase mappings, not only ASCII letters.
+    pub fn unicode(self) -> Self {
+        Self {
+            unicode: true,
+            ..self
+        }
+    }
+}
+
+impl<'a, T, I, C, E> ParserSealed<'a, I, &'a C::Str, E> for JustIgnoreCase<T, I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    T: OrderedSeq<'a, C>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let start = inp.offset().offset;
+        for expected in self.seq.seq_iter() {
+            let before = inp.offset();
+            match inp.next_inner() {
+                (_, Some(c)) if eq_ignore_case(c, *expected.borrow(), self.unicode) => {}
+                (at, found) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(
+                        at,
+                        Some(Some(T::to_maybe_ref(expected))),
+                        found.map(|f| f.into()),
+                        err_span,
+                    );
+                    return Err(());
+                }
+            }
+        }
+        let end = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(start..end)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn gen_input(&self, gen: &mut generate::Generator<I::Token>) -> Result<Option<&'a C::Str>, ()>
+    where
+        I::Token: Clone,
+    {
+        for expected in self.seq.seq_iter() {
+            let c = (*expected.borrow()).to_char();
+            // Randomly swap the case of ASCII letters
+            let c = match c {
+                'a'..='z' | 'A'..='Z' if gen.chance() => C::from_ascii(c as u8 ^ 0x20),
+                _ => *expected.borrow(),
+            };
+            gen.tokens.push(c);
+        }
+        Ok(None)
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+fn eq_ignore_case<C: Char>(a: C, b: C, unicode: bool) -> bool {
+    let (a, b) = (a.to_char(), b.to_char());
+    if a == b || a.eq_ignore_ascii_case(&b) {
+        true
+    } else if unicode && (!a.is_ascii() || !b.is_ascii()) {
+        a.to_lowercase().eq(b.to_lowercase())
+    } else {
+        false
+    }
+}
+
+/// Like [`keyword`], but matches the keyword case-insensitively (see [`just_ignore_case`]).
+///
+/// As with [`keyword`], the keyword is rejected if it is immediately followed by another identifier character.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]), with the case of the input preserved.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let select = text::keyword_ignore_case::<_, _, _, extra::Err<Simple<char>>>("SELECT");
+///
+/// assert_eq!(select.parse("select").into_result(), Ok("select"));
+/// assert_eq!(select.parse("Select").into_result(), Ok("Select"));
+/// assert_eq!(select.lazy().parse("SELECT *").into_result(), Ok("SELECT"));
+/// // Part of a larger identifier
+/// assert!(select.lazy().parse("selection").has_errors());
+/// ```
+#[must_use]
+pub fn keyword_ignore_case<'a, T, I, C, E>(keyword: T) -> KeywordIgnoreCase<T, I, C, E>
+where
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    T: OrderedSeq<'a, C>,
+{
+    KeywordIgnoreCase {
+        just: just_ignore_case(keyword),
+    }
+}
+
+/// See [`keyword_ignore_case`].
+pub struct KeywordIgnoreCase<T, I, C, E> {
+    just: JustIgnoreCase<T, I, C, E>,
+}
+
+impl<T: Copy, I, C, E> Copy for KeywordIgnoreCase<T, I, C, E> {}
+impl<T: Clone, I, C, E> Clone for KeywordIgnoreCase<T, I, C, E> {
+    fn clone(&self) -> Self {
+        Self {
+            just: self.just.clone(),
+        }
+    }
+}
+
+impl<T, I, C, E> KeywordIgnoreCase<T, I, C, E> {
+    /// Match all characters case-insensitively according to their Unicode lowercase mappings, not only ASCII letters.
+    pub fn unicode(self) -> Self {
+        Self {
+            just: self.just.unicode(),
+        }
+    }
+}
+
+impl<'a, T, I, C, E> ParserSealed<'a, I, &'a C::Str, E> for KeywordIgnoreCase<T, I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    T: OrderedSeq<'a, C>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset();
+        let out = self.just.go::<M>(inp)?;
+        match inp.peek() {
+            Some(c) if c.to_char().is_ascii_alphanumeric() || c.to_char() == '_' => {
+                inp.skip();
+                let err_span = inp.span_since(before);
+                inp.add_alt(inp.offset().offset, None, Some(MaybeRef::Val(c)), err_span);
+                Err(())
+            }
+            _ => Ok(out),
+        }
+    }
+
+    gen_input!(just: &'a C::Str);
+
+    go_extra!(&'a C::Str);
+}
+
+/// A parser that accepts a single extended grapheme cluster, as defined by
+/// [UAX #29](https://www.unicode.org/reports/tr29/).
+///
+/// A grapheme cluster is what a reader would consider to be a single character, even when it is made up of several
+/// `char`s: a letter followed by combining accents, a Hangul syllable written as separate jamo, or an emoji sequence
+/// such as a flag or a family. Use this parser instead of [`any`] when a grammar deals in user-perceived characters,
+/// such as when limiting the length of a field.
+///
+/// *This parser requires the `unicode-segmentation` feature.*
+///
+/// The output type of this parser is `&str`, the slice of the input that makes up the grapheme cluster.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let grapheme = text::grapheme::<_, extra::Err<Simple<char>>>();
+///
+/// // `e` followed by a combining acute accent
+/// assert_eq!(grapheme.parse("e\u{301}").into_result(), Ok("e\u{301}"));
+/// // The flag of Japan, made of two regional indicators
+/// assert_eq!(grapheme.parse("🇯🇵").into_result(), Ok("🇯🇵"));
+/// assert!(grapheme.parse("").has_errors());
+///
+/// // A user name of at most 4 user-perceived characters
+/// let name = grapheme.repeated().at_most(4).slice();
+///
+/// assert_eq!(name.parse("ne\u{301}ne\u{301}").into_result(), Ok("ne\u{301}ne\u{301}"));
+/// assert!(name.parse("ne\u{301}ne\u{301}s").has_errors());
+/// ```
+#[cfg(feature = "unicode-segmentation")]
+#[must_use]
+pub const fn grapheme<'a, I: StrInput<'a, char>, E: ParserExtra<'a, I>>() -> Grapheme<I, E> {
+    Grapheme {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`grapheme`].
+#[cfg(feature = "unicode-segmentation")]
+pub struct Grapheme<I, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl<I, E> Copy for Grapheme<I, E> {}
+#[cfg(feature = "unicode-segmentation")]
+impl<I, E> Clone for Grapheme<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl<'a, I, E> ParserSealed<'a, I, &'a str, E> for Grapheme<I, E>
+where
+    I: StrInput<'a, char>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a str> {
+        let before = inp.offset();
+        let trailing = inp.slice_trailing_inner();
+        match trailing.graphemes(true).next() {
+            Some(grapheme) => {
+                // More input might extend the grapheme, such as with a combining mark
+                if grapheme.len() == trailing.len() {
+                    inp.reach_end();
+                }
+                inp.skip_bytes(grapheme.len());
+                Ok(M::bind(|| grapheme))
+            }
+            None => {
+                let (at, found) = inp.next_inner();
+                let err_span = inp.span_since(before);
+                inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(&'a str);
+}
+
+/// A parser that accepts a single word, as defined by the word boundaries of
+/// [UAX #29](https://www.unicode.org/reports/tr29/).
+///
+/// Words are found the same way as by [`str::unicode_words`](https://docs.rs/unicode-segmentation): the input is
+/// split at word boundaries, and a segment is a word if it contains at least one alphanumeric character. Unlike
+/// [`ident`], this means that words may contain letters from any script, as well as the apostrophes and periods that
+/// appear within words (such as `can't` or `e.g`), and that words in scripts written without spaces are found as
+/// single characters. Whitespace and punctuation between words are not accepted, so they must be parsed separately.
+///
+/// *This parser requires the `unicode-segmentation` feature.*
+///
+/// The output type of this parser is `&str`, the slice of the input that makes up the word.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let word = text::word::<_, extra::Err<Simple<char>>>();
+///
+/// assert_eq!(word.lazy().parse("can't stop").into_result(), Ok("can't"));
+/// assert_eq!(word.lazy().parse("naïve!").into_result(), Ok("naïve"));
+/// assert_eq!(word.lazy().parse("3.14 metres").into_result(), Ok("3.14"));
+/// assert!(word.parse(", and").has_errors());
+///
+/// // Tokenize a sentence into its words
+/// let words = word
+///     .padded_by(any().filter(|c: &char| !c.is_alphanumeric()).repeated())
+///     .repeated()
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     words.parse("Hello, wörld! It's 9 o'clock.").into_result(),
+///     Ok(vec!["Hello", "wörld", "It's", "9", "o'clock"]),
+/// );
+/// ```
+#[cfg(feature = "unicode-segmentation")]
+#[must_use]
+pub const fn word<'a, I: StrInput<'a, char>, E: ParserExtra<'a, I>>() -> Word<I, E> {
+    Word {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`word`].
+#[cfg(feature = "unicode-segmentation")]
+pub struct Word<I, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl<I, E> Copy for Word<I, E> {}
+#[cfg(feature = "unicode-segmentation")]
+impl<I, E> Clone for Word<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl<'a, I, E> ParserSealed<'a, I, &'a str, E> for Word<I, E>
+where
+    I: StrInput<'a, char>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a str> {
+        let before = inp.offset();
+        let trailing = inp.slice_trailing_inner();
+        match trailing.split_word_bounds().next() {
+            Some(word) if word.chars().any(char::is_alphanumeric) => {
+                if word.len() == trailing.len() {
+                    inp.reach_end();
+                }
+                inp.skip_bytes(word.len());
+                Ok(M::bind(|| word))
+            }
+            _ => {
+                let (at, found) = inp.next_inner();
+                let err_span = inp.span_since(before);
+                inp.add_alt(at, None, found.map(|f| f.into()), err_span);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(&'a str);
+}
+
+/// Named classes of ASCII characters.
+///
+/// Each class is an array of inclusive ranges that can be passed to [`one_of`] or [`none_of`]. Errors generated by
+/// these parsers describe the expected input in terms of the ranges (i.e: `expected 'a'..'z' or 'A'..'Z'`) rather
+/// than by enumerating every character. Classes can also be used with [`Parser::filter`] via [`Seq::contains`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, container::Seq};
+/// let word = one_of::<_, _, extra::Err<Simple<char>>>(text::ascii::alphabetic())
+///     .repeated()
+///     .at_least(1)
+///     .collect::<String>();
+///
+/// assert_eq!(word.parse("Hello").into_result(), Ok("Hello".to_string()));
+/// assert!(word.parse("h3llo").has_errors());
+///
+/// let not_digit = any::<_, extra::Err<Simple<char>>>().filter(|c| !text::ascii::digit().contains(c));
+///
+/// assert_eq!(not_digit.parse("x").into_result(), Ok('x'));
+/// assert!(not_digit.parse("7").has_errors());
+/// ```
+pub mod ascii {
+    use super::Char;
+    use core::ops::RangeInclusive;
+
+    fn range<C: Char>(from: u8, to: u8) -> RangeInclusive<C> {
+        C::from_ascii(from)..=C::from_ascii(to)
+    }
+
+    /// ASCII alphabetic characters (`a-z`, `A-Z`).
+    #[must_use]
+    pub fn alphabetic<C: Char>() -> [RangeInclusive<C>; 2] {
+        [range(b'a', b'z'), range(b'A', b'Z')]
+    }
+
+    /// ASCII lowercase characters (`a-z`).
+    #[must_use]
+    pub fn lowercase<C: Char>() -> [RangeInclusive<C>; 1] {
+        [range(b'a', b'z')]
+    }
+
+    /// ASCII uppercase characters (`A-Z`).
+    #[must_use]
+    pub fn uppercase<C: Char>() -> [RangeInclusive<C>; 1] {
+        [range(b'A', b'Z')]
+    }
+
+    /// ASCII decimal digits (`0-9`).
+    #[must_use]
+    pub fn digit<C: Char>() -> [RangeInclusive<C>; 1] {
+        [range(b'0', b'9')]
+    }
+
+    /// ASCII hexadecimal digits (`0-9`, `a-f`, `A-F`).
+    #[must_use]
+    pub fn hex_digit<C: Char>() -> [RangeInclusive<C>; 3] {
+        [range(b'0', b'9'), range(b'a', b'f'), range(b'A', b'F')]
+    }
+
+    /// ASCII alphanumeric characters (`a-z`, `A-Z`, `0-9`).
+    #[must_use]
+    pub fn alphanumeric<C: Char>() -> [RangeInclusive<C>; 3] {
+        [range(b'a', b'z'), range(b'A', b'Z'), range(b'0', b'9')]
+    }
+
+    /// ASCII punctuation characters, as defined by [`char::is_ascii_punctuation`].
+    #[must_use]
+    pub fn punctuation<C: Char>() -> [RangeInclusive<C>; 4] {
+        [
+            range(b'!', b'/'),
+            range(b':', b'@'),
+            range(b'[', b'`'),
+            range(b'{', b'~'),
+        ]
+    }
+
+    /// ASCII whitespace characters, as defined by [`char::is_ascii_whitespace`].
+    #[must_use]
+    pub fn whitespace<C: Char>() -> [RangeInclusive<C>; 3] {
+        [
+            range(b'\t', b'\n'),
+            range(b'\x0C', b'\r'),
+            range(b' ', b' '),
+        ]
+    }
+}
+
+pub mod scan;
+
+#[cfg(feature = "unicode")]
+pub mod unicode;
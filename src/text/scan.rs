@@ -0,0 +1,460 @@
+//! Fast, byte-oriented scanning of text.
+//!
+//! Lexers spend much of their time in loops that consume one character at a time, such as skipping whitespace or
+//! finding the end of a comment or string literal. For [`StrInput`]s, the parsers in this module perform these scans
+//! directly over the bytes of the input, checking each byte against a [`ByteSet`] lookup table rather than decoding
+//! characters. With the `memchr` feature enabled, [`scan_until`] uses the vectorised routines of
+//! [`memchr`](https://docs.rs/memchr) when searching for up to three distinct bytes.
+//!
+//! A [`ByteSet`] can also be passed to [`one_of`] and [`none_of`], which then check each token with a single table
+//! lookup instead of comparing it against every member of the set.
+//!
+//! When the input is a `&str`, a [`ByteSet`] only matches ASCII characters, since other bytes are not characters on
+//! their own: any non-ASCII bytes in the set are ignored.
+
+use super::*;
+
+/// A set of bytes, stored as a 256-bit lookup table.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::text::scan::ByteSet;
+/// const HEX: ByteSet = ByteSet::from_range(b'0'..=b'9')
+///     .union(ByteSet::from_range(b'a'..=b'f'))
+///     .union(ByteSet::from_range(b'A'..=b'F'));
+///
+/// assert!(HEX.contains(b'c'));
+/// assert!(!HEX.contains(b'g'));
+/// assert_eq!(HEX.len(), 22);
+/// assert_eq!(HEX.prefix_len(b"c0ffee!"), 6);
+/// assert_eq!(HEX.find(b"xyz9"), Some(3));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteSet {
+    bits: [u64; 4],
+}
+
+impl ByteSet {
+    /// The empty set.
+    pub const EMPTY: Self = Self { bits: [0; 4] };
+
+    /// ASCII whitespace: space, tab, line feed, vertical tab, form feed, and carriage return.
+    ///
+    /// These are the ASCII characters accepted by [`whitespace`].
+    pub const WHITESPACE: Self = Self::from_bytes(b" \t\n\x0B\x0C\r");
+
+    /// ASCII inline whitespace: space and tab.
+    pub const INLINE_WHITESPACE: Self = Self::from_bytes(b" \t");
+
+    /// ASCII line breaks: line feed, carriage return, vertical tab, and form feed.
+    ///
+    /// These are the ASCII characters accepted by [`newline`].
+    pub const NEWLINE: Self = Self::from_bytes(b"\n\r\x0B\x0C");
+
+    /// Create a set containing the given bytes.
+    pub const fn from_bytes(bytes: &[u8]) -> Self {
+        let mut set = Self::EMPTY;
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.with(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Create a set containing all bytes in the given range.
+    pub const fn from_range(range: RangeInclusive<u8>) -> Self {
+        let (mut byte, end) = (*range.start(), *range.end());
+        let mut set = Self::EMPTY;
+        while byte <= end {
+            set = set.with(byte);
+            if byte == end {
+                break;
+            }
+            byte += 1;
+        }
+        set
+    }
+
+    /// Add a byte to the set.
+    pub const fn with(self, byte: u8) -> Self {
+        let mut bits = self.bits;
+        bits[(byte / 64) as usize] |= 1 << (byte % 64);
+        Self { bits }
+    }
+
+    /// Create a set containing the bytes of both this set and another.
+    pub const fn union(self, other: Self) -> Self {
+        let mut bits = self.bits;
+        let mut i = 0;
+        while i < 4 {
+            bits[i] |= other.bits[i];
+            i += 1;
+        }
+        Self { bits }
+    }
+
+    /// Create a set containing every byte that is not in this set.
+    pub const fn complement(self) -> Self {
+        let mut bits = self.bits;
+        let mut i = 0;
+        while i < 4 {
+            bits[i] = !bits[i];
+            i += 1;
+        }
+        Self { bits }
+    }
+
+    /// Returns true if the set contains the given byte.
+    #[inline(always)]
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.bits[(byte / 64) as usize] & (1 << (byte % 64)) != 0
+    }
+
+    /// The number of bytes in the set.
+    pub const fn len(&self) -> usize {
+        (self.bits[0].count_ones()
+            + self.bits[1].count_ones()
+            + self.bits[2].count_ones()
+            + self.bits[3].count_ones()) as usize
+    }
+
+    /// Returns true if the set contains no bytes.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the bytes in the set, in ascending order.
+    pub fn bytes(&self) -> ByteSetIter {
+        ByteSetIter {
+            set: *self,
+            next: 0,
+        }
+    }
+
+    /// Find the position of the first byte in `haystack` that belongs to the set.
+    ///
+    /// With the `memchr` feature enabled, sets of up to three bytes are searched for using vectorised routines.
+    #[inline]
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        #[cfg(feature = "memchr")]
+        {
+            let mut bytes = self.bytes();
+            match (bytes.next(), bytes.next(), bytes.next(), bytes.next()) {
+                (Some(a), None, _, _) => return memchr::memchr(a, haystack),
+                (Some(a), Some(b), None, _) => return memchr::memchr2(a, b, haystack),
+                (Some(a), Some(b), Some(c), None) => return memchr::memchr3(a, b, c, haystack),
+                _ => {}
+            }
+        }
+        haystack.iter().position(|b| self.contains(*b))
+    }
+
+    /// Count the number of bytes at the start of `haystack` that belong to the set.
+    #[inline]
+    pub fn prefix_len(&self, haystack: &[u8]) -> usize {
+        haystack
+            .iter()
+            .position(|b| !self.contains(*b))
+            .unwrap_or(haystack.len())
+    }
+
+    // Remove the bytes that are not characters on their own for the given character type
+    fn chars_of<C: Char>(self) -> Self {
+        if C::byte_is_char(0x80) {
+            self
+        } else {
+            Self {
+                bits: [self.bits[0], self.bits[1], 0, 0],
+            }
+        }
+    }
+}
+
+/// An iterator over the bytes in a [`ByteSet`]. See [`ByteSet::bytes`].
+#[derive(Clone, Debug)]
+pub struct ByteSetIter {
+    set: ByteSet,
+    next: u16,
+}
+
+impl Iterator for ByteSetIter {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        while self.next < 256 {
+            let byte = self.next as u8;
+            self.next += 1;
+            if self.set.contains(byte) {
+                return Some(byte);
+            }
+        }
+        None
+    }
+}
+
+impl<'p> Seq<'p, u8> for ByteSet {
+    type Item<'a> = u8;
+
+    type Iter<'a> = ByteSetIter;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.bytes()
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &u8) -> bool {
+        ByteSet::contains(self, *val)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, u8>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+}
+
+impl<'p> Seq<'p, char> for ByteSet {
+    type Item<'a> = char;
+
+    type Iter<'a> = core::iter::Map<ByteSetIter, fn(u8) -> char>;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.chars_of::<char>().bytes().map(char::from)
+    }
+
+    #[inline(always)]
+    fn contains(&self, val: &char) -> bool {
+        val.is_ascii() && ByteSet::contains(self, *val as u8)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, char>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+}
+
+/// See [`scan_while`].
+pub struct ScanWhile<I, C, E> {
+    set: ByteSet,
+    at_least: usize,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for ScanWhile<I, C, E> {}
+impl<I, C, E> Clone for ScanWhile<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, C, E> ScanWhile<I, C, E> {
+    /// Require that at least the given number of characters are matched.
+    pub fn at_least(self, at_least: usize) -> Self {
+        Self { at_least, ..self }
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, &'a C::Str, E> for ScanWhile<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset();
+        let set = self.set.chars_of::<C>();
+        let bytes = C::str_as_bytes(inp.slice_trailing_inner());
+        let len = set.prefix_len(bytes);
+        if len == bytes.len() {
+            inp.reach_end();
+        }
+        inp.skip_bytes(len);
+
+        if len < self.at_least {
+            let at = inp.offset();
+            let found = inp.next_inner().1;
+            let err_span = inp.span_since(at);
+            inp.add_alt(
+                at.offset,
+                set.bytes().map(|b| Some(MaybeRef::Val(C::from_ascii(b)))),
+                found.map(|f| f.into()),
+                err_span,
+            );
+            return Err(());
+        }
+
+        let end = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(before.offset..end)))
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+/// A parser that accepts any number of characters belonging to a [`ByteSet`], scanning the input by byte.
+///
+/// This is equivalent to `one_of(set).repeated().to_slice()`, but much faster on long runs of input. Use
+/// [`ScanWhile::at_least`] to require a minimum number of characters.
+///
+/// The output type of this parser is `&C::Str`, the slice of input that was matched.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::scan::{self, ByteSet};
+///
+/// const DIGITS: ByteSet = ByteSet::from_range(b'0'..=b'9').with(b'_');
+///
+/// let number = scan::scan_while::<_, _, extra::Err<Simple<char>>>(DIGITS).at_least(1);
+///
+/// assert_eq!(number.lazy().parse("1_000 apples").into_result(), Ok("1_000"));
+/// assert!(number.parse("apples").has_errors());
+/// ```
+pub const fn scan_while<'a, I, C, E>(set: ByteSet) -> ScanWhile<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    ScanWhile {
+        set,
+        at_least: 0,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// See [`scan_until`].
+pub struct ScanUntil<I, C, E> {
+    set: ByteSet,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, C, E)>,
+}
+
+impl<I, C, E> Copy for ScanUntil<I, C, E> {}
+impl<I, C, E> Clone for ScanUntil<I, C, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I, C, E> ParserSealed<'a, I, &'a C::Str, E> for ScanUntil<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset();
+        let bytes = C::str_as_bytes(inp.slice_trailing_inner());
+        let len = match self.set.chars_of::<C>().find(bytes) {
+            Some(len) => len,
+            None => {
+                inp.reach_end();
+                bytes.len()
+            }
+        };
+        inp.skip_bytes(len);
+        let end = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(before.offset..end)))
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+/// A parser that accepts all input up to (but not including) the first character belonging to a [`ByteSet`], or up
+/// to the end of the input if there is no such character.
+///
+/// This is useful for quickly skipping over the bodies of comments and string literals. With the `memchr` feature
+/// enabled, sets of up to three bytes are searched for using vectorised routines.
+///
+/// The output type of this parser is `&C::Str`, the slice of input that was skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::scan::{self, ByteSet};
+///
+/// // A string literal without escapes
+/// let string = scan::scan_until::<_, _, extra::Err<Simple<char>>>(ByteSet::from_bytes(b"\"\n"))
+///     .delimited_by(just('"'), just('"'));
+///
+/// assert_eq!(string.parse("\"héllo, wörld\"").into_result(), Ok("héllo, wörld"));
+/// assert!(string.parse("\"unterminated\nstring\"").has_errors());
+/// ```
+pub const fn scan_until<'a, I, C, E>(set: ByteSet) -> ScanUntil<I, C, E>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    ScanUntil {
+        set,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+// Skip characters matching `pred`, scanning over those in `fast` (which must all match `pred`) by byte.
+#[cfg(feature = "alloc")]
+pub(crate) fn skip_while_fast<'a, I, C, E>(
+    inp: &mut InputRef<'a, '_, I, E>,
+    fast: ByteSet,
+    pred: impl Fn(&C) -> bool,
+) where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    let fast = fast.chars_of::<C>();
+    loop {
+        let len = fast.prefix_len(C::str_as_bytes(inp.slice_trailing_inner()));
+        inp.skip_bytes(len);
+        match inp.peek() {
+            Some(c) if pred(&c) => inp.skip(),
+            _ => break,
+        }
+    }
+}
+
+// Skip characters until one matches `pred`, scanning by byte until reaching a byte in `stop` or a byte that is not a
+// character on its own. `stop` must contain every single-byte character that matches `pred`.
+#[cfg(feature = "alloc")]
+pub(crate) fn skip_until_fast<'a, I, C, E>(
+    inp: &mut InputRef<'a, '_, I, E>,
+    stop: ByteSet,
+    pred: impl Fn(&C) -> bool,
+) where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    let stop = stop.chars_of::<C>().union(if C::byte_is_char(0x80) {
+        ByteSet::EMPTY
+    } else {
+        ByteSet::from_range(0x80..=0xFF)
+    });
+    loop {
+        let bytes = C::str_as_bytes(inp.slice_trailing_inner());
+        let len = stop.find(bytes).unwrap_or(bytes.len());
+        inp.skip_bytes(len);
+        match inp.peek() {
+            Some(c) if !pred(&c) => inp.skip(),
+            _ => break,
+        }
+    }
+}
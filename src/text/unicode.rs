@@ -0,0 +1,506 @@
+//! Parsers for Unicode character classes.
+//!
+//! The parsers in this module match a single `char` belonging to a Unicode [general
+//! category](https://www.unicode.org/reports/tr44/#General_Category_Values), [script](https://www.unicode.org/reports/tr24/),
+//! or binary [`Property`]. They allow internationalized grammars to describe character classes that go beyond the
+//! `is_ascii_*` predicates, without hand-written tables. [`just_normalized`] matches text independently of its Unicode
+//! normalization form.
+//!
+//! *This module requires the `unicode` feature.*
+
+use super::*;
+
+pub use unicode_properties::{GeneralCategory, GeneralCategoryGroup};
+pub use unicode_script::Script;
+
+use unicode_properties::{UnicodeEmoji, UnicodeGeneralCategory};
+use unicode_script::UnicodeScript;
+
+use alloc::string::String;
+use unicode_normalization::{char::canonical_combining_class, UnicodeNormalization};
+
+/// A binary Unicode character property.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Property {
+    /// The `Alphabetic` property, as defined by [`char::is_alphabetic`].
+    Alphabetic,
+    /// The `Lowercase` property, as defined by [`char::is_lowercase`].
+    Lowercase,
+    /// The `Uppercase` property, as defined by [`char::is_uppercase`].
+    Uppercase,
+    /// The `White_Space` property, as defined by [`char::is_whitespace`].
+    WhiteSpace,
+    /// Characters in the `Nd`, `Nl`, or `No` general categories, as defined by [`char::is_numeric`].
+    Numeric,
+    /// Characters that are either [`Property::Alphabetic`] or [`Property::Numeric`].
+    Alphanumeric,
+    /// Characters in the `Cc` general category, as defined by [`char::is_control`].
+    Control,
+    /// The `Emoji` property.
+    Emoji,
+    /// The `Emoji_Component` property.
+    EmojiComponent,
+}
+
+/// A class of Unicode characters that can be matched by a [`Class`] parser.
+///
+/// This trait is implemented for [`GeneralCategory`], [`GeneralCategoryGroup`], [`Script`], and [`Property`], as well
+/// as for arrays and slices of them (which match a character belonging to *any* of the given classes).
+pub trait CharClass {
+    /// Determine whether the given character belongs to this class.
+    fn contains(&self, c: char) -> bool;
+
+    /// Call the given function with the name of each class that this class is made up of (such as
+    /// `"uppercase letter"`), which errors use to describe the expected input.
+    ///
+    /// By default, no names are given and errors do not describe the expected input.
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        #![allow(unused_variables)]
+    }
+}
+
+impl CharClass for GeneralCategory {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        c.general_category() == *self
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        f(match self {
+            Self::UppercaseLetter => "uppercase letter",
+            Self::LowercaseLetter => "lowercase letter",
+            Self::TitlecaseLetter => "titlecase letter",
+            Self::ModifierLetter => "modifier letter",
+            Self::OtherLetter => "other letter",
+            Self::NonspacingMark => "nonspacing mark",
+            Self::SpacingMark => "spacing mark",
+            Self::EnclosingMark => "enclosing mark",
+            Self::DecimalNumber => "decimal number",
+            Self::LetterNumber => "letter number",
+            Self::OtherNumber => "other number",
+            Self::ConnectorPunctuation => "connector punctuation",
+            Self::DashPunctuation => "dash punctuation",
+            Self::OpenPunctuation => "open punctuation",
+            Self::ClosePunctuation => "close punctuation",
+            Self::InitialPunctuation => "initial punctuation",
+            Self::FinalPunctuation => "final punctuation",
+            Self::OtherPunctuation => "other punctuation",
+            Self::MathSymbol => "math symbol",
+            Self::CurrencySymbol => "currency symbol",
+            Self::ModifierSymbol => "modifier symbol",
+            Self::OtherSymbol => "other symbol",
+            Self::SpaceSeparator => "space separator",
+            Self::LineSeparator => "line separator",
+            Self::ParagraphSeparator => "paragraph separator",
+            Self::Control => "control character",
+            Self::Format => "format character",
+            Self::Surrogate => "surrogate",
+            Self::PrivateUse => "private use character",
+            Self::Unassigned => "unassigned character",
+        })
+    }
+}
+
+impl CharClass for GeneralCategoryGroup {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        c.general_category_group() == *self
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        f(match self {
+            Self::Letter => "letter",
+            Self::Mark => "mark",
+            Self::Number => "number",
+            Self::Punctuation => "punctuation",
+            Self::Symbol => "symbol",
+            Self::Separator => "separator",
+            Self::Other => "other character",
+        })
+    }
+}
+
+impl CharClass for Script {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        c.script() == *self
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        f(self.full_name())
+    }
+}
+
+impl CharClass for Property {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        match self {
+            Self::Alphabetic => c.is_alphabetic(),
+            Self::Lowercase => c.is_lowercase(),
+            Self::Uppercase => c.is_uppercase(),
+            Self::WhiteSpace => c.is_whitespace(),
+            Self::Numeric => c.is_numeric(),
+            Self::Alphanumeric => c.is_alphanumeric(),
+            Self::Control => c.is_control(),
+            Self::Emoji => c.is_emoji_char(),
+            Self::EmojiComponent => c.is_emoji_component(),
+        }
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        f(match self {
+            Self::Alphabetic => "alphabetic character",
+            Self::Lowercase => "lowercase character",
+            Self::Uppercase => "uppercase character",
+            Self::WhiteSpace => "whitespace",
+            Self::Numeric => "numeric character",
+            Self::Alphanumeric => "alphanumeric character",
+            Self::Control => "control character",
+            Self::Emoji => "emoji",
+            Self::EmojiComponent => "emoji component",
+        })
+    }
+}
+
+impl<T: CharClass> CharClass for [T] {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        self.iter().any(|class| class.contains(c))
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        self.iter().for_each(|class| class.names(f))
+    }
+}
+
+impl<T: CharClass, const N: usize> CharClass for [T; N] {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        CharClass::contains(&self[..], c)
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        CharClass::names(&self[..], f)
+    }
+}
+
+impl<T: CharClass + ?Sized> CharClass for &T {
+    #[inline]
+    fn contains(&self, c: char) -> bool {
+        (**self).contains(c)
+    }
+
+    fn names(&self, f: &mut dyn FnMut(&'static str)) {
+        (**self).names(f)
+    }
+}
+
+/// See [`category`], [`category_group`], [`script`], [`property`], and [`class`].
+pub struct Class<T, I, E> {
+    class: T,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<T: Copy, I, E> Copy for Class<T, I, E> {}
+impl<T: Clone, I, E> Clone for Class<T, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            class: self.class.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, T, I, E> ParserSealed<'a, I, char, E> for Class<T, I, E>
+where
+    T: CharClass,
+    I: ValueInput<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, char> {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(c)) if self.class.contains(c) => Ok(M::bind(|| c)),
+            (at, found) => {
+                // Describe the expected input with the names of the classes, if there are any
+                let mut err = None::<E::Error>;
+                self.class.names(&mut |class| {
+                    let class_err = E::Error::expected_class_found(
+                        class,
+                        found.map(|f| f.into()),
+                        inp.span_since(before),
+                    );
+                    err = Some(match err.take() {
+                        Some(err) => err.merge(class_err),
+                        None => class_err,
+                    });
+                });
+                let err = err.unwrap_or_else(|| {
+                    E::Error::expected_found(None, found.map(|f| f.into()), inp.span_since(before))
+                });
+                inp.add_alt_err(at, err);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(char);
+}
+
+/// A parser that accepts a single character belonging to the given [`CharClass`].
+///
+/// This is the most general parser in this module: it can be used with arrays of classes to match a union of
+/// categories, scripts, and properties.
+///
+/// The output type of this parser is `char`, the character that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::unicode::{self, GeneralCategory};
+///
+/// // Identifier characters, as in UAX #31: letters, combining marks, digits, and connector punctuation
+/// let ident_char = unicode::class::<_, _, extra::Err<Simple<char>>>([
+///     GeneralCategory::UppercaseLetter,
+///     GeneralCategory::LowercaseLetter,
+///     GeneralCategory::NonspacingMark,
+///     GeneralCategory::DecimalNumber,
+///     GeneralCategory::ConnectorPunctuation,
+/// ]);
+///
+/// assert_eq!(ident_char.parse("é").into_result(), Ok('é'));
+/// assert_eq!(ident_char.parse("_").into_result(), Ok('_'));
+/// assert!(ident_char.parse("-").has_errors());
+/// ```
+pub const fn class<'a, T, I, E>(class: T) -> Class<T, I, E>
+where
+    T: CharClass,
+    I: ValueInput<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    Class {
+        class,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that accepts a single character in the given Unicode [`GeneralCategory`] (such as `Nd`, a decimal digit).
+///
+/// The output type of this parser is `char`, the character that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::unicode::{self, GeneralCategory};
+///
+/// let digit = unicode::category::<_, extra::Err<Simple<char>>>(GeneralCategory::DecimalNumber);
+///
+/// assert_eq!(digit.parse("7").into_result(), Ok('7'));
+/// // Decimal digits from other scripts are also accepted
+/// assert_eq!(digit.parse("٣").into_result(), Ok('٣'));
+/// assert!(digit.parse("x").has_errors());
+/// ```
+pub const fn category<'a, I, E>(category: GeneralCategory) -> Class<GeneralCategory, I, E>
+where
+    I: ValueInput<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    class(category)
+}
+
+/// A parser that accepts a single character in any category of the given [`GeneralCategoryGroup`] (such as `L`, any
+/// letter).
+///
+/// The output type of this parser is `char`, the character that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::unicode::{self, GeneralCategoryGroup};
+///
+/// let punct = unicode::category_group::<_, extra::Err<Simple<char>>>(GeneralCategoryGroup::Punctuation);
+///
+/// assert_eq!(punct.parse("¿").into_result(), Ok('¿'));
+/// assert_eq!(punct.parse("」").into_result(), Ok('」'));
+/// assert!(punct.parse("a").has_errors());
+/// ```
+pub const fn category_group<'a, I, E>(
+    group: GeneralCategoryGroup,
+) -> Class<GeneralCategoryGroup, I, E>
+where
+    I: ValueInput<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    class(group)
+}
+
+/// A parser that accepts a single character whose Unicode `Script` property is the given [`Script`].
+///
+/// Note that characters shared between scripts (such as digits and most punctuation) belong to [`Script::Common`]
+/// or [`Script::Inherited`] rather than to the scripts they are used with.
+///
+/// The output type of this parser is `char`, the character that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::unicode::{self, Script};
+///
+/// let greek = unicode::script::<_, extra::Err<Simple<char>>>(Script::Greek)
+///     .repeated()
+///     .at_least(1)
+///     .slice();
+///
+/// assert_eq!(greek.parse("λόγος").into_result(), Ok("λόγος"));
+/// assert!(greek.parse("logos").has_errors());
+/// ```
+pub const fn script<'a, I, E>(script: Script) -> Class<Script, I, E>
+where
+    I: ValueInput<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    class(script)
+}
+
+/// A parser that accepts a single character that has the given binary Unicode [`Property`].
+///
+/// The output type of this parser is `char`, the character that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::unicode::{self, Property};
+///
+/// let word = unicode::property::<_, extra::Err<Simple<char>>>(Property::Alphabetic)
+///     .repeated()
+///     .at_least(1)
+///     .slice();
+///
+/// assert_eq!(word.parse("straße").into_result(), Ok("straße"));
+/// assert_eq!(word.parse("東京").into_result(), Ok("東京"));
+/// assert!(word.parse("42").has_errors());
+/// ```
+pub const fn property<'a, I, E>(property: Property) -> Class<Property, I, E>
+where
+    I: ValueInput<'a, Token = char>,
+    E: ParserExtra<'a, I>,
+{
+    class(property)
+}
+
+/// See [`just_normalized`].
+pub struct JustNormalized<I, E> {
+    // The canonical decomposition (NFD) of the expected text
+    nfd: String,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<I, E> Clone for JustNormalized<I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            nfd: self.nfd.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, &'a str, E> for JustNormalized<I, E>
+where
+    I: StrInput<'a, char>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a str> {
+        let start = inp.offset().offset;
+        let mut expected = self.nfd.chars().peekable();
+        let mut segment = String::new();
+        while let Some(&next) = expected.peek() {
+            // Decompose the input one combining sequence (a character followed by any non-starters) at a time, since
+            // canonical reordering never moves characters across a starter.
+            let before = inp.offset();
+            let first = match inp.next_inner() {
+                (_, Some(c)) => c,
+                (at, None) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(at, Some(Some(MaybeRef::Val(next))), None, err_span);
+                    return Err(());
+                }
+            };
+            segment.clear();
+            segment.push(first);
+            while let Some(c) = inp.peek().filter(|c| canonical_combining_class(*c) != 0) {
+                inp.skip();
+                segment.push(c);
+            }
+
+            for c in segment.chars().nfd() {
+                if expected.next_if_eq(&c).is_none() {
+                    let err_span = inp.span_since(before);
+                    // If the text has ended, the input has extra combining marks: the expected character is the one
+                    // that they modify
+                    let expected = expected.peek().copied().unwrap_or(next);
+                    inp.add_alt(
+                        inp.offset().offset,
+                        Some(Some(MaybeRef::Val(expected))),
+                        Some(MaybeRef::Val(first)),
+                        err_span,
+                    );
+                    return Err(());
+                }
+            }
+        }
+        let end = inp.offset().offset;
+        Ok(M::bind(|| inp.slice_inner(start..end)))
+    }
+
+    go_extra!(&'a str);
+}
+
+/// A parser that accepts the given text, regardless of whether the input and the text are in the same Unicode
+/// normalization form.
+///
+/// Text is compared by its [canonical decomposition](https://unicode.org/reports/tr15/) (NFD), so `"café"` written
+/// with a precomposed `é` (U+00E9, as in NFC) matches input that writes it as `e` followed by a combining acute accent
+/// (U+0301, as in NFD), and vice versa. Input is only consumed in whole combining sequences, so the text is not
+/// matched if the input continues with a combining mark that modifies its last character.
+///
+/// Errors report the expected characters in decomposed form.
+///
+/// The output type of this parser is `&str`, the slice of the input that was matched, in its original form.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::text::unicode;
+///
+/// let cafe = unicode::just_normalized::<_, extra::Err<Simple<char>>>("caf\u{e9}");
+///
+/// // Composed (NFC)
+/// assert_eq!(cafe.parse("caf\u{e9}").into_result(), Ok("caf\u{e9}"));
+/// // Decomposed (NFD)
+/// assert_eq!(cafe.parse("cafe\u{301}").into_result(), Ok("cafe\u{301}"));
+/// // An unaccented `e` is not the same character
+/// assert!(cafe.parse("cafe").has_errors());
+/// // Neither is an `é` with an extra accent
+/// assert!(cafe.parse("cafe\u{301}\u{323}").has_errors());
+/// ```
+pub fn just_normalized<'a, I, E>(text: impl AsRef<str>) -> JustNormalized<I, E>
+where
+    I: StrInput<'a, char>,
+    E: ParserExtra<'a, I>,
+{
+    JustNormalized {
+        nfd: text.as_ref().nfd().collect(),
+        phantom: EmptyPhantom::new(),
+    }
+}
@@ -0,0 +1,208 @@
+//! Tracing of parsers, to find out why a grammar rejects (or accepts) an input.
+//!
+//! Mark the parsers you're interested in with [`Labelled::traced`] (or name them with [`Parser::named`]), then parse
+//! the input with [`Parser::parse_traced`], giving it a [`Subscriber`]. As each traced parser runs, the subscriber
+//! receives an [`Event`] when the parser is entered and another when it matches or fails, identified by the parser's
+//! label. Any `FnMut(Event)` closure is a subscriber, and with the `tracing` feature enabled, [`Tracing`] forwards
+//! events to the [`tracing`](https://docs.rs/tracing) crate.
+//!
+//! Traced parsers only emit events when invoked by [`Parser::parse_traced`] (or [`Parser::parse_with_state_traced`]):
+//! otherwise, they have almost no overhead.
+//!
+//! *This module requires the `trace` feature.*
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, trace::Event};
+//! let call = text::ident::<_, _, extra::Err<Rich<char>>>()
+//!     .then_ignore(just("()"))
+//!     .labelled("call")
+//!     .traced();
+//! let var = text::ident().labelled("var").traced();
+//! let expr = call.or(var);
+//!
+//! let mut log = Vec::new();
+//! let result = expr.parse_traced("foo", &mut |event: Event| log.push(event.to_string()));
+//! assert!(!result.has_errors());
+//!
+//! assert_eq!(log, [
+//!     "enter call at 0",
+//!     "fail call at 3, backtracking 3",
+//!     "enter var at 0",
+//!     "match var at 0..3",
+//! ]);
+//! ```
+
+use super::*;
+
+/// An event emitted by a traced parser. See [`Labelled::traced`].
+///
+/// Offsets are measured in offsets of the input (bytes for `&str`, tokens for slices).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// The parser was entered.
+    Enter {
+        /// The label of the parser.
+        rule: &'a str,
+        /// The offset at which the parser was entered.
+        offset: usize,
+    },
+    /// The parser matched some input.
+    Match {
+        /// The label of the parser.
+        rule: &'a str,
+        /// The span of the input that was matched.
+        span: Range<usize>,
+    },
+    /// The parser failed, and the input will be rewound to where it was entered.
+    Fail {
+        /// The label of the parser.
+        rule: &'a str,
+        /// The furthest offset that the parser reached before failing.
+        offset: usize,
+        /// The amount of input read by the parser before failing, which must be parsed again by whatever is attempted
+        /// next.
+        backtracked: usize,
+    },
+}
+
+impl Event<'_> {
+    /// The label of the parser that emitted this event.
+    pub fn rule(&self) -> &str {
+        match self {
+            Self::Enter { rule, .. } | Self::Match { rule, .. } | Self::Fail { rule, .. } => rule,
+        }
+    }
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Enter { rule, offset } => write!(f, "enter {} at {}", rule, offset),
+            Self::Match { rule, span } => {
+                write!(f, "match {} at {}..{}", rule, span.start, span.end)
+            }
+            Self::Fail {
+                rule,
+                offset,
+                backtracked,
+            } => write!(
+                f,
+                "fail {} at {}, backtracking {}",
+                rule, offset, backtracked
+            ),
+        }
+    }
+}
+
+/// A receiver of the [`Event`]s emitted by traced parsers. See [`Parser::parse_traced`].
+///
+/// This trait is implemented for all `FnMut(Event)` closures.
+pub trait Subscriber {
+    /// Receive an event from a traced parser.
+    fn event(&mut self, event: Event<'_>);
+}
+
+impl<F: FnMut(Event<'_>)> Subscriber for F {
+    fn event(&mut self, event: Event<'_>) {
+        self(event)
+    }
+}
+
+/// A [`Subscriber`] that forwards events to the [`tracing`](https://docs.rs/tracing) crate, at the `TRACE` level.
+///
+/// Each event is emitted with a `rule` field containing the label of the parser that emitted it, along with the other
+/// fields of the [`Event`].
+///
+/// *This type requires the `tracing` feature.*
+#[cfg(feature = "tracing")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Tracing;
+
+#[cfg(feature = "tracing")]
+impl Subscriber for Tracing {
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Enter { rule, offset } => tracing::trace!(rule, offset, "enter"),
+            Event::Match { rule, span } => {
+                tracing::trace!(rule, start = span.start, end = span.end, "match")
+            }
+            Event::Fail {
+                rule,
+                offset,
+                backtracked,
+            } => tracing::trace!(rule, offset, backtracked, "fail"),
+        }
+    }
+}
+
+// Invoke a parser, emitting events for it under the given label if the parse is being traced
+#[inline]
+pub(crate) fn go_traced<'a, 'parse, I, E, M, O>(
+    inp: &mut InputRef<'a, 'parse, I, E>,
+    rule: &str,
+    go: impl FnOnce(&mut InputRef<'a, 'parse, I, E>) -> PResult<M, O>,
+) -> PResult<M, O>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    M: Mode,
+{
+    let Some(subscriber) = inp.trace.as_deref_mut() else {
+        return go(inp);
+    };
+
+    let before = inp.offset.into();
+    subscriber.event(Event::Enter {
+        rule,
+        offset: before,
+    });
+    let res = go(inp);
+    let after = inp.offset.into();
+    if let Some(subscriber) = inp.trace.as_deref_mut() {
+        subscriber.event(match res {
+            Ok(_) => Event::Match {
+                rule,
+                span: before..after,
+            },
+            Err(()) => Event::Fail {
+                rule,
+                offset: after,
+                backtracked: after.saturating_sub(before),
+            },
+        });
+    }
+    res
+}
+
+/// See [`Labelled::traced`].
+#[derive(Clone)]
+pub struct Traced<A> {
+    pub(crate) parser: A,
+    pub(crate) label: String,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Traced<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        go_traced::<_, _, M, _>(inp, &self.label, |inp| self.parser.go::<M>(inp))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn first_tokens(&self) -> Option<Vec<MaybeRef<'a, I::Token>>> {
+        ParserSealed::<I, O, E>::first_tokens(&self.parser)
+    }
+
+    graph_node!(parser: O);
+
+    gen_input!(parser: O);
+
+    go_extra!(O);
+}
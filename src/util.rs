@@ -110,14 +110,14 @@ impl<T, R: DerefMut<Target = T>> DerefMut for Maybe<T, R> {
     }
 }
 
-impl<'a, T> From<T> for Maybe<T, &'a T> {
+impl<T> From<T> for Maybe<T, &T> {
     #[inline]
     fn from(x: T) -> Self {
         Self::Val(x)
     }
 }
 
-impl<'a, T> From<T> for Maybe<T, &'a mut T> {
+impl<T> From<T> for Maybe<T, &mut T> {
     #[inline]
     fn from(x: T) -> Self {
         Self::Val(x)
@@ -137,3 +137,198 @@ impl<'a, T> From<&'a mut T> for Maybe<T, &'a mut T> {
         Self::Ref(x)
     }
 }
+
+/// The most errors that a parse can produce without the `alloc` feature. Errors produced after this many have been
+/// produced are discarded.
+pub const MAX_ERRORS: usize = 16;
+
+/// The errors produced by a parse.
+///
+/// This is a [`Vec`] when the `alloc` feature is enabled. Otherwise, it is a [`FixedVec`] that holds up to
+/// [`MAX_ERRORS`] errors.
+#[cfg(feature = "alloc")]
+pub type ErrorVec<E> = Vec<E>;
+
+/// The errors produced by a parse.
+///
+/// This is a [`Vec`] when the `alloc` feature is enabled. Otherwise, it is a [`FixedVec`] that holds up to
+/// [`MAX_ERRORS`] errors.
+#[cfg(not(feature = "alloc"))]
+pub type ErrorVec<E> = FixedVec<E, MAX_ERRORS>;
+
+/// A vector with a fixed capacity of `N` items, stored inline, for use where no allocator is available.
+///
+/// Items pushed once the vector is full are discarded. The items can be accessed as a slice.
+pub struct FixedVec<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> FixedVec<T, N> {
+    /// Create an empty vector.
+    pub fn new() -> Self {
+        Self {
+            items: MaybeUninitExt::uninit_array(),
+            len: 0,
+        }
+    }
+
+    /// Add an item to the end of the vector, discarding it if the vector is already full.
+    pub fn push(&mut self, item: T) {
+        if let Some(slot) = self.items.get_mut(self.len) {
+            slot.write(item);
+            self.len += 1;
+        }
+    }
+
+    /// Returns true if the vector can hold no more items.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Shorten the vector to `len` items, dropping the rest. Does nothing if the vector is already no longer than
+    /// `len`.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+            // SAFETY: Items before `self.len` are initialised, and this one is no longer considered part of the vector
+            unsafe { self.items[self.len].assume_init_drop() };
+        }
+    }
+}
+
+impl<T, const N: usize> Default for FixedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for FixedVec<T, N> {
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<T, const N: usize> Deref for FixedVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // SAFETY: Items before `self.len` are initialised, and `MaybeUninit<T>` has the same layout as `T`
+        unsafe { &*(&self.items[..self.len] as *const [MaybeUninit<T>] as *const [T]) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for FixedVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: Items before `self.len` are initialised, and `MaybeUninit<T>` has the same layout as `T`
+        unsafe { &mut *(&mut self.items[..self.len] as *mut [MaybeUninit<T>] as *mut [T]) }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for FixedVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for FixedVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        <[T]>::fmt(self, f)
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for FixedVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for FixedVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for FixedVec<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for FixedVec<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for FixedVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for FixedVec<T, N> {
+    /// Collect items into a vector, discarding any after the first `N`.
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        let mut vec = Self::new();
+        iter.into_iter().take(N).for_each(|item| vec.push(item));
+        vec
+    }
+}
+
+impl<T, const N: usize> IntoIterator for FixedVec<T, N> {
+    type Item = T;
+    type IntoIter = FixedVecIntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let this = core::mem::ManuallyDrop::new(self);
+        FixedVecIntoIter {
+            // SAFETY: `this` is never used again or dropped, so ownership of its items moves to the iterator
+            items: unsafe { core::ptr::read(&this.items) },
+            next: 0,
+            len: this.len,
+        }
+    }
+}
+
+impl<'b, T, const N: usize> IntoIterator for &'b FixedVec<T, N> {
+    type Item = &'b T;
+    type IntoIter = core::slice::Iter<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the items of a [`FixedVec`], produced by [`FixedVec::into_iter`].
+pub struct FixedVecIntoIter<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    // Items from `next` up to `len` are initialised, and have not yet been yielded
+    next: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> Iterator for FixedVecIntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.next < self.len {
+            self.next += 1;
+            // SAFETY: The item is initialised, and is no longer considered part of the iterator
+            Some(unsafe { self.items[self.next - 1].assume_init_read() })
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len - self.next, Some(self.len - self.next))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for FixedVecIntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for FixedVecIntoIter<T, N> {
+    fn drop(&mut self) {
+        for item in &mut self.items[self.next..self.len] {
+            // SAFETY: Items that have not yet been yielded are initialised
+            unsafe { item.assume_init_drop() };
+        }
+    }
+}